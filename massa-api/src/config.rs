@@ -1,11 +1,12 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use jsonrpc_core::serde::Deserialize;
+use massa_time::MassaTime;
 use std::net::SocketAddr;
 
 /// API settings.
 /// the API settings
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct APIConfig {
     /// when looking for next draw we want to look at max `draw_lookahead_period_count`
     pub draw_lookahead_period_count: u64,
@@ -21,4 +22,16 @@ pub struct APIConfig {
     pub max_function_name_length: u16,
     /// max parameter size
     pub max_parameter_size: u32,
+    /// command compiling smart contract sources, empty to disable source verification.
+    /// `{version}`, `{entry}` and `{output}` are replaced by the compiler version, the file to compile
+    /// and the file to write the bytecode to
+    pub contract_compiler_command: Vec<String>,
+    /// max duration of the compilation of smart contract sources
+    pub contract_compilation_timeout: MassaTime,
+    /// max size in bytes of a smart contract source file submitted for verification
+    pub max_contract_source_file_size: u64,
+    /// max total size in bytes of the smart contract source files submitted for verification
+    pub max_contract_sources_size: u64,
+    /// max number of smart contract source compilations running at the same time
+    pub max_contract_compilations: usize,
 }
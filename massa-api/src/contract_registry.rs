@@ -0,0 +1,465 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Registry of smart contract sources verified to compile into deployed bytecodes.
+//!
+//! Submitted sources are compiled with the command configured in `APIConfig::contract_compiler_command`.
+//! They are registered under the hash of the bytecode they compile into only if that hash
+//! is the one of the final bytecode of the address the submitter claims to have deployed them at.
+//! The registry is kept in memory and saved to its file after each change.
+//!
+//! Only AssemblyScript files are accepted in a bundle: configuration and manifest files
+//! (`asconfig.json`, `package.json`, `.npmrc`...) can make the compiler run arbitrary code,
+//! so they are refused along with hidden files and `node_modules` directories.
+
+use crate::config::APIConfig;
+use crate::error::ApiError;
+use massa_hash::Hash;
+use massa_models::api::{ContractSourceBundle, VerifiedContractSources};
+use massa_time::MassaTime;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::warn;
+
+/// Max number of bytes of the compiler error output returned to the submitter
+const MAX_COMPILER_OUTPUT_LENGTH: usize = 4096;
+
+/// Extension of the source files accepted in a bundle
+const SOURCE_FILE_EXTENSION: &str = "ts";
+
+/// Counter used to give each compilation its own working directory
+static COMPILATION_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Content of the registry
+struct RegistryContent {
+    /// file in which the registry is saved
+    path: PathBuf,
+    /// verified sources by bytecode hash
+    entries: HashMap<Hash, VerifiedContractSources>,
+}
+
+/// Verified smart contract sources, by hash of the bytecode they compile into.
+/// Clones share the same registry.
+#[derive(Clone)]
+pub struct ContractRegistry(Arc<RwLock<RegistryContent>>);
+
+impl ContractRegistry {
+    /// Loads the registry from its file, or starts an empty one if the file does not exist or is invalid
+    pub fn load(path: PathBuf) -> Self {
+        let entries = if path.is_file() {
+            match std::fs::read(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|data| {
+                    serde_json::from_slice::<Vec<VerifiedContractSources>>(&data)
+                        .map_err(|err| err.to_string())
+                }) {
+                Ok(entries) => entries
+                    .into_iter()
+                    .map(|entry| (entry.code_hash, entry))
+                    .collect(),
+                Err(err) => {
+                    warn!(
+                        "could not load the contract registry from {}: {}",
+                        path.display(),
+                        err
+                    );
+                    HashMap::new()
+                }
+            }
+        } else {
+            HashMap::new()
+        };
+        ContractRegistry(Arc::new(RwLock::new(RegistryContent { path, entries })))
+    }
+
+    /// Gets the verified sources of bytecodes, `None` for the bytecodes without verified sources
+    pub async fn get(&self, code_hashes: &[Option<Hash>]) -> Vec<Option<VerifiedContractSources>> {
+        let content = self.0.read().await;
+        code_hashes
+            .iter()
+            .map(|code_hash| {
+                code_hash
+                    .as_ref()
+                    .and_then(|code_hash| content.entries.get(code_hash))
+                    .cloned()
+            })
+            .collect()
+    }
+
+    /// Registers verified sources, replacing any previous sources of the same bytecode,
+    /// and saves the registry to its file
+    pub async fn insert(&self, sources: VerifiedContractSources) -> Result<(), ApiError> {
+        let mut content = self.0.write().await;
+        content.entries.insert(sources.code_hash, sources);
+        let mut entries: Vec<&VerifiedContractSources> = content.entries.values().collect();
+        entries.sort_unstable_by_key(|entry| entry.verified_at);
+        let data = serde_json::to_vec_pretty(&entries)
+            .map_err(|err| ApiError::ContractVerificationError(err.to_string()))?;
+        if let Some(parent) = content.path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&content.path, data).await?;
+        Ok(())
+    }
+}
+
+/// Checks that a path submitted in a source bundle is an AssemblyScript file
+/// that stays inside the sources directory
+fn check_source_path(path: &str) -> Result<(), ApiError> {
+    let path_buf = Path::new(path);
+    let is_valid = !path.is_empty()
+        && path_buf.extension().and_then(|ext| ext.to_str()) == Some(SOURCE_FILE_EXTENSION)
+        && path_buf.components().all(|component| match component {
+            Component::Normal(name) => {
+                let name = name.to_string_lossy();
+                !name.starts_with('.') && name != "node_modules"
+            }
+            _ => false,
+        });
+    if is_valid {
+        Ok(())
+    } else {
+        Err(ApiError::ContractVerificationError(format!(
+            "invalid source file path: {}",
+            path
+        )))
+    }
+}
+
+/// Checks the compiler version, the file paths and the sizes of a source bundle
+fn check_source_bundle(config: &APIConfig, bundle: &ContractSourceBundle) -> Result<(), ApiError> {
+    if bundle.compiler_version.is_empty()
+        || !bundle
+            .compiler_version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+    {
+        return Err(ApiError::ContractVerificationError(format!(
+            "invalid compiler version: {}",
+            bundle.compiler_version
+        )));
+    }
+    let mut total_size: u64 = 0;
+    for source in &bundle.sources {
+        check_source_path(&source.path)?;
+        let size = source.content.len() as u64;
+        if size > config.max_contract_source_file_size {
+            return Err(ApiError::ContractVerificationError(format!(
+                "source file {} is too large: {} bytes, the limit is {}",
+                source.path, size, config.max_contract_source_file_size
+            )));
+        }
+        total_size = total_size.saturating_add(size);
+    }
+    if total_size > config.max_contract_sources_size {
+        return Err(ApiError::ContractVerificationError(format!(
+            "sources are too large: {} bytes, the limit is {}",
+            total_size, config.max_contract_sources_size
+        )));
+    }
+    if !bundle
+        .sources
+        .iter()
+        .any(|source| source.path == bundle.entry_file)
+    {
+        return Err(ApiError::ContractVerificationError(format!(
+            "entry file {} is not part of the sources",
+            bundle.entry_file
+        )));
+    }
+    Ok(())
+}
+
+/// Compiles the sources of a bundle and checks that they produce the expected bytecode
+///
+/// # Arguments
+/// * `code_hash`: hash of the final bytecode deployed at the address of the bundle
+/// * `verified_at`: time of the verification
+///
+/// # Returns
+/// The verified sources, ready to be registered
+pub(crate) async fn verify_contract_sources(
+    config: &APIConfig,
+    bundle: ContractSourceBundle,
+    code_hash: Hash,
+    verified_at: MassaTime,
+) -> Result<VerifiedContractSources, ApiError> {
+    let bytecode = compile_contract_sources(config, &bundle).await?;
+    let compiled_hash = Hash::compute_from(&bytecode);
+    if compiled_hash != code_hash {
+        return Err(ApiError::ContractVerificationError(format!(
+            "the sources compile into a bytecode of hash {} instead of {}",
+            compiled_hash, code_hash
+        )));
+    }
+    Ok(VerifiedContractSources {
+        code_hash,
+        compiler_version: bundle.compiler_version,
+        entry_file: bundle.entry_file,
+        sources: bundle.sources,
+        abi: bundle.abi,
+        verified_at,
+    })
+}
+
+/// Compiles the sources of a bundle with the configured compiler command
+///
+/// # Returns
+/// The compiled bytecode
+async fn compile_contract_sources(
+    config: &APIConfig,
+    bundle: &ContractSourceBundle,
+) -> Result<Vec<u8>, ApiError> {
+    if config.contract_compiler_command.is_empty() {
+        return Err(ApiError::MissingConfig(
+            "smart contract source verification is disabled on this node".into(),
+        ));
+    }
+    check_source_bundle(config, bundle)?;
+
+    let work_dir = std::env::temp_dir().join(format!(
+        "massa_contract_verification_{}_{}",
+        std::process::id(),
+        COMPILATION_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let result = compile_in_dir(config, bundle, &work_dir).await;
+    if let Err(err) = tokio::fs::remove_dir_all(&work_dir).await {
+        warn!(
+            "could not remove the contract compilation directory {}: {}",
+            work_dir.display(),
+            err
+        );
+    }
+    result
+}
+
+/// Writes the sources of a bundle in a working directory and compiles them there
+async fn compile_in_dir(
+    config: &APIConfig,
+    bundle: &ContractSourceBundle,
+    work_dir: &Path,
+) -> Result<Vec<u8>, ApiError> {
+    let sources_dir = work_dir.join("sources");
+    for source in &bundle.sources {
+        let path = sources_dir.join(&source.path);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, &source.content).await?;
+    }
+    let entry = sources_dir.join(&bundle.entry_file);
+    let output = work_dir.join("output.wasm");
+
+    let args: Vec<String> = config
+        .contract_compiler_command
+        .iter()
+        .map(|arg| {
+            arg.replace("{version}", &bundle.compiler_version)
+                .replace("{entry}", &entry.to_string_lossy())
+                .replace("{output}", &output.to_string_lossy())
+        })
+        .collect();
+    let mut command = tokio::process::Command::new(&args[0]);
+    command
+        .args(&args[1..])
+        .current_dir(&sources_dir)
+        .stdin(Stdio::null())
+        .kill_on_drop(true);
+
+    let compilation = tokio::time::timeout(
+        config.contract_compilation_timeout.to_duration(),
+        command.output(),
+    )
+    .await
+    .map_err(|_| ApiError::ContractVerificationError("compilation timed out".into()))??;
+    if !compilation.status.success() {
+        let mut stderr = String::from_utf8_lossy(&compilation.stderr).into_owned();
+        if stderr.len() > MAX_COMPILER_OUTPUT_LENGTH {
+            let mut end = MAX_COMPILER_OUTPUT_LENGTH;
+            while !stderr.is_char_boundary(end) {
+                end -= 1;
+            }
+            stderr.truncate(end);
+        }
+        return Err(ApiError::ContractVerificationError(format!(
+            "compilation failed ({}): {}",
+            compilation.status, stderr
+        )));
+    }
+    Ok(tokio::fs::read(&output).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_models::abi::ContractAbi;
+    use massa_models::address::Address;
+    use massa_models::api::ContractSourceFile;
+    use std::str::FromStr;
+
+    /// Config whose "compiler" copies the entry file to the bytecode file
+    fn get_config() -> APIConfig {
+        APIConfig {
+            draw_lookahead_period_count: 10,
+            bind_private: "127.0.0.1:0".parse().unwrap(),
+            bind_public: "127.0.0.1:0".parse().unwrap(),
+            max_arguments: 128,
+            max_datastore_value_length: 1_000,
+            max_function_name_length: 255,
+            max_parameter_size: 1_000,
+            contract_compiler_command: vec!["cp".into(), "{entry}".into(), "{output}".into()],
+            contract_compilation_timeout: MassaTime::from_millis(10_000),
+            max_contract_source_file_size: 100,
+            max_contract_sources_size: 150,
+            max_contract_compilations: 1,
+        }
+    }
+
+    fn get_bundle(sources: Vec<(&str, &str)>) -> ContractSourceBundle {
+        ContractSourceBundle {
+            address: Address::from_str("A12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x")
+                .unwrap(),
+            compiler_version: "0.20.0".into(),
+            entry_file: sources[0].0.into(),
+            sources: sources
+                .into_iter()
+                .map(|(path, content)| ContractSourceFile {
+                    path: path.into(),
+                    content: content.into(),
+                })
+                .collect(),
+            abi: ContractAbi::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verify_contract_sources() {
+        let config = get_config();
+        let bundle = get_bundle(vec![
+            ("assembly/main.ts", "export function main(): void {}"),
+            ("assembly/lib.ts", "export const answer = 42;"),
+        ]);
+        let code_hash = Hash::compute_from(b"export function main(): void {}");
+        let verified_at = MassaTime::from_millis(1_000);
+
+        let verified = verify_contract_sources(&config, bundle.clone(), code_hash, verified_at)
+            .await
+            .unwrap();
+        assert_eq!(verified.code_hash, code_hash);
+        assert_eq!(verified.entry_file, "assembly/main.ts");
+        assert_eq!(verified.sources, bundle.sources);
+        assert_eq!(verified.verified_at, verified_at);
+
+        // sources that do not compile into the deployed bytecode are refused
+        let other_hash = Hash::compute_from(b"other bytecode");
+        assert!(matches!(
+            verify_contract_sources(&config, bundle, other_hash, verified_at).await,
+            Err(ApiError::ContractVerificationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verification_disabled() {
+        let config = APIConfig {
+            contract_compiler_command: Vec::new(),
+            ..get_config()
+        };
+        let bundle = get_bundle(vec![("main.ts", "")]);
+        assert!(matches!(
+            verify_contract_sources(
+                &config,
+                bundle,
+                Hash::compute_from(b""),
+                MassaTime::from_millis(0)
+            )
+            .await,
+            Err(ApiError::MissingConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_reject_invalid_source_paths() {
+        for path in [
+            "asconfig.json",
+            "package.json",
+            "assembly/package-lock.json",
+            ".npmrc",
+            "assembly/.hidden.ts",
+            "node_modules/assemblyscript/index.ts",
+            "../main.ts",
+            "/tmp/main.ts",
+            "assembly/../../main.ts",
+            "main",
+            "",
+        ] {
+            assert!(check_source_path(path).is_err(), "{} was accepted", path);
+        }
+        for path in ["main.ts", "assembly/main.ts", "assembly/contracts/token.ts"] {
+            assert!(check_source_path(path).is_ok(), "{} was refused", path);
+        }
+    }
+
+    #[test]
+    fn test_reject_invalid_bundles() {
+        let config = get_config();
+        let file_content = "a".repeat(80);
+        let large_file_content = "a".repeat(101);
+
+        // configuration file next to the sources
+        let bundle = get_bundle(vec![("main.ts", ""), ("asconfig.json", "{}")]);
+        assert!(check_source_bundle(&config, &bundle).is_err());
+
+        // file larger than the per-file limit
+        let bundle = get_bundle(vec![("main.ts", &large_file_content)]);
+        assert!(check_source_bundle(&config, &bundle).is_err());
+
+        // files within the per-file limit but larger than the total limit
+        let bundle = get_bundle(vec![("main.ts", &file_content), ("lib.ts", &file_content)]);
+        assert!(check_source_bundle(&config, &bundle).is_err());
+
+        // entry file missing from the sources
+        let mut bundle = get_bundle(vec![("main.ts", "")]);
+        bundle.entry_file = "other.ts".into();
+        assert!(check_source_bundle(&config, &bundle).is_err());
+
+        // compiler version that could be interpreted by the compiler command
+        let mut bundle = get_bundle(vec![("main.ts", "")]);
+        bundle.compiler_version = "0.20.0 --transform evil".into();
+        assert!(check_source_bundle(&config, &bundle).is_err());
+
+        let bundle = get_bundle(vec![("main.ts", &file_content), ("lib.ts", "")]);
+        assert!(check_source_bundle(&config, &bundle).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_registry_persistence() {
+        let path = std::env::temp_dir().join(format!(
+            "massa_contract_registry_test_{}.json",
+            std::process::id()
+        ));
+        let code_hash = Hash::compute_from(b"bytecode");
+        let verified = VerifiedContractSources {
+            code_hash,
+            compiler_version: "0.20.0".into(),
+            entry_file: "main.ts".into(),
+            sources: get_bundle(vec![("main.ts", "")]).sources,
+            abi: ContractAbi::default(),
+            verified_at: MassaTime::from_millis(1_000),
+        };
+
+        let registry = ContractRegistry::load(path.clone());
+        registry.insert(verified.clone()).await.unwrap();
+        let other_hash = Hash::compute_from(b"other bytecode");
+        let reloaded = ContractRegistry::load(path.clone());
+        assert_eq!(
+            reloaded
+                .get(&[Some(code_hash), Some(other_hash), None])
+                .await,
+            vec![Some(verified), None, None]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -39,6 +39,10 @@ pub enum ApiError {
     TimeError(#[from] TimeError),
     /// wallet error: {0}
     WalletError(#[from] WalletError),
+    /// I/O error: {0}
+    IOError(#[from] std::io::Error),
+    /// contract verification error: {0}
+    ContractVerificationError(String),
     /// not found
     NotFound,
     /// inconsistency: {0}
@@ -47,7 +51,7 @@ pub enum ApiError {
     MissingCommandSender(String),
     /// missing configuration {0}
     MissingConfig(String),
-    /// the wrong API (either Public or Private) was called
+    /// the wrong API (either Public, Private or Light) was called
     WrongAPI,
 }
 
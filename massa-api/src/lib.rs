@@ -10,10 +10,15 @@ use jsonrpc_derive::rpc;
 use jsonrpc_http_server::{CloseHandle, ServerBuilder};
 use massa_consensus_exports::{ConsensusCommandSender, ConsensusConfig};
 use massa_execution_exports::ExecutionController;
+use massa_hash::Hash;
+use massa_ledger_exports::{LedgerProofs, LedgerSubEntry};
 use massa_models::api::{
-    AddressInfo, BlockInfo, BlockSummary, DatastoreEntryInput, DatastoreEntryOutput,
-    EndorsementInfo, EventFilter, NodeStatus, OperationInfo, OperationInput,
-    ReadOnlyBytecodeExecution, ReadOnlyCall, TimeInterval,
+    AddressHistoryEntry, AddressHistoryRequest, AddressInfo, AsyncMessageFilter, AsyncMessageInfo,
+    BannedPeerInfo, BlockExecutionReceipt, BlockInfo, BlockSummary, ContractMetadata,
+    ContractSourceBundle, DatastoreEntryInput, DatastoreEntryOutput, EndorsementInfo, EventFilter,
+    LightClientBalance, LightClientOperationInclusion, LightClientStatus, NodeStatus,
+    OperationInfo, OperationInput, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyCallBatch,
+    TimeInterval, VerifiedContractSources,
 };
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
@@ -32,23 +37,27 @@ use massa_models::{
 use massa_network_exports::{NetworkCommandSender, NetworkConfig};
 use massa_pool_exports::PoolController;
 use massa_pos_exports::SelectorController;
-use massa_protocol_exports::ProtocolCommandSender;
+use massa_protocol_exports::{LightClientCommandSender, ProtocolCommandSender};
 use massa_signature::KeyPair;
 use massa_storage::Storage;
+use massa_time::MassaTime;
 use massa_wallet::Wallet;
 use parking_lot::RwLock;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use std::thread;
 use std::thread::JoinHandle;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Semaphore};
 use tracing::{info, warn};
 
 mod config;
+mod contract_registry;
 mod error;
+mod light;
 mod private;
 mod public;
 pub use config::APIConfig;
+pub use contract_registry::ContractRegistry;
 
 /// Public API component
 pub struct Public {
@@ -78,6 +87,8 @@ pub struct Public {
     pub compensation_millis: i64,
     /// our node id
     pub node_id: NodeId,
+    /// verified smart contract sources
+    pub contract_registry: ContractRegistry,
 }
 
 /// Private API content
@@ -96,6 +107,20 @@ pub struct Private {
     pub stop_node_channel: mpsc::Sender<()>,
     /// User wallet
     pub node_wallet: Arc<RwLock<Wallet>>,
+    /// compensation milliseconds (used to sync time with bootstrap server)
+    pub compensation_millis: i64,
+    /// verified smart contract sources
+    pub contract_registry: ContractRegistry,
+    /// permits limiting the number of smart contract source compilations running at the same time
+    pub contract_compilations: Arc<Semaphore>,
+}
+
+/// API of a node running in light client mode
+pub struct Light {
+    /// link to the light client component
+    pub light_client_command_sender: LightClientCommandSender,
+    /// API settings
+    pub api_settings: APIConfig,
 }
 
 /// The API wrapper
@@ -175,6 +200,14 @@ pub trait Endpoints {
         _: Vec<ReadOnlyCall>,
     ) -> BoxFuture<Result<Vec<ExecuteReadOnlyResponse>, ApiError>>;
 
+    /// Execute a batch of SC function calls in read-only mode against the same state.
+    /// Results are returned in the order of the calls.
+    #[rpc(name = "execute_read_only_call_batch")]
+    fn execute_read_only_call_batch(
+        &self,
+        _: ReadOnlyCallBatch,
+    ) -> BoxFuture<Result<Vec<ExecuteReadOnlyResponse>, ApiError>>;
+
     /// Remove a vector of addresses used to stake.
     /// No confirmation to expect.
     #[rpc(name = "remove_staking_addresses")]
@@ -184,15 +217,29 @@ pub trait Endpoints {
     #[rpc(name = "get_staking_addresses")]
     fn get_staking_addresses(&self) -> BoxFuture<Result<PreHashSet<Address>, ApiError>>;
 
-    /// Bans given IP address(es).
+    /// Bans given IP address(es), for the given duration in milliseconds
+    /// or the default ban duration if none.
     /// No confirmation to expect.
     #[rpc(name = "node_ban_by_ip")]
-    fn node_ban_by_ip(&self, _: Vec<IpAddr>) -> BoxFuture<Result<(), ApiError>>;
+    fn node_ban_by_ip(
+        &self,
+        _: Vec<IpAddr>,
+        _: Option<MassaTime>,
+    ) -> BoxFuture<Result<(), ApiError>>;
 
-    /// Bans given node id.
+    /// Bans given node id, for the given duration in milliseconds
+    /// or the default ban duration if none.
     /// No confirmation to expect.
     #[rpc(name = "node_ban_by_id")]
-    fn node_ban_by_id(&self, _: Vec<NodeId>) -> BoxFuture<Result<(), ApiError>>;
+    fn node_ban_by_id(
+        &self,
+        _: Vec<NodeId>,
+        _: Option<MassaTime>,
+    ) -> BoxFuture<Result<(), ApiError>>;
+
+    /// Returns the currently banned peers, with the reason and expiry of their ban.
+    #[rpc(name = "get_banned_peers")]
+    fn get_banned_peers(&self) -> BoxFuture<Result<Vec<BannedPeerInfo>, ApiError>>;
 
     /// whitelist given IP address.
     /// No confirmation to expect.
@@ -216,6 +263,14 @@ pub trait Endpoints {
     #[rpc(name = "node_unban_by_id")]
     fn node_unban_by_id(&self, _: Vec<NodeId>) -> BoxFuture<Result<(), ApiError>>;
 
+    /// Submit the sources of a deployed smart contract for verification.
+    /// They are registered if they compile into the final bytecode of the contract.
+    #[rpc(name = "submit_contract_sources")]
+    fn submit_contract_sources(
+        &self,
+        _: ContractSourceBundle,
+    ) -> BoxFuture<Result<VerifiedContractSources, ApiError>>;
+
     /// Summary of the current state: time, last final blocks (hash, thread, slot, timestamp), clique count, connected nodes count.
     #[rpc(name = "get_status")]
     fn get_status(&self) -> BoxFuture<Result<NodeStatus, ApiError>>;
@@ -264,10 +319,30 @@ pub trait Endpoints {
         _: Vec<DatastoreEntryInput>,
     ) -> BoxFuture<Result<Vec<DatastoreEntryOutput>, ApiError>>;
 
+    /// Get proofs of the final values of ledger sub-entries, or of their absence,
+    /// along with the final ledger root they can be checked against.
+    #[rpc(name = "get_ledger_proof")]
+    fn get_ledger_proof(
+        &self,
+        _: Vec<(Address, LedgerSubEntry)>,
+    ) -> BoxFuture<Result<LedgerProofs, ApiError>>;
+
+    /// Get the root of the final ledger at the output of a recent final slot,
+    /// `None` if the slot is not final yet or too old to be remembered.
+    #[rpc(name = "get_ledger_root")]
+    fn get_ledger_root(&self, _: Slot) -> BoxFuture<Result<Option<Hash>, ApiError>>;
+
     /// Get addresses.
     #[rpc(name = "get_addresses")]
     fn get_addresses(&self, _: Vec<Address>) -> BoxFuture<Result<Vec<AddressInfo>, ApiError>>;
 
+    /// Get the bytecode metadata and the verified sources of smart contracts.
+    #[rpc(name = "get_contract_metadata")]
+    fn get_contract_metadata(
+        &self,
+        _: Vec<Address>,
+    ) -> BoxFuture<Result<Vec<ContractMetadata>, ApiError>>;
+
     /// Adds operations to pool. Returns operations that were ok and sent to pool.
     #[rpc(name = "send_operations")]
     fn send_operations(
@@ -286,6 +361,54 @@ pub trait Endpoints {
         &self,
         _: EventFilter,
     ) -> BoxFuture<Result<Vec<SCOutputEvent>, ApiError>>;
+
+    /// Get asynchronous messages and their status, optionally filtered by:
+    /// * sender address
+    /// * destination address
+    /// * emission slot
+    /// * emission index
+    ///
+    /// Only the page described by the `offset` and `limit` of the filter is returned.
+    #[rpc(name = "get_async_messages")]
+    fn get_async_messages(
+        &self,
+        _: AsyncMessageFilter,
+    ) -> BoxFuture<Result<Vec<AsyncMessageInfo>, ApiError>>;
+
+    /// Get a page of the history of operations and coin transfers involving an address, most recent first.
+    /// Requires the address history to be enabled in the node configuration.
+    #[rpc(name = "get_address_history")]
+    fn get_address_history(
+        &self,
+        _: AddressHistoryRequest,
+    ) -> BoxFuture<Result<Vec<AddressHistoryEntry>, ApiError>>;
+
+    /// Get the execution receipt of a recently executed block: outcome and gas of its operations,
+    /// async messages executed at its slot, fees and split of the block reward.
+    #[rpc(name = "get_block_execution_receipt")]
+    fn get_block_execution_receipt(
+        &self,
+        _: BlockId,
+    ) -> BoxFuture<Result<Option<BlockExecutionReceipt>, ApiError>>;
+
+    /// Light client mode only: status of the followed headers.
+    #[rpc(name = "get_light_client_status")]
+    fn get_light_client_status(&self) -> BoxFuture<Result<LightClientStatus, ApiError>>;
+
+    /// Light client mode only: final balances of addresses, fetched from a full node.
+    /// Balances are not trustless: they are only confirmed by other full nodes.
+    #[rpc(name = "get_light_client_balances")]
+    fn get_light_client_balances(
+        &self,
+        _: Vec<Address>,
+    ) -> BoxFuture<Result<Vec<LightClientBalance>, ApiError>>;
+
+    /// Light client mode only: blocks including operations, as proven by a full node.
+    #[rpc(name = "get_light_client_operations")]
+    fn get_light_client_operations(
+        &self,
+        _: Vec<OperationId>,
+    ) -> BoxFuture<Result<Vec<LightClientOperationInclusion>, ApiError>>;
 }
 
 fn wrong_api<T>() -> BoxFuture<Result<T, ApiError>> {
@@ -0,0 +1,289 @@
+//! Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use crate::config::APIConfig;
+use crate::error::ApiError;
+use crate::{Endpoints, Light, RpcServer, StopHandle, API};
+
+use jsonrpc_core::BoxFuture;
+use massa_hash::Hash;
+use massa_ledger_exports::{LedgerProofs, LedgerSubEntry};
+use massa_models::api::{
+    AddressHistoryEntry, AddressHistoryRequest, AddressInfo, AsyncMessageFilter, AsyncMessageInfo,
+    BannedPeerInfo, BlockExecutionReceipt, BlockInfo, BlockSummary, ContractMetadata,
+    ContractSourceBundle, DatastoreEntryInput, DatastoreEntryOutput, EndorsementInfo, EventFilter,
+    LightClientBalance, LightClientOperationInclusion, LightClientStatus, NodeStatus,
+    OperationInfo, OperationInput, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyCallBatch,
+    TimeInterval, VerifiedContractSources,
+};
+use massa_models::clique::Clique;
+use massa_models::composite::PubkeySig;
+use massa_models::execution::ExecuteReadOnlyResponse;
+use massa_models::node::NodeId;
+use massa_models::output_event::SCOutputEvent;
+use massa_models::prehash::PreHashSet;
+use massa_models::{
+    address::Address,
+    block::{Block, BlockId},
+    endorsement::EndorsementId,
+    operation::OperationId,
+    slot::Slot,
+};
+use massa_protocol_exports::LightClientCommandSender;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use std::net::{IpAddr, SocketAddr};
+
+impl API<Light> {
+    /// generate a new light client API
+    pub fn new(
+        light_client_command_sender: LightClientCommandSender,
+        api_settings: APIConfig,
+    ) -> Self {
+        API(Light {
+            light_client_command_sender,
+            api_settings,
+        })
+    }
+}
+
+impl RpcServer for API<Light> {
+    fn serve(self, url: &SocketAddr) -> StopHandle {
+        crate::serve(self, url)
+    }
+}
+
+/// Only the light client endpoints are available: the node neither executes blocks
+/// nor keeps them.
+#[doc(hidden)]
+impl Endpoints for API<Light> {
+    fn stop_node(&self) -> BoxFuture<Result<(), ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn node_sign_message(&self, _: Vec<u8>) -> BoxFuture<Result<PubkeySig, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn add_staking_secret_keys(&self, _: Vec<KeyPair>) -> BoxFuture<Result<(), ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn execute_read_only_bytecode(
+        &self,
+        _: Vec<ReadOnlyBytecodeExecution>,
+    ) -> BoxFuture<Result<Vec<ExecuteReadOnlyResponse>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn execute_read_only_call(
+        &self,
+        _: Vec<ReadOnlyCall>,
+    ) -> BoxFuture<Result<Vec<ExecuteReadOnlyResponse>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn execute_read_only_call_batch(
+        &self,
+        _: ReadOnlyCallBatch,
+    ) -> BoxFuture<Result<Vec<ExecuteReadOnlyResponse>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn remove_staking_addresses(&self, _: Vec<Address>) -> BoxFuture<Result<(), ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_staking_addresses(&self) -> BoxFuture<Result<PreHashSet<Address>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn node_ban_by_ip(
+        &self,
+        _: Vec<IpAddr>,
+        _: Option<MassaTime>,
+    ) -> BoxFuture<Result<(), ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn node_ban_by_id(
+        &self,
+        _: Vec<NodeId>,
+        _: Option<MassaTime>,
+    ) -> BoxFuture<Result<(), ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_banned_peers(&self) -> BoxFuture<Result<Vec<BannedPeerInfo>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn node_unban_by_id(&self, _: Vec<NodeId>) -> BoxFuture<Result<(), ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn node_unban_by_ip(&self, _: Vec<IpAddr>) -> BoxFuture<Result<(), ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_status(&self) -> BoxFuture<Result<NodeStatus, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_cliques(&self) -> BoxFuture<Result<Vec<Clique>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_stakers(&self) -> BoxFuture<Result<Vec<(Address, u64)>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_operations(
+        &self,
+        _: Vec<OperationId>,
+    ) -> BoxFuture<Result<Vec<OperationInfo>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_endorsements(
+        &self,
+        _: Vec<EndorsementId>,
+    ) -> BoxFuture<Result<Vec<EndorsementInfo>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_block(&self, _: BlockId) -> BoxFuture<Result<BlockInfo, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_blockclique_block_by_slot(&self, _: Slot) -> BoxFuture<Result<Option<Block>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_graph_interval(
+        &self,
+        _: TimeInterval,
+    ) -> BoxFuture<Result<Vec<BlockSummary>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_datastore_entries(
+        &self,
+        _: Vec<DatastoreEntryInput>,
+    ) -> BoxFuture<Result<Vec<DatastoreEntryOutput>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_ledger_proof(
+        &self,
+        _: Vec<(Address, LedgerSubEntry)>,
+    ) -> BoxFuture<Result<LedgerProofs, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_ledger_root(&self, _: Slot) -> BoxFuture<Result<Option<Hash>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_addresses(&self, _: Vec<Address>) -> BoxFuture<Result<Vec<AddressInfo>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn submit_contract_sources(
+        &self,
+        _: ContractSourceBundle,
+    ) -> BoxFuture<Result<VerifiedContractSources, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_contract_metadata(
+        &self,
+        _: Vec<Address>,
+    ) -> BoxFuture<Result<Vec<ContractMetadata>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn send_operations(
+        &self,
+        _: Vec<OperationInput>,
+    ) -> BoxFuture<Result<Vec<OperationId>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_filtered_sc_output_event(
+        &self,
+        _: EventFilter,
+    ) -> BoxFuture<Result<Vec<SCOutputEvent>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_async_messages(
+        &self,
+        _: AsyncMessageFilter,
+    ) -> BoxFuture<Result<Vec<AsyncMessageInfo>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_address_history(
+        &self,
+        _: AddressHistoryRequest,
+    ) -> BoxFuture<Result<Vec<AddressHistoryEntry>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_block_execution_receipt(
+        &self,
+        _: BlockId,
+    ) -> BoxFuture<Result<Option<BlockExecutionReceipt>, ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn node_whitelist(&self, _: Vec<IpAddr>) -> BoxFuture<Result<(), ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn node_remove_from_whitelist(&self, _: Vec<IpAddr>) -> BoxFuture<Result<(), ApiError>> {
+        crate::wrong_api()
+    }
+
+    fn get_light_client_status(&self) -> BoxFuture<Result<LightClientStatus, ApiError>> {
+        let light_client_command_sender = self.0.light_client_command_sender.clone();
+        let closure = async move || Ok(light_client_command_sender.get_status().await?);
+        Box::pin(closure())
+    }
+
+    fn get_light_client_balances(
+        &self,
+        addresses: Vec<Address>,
+    ) -> BoxFuture<Result<Vec<LightClientBalance>, ApiError>> {
+        if addresses.len() as u64 > self.0.api_settings.max_arguments {
+            let closure =
+                async move || Err(ApiError::TooManyArguments("too many arguments".into()));
+            return Box::pin(closure());
+        }
+        let light_client_command_sender = self.0.light_client_command_sender.clone();
+        let closure = async move || {
+            Ok(light_client_command_sender
+                .get_final_balances(addresses)
+                .await?)
+        };
+        Box::pin(closure())
+    }
+
+    fn get_light_client_operations(
+        &self,
+        operation_ids: Vec<OperationId>,
+    ) -> BoxFuture<Result<Vec<LightClientOperationInclusion>, ApiError>> {
+        if operation_ids.len() as u64 > self.0.api_settings.max_arguments {
+            let closure =
+                async move || Err(ApiError::TooManyArguments("too many arguments".into()));
+            return Box::pin(closure());
+        }
+        let light_client_command_sender = self.0.light_client_command_sender.clone();
+        let closure = async move || {
+            Ok(light_client_command_sender
+                .get_operation_inclusion(operation_ids)
+                .await?)
+        };
+        Box::pin(closure())
+    }
+}
@@ -1,23 +1,29 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
 use crate::config::APIConfig;
+use crate::contract_registry::verify_contract_sources;
 use crate::error::ApiError;
-use crate::{Endpoints, Private, RpcServer, StopHandle, API};
+use crate::{ContractRegistry, Endpoints, Private, RpcServer, StopHandle, API};
 
 use jsonrpc_core::BoxFuture;
-use jsonrpc_http_server::tokio::sync::mpsc;
+use jsonrpc_http_server::tokio::sync::{mpsc, Semaphore};
 
 use massa_consensus_exports::{ConsensusCommandSender, ConsensusConfig};
 use massa_execution_exports::ExecutionController;
+use massa_hash::Hash;
+use massa_ledger_exports::{LedgerProofs, LedgerSubEntry};
 use massa_models::api::{
-    AddressInfo, BlockInfo, BlockSummary, DatastoreEntryInput, DatastoreEntryOutput,
-    EndorsementInfo, EventFilter, NodeStatus, OperationInfo, OperationInput,
-    ReadOnlyBytecodeExecution, ReadOnlyCall, TimeInterval,
+    AddressHistoryEntry, AddressHistoryRequest, AddressInfo, AsyncMessageFilter, AsyncMessageInfo,
+    BannedPeerInfo, BlockExecutionReceipt, BlockInfo, BlockSummary, ContractMetadata,
+    ContractSourceBundle, DatastoreEntryInput, DatastoreEntryOutput, EndorsementInfo, EventFilter,
+    LightClientBalance, LightClientOperationInclusion, LightClientStatus, NodeStatus,
+    OperationInfo, OperationInput, ReadOnlyBytecodeExecution, ReadOnlyCall, ReadOnlyCallBatch,
+    TimeInterval, VerifiedContractSources,
 };
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
 use massa_models::execution::ExecuteReadOnlyResponse;
-use massa_models::node::NodeId;
+use massa_models::node::{BanReason, NodeId};
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::{
@@ -29,6 +35,7 @@ use massa_models::{
 };
 use massa_network_exports::NetworkCommandSender;
 use massa_signature::KeyPair;
+use massa_time::MassaTime;
 use massa_wallet::Wallet;
 
 use parking_lot::RwLock;
@@ -44,8 +51,12 @@ impl API<Private> {
         api_settings: APIConfig,
         consensus_settings: ConsensusConfig,
         node_wallet: Arc<RwLock<Wallet>>,
+        compensation_millis: i64,
+        contract_registry: ContractRegistry,
     ) -> (Self, mpsc::Receiver<()>) {
         let (stop_node_channel, rx) = mpsc::channel(1);
+        let contract_compilations =
+            Arc::new(Semaphore::new(api_settings.max_contract_compilations));
         (
             API(Private {
                 consensus_command_sender,
@@ -55,6 +66,9 @@ impl API<Private> {
                 api_settings,
                 stop_node_channel,
                 node_wallet,
+                compensation_millis,
+                contract_registry,
+                contract_compilations,
             }),
             rx,
         )
@@ -110,6 +124,13 @@ impl Endpoints for API<Private> {
         crate::wrong_api::<_>()
     }
 
+    fn execute_read_only_call_batch(
+        &self,
+        _batch: ReadOnlyCallBatch,
+    ) -> BoxFuture<Result<Vec<ExecuteReadOnlyResponse>, ApiError>> {
+        crate::wrong_api::<_>()
+    }
+
     fn remove_staking_addresses(&self, addresses: Vec<Address>) -> BoxFuture<Result<(), ApiError>> {
         let node_wallet = self.0.node_wallet.clone();
         let closure = async move || {
@@ -126,15 +147,54 @@ impl Endpoints for API<Private> {
         Box::pin(closure())
     }
 
-    fn node_ban_by_ip(&self, ips: Vec<IpAddr>) -> BoxFuture<Result<(), ApiError>> {
+    fn node_ban_by_ip(
+        &self,
+        ips: Vec<IpAddr>,
+        duration: Option<MassaTime>,
+    ) -> BoxFuture<Result<(), ApiError>> {
+        let network_command_sender = self.0.network_command_sender.clone();
+        let closure = async move || {
+            Ok(network_command_sender
+                .node_ban_by_ips(ips, BanReason::Manual, duration)
+                .await?)
+        };
+        Box::pin(closure())
+    }
+
+    fn node_ban_by_id(
+        &self,
+        ids: Vec<NodeId>,
+        duration: Option<MassaTime>,
+    ) -> BoxFuture<Result<(), ApiError>> {
         let network_command_sender = self.0.network_command_sender.clone();
-        let closure = async move || Ok(network_command_sender.node_ban_by_ips(ips).await?);
+        let closure = async move || {
+            Ok(network_command_sender
+                .node_ban_by_ids(ids, BanReason::Manual, duration)
+                .await?)
+        };
         Box::pin(closure())
     }
 
-    fn node_ban_by_id(&self, ids: Vec<NodeId>) -> BoxFuture<Result<(), ApiError>> {
+    fn get_banned_peers(&self) -> BoxFuture<Result<Vec<BannedPeerInfo>, ApiError>> {
         let network_command_sender = self.0.network_command_sender.clone();
-        let closure = async move || Ok(network_command_sender.node_ban_by_ids(ids).await?);
+        let closure = async move || {
+            let peers = network_command_sender.get_peers().await?;
+            let mut banned_peers: Vec<BannedPeerInfo> = peers
+                .peers
+                .values()
+                .filter(|peer| peer.peer_info.banned)
+                .filter_map(|peer| {
+                    peer.peer_info.ban.map(|ban| BannedPeerInfo {
+                        address: peer.peer_info.address,
+                        reason: ban.reason,
+                        banned_at: ban.banned_at,
+                        expires_at: ban.expires_at,
+                    })
+                })
+                .collect();
+            banned_peers.sort_unstable_by_key(|peer| peer.banned_at);
+            Ok(banned_peers)
+        };
         Box::pin(closure())
     }
 
@@ -150,6 +210,56 @@ impl Endpoints for API<Private> {
         Box::pin(closure())
     }
 
+    fn submit_contract_sources(
+        &self,
+        bundle: ContractSourceBundle,
+    ) -> BoxFuture<Result<VerifiedContractSources, ApiError>> {
+        if bundle.sources.len() as u64 > self.0.api_settings.max_arguments {
+            let closure =
+                async move || Err(ApiError::TooManyArguments("too many source files".into()));
+            return Box::pin(closure());
+        }
+        // refuse the submission rather than queuing it when all compilation slots are taken
+        let permit = match self.0.contract_compilations.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(_) => {
+                let closure = async move || {
+                    Err(ApiError::ContractVerificationError(
+                        "too many compilations in progress, retry later".into(),
+                    ))
+                };
+                return Box::pin(closure());
+            }
+        };
+
+        // only the final bytecode is verified, as the candidate one may still change
+        let code_hash = self
+            .0
+            .execution_controller
+            .get_addresses_infos(&[bundle.address])
+            .pop()
+            .and_then(|info| info.final_bytecode_metadata)
+            .map(|metadata| metadata.code_hash);
+        let api_cfg = self.0.api_settings.clone();
+        let compensation_millis = self.0.compensation_millis;
+        let contract_registry = self.0.contract_registry.clone();
+        let closure = async move || {
+            let code_hash = code_hash.ok_or_else(|| {
+                ApiError::ContractVerificationError(format!(
+                    "no final bytecode at address {}",
+                    bundle.address
+                ))
+            })?;
+            let verified_at = MassaTime::now(compensation_millis)?;
+            let verified = verify_contract_sources(&api_cfg, bundle, code_hash, verified_at).await;
+            drop(permit);
+            let verified = verified?;
+            contract_registry.insert(verified.clone()).await?;
+            Ok(verified)
+        };
+        Box::pin(closure())
+    }
+
     fn get_status(&self) -> BoxFuture<Result<NodeStatus, ApiError>> {
         crate::wrong_api::<NodeStatus>()
     }
@@ -198,10 +308,28 @@ impl Endpoints for API<Private> {
         crate::wrong_api()
     }
 
+    fn get_ledger_proof(
+        &self,
+        _: Vec<(Address, LedgerSubEntry)>,
+    ) -> BoxFuture<Result<LedgerProofs, ApiError>> {
+        crate::wrong_api::<LedgerProofs>()
+    }
+
+    fn get_ledger_root(&self, _: Slot) -> BoxFuture<Result<Option<Hash>, ApiError>> {
+        crate::wrong_api::<Option<Hash>>()
+    }
+
     fn get_addresses(&self, _: Vec<Address>) -> BoxFuture<Result<Vec<AddressInfo>, ApiError>> {
         crate::wrong_api::<Vec<AddressInfo>>()
     }
 
+    fn get_contract_metadata(
+        &self,
+        _: Vec<Address>,
+    ) -> BoxFuture<Result<Vec<ContractMetadata>, ApiError>> {
+        crate::wrong_api::<Vec<ContractMetadata>>()
+    }
+
     fn send_operations(
         &self,
         _: Vec<OperationInput>,
@@ -216,6 +344,27 @@ impl Endpoints for API<Private> {
         crate::wrong_api::<Vec<SCOutputEvent>>()
     }
 
+    fn get_async_messages(
+        &self,
+        _: AsyncMessageFilter,
+    ) -> BoxFuture<Result<Vec<AsyncMessageInfo>, ApiError>> {
+        crate::wrong_api::<Vec<AsyncMessageInfo>>()
+    }
+
+    fn get_address_history(
+        &self,
+        _: AddressHistoryRequest,
+    ) -> BoxFuture<Result<Vec<AddressHistoryEntry>, ApiError>> {
+        crate::wrong_api::<Vec<AddressHistoryEntry>>()
+    }
+
+    fn get_block_execution_receipt(
+        &self,
+        _: BlockId,
+    ) -> BoxFuture<Result<Option<BlockExecutionReceipt>, ApiError>> {
+        crate::wrong_api::<Option<BlockExecutionReceipt>>()
+    }
+
     fn node_whitelist(&self, ips: Vec<IpAddr>) -> BoxFuture<Result<(), ApiError>> {
         let network_command_sender = self.0.network_command_sender.clone();
         let closure = async move || Ok(network_command_sender.whitelist(ips).await?);
@@ -227,4 +376,22 @@ impl Endpoints for API<Private> {
         let closure = async move || Ok(network_command_sender.remove_from_whitelist(ips).await?);
         Box::pin(closure())
     }
+
+    fn get_light_client_status(&self) -> BoxFuture<Result<LightClientStatus, ApiError>> {
+        crate::wrong_api::<LightClientStatus>()
+    }
+
+    fn get_light_client_balances(
+        &self,
+        _: Vec<Address>,
+    ) -> BoxFuture<Result<Vec<LightClientBalance>, ApiError>> {
+        crate::wrong_api::<Vec<LightClientBalance>>()
+    }
+
+    fn get_light_client_operations(
+        &self,
+        _: Vec<OperationId>,
+    ) -> BoxFuture<Result<Vec<LightClientOperationInclusion>, ApiError>> {
+        crate::wrong_api::<Vec<LightClientOperationInclusion>>()
+    }
 }
@@ -2,18 +2,26 @@
 #![allow(clippy::too_many_arguments)]
 use crate::config::APIConfig;
 use crate::error::ApiError;
-use crate::{Endpoints, Public, RpcServer, StopHandle, API};
+use crate::{ContractRegistry, Endpoints, Public, RpcServer, StopHandle, API};
 use jsonrpc_core::BoxFuture;
+use massa_async_pool::Change;
 use massa_consensus_exports::{ConsensusCommandSender, ConsensusConfig};
 use massa_execution_exports::{
-    ExecutionController, ExecutionStackElement, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ExecutionController, ExecutionError, ExecutionOutput, ExecutionStackElement,
+    ReadOnlyExecutionBatch, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget,
 };
 use massa_graph::DiscardReason;
+use massa_hash::Hash;
+use massa_ledger_exports::{
+    LedgerProofs, LedgerSubEntry, SetOrDelete, SetOrKeep, SetUpdateOrDelete,
+};
 use massa_models::api::{
-    BlockGraphStatus, DatastoreEntryInput, DatastoreEntryOutput, OperationInput,
-    ReadOnlyBytecodeExecution, ReadOnlyCall, SlotAmount,
+    BlockGraphStatus, ContractMetadata, ContractSourceBundle, DatastoreEntryInput,
+    DatastoreEntryOutput, OperationInput, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    ReadOnlyCallBatch, SlotAmount, VerifiedContractSources,
 };
-use massa_models::execution::ReadOnlyResult;
+use massa_models::execution::{ReadOnlyLedgerChange, ReadOnlyResult, ReadOnlyStateChanges};
 use massa_models::operation::OperationDeserializer;
 use massa_models::wrapped::WrappedDeserializer;
 use massa_models::{
@@ -28,8 +36,10 @@ use itertools::{izip, Itertools};
 use massa_models::{
     address::Address,
     api::{
-        AddressInfo, BlockInfo, BlockInfoContent, BlockSummary, EndorsementInfo, EventFilter,
-        NodeStatus, OperationInfo, TimeInterval,
+        AddressHistoryEntry, AddressHistoryRequest, AddressInfo, AsyncMessageFilter,
+        AsyncMessageInfo, AsyncMessageStatus, BannedPeerInfo, BlockExecutionReceipt, BlockInfo,
+        BlockInfoContent, BlockSummary, EndorsementInfo, EventFilter, LightClientBalance,
+        LightClientOperationInclusion, LightClientStatus, NodeStatus, OperationInfo, TimeInterval,
     },
     block::BlockId,
     clique::Clique,
@@ -68,6 +78,7 @@ impl API<Public> {
         compensation_millis: i64,
         node_id: NodeId,
         storage: Storage,
+        contract_registry: ContractRegistry,
     ) -> Self {
         API(Public {
             consensus_command_sender,
@@ -83,6 +94,7 @@ impl API<Public> {
             execution_controller,
             selector_controller,
             storage,
+            contract_registry,
         })
     }
 }
@@ -123,6 +135,8 @@ impl Endpoints for API<Public> {
             address,
             simulated_gas_price,
             bytecode,
+            state_overrides,
+            final_state_only,
         } in reqs
         {
             let address = address.unwrap_or_else(|| {
@@ -145,22 +159,15 @@ impl Endpoints for API<Public> {
                     coins: Default::default(),
                     owned_addresses: vec![address],
                 }],
+                state_overrides,
+                final_state_only,
             };
 
             // run
             let result = self.0.execution_controller.execute_readonly_request(req);
 
             // map result
-            let result = ExecuteReadOnlyResponse {
-                executed_at: result.as_ref().map_or_else(|_| Slot::new(0, 0), |v| v.slot),
-                result: result.as_ref().map_or_else(
-                    |err| ReadOnlyResult::Error(format!("readonly call failed: {}", err)),
-                    |_| ReadOnlyResult::Ok,
-                ),
-                output_events: result.map_or_else(|_| Default::default(), |mut v| v.events.take()),
-            };
-
-            res.push(result);
+            res.push(read_only_response(result));
         }
 
         // return result
@@ -179,62 +186,20 @@ impl Endpoints for API<Public> {
         }
 
         let mut res: Vec<ExecuteReadOnlyResponse> = Vec::with_capacity(reqs.len());
-        for ReadOnlyCall {
-            max_gas,
-            simulated_gas_price,
-            target_address,
-            target_function,
-            parameter,
-            caller_address,
-        } in reqs
-        {
-            let caller_address = caller_address.unwrap_or_else(|| {
-                // if no addr provided, use a random one
-                Address::from_public_key(&KeyPair::generate().get_public_key())
-            });
-
+        for call in reqs {
             // TODO:
             // * set a maximum gas value for read-only executions to prevent attacks
             // * stop mapping request and result, reuse execution's structures
             // * remove async stuff
 
             // translate request
-            let req = ReadOnlyExecutionRequest {
-                max_gas,
-                simulated_gas_price,
-                target: ReadOnlyExecutionTarget::FunctionCall {
-                    target_func: target_function,
-                    target_addr: target_address,
-                    parameter,
-                },
-                call_stack: vec![
-                    ExecutionStackElement {
-                        address: caller_address,
-                        coins: Default::default(),
-                        owned_addresses: vec![caller_address],
-                    },
-                    ExecutionStackElement {
-                        address: target_address,
-                        coins: Default::default(),
-                        owned_addresses: vec![target_address],
-                    },
-                ],
-            };
+            let req = read_only_call_request(call);
 
             // run
             let result = self.0.execution_controller.execute_readonly_request(req);
 
             // map result
-            let result = ExecuteReadOnlyResponse {
-                executed_at: result.as_ref().map_or_else(|_| Slot::new(0, 0), |v| v.slot),
-                result: result.as_ref().map_or_else(
-                    |err| ReadOnlyResult::Error(format!("readonly call failed: {}", err)),
-                    |_| ReadOnlyResult::Ok,
-                ),
-                output_events: result.map_or_else(|_| Default::default(), |mut v| v.events.take()),
-            };
-
-            res.push(result);
+            res.push(read_only_response(result));
         }
 
         // return result
@@ -242,6 +207,39 @@ impl Endpoints for API<Public> {
         Box::pin(closure())
     }
 
+    fn execute_read_only_call_batch(
+        &self,
+        batch: ReadOnlyCallBatch,
+    ) -> BoxFuture<Result<Vec<ExecuteReadOnlyResponse>, ApiError>> {
+        if batch.calls.len() as u64 > self.0.api_settings.max_arguments {
+            let closure =
+                async move || Err(ApiError::TooManyArguments("too many arguments".into()));
+            return Box::pin(closure());
+        }
+
+        let batch_len = batch.calls.len();
+        let req = ReadOnlyExecutionBatch {
+            requests: batch
+                .calls
+                .into_iter()
+                .map(read_only_call_request)
+                .collect(),
+            share_state: batch.share_state,
+        };
+
+        // all the calls of the batch run against the same state snapshot
+        let res: Vec<ExecuteReadOnlyResponse> =
+            match self.0.execution_controller.execute_readonly_batch(req) {
+                Ok(results) => results.into_iter().map(read_only_response).collect(),
+                Err(err) => (0..batch_len)
+                    .map(|_| read_only_response(Err(err.clone())))
+                    .collect(),
+            };
+
+        let closure = async move || Ok(res);
+        Box::pin(closure())
+    }
+
     fn remove_staking_addresses(&self, _: Vec<Address>) -> BoxFuture<Result<(), ApiError>> {
         crate::wrong_api::<()>()
     }
@@ -250,14 +248,26 @@ impl Endpoints for API<Public> {
         crate::wrong_api::<PreHashSet<Address>>()
     }
 
-    fn node_ban_by_ip(&self, _: Vec<IpAddr>) -> BoxFuture<Result<(), ApiError>> {
+    fn node_ban_by_ip(
+        &self,
+        _: Vec<IpAddr>,
+        _: Option<MassaTime>,
+    ) -> BoxFuture<Result<(), ApiError>> {
         crate::wrong_api::<()>()
     }
 
-    fn node_ban_by_id(&self, _: Vec<NodeId>) -> BoxFuture<Result<(), ApiError>> {
+    fn node_ban_by_id(
+        &self,
+        _: Vec<NodeId>,
+        _: Option<MassaTime>,
+    ) -> BoxFuture<Result<(), ApiError>> {
         crate::wrong_api::<()>()
     }
 
+    fn get_banned_peers(&self) -> BoxFuture<Result<Vec<BannedPeerInfo>, ApiError>> {
+        crate::wrong_api::<Vec<BannedPeerInfo>>()
+    }
+
     fn node_unban_by_ip(&self, _: Vec<IpAddr>) -> BoxFuture<Result<(), ApiError>> {
         crate::wrong_api::<()>()
     }
@@ -299,18 +309,29 @@ impl Endpoints for API<Public> {
                 pool_command_sender.get_endorsement_count(),
             );
 
+            let peers = peers?;
+
             Ok(NodeStatus {
                 node_id,
                 node_ip: network_config.routable_ip,
                 version,
                 current_time: now,
-                connected_nodes: peers?
+                connected_nodes: peers
                     .peers
                     .iter()
-                    .flat_map(|(ip, peer)| {
+                    .flat_map(|(address, peer)| {
+                        peer.active_nodes
+                            .iter()
+                            .map(move |(id, is_outgoing)| (*id, (address.ip(), *is_outgoing)))
+                    })
+                    .collect(),
+                connected_nodes_scores: peers
+                    .peers
+                    .values()
+                    .flat_map(|peer| {
                         peer.active_nodes
                             .iter()
-                            .map(move |(id, is_outgoing)| (*id, (*ip, *is_outgoing)))
+                            .map(move |(id, _)| (*id, peer.peer_info.score))
                     })
                     .collect(),
                 last_slot,
@@ -389,7 +410,7 @@ impl Endpoints for API<Public> {
         // ask pool whether it carries the operations
         let in_pool = self.0.pool_command_sender.contains_operations(&ops);
 
-        let api_cfg = self.0.api_settings;
+        let api_cfg = self.0.api_settings.clone();
         let consensus_command_sender = self.0.consensus_command_sender.clone();
         let closure = async move || {
             if ops.len() as u64 > api_cfg.max_arguments {
@@ -474,7 +495,7 @@ impl Endpoints for API<Public> {
         let in_pool = self.0.pool_command_sender.contains_endorsements(&eds);
 
         let consensus_command_sender = self.0.consensus_command_sender.clone();
-        let api_cfg = self.0.api_settings;
+        let api_cfg = self.0.api_settings.clone();
         let closure = async move || {
             if eds.len() as u64 > api_cfg.max_arguments {
                 return Err(ApiError::TooManyArguments("too many arguments".into()));
@@ -666,6 +687,26 @@ impl Endpoints for API<Public> {
         Box::pin(closure())
     }
 
+    fn get_ledger_proof(
+        &self,
+        entries: Vec<(Address, LedgerSubEntry)>,
+    ) -> BoxFuture<Result<LedgerProofs, ApiError>> {
+        if entries.len() as u64 > self.0.api_settings.max_arguments {
+            let closure =
+                async move || Err(ApiError::TooManyArguments("too many arguments".into()));
+            return Box::pin(closure());
+        }
+        let execution_controller = self.0.execution_controller.clone();
+        let closure = async move || Ok(execution_controller.get_final_ledger_proofs(entries));
+        Box::pin(closure())
+    }
+
+    fn get_ledger_root(&self, slot: Slot) -> BoxFuture<Result<Option<Hash>, ApiError>> {
+        let execution_controller = self.0.execution_controller.clone();
+        let closure = async move || Ok(execution_controller.get_final_ledger_root(slot));
+        Box::pin(closure())
+    }
+
     fn get_addresses(
         &self,
         addresses: Vec<Address>,
@@ -781,6 +822,10 @@ impl Endpoints for API<Public> {
                     .into_iter()
                     .collect::<Vec<_>>(),
 
+                // bytecode metadata
+                final_bytecode_metadata: execution_infos.final_bytecode_metadata,
+                candidate_bytecode_metadata: execution_infos.candidate_bytecode_metadata,
+
                 // deferred credits
                 deferred_credits: execution_infos
                     .future_deferred_credits
@@ -806,19 +851,68 @@ impl Endpoints for API<Public> {
         Box::pin(closure())
     }
 
+    fn submit_contract_sources(
+        &self,
+        _: ContractSourceBundle,
+    ) -> BoxFuture<Result<VerifiedContractSources, ApiError>> {
+        crate::wrong_api::<VerifiedContractSources>()
+    }
+
+    fn get_contract_metadata(
+        &self,
+        addresses: Vec<Address>,
+    ) -> BoxFuture<Result<Vec<ContractMetadata>, ApiError>> {
+        if addresses.len() as u64 > self.0.api_settings.max_arguments {
+            let closure =
+                async move || Err(ApiError::TooManyArguments("too many arguments".into()));
+            return Box::pin(closure());
+        }
+
+        let bytecode_metadata: Vec<_> = self
+            .0
+            .execution_controller
+            .get_addresses_infos(&addresses)
+            .into_iter()
+            .map(|info| info.final_bytecode_metadata)
+            .collect();
+        let contract_registry = self.0.contract_registry.clone();
+        let closure = async move || {
+            let code_hashes: Vec<Option<Hash>> = bytecode_metadata
+                .iter()
+                .map(|metadata| metadata.as_ref().map(|metadata| metadata.code_hash))
+                .collect();
+            let verified_sources = contract_registry.get(&code_hashes).await;
+            Ok(addresses
+                .into_iter()
+                .zip(bytecode_metadata)
+                .zip(verified_sources)
+                .map(
+                    |((address, bytecode_metadata), verified_sources)| ContractMetadata {
+                        address,
+                        bytecode_metadata,
+                        verified_sources,
+                    },
+                )
+                .collect())
+        };
+        Box::pin(closure())
+    }
+
     fn send_operations(
         &self,
         ops: Vec<OperationInput>,
     ) -> BoxFuture<Result<Vec<OperationId>, ApiError>> {
         let mut cmd_sender = self.0.pool_command_sender.clone();
         let mut protocol_sender = self.0.protocol_command_sender.clone();
-        let api_cfg = self.0.api_settings;
+        let api_cfg = self.0.api_settings.clone();
+        let thread_count = self.0.consensus_config.thread_count;
         let mut to_send = self.0.storage.clone_without_refs();
         let closure = async move || {
             if ops.len() as u64 > api_cfg.max_arguments {
                 return Err(ApiError::TooManyArguments("too many arguments".into()));
             }
             let operation_deserializer = WrappedDeserializer::new(OperationDeserializer::new(
+                thread_count,
                 api_cfg.max_datastore_value_length,
                 api_cfg.max_function_name_length,
                 api_cfg.max_parameter_size,
@@ -846,6 +940,7 @@ impl Endpoints for API<Public> {
                 .map(|op| match op {
                     Ok(operation) => {
                         operation.verify_signature()?;
+                        operation.verify_multisig_signatures()?;
                         Ok(operation)
                     }
                     Err(e) => Err(e),
@@ -880,6 +975,74 @@ impl Endpoints for API<Public> {
         Box::pin(closure())
     }
 
+    /// Get asynchronous messages and their status, optionally filtered by:
+    /// * sender address
+    /// * destination address
+    /// * emission slot
+    /// * emission index
+    fn get_async_messages(
+        &self,
+        mut filter: AsyncMessageFilter,
+    ) -> BoxFuture<Result<Vec<AsyncMessageInfo>, ApiError>> {
+        let max_arguments = self.0.api_settings.max_arguments;
+        match filter.limit {
+            Some(limit) if limit > max_arguments => {
+                let closure = async move || {
+                    Err(ApiError::TooManyArguments(
+                        "too many messages requested".into(),
+                    ))
+                };
+                return Box::pin(closure());
+            }
+            Some(_) => {}
+            None => filter.limit = Some(max_arguments),
+        }
+
+        let messages = self
+            .0
+            .execution_controller
+            .get_filtered_async_messages(filter);
+
+        let closure = async move || Ok(messages);
+        Box::pin(closure())
+    }
+
+    /// Get a page of the history of operations and coin transfers involving an address, most recent first
+    fn get_address_history(
+        &self,
+        req: AddressHistoryRequest,
+    ) -> BoxFuture<Result<Vec<AddressHistoryEntry>, ApiError>> {
+        if req.limit > self.0.api_settings.max_arguments {
+            let closure = async move || {
+                Err(ApiError::TooManyArguments(
+                    "too many entries requested".into(),
+                ))
+            };
+            return Box::pin(closure());
+        }
+
+        let history = self.0.execution_controller.get_address_history(
+            &req.address,
+            req.offset as usize,
+            req.limit as usize,
+        );
+
+        let closure = async move || Ok(history);
+        Box::pin(closure())
+    }
+
+    fn get_block_execution_receipt(
+        &self,
+        block_id: BlockId,
+    ) -> BoxFuture<Result<Option<BlockExecutionReceipt>, ApiError>> {
+        let receipt = self
+            .0
+            .execution_controller
+            .get_block_execution_receipt(&block_id);
+        let closure = async move || Ok(receipt);
+        Box::pin(closure())
+    }
+
     fn node_whitelist(&self, _: Vec<IpAddr>) -> BoxFuture<Result<(), ApiError>> {
         crate::wrong_api::<()>()
     }
@@ -887,4 +1050,179 @@ impl Endpoints for API<Public> {
     fn node_remove_from_whitelist(&self, _: Vec<IpAddr>) -> BoxFuture<Result<(), ApiError>> {
         crate::wrong_api::<()>()
     }
+
+    fn get_light_client_status(&self) -> BoxFuture<Result<LightClientStatus, ApiError>> {
+        crate::wrong_api::<LightClientStatus>()
+    }
+
+    fn get_light_client_balances(
+        &self,
+        _: Vec<Address>,
+    ) -> BoxFuture<Result<Vec<LightClientBalance>, ApiError>> {
+        crate::wrong_api::<Vec<LightClientBalance>>()
+    }
+
+    fn get_light_client_operations(
+        &self,
+        _: Vec<OperationId>,
+    ) -> BoxFuture<Result<Vec<LightClientOperationInclusion>, ApiError>> {
+        crate::wrong_api::<Vec<LightClientOperationInclusion>>()
+    }
+}
+
+/// Translates a read-only SC call into an execution request
+fn read_only_call_request(call: ReadOnlyCall) -> ReadOnlyExecutionRequest {
+    let ReadOnlyCall {
+        max_gas,
+        simulated_gas_price,
+        target_address,
+        target_function,
+        parameter,
+        caller_address,
+        state_overrides,
+        final_state_only,
+    } = call;
+    let caller_address = caller_address.unwrap_or_else(|| {
+        // if no addr provided, use a random one
+        Address::from_public_key(&KeyPair::generate().get_public_key())
+    });
+    ReadOnlyExecutionRequest {
+        max_gas,
+        simulated_gas_price,
+        target: ReadOnlyExecutionTarget::FunctionCall {
+            target_func: target_function,
+            target_addr: target_address,
+            parameter,
+        },
+        call_stack: vec![
+            ExecutionStackElement {
+                address: caller_address,
+                coins: Default::default(),
+                owned_addresses: vec![caller_address],
+            },
+            ExecutionStackElement {
+                address: target_address,
+                coins: Default::default(),
+                owned_addresses: vec![target_address],
+            },
+        ],
+        state_overrides,
+        final_state_only,
+    }
+}
+
+/// Translates the outcome of a read-only execution into its API representation
+fn read_only_response(
+    result: Result<ReadOnlyExecutionOutput, ExecutionError>,
+) -> ExecuteReadOnlyResponse {
+    match result {
+        Ok(mut output) => ExecuteReadOnlyResponse {
+            executed_at: output.out.slot,
+            result: ReadOnlyResult::Ok,
+            gas_cost: output.gas_cost,
+            state_changes: read_only_state_changes(&output.out),
+            output_events: output.out.events.take(),
+        },
+        Err(err) => ExecuteReadOnlyResponse {
+            executed_at: Slot::new(0, 0),
+            result: ReadOnlyResult::Error(
+                std::iter::once("readonly call failed".to_string())
+                    .chain(err.chain())
+                    .collect(),
+            ),
+            gas_cost: 0,
+            state_changes: Default::default(),
+            output_events: Default::default(),
+        },
+    }
+}
+
+/// Translates the state changes of a read-only execution into their API representation
+fn read_only_state_changes(output: &ExecutionOutput) -> ReadOnlyStateChanges {
+    let changes = &output.state_changes;
+    let ledger_changes = changes
+        .ledger_changes
+        .0
+        .iter()
+        .map(|(address, change)| match change {
+            SetUpdateOrDelete::Set(entry) => ReadOnlyLedgerChange {
+                address: *address,
+                deleted: false,
+                sequential_balance: Some(entry.sequential_balance),
+                parallel_balance: Some(entry.parallel_balance),
+                bytecode: Some(entry.bytecode.clone()),
+                datastore: entry
+                    .datastore
+                    .iter()
+                    .map(|(key, value)| (key.clone(), Some(value.clone())))
+                    .collect(),
+            },
+            SetUpdateOrDelete::Update(update) => ReadOnlyLedgerChange {
+                address: *address,
+                deleted: false,
+                sequential_balance: match update.sequential_balance {
+                    SetOrKeep::Set(balance) => Some(balance),
+                    SetOrKeep::Keep => None,
+                },
+                parallel_balance: match update.parallel_balance {
+                    SetOrKeep::Set(balance) => Some(balance),
+                    SetOrKeep::Keep => None,
+                },
+                bytecode: match &update.bytecode {
+                    SetOrKeep::Set(bytecode) => Some(bytecode.clone()),
+                    SetOrKeep::Keep => None,
+                },
+                datastore: update
+                    .datastore
+                    .iter()
+                    .map(|(key, value)| match value {
+                        SetOrDelete::Set(value) => (key.clone(), Some(value.clone())),
+                        SetOrDelete::Delete => (key.clone(), None),
+                    })
+                    .collect(),
+            },
+            SetUpdateOrDelete::Delete => ReadOnlyLedgerChange {
+                address: *address,
+                deleted: true,
+                sequential_balance: None,
+                parallel_balance: None,
+                bytecode: None,
+                datastore: Vec::new(),
+            },
+        })
+        .collect();
+    let mut async_messages_added = Vec::new();
+    let mut async_messages_removed = Vec::new();
+    for change in changes.async_pool_changes.0.iter() {
+        match change {
+            Change::Add(_, message) => {
+                async_messages_added.push(message.info(AsyncMessageStatus::Pending, None, false))
+            }
+            Change::Delete((_, emission_slot, emission_index)) => {
+                async_messages_removed.push((*emission_slot, *emission_index))
+            }
+        }
+    }
+    ReadOnlyStateChanges {
+        ledger_changes,
+        async_messages_added,
+        async_messages_removed,
+        deferred_credits: changes
+            .pos_changes
+            .deferred_credits
+            .0
+            .iter()
+            .flat_map(|(slot, credits)| {
+                credits
+                    .iter()
+                    .map(move |(address, amount)| (*slot, *address, *amount))
+            })
+            .collect(),
+        roll_changes: changes
+            .pos_changes
+            .roll_changes
+            .iter()
+            .map(|(address, roll_count)| (*address, *roll_count))
+            .collect(),
+    }
 }
@@ -8,6 +8,7 @@ use massa_models::slot::{SlotDeserializer, SlotSerializer};
 use massa_models::{
     address::Address,
     amount::Amount,
+    api::{AsyncMessageInfo, AsyncMessageStatus},
     serialization::{VecU8Deserializer, VecU8Serializer},
     slot::Slot,
 };
@@ -218,6 +219,35 @@ impl AsyncMessage {
             self.emission_index,
         )
     }
+
+    /// Builds the `AsyncMessageInfo` describing the message and its status
+    ///
+    /// # Arguments
+    /// * `status`: current status of the message
+    /// * `status_slot`: slot at which the status was reached (None for pending messages)
+    /// * `is_final`: whether the status is final
+    pub fn info(
+        &self,
+        status: AsyncMessageStatus,
+        status_slot: Option<Slot>,
+        is_final: bool,
+    ) -> AsyncMessageInfo {
+        AsyncMessageInfo {
+            emission_slot: self.emission_slot,
+            emission_index: self.emission_index,
+            sender: self.sender,
+            destination: self.destination,
+            handler: self.handler.clone(),
+            max_gas: self.max_gas,
+            gas_price: self.gas_price,
+            coins: self.coins,
+            validity_start: self.validity_start,
+            validity_end: self.validity_end,
+            status_slot,
+            status,
+            is_final,
+        }
+    }
 }
 
 pub struct AsyncMessageSerializer {
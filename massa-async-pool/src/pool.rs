@@ -130,6 +130,44 @@ impl AsyncPool {
             .collect()
     }
 
+    /// Gets the messages of the pool matching a predicate, in priority order (from highest to lowest priority)
+    ///
+    /// # arguments
+    /// * `predicate`: function returning true for the messages to select
+    pub fn get_filtered_messages<F>(&self, predicate: F) -> Vec<(AsyncMessageId, AsyncMessage)>
+    where
+        F: Fn(&AsyncMessage) -> bool,
+    {
+        self.messages
+            .iter()
+            .filter(|(_id, msg)| predicate(msg))
+            .map(|(id, msg)| (*id, msg.clone()))
+            .collect()
+    }
+
+    /// Finds a message of the pool from its emission slot and index
+    ///
+    /// # arguments
+    /// * `emission_slot`: slot at which the message was emitted
+    /// * `emission_index`: index of the message among the ones emitted at `emission_slot`
+    pub fn find_message(
+        &self,
+        emission_slot: &Slot,
+        emission_index: u64,
+    ) -> Option<(AsyncMessageId, AsyncMessage)> {
+        self.messages
+            .iter()
+            .find(|(_id, msg)| {
+                msg.emission_slot == *emission_slot && msg.emission_index == emission_index
+            })
+            .map(|(id, msg)| (*id, msg.clone()))
+    }
+
+    /// Removes a message from the pool, returning it if it was present
+    pub fn remove_message(&mut self, message_id: &AsyncMessageId) -> Option<AsyncMessage> {
+        self.messages.remove(message_id)
+    }
+
     /// Used for bootstrap
     /// Take a part of the async pool starting from the next element after `last_id` and with a max length of the constant `ASYNC_POOL_PART_SIZE_MESSAGE_BYTES`.
     /// Should always follow the same behavior as the `get_ledger_part` from `FinalLedger`method.
@@ -240,3 +278,46 @@ fn test_take_batch() {
     pool.take_batch_to_execute(Slot::new(2, 0), 19);
     assert_eq!(pool.messages.len(), 6);
 }
+
+#[test]
+fn test_find_and_remove_message() {
+    use massa_hash::Hash;
+    use massa_models::{address::Address, amount::Amount, slot::Slot};
+    use std::str::FromStr;
+
+    let config = AsyncPoolConfig {
+        thread_count: 2,
+        max_length: 10,
+        max_data_async_message: 1000000,
+        part_size_message_bytes: 1_000_000,
+    };
+    let mut pool = AsyncPool::new(config);
+    let sender = Address(Hash::compute_from(b"abc"));
+    let destination = Address(Hash::compute_from(b"def"));
+    for i in 0..5 {
+        let msg = AsyncMessage {
+            emission_slot: Slot::new(1, 0),
+            emission_index: i,
+            sender: if i % 2 == 0 { sender } else { destination },
+            destination,
+            handler: "function".to_string(),
+            validity_start: Slot::new(1, 0),
+            validity_end: Slot::new(3, 0),
+            max_gas: 10,
+            gas_price: Amount::from_str("0.1").unwrap(),
+            coins: Amount::from_str("0.3").unwrap(),
+            data: Vec::new(),
+        };
+        pool.messages.insert(msg.compute_id(), msg);
+    }
+    assert_eq!(
+        pool.get_filtered_messages(|msg| msg.sender == sender).len(),
+        3
+    );
+    let (id, msg) = pool.find_message(&Slot::new(1, 0), 3).unwrap();
+    assert_eq!(msg.emission_index, 3);
+    assert!(pool.find_message(&Slot::new(1, 1), 3).is_none());
+    assert_eq!(pool.remove_message(&id).unwrap().emission_index, 3);
+    assert!(pool.find_message(&Slot::new(1, 0), 3).is_none());
+    assert_eq!(pool.messages.len(), 4);
+}
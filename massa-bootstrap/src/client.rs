@@ -4,7 +4,7 @@ use massa_final_state::{ExecutedOpsStreamingStep, FinalState};
 use massa_ledger_exports::get_address_from_key;
 use massa_logging::massa_trace;
 use massa_models::version::Version;
-use massa_pos_exports::PoSCycleStreamingStep;
+use massa_pos_exports::{PoSCycleStreamingStep, PoSFinalState};
 use massa_signature::PublicKey;
 use massa_time::MassaTime;
 use nom::AsBytes;
@@ -20,7 +20,7 @@ use crate::{
     client_binder::BootstrapClientBinder,
     error::BootstrapError,
     messages::{BootstrapClientMessage, BootstrapServerMessage},
-    BootstrapConfig, Establisher, GlobalBootstrapState,
+    BootstrapConfig, Establisher, GlobalBootstrapState, LightBootstrapState,
 };
 
 /// This function will send the starting point to receive a stream of the ledger and will receive and process each part until receive a `BootstrapServerMessage::FinalStateFinished` message from the server.
@@ -156,17 +156,14 @@ async fn stream_final_state(
     }
 }
 
-/// Gets the state from a bootstrap server (internal private function)
+/// Reads the error the server may send at connection, makes the handshake and syncs clocks.
+/// Returns the clock compensation in milliseconds.
 /// needs to be CANCELLABLE
-async fn bootstrap_from_server(
+async fn handshake_with_server(
     cfg: &BootstrapConfig,
     client: &mut BootstrapClientBinder,
-    next_bootstrap_message: &mut BootstrapClientMessage,
-    global_bootstrap_state: &mut GlobalBootstrapState,
     our_version: Version,
-) -> Result<(), BootstrapError> {
-    massa_trace!("bootstrap.lib.bootstrap_from_server", {});
-
+) -> Result<i64, BootstrapError> {
     // read error (if sent by the server)
     // client.next() is not cancel-safe but we drop the whole client object if cancelled => it's OK
     match tokio::time::timeout(cfg.read_error_timeout.into(), client.next()).await {
@@ -266,7 +263,22 @@ async fn bootstrap_from_server(
         0
     };
 
-    global_bootstrap_state.compensation_millis = compensation_millis;
+    Ok(compensation_millis)
+}
+
+/// Gets the state from a bootstrap server (internal private function)
+/// needs to be CANCELLABLE
+async fn bootstrap_from_server(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    next_bootstrap_message: &mut BootstrapClientMessage,
+    global_bootstrap_state: &mut GlobalBootstrapState,
+    our_version: Version,
+) -> Result<(), BootstrapError> {
+    massa_trace!("bootstrap.lib.bootstrap_from_server", {});
+
+    global_bootstrap_state.compensation_millis =
+        handshake_with_server(cfg, client, our_version).await?;
 
     let write_timeout: std::time::Duration = cfg.write_timeout.into();
     // Loop to ask data to the server depending on the last message we sent
@@ -287,6 +299,9 @@ async fn bootstrap_from_server(
                 .await?
                 {
                     BootstrapServerMessage::BootstrapPeers { peers } => peers,
+                    BootstrapServerMessage::LegacyBootstrapPeers { peers } => {
+                        peers.with_port(cfg.protocol_port)
+                    }
                     BootstrapServerMessage::BootstrapError { error } => {
                         return Err(BootstrapError::ReceivedError(error))
                     }
@@ -338,12 +353,97 @@ async fn bootstrap_from_server(
             BootstrapClientMessage::BootstrapError { error: _ } => {
                 panic!("The next message to send shouldn't be BootstrapError");
             }
+            BootstrapClientMessage::AskLightState => {
+                return Err(BootstrapError::GeneralError(
+                    "the light state is not part of a full bootstrap".into(),
+                ));
+            }
         };
     }
     info!("Successful bootstrap");
     Ok(())
 }
 
+/// Gets the state of a light client from a bootstrap server (internal private function)
+/// needs to be CANCELLABLE
+async fn light_bootstrap_from_server(
+    cfg: &BootstrapConfig,
+    client: &mut BootstrapClientBinder,
+    pos_state: &mut PoSFinalState,
+    our_version: Version,
+) -> Result<LightBootstrapState, BootstrapError> {
+    massa_trace!("bootstrap.lib.light_bootstrap_from_server", {});
+
+    let compensation_millis = handshake_with_server(cfg, client, our_version).await?;
+
+    let write_timeout: std::time::Duration = cfg.write_timeout.into();
+    let (final_headers, pos_cycle_parts, slot) = match send_client_message(
+        &BootstrapClientMessage::AskLightState,
+        client,
+        write_timeout,
+        cfg.read_timeout.into(),
+        "ask light state timed out",
+    )
+    .await?
+    {
+        BootstrapServerMessage::LightState {
+            final_headers,
+            pos_cycle_parts,
+            slot,
+        } => (final_headers, pos_cycle_parts, slot),
+        BootstrapServerMessage::BootstrapError { error } => {
+            return Err(BootstrapError::ReceivedError(error))
+        }
+        other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+    };
+    // a previous attempt may have left a part of the cycle history
+    pos_state.cycle_history.clear();
+    for part in pos_cycle_parts {
+        pos_state.set_cycle_history_part(part.as_bytes())?;
+    }
+
+    let peers = match send_client_message(
+        &BootstrapClientMessage::AskBootstrapPeers,
+        client,
+        write_timeout,
+        cfg.read_timeout.into(),
+        "ask bootstrap peers timed out",
+    )
+    .await?
+    {
+        BootstrapServerMessage::BootstrapPeers { peers } => peers,
+        BootstrapServerMessage::LegacyBootstrapPeers { peers } => {
+            peers.with_port(cfg.protocol_port)
+        }
+        BootstrapServerMessage::BootstrapError { error } => {
+            return Err(BootstrapError::ReceivedError(error))
+        }
+        other => return Err(BootstrapError::UnexpectedServerMessage(other)),
+    };
+
+    match tokio::time::timeout(
+        write_timeout,
+        client.send(&BootstrapClientMessage::BootstrapSuccess),
+    )
+    .await
+    {
+        Err(_) => Err(std::io::Error::new(
+            std::io::ErrorKind::TimedOut,
+            "send bootstrap success timed out",
+        )
+        .into()),
+        Ok(Err(e)) => Err(e),
+        Ok(Ok(_)) => Ok(()),
+    }?;
+    info!("Successful light bootstrap");
+    Ok(LightBootstrapState {
+        final_headers,
+        slot,
+        compensation_millis,
+        peers,
+    })
+}
+
 async fn send_client_message(
     message_to_send: &BootstrapClientMessage,
     client: &mut BootstrapClientBinder,
@@ -482,3 +582,64 @@ pub async fn get_state(
         }
     }
 }
+
+/// Gets the state of a light client from a bootstrap server:
+/// the headers of the final blocks and the Proof of Stake cycle history, written in `pos_state`.
+/// The ledger is not bootstrapped.
+/// needs to be CANCELLABLE
+pub async fn get_light_state(
+    bootstrap_config: &BootstrapConfig,
+    pos_state: &mut PoSFinalState,
+    mut establisher: Establisher,
+    version: Version,
+    genesis_timestamp: MassaTime,
+    end_timestamp: Option<MassaTime>,
+) -> Result<LightBootstrapState, BootstrapError> {
+    massa_trace!("bootstrap.lib.get_light_state", {});
+    // a light client follows the final blocks of a full node
+    if MassaTime::now(0)? < genesis_timestamp {
+        return Err(BootstrapError::GeneralError(
+            "a light client cannot start before genesis".into(),
+        ));
+    }
+    if bootstrap_config.bootstrap_list.is_empty() {
+        return Err(BootstrapError::GeneralError(
+            "no bootstrap nodes found in list".into(),
+        ));
+    }
+    let mut shuffled_list = bootstrap_config.bootstrap_list.clone();
+    shuffled_list.shuffle(&mut StdRng::from_entropy());
+    loop {
+        for (addr, pub_key) in shuffled_list.iter() {
+            if let Some(end) = end_timestamp {
+                if MassaTime::now(0).expect("could not get now time") > end {
+                    panic!("This episode has come to an end, please get the latest testnet node version to continue");
+                }
+            }
+            info!("Start light bootstrapping from {}", addr);
+            match connect_to_server(&mut establisher, bootstrap_config, addr, pub_key).await {
+                Ok(mut client) => {
+                    match light_bootstrap_from_server(bootstrap_config, &mut client, pos_state, version)
+                    .await  // cancellable
+                    {
+                        Err(BootstrapError::ReceivedError(error)) => warn!("Error received from bootstrap server: {}", error),
+                        Err(e) => {
+                            warn!("Error while bootstrapping: {}", e);
+                            // We allow unused result because we don't care if an error is thrown when sending the error message to the server we will close the socket anyway.
+                            let _ = tokio::time::timeout(bootstrap_config.write_error_timeout.into(), client.send(&BootstrapClientMessage::BootstrapError { error: e.to_string() })).await;
+                        }
+                        Ok(light_state) => {
+                            return Ok(light_state)
+                        }
+                    }
+                }
+                Err(e) => {
+                    warn!("Error while connecting to bootstrap server: {}", e);
+                }
+            };
+
+            info!("Bootstrap from server {} failed. Your node will try to bootstrap from another server in {:#?}.", addr, bootstrap_config.retry_delay.to_duration());
+            sleep(bootstrap_config.retry_delay.into()).await;
+        }
+    }
+}
@@ -14,6 +14,7 @@ pub mod types {
 #[cfg(not(test))]
 /// Connection types
 pub mod types {
+    use massa_network_exports::{connect_through_proxy, settings::Socks5ProxyConfig};
     use massa_time::MassaTime;
     use std::{io, net::SocketAddr};
     use tokio::{
@@ -44,9 +45,10 @@ pub mod types {
         }
     }
 
-    /// Initiates a connection with given timeout in milliseconds
+    /// Initiates a connection with given timeout in milliseconds,
+    /// through a SOCKS5 proxy if one is configured
     #[derive(Debug)]
-    pub struct DefaultConnector(MassaTime);
+    pub struct DefaultConnector(MassaTime, Option<Socks5ProxyConfig>);
 
     impl DefaultConnector {
         /// Tries to connect to address
@@ -54,7 +56,13 @@ pub mod types {
         /// # Argument
         /// * `addr`: `SocketAddr` we are trying to connect to.
         pub async fn connect(&mut self, addr: SocketAddr) -> io::Result<Duplex> {
-            match timeout(self.0.to_duration(), TcpStream::connect(addr)).await {
+            let connection = async {
+                match &self.1 {
+                    Some(proxy) => connect_through_proxy(proxy, addr).await,
+                    None => TcpStream::connect(addr).await,
+                }
+            };
+            match timeout(self.0.to_duration(), connection).await {
                 Ok(Ok(sock)) => Ok(sock),
                 Ok(Err(e)) => Err(e),
                 Err(e) => Err(io::Error::new(io::ErrorKind::TimedOut, e)),
@@ -64,12 +72,20 @@ pub mod types {
 
     /// Establishes a connection
     #[derive(Debug)]
-    pub struct DefaultEstablisher;
+    pub struct DefaultEstablisher {
+        /// SOCKS5 proxy used for outgoing connections
+        proxy: Option<Socks5ProxyConfig>,
+    }
 
     impl DefaultEstablisher {
         /// Creates an Establisher.
         pub fn new() -> Self {
-            DefaultEstablisher {}
+            DefaultEstablisher { proxy: None }
+        }
+
+        /// Creates an Establisher dialing outgoing connections through `proxy` if it is set.
+        pub fn with_proxy(proxy: Option<Socks5ProxyConfig>) -> Self {
+            DefaultEstablisher { proxy }
         }
 
         /// Gets the associated listener
@@ -88,7 +104,7 @@ pub mod types {
             &mut self,
             timeout_duration: MassaTime,
         ) -> io::Result<DefaultConnector> {
-            Ok(DefaultConnector(timeout_duration))
+            Ok(DefaultConnector(timeout_duration, self.proxy.clone()))
         }
     }
 
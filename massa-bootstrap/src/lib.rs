@@ -17,6 +17,7 @@
 pub use establisher::types::Establisher;
 use massa_final_state::FinalState;
 use massa_graph::BootstrapableGraph;
+use massa_models::{block::WrappedHeader, slot::Slot};
 use massa_network_exports::BootstrapPeers;
 use parking_lot::RwLock;
 use std::sync::Arc;
@@ -29,7 +30,7 @@ mod messages;
 mod server;
 mod server_binder;
 mod settings;
-pub use client::get_state;
+pub use client::{get_light_state, get_state};
 pub use establisher::types;
 pub use messages::{
     BootstrapClientMessage, BootstrapClientMessageDeserializer, BootstrapClientMessageSerializer,
@@ -66,3 +67,18 @@ impl GlobalBootstrapState {
         }
     }
 }
+
+/// the bootstrap state of a light client, its Proof of Stake cycle history being written in a `PoSFinalState`
+pub struct LightBootstrapState {
+    /// headers of the final blocks
+    pub final_headers: Vec<WrappedHeader>,
+
+    /// slot the Proof of Stake cycle history is attached to
+    pub slot: Slot,
+
+    /// timestamp correction in milliseconds
+    pub compensation_millis: i64,
+
+    /// list of network peers
+    pub peers: BootstrapPeers,
+}
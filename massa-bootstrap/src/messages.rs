@@ -10,14 +10,19 @@ use massa_graph::{
     BootstrapableGraph, BootstrapableGraphDeserializer, BootstrapableGraphSerializer,
 };
 use massa_ledger_exports::{KeyDeserializer, KeySerializer};
+use massa_models::block::{BlockHeader, BlockHeaderDeserializer, WrappedHeader};
 use massa_models::serialization::{VecU8Deserializer, VecU8Serializer};
 use massa_models::slot::SlotDeserializer;
+use massa_models::wrapped::{WrappedDeserializer, WrappedSerializer};
 use massa_models::{
     slot::Slot,
     slot::SlotSerializer,
     version::{Version, VersionDeserializer, VersionSerializer},
 };
-use massa_network_exports::{BootstrapPeers, BootstrapPeersDeserializer, BootstrapPeersSerializer};
+use massa_network_exports::{
+    BootstrapPeers, BootstrapPeersDeserializer, BootstrapPeersSerializer, LegacyBootstrapPeers,
+    LegacyBootstrapPeersDeserializer, LegacyBootstrapPeersSerializer,
+};
 use massa_pos_exports::{
     PoSCycleStreamingStep, PoSCycleStreamingStepDeserializer, PoSCycleStreamingStepSerializer,
 };
@@ -54,6 +59,11 @@ pub enum BootstrapServerMessage {
         /// Server peers
         peers: BootstrapPeers,
     },
+    /// Bootstrap peers without ports, sent to clients that do not support peer ports
+    LegacyBootstrapPeers {
+        /// Server peers reachable on the default protocol port
+        peers: LegacyBootstrapPeers,
+    },
     /// Consensus state
     ConsensusState {
         /// block graph
@@ -78,6 +88,15 @@ pub enum BootstrapServerMessage {
     },
     /// Message sent when there is no state part left
     FinalStateFinished,
+    /// State needed by a light client: final headers and Proof of Stake draws
+    LightState {
+        /// headers of the final blocks of the graph
+        final_headers: Vec<WrappedHeader>,
+        /// Proof of Stake cycle_history, one part per cycle
+        pos_cycle_parts: Vec<Vec<u8>>,
+        /// Slot the Proof of Stake cycle_history is attached to
+        slot: Slot,
+    },
     /// Slot sent to get state changes is too old
     SlotTooOld,
     /// Bootstrap error
@@ -97,8 +116,13 @@ enum MessageServerTypeId {
     FinalStateFinished = 4u32,
     SlotTooOld = 5u32,
     BootstrapError = 6u32,
+    PeersWithPorts = 7u32,
+    LightState = 8u32,
 }
 
+/// Maximum number of cycles in the Proof of Stake cycle_history sent to light clients
+const MAX_LIGHT_STATE_CYCLES: u32 = 6;
+
 /// Serializer for `BootstrapServerMessage`
 #[derive(Default)]
 pub struct BootstrapServerMessageSerializer {
@@ -106,10 +130,12 @@ pub struct BootstrapServerMessageSerializer {
     time_serializer: MassaTimeSerializer,
     version_serializer: VersionSerializer,
     peers_serializer: BootstrapPeersSerializer,
+    legacy_peers_serializer: LegacyBootstrapPeersSerializer,
     state_changes_serializer: StateChangesSerializer,
     bootstrapable_graph_serializer: BootstrapableGraphSerializer,
     vec_u8_serializer: VecU8Serializer,
     slot_serializer: SlotSerializer,
+    wrapped_serializer: WrappedSerializer,
 }
 
 impl BootstrapServerMessageSerializer {
@@ -120,10 +146,12 @@ impl BootstrapServerMessageSerializer {
             time_serializer: MassaTimeSerializer::new(),
             version_serializer: VersionSerializer::new(),
             peers_serializer: BootstrapPeersSerializer::new(),
+            legacy_peers_serializer: LegacyBootstrapPeersSerializer::new(),
             state_changes_serializer: StateChangesSerializer::new(),
             bootstrapable_graph_serializer: BootstrapableGraphSerializer::new(),
             vec_u8_serializer: VecU8Serializer::new(),
             slot_serializer: SlotSerializer::new(),
+            wrapped_serializer: WrappedSerializer::new(),
         }
     }
 }
@@ -162,9 +190,14 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
             }
             BootstrapServerMessage::BootstrapPeers { peers } => {
                 self.u32_serializer
-                    .serialize(&u32::from(MessageServerTypeId::Peers), buffer)?;
+                    .serialize(&u32::from(MessageServerTypeId::PeersWithPorts), buffer)?;
                 self.peers_serializer.serialize(peers, buffer)?;
             }
+            BootstrapServerMessage::LegacyBootstrapPeers { peers } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::Peers), buffer)?;
+                self.legacy_peers_serializer.serialize(peers, buffer)?;
+            }
             BootstrapServerMessage::ConsensusState { graph } => {
                 self.u32_serializer
                     .serialize(&u32::from(MessageServerTypeId::ConsensusState), buffer)?;
@@ -200,6 +233,25 @@ impl Serializer<BootstrapServerMessage> for BootstrapServerMessageSerializer {
                 self.u32_serializer
                     .serialize(&u32::from(MessageServerTypeId::FinalStateFinished), buffer)?;
             }
+            BootstrapServerMessage::LightState {
+                final_headers,
+                pos_cycle_parts,
+                slot,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageServerTypeId::LightState), buffer)?;
+                self.u32_serializer
+                    .serialize(&(final_headers.len() as u32), buffer)?;
+                for header in final_headers {
+                    self.wrapped_serializer.serialize(header, buffer)?;
+                }
+                self.u32_serializer
+                    .serialize(&(pos_cycle_parts.len() as u32), buffer)?;
+                for part in pos_cycle_parts {
+                    self.vec_u8_serializer.serialize(part, buffer)?;
+                }
+                self.slot_serializer.serialize(slot, buffer)?;
+            }
             BootstrapServerMessage::SlotTooOld => {
                 self.u32_serializer
                     .serialize(&u32::from(MessageServerTypeId::SlotTooOld), buffer)?;
@@ -226,12 +278,16 @@ pub struct BootstrapServerMessageDeserializer {
     time_deserializer: MassaTimeDeserializer,
     version_deserializer: VersionDeserializer,
     peers_deserializer: BootstrapPeersDeserializer,
+    legacy_peers_deserializer: LegacyBootstrapPeersDeserializer,
     length_state_changes: U32VarIntDeserializer,
     state_changes_deserializer: StateChangesDeserializer,
     bootstrapable_graph_deserializer: BootstrapableGraphDeserializer,
     final_state_parts_deserializer: VecU8Deserializer,
     length_bootstrap_error: U32VarIntDeserializer,
     slot_deserializer: SlotDeserializer,
+    length_final_headers: U32VarIntDeserializer,
+    header_deserializer: WrappedDeserializer<BlockHeader, BlockHeaderDeserializer>,
+    length_pos_cycle_parts: U32VarIntDeserializer,
 }
 
 impl BootstrapServerMessageDeserializer {
@@ -263,6 +319,7 @@ impl BootstrapServerMessageDeserializer {
             )),
             version_deserializer: VersionDeserializer::new(),
             peers_deserializer: BootstrapPeersDeserializer::new(max_advertise_length),
+            legacy_peers_deserializer: LegacyBootstrapPeersDeserializer::new(max_advertise_length),
             state_changes_deserializer: StateChangesDeserializer::new(
                 thread_count,
                 max_async_pool_changes,
@@ -297,6 +354,18 @@ impl BootstrapServerMessageDeserializer {
                 Included(0),
                 Included(max_bootstrap_error_length),
             ),
+            length_final_headers: U32VarIntDeserializer::new(
+                Included(0),
+                Included(max_bootstrap_blocks),
+            ),
+            header_deserializer: WrappedDeserializer::new(BlockHeaderDeserializer::new(
+                thread_count,
+                endorsement_count,
+            )),
+            length_pos_cycle_parts: U32VarIntDeserializer::new(
+                Included(0),
+                Included(MAX_LIGHT_STATE_CYCLES),
+            ),
         }
     }
 }
@@ -365,10 +434,17 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                 )
                 .parse(input),
                 MessageServerTypeId::Peers => context("Failed peers deserialization", |input| {
-                    self.peers_deserializer.deserialize(input)
+                    self.legacy_peers_deserializer.deserialize(input)
                 })
-                .map(|peers| BootstrapServerMessage::BootstrapPeers { peers })
+                .map(|peers| BootstrapServerMessage::LegacyBootstrapPeers { peers })
                 .parse(input),
+                MessageServerTypeId::PeersWithPorts => {
+                    context("Failed peers deserialization", |input| {
+                        self.peers_deserializer.deserialize(input)
+                    })
+                    .map(|peers| BootstrapServerMessage::BootstrapPeers { peers })
+                    .parse(input)
+                }
                 MessageServerTypeId::ConsensusState => {
                     context("Failed graph deserialization", |input| {
                         self.bootstrapable_graph_deserializer.deserialize(input)
@@ -433,6 +509,37 @@ impl Deserializer<BootstrapServerMessage> for BootstrapServerMessageDeserializer
                 MessageServerTypeId::FinalStateFinished => {
                     Ok((input, BootstrapServerMessage::FinalStateFinished))
                 }
+                MessageServerTypeId::LightState => tuple((
+                    context(
+                        "Failed final_headers deserialization",
+                        length_count(
+                            context("Failed length deserialization", |input| {
+                                self.length_final_headers.deserialize(input)
+                            }),
+                            |input| self.header_deserializer.deserialize(input),
+                        ),
+                    ),
+                    context(
+                        "Failed pos_cycle_parts deserialization",
+                        length_count(
+                            context("Failed length deserialization", |input| {
+                                self.length_pos_cycle_parts.deserialize(input)
+                            }),
+                            |input| self.final_state_parts_deserializer.deserialize(input),
+                        ),
+                    ),
+                    context("Failed slot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(
+                    |(final_headers, pos_cycle_parts, slot)| BootstrapServerMessage::LightState {
+                        final_headers,
+                        pos_cycle_parts,
+                        slot,
+                    },
+                )
+                .parse(input),
                 MessageServerTypeId::SlotTooOld => Ok((input, BootstrapServerMessage::SlotTooOld)),
                 MessageServerTypeId::BootstrapError => context(
                     "Failed BootstrapError deserialization",
@@ -457,6 +564,8 @@ pub enum BootstrapClientMessage {
     AskBootstrapPeers,
     /// Ask for consensus state
     AskConsensusState,
+    /// Ask for the state needed by a light client
+    AskLightState,
     /// Ask for a part of the final state
     AskFinalStatePart {
         /// Slot we are attached to for changes
@@ -489,6 +598,7 @@ enum MessageClientTypeId {
     AskFinalStatePart = 2u32,
     BootstrapError = 3u32,
     BootstrapSuccess = 4u32,
+    AskLightState = 5u32,
 }
 
 /// Serializer for `BootstrapClientMessage`
@@ -551,6 +661,10 @@ impl Serializer<BootstrapClientMessage> for BootstrapClientMessageSerializer {
                 self.u32_serializer
                     .serialize(&u32::from(MessageClientTypeId::AskConsensusState), buffer)?;
             }
+            BootstrapClientMessage::AskLightState => {
+                self.u32_serializer
+                    .serialize(&u32::from(MessageClientTypeId::AskLightState), buffer)?;
+            }
             BootstrapClientMessage::AskFinalStatePart {
                 last_slot,
                 last_key,
@@ -670,6 +784,9 @@ impl Deserializer<BootstrapClientMessage> for BootstrapClientMessageDeserializer
                 MessageClientTypeId::AskConsensusState => {
                     Ok((input, BootstrapClientMessage::AskConsensusState))
                 }
+                MessageClientTypeId::AskLightState => {
+                    Ok((input, BootstrapClientMessage::AskLightState))
+                }
                 MessageClientTypeId::AskFinalStatePart => {
                     if input.is_empty() {
                         Ok((
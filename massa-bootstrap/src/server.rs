@@ -439,7 +439,7 @@ async fn manage_bootstrap(
     massa_trace!("bootstrap.lib.manage_bootstrap", {});
     let read_error_timeout: std::time::Duration = bootstrap_config.read_error_timeout.into();
 
-    match tokio::time::timeout(
+    let client_version = match tokio::time::timeout(
         bootstrap_config.read_timeout.into(),
         server.handshake(version),
     )
@@ -453,7 +453,7 @@ async fn manage_bootstrap(
             .into())
         }
         Ok(Err(e)) => return Err(e),
-        Ok(Ok(_)) => (),
+        Ok(Ok(client_version)) => client_version,
     };
 
     match tokio::time::timeout(read_error_timeout, server.next()).await {
@@ -494,14 +494,17 @@ async fn manage_bootstrap(
             Ok(Err(e)) => break Err(e),
             Ok(Ok(msg)) => match msg {
                 BootstrapClientMessage::AskBootstrapPeers => {
-                    match tokio::time::timeout(
-                        write_timeout,
-                        server.send(BootstrapServerMessage::BootstrapPeers {
+                    // older clients only understand peers on the default protocol port
+                    let message = if client_version.supports_peer_ports(&version) {
+                        BootstrapServerMessage::BootstrapPeers {
                             peers: data_peers.clone(),
-                        }),
-                    )
-                    .await
-                    {
+                        }
+                    } else {
+                        BootstrapServerMessage::LegacyBootstrapPeers {
+                            peers: data_peers.to_legacy(bootstrap_config.protocol_port),
+                        }
+                    };
+                    match tokio::time::timeout(write_timeout, server.send(message)).await {
                         Err(_) => Err(std::io::Error::new(
                             std::io::ErrorKind::TimedOut,
                             "bootstrap peers send timed out",
@@ -553,6 +556,41 @@ async fn manage_bootstrap(
                         }
                     }?;
                 }
+                BootstrapClientMessage::AskLightState => {
+                    let message = {
+                        let final_state_read = final_state.read();
+                        let mut pos_cycle_parts = Vec::new();
+                        let mut cycle_step = PoSCycleStreamingStep::Started;
+                        loop {
+                            let (part, new_cycle_step) = final_state_read
+                                .pos_state
+                                .get_cycle_history_part(cycle_step)?;
+                            if new_cycle_step == PoSCycleStreamingStep::Finished {
+                                break;
+                            }
+                            pos_cycle_parts.push(part);
+                            cycle_step = new_cycle_step;
+                        }
+                        BootstrapServerMessage::LightState {
+                            final_headers: data_graph
+                                .final_blocks
+                                .iter()
+                                .map(|active_block| active_block.block.content.header.clone())
+                                .collect(),
+                            pos_cycle_parts,
+                            slot: final_state_read.slot,
+                        }
+                    };
+                    match tokio::time::timeout(write_timeout, server.send(message)).await {
+                        Err(_) => Err(std::io::Error::new(
+                            std::io::ErrorKind::TimedOut,
+                            "bootstrap light state send timed out",
+                        )
+                        .into()),
+                        Ok(Err(e)) => Err(e),
+                        Ok(Ok(_)) => Ok(()),
+                    }?;
+                }
                 BootstrapClientMessage::BootstrapSuccess => break Ok(()),
                 BootstrapClientMessage::BootstrapError { error } => {
                     break Err(BootstrapError::ReceivedError(error));
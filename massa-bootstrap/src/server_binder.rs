@@ -67,9 +67,11 @@ impl BootstrapServerBinder {
     /// Performs a handshake. Should be called after connection
     /// NOT cancel-safe
     /// MUST always be followed by a send of the BootstrapMessage::BootstrapTime
-    pub async fn handshake(&mut self, version: Version) -> Result<(), BootstrapError> {
+    ///
+    /// Returns the version of the client
+    pub async fn handshake(&mut self, version: Version) -> Result<Version, BootstrapError> {
         // read version and random bytes, send signature
-        let msg_hash = {
+        let (msg_hash, received_version) = {
             let mut version_bytes = Vec::new();
             self.version_serializer
                 .serialize(&version, &mut version_bytes)?;
@@ -82,13 +84,13 @@ impl BootstrapServerBinder {
             if !received_version.is_compatible(&version) {
                 return Err(BootstrapError::IncompatibleVersionError(format!("Received a bad incompatible version in handshake. (excepted: {}, received: {})", version, received_version)));
             }
-            Hash::compute_from(&msg_bytes)
+            (Hash::compute_from(&msg_bytes), received_version)
         };
 
         // save prev sig
         self.prev_message = Some(msg_hash);
 
-        Ok(())
+        Ok(received_version)
     }
 
     /// Writes the next message. NOT cancel-safe
@@ -52,6 +52,8 @@ pub struct BootstrapConfig {
     pub endorsement_count: u32,
     /// max advertise length
     pub max_advertise_length: u32,
+    /// default protocol port, assumed for peers exchanged without a port
+    pub protocol_port: u16,
     /// max bootstrap blocks length
     pub max_bootstrap_blocks_length: u32,
     /// max operations per blocks
@@ -67,7 +67,7 @@ async fn test_binders() {
 
     let server_thread = tokio::spawn(async move {
         // Test message 1
-        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0.ip()];
+        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0];
         let test_peers_message = BootstrapServerMessage::BootstrapPeers {
             peers: BootstrapPeers(vector_peers.clone()),
         };
@@ -87,9 +87,9 @@ async fn test_binders() {
 
         // Test message 3
         let vector_peers = vec![
-            bootstrap_config.bootstrap_list[0].0.ip(),
-            bootstrap_config.bootstrap_list[0].0.ip(),
-            bootstrap_config.bootstrap_list[0].0.ip(),
+            bootstrap_config.bootstrap_list[0].0,
+            bootstrap_config.bootstrap_list[0].0,
+            bootstrap_config.bootstrap_list[0].0,
         ];
         let test_peers_message = BootstrapServerMessage::BootstrapPeers {
             peers: BootstrapPeers(vector_peers.clone()),
@@ -100,7 +100,7 @@ async fn test_binders() {
 
     let client_thread = tokio::spawn(async move {
         // Test message 1
-        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0.ip()];
+        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0];
 
         let version: Version = Version::from_str("TEST.1.10").unwrap();
 
@@ -122,9 +122,9 @@ async fn test_binders() {
 
         // Test message 3
         let vector_peers = vec![
-            bootstrap_config.bootstrap_list[0].0.ip(),
-            bootstrap_config.bootstrap_list[0].0.ip(),
-            bootstrap_config.bootstrap_list[0].0.ip(),
+            bootstrap_config.bootstrap_list[0].0,
+            bootstrap_config.bootstrap_list[0].0,
+            bootstrap_config.bootstrap_list[0].0,
         ];
         let message = client.next().await.unwrap();
         match message {
@@ -181,7 +181,7 @@ async fn test_binders_double_send_server_works() {
 
     let server_thread = tokio::spawn(async move {
         // Test message 1
-        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0.ip()];
+        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0];
         let test_peers_message = BootstrapServerMessage::BootstrapPeers {
             peers: BootstrapPeers(vector_peers.clone()),
         };
@@ -193,9 +193,9 @@ async fn test_binders_double_send_server_works() {
 
         // Test message 2
         let vector_peers = vec![
-            bootstrap_config.bootstrap_list[0].0.ip(),
-            bootstrap_config.bootstrap_list[0].0.ip(),
-            bootstrap_config.bootstrap_list[0].0.ip(),
+            bootstrap_config.bootstrap_list[0].0,
+            bootstrap_config.bootstrap_list[0].0,
+            bootstrap_config.bootstrap_list[0].0,
         ];
         let test_peers_message = BootstrapServerMessage::BootstrapPeers {
             peers: BootstrapPeers(vector_peers.clone()),
@@ -206,7 +206,7 @@ async fn test_binders_double_send_server_works() {
 
     let client_thread = tokio::spawn(async move {
         // Test message 1
-        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0.ip()];
+        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0];
 
         let version: Version = Version::from_str("TEST.1.10").unwrap();
 
@@ -221,9 +221,9 @@ async fn test_binders_double_send_server_works() {
 
         // Test message 2
         let vector_peers = vec![
-            bootstrap_config.bootstrap_list[0].0.ip(),
-            bootstrap_config.bootstrap_list[0].0.ip(),
-            bootstrap_config.bootstrap_list[0].0.ip(),
+            bootstrap_config.bootstrap_list[0].0,
+            bootstrap_config.bootstrap_list[0].0,
+            bootstrap_config.bootstrap_list[0].0,
         ];
         let message = client.next().await.unwrap();
         match message {
@@ -280,7 +280,7 @@ async fn test_binders_try_double_send_client_works() {
 
     let server_thread = tokio::spawn(async move {
         // Test message 1
-        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0.ip()];
+        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0];
         let test_peers_message = BootstrapServerMessage::BootstrapPeers {
             peers: BootstrapPeers(vector_peers.clone()),
         };
@@ -310,7 +310,7 @@ async fn test_binders_try_double_send_client_works() {
 
     let client_thread = tokio::spawn(async move {
         // Test message 1
-        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0.ip()];
+        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0];
         let version: Version = Version::from_str("TEST.1.10").unwrap();
 
         client.handshake(version).await.unwrap();
@@ -337,7 +337,7 @@ async fn test_binders_try_double_send_client_works() {
             .await
             .unwrap();
 
-        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0.ip()];
+        let vector_peers = vec![bootstrap_config.bootstrap_list[0].0];
         let message = client.next().await.unwrap();
         match message {
             BootstrapServerMessage::BootstrapPeers { peers } => {
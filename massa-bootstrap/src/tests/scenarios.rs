@@ -12,7 +12,7 @@ use crate::tests::tools::{
 };
 use crate::BootstrapConfig;
 use crate::{
-    get_state, start_bootstrap_server,
+    get_light_state, get_state, start_bootstrap_server,
     tests::tools::{assert_eq_bootstrap_graph, get_bootstrap_config},
 };
 use massa_consensus_exports::{commands::ConsensusCommand, ConsensusCommandSender};
@@ -270,3 +270,200 @@ async fn test_bootstrap_server() {
     server_selector_manager.stop();
     client_selector_manager.stop();
 }
+
+#[tokio::test]
+#[serial]
+async fn test_light_bootstrap_server() {
+    let (bootstrap_config, keypair): &(BootstrapConfig, KeyPair) = &BOOTSTRAP_CONFIG_KEYPAIR;
+
+    let rolls_path = PathBuf::from_str("../massa-node/base_config/initial_rolls.json").unwrap();
+    let genesis_address = Address::from_public_key(&KeyPair::generate().get_public_key());
+    let (mut server_selector_manager, server_selector_controller) =
+        start_selector_worker(SelectorConfig {
+            thread_count: 2,
+            periods_per_cycle: 2,
+            genesis_address,
+            ..Default::default()
+        })
+        .expect("could not start server selector controller");
+    let (mut client_selector_manager, client_selector_controller) =
+        start_selector_worker(SelectorConfig {
+            thread_count: 2,
+            periods_per_cycle: 2,
+            genesis_address,
+            ..Default::default()
+        })
+        .expect("could not start client selector controller");
+
+    let (consensus_cmd_tx, mut consensus_cmd_rx) = mpsc::channel::<ConsensusCommand>(5);
+    let (network_cmd_tx, mut network_cmd_rx) = mpsc::channel::<NetworkCommand>(5);
+    let final_state_bootstrap = get_random_final_state_bootstrap(
+        PoSFinalState::new(
+            &"".to_string(),
+            &rolls_path,
+            2,
+            2,
+            server_selector_controller.clone(),
+        )
+        .unwrap(),
+    );
+    let final_state = Arc::new(RwLock::new(final_state_bootstrap));
+
+    let (bootstrap_establisher, bootstrap_interface) = mock_establisher::new();
+    let bootstrap_manager = start_bootstrap_server(
+        ConsensusCommandSender(consensus_cmd_tx),
+        NetworkCommandSender(network_cmd_tx),
+        final_state.clone(),
+        bootstrap_config.clone(),
+        bootstrap_establisher,
+        keypair.clone(),
+        0,
+        Version::from_str("TEST.1.10").unwrap(),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    let mut pos_state_client = PoSFinalState::new(
+        &"".to_string(),
+        &rolls_path,
+        2,
+        2,
+        client_selector_controller.clone(),
+    )
+    .unwrap();
+
+    // launch the get_light_state process
+    let (remote_establisher, mut remote_interface) = mock_establisher::new();
+    let get_light_state_h = tokio::spawn(async move {
+        let light_state = get_light_state(
+            bootstrap_config,
+            &mut pos_state_client,
+            remote_establisher,
+            Version::from_str("TEST.1.10").unwrap(),
+            MassaTime::now(0).unwrap().saturating_sub(1000.into()),
+            None,
+        )
+        .await
+        .unwrap();
+        (light_state, pos_state_client)
+    });
+
+    // accept connection attempt from remote
+    let (remote_rw, _, resp) = tokio::time::timeout(
+        std::time::Duration::from_millis(1000),
+        remote_interface.wait_connection_attempt_from_controller(),
+    )
+    .await
+    .expect("timeout waiting for connection attempt from remote")
+    .expect("error receiving connection attempt from remote");
+    resp.send(true)
+        .expect("could not send connection accept to remote");
+
+    // connect to bootstrap
+    let remote_addr = std::net::SocketAddr::from_str("82.245.72.98:10000").unwrap(); // not checked
+    let bootstrap_rw = tokio::time::timeout(
+        std::time::Duration::from_millis(1000),
+        bootstrap_interface.connect_to_controller(&remote_addr),
+    )
+    .await
+    .expect("timeout while connecting to bootstrap")
+    .expect("could not connect to bootstrap");
+
+    // launch bridge
+    let bridge = tokio::spawn(async move {
+        bridge_mock_streams(remote_rw, bootstrap_rw).await;
+    });
+
+    // intercept peers being asked
+    let wait_peers = async move || {
+        let response = match wait_network_command(&mut network_cmd_rx, 1000.into(), |cmd| match cmd
+        {
+            NetworkCommand::GetBootstrapPeers(resp) => Some(resp),
+            _ => None,
+        })
+        .await
+        {
+            Some(resp) => resp,
+            None => panic!("timeout waiting for get peers command"),
+        };
+        let sent_peers = get_peers();
+        response.send(sent_peers.clone()).unwrap();
+        sent_peers
+    };
+
+    // wait for bootstrap to ask consensus for bootstrap graph, send it
+    let wait_graph = async move || {
+        let response =
+            match wait_consensus_command(&mut consensus_cmd_rx, 1000.into(), |cmd| match cmd {
+                ConsensusCommand::GetBootstrapState(resp) => Some(resp),
+                _ => None,
+            })
+            .await
+            {
+                Some(resp) => resp,
+                None => panic!("timeout waiting for get boot graph consensus command"),
+            };
+        let sent_graph = get_boot_state();
+        response.send(Box::new(sent_graph.clone())).await.unwrap();
+        sent_graph
+    };
+
+    // wait for peers and graph
+    let (sent_peers, sent_graph) = tokio::join!(wait_peers(), wait_graph());
+
+    // wait for get_light_state
+    let (light_state, mut pos_state_client) = get_light_state_h
+        .await
+        .expect("error while waiting for get_light_state to finish");
+
+    // wait for bridge
+    bridge.await.expect("bridge join failed");
+
+    // check peers
+    assert_eq!(
+        sent_peers.0, light_state.peers.0,
+        "mismatch between sent and received peers"
+    );
+
+    // check final headers
+    let sent_header_ids: Vec<_> = sent_graph
+        .final_blocks
+        .iter()
+        .map(|active_block| active_block.block.content.header.id)
+        .collect();
+    let received_header_ids: Vec<_> = light_state
+        .final_headers
+        .iter()
+        .map(|header| header.id)
+        .collect();
+    assert_eq!(
+        sent_header_ids, received_header_ids,
+        "mismatch between sent and received final headers"
+    );
+
+    // check the PoS cycle history, the only part of the final state sent to light clients
+    assert_eq!(light_state.slot, final_state.read().slot);
+    assert_eq!(
+        final_state.read().pos_state.cycle_history,
+        pos_state_client.cycle_history,
+        "PoS cycle_history mismatching"
+    );
+
+    // check selection draw
+    final_state.write().compute_initial_draws().unwrap();
+    pos_state_client.compute_initial_draws().unwrap();
+    let server_selection = server_selector_controller.get_entire_selection();
+    let client_selection = client_selector_controller.get_entire_selection();
+    assert_eq_pos_selection(&server_selection, &client_selection);
+
+    // stop bootstrap server
+    bootstrap_manager
+        .stop()
+        .await
+        .expect("could not stop bootstrap server");
+
+    // stop selector controllers
+    server_selector_manager.stop();
+    client_selector_manager.stop();
+}
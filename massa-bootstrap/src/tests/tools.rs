@@ -278,6 +278,7 @@ pub fn get_bootstrap_config(bootstrap_public_key: PublicKey) -> BootstrapConfig
         periods_per_cycle: PERIODS_PER_CYCLE,
         endorsement_count: ENDORSEMENT_COUNT,
         max_advertise_length: MAX_ADVERTISE_LENGTH,
+        protocol_port: 31244,
         max_bootstrap_async_pool_changes: MAX_BOOTSTRAP_ASYNC_POOL_CHANGES,
         max_bootstrap_blocks_length: MAX_BOOTSTRAP_BLOCKS,
         max_bootstrap_error_length: MAX_BOOTSTRAP_ERROR_LENGTH,
@@ -434,8 +435,8 @@ pub fn get_boot_state() -> BootstrapableGraph {
 
 pub fn get_peers() -> BootstrapPeers {
     BootstrapPeers(vec![
-        "82.245.123.77".parse().unwrap(),
-        "82.220.123.78".parse().unwrap(),
+        "82.245.123.77:31244".parse().unwrap(),
+        "82.220.123.78:31245".parse().unwrap(),
     ])
 }
 
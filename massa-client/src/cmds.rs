@@ -3,10 +3,17 @@
 use crate::repl::Output;
 use anyhow::{anyhow, bail, Result};
 use console::style;
+use massa_models::abi::ContractAbi;
 use massa_models::api::{
-    AddressInfo, CompactAddressInfo, DatastoreEntryInput, EventFilter, OperationInput,
+    AddressHistoryRequest, AddressInfo, AsyncMessageFilter, AsyncMessageStatus, CompactAddressInfo,
+    ContractSourceBundle, DatastoreEntryInput, EventFilter, OperationInput,
 };
 use massa_models::api::{ReadOnlyBytecodeExecution, ReadOnlyCall};
+use massa_models::execution::ExecuteReadOnlyResponse;
+use massa_models::multisig::{
+    combine_multisig_signatures, MultisigAction, MultisigDescriptor, MultisigProposal,
+    MultisigSignature,
+};
 use massa_models::node::NodeId;
 use massa_models::prehash::PreHashMap;
 use massa_models::timeslots::get_current_latest_block_slot;
@@ -18,11 +25,12 @@ use massa_models::{
     operation::{Operation, OperationId, OperationType},
     slot::Slot,
 };
+use massa_sdk::abi::{decode_event, encode_arguments, encode_json_arguments, DecodedEvent};
 use massa_sdk::Client;
-use massa_signature::KeyPair;
+use massa_signature::{KeyPair, PublicKey};
 use massa_time::MassaTime;
 use massa_wallet::Wallet;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::Write as _;
 use std::fmt::{Debug, Display};
@@ -32,6 +40,9 @@ use std::process;
 use strum::{EnumMessage, EnumProperty, IntoEnumIterator};
 use strum_macros::{Display, EnumIter, EnumMessage, EnumProperty, EnumString};
 
+/// Number of address history entries requested when no limit is given
+const DEFAULT_ADDRESS_HISTORY_LIMIT: u64 = 50;
+
 /// All the client commands
 /// the order they are defined is the order they are displayed in so be careful
 /// Maybe it would be worth renaming some of them for consistency
@@ -60,18 +71,24 @@ pub enum Command {
 
     #[strum(
         ascii_case_insensitive,
-        props(args = "IpAddr1 IpAddr2 ..."),
-        message = "ban given IP address(es)"
+        props(args = "IpAddr1 IpAddr2 ... [DurationMs]"),
+        message = "ban given IP address(es), for the node's default ban duration if none is given"
     )]
     node_ban_by_ip,
 
     #[strum(
         ascii_case_insensitive,
-        props(args = "Id1 Id2 ..."),
-        message = "ban given id(s)"
+        props(args = "Id1 Id2 ... [DurationMs]"),
+        message = "ban given id(s), for the node's default ban duration if none is given"
     )]
     node_ban_by_id,
 
+    #[strum(
+        ascii_case_insensitive,
+        message = "show banned peers with the reason and expiry of their ban"
+    )]
+    node_get_banned_peers,
+
     #[strum(ascii_case_insensitive, message = "stops the node")]
     node_stop,
 
@@ -133,6 +150,20 @@ pub enum Command {
     )]
     get_datastore_entry,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address1 Address2 ..."),
+        message = "show the bytecode metadata of smart contracts, and their verified sources and functions if any"
+    )]
+    get_contract_metadata,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "PathToSourceBundle"),
+        message = "submit the sources of a deployed smart contract (JSON bundle with address, compiler_version, entry_file, sources and abi) for verification by the node"
+    )]
+    submit_contract_sources,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "BlockId"),
@@ -157,12 +188,35 @@ pub enum Command {
     #[strum(
         ascii_case_insensitive,
         props(
-            args = "start=Slot end=Slot emitter_address=Address caller_address=Address operation_id=OperationId is_final=bool"
+            args = "start=Slot end=Slot emitter_address=Address caller_address=Address operation_id=OperationId async_message=Period,Thread,EmissionIndex topic=String is_final=bool"
         ),
         message = "show events emitted by smart contracts with various filters"
     )]
     get_filtered_sc_output_event,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "sender=Address destination=Address emission_slot=Slot emission_index=u64 offset=u64 limit=u64"
+        ),
+        message = "show pending asynchronous messages and the outcome of past ones with various filters"
+    )]
+    get_async_messages,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address offset=u64 limit=u64"),
+        message = "show the operations and coin transfers involving an address, most recent first (requires the address history to be enabled on the node)"
+    )]
+    get_address_history,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "BlockId"),
+        message = "show the execution receipt of a recent block: operation outcomes, fees and reward split"
+    )]
+    get_block_execution_receipt,
+
     #[strum(
         ascii_case_insensitive,
         message = "show wallet info (keys, addresses, balances ...)"
@@ -217,6 +271,43 @@ pub enum Command {
     )]
     send_transaction,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SenderAddress EmissionSlot EmissionIndex Fee"),
+        message = "cancel a pending asynchronous message sent by a wallet address, reimbursing its coins"
+    )]
+    cancel_async_message,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Threshold PublicKey1 PublicKey2 ..."),
+        message = "show the address of the multisig account requiring Threshold signatures among the given public keys"
+    )]
+    multisig_address,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "ProposalFile Threshold PublicKey1,PublicKey2,... transaction ReceiverAddress Amount | buy_rolls RollCount | sell_rolls RollCount"
+        ),
+        message = "write to ProposalFile a proposal of action on behalf of a multisig account, to be signed by its signers"
+    )]
+    multisig_propose,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "ProposalFile"),
+        message = "add to ProposalFile the signatures of the wallet addresses that are signers of the proposal"
+    )]
+    multisig_sign,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "SenderAddress Fee ProposalFile1 ProposalFile2 ..."),
+        message = "merge the signatures of the given copies of a proposal and submit it, the sender pays the fee and must be in the thread of the multisig address"
+    )]
+    multisig_submit,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "SenderAddress PathToBytecode MaxGas GasPrice Coins Fee",),
@@ -233,6 +324,15 @@ pub enum Command {
     )]
     call_smart_contract,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "SenderAddress TargetAddress FunctionName MaxGas GasPrice Coins Fee Arg1 Arg2 ... (or json=JsonArguments)",
+        ),
+        message = "call a function of a smart contract, encoding the arguments according to the ABI of the contract"
+    )]
+    call_smart_contract_abi,
+
     #[strum(
         ascii_case_insensitive,
         props(args = "PathToBytecode MaxGas GasPrice Address",),
@@ -247,6 +347,22 @@ pub enum Command {
     )]
     read_only_call,
 
+    #[strum(
+        ascii_case_insensitive,
+        props(
+            args = "TargetAddress FunctionName MaxGas GasPrice Arg1 Arg2 ... (or json=JsonArguments)",
+        ),
+        message = "call a smart contract function without executing it on chain, encoding the arguments and decoding the events according to the ABI of the contract"
+    )]
+    read_only_call_abi,
+
+    #[strum(
+        ascii_case_insensitive,
+        props(args = "Address"),
+        message = "show the ABI of a smart contract, as published in its datastore or verified from its sources"
+    )]
+    get_contract_abi,
+
     #[strum(
         ascii_case_insensitive,
         message = "show time remaining to end of current episode"
@@ -339,6 +455,29 @@ impl Display for ExtendedWallet {
     }
 }
 
+/// Result of a read-only call with its events decoded according to the ABI of the called contract
+#[derive(Debug, Serialize)]
+pub struct DecodedReadOnlyResponse {
+    /// raw response
+    pub response: ExecuteReadOnlyResponse,
+    /// decoded events emitted by the called contract, None for the events that could not be decoded
+    pub events: Vec<Option<DecodedEvent>>,
+}
+
+impl Display for DecodedReadOnlyResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.response)?;
+        let decoded_events: Vec<&DecodedEvent> = self.events.iter().flatten().collect();
+        if !decoded_events.is_empty() {
+            writeln!(f, "Decoded events:")?;
+            for event in decoded_events {
+                writeln!(f, "\t{}", event)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Command {
     /// Display the help of the command
     /// with fancy colors and so on
@@ -423,8 +562,9 @@ impl Command {
             }
 
             Command::node_ban_by_ip => {
+                let (parameters, duration) = parse_ban_duration(parameters);
                 let ips = parse_vec::<IpAddr>(parameters)?;
-                match client.private.node_ban_by_ip(ips).await {
+                match client.private.node_ban_by_ip(ips, duration).await {
                     Ok(()) => {
                         if !json {
                             println!("Request of banning successfully sent!")
@@ -436,8 +576,9 @@ impl Command {
             }
 
             Command::node_ban_by_id => {
+                let (parameters, duration) = parse_ban_duration(parameters);
                 let ids = parse_vec::<NodeId>(parameters)?;
-                match client.private.node_ban_by_id(ids).await {
+                match client.private.node_ban_by_id(ids, duration).await {
                     Ok(()) => {
                         if !json {
                             println!("Request of banning successfully sent!")
@@ -460,6 +601,11 @@ impl Command {
                 Ok(Box::new(()))
             }
 
+            Command::node_get_banned_peers => match client.private.get_banned_peers().await {
+                Ok(banned_peers) => Ok(Box::new(banned_peers)),
+                Err(e) => rpc_error!(e),
+            },
+
             Command::node_get_staking_addresses => {
                 match client.private.get_staking_addresses().await {
                     Ok(staking_addresses) => Ok(Box::new(staking_addresses)),
@@ -553,6 +699,38 @@ impl Command {
                 }
             }
 
+            Command::get_contract_metadata => {
+                let addresses = parse_vec::<Address>(parameters)?;
+                match client.public.get_contract_metadata(addresses).await {
+                    Ok(metadata) => Ok(Box::new(metadata)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_contract_abi => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let address = parameters[0].parse::<Address>()?;
+                match client.public.get_contract_abi(address).await {
+                    Ok(abi) => Ok(Box::new(abi)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::submit_contract_sources => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let path = parameters[0].parse::<PathBuf>()?;
+                let bundle: ContractSourceBundle =
+                    serde_json::from_slice(&get_file_as_byte_vec(&path).await?)?;
+                match client.private.submit_contract_sources(bundle).await {
+                    Ok(verified) => Ok(Box::new(verified)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::get_block => {
                 if parameters.len() != 1 {
                     bail!("wrong param numbers")
@@ -581,13 +759,15 @@ impl Command {
             }
 
             Command::get_filtered_sc_output_event => {
-                let p_list: [&str; 6] = [
+                let p_list: [&str; 8] = [
                     "start",
                     "end",
                     "emitter_address",
                     "caller_address",
                     "operation_id",
                     "is_final",
+                    "async_message",
+                    "topic",
                 ];
                 let mut p: HashMap<&str, &str> = HashMap::new();
                 for v in parameters {
@@ -604,6 +784,8 @@ impl Command {
                     emitter_address: parse_key_value(&p, p_list[2]),
                     original_caller_address: parse_key_value(&p, p_list[3]),
                     original_operation_id: parse_key_value(&p, p_list[4]),
+                    original_async_message: parse_key_value(&p, p_list[6]),
+                    topic: parse_key_value(&p, p_list[7]),
                     is_final: parse_key_value(&p, p_list[5]),
                 };
                 match client.public.get_filtered_sc_output_event(filter).await {
@@ -612,6 +794,75 @@ impl Command {
                 }
             }
 
+            Command::get_async_messages => {
+                let p_list: [&str; 6] = [
+                    "sender",
+                    "destination",
+                    "emission_slot",
+                    "emission_index",
+                    "offset",
+                    "limit",
+                ];
+                let mut p: HashMap<&str, &str> = HashMap::new();
+                for v in parameters {
+                    let s: Vec<&str> = v.split('=').collect();
+                    if s.len() == 2 && p_list.contains(&s[0]) {
+                        p.insert(s[0], s[1]);
+                    } else {
+                        bail!("invalid parameter");
+                    }
+                }
+                let filter = AsyncMessageFilter {
+                    sender: parse_key_value(&p, p_list[0]),
+                    destination: parse_key_value(&p, p_list[1]),
+                    emission_slot: parse_key_value(&p, p_list[2]),
+                    emission_index: parse_key_value(&p, p_list[3]),
+                    offset: parse_key_value(&p, p_list[4]).unwrap_or_default(),
+                    limit: parse_key_value(&p, p_list[5]),
+                };
+                match client.public.get_async_messages(filter).await {
+                    Ok(messages) => Ok(Box::new(messages)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_address_history => {
+                if parameters.is_empty() {
+                    bail!("wrong number of parameters");
+                }
+                let address = parameters[0].parse::<Address>()?;
+                let p_list: [&str; 2] = ["offset", "limit"];
+                let mut p: HashMap<&str, &str> = HashMap::new();
+                for v in &parameters[1..] {
+                    let s: Vec<&str> = v.split('=').collect();
+                    if s.len() == 2 && p_list.contains(&s[0]) {
+                        p.insert(s[0], s[1]);
+                    } else {
+                        bail!("invalid parameter");
+                    }
+                }
+                let request = AddressHistoryRequest {
+                    address,
+                    offset: parse_key_value(&p, p_list[0]).unwrap_or(0),
+                    limit: parse_key_value(&p, p_list[1]).unwrap_or(DEFAULT_ADDRESS_HISTORY_LIMIT),
+                };
+                match client.public.get_address_history(request).await {
+                    Ok(history) => Ok(Box::new(history)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
+            Command::get_block_execution_receipt => {
+                if parameters.len() != 1 {
+                    bail!("wrong param numbers")
+                }
+                let block_id = parameters[0].parse::<BlockId>()?;
+                match client.public.get_block_execution_receipt(block_id).await {
+                    Ok(receipt) => Ok(Box::new(receipt)),
+                    Err(e) => rpc_error!(e),
+                }
+            }
+
             Command::wallet_info => {
                 if !json {
                     client_warning!("do not share your key");
@@ -796,6 +1047,160 @@ impl Command {
                 )
                 .await
             }
+
+            Command::cancel_async_message => {
+                if parameters.len() != 4 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let emission_slot = parameters[1].parse::<Slot>()?;
+                let emission_index = parameters[2].parse::<u64>()?;
+                let fee = parameters[3].parse::<Amount>()?;
+
+                if !json {
+                    let filter = AsyncMessageFilter {
+                        sender: Some(addr),
+                        emission_slot: Some(emission_slot),
+                        emission_index: Some(emission_index),
+                        ..Default::default()
+                    };
+                    if let Ok(messages) = client.public.get_async_messages(filter).await {
+                        if !messages
+                            .iter()
+                            .any(|msg| msg.status == AsyncMessageStatus::Pending)
+                        {
+                            client_warning!(
+                                "no pending message matches, this operation will certainly fail"
+                            );
+                        }
+                    }
+                }
+
+                send_operation(
+                    client,
+                    wallet,
+                    OperationType::CancelAsyncMessage {
+                        emission_slot,
+                        emission_index,
+                    },
+                    fee,
+                    addr,
+                    json,
+                )
+                .await
+            }
+            Command::multisig_address => {
+                if parameters.len() < 2 {
+                    bail!("wrong number of parameters");
+                }
+                let threshold = parameters[0].parse::<u8>()?;
+                let public_keys = parse_vec::<PublicKey>(&parameters[1..])?;
+                let descriptor = MultisigDescriptor::new(threshold, public_keys)?;
+                Ok(Box::new(descriptor.to_string()))
+            }
+
+            Command::multisig_propose => {
+                if parameters.len() < 5 {
+                    bail!("wrong number of parameters");
+                }
+                let path = parameters[0].parse::<PathBuf>()?;
+                let threshold = parameters[1].parse::<u8>()?;
+                let public_keys = parameters[2]
+                    .split(',')
+                    .map(|key| key.parse::<PublicKey>())
+                    .collect::<Result<Vec<_>, _>>()?;
+                let descriptor = MultisigDescriptor::new(threshold, public_keys)?;
+                let action = match (parameters[3].as_str(), &parameters[4..]) {
+                    ("transaction", [recipient_address, amount]) => MultisigAction::Transaction {
+                        recipient_address: recipient_address.parse::<Address>()?,
+                        amount: amount.parse::<Amount>()?,
+                    },
+                    ("buy_rolls", [roll_count]) => MultisigAction::RollBuy {
+                        roll_count: roll_count.parse::<u64>()?,
+                    },
+                    ("sell_rolls", [roll_count]) => MultisigAction::RollSell {
+                        roll_count: roll_count.parse::<u64>()?,
+                    },
+                    _ => bail!("unknown multisig action or wrong number of parameters"),
+                };
+                let expire_period = get_expire_period(client, descriptor.address()).await?;
+                let proposal_file = MultisigProposalFile {
+                    proposal: MultisigProposal {
+                        descriptor,
+                        action,
+                        // distinguishes repeated identical actions
+                        nonce: MassaTime::now(0)?.to_millis(),
+                        expire_period,
+                    },
+                    signatures: Vec::new(),
+                };
+                proposal_file.write(&path).await?;
+                if !json {
+                    println!("Proposal written to {}:", path.display());
+                }
+                Ok(Box::new(proposal_file.proposal.to_string()))
+            }
+
+            Command::multisig_sign => {
+                if parameters.len() != 1 {
+                    bail!("wrong number of parameters");
+                }
+                let path = parameters[0].parse::<PathBuf>()?;
+                let mut proposal_file = MultisigProposalFile::read(&path).await?;
+                let new_signatures = wallet.sign_multisig_proposal(&proposal_file.proposal)?;
+                if new_signatures.is_empty() {
+                    bail!("no wallet key is among the signers of this proposal");
+                }
+                proposal_file.signatures = combine_multisig_signatures(
+                    proposal_file.signatures.into_iter().chain(new_signatures),
+                );
+                proposal_file.write(&path).await?;
+                Ok(Box::new(format!(
+                    "{}/{} signatures collected",
+                    proposal_file.signatures.len(),
+                    proposal_file.proposal.descriptor.threshold
+                )))
+            }
+
+            Command::multisig_submit => {
+                if parameters.len() < 3 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let fee = parameters[1].parse::<Amount>()?;
+                let mut proposal = None;
+                let mut signatures = Vec::new();
+                for path in parse_vec::<PathBuf>(&parameters[2..])? {
+                    let proposal_file = MultisigProposalFile::read(&path).await?;
+                    if let Some(p) = &proposal {
+                        if *p != proposal_file.proposal {
+                            bail!("{} contains a different proposal", path.display());
+                        }
+                    }
+                    proposal.get_or_insert(proposal_file.proposal);
+                    signatures.extend(proposal_file.signatures);
+                }
+                let proposal = proposal.ok_or_else(|| anyhow!("no proposal file given"))?;
+                let signatures = combine_multisig_signatures(signatures);
+                proposal.verify(&signatures)?;
+
+                send_operation_content(
+                    client,
+                    wallet,
+                    Operation {
+                        fee,
+                        expire_period: proposal.expire_period,
+                        op: OperationType::MultisigExecute {
+                            proposal,
+                            signatures,
+                        },
+                    },
+                    addr,
+                    json,
+                )
+                .await
+            }
+
             Command::when_episode_ends => {
                 let end = match client.public.get_status().await {
                     Ok(node_status) => node_status.config.end_timestamp,
@@ -899,33 +1304,8 @@ impl Command {
                 let coins = parameters[6].parse::<Amount>()?;
                 let fee = parameters[7].parse::<Amount>()?;
                 if !json {
-                    match gas_price
-                        .checked_mul_u64(max_gas)
-                        .and_then(|x| x.checked_add(fee))
-                    {
-                        Some(total) => {
-                            if let Ok(addresses_info) =
-                                client.public.get_addresses(vec![target_addr]).await
-                            {
-                                match addresses_info.get(0) {
-                                    Some(info) => {
-                                        if info.candidate_sequential_balance < total {
-                                            client_warning!("this operation may be rejected due to insufficient balance");
-                                        }
-                                    }
-                                    None => {
-                                        client_warning!(format!(
-                                            "address {} not found",
-                                            target_addr
-                                        ));
-                                    }
-                                }
-                            }
-                        }
-                        None => {
-                            client_warning!("the total amount hit the limit overflow, operation will certainly be rejected");
-                        }
-                    }
+                    check_call_balance(client, target_addr, max_gas, gas_price, fee).await;
+                    show_called_function(client, target_addr, &target_func).await;
                 };
                 send_operation(
                     client,
@@ -945,6 +1325,40 @@ impl Command {
                 )
                 .await
             }
+            Command::call_smart_contract_abi => {
+                if parameters.len() < 7 {
+                    bail!("wrong number of parameters");
+                }
+                let addr = parameters[0].parse::<Address>()?;
+                let target_addr = parameters[1].parse::<Address>()?;
+                let target_func = parameters[2].clone();
+                let max_gas = parameters[3].parse::<u64>()?;
+                let gas_price = parameters[4].parse::<Amount>()?;
+                let coins = parameters[5].parse::<Amount>()?;
+                let fee = parameters[6].parse::<Amount>()?;
+                let (_, param) =
+                    encode_abi_call(client, target_addr, &target_func, &parameters[7..]).await?;
+                if !json {
+                    check_call_balance(client, target_addr, max_gas, gas_price, fee).await;
+                }
+                send_operation(
+                    client,
+                    wallet,
+                    OperationType::CallSC {
+                        target_addr,
+                        target_func,
+                        param,
+                        max_gas,
+                        sequential_coins: Amount::zero(),
+                        parallel_coins: coins,
+                        gas_price,
+                    },
+                    fee,
+                    addr,
+                    json,
+                )
+                .await
+            }
             Command::wallet_sign => {
                 if parameters.len() != 2 {
                     bail!("wrong number of parameters");
@@ -978,6 +1392,8 @@ impl Command {
                         simulated_gas_price,
                         bytecode,
                         address,
+                        state_overrides: Vec::new(),
+                        final_state_only: false,
                     })
                     .await
                 {
@@ -1009,6 +1425,8 @@ impl Command {
                         parameter,
                         max_gas,
                         simulated_gas_price,
+                        state_overrides: Vec::new(),
+                        final_state_only: false,
                     })
                     .await
                 {
@@ -1016,6 +1434,48 @@ impl Command {
                     Err(e) => rpc_error!(e),
                 }
             }
+            Command::read_only_call_abi => {
+                if parameters.len() < 4 {
+                    bail!("wrong number of parameters");
+                }
+                let target_address = parameters[0].parse::<Address>()?;
+                let target_function = parameters[1].clone();
+                let max_gas = parameters[2].parse::<u64>()?;
+                let simulated_gas_price = parameters[3].parse::<Amount>()?;
+                let (abi, parameter) =
+                    encode_abi_call(client, target_address, &target_function, &parameters[4..])
+                        .await?;
+                let response = match client
+                    .public
+                    .execute_read_only_call(ReadOnlyCall {
+                        caller_address: None,
+                        target_address,
+                        target_function: target_function.clone(),
+                        parameter,
+                        max_gas,
+                        simulated_gas_price,
+                        state_overrides: Vec::new(),
+                        final_state_only: false,
+                    })
+                    .await
+                {
+                    Ok(mut responses) if !responses.is_empty() => responses.remove(0),
+                    Ok(_) => bail!("no response from the node"),
+                    Err(e) => rpc_error!(e),
+                };
+                let events = response
+                    .output_events
+                    .iter()
+                    .map(|event| {
+                        if event.context.call_stack.back() == Some(&target_address) {
+                            decode_event(&abi, &event.data)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect();
+                Ok(Box::new(DecodedReadOnlyResponse { response, events }))
+            }
             Command::node_whitelist => {
                 let ips = parse_vec::<IpAddr>(parameters)?;
                 match client.private.node_whitelist(ips).await {
@@ -1053,6 +1513,116 @@ async fn send_operation(
     addr: Address,
     json: bool,
 ) -> Result<Box<dyn Output>> {
+    let expire_period = get_expire_period(client, addr).await?;
+    send_operation_content(
+        client,
+        wallet,
+        Operation {
+            fee,
+            expire_period,
+            op,
+        },
+        addr,
+        json,
+    )
+    .await
+}
+
+/// Warns if the balance of an address may not cover the gas and the fee of a smart contract call
+async fn check_call_balance(
+    client: &Client,
+    address: Address,
+    max_gas: u64,
+    gas_price: Amount,
+    fee: Amount,
+) {
+    match gas_price
+        .checked_mul_u64(max_gas)
+        .and_then(|x| x.checked_add(fee))
+    {
+        Some(total) => {
+            if let Ok(addresses_info) = client.public.get_addresses(vec![address]).await {
+                match addresses_info.get(0) {
+                    Some(info) => {
+                        if info.candidate_sequential_balance < total {
+                            client_warning!(
+                                "this operation may be rejected due to insufficient balance"
+                            );
+                        }
+                    }
+                    None => {
+                        client_warning!(format!("address {} not found", address));
+                    }
+                }
+            }
+        }
+        None => {
+            client_warning!(
+                "the total amount hit the limit overflow, operation will certainly be rejected"
+            );
+        }
+    }
+}
+
+/// Shows the signature of a called smart contract function if the ABI of the contract is known,
+/// or the functions it exports if it does not export the called one
+async fn show_called_function(client: &Client, target_addr: Address, target_func: &str) {
+    let abi = match client.public.get_contract_abi(target_addr).await {
+        Ok(Some(abi)) => abi,
+        _ => return,
+    };
+    match abi.get_function(target_func) {
+        Some(function) => println!("Calling {}", function),
+        None => client_warning!(format!(
+            "{} is not exported by the verified sources of {}, available functions: {}",
+            target_func,
+            target_addr,
+            abi.functions
+                .iter()
+                .map(|function| function.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Encodes the parameter of a call to a smart contract function according to the ABI of the contract.
+/// The arguments are either given one by one, or all at once as `json=` followed by a JSON array or object.
+async fn encode_abi_call(
+    client: &Client,
+    target_addr: Address,
+    target_func: &str,
+    args: &[String],
+) -> Result<(ContractAbi, String)> {
+    let abi = match client.public.get_contract_abi(target_addr).await {
+        Ok(Some(abi)) => abi,
+        Ok(None) => bail!("no ABI is published or verified for {}", target_addr),
+        Err(e) => rpc_error!(e),
+    };
+    let function = match abi.get_function(target_func) {
+        Some(function) => function,
+        None => bail!(
+            "{} is not exported by {}, available functions: {}",
+            target_func,
+            target_addr,
+            abi.functions
+                .iter()
+                .map(|function| function.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    };
+    let param = match args {
+        [arg] if arg.starts_with("json=") => {
+            encode_json_arguments(function, &serde_json::from_str(&arg["json=".len()..])?)?
+        }
+        _ => encode_arguments(function, args)?,
+    };
+    Ok((abi, param))
+}
+
+/// Computes the expiration period of an operation created now by an address
+async fn get_expire_period(client: &Client, addr: Address) -> Result<u64> {
     let cfg = match client.public.get_status().await {
         Ok(node_status) => node_status,
         Err(e) => rpc_error!(e),
@@ -1065,15 +1635,18 @@ async fn send_operation(
     if slot.thread >= addr.get_thread(cfg.thread_count) {
         expire_period += 1;
     };
+    Ok(expire_period)
+}
 
-    let op = wallet.create_operation(
-        Operation {
-            fee,
-            expire_period,
-            op,
-        },
-        addr,
-    )?;
+/// Signs an operation with the key of the given wallet address and sends it
+async fn send_operation_content(
+    client: &Client,
+    wallet: &Wallet,
+    content: Operation,
+    addr: Address,
+    json: bool,
+) -> Result<Box<dyn Output>> {
+    let op = wallet.create_operation(content, addr)?;
 
     match client
         .public
@@ -1094,12 +1667,41 @@ async fn send_operation(
     }
 }
 
+/// Multisig proposal and the signatures collected so far, as exchanged between signers
+#[derive(Debug, Serialize, Deserialize)]
+struct MultisigProposalFile {
+    proposal: MultisigProposal,
+    signatures: Vec<MultisigSignature>,
+}
+
+impl MultisigProposalFile {
+    async fn read(path: &std::path::Path) -> Result<Self> {
+        Ok(serde_json::from_slice(&tokio::fs::read(path).await?)?)
+    }
+
+    async fn write(&self, path: &std::path::Path) -> Result<()> {
+        tokio::fs::write(path, serde_json::to_vec_pretty(self)?).await?;
+        Ok(())
+    }
+}
+
 /// TODO: ugly utilities functions
 /// takes a slice of string and makes it into a `Vec<T>`
 pub fn parse_vec<T: std::str::FromStr>(args: &[String]) -> anyhow::Result<Vec<T>, T::Err> {
     args.iter().map(|x| x.parse::<T>()).collect()
 }
 
+/// Splits an optional trailing ban duration in milliseconds from the banned ips or ids
+fn parse_ban_duration(args: &[String]) -> (&[String], Option<MassaTime>) {
+    match args.split_last() {
+        Some((last, rest)) => match last.parse::<u64>() {
+            Ok(millis) => (rest, Some(MassaTime::from_millis(millis))),
+            Err(_) => (args, None),
+        },
+        None => (args, None),
+    }
+}
+
 /// reads a file
 async fn get_file_as_byte_vec(filename: &std::path::Path) -> Result<Vec<u8>> {
     Ok(tokio::fs::read(filename).await?)
@@ -1,14 +1,17 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use crate::cmds::{Command, ExtendedWallet};
+use crate::cmds::{Command, DecodedReadOnlyResponse, ExtendedWallet};
 use crate::settings::SETTINGS;
 use crate::utils::longest_common_prefix;
 use console::style;
 use dialoguer::{theme::ColorfulTheme, Completion, History, Input};
 use erased_serde::{Serialize, Serializer};
 use glob::glob;
+use massa_models::abi::ContractAbi;
 use massa_models::api::{
-    AddressInfo, BlockInfo, DatastoreEntryOutput, EndorsementInfo, NodeStatus, OperationInfo,
+    AddressHistoryEntry, AddressInfo, AsyncMessageInfo, BannedPeerInfo, BlockExecutionReceipt,
+    BlockInfo, ContractMetadata, DatastoreEntryOutput, EndorsementInfo, NodeStatus, OperationInfo,
+    VerifiedContractSources,
 };
 use massa_models::composite::PubkeySig;
 use massa_models::execution::ExecuteReadOnlyResponse;
@@ -314,12 +317,91 @@ impl Output for Vec<SCOutputEvent> {
     }
 }
 
+impl Output for Vec<AsyncMessageInfo> {
+    fn pretty_print(&self) {
+        for message_info in self {
+            println!("{}", message_info);
+        }
+    }
+}
+
+impl Output for Vec<AddressHistoryEntry> {
+    fn pretty_print(&self) {
+        for entry in self {
+            print!("{}", entry);
+        }
+    }
+}
+
+impl Output for Vec<ContractMetadata> {
+    fn pretty_print(&self) {
+        for metadata in self {
+            println!("{}", metadata);
+        }
+    }
+}
+
+impl Output for VerifiedContractSources {
+    fn pretty_print(&self) {
+        println!(
+            "Sources verified and registered for bytecode {}",
+            self.code_hash
+        );
+    }
+}
+
+impl Output for Option<BlockExecutionReceipt> {
+    fn pretty_print(&self) {
+        match self {
+            Some(receipt) => println!("{}", receipt),
+            None => println!("No receipt found for this block"),
+        }
+    }
+}
+
+impl Output for Vec<BannedPeerInfo> {
+    fn pretty_print(&self) {
+        if self.is_empty() {
+            println!("No banned peers");
+        }
+        for peer in self {
+            print!("{}", peer);
+        }
+    }
+}
+
 impl Output for PubkeySig {
     fn pretty_print(&self) {
         println!("{}", self);
     }
 }
 
+impl Output for DecodedReadOnlyResponse {
+    fn pretty_print(&self) {
+        println!("{}", self);
+    }
+}
+
+impl Output for Option<ContractAbi> {
+    fn pretty_print(&self) {
+        match self {
+            Some(abi) => {
+                println!("Functions:");
+                for function in &abi.functions {
+                    println!("\t{}", function);
+                }
+                if !abi.events.is_empty() {
+                    println!("Events:");
+                    for event in &abi.events {
+                        println!("\t{}", event);
+                    }
+                }
+            }
+            None => println!("No ABI published or verified for this address"),
+        }
+    }
+}
+
 impl Output for ExecuteReadOnlyResponse {
     fn pretty_print(&self) {
         println!("{}", self);
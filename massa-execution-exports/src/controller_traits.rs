@@ -2,13 +2,18 @@
 
 //! This module exports generic traits representing interfaces for interacting with the Execution worker
 
-use crate::types::ExecutionOutput;
+use crate::types::ReadOnlyExecutionBatch;
+use crate::types::ReadOnlyExecutionOutput;
 use crate::types::ReadOnlyExecutionRequest;
 use crate::ExecutionAddressInfo;
 use crate::ExecutionError;
+use massa_hash::Hash;
+use massa_ledger_exports::{LedgerProofs, LedgerSubEntry};
 use massa_models::address::Address;
 use massa_models::amount::Amount;
-use massa_models::api::EventFilter;
+use massa_models::api::{
+    AddressHistoryEntry, AsyncMessageFilter, AsyncMessageInfo, BlockExecutionReceipt, EventFilter,
+};
 use massa_models::block::BlockId;
 use massa_models::operation::OperationId;
 use massa_models::output_event::SCOutputEvent;
@@ -40,6 +45,38 @@ pub trait ExecutionController: Send + Sync {
     /// * operation id
     fn get_filtered_sc_output_event(&self, filter: EventFilter) -> Vec<SCOutputEvent>;
 
+    /// Get asynchronous messages and their status, optionally filtered by:
+    /// * sender address
+    /// * destination address
+    /// * emission slot
+    /// * emission index
+    ///
+    /// # Return value
+    /// * pending messages of the candidate pool, followed by the outcomes of the messages that left the pool
+    fn get_filtered_async_messages(&self, filter: AsyncMessageFilter) -> Vec<AsyncMessageInfo>;
+
+    /// Get a page of the history of operations and coin transfers involving an address
+    ///
+    /// # Arguments
+    /// * `address`: address whose history is requested
+    /// * `offset`: number of most recent entries to skip
+    /// * `limit`: maximum number of entries to return
+    ///
+    /// # Return value
+    /// * history entries, most recent first (empty if the address history is disabled)
+    fn get_address_history(
+        &self,
+        address: &Address,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<AddressHistoryEntry>;
+
+    /// Get the receipt of the execution of a block, if it was executed recently
+    ///
+    /// # Return value
+    /// * the candidate receipt if the block was executed speculatively, the final one otherwise
+    fn get_block_execution_receipt(&self, block_id: &BlockId) -> Option<BlockExecutionReceipt>;
+
     /// Get the final and active values of sequential balances.
     ///
     /// # Return value
@@ -59,24 +96,55 @@ pub trait ExecutionController: Send + Sync {
         input: Vec<(Address, Vec<u8>)>,
     ) -> Vec<(Option<Vec<u8>>, Option<Vec<u8>>)>;
 
+    /// Get proofs of the final values of ledger sub-entries against the final ledger root
+    ///
+    /// # Return value
+    /// * the final slot, the ledger root at that slot and one proof per queried sub-entry
+    fn get_final_ledger_proofs(&self, input: Vec<(Address, LedgerSubEntry)>) -> LedgerProofs;
+
+    /// Get the root of the final ledger at the output of a recent final slot
+    ///
+    /// # Return value
+    /// * `None` if the slot is not final yet or too old to be remembered
+    fn get_final_ledger_root(&self, slot: Slot) -> Option<Hash>;
+
     /// Returns for a given cycle the stakers taken into account
     /// by the selector. That correspond to the roll_counts in `cycle - 3`.
     ///
     /// By default it returns an empty map.
     fn get_cycle_active_rolls(&self, cycle: u64) -> BTreeMap<Address, u64>;
 
+    /// Returns the roll counts at the end of a final cycle
+    ///
+    /// # Return value
+    /// * `None` if the cycle is not complete yet or too old to be remembered
+    fn get_final_cycle_rolls(&self, cycle: u64) -> Option<BTreeMap<Address, u64>>;
+
     /// Execute read-only SC function call without causing modifications to the consensus state
     ///
     /// # arguments
     /// * `req`: an instance of `ReadOnlyCallRequest` describing the parameters of the execution
     ///
     /// # returns
-    /// An instance of `ExecutionOutput` containing a summary of the effects of the execution,
+    /// An instance of `ReadOnlyExecutionOutput` containing a summary of the effects of the execution,
     /// or an error if the execution failed.
     fn execute_readonly_request(
         &self,
         req: ReadOnlyExecutionRequest,
-    ) -> Result<ExecutionOutput, ExecutionError>;
+    ) -> Result<ReadOnlyExecutionOutput, ExecutionError>;
+
+    /// Execute a batch of read-only requests, in order, against the same candidate state
+    ///
+    /// # arguments
+    /// * `batch`: the requests and whether they see the state changes of the previous ones
+    ///
+    /// # returns
+    /// The outcome of each request in the order of the batch,
+    /// or an error if the batch could not be executed.
+    fn execute_readonly_batch(
+        &self,
+        batch: ReadOnlyExecutionBatch,
+    ) -> Result<Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>, ExecutionError>;
 
     /// List which operations inside the provided list were not executed
     fn unexecuted_ops_among(
@@ -5,6 +5,16 @@
 use displaydoc::Display;
 use thiserror::Error;
 
+/// Chain of errors, outermost first
+#[derive(Clone, Debug)]
+pub struct ErrorChain(pub Vec<String>);
+
+impl std::fmt::Display for ErrorChain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.join(": "))
+    }
+}
+
 /// Errors of the execution component.
 #[non_exhaustive]
 #[derive(Clone, Display, Error, Debug)]
@@ -15,6 +25,9 @@ pub enum ExecutionError {
     /// Runtime error: {0}
     RuntimeError(String),
 
+    /// Runtime error: {0}
+    RuntimeErrorChain(ErrorChain),
+
     /// `MassaHashError`: {0}
     MassaHashError(#[from] massa_hash::MassaHashError),
 
@@ -30,6 +43,12 @@ pub enum ExecutionError {
     /// Transaction error: {0}
     TransactionError(String),
 
+    /// Async message cancel error: {0}
+    AsyncMessageCancelError(String),
+
+    /// Multisig error: {0}
+    MultisigError(String),
+
     /// Block gas error: {0}
     BlockGasError(String),
 
@@ -39,6 +58,19 @@ pub enum ExecutionError {
     /// Not enough gas in the block: {0}
     NotEnoughGas(String),
 
+    /// Read-only batch gas error: {0}
+    ReadOnlyBatchGasError(String),
+
     /// Inlcude operation error: {0}
     InlcudeOperationError(String),
 }
+
+impl ExecutionError {
+    /// Gets the chain of errors that led to this one, outermost first
+    pub fn chain(&self) -> Vec<String> {
+        match self {
+            ExecutionError::RuntimeErrorChain(chain) => chain.0.clone(),
+            err => vec![err.to_string()],
+        }
+    }
+}
@@ -52,6 +52,8 @@ impl EventStore {
     /// * emitter address
     /// * original caller address
     /// * operation id
+    /// * origin asynchronous message
+    /// * structured event topic
     /// * is_final
     pub fn get_filtered_sc_output_events(&self, filter: &EventFilter) -> VecDeque<SCOutputEvent> {
         self.0
@@ -87,6 +89,20 @@ impl EventStore {
                     (Some(_), None) => return false,
                     _ => (),
                 }
+                match (
+                    filter.original_async_message,
+                    x.context.origin_async_message,
+                ) {
+                    (Some(origin1), Some(origin2)) if origin1 != origin2 => return false,
+                    (Some(_), None) => return false,
+                    _ => (),
+                }
+                if let Some(topic) = &filter.topic {
+                    match &x.structured {
+                        Some(structured) if structured.topic == *topic => (),
+                        _ => return false,
+                    }
+                }
                 true
             })
             .cloned()
@@ -109,9 +125,11 @@ fn test_prune() {
                 index_in_slot: 1,
                 call_stack: VecDeque::new(),
                 origin_operation_id: None,
+                origin_async_message: None,
                 is_final: false,
             },
             data: i.to_string(),
+            structured: None,
         });
     }
     assert_eq!(store.0.len(), 10);
@@ -121,3 +139,88 @@ fn test_prune() {
     assert_eq!(store.0[1].data, "8");
     assert_eq!(store.0[0].data, "7");
 }
+
+#[test]
+fn test_filter_by_async_message() {
+    use massa_models::output_event::{AsyncMessageOrigin, EventExecutionContext, SCOutputEvent};
+    use massa_models::slot::Slot;
+    use std::str::FromStr;
+
+    let mut store = EventStore(VecDeque::new());
+    for i in 0..4 {
+        store.push(SCOutputEvent {
+            context: EventExecutionContext {
+                slot: Slot::new(1, 0),
+                block: None,
+                read_only: false,
+                index_in_slot: i,
+                call_stack: VecDeque::new(),
+                origin_operation_id: None,
+                origin_async_message: if i % 2 == 0 {
+                    None
+                } else {
+                    Some(AsyncMessageOrigin {
+                        emission_slot: Slot::new(0, 1),
+                        emission_index: i,
+                    })
+                },
+                is_final: false,
+            },
+            data: i.to_string(),
+            structured: None,
+        });
+    }
+    let filter = EventFilter {
+        original_async_message: Some(AsyncMessageOrigin::from_str("0,1,3").unwrap()),
+        ..Default::default()
+    };
+    let events = store.get_filtered_sc_output_events(&filter);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].data, "3");
+}
+
+#[test]
+fn test_filter_by_topic() {
+    use massa_models::output_event::{
+        EventExecutionContext, EventValue, SCOutputEvent, StructuredEvent, TRANSFER_EVENT_TOPIC,
+    };
+    use massa_models::slot::Slot;
+
+    let mut store = EventStore(VecDeque::new());
+    for i in 0..4 {
+        store.push(SCOutputEvent {
+            context: EventExecutionContext {
+                slot: Slot::new(1, 0),
+                block: None,
+                read_only: false,
+                index_in_slot: i,
+                call_stack: VecDeque::new(),
+                origin_operation_id: None,
+                origin_async_message: None,
+                is_final: false,
+            },
+            data: i.to_string(),
+            structured: match i {
+                0 => None,
+                1 => Some(StructuredEvent::with_fields(
+                    "other",
+                    vec![("index".to_string(), EventValue::U64(i))],
+                )),
+                _ => Some(StructuredEvent::with_fields(
+                    TRANSFER_EVENT_TOPIC,
+                    vec![("index".to_string(), EventValue::U64(i))],
+                )),
+            },
+        });
+    }
+    let filter = EventFilter {
+        topic: Some(TRANSFER_EVENT_TOPIC.to_string()),
+        ..Default::default()
+    };
+    let events = store.get_filtered_sc_output_events(&filter);
+    assert_eq!(events.len(), 2);
+    assert_eq!(
+        events[0].structured.as_ref().unwrap().get_field("index"),
+        Some(&EventValue::U64(2))
+    );
+}
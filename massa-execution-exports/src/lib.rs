@@ -51,12 +51,13 @@ mod settings;
 mod types;
 
 pub use controller_traits::{ExecutionController, ExecutionManager};
-pub use error::ExecutionError;
+pub use error::{ErrorChain, ExecutionError};
 pub use event_store::EventStore;
 pub use settings::ExecutionConfig;
 pub use types::{
     ExecutionAddressInfo, ExecutionOutput, ExecutionStackElement, ReadOnlyCallRequest,
-    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ReadOnlyExecutionBatch, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget,
 };
 
 #[cfg(feature = "testing")]
@@ -11,12 +11,32 @@ use num::rational::Ratio;
 pub struct ExecutionConfig {
     /// read-only execution request queue length
     pub readonly_queue_length: usize,
+    /// maximum gas summed over the requests of a read-only batch
+    pub max_read_only_batch_gas: u64,
     /// maximum number of SC output events kept in cache
     pub max_final_events: usize,
+    /// maximum number of final asynchronous message outcomes kept in cache
+    pub max_final_async_message_outcomes: usize,
+    /// maximum number of final block execution receipts kept in cache
+    pub max_final_block_receipts: usize,
+    /// maintain a per-address history of operations and coin transfers
+    pub address_history_enabled: bool,
+    /// maximum number of final history entries kept in cache per address
+    pub max_address_history_length: usize,
+    /// maximum number of final history entries kept in cache, all addresses included
+    pub max_final_address_history_entries: usize,
     /// maximum available gas for asynchronous messages execution
     pub max_async_gas: u64,
     /// maximum gas per block
     pub max_gas_per_block: u64,
+    /// period from which the unused gas of operations is refunded and only their consumed gas counts in the block gas
+    pub gas_refund_start_period: u64,
+    /// period from which the metadata of bytecodes is recorded and bytecodes can be made immutable
+    pub bytecode_metadata_start_period: u64,
+    /// period from which `CancelAsyncMessage` operations can be included in blocks
+    pub async_message_cancellation_start_period: u64,
+    /// period from which `MultisigExecute` operations can be included in blocks
+    pub multisig_start_period: u64,
     /// number of threads
     pub thread_count: u8,
     /// price of a roll inside the network
@@ -43,4 +63,7 @@ pub struct ExecutionConfig {
     pub max_miss_ratio: Ratio<u64>,
     /// Max size of a datastore key
     pub max_datastore_key_length: u8,
+    /// number of threads executing the operations of a block optimistically in parallel
+    /// (0 or 1 to execute them sequentially)
+    pub parallel_execution_threads: usize,
 }
@@ -11,7 +11,13 @@ impl Default for ExecutionConfig {
     fn default() -> Self {
         Self {
             readonly_queue_length: 100,
+            max_read_only_batch_gas: MAX_GAS_PER_BLOCK,
             max_final_events: 1000,
+            max_final_async_message_outcomes: 1000,
+            max_final_block_receipts: 1000,
+            address_history_enabled: true,
+            max_address_history_length: 1000,
+            max_final_address_history_entries: 10000,
             max_async_gas: MAX_ASYNC_GAS,
             thread_count: THREAD_COUNT,
             roll_price: ROLL_PRICE,
@@ -19,6 +25,10 @@ impl Default for ExecutionConfig {
             block_reward: BLOCK_REWARD,
             endorsement_count: ENDORSEMENT_COUNT as u64,
             max_gas_per_block: MAX_GAS_PER_BLOCK,
+            gas_refund_start_period: 0,
+            bytecode_metadata_start_period: 0,
+            async_message_cancellation_start_period: 0,
+            multisig_start_period: 0,
             operation_validity_period: OPERATION_VALIDITY_PERIODS,
             periods_per_cycle: PERIODS_PER_CYCLE,
             clock_compensation: Default::default(),
@@ -29,6 +39,7 @@ impl Default for ExecutionConfig {
             stats_time_window_duration: MassaTime::from_millis(30000),
             max_miss_ratio: *POS_MISS_RATE_DEACTIVATION_THRESHOLD,
             max_datastore_key_length: MAX_DATASTORE_KEY_LENGTH,
+            parallel_execution_threads: 0,
         }
     }
 }
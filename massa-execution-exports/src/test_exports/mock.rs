@@ -3,13 +3,26 @@
 //! This file defines utilities to mock the crate for testing purposes
 
 use crate::{
-    ExecutionAddressInfo, ExecutionController, ExecutionError, ExecutionOutput,
-    ReadOnlyExecutionRequest,
+    ExecutionAddressInfo, ExecutionController, ExecutionError, ReadOnlyExecutionBatch,
+    ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+};
+use massa_hash::Hash;
+use massa_ledger_exports::{
+    merkle_empty_hash, LedgerEntry, LedgerProof, LedgerProofs, LedgerSubEntry,
 };
-use massa_ledger_exports::LedgerEntry;
 use massa_models::{
-    address::Address, amount::Amount, api::EventFilter, block::BlockId, operation::OperationId,
-    output_event::SCOutputEvent, prehash::PreHashSet, slot::Slot, stats::ExecutionStats,
+    address::Address,
+    amount::Amount,
+    api::{
+        AddressHistoryEntry, AsyncMessageFilter, AsyncMessageInfo, BlockExecutionReceipt,
+        EventFilter,
+    },
+    block::BlockId,
+    operation::OperationId,
+    output_event::SCOutputEvent,
+    prehash::PreHashSet,
+    slot::Slot,
+    stats::ExecutionStats,
 };
 use massa_storage::Storage;
 use massa_time::MassaTime;
@@ -56,7 +69,16 @@ pub enum MockExecutionControllerMessage {
         /// read only execution request
         req: ReadOnlyExecutionRequest,
         /// response channel
-        response_tx: mpsc::Sender<Result<ExecutionOutput, ExecutionError>>,
+        response_tx: mpsc::Sender<Result<ReadOnlyExecutionOutput, ExecutionError>>,
+    },
+    /// read only execution batch
+    ExecuteReadonlyBatch {
+        /// read only execution batch
+        batch: ReadOnlyExecutionBatch,
+        /// response channel
+        response_tx: mpsc::Sender<
+            Result<Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>, ExecutionError>,
+        >,
     },
     /// Unexecuted operation among call
     UnexecutedOpsAmong {
@@ -111,6 +133,9 @@ impl ExecutionController for MockExecutionController {
             time_window_end: MassaTime::now(0).unwrap(),
             final_block_count: 0,
             final_executed_operations_count: 0,
+            final_gas_used: 0,
+            final_max_gas: 0,
+            final_gas_refunds: Default::default(),
             active_cursor: Slot::new(0, 0),
         }
     }
@@ -141,6 +166,19 @@ impl ExecutionController for MockExecutionController {
         response_rx.recv().unwrap()
     }
 
+    fn get_filtered_async_messages(&self, _filter: AsyncMessageFilter) -> Vec<AsyncMessageInfo> {
+        Vec::default()
+    }
+
+    fn get_address_history(
+        &self,
+        _address: &Address,
+        _offset: usize,
+        _limit: usize,
+    ) -> Vec<AddressHistoryEntry> {
+        Vec::default()
+    }
+
     fn get_final_and_candidate_sequential_balances(
         &self,
         addresses: &[Address],
@@ -166,6 +204,32 @@ impl ExecutionController for MockExecutionController {
         Vec::default()
     }
 
+    fn get_block_execution_receipt(&self, _block_id: &BlockId) -> Option<BlockExecutionReceipt> {
+        None
+    }
+
+    fn get_final_ledger_proofs(&self, input: Vec<(Address, LedgerSubEntry)>) -> LedgerProofs {
+        // proofs of exclusion from an empty ledger
+        LedgerProofs {
+            slot: Slot::new(0, 0),
+            root: merkle_empty_hash(),
+            proofs: input
+                .iter()
+                .map(|(addr, sub_entry)| LedgerProof {
+                    key: sub_entry.key(addr),
+                    value: None,
+                    siblings: Vec::new(),
+                    other_leaf: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn get_final_ledger_root(&self, slot: Slot) -> Option<Hash> {
+        // the empty ledger of `get_final_ledger_proofs`
+        (slot == Slot::new(0, 0)).then(merkle_empty_hash)
+    }
+
     fn get_addresses_infos(&self, _addresses: &[Address]) -> Vec<ExecutionAddressInfo> {
         Vec::default()
     }
@@ -174,10 +238,14 @@ impl ExecutionController for MockExecutionController {
         BTreeMap::default()
     }
 
+    fn get_final_cycle_rolls(&self, _cycle: u64) -> Option<BTreeMap<Address, u64>> {
+        None
+    }
+
     fn execute_readonly_request(
         &self,
         req: ReadOnlyExecutionRequest,
-    ) -> Result<ExecutionOutput, ExecutionError> {
+    ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
         let (response_tx, response_rx) = mpsc::channel();
         self.0
             .lock()
@@ -186,6 +254,18 @@ impl ExecutionController for MockExecutionController {
         response_rx.recv().unwrap()
     }
 
+    fn execute_readonly_batch(
+        &self,
+        batch: ReadOnlyExecutionBatch,
+    ) -> Result<Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>, ExecutionError> {
+        let (response_tx, response_rx) = mpsc::channel();
+        self.0
+            .lock()
+            .send(MockExecutionControllerMessage::ExecuteReadonlyBatch { batch, response_tx })
+            .unwrap();
+        response_rx.recv().unwrap()
+    }
+
     fn unexecuted_ops_among(
         &self,
         ops: &PreHashSet<OperationId>,
@@ -5,7 +5,13 @@
 use crate::event_store::EventStore;
 use massa_final_state::StateChanges;
 use massa_models::{
-    address::Address, address::ExecutionAddressCycleInfo, amount::Amount, block::BlockId,
+    address::Address,
+    address::ExecutionAddressCycleInfo,
+    amount::Amount,
+    api::{AddressHistoryEntry, AsyncMessageInfo, BlockExecutionReceipt},
+    block::BlockId,
+    bytecode::BytecodeMetadata,
+    execution::ReadOnlyStateOverride,
     slot::Slot,
 };
 use std::collections::{BTreeMap, BTreeSet};
@@ -31,6 +37,11 @@ pub struct ExecutionAddressInfo {
     /// candidate datastore keys of the address
     pub candidate_datastore_keys: BTreeSet<Vec<u8>>,
 
+    /// final metadata of the bytecode of the address
+    pub final_bytecode_metadata: Option<BytecodeMetadata>,
+    /// candidate metadata of the bytecode of the address
+    pub candidate_bytecode_metadata: Option<BytecodeMetadata>,
+
     /// future deferred credits
     pub future_deferred_credits: BTreeMap<Slot, Amount>,
 
@@ -49,6 +60,21 @@ pub struct ExecutionOutput {
     pub state_changes: StateChanges,
     /// events emitted by the execution step
     pub events: EventStore,
+    /// outcomes of the asynchronous messages that were executed, failed or evicted during the execution step
+    pub async_message_outcomes: Vec<AsyncMessageInfo>,
+    /// address history entries (operations and coin transfers) recorded during the execution step
+    pub address_history: Vec<AddressHistoryEntry>,
+    /// receipt of the execution of the block at that slot (None if miss)
+    pub block_receipt: Option<BlockExecutionReceipt>,
+}
+
+/// structure describing the output of a read-only execution
+#[derive(Debug, Clone)]
+pub struct ReadOnlyExecutionOutput {
+    /// Output of the execution: state changes and events
+    pub out: ExecutionOutput,
+    /// Gas used by the execution
+    pub gas_cost: u64,
 }
 
 /// structure describing different types of read-only execution request
@@ -62,6 +88,20 @@ pub struct ReadOnlyExecutionRequest {
     pub call_stack: Vec<ExecutionStackElement>,
     /// Target of the request
     pub target: ReadOnlyExecutionTarget,
+    /// State injected on top of the executed state before the execution.
+    /// It is not part of the resulting state changes.
+    pub state_overrides: Vec<ReadOnlyStateOverride>,
+    /// Execute on top of the final state only, instead of the final state and the candidate slots
+    pub final_state_only: bool,
+}
+
+/// structure describing a batch of read-only execution requests run against the same state
+#[derive(Debug, Clone)]
+pub struct ReadOnlyExecutionBatch {
+    /// Requests to execute, in order
+    pub requests: Vec<ReadOnlyExecutionRequest>,
+    /// Apply the state changes of each successful request before executing the next ones
+    pub share_state: bool,
 }
 
 /// structure describing different possible targets of a read-only execution request
@@ -0,0 +1,178 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Bounded cache of the final history of operations and coin transfers, per address.
+
+use massa_models::address::Address;
+use massa_models::api::AddressHistoryEntry;
+use massa_models::prehash::PreHashMap;
+use std::collections::VecDeque;
+
+/// Final history of operations and coin transfers, indexed by involved address.
+///
+/// The cache is bounded both in total and per address:
+/// when it is full, the oldest entries are evicted first, whatever addresses they involve.
+pub(crate) struct FinalAddressHistory {
+    /// final entries, oldest first
+    entries: VecDeque<AddressHistoryEntry>,
+    /// sequence number of the front entry of `entries`
+    first_seq: u64,
+    /// sequence numbers of the entries involving each address, oldest first
+    by_address: PreHashMap<Address, VecDeque<u64>>,
+    /// maximum number of entries kept, all addresses included
+    max_entries: usize,
+    /// maximum number of entries kept per address
+    max_entries_per_address: usize,
+}
+
+/// Returns the distinct addresses involved in an entry
+fn involved_addresses(entry: &AddressHistoryEntry) -> impl Iterator<Item = Address> {
+    let mut addresses = vec![entry.from, entry.to];
+    addresses.dedup();
+    addresses.into_iter().flatten()
+}
+
+impl FinalAddressHistory {
+    /// Creates an empty history
+    ///
+    /// # Arguments
+    /// * `max_entries`: maximum number of entries kept, all addresses included
+    /// * `max_entries_per_address`: maximum number of entries kept per address
+    pub fn new(max_entries: usize, max_entries_per_address: usize) -> Self {
+        FinalAddressHistory {
+            entries: Default::default(),
+            first_seq: 0,
+            by_address: Default::default(),
+            max_entries,
+            max_entries_per_address,
+        }
+    }
+
+    /// Records a final entry in the history of the addresses it involves,
+    /// evicting the oldest entries if the history is full
+    pub fn push(&mut self, entry: AddressHistoryEntry) {
+        let seq = self.first_seq + self.entries.len() as u64;
+        for address in involved_addresses(&entry) {
+            let seqs = self.by_address.entry(address).or_default();
+            seqs.push_back(seq);
+            if seqs.len() > self.max_entries_per_address {
+                seqs.pop_front();
+            }
+            if seqs.is_empty() {
+                self.by_address.remove(&address);
+            }
+        }
+        self.entries.push_back(entry);
+
+        while self.entries.len() > self.max_entries {
+            let evicted = match self.entries.pop_front() {
+                Some(entry) => entry,
+                None => break,
+            };
+            for address in involved_addresses(&evicted) {
+                if let Some(seqs) = self.by_address.get_mut(&address) {
+                    // the entry may already have been evicted from the history of this address
+                    if seqs.front() == Some(&self.first_seq) {
+                        seqs.pop_front();
+                    }
+                    if seqs.is_empty() {
+                        self.by_address.remove(&address);
+                    }
+                }
+            }
+            self.first_seq += 1;
+        }
+    }
+
+    /// Iterates over the final entries involving an address, most recent first
+    pub fn iter_rev<'a>(
+        &'a self,
+        address: &Address,
+    ) -> impl Iterator<Item = &'a AddressHistoryEntry> + 'a {
+        let first_seq = self.first_seq;
+        self.by_address
+            .get(address)
+            .into_iter()
+            .flat_map(|seqs| seqs.iter().rev())
+            .filter_map(move |seq| self.entries.get((seq - first_seq) as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use massa_hash::Hash;
+    use massa_models::{amount::Amount, api::AddressHistoryEntryKind, slot::Slot};
+
+    fn transfer(index: u64, from: Address, to: Address) -> AddressHistoryEntry {
+        AddressHistoryEntry {
+            slot: Slot::new(1, 0),
+            index_in_slot: index,
+            kind: AddressHistoryEntryKind::SequentialTransfer,
+            operation_id: None,
+            from: Some(from),
+            to: Some(to),
+            amount: Amount::from_mantissa_scale(index, 0),
+            is_final: true,
+        }
+    }
+
+    fn indexes(history: &FinalAddressHistory, address: &Address) -> Vec<u64> {
+        history
+            .iter_rev(address)
+            .map(|entry| entry.index_in_slot)
+            .collect()
+    }
+
+    #[test]
+    fn test_record_and_query() {
+        let a = Address(Hash::compute_from(b"a"));
+        let b = Address(Hash::compute_from(b"b"));
+        let c = Address(Hash::compute_from(b"c"));
+        let mut history = FinalAddressHistory::new(100, 100);
+        history.push(transfer(0, a, b));
+        history.push(transfer(1, b, c));
+        history.push(transfer(2, a, a));
+        assert_eq!(indexes(&history, &a), vec![2, 0]);
+        assert_eq!(indexes(&history, &b), vec![1, 0]);
+        assert_eq!(indexes(&history, &c), vec![1]);
+        assert_eq!(history.entries.len(), 3);
+        assert_eq!(history.by_address.len(), 3);
+    }
+
+    #[test]
+    fn test_prune_oldest_entries() {
+        let a = Address(Hash::compute_from(b"a"));
+        let b = Address(Hash::compute_from(b"b"));
+        let c = Address(Hash::compute_from(b"c"));
+        let mut history = FinalAddressHistory::new(2, 100);
+        history.push(transfer(0, a, b));
+        history.push(transfer(1, b, c));
+        history.push(transfer(2, c, c));
+        // the oldest entry was evicted, and `a` is no longer tracked
+        assert_eq!(history.entries.len(), 2);
+        assert!(indexes(&history, &a).is_empty());
+        assert_eq!(indexes(&history, &b), vec![1]);
+        assert_eq!(indexes(&history, &c), vec![2, 1]);
+        assert_eq!(history.by_address.len(), 2);
+    }
+
+    #[test]
+    fn test_prune_per_address() {
+        let a = Address(Hash::compute_from(b"a"));
+        let b = Address(Hash::compute_from(b"b"));
+        let c = Address(Hash::compute_from(b"c"));
+        let mut history = FinalAddressHistory::new(3, 2);
+        history.push(transfer(0, a, b));
+        history.push(transfer(1, a, c));
+        history.push(transfer(2, a, c));
+        // only the 2 most recent entries of each address are kept
+        assert_eq!(indexes(&history, &a), vec![2, 1]);
+        assert_eq!(indexes(&history, &b), vec![0]);
+        assert_eq!(indexes(&history, &c), vec![2, 1]);
+        // evicting an entry already evicted from the history of an address keeps the history of that address
+        history.push(transfer(3, b, b));
+        assert_eq!(indexes(&history, &a), vec![2, 1]);
+        assert_eq!(indexes(&history, &b), vec![3]);
+        assert_eq!(history.entries.len(), 3);
+    }
+}
@@ -7,6 +7,7 @@
 //! More generally, the context acts only on its own state
 //! and does not write anything persistent to the consensus state.
 
+use crate::parallel_execution::{StateAccesses, StateKey};
 use crate::speculative_async_pool::SpeculativeAsyncPool;
 use crate::speculative_executed_ops::SpeculativeExecutedOps;
 use crate::speculative_ledger::SpeculativeLedger;
@@ -21,15 +22,28 @@ use massa_models::address::ExecutionAddressCycleInfo;
 use massa_models::{
     address::Address,
     amount::Amount,
+    api::{
+        AddressHistoryEntry, AddressHistoryEntryKind, AsyncMessageEvictionReason, AsyncMessageInfo,
+        AsyncMessageStatus,
+    },
     block::BlockId,
+    bytecode::{
+        BytecodeMetadata, BytecodeMetadataDeserializer, BYTECODE_METADATA_KEY,
+        IMMUTABLE_BYTECODE_KEY,
+    },
     operation::OperationId,
-    output_event::{EventExecutionContext, SCOutputEvent},
+    output_event::{
+        AsyncMessageOrigin, AsyncMessageOutcomeEvent, EventExecutionContext, EventValue,
+        SCOutputEvent, StructuredEvent, ASYNC_MESSAGE_OUTCOME_EVENT_KEY,
+        ASYNC_MESSAGE_OUTCOME_EVENT_TOPIC, BYTECODE_CHANGE_EVENT_TOPIC, BYTECODE_LOCK_EVENT_TOPIC,
+    },
     slot::Slot,
 };
 use massa_pos_exports::PoSChanges;
 use parking_lot::RwLock;
-use rand::SeedableRng;
+use rand::{distributions::Distribution, Rng, SeedableRng};
 use rand_xoshiro::Xoshiro256PlusPlus;
+use std::cell::RefCell;
 use std::collections::BTreeMap;
 use std::sync::Arc;
 use tracing::debug;
@@ -61,6 +75,9 @@ pub(crate) struct ExecutionContextSnapshot {
     /// generated events during this execution, with multiple indexes
     pub events: EventStore,
 
+    /// address history entries recorded so far during this execution
+    pub address_history: Vec<AddressHistoryEntry>,
+
     /// Unsafe random state
     pub unsafe_rng: Xoshiro256PlusPlus,
 }
@@ -122,6 +139,18 @@ pub(crate) struct ExecutionContext {
 
     /// operation id that originally caused this execution (if any)
     pub origin_operation_id: Option<OperationId>,
+
+    /// asynchronous message that originally caused this execution (if any)
+    pub origin_async_message: Option<AsyncMessageOrigin>,
+
+    /// outcomes of the asynchronous messages executed, failed or evicted during this execution
+    pub async_message_outcomes: Vec<AsyncMessageInfo>,
+
+    /// operations and coin transfers recorded during this execution for the address history
+    pub address_history: Vec<AddressHistoryEntry>,
+
+    /// parts of the state read and written during this execution, if they are tracked
+    accesses: Option<RefCell<StateAccesses>>,
 }
 
 impl ExecutionContext {
@@ -167,10 +196,134 @@ impl ExecutionContext {
             events: Default::default(),
             unsafe_rng: Xoshiro256PlusPlus::from_seed([0u8; 32]),
             origin_operation_id: Default::default(),
+            origin_async_message: Default::default(),
+            async_message_outcomes: Default::default(),
+            address_history: Default::default(),
+            accesses: Default::default(),
             config,
         }
     }
 
+    /// Forks the context to execute an operation on top of its current state,
+    /// keeping the changes caused by the fork separate so that they can be applied back (see `apply_fork`).
+    /// The state accesses of the fork are tracked.
+    ///
+    /// The fork reads the ledger, roll state and executed operations through the active history:
+    /// the current changes of the context (see `get_state_changes_snapshot`)
+    /// must be at the back of the active history while the fork is used.
+    pub(crate) fn fork(&self) -> Self {
+        ExecutionContext {
+            config: self.config.clone(),
+            speculative_ledger: self.speculative_ledger.fork(),
+            speculative_async_pool: self.speculative_async_pool.fork(),
+            speculative_roll_state: self.speculative_roll_state.fork(),
+            speculative_executed_ops: self.speculative_executed_ops.fork(),
+            max_gas: self.max_gas,
+            gas_price: self.gas_price,
+            slot: self.slot,
+            created_addr_index: self.created_addr_index,
+            created_event_index: self.created_event_index,
+            created_message_index: self.created_message_index,
+            opt_block_id: self.opt_block_id,
+            stack: self.stack.clone(),
+            read_only: self.read_only,
+            events: Default::default(),
+            unsafe_rng: self.unsafe_rng.clone(),
+            origin_operation_id: None,
+            origin_async_message: None,
+            async_message_outcomes: Default::default(),
+            address_history: Default::default(),
+            accesses: Some(Default::default()),
+        }
+    }
+
+    /// Applies the changes caused by an execution on a fork of this context (see `fork`)
+    /// as if the execution happened on this context.
+    /// This is only valid if none of the state read by the execution was modified since the fork was created.
+    ///
+    /// # Arguments
+    /// * `fork`: the fork on which the execution happened
+    /// * `accesses`: the state accesses of the execution
+    pub(crate) fn apply_fork(&mut self, fork: &mut ExecutionContext, accesses: &StateAccesses) {
+        self.speculative_ledger
+            .apply_changes(fork.speculative_ledger.take());
+        self.speculative_roll_state
+            .apply_changes(fork.speculative_roll_state.take());
+        self.speculative_executed_ops
+            .apply_changes(fork.speculative_executed_ops.take());
+
+        // the fork started from the current pool, random generator and counters:
+        // the ones it modified replace the current ones
+        if accesses.writes.contains(&StateKey::AsyncPool) {
+            self.speculative_async_pool
+                .apply_fork(&mut fork.speculative_async_pool);
+            self.created_message_index = fork.created_message_index;
+        }
+        if accesses.writes.contains(&StateKey::AddressCounter) {
+            self.created_addr_index = fork.created_addr_index;
+        }
+        if accesses.writes.contains(&StateKey::UnsafeRng) {
+            self.unsafe_rng = fork.unsafe_rng.clone();
+        }
+
+        // events and address history entries are indexed within the slot
+        for event in fork.events.take() {
+            self.event_emit(event);
+        }
+        for mut entry in std::mem::take(&mut fork.address_history) {
+            entry.index_in_slot = self.address_history.len() as u64;
+            self.address_history.push(entry);
+        }
+        self.async_message_outcomes
+            .append(&mut fork.async_message_outcomes);
+
+        // the fork set up the execution of an operation if it started executing it
+        if fork.origin_operation_id.is_some() {
+            self.origin_operation_id = fork.origin_operation_id;
+            self.stack = std::mem::take(&mut fork.stack);
+            self.max_gas = fork.max_gas;
+            self.gas_price = fork.gas_price;
+        }
+    }
+
+    /// Returns a copy of the state changes caused so far in the context.
+    /// The changes of the asynchronous pool are not included.
+    pub(crate) fn get_state_changes_snapshot(&self) -> StateChanges {
+        StateChanges {
+            ledger_changes: self.speculative_ledger.get_snapshot(),
+            pos_changes: self.speculative_roll_state.get_snapshot(),
+            executed_ops: self.speculative_executed_ops.get_snapshot(),
+            ..Default::default()
+        }
+    }
+
+    /// Starts tracking the parts of the state read and written in the context
+    pub(crate) fn track_accesses(&mut self) {
+        self.accesses = Some(Default::default());
+    }
+
+    /// Stops tracking state accesses and returns the ones tracked so far
+    pub(crate) fn take_accesses(&mut self) -> StateAccesses {
+        self.accesses
+            .take()
+            .map(RefCell::into_inner)
+            .unwrap_or_default()
+    }
+
+    /// Records that a part of the state was read, if accesses are tracked
+    fn record_read(&self, key: StateKey) {
+        if let Some(accesses) = &self.accesses {
+            accesses.borrow_mut().reads.insert(key);
+        }
+    }
+
+    /// Records that a part of the state was written, if accesses are tracked
+    fn record_write(&self, key: StateKey) {
+        if let Some(accesses) = &self.accesses {
+            accesses.borrow_mut().writes.insert(key);
+        }
+    }
+
     /// Returns a snapshot containing the clone of the current execution state.
     /// Note that the snapshot does not include slot-level information such as the slot number or block ID.
     pub(crate) fn get_snapshot(&self) -> ExecutionContextSnapshot {
@@ -183,6 +336,7 @@ impl ExecutionContext {
             created_event_index: self.created_event_index,
             stack: self.stack.clone(),
             events: self.events.clone(),
+            address_history: self.address_history.clone(),
             unsafe_rng: self.unsafe_rng.clone(),
         }
     }
@@ -219,6 +373,7 @@ impl ExecutionContext {
         self.created_event_index = snapshot.created_event_index;
         self.stack = snapshot.stack;
         self.events = snapshot.events;
+        self.address_history = snapshot.address_history;
         self.unsafe_rng = snapshot.unsafe_rng;
 
         // If there was an error, emit the corresponding event now.
@@ -390,6 +545,8 @@ impl ExecutionContext {
 
         // deterministically generate a new unique smart contract address
 
+        self.record_read(StateKey::AddressCounter);
+
         // create a seed from the current slot
         let mut data: Vec<u8> = self.slot.to_bytes_key().to_vec();
         // add the index of the created address within this context to the seed
@@ -404,9 +561,15 @@ impl ExecutionContext {
         // hash the seed to get a unique address
         let address = Address(massa_hash::Hash::compute_from(&data));
 
-        // add this address with its bytecode to the speculative ledger
+        // add this address with its bytecode and the metadata of the bytecode to the speculative ledger
+        let metadata = BytecodeMetadata::genesis(&[]).next(&bytecode, self.slot);
         self.speculative_ledger
             .create_new_sc_address(address, bytecode)?;
+        self.record_write(StateKey::Ledger(address));
+        if self.bytecode_metadata_active() {
+            self.set_bytecode_metadata(&address, &metadata)?;
+            self.emit_bytecode_change_event(&address, &metadata);
+        }
 
         // add the address to owned addresses
         // so that the current call has write access to it
@@ -425,27 +588,32 @@ impl ExecutionContext {
 
         // increment the address creation counter at this slot
         self.created_addr_index += 1;
+        self.record_write(StateKey::AddressCounter);
 
         Ok(address)
     }
 
     /// gets the bytecode of an address if it exists in the speculative ledger, or returns None
     pub fn get_bytecode(&self, address: &Address) -> Option<Vec<u8>> {
+        self.record_read(StateKey::Ledger(*address));
         self.speculative_ledger.get_bytecode(address)
     }
 
     /// gets the data from a datastore entry of an address if it exists in the speculative ledger, or returns None
     pub fn get_data_entry(&self, address: &Address, key: &[u8]) -> Option<Vec<u8>> {
+        self.record_read(StateKey::Ledger(*address));
         self.speculative_ledger.get_data_entry(address, key)
     }
 
     /// checks if a datastore entry exists in the speculative ledger
     pub fn has_data_entry(&self, address: &Address, key: &[u8]) -> bool {
+        self.record_read(StateKey::Ledger(*address));
         self.speculative_ledger.has_data_entry(address, key)
     }
 
     /// gets the effective parallel balance of an address
     pub fn get_parallel_balance(&self, address: &Address) -> Option<Amount> {
+        self.record_read(StateKey::Ledger(*address));
         self.speculative_ledger.get_parallel_balance(address)
     }
 
@@ -471,7 +639,14 @@ impl ExecutionContext {
             )));
         }
 
+        // setting this key makes the bytecode immutable instead of creating an entry
+        if key == IMMUTABLE_BYTECODE_KEY.as_bytes() && self.bytecode_metadata_active() {
+            return self.make_bytecode_immutable(address);
+        }
+
         // set data entry
+        self.record_read(StateKey::Ledger(*address));
+        self.record_write(StateKey::Ledger(*address));
         self.speculative_ledger.set_data_entry(address, key, data)
     }
 
@@ -498,6 +673,8 @@ impl ExecutionContext {
         }
 
         // get current data entry
+        self.record_read(StateKey::Ledger(*address));
+        self.record_write(StateKey::Ledger(*address));
         let mut res_data = self
             .speculative_ledger
             .get_data_entry(address, &key)
@@ -536,6 +713,8 @@ impl ExecutionContext {
         }
 
         // delete entry
+        self.record_read(StateKey::Ledger(*address));
+        self.record_write(StateKey::Ledger(*address));
         self.speculative_ledger.delete_data_entry(address, key)
     }
 
@@ -567,8 +746,17 @@ impl ExecutionContext {
             }
         }
         // do the transfer
+        self.record_transfer_read(from_addr, to_addr);
         self.speculative_ledger
-            .transfer_sequential_coins(from_addr, to_addr, amount)
+            .transfer_sequential_coins(from_addr, to_addr, amount)?;
+        self.record_transfer_write(from_addr, to_addr);
+        self.record_coin_transfer(
+            AddressHistoryEntryKind::SequentialTransfer,
+            from_addr,
+            to_addr,
+            amount,
+        );
+        Ok(())
     }
 
     /// Transfers parallel coins from one address to another.
@@ -599,8 +787,75 @@ impl ExecutionContext {
             }
         }
         // do the transfer
+        self.record_transfer_read(from_addr, to_addr);
         self.speculative_ledger
-            .transfer_parallel_coins(from_addr, to_addr, amount)
+            .transfer_parallel_coins(from_addr, to_addr, amount)?;
+        self.record_transfer_write(from_addr, to_addr);
+        self.record_coin_transfer(
+            AddressHistoryEntryKind::ParallelTransfer,
+            from_addr,
+            to_addr,
+            amount,
+        );
+        Ok(())
+    }
+
+    /// Records that the ledger entries involved in a transfer were read
+    fn record_transfer_read(&self, from_addr: Option<Address>, to_addr: Option<Address>) {
+        for addr in from_addr.into_iter().chain(to_addr) {
+            self.record_read(StateKey::Ledger(addr));
+        }
+    }
+
+    /// Records that the ledger entries involved in a transfer were written
+    fn record_transfer_write(&self, from_addr: Option<Address>, to_addr: Option<Address>) {
+        for addr in from_addr.into_iter().chain(to_addr) {
+            self.record_write(StateKey::Ledger(addr));
+        }
+    }
+
+    /// Records an entry in the address history of the addresses involved, if the history is enabled.
+    ///
+    /// # Arguments
+    /// * `kind`: kind of the entry
+    /// * `from`: sender of the operation, or debited address of the transfer
+    /// * `to`: recipient or target of the operation, or credited address of the transfer
+    /// * `amount`: amount of coins transferred, or fee paid for operations
+    pub fn record_address_history(
+        &mut self,
+        kind: AddressHistoryEntryKind,
+        from: Option<Address>,
+        to: Option<Address>,
+        amount: Amount,
+    ) {
+        if !self.config.address_history_enabled || self.read_only {
+            return;
+        }
+        self.address_history.push(AddressHistoryEntry {
+            slot: self.slot,
+            index_in_slot: self.address_history.len() as u64,
+            kind,
+            operation_id: self.origin_operation_id,
+            from,
+            to,
+            amount,
+            is_final: false,
+        });
+    }
+
+    /// Records a successful coin transfer in the address history.
+    /// Only credits to an address are recorded: coin destructions (fees, roll purchases, coins locked in messages)
+    /// are visible through the corresponding operation entries.
+    fn record_coin_transfer(
+        &mut self,
+        kind: AddressHistoryEntryKind,
+        from_addr: Option<Address>,
+        to_addr: Option<Address>,
+        amount: Amount,
+    ) {
+        if to_addr.is_some() && !amount.is_zero() {
+            self.record_address_history(kind, from_addr, to_addr, amount);
+        }
     }
 
     /// Add a new asynchronous message to speculative pool
@@ -608,6 +863,9 @@ impl ExecutionContext {
     /// # Arguments
     /// * `msg`: asynchronous message to add
     pub fn push_new_message(&mut self, msg: AsyncMessage) {
+        // the emission index of the message depends on the messages emitted before
+        self.record_read(StateKey::AsyncPool);
+        self.record_write(StateKey::AsyncPool);
         self.speculative_async_pool.push_new_message(msg);
     }
 
@@ -624,6 +882,105 @@ impl ExecutionContext {
         }
     }
 
+    /// Cancels a pending asynchronous message on behalf of its sender,
+    /// removing it from the speculative pool and reimbursing `msg.coins` to the sender.
+    ///
+    /// # Arguments
+    /// * `sender_addr`: address requesting the cancellation, must be the sender of the message
+    /// * `emission_slot`: slot at which the message was emitted
+    /// * `emission_index`: index of the message among the ones emitted at `emission_slot`
+    pub fn cancel_async_message_from_sender(
+        &mut self,
+        sender_addr: &Address,
+        emission_slot: &Slot,
+        emission_index: u64,
+    ) -> Result<(), ExecutionError> {
+        self.record_read(StateKey::AsyncPool);
+        let (msg_id, msg) = self
+            .speculative_async_pool
+            .find_message(emission_slot, emission_index)
+            .ok_or_else(|| {
+                ExecutionError::AsyncMessageCancelError(format!(
+                    "no pending message emitted at slot {} with index {}",
+                    emission_slot, emission_index
+                ))
+            })?;
+        if msg.sender != *sender_addr {
+            return Err(ExecutionError::AsyncMessageCancelError(format!(
+                "address {} is not the sender of the message emitted at slot {} with index {}",
+                sender_addr, emission_slot, emission_index
+            )));
+        }
+        self.record_write(StateKey::AsyncPool);
+        self.speculative_async_pool.cancel_message(&msg_id);
+        self.cancel_async_message(&msg);
+        self.record_async_message_outcome(
+            &msg,
+            AsyncMessageStatus::Evicted(AsyncMessageEvictionReason::Cancelled),
+            0,
+        );
+        Ok(())
+    }
+
+    /// Records the outcome of an asynchronous message that left the pool at the current slot,
+    /// and emits the corresponding outcome event.
+    /// Messages that were not executed successfully have their coins reimbursed to the sender.
+    ///
+    /// # Arguments
+    /// * `msg`: the asynchronous message
+    /// * `status`: what happened to the message
+    /// * `gas_used`: gas consumed by the execution of the message
+    pub fn record_async_message_outcome(
+        &mut self,
+        msg: &AsyncMessage,
+        status: AsyncMessageStatus,
+        gas_used: u64,
+    ) {
+        let refund = match status {
+            AsyncMessageStatus::Executed => Amount::zero(),
+            _ => msg.coins,
+        };
+        let structured = StructuredEvent::with_fields(
+            ASYNC_MESSAGE_OUTCOME_EVENT_TOPIC,
+            vec![
+                (
+                    "emission_slot".to_string(),
+                    EventValue::Slot(msg.emission_slot),
+                ),
+                (
+                    "emission_index".to_string(),
+                    EventValue::U64(msg.emission_index),
+                ),
+                ("gas_price".to_string(), EventValue::Amount(msg.gas_price)),
+                ("status".to_string(), EventValue::String(status.to_string())),
+                ("gas_used".to_string(), EventValue::U64(gas_used)),
+                ("refund".to_string(), EventValue::Amount(refund)),
+            ],
+        );
+        let outcome = AsyncMessageOutcomeEvent {
+            emission_slot: msg.emission_slot,
+            emission_index: msg.emission_index,
+            gas_price: msg.gas_price,
+            status: status.clone(),
+            gas_used,
+            refund,
+        };
+        let mut event = self.event_create(
+            serde_json::json!({ ASYNC_MESSAGE_OUTCOME_EVENT_KEY: outcome }).to_string(),
+        );
+        // the event is attributed to the message sender and destination, whatever the current call stack is
+        event.context.call_stack = [msg.sender, msg.destination].into_iter().collect();
+        event.context.origin_async_message = Some(AsyncMessageOrigin {
+            emission_slot: msg.emission_slot,
+            emission_index: msg.emission_index,
+        });
+        event.structured = Some(structured);
+        self.event_emit(event);
+
+        let info = msg.info(status, Some(self.slot), false);
+        self.async_message_outcomes.push(info);
+    }
+
     /// Add `roll_count` rolls to the buyer address.
     /// Validity checks must be performed _outside_ of this function.
     ///
@@ -631,6 +988,8 @@ impl ExecutionContext {
     /// * `buyer_addr`: address that will receive the rolls
     /// * `roll_count`: number of rolls it will receive
     pub fn add_rolls(&mut self, buyer_addr: &Address, roll_count: u64) {
+        self.record_read(StateKey::Rolls(*buyer_addr));
+        self.record_write(StateKey::Rolls(*buyer_addr));
         self.speculative_roll_state
             .add_rolls(buyer_addr, roll_count);
     }
@@ -645,6 +1004,8 @@ impl ExecutionContext {
         seller_addr: &Address,
         roll_count: u64,
     ) -> Result<(), ExecutionError> {
+        self.record_read(StateKey::Rolls(*seller_addr));
+        self.record_write(StateKey::Rolls(*seller_addr));
         self.speculative_roll_state.try_sell_rolls(
             seller_addr,
             self.slot,
@@ -702,6 +1063,13 @@ impl ExecutionContext {
         let deleted_messages = self.speculative_async_pool.settle_slot(&slot);
         for (_msg_id, msg) in deleted_messages {
             self.cancel_async_message(&msg);
+            // messages are only deleted at settlement if they expired or if the pool overflowed
+            let reason = if slot >= msg.validity_end {
+                AsyncMessageEvictionReason::Expired
+            } else {
+                AsyncMessageEvictionReason::LowPriority
+            };
+            self.record_async_message_outcome(&msg, AsyncMessageStatus::Evicted(reason), 0);
         }
 
         // execute the deferred credits coming from roll sells
@@ -733,6 +1101,9 @@ impl ExecutionContext {
             block_id: std::mem::take(&mut self.opt_block_id),
             state_changes,
             events: std::mem::take(&mut self.events),
+            async_message_outcomes: std::mem::take(&mut self.async_message_outcomes),
+            address_history: std::mem::take(&mut self.address_history),
+            block_receipt: None,
         }
     }
 
@@ -755,8 +1126,108 @@ impl ExecutionContext {
             )));
         }
 
-        // set data entry
-        self.speculative_ledger.set_bytecode(address, bytecode)
+        // before the activation of bytecode metadata, the bytecode is changed without any check
+        self.record_read(StateKey::Ledger(*address));
+        self.record_write(StateKey::Ledger(*address));
+        if !self.bytecode_metadata_active() {
+            return self.speculative_ledger.set_bytecode(address, bytecode);
+        }
+
+        // check that the bytecode can still be changed
+        let metadata = self
+            .get_bytecode_metadata(address)
+            .unwrap_or_else(|| BytecodeMetadata::genesis(&[]));
+        if metadata.immutable {
+            return Err(ExecutionError::RuntimeError(format!(
+                "the bytecode of address {} is immutable",
+                address
+            )));
+        }
+
+        // set the bytecode and its metadata
+        let metadata = metadata.next(&bytecode, self.slot);
+        self.speculative_ledger.set_bytecode(address, bytecode)?;
+        self.set_bytecode_metadata(address, &metadata)?;
+        self.emit_bytecode_change_event(address, &metadata);
+        Ok(())
+    }
+
+    /// Whether the metadata of bytecodes is recorded at the slot of this context
+    fn bytecode_metadata_active(&self) -> bool {
+        self.slot.period >= self.config.bytecode_metadata_start_period
+    }
+
+    /// Gets the metadata of the bytecode of an address, or None if the address does not exist
+    pub fn get_bytecode_metadata(&self, address: &Address) -> Option<BytecodeMetadata> {
+        let bytecode = self.get_bytecode(address)?;
+        let entry = self
+            .speculative_ledger
+            .get_data_entry(address, BYTECODE_METADATA_KEY);
+        Some(BytecodeMetadata::from_datastore(
+            entry.as_deref(),
+            &bytecode,
+            &BytecodeMetadataDeserializer::new(self.config.thread_count),
+        ))
+    }
+
+    /// Forbids any future change of the bytecode of an address.
+    /// Does nothing if the bytecode is already immutable.
+    fn make_bytecode_immutable(&mut self, address: &Address) -> Result<(), ExecutionError> {
+        self.record_read(StateKey::Ledger(*address));
+        self.record_write(StateKey::Ledger(*address));
+        let mut metadata = self.get_bytecode_metadata(address).ok_or_else(|| {
+            ExecutionError::RuntimeError(format!(
+                "could not make the bytecode of address {} immutable: address not found",
+                address
+            ))
+        })?;
+        if metadata.immutable {
+            return Ok(());
+        }
+        metadata.immutable = true;
+        self.set_bytecode_metadata(address, &metadata)?;
+        self.event_emit_structured(StructuredEvent::with_fields(
+            BYTECODE_LOCK_EVENT_TOPIC,
+            vec![
+                ("address".to_string(), EventValue::Address(*address)),
+                (
+                    "code_hash".to_string(),
+                    EventValue::String(metadata.code_hash.to_string()),
+                ),
+            ],
+        ));
+        Ok(())
+    }
+
+    /// Records the metadata of the bytecode of an address in its datastore
+    fn set_bytecode_metadata(
+        &mut self,
+        address: &Address,
+        metadata: &BytecodeMetadata,
+    ) -> Result<(), ExecutionError> {
+        let data = metadata.to_datastore().map_err(|err| {
+            ExecutionError::RuntimeError(format!(
+                "could not serialize the bytecode metadata of address {}: {}",
+                address, err
+            ))
+        })?;
+        self.speculative_ledger
+            .set_data_entry(address, BYTECODE_METADATA_KEY.to_vec(), data)
+    }
+
+    /// Emits an event describing a change of the bytecode of an address
+    fn emit_bytecode_change_event(&mut self, address: &Address, metadata: &BytecodeMetadata) {
+        self.event_emit_structured(StructuredEvent::with_fields(
+            BYTECODE_CHANGE_EVENT_TOPIC,
+            vec![
+                ("address".to_string(), EventValue::Address(*address)),
+                (
+                    "code_hash".to_string(),
+                    EventValue::String(metadata.code_hash.to_string()),
+                ),
+                ("version".to_string(), EventValue::U64(metadata.version)),
+            ],
+        ));
     }
 
     /// Creates a new event but does not emit it.
@@ -773,11 +1244,28 @@ impl ExecutionContext {
             read_only: self.read_only,
             index_in_slot: self.created_event_index,
             origin_operation_id: self.origin_operation_id,
+            origin_async_message: self.origin_async_message,
             is_final: false,
         };
 
         // Return the event
-        SCOutputEvent { context, data }
+        SCOutputEvent {
+            context,
+            data,
+            structured: None,
+        }
+    }
+
+    /// Creates and emits a structured event generated by the node itself.
+    /// The JSON representation of the structured event is used as the event data.
+    ///
+    /// # Arguments:
+    /// structured: the typed content of the event
+    pub fn event_emit_structured(&mut self, structured: StructuredEvent) {
+        let data = serde_json::to_string(&structured).unwrap_or_default();
+        let mut event = self.event_create(data);
+        event.structured = Some(structured);
+        self.event_emit(event);
     }
 
     /// Emits a previously created event.
@@ -793,8 +1281,16 @@ impl ExecutionContext {
         self.events.push(event);
     }
 
+    /// Draws a value from the unsafe random number generator
+    pub fn unsafe_random<T, D: Distribution<T>>(&mut self, distr: D) -> T {
+        self.record_read(StateKey::UnsafeRng);
+        self.record_write(StateKey::UnsafeRng);
+        self.unsafe_rng.sample(distr)
+    }
+
     /// Check if an operation was previously executed (to prevent reuse)
     pub fn is_op_executed(&self, op_id: &OperationId) -> bool {
+        self.record_read(StateKey::ExecutedOp(*op_id));
         self.speculative_executed_ops.is_op_executed(op_id)
     }
 
@@ -805,6 +1301,7 @@ impl ExecutionContext {
     /// * `op_id`: operation ID
     /// * `op_valid_until_slot`: slot until which the operation remains valid (included)
     pub fn insert_executed_op(&mut self, op_id: OperationId, op_valid_until_slot: Slot) {
+        self.record_write(StateKey::ExecutedOp(op_id));
         self.speculative_executed_ops
             .insert_executed_op(op_id, op_valid_until_slot)
     }
@@ -815,6 +1312,7 @@ impl ExecutionContext {
         address: &Address,
         periods_per_cycle: u64,
     ) -> Vec<ExecutionAddressCycleInfo> {
+        self.record_read(StateKey::Rolls(*address));
         self.speculative_roll_state
             .get_address_cycle_infos(address, periods_per_cycle, self.slot)
     }
@@ -829,6 +1327,7 @@ impl ExecutionContext {
             .slot
             .get_next_slot(thread_count)
             .expect("unexpected slot overflow in context.get_addresses_deferred_credits");
+        self.record_read(StateKey::Rolls(*address));
         self.speculative_roll_state
             .get_address_deferred_credits(address, min_slot)
     }
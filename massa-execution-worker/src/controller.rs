@@ -7,9 +7,13 @@ use crate::execution::ExecutionState;
 use crate::request_queue::{RequestQueue, RequestWithResponseSender};
 use massa_execution_exports::{
     ExecutionAddressInfo, ExecutionConfig, ExecutionController, ExecutionError, ExecutionManager,
-    ExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionBatch, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+};
+use massa_hash::Hash;
+use massa_ledger_exports::{LedgerProofs, LedgerSubEntry};
+use massa_models::api::{
+    AddressHistoryEntry, AsyncMessageFilter, AsyncMessageInfo, BlockExecutionReceipt, EventFilter,
 };
-use massa_models::api::EventFilter;
 use massa_models::output_event::SCOutputEvent;
 use massa_models::prehash::PreHashSet;
 use massa_models::stats::ExecutionStats;
@@ -31,7 +35,8 @@ pub(crate) struct ExecutionInputData {
     /// new blockclique (if there is a new one), blocks indexed by slot
     pub new_blockclique: Option<HashMap<Slot, (BlockId, Storage)>>,
     /// queue for read-only execution requests and response MPSCs to send back their outputs
-    pub readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ExecutionOutput>,
+    pub readonly_requests:
+        RequestQueue<ReadOnlyExecutionBatch, Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>>,
 }
 
 impl Display for ExecutionInputData {
@@ -116,6 +121,29 @@ impl ExecutionController for ExecutionControllerImpl {
             .get_filtered_sc_output_event(filter)
     }
 
+    /// Get asynchronous messages and their status, optionally filtered by:
+    /// * sender address
+    /// * destination address
+    /// * emission slot
+    /// * emission index
+    fn get_filtered_async_messages(&self, filter: AsyncMessageFilter) -> Vec<AsyncMessageInfo> {
+        self.execution_state
+            .read()
+            .get_filtered_async_messages(filter)
+    }
+
+    /// Get a page of the history of operations and coin transfers involving an address, most recent first
+    fn get_address_history(
+        &self,
+        address: &Address,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<AddressHistoryEntry> {
+        self.execution_state
+            .read()
+            .get_address_history(address, offset, limit)
+    }
+
     /// Get a copy of a single datastore entry with its final and active values
     ///
     /// # Return value
@@ -132,6 +160,29 @@ impl ExecutionController for ExecutionControllerImpl {
         result
     }
 
+    /// Get the receipt of the execution of a block, if it was executed recently
+    fn get_block_execution_receipt(&self, block_id: &BlockId) -> Option<BlockExecutionReceipt> {
+        self.execution_state
+            .read()
+            .get_block_execution_receipt(block_id)
+    }
+
+    /// Get proofs of the final values of ledger sub-entries against the final ledger root
+    ///
+    /// # Return value
+    /// * the final slot, the ledger root at that slot and one proof per queried sub-entry
+    fn get_final_ledger_proofs(&self, input: Vec<(Address, LedgerSubEntry)>) -> LedgerProofs {
+        self.execution_state.read().get_final_ledger_proofs(&input)
+    }
+
+    /// Get the root of the final ledger at the output of a recent final slot
+    ///
+    /// # Return value
+    /// * `None` if the slot is not final yet or too old to be remembered
+    fn get_final_ledger_root(&self, slot: Slot) -> Option<Hash> {
+        self.execution_state.read().get_final_ledger_root(slot)
+    }
+
     /// Get the final and candidate values of sequential balances.
     ///
     /// # Return value
@@ -153,12 +204,35 @@ impl ExecutionController for ExecutionControllerImpl {
         self.execution_state.read().get_cycle_active_rolls(cycle)
     }
 
+    /// Return the roll counts at the end of a final cycle
+    fn get_final_cycle_rolls(&self, cycle: u64) -> Option<BTreeMap<Address, u64>> {
+        self.execution_state.read().get_final_cycle_rolls(cycle)
+    }
+
     /// Executes a read-only request
     /// Read-only requests do not modify consensus state
     fn execute_readonly_request(
         &self,
         req: ReadOnlyExecutionRequest,
-    ) -> Result<ExecutionOutput, ExecutionError> {
+    ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
+        self.execute_readonly_batch(ReadOnlyExecutionBatch {
+            requests: vec![req],
+            share_state: false,
+        })?
+        .pop()
+        .unwrap_or_else(|| {
+            Err(ExecutionError::ChannelError(
+                "missing readonly execution response".into(),
+            ))
+        })
+    }
+
+    /// Executes a batch of read-only requests against the same state
+    /// Read-only requests do not modify consensus state
+    fn execute_readonly_batch(
+        &self,
+        batch: ReadOnlyExecutionBatch,
+    ) -> Result<Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>, ExecutionError> {
         let resp_rx = {
             let mut input_data = self.input_data.1.lock();
 
@@ -170,13 +244,14 @@ impl ExecutionController for ExecutionControllerImpl {
             }
 
             // prepare the channel to send back the result of the read-only execution
-            let (resp_tx, resp_rx) =
-                std::sync::mpsc::channel::<Result<ExecutionOutput, ExecutionError>>();
+            let (resp_tx, resp_rx) = std::sync::mpsc::channel::<
+                Result<Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>, ExecutionError>,
+            >();
 
-            // append the request to the queue of input read-only requests
+            // append the batch to the queue of input read-only requests
             input_data
                 .readonly_requests
-                .push(RequestWithResponseSender::new(req, resp_tx));
+                .push(RequestWithResponseSender::new(batch, resp_tx));
 
             // wake up the execution main loop
             self.input_data.0.notify_one();
@@ -218,6 +293,8 @@ impl ExecutionController for ExecutionControllerImpl {
                 exec_state.get_final_and_candidate_sequential_balance(addr);
             let (final_roll_count, candidate_roll_count) =
                 exec_state.get_final_and_candidate_rolls(addr);
+            let (final_bytecode_metadata, candidate_bytecode_metadata) =
+                exec_state.get_final_and_candidate_bytecode_metadata(addr);
             res.push(ExecutionAddressInfo {
                 final_datastore_keys,
                 candidate_datastore_keys,
@@ -227,6 +304,8 @@ impl ExecutionController for ExecutionControllerImpl {
                 candidate_sequential_balance: candidate_sequential_balance.unwrap_or_default(),
                 final_roll_count,
                 candidate_roll_count,
+                final_bytecode_metadata,
+                candidate_bytecode_metadata,
                 future_deferred_credits: exec_state.get_address_future_deferred_credits(addr),
                 cycle_infos: exec_state.get_address_cycle_infos(addr),
             });
@@ -9,32 +9,49 @@
 //! * the output of the execution is extracted from the context
 
 use crate::active_history::{ActiveHistory, HistorySearchResult};
+use crate::address_history::FinalAddressHistory;
 use crate::context::ExecutionContext;
 use crate::interface_impl::InterfaceImpl;
+use crate::parallel_execution::{execute_operations_speculatively, StateKey};
 use crate::stats::ExecutionStatsCounter;
-use massa_async_pool::AsyncMessage;
+use massa_async_pool::{AsyncMessage, Change};
 use massa_execution_exports::{
-    EventStore, ExecutionConfig, ExecutionError, ExecutionOutput, ExecutionStackElement,
-    ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ErrorChain, EventStore, ExecutionConfig, ExecutionError, ExecutionOutput,
+    ExecutionStackElement, ReadOnlyExecutionOutput, ReadOnlyExecutionRequest,
+    ReadOnlyExecutionTarget,
+};
+use massa_final_state::{FinalState, StateChanges};
+use massa_hash::Hash;
+use massa_ledger_exports::{
+    LedgerChanges, LedgerProofs, LedgerSubEntry, SetOrDelete, SetUpdateOrDelete,
 };
-use massa_final_state::FinalState;
-use massa_ledger_exports::{SetOrDelete, SetUpdateOrDelete};
 use massa_models::address::ExecutionAddressCycleInfo;
-use massa_models::api::EventFilter;
-use massa_models::output_event::SCOutputEvent;
+use massa_models::api::{
+    AddressHistoryEntry, AddressHistoryEntryKind, AsyncMessageFilter, AsyncMessageInfo,
+    AsyncMessageStatus, BlockExecutionReceipt, EventFilter, OperationExecutionOutcome,
+    OperationReceipt,
+};
+use massa_models::execution::ReadOnlyStateOverride;
+use massa_models::multisig::MultisigAction;
+use massa_models::output_event::{
+    AsyncMessageOrigin, EventValue, SCOutputEvent, StructuredEvent, ROLL_BUY_EVENT_TOPIC,
+    ROLL_SELL_EVENT_TOPIC, TRANSFER_EVENT_TOPIC,
+};
 use massa_models::prehash::PreHashSet;
 use massa_models::stats::ExecutionStats;
 use massa_models::{
     address::Address,
     block::BlockId,
+    bytecode::{BytecodeMetadata, BytecodeMetadataDeserializer, BYTECODE_METADATA_KEY},
     operation::{OperationId, OperationType, WrappedOperation},
+    wrapped::Id,
 };
 use massa_models::{amount::Amount, slot::Slot};
 use massa_pos_exports::SelectorController;
 use massa_sc_runtime::Interface;
 use massa_storage::Storage;
 use parking_lot::{Mutex, RwLock};
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
 use std::{collections::HashMap, sync::Arc};
 use tracing::debug;
 
@@ -62,12 +79,20 @@ pub(crate) struct ExecutionState {
     pub final_cursor: Slot,
     // store containing execution events that became final
     final_events: EventStore,
+    // outcomes of the asynchronous messages that left the pool in final slots, oldest first
+    final_async_message_outcomes: VecDeque<AsyncMessageInfo>,
+    // final history of operations and coin transfers, per address, oldest first
+    final_address_history: FinalAddressHistory,
+    // receipts of the blocks executed in final slots, oldest first
+    final_block_receipts: VecDeque<BlockExecutionReceipt>,
     // final state with atomic R/W access
     final_state: Arc<RwLock<FinalState>>,
     // execution context (see documentation in context.rs)
     execution_context: Arc<Mutex<ExecutionContext>>,
     // execution interface allowing the VM runtime to access the Massa context
     execution_interface: Box<dyn Interface>,
+    // executor of the operations of blocks, sharing the execution context
+    operation_executor: OperationExecutor,
     // execution statistics
     stats_counter: ExecutionStatsCounter,
 }
@@ -102,15 +127,28 @@ impl ExecutionState {
             execution_context.clone(),
         ));
 
+        // Instantiate the executor of block operations, share the execution context with it
+        let operation_executor = OperationExecutor::new(config.clone(), execution_context.clone());
+
         // build the execution state
         ExecutionState {
             final_state,
             execution_context,
             execution_interface,
+            operation_executor,
             // empty execution output history: it is not recovered through bootstrap
             active_history,
             // empty final event store: it is not recovered through bootstrap
             final_events: Default::default(),
+            // empty final async message outcomes: they are not recovered through bootstrap
+            final_async_message_outcomes: Default::default(),
+            // empty final address history: it is not recovered through bootstrap
+            final_address_history: FinalAddressHistory::new(
+                config.max_final_address_history_entries,
+                config.max_address_history_length,
+            ),
+            // empty final block receipts: they are not recovered through bootstrap
+            final_block_receipts: Default::default(),
             // no active slots executed yet: set active_cursor to the last final block
             active_cursor: last_final_slot,
             final_cursor: last_final_slot,
@@ -151,6 +189,24 @@ impl ExecutionState {
             self.stats_counter
                 .register_final_executed_operations(exec_out.state_changes.executed_ops.len());
         }
+        if let Some(receipt) = &exec_out.block_receipt {
+            let (gas_used, max_gas, refunds) = receipt
+                .operations
+                .iter()
+                .filter(|op| !matches!(op.outcome, OperationExecutionOutcome::Skipped(_)))
+                .fold(
+                    (0u64, 0u64, Amount::default()),
+                    |(gas_used, max_gas, refunds), op| {
+                        (
+                            gas_used.saturating_add(op.gas),
+                            max_gas.saturating_add(op.max_gas),
+                            refunds.saturating_add(op.refund),
+                        )
+                    },
+                );
+            self.stats_counter
+                .register_final_gas_usage(gas_used, max_gas, refunds);
+        }
 
         // apply state changes to the final ledger
         self.final_state
@@ -170,6 +226,32 @@ impl ExecutionState {
         exec_out.events.finalize();
         self.final_events.extend(exec_out.events);
         self.final_events.prune(self.config.max_final_events);
+
+        // append async message outcomes to the final outcome history
+        for mut outcome in exec_out.async_message_outcomes {
+            outcome.is_final = true;
+            self.final_async_message_outcomes.push_back(outcome);
+        }
+        let excess_count = self
+            .final_async_message_outcomes
+            .len()
+            .saturating_sub(self.config.max_final_async_message_outcomes);
+        self.final_async_message_outcomes.drain(..excess_count);
+
+        // index final address history entries by involved address
+        for mut entry in exec_out.address_history {
+            entry.is_final = true;
+            self.final_address_history.push(entry);
+        }
+
+        // append the block receipt to the final receipts
+        if let Some(mut receipt) = exec_out.block_receipt {
+            receipt.is_final = true;
+            self.final_block_receipts.push_back(receipt);
+            if self.final_block_receipts.len() > self.config.max_final_block_receipts {
+                self.final_block_receipts.pop_front();
+            }
+        }
     }
 
     /// Applies an execution output to the active (non-final) state
@@ -255,21 +337,54 @@ impl ExecutionState {
             }
         }
     }
+}
 
-    /// Execute an operation in the context of a block.
-    /// Assumes the execution context was initialized at the beginning of the slot.
+/// Executes operations in an execution context.
+/// The execution context is either the one of the slot being executed,
+/// or a fork of it when operations are executed in parallel (see `parallel_execution.rs`).
+pub(crate) struct OperationExecutor {
+    // execution config
+    config: ExecutionConfig,
+    // execution context in which the operations are executed
+    execution_context: Arc<Mutex<ExecutionContext>>,
+    // execution interface allowing the VM runtime to access the execution context
+    execution_interface: Box<dyn Interface>,
+}
+
+impl OperationExecutor {
+    /// Creates an `OperationExecutor` executing operations in the given execution context
+    pub fn new(
+        config: ExecutionConfig,
+        execution_context: Arc<Mutex<ExecutionContext>>,
+    ) -> OperationExecutor {
+        let execution_interface = Box::new(InterfaceImpl::new(
+            config.clone(),
+            execution_context.clone(),
+        ));
+        OperationExecutor {
+            config,
+            execution_context,
+            execution_interface,
+        }
+    }
+
+    /// Checks that an operation can be included in a block,
+    /// independently of the state in which it is executed.
+    /// The max gas of the operation must fit in the remaining block gas,
+    /// even though only the gas it actually consumes is then counted in the block gas.
     ///
     /// # Arguments
-    /// * `operation`: operation to execute
+    /// * `operation`: operation to check
     /// * `block_slot`: slot of the block in which the op is included
-    /// * `remaining_block_gas`: mutable reference towards the remaining gas in the block
-    /// * `block_credits`: mutable reference towards the total block reward/fee credits
-    pub fn execute_operation(
+    /// * `remaining_block_gas`: remaining gas in the block
+    ///
+    /// # Returns
+    /// An error if the operation cannot be included in the block
+    pub fn check_operation_inclusion(
         &self,
         operation: &WrappedOperation,
         block_slot: Slot,
-        remaining_block_gas: &mut u64,
-        block_credits: &mut Amount,
+        remaining_block_gas: u64,
     ) -> Result<(), ExecutionError> {
         // check validity period
         if !(operation
@@ -279,33 +394,75 @@ impl ExecutionState {
             return Err(ExecutionError::InvalidSlotRange);
         }
 
+        // check that the operation type is already active
+        let start_period = match &operation.content.op {
+            OperationType::CancelAsyncMessage { .. } => {
+                self.config.async_message_cancellation_start_period
+            }
+            OperationType::MultisigExecute { .. } => self.config.multisig_start_period,
+            _ => 0,
+        };
+        if block_slot.period < start_period {
+            return Err(ExecutionError::InlcudeOperationError(format!(
+                "operation type not active before period {}",
+                start_period
+            )));
+        }
+
         // check remaining block gas
-        let op_gas = operation.get_gas_usage();
-        let new_remaining_block_gas = remaining_block_gas.checked_sub(op_gas).ok_or_else(|| {
-            ExecutionError::NotEnoughGas(
+        if operation.get_gas_usage() > remaining_block_gas {
+            return Err(ExecutionError::NotEnoughGas(
                 "not enough remaining block gas to execute operation".to_string(),
-            )
-        })?;
-
-        // get the operation's sender address
-        let sender_addr = operation.creator_address;
-
-        // get the thread to which the operation belongs
-        let op_thread = sender_addr.get_thread(self.config.thread_count);
+            ));
+        }
 
         // check block/op thread compatibility
+        let op_thread = operation
+            .creator_address
+            .get_thread(self.config.thread_count);
         if op_thread != block_slot.thread {
             return Err(ExecutionError::InlcudeOperationError(
                 "operation vs block thread mismatch".to_string(),
             ));
         }
 
+        Ok(())
+    }
+
+    /// Execute an operation in the context of a block.
+    /// Assumes the execution context was initialized at the beginning of the slot.
+    ///
+    /// # Arguments
+    /// * `operation`: operation to execute
+    /// * `block_slot`: slot of the block in which the op is included
+    /// * `remaining_block_gas`: mutable reference towards the remaining gas in the block
+    /// * `block_credits`: mutable reference towards the total block reward/fee credits
+    ///
+    /// # Returns
+    /// The receipt of the execution, or an error if the operation could not be executed and paid no fee
+    pub fn execute_operation(
+        &self,
+        operation: &WrappedOperation,
+        block_slot: Slot,
+        remaining_block_gas: &mut u64,
+        block_credits: &mut Amount,
+    ) -> Result<OperationReceipt, ExecutionError> {
+        // check validity period, remaining block gas and block/op thread compatibility
+        self.check_operation_inclusion(operation, block_slot, *remaining_block_gas)?;
+
+        // get the operation's sender address
+        let sender_addr = operation.creator_address;
+
+        // get the thread to which the operation belongs
+        let op_thread = sender_addr.get_thread(self.config.thread_count);
+
         // get operation ID
         let operation_id = operation.id;
 
-        // compute fee from (op.max_gas * op.gas_price + op.fee)
+        // compute fee from (op.max_gas * op.gas_price + op.fee),
+        // the unused gas is refunded once the operation is executed
         let op_fees = operation.get_total_fee();
-        let new_block_credits = block_credits.saturating_add(op_fees);
+        let max_gas = operation.get_gas_usage();
 
         let context_snapshot;
         {
@@ -338,6 +495,27 @@ impl ExecutionState {
                 Slot::new(operation.content.expire_period, op_thread),
             );
 
+            // set the context origin operation ID
+            context.origin_operation_id = Some(operation_id);
+
+            // record the operation in the history of its sender and of its recipient or target, if any
+            let op_target = match &operation.content.op {
+                OperationType::Transaction {
+                    recipient_address, ..
+                } => Some(*recipient_address),
+                OperationType::CallSC { target_addr, .. } => Some(*target_addr),
+                OperationType::MultisigExecute { proposal, .. } => {
+                    Some(proposal.descriptor.address())
+                }
+                _ => None,
+            };
+            context.record_address_history(
+                AddressHistoryEntryKind::Operation,
+                Some(sender_addr),
+                op_target,
+                op_fees,
+            );
+
             // save a snapshot of the context to revert any further changes on error
             context_snapshot = context.get_snapshot();
 
@@ -347,19 +525,11 @@ impl ExecutionState {
             // set the context max gas to match the one defined in the operation
             context.max_gas = operation.get_gas_usage();
 
-            // set the context origin operation ID
-            context.origin_operation_id = Some(operation_id);
-
             // execution context lock dropped here because the op-specific execution functions below acquire it again
         }
 
-        // update block gas
-        *remaining_block_gas = new_remaining_block_gas;
-
-        // update block credits
-        *block_credits = new_block_credits;
-
         // Call the execution process specific to the operation type.
+        // Smart contract executions return the gas they consumed, the other operations consume no gas.
         let execution_result = match &operation.content.op {
             OperationType::ExecuteSC { .. } => {
                 self.execute_executesc_op(&operation.content.op, sender_addr)
@@ -367,37 +537,79 @@ impl ExecutionState {
             OperationType::CallSC { .. } => {
                 self.execute_callsc_op(&operation.content.op, sender_addr)
             }
-            OperationType::RollBuy { .. } => {
-                self.execute_roll_buy_op(&operation.content.op, sender_addr)
-            }
-            OperationType::RollSell { .. } => {
-                self.execute_roll_sell_op(&operation.content.op, sender_addr)
-            }
-            OperationType::Transaction { .. } => {
-                self.execute_transaction_op(&operation.content.op, sender_addr)
-            }
+            OperationType::RollBuy { .. } => self
+                .execute_roll_buy_op(&operation.content.op, sender_addr)
+                .map(|_| 0),
+            OperationType::RollSell { .. } => self
+                .execute_roll_sell_op(&operation.content.op, sender_addr)
+                .map(|_| 0),
+            OperationType::Transaction { .. } => self
+                .execute_transaction_op(&operation.content.op, sender_addr)
+                .map(|_| 0),
+            OperationType::CancelAsyncMessage { .. } => self
+                .execute_cancel_async_message_op(&operation.content.op, sender_addr)
+                .map(|_| 0),
+            OperationType::MultisigExecute { .. } => self
+                .execute_multisig_op(&operation.content.op, sender_addr)
+                .map(|_| 0),
         };
 
-        {
-            // lock execution context
-            let mut context = context_guard!(self);
+        // lock execution context
+        let mut context = context_guard!(self);
 
-            // check execution results
-            match execution_result {
-                Ok(_) => {}
-                Err(err) => {
-                    // an error occurred: emit error event and reset context to snapshot
-                    let err = ExecutionError::RuntimeError(format!(
-                        "runtime error when executing operation {}: {}",
-                        operation_id, &err
-                    ));
-                    debug!("{}", &err);
-                    context.reset_to_snapshot(context_snapshot, Some(err));
-                }
+        // check execution results
+        let (outcome, gas) = match execution_result {
+            Ok(gas_used) => (OperationExecutionOutcome::Success, gas_used),
+            Err(err) => {
+                // an error occurred: emit error event and reset context to snapshot.
+                // The gas consumed by a failed execution is unknown: all of it is charged.
+                let err = ExecutionError::RuntimeError(format!(
+                    "runtime error when executing operation {}: {}",
+                    operation_id, &err
+                ));
+                debug!("{}", &err);
+                let outcome = OperationExecutionOutcome::Failure(err.to_string());
+                context.reset_to_snapshot(context_snapshot, Some(err));
+                (outcome, max_gas)
+            }
+        };
+        // before the gas refund start period,
+        // operations pay for their max gas and use it up in the block gas
+        let gas = if block_slot.period >= self.config.gas_refund_start_period {
+            gas
+        } else {
+            max_gas
+        };
+
+        // refund the unused gas to the sequential balance of the sender
+        let mut refund = operation
+            .get_gas_price()
+            .saturating_mul_u64(max_gas.saturating_sub(gas));
+        if !refund.is_zero() {
+            if let Err(err) =
+                context.transfer_sequential_coins(None, Some(sender_addr), refund, false)
+            {
+                debug!(
+                    "failed to refund {} unused gas coins to operation sender {}: {}",
+                    refund, sender_addr, err
+                );
+                refund = Amount::default();
             }
         }
+        let fee = op_fees.saturating_sub(refund);
 
-        Ok(())
+        // only the consumed gas is counted in the block gas, and only the fee actually paid is credited
+        *remaining_block_gas = remaining_block_gas.saturating_sub(gas);
+        *block_credits = block_credits.saturating_add(fee);
+
+        Ok(OperationReceipt {
+            operation_id,
+            outcome,
+            gas,
+            max_gas,
+            fee,
+            refund,
+        })
     }
 
     /// Execute an operation of type `RollSell`
@@ -435,6 +647,16 @@ impl ExecutionState {
                 seller_addr, roll_count, err
             )));
         }
+
+        // emit a structured event describing the roll sale
+        context.event_emit_structured(StructuredEvent::with_fields(
+            ROLL_SELL_EVENT_TOPIC,
+            vec![
+                ("address".to_string(), EventValue::Address(seller_addr)),
+                ("roll_count".to_string(), EventValue::U64(*roll_count)),
+            ],
+        ));
+
         Ok(())
     }
 
@@ -490,6 +712,15 @@ impl ExecutionState {
         // add rolls to the buyer withing the context
         context.add_rolls(&buyer_addr, *roll_count);
 
+        // emit a structured event describing the roll purchase
+        context.event_emit_structured(StructuredEvent::with_fields(
+            ROLL_BUY_EVENT_TOPIC,
+            vec![
+                ("address".to_string(), EventValue::Address(buyer_addr)),
+                ("roll_count".to_string(), EventValue::U64(*roll_count)),
+            ],
+        ));
+
         Ok(())
     }
 
@@ -538,20 +769,156 @@ impl ExecutionState {
             )));
         }
 
+        // emit a structured event describing the transfer
+        context.event_emit_structured(StructuredEvent::with_fields(
+            TRANSFER_EVENT_TOPIC,
+            vec![
+                ("from".to_string(), EventValue::Address(sender_addr)),
+                ("to".to_string(), EventValue::Address(*recipient_address)),
+                ("amount".to_string(), EventValue::Amount(*amount)),
+            ],
+        ));
+
         Ok(())
     }
 
+    /// Execute an operation of type `CancelAsyncMessage`
+    /// Will panic if called with another operation type
+    ///
+    /// # Arguments
+    /// * `operation`: the `WrappedOperation` to process, must be a `CancelAsyncMessage`
+    /// * `sender_addr`: address of the sender
+    pub fn execute_cancel_async_message_op(
+        &self,
+        operation: &OperationType,
+        sender_addr: Address,
+    ) -> Result<(), ExecutionError> {
+        // process async message cancellation operations only
+        let (emission_slot, emission_index) = match operation {
+            OperationType::CancelAsyncMessage {
+                emission_slot,
+                emission_index,
+            } => (emission_slot, emission_index),
+            _ => panic!("unexpected operation type"),
+        };
+
+        // acquire write access to the context
+        let mut context = context_guard!(self);
+
+        // Set call stack
+        // This needs to be defined before anything can fail, so that the emitted event contains the right stack
+        context.stack = vec![ExecutionStackElement {
+            address: sender_addr,
+            coins: Default::default(),
+            owned_addresses: vec![sender_addr],
+        }];
+
+        // remove the message from the pool and reimburse its coins to the sender
+        context.cancel_async_message_from_sender(&sender_addr, emission_slot, *emission_index)
+    }
+
+    /// Execute an operation of type `MultisigExecute`
+    /// Will panic if called with another operation type
+    ///
+    /// # Arguments
+    /// * `operation`: the `WrappedOperation` to process, must be a `MultisigExecute`
+    /// * `sender_addr`: address of the sender, who only pays the fee
+    pub fn execute_multisig_op(
+        &self,
+        operation: &OperationType,
+        sender_addr: Address,
+    ) -> Result<(), ExecutionError> {
+        // process multisig operations only
+        let (proposal, signatures) = match operation {
+            OperationType::MultisigExecute {
+                proposal,
+                signatures,
+            } => (proposal, signatures),
+            _ => panic!("unexpected operation type"),
+        };
+
+        // Set call stack
+        // This needs to be defined before anything can fail, so that the emitted event contains the right stack
+        context_guard!(self).stack = vec![ExecutionStackElement {
+            address: sender_addr,
+            coins: Default::default(),
+            owned_addresses: vec![sender_addr],
+        }];
+
+        // the proposal must be executed within its own validity period, whatever the operation carrying it
+        let slot = context_guard!(self).slot;
+        if !proposal
+            .get_validity_range(self.config.operation_validity_period)
+            .contains(&slot.period)
+        {
+            return Err(ExecutionError::MultisigError(format!(
+                "proposal expiring at period {} cannot be executed at slot {}",
+                proposal.expire_period, slot
+            )));
+        }
+
+        // signatures are checked at reception, check them again to never trust the block producer
+        if let Err(err) = proposal.verify(signatures) {
+            return Err(ExecutionError::MultisigError(format!(
+                "invalid multisig signatures: {}",
+                err
+            )));
+        }
+
+        // the multisig ledger entry must be in the thread of the block
+        let multisig_addr = proposal.descriptor.address();
+        let multisig_thread = multisig_addr.get_thread(self.config.thread_count);
+        if multisig_thread != sender_addr.get_thread(self.config.thread_count) {
+            return Err(ExecutionError::MultisigError(format!(
+                "multisig address {} and sender {} are in different threads",
+                multisig_addr, sender_addr
+            )));
+        }
+
+        // the proposal hash is registered as an executed operation to prevent replays
+        let proposal_id = match proposal.compute_hash() {
+            Ok(hash) => OperationId::new(hash),
+            Err(err) => return Err(ExecutionError::MultisigError(err.to_string())),
+        };
+        {
+            let mut context = context_guard!(self);
+            if context.is_op_executed(&proposal_id) {
+                return Err(ExecutionError::MultisigError(format!(
+                    "proposal {} was executed previously",
+                    proposal_id
+                )));
+            }
+            context.insert_executed_op(
+                proposal_id,
+                Slot::new(proposal.expire_period, multisig_thread),
+            );
+        }
+
+        // execute the action as if the multisig address had sent it
+        let action = proposal.action.to_operation_type();
+        match &proposal.action {
+            MultisigAction::Transaction { .. } => {
+                self.execute_transaction_op(&action, multisig_addr)
+            }
+            MultisigAction::RollBuy { .. } => self.execute_roll_buy_op(&action, multisig_addr),
+            MultisigAction::RollSell { .. } => self.execute_roll_sell_op(&action, multisig_addr),
+        }
+    }
+
     /// Execute an operation of type `ExecuteSC`
     /// Will panic if called with another operation type
     ///
     /// # Arguments
     /// * `operation`: the `WrappedOperation` to process, must be an `ExecuteSC`
     /// * `sender_addr`: address of the sender
+    ///
+    /// # Returns
+    /// The gas consumed by the execution
     pub fn execute_executesc_op(
         &self,
         operation: &OperationType,
         sender_addr: Address,
-    ) -> Result<(), ExecutionError> {
+    ) -> Result<u64, ExecutionError> {
         // process ExecuteSC operations only
         let (bytecode, max_gas, coins) = match &operation {
             OperationType::ExecuteSC {
@@ -601,17 +968,15 @@ impl ExecutionState {
 
         // run the VM on the bytecode contained in the operation
         match massa_sc_runtime::run_main(bytecode, *max_gas, &*self.execution_interface) {
-            Ok(_reamining_gas) => {}
+            Ok(remaining_gas) => Ok(max_gas.saturating_sub(remaining_gas)),
             Err(err) => {
                 // there was an error during bytecode execution
-                return Err(ExecutionError::RuntimeError(format!(
+                Err(ExecutionError::RuntimeError(format!(
                     "bytecode execution error: {}",
                     err
-                )));
+                )))
             }
         }
-
-        Ok(())
     }
 
     /// Execute an operation of type `CallSC`
@@ -622,11 +987,14 @@ impl ExecutionState {
     /// * `block_creator_addr`: address of the block creator
     /// * `operation_id`: ID of the operation
     /// * `sender_addr`: address of the sender
+    ///
+    /// # Returns
+    /// The gas consumed by the execution
     pub fn execute_callsc_op(
         &self,
         operation: &OperationType,
         sender_addr: Address,
-    ) -> Result<(), ExecutionError> {
+    ) -> Result<u64, ExecutionError> {
         // process CallSC operations only
         let (max_gas, target_addr, target_func, param, parallel_coins, sequential_coins) =
             match &operation {
@@ -716,7 +1084,7 @@ impl ExecutionState {
 
             // quit if there is no function to be called
             if target_func.is_empty() {
-                return Ok(());
+                return Ok(0);
             }
 
             // Load bytecode. Assume empty bytecode if not found.
@@ -731,19 +1099,19 @@ impl ExecutionState {
             param,
             &*self.execution_interface,
         ) {
-            Ok(_reamining_gas) => {}
+            Ok(remaining_gas) => Ok(max_gas.saturating_sub(remaining_gas)),
             Err(err) => {
                 // there was an error during bytecode execution
-                return Err(ExecutionError::RuntimeError(format!(
+                Err(ExecutionError::RuntimeError(format!(
                     "bytecode execution error: {}",
                     err
-                )));
+                )))
             }
         }
-
-        Ok(())
     }
+}
 
+impl ExecutionState {
     /// Tries to execute an asynchronous message
     /// If the execution failed reimburse the message sender.
     ///
@@ -762,6 +1130,10 @@ impl ExecutionState {
             context_snapshot = context.get_snapshot();
             context.max_gas = message.max_gas;
             context.gas_price = message.gas_price;
+            context.origin_async_message = Some(AsyncMessageOrigin {
+                emission_slot: message.emission_slot,
+                emission_index: message.emission_index,
+            });
             context.stack = vec![
                 ExecutionStackElement {
                     address: message.sender,
@@ -789,6 +1161,12 @@ impl ExecutionState {
                     };
                     context.reset_to_snapshot(context_snapshot, Some(err.clone()));
                     context.cancel_async_message(&message);
+                    context.record_async_message_outcome(
+                        &message,
+                        AsyncMessageStatus::ExecutionFailed(err.to_string()),
+                        0,
+                    );
+                    context.origin_async_message = None;
                     return Err(err);
                 }
             };
@@ -807,6 +1185,12 @@ impl ExecutionState {
                 ));
                 context.reset_to_snapshot(context_snapshot, Some(err.clone()));
                 context.cancel_async_message(&message);
+                context.record_async_message_outcome(
+                    &message,
+                    AsyncMessageStatus::ExecutionFailed(err.to_string()),
+                    0,
+                );
+                context.origin_async_message = None;
                 return Err(err);
             }
 
@@ -814,25 +1198,43 @@ impl ExecutionState {
         };
 
         // run the target function
-        if let Err(err) = massa_sc_runtime::run_function(
+        let run_result = massa_sc_runtime::run_function(
             &bytecode,
             message.max_gas,
             &message.handler,
             data,
             &*self.execution_interface,
-        ) {
-            // execution failed: reset context to snapshot and reimburse sender
-            let err = ExecutionError::RuntimeError(format!(
-                "async message runtime execution error: {}",
-                err
-            ));
-            let mut context = context_guard!(self);
-            context.reset_to_snapshot(context_snapshot, Some(err.clone()));
-            context.cancel_async_message(&message);
-            Err(err)
-        } else {
-            Ok(())
-        }
+        );
+
+        let mut context = context_guard!(self);
+        let result = match run_result {
+            Ok(remaining_gas) => {
+                context.record_async_message_outcome(
+                    &message,
+                    AsyncMessageStatus::Executed,
+                    message.max_gas.saturating_sub(remaining_gas),
+                );
+                Ok(())
+            }
+            Err(err) => {
+                // execution failed: reset context to snapshot and reimburse sender
+                let err = ExecutionError::RuntimeError(format!(
+                    "async message runtime execution error: {}",
+                    err
+                ));
+                context.reset_to_snapshot(context_snapshot, Some(err.clone()));
+                context.cancel_async_message(&message);
+                // the runtime does not report the gas consumed by failed executions: consider all of it used
+                context.record_async_message_outcome(
+                    &message,
+                    AsyncMessageStatus::ExecutionFailed(err.to_string()),
+                    message.max_gas,
+                );
+                Err(err)
+            }
+        };
+        context.origin_async_message = None;
+        result
     }
 
     /// Executes a full slot (with or without a block inside) without causing any changes to the state,
@@ -876,6 +1278,7 @@ impl ExecutionState {
         }
 
         // Check if there is a block at this slot
+        let mut block_receipt = None;
         if let Some((block_id, block_store)) = opt_block {
             // Retrieve the block from storage
             let stored_block = block_store
@@ -932,21 +1335,52 @@ impl ExecutionState {
 
             // Try executing the operations of this block in the order in which they appear in the block.
             // Errors are logged but do not interrupt the execution of the slot.
-            for (op_index, operation) in operations.into_iter().enumerate() {
-                match self.execute_operation(
-                    &operation,
-                    stored_block.content.header.content.slot,
+            let block_slot = stored_block.content.header.content.slot;
+            let results = if self.config.parallel_execution_threads > 1 && operations.len() > 1 {
+                self.execute_operations_in_parallel(
+                    &operations,
+                    block_slot,
                     &mut remaining_block_gas,
                     &mut block_credits,
-                ) {
-                    Err(ExecutionError::NotEnoughGas(_))
-                    | Err(ExecutionError::InvalidSlotRange) => debug!("Ignoring operation"),
-                    Err(err) => debug!(
-                        "failed executing operation index {} in block {}: {}",
-                        op_index, block_id, err
-                    ),
-                    _ => {}
-                }
+                )
+            } else {
+                operations
+                    .iter()
+                    .map(|operation| {
+                        self.operation_executor.execute_operation(
+                            operation,
+                            block_slot,
+                            &mut remaining_block_gas,
+                            &mut block_credits,
+                        )
+                    })
+                    .collect()
+            };
+            let mut operation_receipts = Vec::with_capacity(operations.len());
+            for (op_index, (operation, result)) in operations.into_iter().zip(results).enumerate() {
+                let receipt = match result {
+                    Ok(receipt) => receipt,
+                    Err(err) => {
+                        match err {
+                            ExecutionError::NotEnoughGas(_) | ExecutionError::InvalidSlotRange => {
+                                debug!("Ignoring operation")
+                            }
+                            _ => debug!(
+                                "failed executing operation index {} in block {}: {}",
+                                op_index, block_id, err
+                            ),
+                        }
+                        OperationReceipt {
+                            operation_id: operation.id,
+                            outcome: OperationExecutionOutcome::Skipped(err.to_string()),
+                            gas: 0,
+                            max_gas: operation.get_gas_usage(),
+                            fee: Amount::default(),
+                            refund: Amount::default(),
+                        }
+                    }
+                };
+                operation_receipts.push(receipt);
             }
 
             // Get block creator address
@@ -958,11 +1392,16 @@ impl ExecutionState {
             // Update speculative rolls state production stats
             context.update_production_stats(&block_creator_addr, slot, Some(block_id));
 
+            // block rewards are not caused by any operation
+            context.origin_operation_id = None;
+
             // Credit endorsement producers and endorsed block producers
             let mut remaining_credit = block_credits;
             let block_credit_part = block_credits
                 .checked_div_u64(3 * (1 + (self.config.endorsement_count)))
                 .expect("critical: block_credits checked_div factor is 0");
+            let mut endorser_credits = Vec::with_capacity(endorsement_creators.len());
+            let mut endorsement_target_credits = Vec::with_capacity(endorsement_creators.len());
             for (endorsement_creator, endorsement_target_creator) in endorsement_creators
                 .iter()
                 .zip(endorsement_target_creators.into_iter())
@@ -976,6 +1415,7 @@ impl ExecutionState {
                 ) {
                     Ok(_) => {
                         remaining_credit = remaining_credit.saturating_sub(block_credit_part);
+                        endorser_credits.push((*endorsement_creator, block_credit_part));
                     }
                     Err(err) => {
                         debug!(
@@ -994,6 +1434,8 @@ impl ExecutionState {
                 ) {
                     Ok(_) => {
                         remaining_credit = remaining_credit.saturating_sub(block_credit_part);
+                        endorsement_target_credits
+                            .push((endorsement_target_creator, block_credit_part));
                     }
                     Err(err) => {
                         debug!(
@@ -1005,17 +1447,37 @@ impl ExecutionState {
             }
 
             // Credit block creator with remaining_credit
-            if let Err(err) = context.transfer_sequential_coins(
+            let producer_credit = match context.transfer_sequential_coins(
                 None,
                 Some(block_creator_addr),
                 remaining_credit,
                 false,
             ) {
-                debug!(
-                    "failed to credit {} sequential coins to block creator {} on block execution: {}",
-                    remaining_credit, block_creator_addr, err
-                )
-            }
+                Ok(_) => remaining_credit,
+                Err(err) => {
+                    debug!(
+                        "failed to credit {} sequential coins to block creator {} on block execution: {}",
+                        remaining_credit, block_creator_addr, err
+                    );
+                    Amount::default()
+                }
+            };
+
+            block_receipt = Some(BlockExecutionReceipt {
+                block_id,
+                slot,
+                producer: block_creator_addr,
+                operations: operation_receipts,
+                // filled with the outcomes of the slot once it is settled
+                async_messages: Vec::new(),
+                total_fees: block_credits.saturating_sub(self.config.block_reward),
+                block_reward: self.config.block_reward,
+                block_credit_part,
+                endorser_credits,
+                endorsement_target_credits,
+                producer_credit,
+                is_final: false,
+            });
         } else {
             // the slot is a miss, check who was supposed to be the creator and update production stats
             let producer_addr = selector
@@ -1025,7 +1487,88 @@ impl ExecutionState {
         }
 
         // Finish slot and return the execution output
-        context_guard!(self).settle_slot()
+        let mut exec_out = context_guard!(self).settle_slot();
+        if let Some(receipt) = block_receipt.as_mut() {
+            receipt.async_messages = exec_out.async_message_outcomes.clone();
+        }
+        exec_out.block_receipt = block_receipt;
+        exec_out
+    }
+
+    /// Executes the operations of a block optimistically in parallel (see `parallel_execution.rs`).
+    /// The output is the same as when executing the operations one after the other.
+    /// Assumes the execution context was initialized at the beginning of the slot.
+    ///
+    /// # Arguments
+    /// * `operations`: operations to execute, in the order in which they appear in the block
+    /// * `block_slot`: slot of the block in which the ops are included
+    /// * `remaining_block_gas`: mutable reference towards the remaining gas in the block
+    /// * `block_credits`: mutable reference towards the total block reward/fee credits
+    ///
+    /// # Returns
+    /// The result of the execution of each operation, as returned by `OperationExecutor::execute_operation`
+    fn execute_operations_in_parallel(
+        &self,
+        operations: &[WrappedOperation],
+        block_slot: Slot,
+        remaining_block_gas: &mut u64,
+        block_credits: &mut Amount,
+    ) -> Vec<Result<OperationReceipt, ExecutionError>> {
+        // expose the changes caused so far in the slot to the forks through the active history.
+        // This is safe because nothing else accesses the history while the slot is executed.
+        let forks: Vec<ExecutionContext> = {
+            let context = context_guard!(self);
+            self.active_history.write().0.push_back(ExecutionOutput {
+                slot: context.slot,
+                block_id: None,
+                state_changes: context.get_state_changes_snapshot(),
+                events: Default::default(),
+                async_message_outcomes: Default::default(),
+                address_history: Default::default(),
+                block_receipt: None,
+            });
+            operations.iter().map(|_| context.fork()).collect()
+        };
+        let executions =
+            execute_operations_speculatively(&self.config, forks, operations, block_slot);
+        self.active_history.write().0.pop_back();
+
+        // commit the executions in block order
+        let mut written_keys: HashSet<StateKey> = HashSet::new();
+        let mut results = Vec::with_capacity(operations.len());
+        for (operation, mut execution) in operations.iter().zip(executions) {
+            if execution.accesses.conflicts_with(&written_keys) {
+                // the execution read a state modified by a previous operation: execute it again
+                context_guard!(self).track_accesses();
+                results.push(self.operation_executor.execute_operation(
+                    operation,
+                    block_slot,
+                    remaining_block_gas,
+                    block_credits,
+                ));
+                written_keys.extend(context_guard!(self).take_accesses().writes);
+                continue;
+            }
+
+            // the block gas is only known once the previous operations are committed
+            match self.operation_executor.check_operation_inclusion(
+                operation,
+                block_slot,
+                *remaining_block_gas,
+            ) {
+                Ok(()) => {
+                    context_guard!(self).apply_fork(&mut execution.fork, &execution.accesses);
+                    if let Ok(receipt) = &execution.result {
+                        *remaining_block_gas = remaining_block_gas.saturating_sub(receipt.gas);
+                        *block_credits = block_credits.saturating_add(receipt.fee);
+                    }
+                    written_keys.extend(execution.accesses.writes);
+                    results.push(execution.result);
+                }
+                Err(err) => results.push(Err(err)),
+            }
+        }
+        results
     }
 
     /// Runs a read-only execution request.
@@ -1035,22 +1578,53 @@ impl ExecutionState {
     ///
     /// # Arguments
     /// * `req`: a read-only execution request
+    /// * `previous_outputs`: outputs of previous read-only executions to apply before this one
     ///
     /// # Returns
-    ///  `ExecutionOutput` describing the output of the execution, or an error
+    ///  `ReadOnlyExecutionOutput` describing the output of the execution, or an error
     pub(crate) fn execute_readonly_request(
         &self,
         req: ReadOnlyExecutionRequest,
-    ) -> Result<ExecutionOutput, ExecutionError> {
+        previous_outputs: &[ExecutionOutput],
+    ) -> Result<ReadOnlyExecutionOutput, ExecutionError> {
         // TODO ensure that speculative things are reset after every execution ends (incl. on error and readonly)
         // otherwise, on prod stats accumulation etc... from the API we might be counting the remainder of this speculative execution
 
-        // set the execution slot to be the one after the latest executed active slot
-        let slot = self
-            .active_cursor
+        // set the execution slot to be the one after the latest executed slot,
+        // ignoring the candidate slots if the execution happens on top of the final state only
+        let (cursor, active_history) = if req.final_state_only {
+            (self.final_cursor, Default::default())
+        } else {
+            (self.active_cursor, self.active_history.clone())
+        };
+        let slot = cursor
             .get_next_slot(self.config.thread_count)
             .expect("slot overflow in readonly execution");
 
+        // inject the previous outputs and the state overrides on top of the history,
+        // so that they are seen by the execution but are not part of its output
+        let active_history = if previous_outputs.is_empty() && req.state_overrides.is_empty() {
+            active_history
+        } else {
+            let mut history = ActiveHistory(active_history.read().0.clone());
+            history.0.extend(previous_outputs.iter().cloned());
+            if !req.state_overrides.is_empty() {
+                history.0.push_back(ExecutionOutput {
+                    slot,
+                    block_id: None,
+                    state_changes: StateChanges {
+                        ledger_changes: state_overrides_to_ledger_changes(req.state_overrides),
+                        ..Default::default()
+                    },
+                    events: Default::default(),
+                    async_message_outcomes: Default::default(),
+                    address_history: Default::default(),
+                    block_receipt: None,
+                });
+            }
+            Arc::new(RwLock::new(history))
+        };
+
         // create a readonly execution context
         let execution_context = ExecutionContext::readonly(
             self.config.clone(),
@@ -1059,18 +1633,17 @@ impl ExecutionState {
             req.simulated_gas_price,
             req.call_stack,
             self.final_state.clone(),
-            self.active_history.clone(),
+            active_history,
         );
 
         // run the intepreter according to the target type
-        match req.target {
+        let run_result = match req.target {
             ReadOnlyExecutionTarget::BytecodeExecution(bytecode) => {
                 // set the execution context for execution
                 *context_guard!(self) = execution_context;
 
                 // run the bytecode's main function
                 massa_sc_runtime::run_main(&bytecode, req.max_gas, &*self.execution_interface)
-                    .map_err(|err| ExecutionError::RuntimeError(err.to_string()))?;
             }
             ReadOnlyExecutionTarget::FunctionCall {
                 target_addr,
@@ -1093,12 +1666,21 @@ impl ExecutionState {
                     &parameter,
                     &*self.execution_interface,
                 )
-                .map_err(|err| ExecutionError::RuntimeError(err.to_string()))?;
             }
-        }
+        };
+
+        // keep the whole chain of causes of runtime errors
+        let remaining_gas = run_result.map_err(|err| {
+            ExecutionError::RuntimeErrorChain(ErrorChain(
+                err.chain().map(|cause| cause.to_string()).collect(),
+            ))
+        })?;
 
         // return the execution output
-        Ok(context_guard!(self).settle_slot())
+        Ok(ReadOnlyExecutionOutput {
+            out: context_guard!(self).settle_slot(),
+            gas_cost: req.max_gas.saturating_sub(remaining_gas),
+        })
     }
 
     /// Gets a parallel balance both at the latest final and candidate executed slots
@@ -1139,6 +1721,37 @@ impl ExecutionState {
         )
     }
 
+    /// Gets the receipt of the execution of a block, looking at candidate slots first
+    pub fn get_block_execution_receipt(&self, block_id: &BlockId) -> Option<BlockExecutionReceipt> {
+        let matches = |receipt: &&BlockExecutionReceipt| receipt.block_id == *block_id;
+        if let Some(receipt) = self
+            .active_history
+            .read()
+            .0
+            .iter()
+            .rev()
+            .filter_map(|output| output.block_receipt.as_ref())
+            .find(matches)
+        {
+            return Some(receipt.clone());
+        }
+        self.final_block_receipts
+            .iter()
+            .rev()
+            .find(matches)
+            .cloned()
+    }
+
+    /// Gets proofs of the final values of ledger sub-entries against the final ledger root
+    pub fn get_final_ledger_proofs(&self, input: &[(Address, LedgerSubEntry)]) -> LedgerProofs {
+        self.final_state.read().get_ledger_proofs(input)
+    }
+
+    /// Gets the root of the final ledger at the output of a recent final slot
+    pub fn get_final_ledger_root(&self, slot: Slot) -> Option<Hash> {
+        self.final_state.read().get_ledger_root_at(slot)
+    }
+
     /// Gets roll counts both at the latest final and active executed slots
     pub fn get_final_and_candidate_rolls(&self, address: &Address) -> (u64, u64) {
         let final_rolls = self.final_state.read().pos_state.get_rolls_for(address);
@@ -1171,13 +1784,42 @@ impl ExecutionState {
         )
     }
 
+    /// Gets the metadata of the bytecode of an address both at the latest final and candidate executed slots.
+    /// The metadata is None if the address does not exist.
+    pub fn get_final_and_candidate_bytecode_metadata(
+        &self,
+        address: &Address,
+    ) -> (Option<BytecodeMetadata>, Option<BytecodeMetadata>) {
+        let (final_entry, candidate_entry) =
+            self.get_final_and_active_data_entry(address, BYTECODE_METADATA_KEY);
+        let final_bytecode = self.final_state.read().ledger.get_bytecode(address);
+        let candidate_bytecode = match self.active_history.read().fetch_bytecode(address) {
+            HistorySearchResult::Present(bytecode) => Some(bytecode),
+            HistorySearchResult::NoInfo => final_bytecode.clone(),
+            HistorySearchResult::Absent => None,
+        };
+        let deserializer = BytecodeMetadataDeserializer::new(self.config.thread_count);
+        (
+            final_bytecode.map(|bytecode| {
+                BytecodeMetadata::from_datastore(final_entry.as_deref(), &bytecode, &deserializer)
+            }),
+            candidate_bytecode.map(|bytecode| {
+                BytecodeMetadata::from_datastore(
+                    candidate_entry.as_deref(),
+                    &bytecode,
+                    &deserializer,
+                )
+            }),
+        )
+    }
+
     /// Get every final and active datastore key of the given address
     pub fn get_final_and_candidate_datastore_keys(
         &self,
         addr: &Address,
     ) -> (BTreeSet<Vec<u8>>, BTreeSet<Vec<u8>>) {
         // here, get the final keys from the final ledger, and make a copy of it for the candidate list
-        let final_keys = self.final_state.read().ledger.get_datastore_keys(addr);
+        let mut final_keys = self.final_state.read().ledger.get_datastore_keys(addr);
         let mut candidate_keys = final_keys.clone();
 
         // here, traverse the history from oldest to newest, applying additions and deletions
@@ -1208,6 +1850,9 @@ impl ExecutionState {
             }
         }
 
+        // the bytecode metadata is exposed on its own, not as a datastore entry
+        final_keys.remove(BYTECODE_METADATA_KEY);
+        candidate_keys.remove(BYTECODE_METADATA_KEY);
         (final_keys, candidate_keys)
     }
 
@@ -1231,6 +1876,114 @@ impl ExecutionState {
         }
     }
 
+    /// Gets the roll counts at the end of a complete cycle, if it is still in the history
+    pub fn get_final_cycle_rolls(&self, cycle: u64) -> Option<BTreeMap<Address, u64>> {
+        let final_state = self.final_state.read();
+        let index = final_state.pos_state.get_cycle_index(cycle)?;
+        let cycle_info = &final_state.pos_state.cycle_history[index];
+        cycle_info.complete.then(|| cycle_info.roll_counts.clone())
+    }
+
+    /// Gets a page of the asynchronous messages optionally filtered by sender, destination,
+    /// emission slot and emission index.
+    /// The messages pending in the candidate pool (highest priority first) come first,
+    /// followed by the known outcomes of the messages that left the pool (oldest first).
+    pub fn get_filtered_async_messages(&self, filter: AsyncMessageFilter) -> Vec<AsyncMessageInfo> {
+        let predicate = |msg: &AsyncMessage| {
+            filter.matches(
+                &msg.sender,
+                &msg.destination,
+                &msg.emission_slot,
+                msg.emission_index,
+            )
+        };
+
+        // get the matching pending messages of the final pool
+        let final_messages: BTreeMap<_, _> = self
+            .final_state
+            .read()
+            .async_pool
+            .get_filtered_messages(predicate)
+            .into_iter()
+            .collect();
+        let final_ids: BTreeSet<_> = final_messages.keys().copied().collect();
+
+        // apply the active history to the matching messages to get the candidate ones
+        let mut candidate_messages = final_messages;
+        let history = self.active_history.read();
+        for output in history.0.iter() {
+            for change in output.state_changes.async_pool_changes.0.iter() {
+                match change {
+                    Change::Add(id, msg) if predicate(msg) => {
+                        candidate_messages.insert(*id, msg.clone());
+                    }
+                    Change::Add(..) => {}
+                    Change::Delete(id) => {
+                        candidate_messages.remove(id);
+                    }
+                }
+            }
+        }
+
+        // list the pending messages followed by the outcomes of the messages that left the pool,
+        // and only keep the requested page
+        let outcome_matches = |info: &&AsyncMessageInfo| {
+            filter.matches(
+                &info.sender,
+                &info.destination,
+                &info.emission_slot,
+                info.emission_index,
+            )
+        };
+        let outcomes = self
+            .final_async_message_outcomes
+            .iter()
+            .chain(
+                history
+                    .0
+                    .iter()
+                    .flat_map(|output| output.async_message_outcomes.iter()),
+            )
+            .filter(outcome_matches)
+            .cloned();
+        candidate_messages
+            .into_iter()
+            .map(|(id, msg)| msg.info(AsyncMessageStatus::Pending, None, final_ids.contains(&id)))
+            .chain(outcomes)
+            .skip(filter.offset as usize)
+            .take(filter.limit.map_or(usize::MAX, |limit| limit as usize))
+            .collect()
+    }
+
+    /// Gets a page of the history of operations and coin transfers involving an address, most recent first.
+    /// Candidate entries come before final ones.
+    ///
+    /// # Arguments
+    /// * `address`: address whose history is requested
+    /// * `offset`: number of most recent entries to skip
+    /// * `limit`: maximum number of entries to return
+    pub fn get_address_history(
+        &self,
+        address: &Address,
+        offset: usize,
+        limit: usize,
+    ) -> Vec<AddressHistoryEntry> {
+        let history = self.active_history.read();
+        let candidate_entries = history
+            .0
+            .iter()
+            .rev()
+            .flat_map(|output| output.address_history.iter().rev())
+            .filter(|entry| entry.involves(address));
+        let final_entries = self.final_address_history.iter_rev(address);
+        candidate_entries
+            .chain(final_entries)
+            .skip(offset)
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
     /// Gets execution events optionally filtered by:
     /// * start slot
     /// * end slot
@@ -1312,3 +2065,24 @@ impl ExecutionState {
         context_guard!(self).get_address_future_deferred_credits(address, self.config.thread_count)
     }
 }
+
+/// Translates read-only state overrides into the ledger changes setting them
+fn state_overrides_to_ledger_changes(state_overrides: Vec<ReadOnlyStateOverride>) -> LedgerChanges {
+    let mut ledger_changes = LedgerChanges::default();
+    for state_override in state_overrides {
+        let address = state_override.address;
+        if let Some(balance) = state_override.sequential_balance {
+            ledger_changes.set_sequential_balance(address, balance);
+        }
+        if let Some(balance) = state_override.parallel_balance {
+            ledger_changes.set_parallel_balance(address, balance);
+        }
+        if let Some(bytecode) = state_override.bytecode {
+            ledger_changes.set_bytecode(address, bytecode);
+        }
+        for (key, value) in state_override.datastore {
+            ledger_changes.set_data_entry(address, key, value);
+        }
+    }
+    ledger_changes
+}
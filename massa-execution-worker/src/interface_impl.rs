@@ -10,12 +10,15 @@ use anyhow::{bail, Result};
 use massa_async_pool::AsyncMessage;
 use massa_execution_exports::ExecutionConfig;
 use massa_execution_exports::ExecutionStackElement;
+use massa_models::config::{MAX_EVENT_FIELD_COUNT, MAX_EVENT_TOPIC_LENGTH, MAX_EVENT_VALUE_LENGTH};
+use massa_models::output_event::{
+    StructuredEvent, StructuredEventDeserializer, SYSTEM_EVENT_TOPIC_PREFIX,
+};
 use massa_models::{
     address::Address, amount::Amount, slot::Slot, timeslots::get_block_slot_timestamp,
 };
 use massa_sc_runtime::{Interface, InterfaceClone};
 use parking_lot::Mutex;
-use rand::Rng;
 use std::str::FromStr;
 use std::sync::Arc;
 use tracing::debug;
@@ -267,6 +270,7 @@ impl Interface for InterfaceImpl {
     /// Sets a datastore entry for the current address (top of the call stack).
     /// Fails if the address does not exist.
     /// Creates the entry if does not exist.
+    /// Setting the `IMMUTABLE_BYTECODE_KEY` entry makes the bytecode of the address immutable instead.
     ///
     /// # Arguments
     /// * address: string representation of the address
@@ -436,13 +440,32 @@ impl Interface for InterfaceImpl {
     }
 
     /// Emits an execution event to be stored.
+    /// If the data starts with `STRUCTURED_EVENT_DATA_PREFIX`, the event is a structured one
+    /// whose topic cannot be one of the topics reserved to the node.
     ///
     /// # Arguments:
     /// data: the string data that is the payload of the event
     fn generate_event(&self, data: String) -> Result<()> {
+        let deserializer = StructuredEventDeserializer::new(
+            self.config.thread_count,
+            MAX_EVENT_TOPIC_LENGTH,
+            MAX_EVENT_FIELD_COUNT,
+            MAX_EVENT_VALUE_LENGTH,
+        );
+        let structured = StructuredEvent::from_event_data(&data, &deserializer)?;
         let mut context = context_guard!(self);
-        let event = context.event_create(data);
-        context.event_emit(event);
+        match structured {
+            Some(structured) => {
+                if structured.topic.starts_with(SYSTEM_EVENT_TOPIC_PREFIX) {
+                    bail!("event topic {} is reserved to the node", structured.topic);
+                }
+                context.event_emit_structured(structured);
+            }
+            None => {
+                let event = context.event_create(data);
+                context.event_emit(event);
+            }
+        }
         Ok(())
     }
 
@@ -466,7 +489,7 @@ impl Interface for InterfaceImpl {
     /// it can be both predicted and manipulated before the execution
     fn unsafe_random(&self) -> Result<i64> {
         let distr = rand::distributions::Uniform::new_inclusive(i64::MIN, i64::MAX);
-        Ok(context_guard!(self).unsafe_rng.sample(distr))
+        Ok(context_guard!(self).unsafe_random(distr))
     }
 
     /// Adds an asynchronous message to the context speculative asynchronous pool
@@ -530,7 +553,8 @@ impl Interface for InterfaceImpl {
         Ok(slot.thread)
     }
 
-    /// Sets the bytecode of the current address
+    /// Sets the bytecode of the current address.
+    /// Fails if the bytecode of the current address is immutable.
     fn raw_set_bytecode(&self, bytecode: &[u8]) -> Result<()> {
         let mut execution_context = context_guard!(self);
         let address = execution_context.get_current_address()?;
@@ -541,7 +565,8 @@ impl Interface for InterfaceImpl {
     }
 
     /// Sets the bytecode of an arbitrary address.
-    /// Fails if the address does not exist of if the context doesn't have write access rights on it.
+    /// Fails if the address does not exist of if the context doesn't have write access rights on it,
+    /// or if the bytecode of the address is immutable.
     fn raw_set_bytecode_for(&self, address: &str, bytecode: &[u8]) -> Result<()> {
         let address = massa_models::address::Address::from_str(address)?;
         let mut execution_context = context_guard!(self);
@@ -57,6 +57,13 @@
 //! It also serves as an access point to the current execution state and speculative ledger
 //! as defined in `speculative_ledger.rs`.
 //!
+//! ## `parallel_execution.rs`
+//! Executes the operations of a block optimistically in parallel on forks of the execution context,
+//! committing them in block order and executing again the ones that conflict with previous operations.
+//!
+//! ## `address_history.rs`
+//! A bounded cache of the final history of operations and coin transfers, indexed by address.
+//!
 //! ## `speculative_ledger.rs`
 //! A speculative (non-final) ledger that supports canceling already-executed operations
 //! in the case of some blockclique changes.
@@ -77,10 +84,12 @@
 #![feature(map_try_insert)]
 
 mod active_history;
+mod address_history;
 mod context;
 mod controller;
 mod execution;
 mod interface_impl;
+mod parallel_execution;
 mod request_queue;
 mod speculative_async_pool;
 mod speculative_executed_ops;
@@ -0,0 +1,158 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! This module executes the operations of a block optimistically in parallel.
+//!
+//! Each operation is executed on its own fork of the execution context (see `ExecutionContext::fork`),
+//! all forks starting from the state preceding the first operation of the block.
+//! The parts of the state read and written by each execution are tracked,
+//! so that the executions can then be committed in block order:
+//! an execution that read a part of the state written by a previously committed operation
+//! is discarded and the operation is executed again on top of the committed state.
+//! This makes the output of the block identical to the one of a sequential execution.
+
+use crate::context::ExecutionContext;
+use crate::execution::OperationExecutor;
+use massa_execution_exports::{ExecutionConfig, ExecutionError};
+use massa_models::address::Address;
+use massa_models::amount::Amount;
+use massa_models::api::OperationReceipt;
+use massa_models::operation::{OperationId, WrappedOperation};
+use massa_models::slot::Slot;
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Part of the execution state that an execution can read or write
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum StateKey {
+    /// ledger entry of an address (balances, bytecode and datastore)
+    Ledger(Address),
+    /// rolls, production stats and deferred credits of an address
+    Rolls(Address),
+    /// whether an operation was executed
+    ExecutedOp(OperationId),
+    /// asynchronous pool and counter of the messages emitted in the slot
+    AsyncPool,
+    /// counter of the addresses created in the slot
+    AddressCounter,
+    /// unsafe random number generator
+    UnsafeRng,
+}
+
+/// Parts of the execution state read and written by an execution
+#[derive(Debug, Default, Clone)]
+pub(crate) struct StateAccesses {
+    /// parts of the state read by the execution
+    pub reads: HashSet<StateKey>,
+    /// parts of the state written by the execution
+    pub writes: HashSet<StateKey>,
+}
+
+impl StateAccesses {
+    /// Checks whether the execution read a part of the state written by previous executions
+    pub fn conflicts_with(&self, previous_writes: &HashSet<StateKey>) -> bool {
+        !self.reads.is_disjoint(previous_writes)
+    }
+}
+
+/// Result of the execution of an operation on a fork of the execution context
+pub(crate) struct SpeculativeOperation {
+    /// result of the execution, as returned by `OperationExecutor::execute_operation`
+    pub result: Result<OperationReceipt, ExecutionError>,
+    /// fork holding the changes caused by the execution
+    pub fork: ExecutionContext,
+    /// parts of the state read and written by the execution
+    pub accesses: StateAccesses,
+}
+
+/// Executes operations concurrently, each one on its own fork of the execution context.
+/// Block gas and credits are not accounted for: they are handled when the executions are committed.
+///
+/// # Arguments
+/// * `config`: execution configuration
+/// * `forks`: one fork of the execution context per operation (see `ExecutionContext::fork`)
+/// * `operations`: operations to execute
+/// * `block_slot`: slot of the block in which the operations are included
+///
+/// # Returns
+/// The speculative execution of each operation, in the order of the operations
+pub(crate) fn execute_operations_speculatively(
+    config: &ExecutionConfig,
+    forks: Vec<ExecutionContext>,
+    operations: &[WrappedOperation],
+    block_slot: Slot,
+) -> Vec<SpeculativeOperation> {
+    let thread_count = config.parallel_execution_threads.max(1);
+
+    // distribute the operations among the threads
+    let mut assignments: Vec<Vec<(usize, ExecutionContext)>> =
+        (0..thread_count).map(|_| Vec::new()).collect();
+    for (index, fork) in forks.into_iter().enumerate() {
+        assignments[index % thread_count].push((index, fork));
+    }
+
+    let mut executions: Vec<(usize, SpeculativeOperation)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = assignments
+            .into_iter()
+            .map(|assignment| {
+                scope.spawn(move || {
+                    assignment
+                        .into_iter()
+                        .map(|(index, fork)| {
+                            (
+                                index,
+                                execute_on_fork(config, fork, &operations[index], block_slot),
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .expect("speculative operation execution thread panicked")
+            })
+            .collect()
+    });
+
+    executions.sort_unstable_by_key(|(index, _)| *index);
+    executions
+        .into_iter()
+        .map(|(_, execution)| execution)
+        .collect()
+}
+
+/// Executes an operation on a fork of the execution context
+fn execute_on_fork(
+    config: &ExecutionConfig,
+    fork: ExecutionContext,
+    operation: &WrappedOperation,
+    block_slot: Slot,
+) -> SpeculativeOperation {
+    let fork = Arc::new(Mutex::new(fork));
+    let result = {
+        // the block gas and credits are checked and updated when committing the execution
+        let mut remaining_block_gas = u64::MAX;
+        let mut block_credits = Amount::default();
+        let executor = OperationExecutor::new(config.clone(), fork.clone());
+        executor.execute_operation(
+            operation,
+            block_slot,
+            &mut remaining_block_gas,
+            &mut block_credits,
+        )
+    };
+    let mut fork = Arc::try_unwrap(fork)
+        .ok()
+        .expect("execution context fork still shared after the execution")
+        .into_inner();
+    let accesses = fork.take_accesses();
+    SpeculativeOperation {
+        result,
+        fork,
+        accesses,
+    }
+}
@@ -15,8 +15,9 @@ use std::sync::Arc;
 /// The `SpeculativeAsyncPool` manipulates this copy to compute the full pool
 /// while keeping track of all the newly added changes.
 pub(crate) struct SpeculativeAsyncPool {
-    /// Copy of the final asynchronous pool with the previous changes applied
-    async_pool: AsyncPool,
+    /// Copy of the final asynchronous pool with the previous changes applied,
+    /// shared with the forks of the `SpeculativeAsyncPool` until one of them modifies it
+    async_pool: Arc<AsyncPool>,
 
     /// List of newly emitted asynchronous messages
     emitted: Vec<(AsyncMessageId, AsyncMessage)>,
@@ -40,12 +41,32 @@ impl SpeculativeAsyncPool {
         }
 
         SpeculativeAsyncPool {
-            async_pool,
+            async_pool: Arc::new(async_pool),
             emitted: Default::default(),
             settled_changes: Default::default(),
         }
     }
 
+    /// Creates a `SpeculativeAsyncPool` seeing the same pool and emitted messages, without any settled changes.
+    /// The copy of the pool is only made when the fork modifies it.
+    pub fn fork(&self) -> Self {
+        SpeculativeAsyncPool {
+            async_pool: self.async_pool.clone(),
+            emitted: self.emitted.clone(),
+            settled_changes: Default::default(),
+        }
+    }
+
+    /// Replaces the pool and emitted messages by the ones of a fork (see `fork` method),
+    /// and appends the changes settled by the fork.
+    /// This is only valid if the pool and emitted messages were not modified since the fork was created.
+    pub fn apply_fork(&mut self, fork: &mut SpeculativeAsyncPool) {
+        self.async_pool = fork.async_pool.clone();
+        self.emitted = std::mem::take(&mut fork.emitted);
+        self.settled_changes
+            .extend(std::mem::take(&mut fork.settled_changes));
+    }
+
     /// Returns the changes caused to the `SpeculativeAsyncPool` since its creation,
     /// and resets their local value to nothing.
     /// This must be called after `settle_emitted_messages()`
@@ -68,6 +89,42 @@ impl SpeculativeAsyncPool {
         self.emitted.push((msg.compute_id(), msg));
     }
 
+    /// Finds a pending message emitted at `emission_slot` with index `emission_index`,
+    /// looking both in the speculative pool and in the newly emitted messages.
+    pub fn find_message(
+        &self,
+        emission_slot: &Slot,
+        emission_index: u64,
+    ) -> Option<(AsyncMessageId, AsyncMessage)> {
+        self.async_pool
+            .find_message(emission_slot, emission_index)
+            .or_else(|| {
+                self.emitted
+                    .iter()
+                    .find(|(_id, msg)| {
+                        msg.emission_slot == *emission_slot && msg.emission_index == emission_index
+                    })
+                    .cloned()
+            })
+    }
+
+    /// Removes a pending message from the speculative pool or from the newly emitted messages,
+    /// settling its deletion from the pool in the changes accumulator if needed.
+    ///
+    /// # Returns
+    /// The removed message, or None if it was not found
+    pub fn cancel_message(&mut self, message_id: &AsyncMessageId) -> Option<AsyncMessage> {
+        if let Some(msg) = Arc::make_mut(&mut self.async_pool).remove_message(message_id) {
+            self.settled_changes.push_delete(*message_id);
+            return Some(msg);
+        }
+        let index = self
+            .emitted
+            .iter()
+            .position(|(id, _msg)| id == message_id)?;
+        Some(self.emitted.remove(index).1)
+    }
+
     /// Takes a batch of asynchronous messages to execute,
     /// removing them from the speculative asynchronous pool and settling their deletion from it in the changes accumulator.
     ///
@@ -83,7 +140,7 @@ impl SpeculativeAsyncPool {
         max_gas: u64,
     ) -> Vec<(AsyncMessageId, AsyncMessage)> {
         // take a batch of messages, removing it from the async pool
-        let msgs = self.async_pool.take_batch_to_execute(slot, max_gas);
+        let msgs = Arc::make_mut(&mut self.async_pool).take_batch_to_execute(slot, max_gas);
 
         // settle deletions
         for (msg_id, _msg) in &msgs {
@@ -102,7 +159,8 @@ impl SpeculativeAsyncPool {
     /// # Returns
     /// the list of deleted `(message_id, message)`, used for reimbursement
     pub fn settle_slot(&mut self, slot: &Slot) -> Vec<(AsyncMessageId, AsyncMessage)> {
-        let deleted_messages = self.async_pool.settle_slot(slot, &mut self.emitted);
+        let deleted_messages =
+            Arc::make_mut(&mut self.async_pool).settle_slot(slot, &mut self.emitted);
         for (msg_id, msg) in std::mem::take(&mut self.emitted) {
             self.settled_changes.push_add(msg_id, msg);
         }
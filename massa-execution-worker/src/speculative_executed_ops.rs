@@ -38,6 +38,17 @@ impl SpeculativeExecutedOps {
         }
     }
 
+    /// Creates a `SpeculativeExecutedOps` on top of the same final state and active history,
+    /// without any newly executed operations
+    pub fn fork(&self) -> Self {
+        SpeculativeExecutedOps::new(self.final_state.clone(), self.active_history.clone())
+    }
+
+    /// Adds executed operations to the ones inserted so far
+    pub fn apply_changes(&mut self, executed_ops: ExecutedOps) {
+        self.executed_ops.extend(executed_ops);
+    }
+
     /// Returns the set of operation IDs caused to the `SpeculativeExecutedOps` since its creation,
     /// and resets their local value to nothing
     pub fn take(&mut self) -> ExecutedOps {
@@ -54,6 +54,21 @@ impl SpeculativeLedger {
         }
     }
 
+    /// Creates a `SpeculativeLedger` on top of the same final state and active history,
+    /// without any added changes
+    pub fn fork(&self) -> Self {
+        SpeculativeLedger::new(
+            self.final_state.clone(),
+            self.active_history.clone(),
+            self.max_datastore_key_length,
+        )
+    }
+
+    /// Applies ledger changes on top of the changes added so far
+    pub fn apply_changes(&mut self, changes: LedgerChanges) {
+        self.added_changes.apply(changes);
+    }
+
     /// Returns the changes caused to the `SpeculativeLedger` since its creation,
     /// and resets their local value to nothing.
     pub fn take(&mut self) -> LedgerChanges {
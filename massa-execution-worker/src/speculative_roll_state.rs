@@ -43,6 +43,34 @@ impl SpeculativeRollState {
         }
     }
 
+    /// Creates a `SpeculativeRollState` on top of the same final state and active history,
+    /// without any added changes
+    pub fn fork(&self) -> Self {
+        SpeculativeRollState::new(self.final_state.clone(), self.active_history.clone())
+    }
+
+    /// Applies PoS changes on top of the changes added so far.
+    /// Roll counts and deferred credits are overwritten, as when they are set by roll sales and purchases.
+    pub fn apply_changes(&mut self, changes: PoSChanges) {
+        self.added_changes.seed_bits.extend(changes.seed_bits);
+        self.added_changes.roll_changes.extend(changes.roll_changes);
+        for (addr, stats) in changes.production_stats {
+            self.added_changes
+                .production_stats
+                .entry(addr)
+                .or_default()
+                .extend(&stats);
+        }
+        for (slot, credits) in changes.deferred_credits.0 {
+            self.added_changes
+                .deferred_credits
+                .0
+                .entry(slot)
+                .or_default()
+                .extend(credits);
+        }
+    }
+
     /// Returns the changes caused to the `SpeculativeRollState` since its creation,
     /// and resets their local value to nothing.
     pub fn take(&mut self) -> PoSChanges {
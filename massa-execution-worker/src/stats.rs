@@ -1,5 +1,6 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_models::amount::Amount;
 use massa_models::slot::Slot;
 use massa_models::stats::ExecutionStats;
 use massa_time::MassaTime;
@@ -15,6 +16,8 @@ pub struct ExecutionStatsCounter {
     final_blocks: VecDeque<(usize, MassaTime)>,
     /// final operations executed in the time window (count, instant)
     final_executed_ops: VecDeque<(usize, MassaTime)>,
+    /// gas of the final operations executed in the time window ((gas used, max gas, refunds), instant)
+    final_gas_usage: VecDeque<((u64, u64, Amount), MassaTime)>,
 }
 
 impl ExecutionStatsCounter {
@@ -25,6 +28,7 @@ impl ExecutionStatsCounter {
             compensation_millis,
             final_blocks: Default::default(),
             final_executed_ops: Default::default(),
+            final_gas_usage: Default::default(),
         }
     }
 
@@ -49,6 +53,15 @@ impl ExecutionStatsCounter {
                 break;
             }
         }
+
+        // prune final gas usage
+        while let Some((_, t)) = self.final_gas_usage.front() {
+            if t < &start_time {
+                self.final_gas_usage.pop_front();
+            } else {
+                break;
+            }
+        }
     }
 
     /// register final blocks
@@ -67,6 +80,15 @@ impl ExecutionStatsCounter {
         self.refresh(current_time);
     }
 
+    /// register the gas used by final executed operations, their max gas and the coins refunded for unused gas
+    pub fn register_final_gas_usage(&mut self, gas_used: u64, max_gas: u64, refunds: Amount) {
+        let current_time =
+            MassaTime::now(self.compensation_millis).expect("could not get current time");
+        self.final_gas_usage
+            .push_back(((gas_used, max_gas, refunds), current_time));
+        self.refresh(current_time);
+    }
+
     /// get statistics
     pub fn get_stats(&self, active_cursor: Slot) -> ExecutionStats {
         let current_time =
@@ -80,9 +102,26 @@ impl ExecutionStatsCounter {
                 0
             }
         };
+        let (final_gas_used, final_max_gas, final_gas_refunds) = self
+            .final_gas_usage
+            .iter()
+            .filter(|(_, t)| t >= &start_time && t <= &current_time)
+            .fold(
+                (0u64, 0u64, Amount::default()),
+                |(gas_used, max_gas, refunds), ((used, max, refund), _)| {
+                    (
+                        gas_used.saturating_add(*used),
+                        max_gas.saturating_add(*max),
+                        refunds.saturating_add(*refund),
+                    )
+                },
+            );
         ExecutionStats {
             final_block_count: self.final_blocks.iter().map(map_func).sum(),
             final_executed_operations_count: self.final_executed_ops.iter().map(map_func).sum(),
+            final_gas_used,
+            final_max_gas,
+            final_gas_refunds,
             time_window_start: start_time,
             time_window_end: current_time,
             active_cursor,
@@ -1,9 +1,14 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::active_history::ActiveHistory;
+use crate::context::ExecutionContext;
+use crate::execution::ExecutionState;
+use crate::interface_impl::InterfaceImpl;
 use crate::start_execution_worker;
 use massa_async_pool::AsyncPoolConfig;
 use massa_execution_exports::{
-    ExecutionConfig, ExecutionError, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
+    ExecutionConfig, ExecutionController, ExecutionError, ExecutionOutput, ExecutionStackElement,
+    ReadOnlyExecutionBatch, ReadOnlyExecutionRequest, ReadOnlyExecutionTarget,
 };
 use massa_final_state::{FinalState, FinalStateConfig};
 use massa_hash::Hash;
@@ -15,19 +20,36 @@ use massa_models::config::{
 use massa_models::prehash::PreHashMap;
 use massa_models::{address::Address, amount::Amount, slot::Slot};
 use massa_models::{
-    api::EventFilter,
+    api::{
+        AddressHistoryEntryKind, AsyncMessageEvictionReason, AsyncMessageFilter,
+        AsyncMessageStatus, BlockExecutionReceipt, EventFilter, OperationExecutionOutcome,
+    },
     block::{Block, BlockHeader, BlockHeaderSerializer, BlockId, BlockSerializer, WrappedBlock},
+    bytecode::BYTECODE_METADATA_KEY,
     config::THREAD_COUNT,
+    execution::ReadOnlyStateOverride,
+    multisig::{MultisigAction, MultisigDescriptor, MultisigProposal},
     operation::{Operation, OperationSerializer, OperationType, WrappedOperation},
+    output_event::{
+        AsyncMessageOrigin, EventValue, StructuredEvent, ASYNC_MESSAGE_OUTCOME_EVENT_KEY,
+        BYTECODE_CHANGE_EVENT_TOPIC, TRANSFER_EVENT_TOPIC,
+    },
     wrapped::WrappedContent,
 };
 use massa_pos_exports::SelectorConfig;
 use massa_pos_worker::start_selector_worker;
+use massa_sc_runtime::Interface;
 use massa_signature::KeyPair;
 use massa_storage::Storage;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use serial_test::serial;
-use std::{cmp::Reverse, collections::HashMap, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tempfile::{NamedTempFile, TempDir};
 
 use super::mock::get_initials;
@@ -110,11 +132,156 @@ fn test_sending_read_only_execution_command() {
             target: ReadOnlyExecutionTarget::BytecodeExecution(
                 include_bytes!("./wasm/event_test.wasm").to_vec(),
             ),
+            state_overrides: vec![],
+            final_state_only: false,
         })
         .unwrap();
     manager.stop();
 }
 
+#[test]
+#[serial]
+fn test_read_only_execution_with_state_overrides() {
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+    let (mut manager, controller) = start_execution_worker(
+        ExecutionConfig::default(),
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    // the target address does not exist: its bytecode is injected for the call
+    let (target_addr, _keypair) = get_random_address_full();
+    let call = |state_overrides| {
+        controller.execute_readonly_request(ReadOnlyExecutionRequest {
+            max_gas: 1_000_000,
+            simulated_gas_price: Amount::from_mantissa_scale(1_000_000, 0),
+            call_stack: vec![ExecutionStackElement {
+                address: target_addr,
+                coins: Default::default(),
+                owned_addresses: vec![target_addr],
+            }],
+            target: ReadOnlyExecutionTarget::FunctionCall {
+                target_addr,
+                target_func: "receive".to_string(),
+                parameter: "hello".to_string(),
+            },
+            state_overrides,
+            final_state_only: true,
+        })
+    };
+    match call(vec![]) {
+        Err(ExecutionError::RuntimeErrorChain(chain)) => assert!(!chain.0.is_empty()),
+        _ => panic!("the call of a missing bytecode should fail at runtime"),
+    }
+    let output = call(vec![ReadOnlyStateOverride {
+        address: target_addr,
+        sequential_balance: None,
+        parallel_balance: Some(Amount::from_str("100").unwrap()),
+        bytecode: Some(include_bytes!("./wasm/receive_message.wasm").to_vec()),
+        datastore: vec![(b"key".to_vec(), b"value".to_vec())],
+    }])
+    .unwrap();
+    assert!(output.gas_cost > 0);
+    let events = output
+        .out
+        .events
+        .get_filtered_sc_output_events(&EventFilter::default());
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].data, "message received: hello");
+    // the overrides are not part of the resulting state changes
+    assert!(!output
+        .out
+        .state_changes
+        .ledger_changes
+        .0
+        .contains_key(&target_addr));
+    manager.stop();
+}
+
+#[test]
+#[serial]
+fn test_read_only_execution_batch() {
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+    let (mut manager, controller) = start_execution_worker(
+        ExecutionConfig::default(),
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    let (target_addr, _keypair) = get_random_address_full();
+    let request = |target| ReadOnlyExecutionRequest {
+        max_gas: 1_000_000,
+        simulated_gas_price: Amount::from_mantissa_scale(1_000_000, 0),
+        call_stack: vec![],
+        target,
+        state_overrides: vec![],
+        final_state_only: false,
+    };
+    let results = controller
+        .execute_readonly_batch(ReadOnlyExecutionBatch {
+            requests: vec![
+                request(ReadOnlyExecutionTarget::BytecodeExecution(
+                    include_bytes!("./wasm/event_test.wasm").to_vec(),
+                )),
+                request(ReadOnlyExecutionTarget::FunctionCall {
+                    target_addr,
+                    target_func: "receive".to_string(),
+                    parameter: "hello".to_string(),
+                }),
+                request(ReadOnlyExecutionTarget::BytecodeExecution(
+                    include_bytes!("./wasm/event_test.wasm").to_vec(),
+                )),
+            ],
+            share_state: true,
+        })
+        .unwrap();
+    // a failure does not prevent the next requests from being executed
+    assert_eq!(results.len(), 3);
+    assert!(results[1].is_err());
+    let first = results[0].as_ref().unwrap();
+    let last = results[2].as_ref().unwrap();
+    // all the requests are executed against the same state
+    assert_eq!(first.out.slot, last.out.slot);
+    assert_eq!(first.gas_cost, last.gas_cost);
+    manager.stop();
+}
+
+#[test]
+#[serial]
+fn test_read_only_execution_batch_gas_limit() {
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+    let (mut manager, controller) = start_execution_worker(
+        ExecutionConfig {
+            max_read_only_batch_gas: 1_500_000,
+            ..ExecutionConfig::default()
+        },
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    let batch = |count| ReadOnlyExecutionBatch {
+        requests: vec![
+            ReadOnlyExecutionRequest {
+                max_gas: 1_000_000,
+                simulated_gas_price: Amount::from_mantissa_scale(1_000_000, 0),
+                call_stack: vec![],
+                target: ReadOnlyExecutionTarget::BytecodeExecution(
+                    include_bytes!("./wasm/event_test.wasm").to_vec(),
+                ),
+                state_overrides: vec![],
+                final_state_only: false,
+            };
+            count
+        ],
+        share_state: false,
+    };
+    // a batch is refused as a whole when its requests may use too much gas in total
+    match controller.execute_readonly_batch(batch(2)) {
+        Err(ExecutionError::ReadOnlyBatchGasError(_)) => {}
+        _ => panic!("a batch above the gas limit should be refused"),
+    }
+    let results = controller.execute_readonly_batch(batch(1)).unwrap();
+    assert!(results[0].is_ok());
+    manager.stop();
+}
+
 /// Test the gas usage in nested calls using call SC operation
 ///
 /// Create a smart contract and send it in the blockclique.
@@ -167,9 +334,13 @@ fn test_nested_call_gas_usage() {
         end: Some(Slot::new(20, 1)),
         ..Default::default()
     });
-    // match the events
-    assert!(!events.is_empty(), "One event was expected");
-    let address = events[0].clone().data;
+    // match the events: the node reports the creation of the contract, then the contract emits its address
+    assert_eq!(events.len(), 2, "Two events were expected");
+    assert_eq!(
+        events[0].structured.as_ref().unwrap().topic,
+        BYTECODE_CHANGE_EVENT_TOPIC
+    );
+    let address = events[1].clone().data;
     // Call the function test of the smart contract
     let operation = create_call_sc_operation(
         &keypair,
@@ -208,6 +379,117 @@ fn test_nested_call_gas_usage() {
     manager.stop();
 }
 
+#[test]
+#[serial]
+fn bytecode_metadata_of_created_contract() {
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        ..ExecutionConfig::default()
+    };
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+    let mut storage = Storage::create_root();
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg,
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    // create a contract by executing the bytecode of `nested_call`
+    let keypair = KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let sender_addr = Address::from_public_key(&keypair.get_public_key());
+    let operation =
+        create_execute_sc_operation(&keypair, include_bytes!("./wasm/nested_call.wasm")).unwrap();
+    storage.store_operations(vec![operation.clone()]);
+    let block = create_block(KeyPair::generate(), vec![operation], Slot::new(1, 0)).unwrap();
+    storage.store_block(block.clone());
+    let mut finalized_blocks: HashMap<Slot, (BlockId, Storage)> = Default::default();
+    finalized_blocks.insert(
+        block.content.header.content.slot,
+        (block.id, storage.clone()),
+    );
+    controller.update_blockclique_status(finalized_blocks, Default::default());
+    std::thread::sleep(Duration::from_millis(10));
+
+    // the creation of the contract is reported by an event
+    let events = controller.get_filtered_sc_output_event(EventFilter {
+        topic: Some(BYTECODE_CHANGE_EVENT_TOPIC.to_string()),
+        ..Default::default()
+    });
+    assert_eq!(events.len(), 1, "One bytecode change event was expected");
+    let change = events[0].structured.as_ref().unwrap();
+    assert_eq!(change.get_field("version"), Some(&EventValue::U64(1)));
+    let contract_addr = match change.get_field("address") {
+        Some(EventValue::Address(address)) => *address,
+        _ => panic!("the bytecode change event should contain the contract address"),
+    };
+
+    // the contract has a first version of its bytecode, that can still be changed
+    let infos = controller.get_addresses_infos(&[contract_addr, sender_addr]);
+    let metadata = infos[0].final_bytecode_metadata.clone().unwrap();
+    assert_eq!(
+        metadata,
+        infos[0].candidate_bytecode_metadata.clone().unwrap()
+    );
+    assert_eq!(metadata.version, 1);
+    assert_eq!(metadata.last_change_slot, Some(Slot::new(1, 0)));
+    assert!(!metadata.immutable);
+    assert_eq!(
+        change.get_field("code_hash"),
+        Some(&EventValue::String(metadata.code_hash.to_string()))
+    );
+    // the metadata is not listed among the datastore keys
+    assert!(!infos[0]
+        .final_datastore_keys
+        .contains(BYTECODE_METADATA_KEY));
+    assert!(!infos[0]
+        .candidate_datastore_keys
+        .contains(BYTECODE_METADATA_KEY));
+    // the bytecode of the sender was never changed
+    let metadata = infos[1].final_bytecode_metadata.clone().unwrap();
+    assert_eq!(metadata.version, 0);
+    assert_eq!(metadata.last_change_slot, None);
+    manager.stop();
+}
+
+#[test]
+#[serial]
+fn no_bytecode_metadata_before_start_period() {
+    // start recording bytecode metadata after the executed block
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        bytecode_metadata_start_period: 2,
+        ..ExecutionConfig::default()
+    };
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+    let mut storage = Storage::create_root();
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg,
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    // create a contract by executing the bytecode of `nested_call`
+    let keypair = KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let operation =
+        create_execute_sc_operation(&keypair, include_bytes!("./wasm/nested_call.wasm")).unwrap();
+    storage.store_operations(vec![operation.clone()]);
+    let block = create_block(KeyPair::generate(), vec![operation], Slot::new(1, 0)).unwrap();
+    storage.store_block(block.clone());
+    let mut finalized_blocks: HashMap<Slot, (BlockId, Storage)> = Default::default();
+    finalized_blocks.insert(
+        block.content.header.content.slot,
+        (block.id, storage.clone()),
+    );
+    controller.update_blockclique_status(finalized_blocks, Default::default());
+    std::thread::sleep(Duration::from_millis(10));
+
+    // the contract was created without any bytecode change event
+    let events = controller.get_filtered_sc_output_event(EventFilter {
+        topic: Some(BYTECODE_CHANGE_EVENT_TOPIC.to_string()),
+        ..Default::default()
+    });
+    assert!(events.is_empty(), "No bytecode change event was expected");
+    manager.stop();
+}
+
 /// # Context
 ///
 /// Functional test for asynchronous messages sending and handling
@@ -276,6 +558,156 @@ fn send_and_receive_async_message() {
     // match the events
     assert!(!events.is_empty(), "One event was expected");
     assert_eq!(events[0].data, "message received: hello my good friend!");
+    // check that the message left the pool and that its outcome was recorded
+    let messages = controller.get_filtered_async_messages(Default::default());
+    assert_eq!(messages.len(), 1, "One message outcome was expected");
+    assert_eq!(messages[0].status, AsyncMessageStatus::Executed);
+    // check that only the requested page is returned
+    assert!(controller
+        .get_filtered_async_messages(AsyncMessageFilter {
+            offset: 1,
+            ..Default::default()
+        })
+        .is_empty());
+    assert!(controller
+        .get_filtered_async_messages(AsyncMessageFilter {
+            limit: Some(0),
+            ..Default::default()
+        })
+        .is_empty());
+    // check that the events generated by the message, outcome included, can be retrieved
+    let events = controller.get_filtered_sc_output_event(EventFilter {
+        original_async_message: Some(AsyncMessageOrigin {
+            emission_slot: messages[0].emission_slot,
+            emission_index: messages[0].emission_index,
+        }),
+        ..Default::default()
+    });
+    assert_eq!(events.len(), 2, "Two events were expected");
+    assert!(events[1].data.contains(ASYNC_MESSAGE_OUTCOME_EVENT_KEY));
+    // stop the execution controller
+    manager.stop();
+}
+
+#[test]
+#[serial]
+pub fn cancel_async_message() {
+    // setup the period duration
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        ..ExecutionConfig::default()
+    };
+    // get a sample final state
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+
+    // init the storage
+    let mut storage = Storage::create_root();
+    // start the execution worker
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg,
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    let sender_keypair =
+        KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let sender_address = Address::from_public_key(&sender_keypair.get_public_key());
+    let (other_address, other_keypair) = get_random_address_full();
+    // the first operation sends a message carrying 100 raw coins, emitted at slot (1, 0) with index 0
+    let send_operation =
+        create_execute_sc_operation(&sender_keypair, include_bytes!("./wasm/send_message.wasm"))
+            .unwrap();
+    // fund another address so that it can try to cancel the message
+    let fund_operation = Operation::new_wrapped(
+        Operation {
+            fee: Amount::zero(),
+            expire_period: 10,
+            op: OperationType::Transaction {
+                recipient_address: other_address,
+                amount: Amount::from_str("100").unwrap(),
+            },
+        },
+        OperationSerializer::new(),
+        &sender_keypair,
+    )
+    .unwrap();
+    // both addresses try to cancel the message, only its sender is allowed to
+    let cancel_op = OperationType::CancelAsyncMessage {
+        emission_slot: Slot::new(1, 0),
+        emission_index: 0,
+    };
+    let cancel_operations: Vec<WrappedOperation> = [&other_keypair, &sender_keypair]
+        .into_iter()
+        .map(|keypair| {
+            Operation::new_wrapped(
+                Operation {
+                    fee: Amount::zero(),
+                    expire_period: 10,
+                    op: cancel_op.clone(),
+                },
+                OperationSerializer::new(),
+                keypair,
+            )
+            .unwrap()
+        })
+        .collect();
+    let operations: Vec<WrappedOperation> = [send_operation, fund_operation]
+        .into_iter()
+        .chain(cancel_operations)
+        .collect();
+    // create the block containing the operations
+    storage.store_operations(operations.clone());
+    let block = create_block(KeyPair::generate(), operations, Slot::new(1, 0)).unwrap();
+    // store the block in storage
+    storage.store_block(block.clone());
+    // set our block as a final block so the operations are processed
+    let mut finalized_blocks: HashMap<Slot, (BlockId, Storage)> = Default::default();
+    finalized_blocks.insert(
+        block.content.header.content.slot,
+        (block.id, storage.clone()),
+    );
+    controller.update_blockclique_status(finalized_blocks, Default::default());
+    let receipt = wait_for_final_block_receipt(&*controller, &block.id);
+    assert_eq!(
+        receipt.operations[0].outcome,
+        OperationExecutionOutcome::Success
+    );
+    assert_eq!(
+        receipt.operations[1].outcome,
+        OperationExecutionOutcome::Success
+    );
+    // the cancellation requested by another address is rejected
+    match &receipt.operations[2].outcome {
+        OperationExecutionOutcome::Failure(err) => assert!(
+            err.contains("is not the sender of the message"),
+            "unexpected error: {}",
+            err
+        ),
+        outcome => panic!("unexpected outcome: {:?}", outcome),
+    }
+    // the cancellation requested by the sender succeeds
+    assert_eq!(
+        receipt.operations[3].outcome,
+        OperationExecutionOutcome::Success
+    );
+    // check that the message left the pool as cancelled
+    let messages = controller.get_filtered_async_messages(Default::default());
+    assert_eq!(messages.len(), 1, "One message outcome was expected");
+    assert_eq!(
+        messages[0].status,
+        AsyncMessageStatus::Evicted(AsyncMessageEvictionReason::Cancelled)
+    );
+    // check that the sender was refunded: its parallel balance holds the coins of the
+    // smart contract execution and the coins of the cancelled message
+    assert_eq!(
+        sample_state
+            .read()
+            .ledger
+            .get_parallel_balance(&sender_address)
+            .unwrap(),
+        Amount::from_str("10")
+            .unwrap()
+            .saturating_add(Amount::from_raw(100))
+    );
     // stop the execution controller
     manager.stop();
 }
@@ -317,6 +749,7 @@ pub fn send_and_receive_transaction() {
         &sender_keypair,
     )
     .unwrap();
+    let operation_id = operation.id;
     // create the block contaning the transaction operation
     storage.store_operations(vec![operation.clone()]);
     let block = create_block(KeyPair::generate(), vec![operation], Slot::new(1, 0)).unwrap();
@@ -339,6 +772,336 @@ pub fn send_and_receive_transaction() {
             .unwrap(),
         Amount::from_str("100").unwrap()
     );
+    // check that a structured transfer event was emitted
+    let events = controller.get_filtered_sc_output_event(EventFilter {
+        topic: Some(TRANSFER_EVENT_TOPIC.to_string()),
+        ..Default::default()
+    });
+    assert_eq!(events.len(), 1, "One transfer event was expected");
+    let transfer = events[0].structured.as_ref().unwrap();
+    assert_eq!(
+        transfer.get_field("to"),
+        Some(&EventValue::Address(recipient_address))
+    );
+    assert_eq!(
+        transfer.get_field("amount"),
+        Some(&EventValue::Amount(Amount::from_str("100").unwrap()))
+    );
+    // check the address history of the recipient: the transfer, then the operation itself
+    let history = controller.get_address_history(&recipient_address, 0, 10);
+    assert_eq!(history.len(), 2, "Two history entries were expected");
+    assert_eq!(history[0].kind, AddressHistoryEntryKind::SequentialTransfer);
+    assert_eq!(history[0].amount, Amount::from_str("100").unwrap());
+    assert_eq!(history[0].operation_id, Some(operation_id));
+    assert_eq!(history[1].kind, AddressHistoryEntryKind::Operation);
+    assert!(history.iter().all(|entry| entry.is_final));
+    // check pagination
+    let page = controller.get_address_history(&recipient_address, 1, 10);
+    assert_eq!(page, history[1..]);
+    // stop the execution controller
+    manager.stop();
+}
+
+#[test]
+#[serial]
+pub fn multisig_transaction() {
+    // setup the period duration
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        ..ExecutionConfig::default()
+    };
+    // get a sample final state
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+
+    // init the storage
+    let mut storage = Storage::create_root();
+    // start the execution worker
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg.clone(),
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    // the sender funds the multisig account and submits its proposals
+    let sender_keypair =
+        KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let sender_thread = Address::from_public_key(&sender_keypair.get_public_key())
+        .get_thread(exec_cfg.thread_count);
+    // generate a 2-of-3 multisig account in the thread of the sender
+    let (signers, descriptor) = loop {
+        let signers: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+        let descriptor =
+            MultisigDescriptor::new(2, signers.iter().map(|k| k.get_public_key()).collect())
+                .unwrap();
+        if descriptor.address().get_thread(exec_cfg.thread_count) == sender_thread {
+            break (signers, descriptor);
+        }
+    };
+    let multisig_address = descriptor.address();
+    let (recipient_address, _keypair) = get_random_address_full();
+    let proposal = MultisigProposal {
+        descriptor,
+        action: MultisigAction::Transaction {
+            recipient_address,
+            amount: Amount::from_str("40").unwrap(),
+        },
+        nonce: 0,
+        expire_period: 10,
+    };
+    let signatures = vec![
+        proposal.sign(&signers[0]).unwrap(),
+        proposal.sign(&signers[2]).unwrap(),
+    ];
+    // create the funding operation and the multisig operation, then submit the same proposal again
+    let multisig_op = OperationType::MultisigExecute {
+        proposal,
+        signatures,
+    };
+    let operations: Vec<WrappedOperation> = [
+        OperationType::Transaction {
+            recipient_address: multisig_address,
+            amount: Amount::from_str("100").unwrap(),
+        },
+        multisig_op.clone(),
+        multisig_op,
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(index, op)| {
+        Operation::new_wrapped(
+            Operation {
+                // different fees so that the replayed operation has its own ID
+                fee: Amount::from_mantissa_scale(index as u64, 9),
+                expire_period: 10,
+                op,
+            },
+            OperationSerializer::new(),
+            &sender_keypair,
+        )
+        .unwrap()
+    })
+    .collect();
+    // create the block containing the operations
+    storage.store_operations(operations.clone());
+    let block = create_block(KeyPair::generate(), operations, Slot::new(1, sender_thread)).unwrap();
+    // store the block in storage
+    storage.store_block(block.clone());
+    // set our block as a final block so the operations are processed
+    let mut finalized_blocks: HashMap<Slot, (BlockId, Storage)> = Default::default();
+    finalized_blocks.insert(
+        block.content.header.content.slot,
+        (block.id, storage.clone()),
+    );
+    controller.update_blockclique_status(finalized_blocks, Default::default());
+    std::thread::sleep(Duration::from_millis(10));
+    // check that the proposal was executed only once
+    assert_eq!(
+        sample_state
+            .read()
+            .ledger
+            .get_sequential_balance(&recipient_address)
+            .unwrap(),
+        Amount::from_str("40").unwrap()
+    );
+    assert_eq!(
+        sample_state
+            .read()
+            .ledger
+            .get_sequential_balance(&multisig_address)
+            .unwrap(),
+        Amount::from_str("60").unwrap()
+    );
+    // stop the execution controller
+    manager.stop();
+}
+
+#[test]
+#[serial]
+pub fn multisig_expired_proposal() {
+    // setup the period duration
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        ..ExecutionConfig::default()
+    };
+    // get a sample final state
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+
+    // init the storage
+    let mut storage = Storage::create_root();
+    // start the execution worker
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg.clone(),
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    let sender_keypair =
+        KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let sender_thread = Address::from_public_key(&sender_keypair.get_public_key())
+        .get_thread(exec_cfg.thread_count);
+    // generate a 1-of-1 multisig account in the thread of the sender
+    let (signer, descriptor) = loop {
+        let signer = KeyPair::generate();
+        let descriptor = MultisigDescriptor::new(1, vec![signer.get_public_key()]).unwrap();
+        if descriptor.address().get_thread(exec_cfg.thread_count) == sender_thread {
+            break (signer, descriptor);
+        }
+    };
+    let multisig_address = descriptor.address();
+    let (recipient_address, _keypair) = get_random_address_full();
+    // the proposal expired before the slot of the block, unlike the operation carrying it
+    let proposal = MultisigProposal {
+        descriptor,
+        action: MultisigAction::Transaction {
+            recipient_address,
+            amount: Amount::from_str("40").unwrap(),
+        },
+        nonce: 0,
+        expire_period: 0,
+    };
+    let signatures = vec![proposal.sign(&signer).unwrap()];
+    let operations: Vec<WrappedOperation> = [
+        OperationType::Transaction {
+            recipient_address: multisig_address,
+            amount: Amount::from_str("100").unwrap(),
+        },
+        OperationType::MultisigExecute {
+            proposal,
+            signatures,
+        },
+    ]
+    .into_iter()
+    .map(|op| {
+        Operation::new_wrapped(
+            Operation {
+                fee: Amount::zero(),
+                expire_period: 10,
+                op,
+            },
+            OperationSerializer::new(),
+            &sender_keypair,
+        )
+        .unwrap()
+    })
+    .collect();
+    // create the block containing the operations
+    storage.store_operations(operations.clone());
+    let block = create_block(KeyPair::generate(), operations, Slot::new(1, sender_thread)).unwrap();
+    // store the block in storage
+    storage.store_block(block.clone());
+    // set our block as a final block so the operations are processed
+    let mut finalized_blocks: HashMap<Slot, (BlockId, Storage)> = Default::default();
+    finalized_blocks.insert(
+        block.content.header.content.slot,
+        (block.id, storage.clone()),
+    );
+    controller.update_blockclique_status(finalized_blocks, Default::default());
+    let receipt = wait_for_final_block_receipt(&*controller, &block.id);
+    // check that the expired proposal was rejected at execution
+    assert_eq!(
+        receipt.operations[0].outcome,
+        OperationExecutionOutcome::Success
+    );
+    assert!(matches!(
+        receipt.operations[1].outcome,
+        OperationExecutionOutcome::Failure(_)
+    ));
+    assert_eq!(
+        sample_state
+            .read()
+            .ledger
+            .get_sequential_balance(&recipient_address),
+        None
+    );
+    assert_eq!(
+        sample_state
+            .read()
+            .ledger
+            .get_sequential_balance(&multisig_address)
+            .unwrap(),
+        Amount::from_str("100").unwrap()
+    );
+    // stop the execution controller
+    manager.stop();
+}
+
+#[test]
+#[serial]
+pub fn no_multisig_before_start_period() {
+    // setup the period duration and activate multisig operations after the executed block
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        multisig_start_period: 2,
+        ..ExecutionConfig::default()
+    };
+    // get a sample final state
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+
+    // init the storage
+    let mut storage = Storage::create_root();
+    // start the execution worker
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg.clone(),
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    let sender_keypair =
+        KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let sender_thread = Address::from_public_key(&sender_keypair.get_public_key())
+        .get_thread(exec_cfg.thread_count);
+    // generate a 1-of-1 multisig account in the thread of the sender
+    let (signer, descriptor) = loop {
+        let signer = KeyPair::generate();
+        let descriptor = MultisigDescriptor::new(1, vec![signer.get_public_key()]).unwrap();
+        if descriptor.address().get_thread(exec_cfg.thread_count) == sender_thread {
+            break (signer, descriptor);
+        }
+    };
+    let (recipient_address, _keypair) = get_random_address_full();
+    let proposal = MultisigProposal {
+        descriptor,
+        action: MultisigAction::Transaction {
+            recipient_address,
+            amount: Amount::from_str("40").unwrap(),
+        },
+        nonce: 0,
+        expire_period: 10,
+    };
+    let signatures = vec![proposal.sign(&signer).unwrap()];
+    let operation = Operation::new_wrapped(
+        Operation {
+            fee: Amount::zero(),
+            expire_period: 10,
+            op: OperationType::MultisigExecute {
+                proposal,
+                signatures,
+            },
+        },
+        OperationSerializer::new(),
+        &sender_keypair,
+    )
+    .unwrap();
+    // create the block containing the operation
+    storage.store_operations(vec![operation.clone()]);
+    let block = create_block(
+        KeyPair::generate(),
+        vec![operation],
+        Slot::new(1, sender_thread),
+    )
+    .unwrap();
+    // store the block in storage
+    storage.store_block(block.clone());
+    // set our block as a final block so the operation is processed
+    let mut finalized_blocks: HashMap<Slot, (BlockId, Storage)> = Default::default();
+    finalized_blocks.insert(
+        block.content.header.content.slot,
+        (block.id, storage.clone()),
+    );
+    controller.update_blockclique_status(finalized_blocks, Default::default());
+    let receipt = wait_for_final_block_receipt(&*controller, &block.id);
+    // check that the operation was skipped before the start period
+    assert!(matches!(
+        receipt.operations[0].outcome,
+        OperationExecutionOutcome::Skipped(_)
+    ));
     // stop the execution controller
     manager.stop();
 }
@@ -400,6 +1163,215 @@ pub fn roll_buy() {
     manager.stop();
 }
 
+#[test]
+#[serial]
+pub fn block_execution_receipt() {
+    // setup the period duration
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        ..ExecutionConfig::default()
+    };
+    // get a sample final state
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+
+    // init the storage
+    let mut storage = Storage::create_root();
+    // start the execution worker
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg.clone(),
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    let keypair = KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let (recipient_address, _keypair) = get_random_address_full();
+    // create a valid transaction and one that expired before the slot of the block
+    let operations: Vec<WrappedOperation> = [10, 0]
+        .into_iter()
+        .map(|expire_period| {
+            Operation::new_wrapped(
+                Operation {
+                    fee: Amount::from_str("1").unwrap(),
+                    expire_period,
+                    op: OperationType::Transaction {
+                        recipient_address,
+                        amount: Amount::from_str("10").unwrap(),
+                    },
+                },
+                OperationSerializer::new(),
+                &keypair,
+            )
+            .unwrap()
+        })
+        .collect();
+    // create the block containing the operations
+    storage.store_operations(operations.clone());
+    let block_keypair = KeyPair::generate();
+    let producer = Address::from_public_key(&block_keypair.get_public_key());
+    let block = create_block(block_keypair, operations.clone(), Slot::new(1, 0)).unwrap();
+    // store the block in storage
+    storage.store_block(block.clone());
+    // set our block as a final block so the operations are processed
+    let mut finalized_blocks: HashMap<Slot, (BlockId, Storage)> = Default::default();
+    finalized_blocks.insert(
+        block.content.header.content.slot,
+        (block.id, storage.clone()),
+    );
+    controller.update_blockclique_status(finalized_blocks, Default::default());
+    std::thread::sleep(Duration::from_millis(10));
+    // check the receipt of the block
+    let receipt = controller
+        .get_block_execution_receipt(&block.id)
+        .expect("missing block execution receipt");
+    assert!(receipt.is_final);
+    assert_eq!(receipt.slot, Slot::new(1, 0));
+    assert_eq!(receipt.producer, producer);
+    assert_eq!(receipt.operations.len(), 2);
+    assert_eq!(receipt.operations[0].operation_id, operations[0].id);
+    assert_eq!(
+        receipt.operations[0].outcome,
+        OperationExecutionOutcome::Success
+    );
+    assert_eq!(receipt.operations[0].fee, Amount::from_str("1").unwrap());
+    assert!(matches!(
+        receipt.operations[1].outcome,
+        OperationExecutionOutcome::Skipped(_)
+    ));
+    assert_eq!(receipt.operations[1].fee, Amount::zero());
+    // without endorsements, the producer gets all the block credits
+    assert_eq!(receipt.total_fees, Amount::from_str("1").unwrap());
+    assert_eq!(receipt.block_reward, exec_cfg.block_reward);
+    assert!(receipt.endorser_credits.is_empty());
+    assert!(receipt.endorsement_target_credits.is_empty());
+    assert_eq!(
+        receipt.producer_credit,
+        exec_cfg
+            .block_reward
+            .saturating_add(Amount::from_str("1").unwrap())
+    );
+    assert_eq!(
+        sample_state
+            .read()
+            .ledger
+            .get_sequential_balance(&producer)
+            .unwrap(),
+        receipt.producer_credit
+    );
+    // unknown blocks have no receipt
+    assert!(controller
+        .get_block_execution_receipt(&BlockId(Hash::compute_from(b"unknown")))
+        .is_none());
+    // stop the execution controller
+    manager.stop();
+}
+
+#[test]
+#[serial]
+pub fn unused_gas_refund() {
+    // setup the period duration
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        ..ExecutionConfig::default()
+    };
+    // get a sample final state
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+
+    // init the storage
+    let mut storage = Storage::create_root();
+    // start the execution worker
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg,
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    // create a smart contract execution paying 1 coin per unit of gas and no fee
+    let keypair = KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let operation =
+        create_execute_sc_operation(&keypair, include_bytes!("./wasm/event_test.wasm")).unwrap();
+    storage.store_operations(vec![operation.clone()]);
+    let block = create_block(KeyPair::generate(), vec![operation], Slot::new(1, 0)).unwrap();
+    // store the block in storage
+    storage.store_block(block.clone());
+    // set our block as a final block so the operation is processed
+    let mut finalized_blocks: HashMap<Slot, (BlockId, Storage)> = Default::default();
+    finalized_blocks.insert(
+        block.content.header.content.slot,
+        (block.id, storage.clone()),
+    );
+    controller.update_blockclique_status(finalized_blocks, Default::default());
+    // check that only the consumed gas was paid and the rest was refunded
+    let receipt = wait_for_final_block_receipt(&*controller, &block.id);
+    let op_receipt = &receipt.operations[0];
+    assert_eq!(op_receipt.outcome, OperationExecutionOutcome::Success);
+    assert_eq!(op_receipt.max_gas, 100_000);
+    assert!(op_receipt.gas > 0 && op_receipt.gas < op_receipt.max_gas);
+    assert_eq!(
+        op_receipt.fee,
+        Amount::from_mantissa_scale(op_receipt.gas, 0)
+    );
+    assert_eq!(
+        op_receipt.refund,
+        Amount::from_mantissa_scale(op_receipt.max_gas - op_receipt.gas, 0)
+    );
+    assert_eq!(receipt.total_fees, op_receipt.fee);
+    // check that the final gas usage is recorded in the stats
+    let stats = controller.get_stats();
+    assert_eq!(stats.final_gas_used, op_receipt.gas);
+    assert_eq!(stats.final_max_gas, op_receipt.max_gas);
+    assert_eq!(stats.final_gas_refunds, op_receipt.refund);
+    // stop the execution controller
+    manager.stop();
+}
+
+#[test]
+#[serial]
+pub fn no_gas_refund_before_start_period() {
+    // setup the period duration and start refunding gas after the executed block
+    let exec_cfg = ExecutionConfig {
+        t0: 100.into(),
+        gas_refund_start_period: 2,
+        ..ExecutionConfig::default()
+    };
+    // get a sample final state
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+
+    // init the storage
+    let mut storage = Storage::create_root();
+    // start the execution worker
+    let (mut manager, controller) = start_execution_worker(
+        exec_cfg,
+        sample_state.clone(),
+        sample_state.read().pos_state.selector.clone(),
+    );
+    // create a smart contract execution paying 1 coin per unit of gas and no fee
+    let keypair = KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let operation =
+        create_execute_sc_operation(&keypair, include_bytes!("./wasm/event_test.wasm")).unwrap();
+    storage.store_operations(vec![operation.clone()]);
+    let block = create_block(KeyPair::generate(), vec![operation], Slot::new(1, 0)).unwrap();
+    // store the block in storage
+    storage.store_block(block.clone());
+    // set our block as a final block so the operation is processed
+    let mut finalized_blocks: HashMap<Slot, (BlockId, Storage)> = Default::default();
+    finalized_blocks.insert(
+        block.content.header.content.slot,
+        (block.id, storage.clone()),
+    );
+    controller.update_blockclique_status(finalized_blocks, Default::default());
+    // check that the max gas was paid and counted, without refund
+    let receipt = wait_for_final_block_receipt(&*controller, &block.id);
+    let op_receipt = &receipt.operations[0];
+    assert_eq!(op_receipt.outcome, OperationExecutionOutcome::Success);
+    assert_eq!(op_receipt.gas, op_receipt.max_gas);
+    assert_eq!(
+        op_receipt.fee,
+        Amount::from_mantissa_scale(op_receipt.max_gas, 0)
+    );
+    assert_eq!(op_receipt.refund, Amount::zero());
+    assert_eq!(receipt.total_fees, op_receipt.fee);
+    // stop the execution controller
+    manager.stop();
+}
+
 #[test]
 #[serial]
 pub fn roll_sell() {
@@ -603,6 +1575,244 @@ fn generate_events() {
     manager.stop();
 }
 
+/// Executes the given blocks one after the other on a fresh sample state,
+/// with `parallel_execution_threads` threads executing the operations of each block.
+fn execute_blocks(
+    parallel_execution_threads: usize,
+    blocks: &[WrappedBlock],
+    storage: &Storage,
+) -> Vec<ExecutionOutput> {
+    let exec_cfg = ExecutionConfig {
+        parallel_execution_threads,
+        // the last smart contract execution of the second block does not fit in the block
+        max_gas_per_block: 400_000,
+        ..ExecutionConfig::default()
+    };
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+    let selector = sample_state.read().pos_state.selector.clone();
+    let mut execution_state = ExecutionState::new(exec_cfg, sample_state);
+    let mut outputs = Vec::new();
+    for block in blocks {
+        let exec_out = execution_state.execute_slot(
+            block.content.header.content.slot,
+            Some((block.id, storage.clone())),
+            &selector,
+        );
+        execution_state.apply_active_execution_output(exec_out.clone());
+        outputs.push(exec_out);
+    }
+    outputs
+}
+
+/// Differential test: executing the operations of blocks in parallel
+/// must give exactly the same output as executing them sequentially.
+#[test]
+#[serial]
+fn parallel_execution_matches_sequential() {
+    let mut storage = Storage::create_root();
+    let funded_keypair =
+        KeyPair::from_str("S1JJeHiZv1C1zZN5GLFcbz6EXYiccmUPLkYuDFA3kayjxP39kFQ").unwrap();
+    let funded_thread =
+        Address::from_public_key(&funded_keypair.get_public_key()).get_thread(THREAD_COUNT);
+    // generate senders in the thread of the funded address
+    let senders: Vec<(Address, KeyPair)> = std::iter::repeat_with(get_random_address_full)
+        .filter(|(address, _)| address.get_thread(THREAD_COUNT) == funded_thread)
+        .take(5)
+        .collect();
+    let (unfunded_address, unfunded_keypair) = senders[4].clone();
+    let transaction = |sender: &KeyPair, recipient_address: Address, amount: &str| {
+        Operation::new_wrapped(
+            Operation {
+                fee: Amount::from_str("1").unwrap(),
+                expire_period: 10,
+                op: OperationType::Transaction {
+                    recipient_address,
+                    amount: Amount::from_str(amount).unwrap(),
+                },
+            },
+            OperationSerializer::new(),
+            sender,
+        )
+        .unwrap()
+    };
+    let execute_sc_with_gas = |sender: &KeyPair, data: &[u8], max_gas: u64| {
+        Operation::new_wrapped(
+            Operation {
+                fee: Amount::from_str("1").unwrap(),
+                expire_period: 10,
+                op: OperationType::ExecuteSC {
+                    data: data.to_vec(),
+                    max_gas,
+                    coins: Amount::from_str("10").unwrap(),
+                    gas_price: Amount::zero(),
+                },
+            },
+            OperationSerializer::new(),
+            sender,
+        )
+        .unwrap()
+    };
+    let execute_sc = |sender: &KeyPair, data: &[u8]| execute_sc_with_gas(sender, data, 100_000);
+
+    // first block: fund the senders, except the last one
+    let funding: Vec<_> = senders[..4]
+        .iter()
+        .map(|(address, _)| transaction(&funded_keypair, *address, "2000"))
+        .collect();
+
+    // second block: independent, shared and dependent transfers, smart contract executions
+    // creating addresses and emitting messages, a failing execution, a roll purchase,
+    // an operation paying no fee and a smart contract execution exceeding the block gas
+    // (only the gas actually consumed is deducted from the block gas, but a failed execution
+    // consumes all of its max gas, so at most 300_000 gas remains for the last operation)
+    let (recipient, _) = get_random_address_full();
+    let operations = vec![
+        transaction(&senders[0].1, recipient, "10"),
+        transaction(&senders[1].1, recipient, "20"),
+        transaction(&senders[2].1, senders[3].0, "30"),
+        transaction(&senders[3].1, senders[0].0, "2010"),
+        execute_sc(&senders[0].1, include_bytes!("./wasm/event_test.wasm")),
+        execute_sc(&senders[1].1, include_bytes!("./wasm/send_message.wasm")),
+        execute_sc(&senders[2].1, include_bytes!("./wasm/send_message.wasm")),
+        Operation::new_wrapped(
+            Operation {
+                fee: Amount::zero(),
+                expire_period: 10,
+                op: OperationType::RollBuy { roll_count: 1 },
+            },
+            OperationSerializer::new(),
+            &senders[1].1,
+        )
+        .unwrap(),
+        transaction(&unfunded_keypair, unfunded_address, "1"),
+        execute_sc(&senders[3].1, include_bytes!("./wasm/execution_error.wasm")),
+        execute_sc_with_gas(
+            &senders[2].1,
+            include_bytes!("./wasm/event_test.wasm"),
+            350_000,
+        ),
+    ];
+    storage.store_operations(funding.clone());
+    storage.store_operations(operations.clone());
+    let blocks = vec![
+        create_block(KeyPair::generate(), funding, Slot::new(1, funded_thread)).unwrap(),
+        create_block(KeyPair::generate(), operations, Slot::new(2, funded_thread)).unwrap(),
+    ];
+    for block in &blocks {
+        storage.store_block(block.clone());
+    }
+
+    let sequential = execute_blocks(0, &blocks, &storage);
+    let parallel = execute_blocks(4, &blocks, &storage);
+
+    // check that the second block covers every kind of outcome
+    let outcomes: Vec<_> = sequential[1]
+        .block_receipt
+        .as_ref()
+        .unwrap()
+        .operations
+        .iter()
+        .map(|receipt| receipt.outcome.clone())
+        .collect();
+    assert_eq!(outcomes[3], OperationExecutionOutcome::Success);
+    assert!(matches!(outcomes[8], OperationExecutionOutcome::Skipped(_)));
+    assert!(matches!(outcomes[9], OperationExecutionOutcome::Failure(_)));
+    assert!(matches!(
+        outcomes[10],
+        OperationExecutionOutcome::Skipped(_)
+    ));
+
+    for (mut seq, mut par) in sequential.into_iter().zip(parallel) {
+        assert_eq!(seq.slot, par.slot);
+        assert_eq!(seq.block_id, par.block_id);
+        let (seq_changes, par_changes) = (&seq.state_changes, &par.state_changes);
+        assert_eq!(seq_changes.ledger_changes, par_changes.ledger_changes);
+        assert_eq!(
+            seq_changes.async_pool_changes,
+            par_changes.async_pool_changes
+        );
+        assert_eq!(seq_changes.executed_ops, par_changes.executed_ops);
+        let (seq_pos, par_pos) = (&seq_changes.pos_changes, &par_changes.pos_changes);
+        assert_eq!(seq_pos.seed_bits, par_pos.seed_bits);
+        assert_eq!(seq_pos.roll_changes, par_pos.roll_changes);
+        assert_eq!(seq_pos.production_stats, par_pos.production_stats);
+        assert_eq!(seq_pos.deferred_credits.0, par_pos.deferred_credits.0);
+        assert_eq!(
+            serde_json::to_value(seq.events.take()).unwrap(),
+            serde_json::to_value(par.events.take()).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&seq.async_message_outcomes).unwrap(),
+            serde_json::to_value(&par.async_message_outcomes).unwrap()
+        );
+        assert_eq!(seq.address_history, par.address_history);
+        assert_eq!(
+            serde_json::to_value(&seq.block_receipt).unwrap(),
+            serde_json::to_value(&par.block_receipt).unwrap()
+        );
+    }
+}
+
+#[test]
+#[serial]
+fn structured_event_emitted_by_contract() {
+    let exec_cfg = ExecutionConfig::default();
+    let (sample_state, _keep_file, _keep_dir) = get_sample_state().unwrap();
+    let context = Arc::new(Mutex::new(ExecutionContext::new(
+        exec_cfg.clone(),
+        sample_state,
+        Arc::new(RwLock::new(ActiveHistory::default())),
+    )));
+    // a contract emits events through the `generate_event` function of the interface
+    let interface = InterfaceImpl::new(exec_cfg, context.clone());
+    let structured = StructuredEvent::with_fields(
+        "token.transfer",
+        vec![("amount".to_string(), EventValue::U64(42))],
+    );
+    interface
+        .generate_event(structured.to_event_data().unwrap())
+        .unwrap();
+    interface.generate_event("plain text".to_string()).unwrap();
+    // contracts cannot emit events with the topics of the node
+    let spoofed = StructuredEvent::with_fields(TRANSFER_EVENT_TOPIC, Vec::new());
+    assert!(interface
+        .generate_event(spoofed.to_event_data().unwrap())
+        .is_err());
+    // check that the structured event can be filtered by topic
+    let events = context.lock().events.clone();
+    let filtered = events.get_filtered_sc_output_events(&EventFilter {
+        topic: Some("token.transfer".to_string()),
+        ..Default::default()
+    });
+    assert_eq!(filtered.len(), 1, "One structured event was expected");
+    assert_eq!(filtered[0].structured, Some(structured));
+    let events = context.lock().events.take();
+    assert_eq!(events.len(), 2, "Two events were expected");
+    assert_eq!(events[1].data, "plain text");
+    assert!(events[1].structured.is_none());
+}
+
+/// Waits until the execution of a block is final and returns its receipt.
+/// Panics if it takes too long.
+fn wait_for_final_block_receipt(
+    controller: &dyn ExecutionController,
+    block_id: &BlockId,
+) -> BlockExecutionReceipt {
+    let start = Instant::now();
+    loop {
+        if let Some(receipt) = controller.get_block_execution_receipt(block_id) {
+            if receipt.is_final {
+                return receipt;
+            }
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "the block was not executed as final in time"
+        );
+        std::thread::sleep(Duration::from_millis(5));
+    }
+}
+
 /// Create an operation for the given sender with `data` as bytecode.
 /// Return a result that should be unwrapped in the root `#[test]` routine.
 fn create_execute_sc_operation(
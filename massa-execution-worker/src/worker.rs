@@ -9,8 +9,8 @@ use crate::controller::{ExecutionControllerImpl, ExecutionInputData, ExecutionMa
 use crate::execution::ExecutionState;
 use crate::request_queue::RequestQueue;
 use massa_execution_exports::{
-    ExecutionConfig, ExecutionController, ExecutionError, ExecutionManager, ExecutionOutput,
-    ReadOnlyExecutionRequest,
+    ExecutionConfig, ExecutionController, ExecutionError, ExecutionManager, ReadOnlyExecutionBatch,
+    ReadOnlyExecutionOutput,
 };
 use massa_final_state::FinalState;
 use massa_models::block::BlockId;
@@ -48,7 +48,8 @@ pub(crate) struct ExecutionThread {
     // Execution state (see execution.rs) to which execution requests are sent
     execution_state: Arc<RwLock<ExecutionState>>,
     /// queue for read-only requests and response MPSCs to send back their outputs
-    readonly_requests: RequestQueue<ReadOnlyExecutionRequest, ExecutionOutput>,
+    readonly_requests:
+        RequestQueue<ReadOnlyExecutionBatch, Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>>,
     /// Selector controller
     selector: Box<dyn SelectorController>,
 }
@@ -416,13 +417,46 @@ impl ExecutionThread {
     /// Cancel those that are in excess if there are too many.
     fn update_readonly_requests(
         &mut self,
-        new_requests: RequestQueue<ReadOnlyExecutionRequest, ExecutionOutput>,
+        new_requests: RequestQueue<
+            ReadOnlyExecutionBatch,
+            Vec<Result<ReadOnlyExecutionOutput, ExecutionError>>,
+        >,
     ) {
         // Append incoming readonly requests to our readonly request queue
         // Excess requests are cancelled
         self.readonly_requests.extend(new_requests);
     }
 
+    /// Runs a batch of read-only execution requests, in order.
+    /// The read access to the execution state is only held during each request,
+    /// so that other readers are not kept waiting by a long batch.
+    /// Slots are executed by this thread only, so all the requests still see the same state.
+    /// If the batch shares its state, the outputs of the successful requests
+    /// are seen by the following ones.
+    ///
+    /// # Returns
+    /// The outcome of each request, in the order of the requests
+    fn execute_readonly_batch(
+        &self,
+        batch: ReadOnlyExecutionBatch,
+    ) -> Vec<Result<ReadOnlyExecutionOutput, ExecutionError>> {
+        let mut shared_outputs = Vec::new();
+        let mut results = Vec::with_capacity(batch.requests.len());
+        for req in batch.requests {
+            let result = self
+                .execution_state
+                .read()
+                .execute_readonly_request(req, &shared_outputs);
+            if batch.share_state {
+                if let Ok(output) = &result {
+                    shared_outputs.push(output.out.clone());
+                }
+            }
+            results.push(result);
+        }
+        results
+    }
+
     /// Executes a read-only request from the queue, if any.
     /// The result of the execution is sent asynchronously through the response channel provided with the request.
     ///
@@ -430,10 +464,21 @@ impl ExecutionThread {
     /// true if a request was executed, false otherwise
     fn execute_one_readonly_request(&mut self) -> bool {
         if let Some(req_resp) = self.readonly_requests.pop() {
-            let (req, resp_tx) = req_resp.into_request_sender_pair();
-
-            // Acquire read access to the execution state and execute the read-only request
-            let outcome = self.execution_state.read().execute_readonly_request(req);
+            let (batch, resp_tx) = req_resp.into_request_sender_pair();
+
+            // refuse batches whose requests may consume too much gas in total
+            let batch_gas = batch
+                .requests
+                .iter()
+                .fold(0u64, |total, req| total.saturating_add(req.max_gas));
+            let outcome = if batch_gas > self.config.max_read_only_batch_gas {
+                Err(ExecutionError::ReadOnlyBatchGasError(format!(
+                    "the requests of the batch have {} max gas in total, above the limit of {}",
+                    batch_gas, self.config.max_read_only_batch_gas
+                )))
+            } else {
+                Ok(self.execute_readonly_batch(batch))
+            };
 
             // Send the execution output through resp_tx.
             // Ignore errors because they just mean that the request emitter dropped the received
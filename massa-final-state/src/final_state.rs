@@ -10,7 +10,8 @@ use crate::{
     ExecutedOpsStreamingStep,
 };
 use massa_async_pool::{AsyncMessageId, AsyncPool, AsyncPoolChanges, Change};
-use massa_ledger_exports::{LedgerChanges, LedgerController};
+use massa_hash::Hash;
+use massa_ledger_exports::{LedgerChanges, LedgerController, LedgerProofs, LedgerSubEntry};
 use massa_models::{address::Address, slot::Slot};
 use massa_pos_exports::{PoSCycleStreamingStep, PoSFinalState, SelectorController};
 use std::collections::VecDeque;
@@ -32,6 +33,9 @@ pub struct FinalState {
     /// history of recent final state changes, useful for streaming bootstrap
     /// `front = oldest`, `back = newest`
     pub changes_history: VecDeque<(Slot, StateChanges)>,
+    /// roots of the ledger Merkle tree at the output of recent final slots
+    /// `front = oldest`, `back = newest`
+    pub ledger_roots_history: VecDeque<(Slot, Hash)>,
 }
 
 impl FinalState {
@@ -72,6 +76,7 @@ impl FinalState {
             config,
             executed_ops,
             changes_history: Default::default(), // no changes in history
+            ledger_roots_history: Default::default(),
         })
     }
 
@@ -116,6 +121,37 @@ impl FinalState {
                 self.changes_history.pop_front();
             }
             self.changes_history.push_back((slot, changes));
+            while self.ledger_roots_history.len() >= self.config.final_history_length {
+                self.ledger_roots_history.pop_front();
+            }
+            self.ledger_roots_history
+                .push_back((slot, self.ledger.get_ledger_root()));
+        }
+    }
+
+    /// Gets the root of the ledger Merkle tree at the output of a recent final slot
+    ///
+    /// # Returns
+    /// `None` if the slot is not final yet or too old for `self.ledger_roots_history`
+    pub fn get_ledger_root_at(&self, slot: Slot) -> Option<Hash> {
+        if slot == self.slot {
+            return Some(self.ledger.get_ledger_root());
+        }
+        self.ledger_roots_history
+            .iter()
+            .rev()
+            .find(|(history_slot, _)| *history_slot == slot)
+            .map(|(_, root)| *root)
+    }
+
+    /// Gets proofs of the values of ledger sub-entries, or of their absence,
+    /// along with the final slot and the ledger root they are proven against
+    pub fn get_ledger_proofs(&self, entries: &[(Address, LedgerSubEntry)]) -> LedgerProofs {
+        let (root, proofs) = self.ledger.get_ledger_proofs(entries);
+        LedgerProofs {
+            slot: self.slot,
+            root,
+            proofs,
         }
     }
 
@@ -27,6 +27,7 @@ pub fn create_final_state(
         ledger,
         async_pool,
         changes_history,
+        ledger_roots_history: Default::default(),
         pos_state,
         executed_ops,
     }
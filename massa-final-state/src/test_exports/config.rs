@@ -36,6 +36,7 @@ impl FinalState {
             async_pool,
             config,
             changes_history: Default::default(), // no changes in history
+            ledger_roots_history: Default::default(),
             pos_state,
             executed_ops,
         }
@@ -209,6 +209,7 @@ impl ExportActiveBlockDeserializer {
                 endorsement_count,
             )),
             wrapped_operation_deserializer: WrappedDeserializer::new(OperationDeserializer::new(
+                thread_count,
                 max_datastore_value_length,
                 max_function_name_length,
                 max_parameters_size,
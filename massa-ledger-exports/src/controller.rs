@@ -1,8 +1,9 @@
+use massa_hash::Hash;
 use massa_models::{address::Address, amount::Amount, error::ModelsError, slot::Slot};
 use std::collections::BTreeSet;
 use std::fmt::Debug;
 
-use crate::{LedgerChanges, LedgerError};
+use crate::{LedgerChanges, LedgerError, LedgerProof, LedgerSubEntry};
 
 pub trait LedgerController: Send + Sync + Debug {
     /// Allows applying `LedgerChanges` to the final ledger
@@ -61,6 +62,22 @@ pub trait LedgerController: Send + Sync + Debug {
     /// A BTreeSet of the datastore keys
     fn get_datastore_keys(&self, addr: &Address) -> BTreeSet<Vec<u8>>;
 
+    /// Get the root of the ledger Merkle tree
+    ///
+    /// # Returns
+    /// The root hash, which is the empty hash for an empty ledger
+    fn get_ledger_root(&self) -> Hash;
+
+    /// Get the root of the ledger Merkle tree and proofs of the values of sub-entries,
+    /// or of their absence, all read from a single snapshot of the ledger
+    ///
+    /// # Arguments
+    /// * `entries`: target addresses and queried sub-entries
+    ///
+    /// # Returns
+    /// The root and one proof per queried sub-entry, checkable against that root
+    fn get_ledger_proofs(&self, entries: &[(Address, LedgerSubEntry)]) -> (Hash, Vec<LedgerProof>);
+
     /// Get a part of the ledger
     /// Used for bootstrap
     /// Return: Tuple with data and last key
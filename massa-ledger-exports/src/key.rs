@@ -4,6 +4,7 @@ use massa_models::{
 };
 use massa_serialization::{DeserializeError, Deserializer, SerializeError, Serializer};
 use nom::error::{ContextError, ParseError};
+use serde::{Deserialize, Serialize};
 use std::ops::Bound::Included;
 
 pub const SEQ_BALANCE_IDENT: u8 = 0u8;
@@ -55,6 +56,31 @@ macro_rules! data_prefix {
     };
 }
 
+/// Ledger sub entry enum
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LedgerSubEntry {
+    /// Sequential Balance
+    SeqBalance,
+    /// Parallel Balance
+    ParBalance,
+    /// Bytecode
+    Bytecode,
+    /// Datastore entry
+    Datastore(Vec<u8>),
+}
+
+impl LedgerSubEntry {
+    /// Get the ledger key of this sub-entry for a given address
+    pub fn key(&self, addr: &Address) -> Vec<u8> {
+        match self {
+            LedgerSubEntry::SeqBalance => seq_balance_key!(addr),
+            LedgerSubEntry::ParBalance => par_balance_key!(addr),
+            LedgerSubEntry::Bytecode => bytecode_key!(addr),
+            LedgerSubEntry::Datastore(key) => data_key!(addr, key),
+        }
+    }
+}
+
 /// Extract an address from a key
 pub fn get_address_from_key(key: &[u8]) -> Option<Address> {
     let address_deserializer = AddressDeserializer::new();
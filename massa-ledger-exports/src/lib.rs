@@ -10,14 +10,16 @@ mod error;
 mod key;
 mod ledger_changes;
 mod ledger_entry;
+mod merkle;
+mod proofs;
 mod types;
 
 pub use config::LedgerConfig;
 pub use controller::LedgerController;
 pub use error::LedgerError;
 pub use key::{
-    get_address_from_key, KeyDeserializer, KeySerializer, BYTECODE_IDENT, DATASTORE_IDENT,
-    PAR_BALANCE_IDENT, SEQ_BALANCE_IDENT,
+    get_address_from_key, KeyDeserializer, KeySerializer, LedgerSubEntry, BYTECODE_IDENT,
+    DATASTORE_IDENT, PAR_BALANCE_IDENT, SEQ_BALANCE_IDENT,
 };
 pub use ledger_changes::{
     DatastoreUpdateDeserializer, DatastoreUpdateSerializer, LedgerChanges,
@@ -28,6 +30,14 @@ pub use ledger_entry::{
     DatastoreDeserializer, DatastoreSerializer, LedgerEntry, LedgerEntryDeserializer,
     LedgerEntrySerializer,
 };
+pub use merkle::{
+    merkle_empty_hash, merkle_leaf_hash, merkle_node_hash, merkle_path, merkle_path_bit,
+    LedgerProof, LedgerProofs, MerkleNode, MerklePath, MERKLE_DEPTH,
+};
+pub use proofs::{
+    LedgerProofDeserializer, LedgerProofSerializer, LedgerProofsDeserializer,
+    LedgerProofsSerializer,
+};
 pub use types::{Applicable, SetOrDelete, SetOrKeep, SetUpdateOrDelete};
 
 #[cfg(feature = "testing")]
@@ -0,0 +1,190 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! This file defines the sparse Merkle tree committing to the content of the ledger.
+//!
+//! Every ledger key is placed along the path given by the hash of the key,
+//! so the shape of the tree does not depend on the order of insertion.
+//! The tree is compressed: a subtree containing a single key is replaced by a leaf
+//! placed at the root of that subtree, and empty subtrees hash to zero.
+//! With random paths, a key is therefore found at a depth of about `log2(N)`.
+
+use massa_hash::{Hash, HASH_SIZE_BYTES};
+use massa_models::slot::Slot;
+use serde::{Deserialize, Serialize};
+
+/// Maximal depth of the ledger Merkle tree: one level per bit of the path
+pub const MERKLE_DEPTH: usize = HASH_SIZE_BYTES * 8;
+
+/// Path of a ledger key in the Merkle tree
+pub type MerklePath = [u8; HASH_SIZE_BYTES];
+
+/// Hash of an empty subtree
+pub fn merkle_empty_hash() -> Hash {
+    Hash::from_bytes(&[0u8; HASH_SIZE_BYTES])
+}
+
+/// Path of a ledger key in the Merkle tree
+pub fn merkle_path(key: &[u8]) -> MerklePath {
+    Hash::compute_from(key).into_bytes()
+}
+
+/// Bit of a path selecting the child of a node at the given depth: 0 for left, 1 for right
+pub fn merkle_path_bit(path: &MerklePath, depth: usize) -> u8 {
+    (path[depth / 8] >> (7 - depth % 8)) & 1
+}
+
+/// Hash of a leaf from the path of its key and the hash of its value.
+///
+/// It does not depend on the depth of the leaf, which changes as keys are added or removed.
+pub fn merkle_leaf_hash(path: &MerklePath, value_hash: &Hash) -> Hash {
+    Hash::compute_from(&[&[0u8][..], path, value_hash.to_bytes()].concat())
+}
+
+/// Hash of an inner node from the hashes of its children
+///
+/// ## Example
+/// ```
+/// use massa_hash::Hash;
+/// use massa_ledger_exports::{merkle_empty_hash, merkle_node_hash};
+///
+/// let empty = merkle_empty_hash();
+/// let hash = Hash::compute_from(b"child");
+/// assert_ne!(merkle_node_hash(&hash, &empty), merkle_node_hash(&empty, &hash));
+/// ```
+pub fn merkle_node_hash(left: &Hash, right: &Hash) -> Hash {
+    Hash::compute_from(&[&[1u8][..], left.to_bytes(), right.to_bytes()].concat())
+}
+
+/// Node of the ledger Merkle tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MerkleNode {
+    /// subtree without any key
+    Empty,
+    /// subtree containing a single key
+    Leaf {
+        /// path of the key
+        path: MerklePath,
+        /// hash of the value of the key
+        value_hash: Hash,
+    },
+    /// subtree containing several keys
+    Inner(Hash),
+}
+
+impl MerkleNode {
+    /// Hash of the node
+    pub fn hash(&self) -> Hash {
+        match self {
+            MerkleNode::Empty => merkle_empty_hash(),
+            MerkleNode::Leaf { path, value_hash } => merkle_leaf_hash(path, value_hash),
+            MerkleNode::Inner(hash) => *hash,
+        }
+    }
+
+    /// Serializes a non-empty node: a tag byte followed by its content
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            MerkleNode::Empty => Vec::new(),
+            MerkleNode::Leaf { path, value_hash } => {
+                [&[0u8][..], path, value_hash.to_bytes()].concat()
+            }
+            MerkleNode::Inner(hash) => [&[1u8][..], hash.to_bytes()].concat(),
+        }
+    }
+
+    /// Deserializes a node written by `to_bytes`
+    ///
+    /// ## Example
+    /// ```
+    /// use massa_hash::Hash;
+    /// use massa_ledger_exports::{merkle_path, MerkleNode};
+    ///
+    /// let leaf = MerkleNode::Leaf {
+    ///     path: merkle_path(b"key"),
+    ///     value_hash: Hash::compute_from(b"value"),
+    /// };
+    /// assert_eq!(MerkleNode::from_bytes(&leaf.to_bytes()), Some(leaf));
+    /// assert_eq!(MerkleNode::from_bytes(&[2u8; 33]), None);
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes.split_first()? {
+            (0, rest) if rest.len() == 2 * HASH_SIZE_BYTES => {
+                let (path, value_hash) = rest.split_at(HASH_SIZE_BYTES);
+                Some(MerkleNode::Leaf {
+                    path: path.try_into().ok()?,
+                    value_hash: Hash::from_bytes(value_hash.try_into().ok()?),
+                })
+            }
+            (1, rest) => Some(MerkleNode::Inner(Hash::from_bytes(rest.try_into().ok()?))),
+            _ => None,
+        }
+    }
+}
+
+/// Proof that a ledger key has a given value, or no value, under a ledger root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerProof {
+    /// ledger key
+    pub key: Vec<u8>,
+    /// value of the key, `None` for a proof of exclusion
+    pub value: Option<Vec<u8>>,
+    /// hashes of the siblings along the path of the key, from the root down to the
+    /// node where the path ends, which is at the depth given by the number of siblings
+    pub siblings: Vec<Hash>,
+    /// for a proof of exclusion, the path and value hash of the leaf of another key
+    /// found where the path ends, `None` if the path ends on an empty subtree
+    pub other_leaf: Option<(MerklePath, Hash)>,
+}
+
+impl LedgerProof {
+    /// Computes the root of the tree from the node where the path ends and its siblings
+    ///
+    /// # Returns
+    /// `None` if the proof is malformed
+    pub fn compute_root(&self) -> Option<Hash> {
+        let path = merkle_path(&self.key);
+        let end_depth = self.siblings.len();
+        if end_depth > MERKLE_DEPTH {
+            return None;
+        }
+        let mut hash = match (&self.value, &self.other_leaf) {
+            (Some(value), None) => merkle_leaf_hash(&path, &Hash::compute_from(value)),
+            (None, None) => merkle_empty_hash(),
+            (None, Some((other_path, value_hash))) => {
+                // the other leaf must be in the subtree where the path ends
+                if *other_path == path
+                    || (0..end_depth).any(|depth| {
+                        merkle_path_bit(other_path, depth) != merkle_path_bit(&path, depth)
+                    })
+                {
+                    return None;
+                }
+                merkle_leaf_hash(other_path, value_hash)
+            }
+            (Some(_), Some(_)) => return None,
+        };
+        for (depth, sibling) in self.siblings.iter().enumerate().rev() {
+            hash = match merkle_path_bit(&path, depth) {
+                0 => merkle_node_hash(&hash, sibling),
+                _ => merkle_node_hash(sibling, &hash),
+            };
+        }
+        Some(hash)
+    }
+
+    /// Checks the proof against a ledger root
+    pub fn verify(&self, root: &Hash) -> bool {
+        self.compute_root().as_ref() == Some(root)
+    }
+}
+
+/// Proofs of ledger sub-entries along with the root they were computed for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerProofs {
+    /// final slot of the ledger
+    pub slot: Slot,
+    /// root of the ledger Merkle tree at that slot
+    pub root: Hash,
+    /// one proof per queried sub-entry
+    pub proofs: Vec<LedgerProof>,
+}
@@ -0,0 +1,317 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! This file defines the serialization of the ledger proofs sent to light clients.
+
+use crate::merkle::{LedgerProof, LedgerProofs, MerklePath, MERKLE_DEPTH};
+use massa_hash::{Hash, HashDeserializer};
+use massa_models::address::ADDRESS_SIZE_BYTES;
+use massa_models::serialization::{VecU8Deserializer, VecU8Serializer};
+use massa_models::slot::{SlotDeserializer, SlotSerializer};
+use massa_serialization::{
+    Deserializer, OptionDeserializer, OptionSerializer, SerializeError, Serializer,
+    U32VarIntDeserializer, U32VarIntSerializer,
+};
+use nom::error::{context, ContextError, ParseError};
+use nom::multi::length_count;
+use nom::sequence::tuple;
+use nom::{IResult, Parser};
+use std::ops::Bound::{Excluded, Included};
+
+/// Serializer for the leaf of another key in a `LedgerProof`
+struct OtherLeafSerializer;
+
+impl Serializer<(MerklePath, Hash)> for OtherLeafSerializer {
+    fn serialize(
+        &self,
+        (path, value_hash): &(MerklePath, Hash),
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        buffer.extend(path);
+        buffer.extend(value_hash.to_bytes());
+        Ok(())
+    }
+}
+
+/// Deserializer for the leaf of another key in a `LedgerProof`
+struct OtherLeafDeserializer {
+    hash_deserializer: HashDeserializer,
+}
+
+impl Deserializer<(MerklePath, Hash)> for OtherLeafDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], (MerklePath, Hash), E> {
+        tuple((
+            context("Failed path deserialization", |input| {
+                self.hash_deserializer.deserialize(input)
+            }),
+            context("Failed value_hash deserialization", |input| {
+                self.hash_deserializer.deserialize(input)
+            }),
+        ))
+        .map(|(path, value_hash)| (path.into_bytes(), value_hash))
+        .parse(buffer)
+    }
+}
+
+/// Serializer for `LedgerProof`
+pub struct LedgerProofSerializer {
+    vec_u8_serializer: VecU8Serializer,
+    value_serializer: OptionSerializer<Vec<u8>, VecU8Serializer>,
+    u32_serializer: U32VarIntSerializer,
+    other_leaf_serializer: OptionSerializer<(MerklePath, Hash), OtherLeafSerializer>,
+}
+
+impl LedgerProofSerializer {
+    /// Creates a new `LedgerProofSerializer`
+    pub fn new() -> Self {
+        Self {
+            vec_u8_serializer: VecU8Serializer::new(),
+            value_serializer: OptionSerializer::new(VecU8Serializer::new()),
+            u32_serializer: U32VarIntSerializer::new(),
+            other_leaf_serializer: OptionSerializer::new(OtherLeafSerializer),
+        }
+    }
+}
+
+impl Default for LedgerProofSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<LedgerProof> for LedgerProofSerializer {
+    fn serialize(&self, value: &LedgerProof, buffer: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.vec_u8_serializer.serialize(&value.key, buffer)?;
+        self.value_serializer.serialize(&value.value, buffer)?;
+        let sibling_count: u32 = value.siblings.len().try_into().map_err(|err| {
+            SerializeError::GeneralError(format!("too many siblings in LedgerProof: {}", err))
+        })?;
+        self.u32_serializer.serialize(&sibling_count, buffer)?;
+        for sibling in value.siblings.iter() {
+            buffer.extend(sibling.to_bytes());
+        }
+        self.other_leaf_serializer
+            .serialize(&value.other_leaf, buffer)?;
+        Ok(())
+    }
+}
+
+/// Deserializer for `LedgerProof`
+pub struct LedgerProofDeserializer {
+    key_deserializer: VecU8Deserializer,
+    value_deserializer: OptionDeserializer<Vec<u8>, VecU8Deserializer>,
+    sibling_count_deserializer: U32VarIntDeserializer,
+    hash_deserializer: HashDeserializer,
+    other_leaf_deserializer: OptionDeserializer<(MerklePath, Hash), OtherLeafDeserializer>,
+}
+
+impl LedgerProofDeserializer {
+    /// Creates a new `LedgerProofDeserializer`
+    pub fn new(max_datastore_key_length: u8, max_datastore_value_length: u64) -> Self {
+        Self {
+            // address, then sub-entry identifier and datastore key
+            key_deserializer: VecU8Deserializer::new(
+                Included(u64::MIN),
+                Included(ADDRESS_SIZE_BYTES as u64 + 1 + max_datastore_key_length as u64),
+            ),
+            value_deserializer: OptionDeserializer::new(VecU8Deserializer::new(
+                Included(u64::MIN),
+                Included(max_datastore_value_length),
+            )),
+            sibling_count_deserializer: U32VarIntDeserializer::new(
+                Included(u32::MIN),
+                Included(MERKLE_DEPTH as u32),
+            ),
+            hash_deserializer: HashDeserializer::new(),
+            other_leaf_deserializer: OptionDeserializer::new(OtherLeafDeserializer {
+                hash_deserializer: HashDeserializer::new(),
+            }),
+        }
+    }
+}
+
+impl Deserializer<LedgerProof> for LedgerProofDeserializer {
+    /// ## Example
+    /// ```rust
+    /// use massa_hash::Hash;
+    /// use massa_ledger_exports::{merkle_path, LedgerProof, LedgerProofDeserializer, LedgerProofSerializer};
+    /// use massa_serialization::{Serializer, Deserializer, DeserializeError};
+    ///
+    /// let proof = LedgerProof {
+    ///     key: b"key".to_vec(),
+    ///     value: None,
+    ///     siblings: vec![Hash::compute_from(b"sibling")],
+    ///     other_leaf: Some((merkle_path(b"other"), Hash::compute_from(b"value"))),
+    /// };
+    /// let mut buffer = Vec::new();
+    /// LedgerProofSerializer::new().serialize(&proof, &mut buffer).unwrap();
+    /// let (rest, deserialized) = LedgerProofDeserializer::new(255, 1000)
+    ///     .deserialize::<DeserializeError>(&buffer)
+    ///     .unwrap();
+    /// assert!(rest.is_empty());
+    /// assert_eq!(deserialized, proof);
+    /// ```
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], LedgerProof, E> {
+        context(
+            "Failed LedgerProof deserialization",
+            tuple((
+                context("Failed key deserialization", |input| {
+                    self.key_deserializer.deserialize(input)
+                }),
+                context("Failed value deserialization", |input| {
+                    self.value_deserializer.deserialize(input)
+                }),
+                context(
+                    "Failed siblings deserialization",
+                    length_count(
+                        |input| self.sibling_count_deserializer.deserialize(input),
+                        |input| self.hash_deserializer.deserialize(input),
+                    ),
+                ),
+                context("Failed other_leaf deserialization", |input| {
+                    self.other_leaf_deserializer.deserialize(input)
+                }),
+            )),
+        )
+        .map(|(key, value, siblings, other_leaf)| LedgerProof {
+            key,
+            value,
+            siblings,
+            other_leaf,
+        })
+        .parse(buffer)
+    }
+}
+
+/// Serializer for `LedgerProofs`
+pub struct LedgerProofsSerializer {
+    slot_serializer: SlotSerializer,
+    u32_serializer: U32VarIntSerializer,
+    proof_serializer: LedgerProofSerializer,
+}
+
+impl LedgerProofsSerializer {
+    /// Creates a new `LedgerProofsSerializer`
+    pub fn new() -> Self {
+        Self {
+            slot_serializer: SlotSerializer::new(),
+            u32_serializer: U32VarIntSerializer::new(),
+            proof_serializer: LedgerProofSerializer::new(),
+        }
+    }
+}
+
+impl Default for LedgerProofsSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<LedgerProofs> for LedgerProofsSerializer {
+    fn serialize(&self, value: &LedgerProofs, buffer: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.slot_serializer.serialize(&value.slot, buffer)?;
+        buffer.extend(value.root.to_bytes());
+        let proof_count: u32 = value.proofs.len().try_into().map_err(|err| {
+            SerializeError::GeneralError(format!("too many proofs in LedgerProofs: {}", err))
+        })?;
+        self.u32_serializer.serialize(&proof_count, buffer)?;
+        for proof in value.proofs.iter() {
+            self.proof_serializer.serialize(proof, buffer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for `LedgerProofs`
+pub struct LedgerProofsDeserializer {
+    slot_deserializer: SlotDeserializer,
+    hash_deserializer: HashDeserializer,
+    proof_count_deserializer: U32VarIntDeserializer,
+    proof_deserializer: LedgerProofDeserializer,
+}
+
+impl LedgerProofsDeserializer {
+    /// Creates a new `LedgerProofsDeserializer`
+    pub fn new(
+        thread_count: u8,
+        max_proof_count: u32,
+        max_datastore_key_length: u8,
+        max_datastore_value_length: u64,
+    ) -> Self {
+        Self {
+            slot_deserializer: SlotDeserializer::new(
+                (Included(u64::MIN), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            ),
+            hash_deserializer: HashDeserializer::new(),
+            proof_count_deserializer: U32VarIntDeserializer::new(
+                Included(u32::MIN),
+                Included(max_proof_count),
+            ),
+            proof_deserializer: LedgerProofDeserializer::new(
+                max_datastore_key_length,
+                max_datastore_value_length,
+            ),
+        }
+    }
+}
+
+impl Deserializer<LedgerProofs> for LedgerProofsDeserializer {
+    /// ## Example
+    /// ```rust
+    /// use massa_hash::Hash;
+    /// use massa_ledger_exports::{LedgerProof, LedgerProofs, LedgerProofsDeserializer, LedgerProofsSerializer};
+    /// use massa_models::slot::Slot;
+    /// use massa_serialization::{Serializer, Deserializer, DeserializeError};
+    ///
+    /// let proofs = LedgerProofs {
+    ///     slot: Slot::new(10, 1),
+    ///     root: Hash::compute_from(b"root"),
+    ///     proofs: vec![LedgerProof {
+    ///         key: b"key".to_vec(),
+    ///         value: Some(b"value".to_vec()),
+    ///         siblings: vec![Hash::compute_from(b"sibling")],
+    ///         other_leaf: None,
+    ///     }],
+    /// };
+    /// let mut buffer = Vec::new();
+    /// LedgerProofsSerializer::new().serialize(&proofs, &mut buffer).unwrap();
+    /// let (rest, deserialized) = LedgerProofsDeserializer::new(32, 10, 255, 1000)
+    ///     .deserialize::<DeserializeError>(&buffer)
+    ///     .unwrap();
+    /// assert!(rest.is_empty());
+    /// assert_eq!(deserialized.slot, proofs.slot);
+    /// assert_eq!(deserialized.root, proofs.root);
+    /// assert_eq!(deserialized.proofs, proofs.proofs);
+    /// ```
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], LedgerProofs, E> {
+        context(
+            "Failed LedgerProofs deserialization",
+            tuple((
+                context("Failed slot deserialization", |input| {
+                    self.slot_deserializer.deserialize(input)
+                }),
+                context("Failed root deserialization", |input| {
+                    self.hash_deserializer.deserialize(input)
+                }),
+                context(
+                    "Failed proofs deserialization",
+                    length_count(
+                        |input| self.proof_count_deserializer.deserialize(input),
+                        |input| self.proof_deserializer.deserialize(input),
+                    ),
+                ),
+            )),
+        )
+        .map(|(slot, root, proofs)| LedgerProofs { slot, root, proofs })
+        .parse(buffer)
+    }
+}
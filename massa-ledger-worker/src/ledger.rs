@@ -2,9 +2,11 @@
 
 //! This file defines the final ledger associating addresses to their balances, bytecode and data.
 
-use crate::ledger_db::{LedgerDB, LedgerSubEntry};
+use crate::ledger_db::LedgerDB;
+use massa_hash::Hash;
 use massa_ledger_exports::{
-    LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerError,
+    LedgerChanges, LedgerConfig, LedgerController, LedgerEntry, LedgerError, LedgerProof,
+    LedgerSubEntry,
 };
 use massa_models::{
     address::Address,
@@ -171,6 +173,23 @@ impl LedgerController for FinalLedger {
         self.sorted_ledger.get_datastore_keys(addr)
     }
 
+    /// Get the root of the ledger Merkle tree
+    ///
+    /// # Returns
+    /// The root hash, which is the empty hash for an empty ledger
+    fn get_ledger_root(&self) -> Hash {
+        self.sorted_ledger.get_ledger_root()
+    }
+
+    /// Get the root of the ledger Merkle tree and proofs of the values of sub-entries,
+    /// or of their absence, all read from a single snapshot of the disk ledger
+    ///
+    /// # Returns
+    /// The root and one proof per queried sub-entry, checkable against that root
+    fn get_ledger_proofs(&self, entries: &[(Address, LedgerSubEntry)]) -> (Hash, Vec<LedgerProof>) {
+        self.sorted_ledger.get_ledger_proofs(entries)
+    }
+
     /// Get a part of the disk ledger.
     ///
     /// Solely used by the bootstrap.
@@ -2,6 +2,7 @@
 
 //! Module to interact with the disk ledger
 
+use massa_hash::{Hash, HASH_SIZE_BYTES};
 use massa_ledger_exports::*;
 use massa_models::{
     address::{Address, ADDRESS_SIZE_BYTES},
@@ -14,9 +15,10 @@ use massa_serialization::{Deserializer, Serializer};
 use nom::multi::many0;
 use nom::sequence::tuple;
 use rocksdb::{
-    ColumnFamilyDescriptor, Direction, IteratorMode, Options, ReadOptions, WriteBatch, DB,
+    ColumnFamily, ColumnFamilyDescriptor, Direction, IteratorMode, Options, ReadOptions, Snapshot,
+    WriteBatch, DB,
 };
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt::Debug;
 use std::ops::Bound;
 use std::path::PathBuf;
@@ -27,21 +29,36 @@ use massa_models::amount::{Amount, AmountDeserializer};
 
 const LEDGER_CF: &str = "ledger";
 const METADATA_CF: &str = "metadata";
+const MERKLE_CF: &str = "merkle";
 const OPEN_ERROR: &str = "critical: rocksdb open operation failed";
 const CRUD_ERROR: &str = "critical: rocksdb crud operation failed";
 const CF_ERROR: &str = "critical: rocksdb column family operation failed";
 const SLOT_KEY: &[u8; 1] = b"s";
+const MERKLE_BACKFILL_CHUNK_SIZE: usize = 10_000;
 
-/// Ledger sub entry enum
-pub enum LedgerSubEntry {
-    /// Sequential Balance
-    SeqBalance,
-    /// Parallel Balance
-    ParBalance,
-    /// Bytecode
-    Bytecode,
-    /// Datastore entry
-    Datastore(Vec<u8>),
+/// Operation batch of the disk ledger
+///
+/// Records the value hash of every written ledger key, `None` if deleted,
+/// to update the Merkle tree on write.
+#[derive(Default)]
+pub(crate) struct LedgerBatch {
+    write_batch: WriteBatch,
+    merkle_leaves: BTreeMap<Vec<u8>, Option<Hash>>,
+}
+
+impl LedgerBatch {
+    /// Set the value of a ledger key
+    fn put(&mut self, handle: &ColumnFamily, key: Vec<u8>, value: Vec<u8>) {
+        self.merkle_leaves
+            .insert(key.clone(), Some(Hash::compute_from(&value)));
+        self.write_batch.put_cf(handle, key, value);
+    }
+
+    /// Delete a ledger key
+    fn delete(&mut self, handle: &ColumnFamily, key: Vec<u8>) {
+        self.merkle_leaves.insert(key.clone(), None);
+        self.write_batch.delete_cf(handle, key);
+    }
 }
 
 /// Disk ledger DB module
@@ -87,6 +104,37 @@ fn test_end_prefix() {
     assert_eq!(end_prefix(&[5, 6, 255]), Some(vec![5, 7]));
 }
 
+/// Decodes a node of the Merkle tree read from the disk, empty if absent
+fn decode_merkle_node(bytes: Option<Vec<u8>>) -> MerkleNode {
+    match bytes {
+        Some(bytes) => {
+            MerkleNode::from_bytes(&bytes).expect("critical: invalid ledger merkle node")
+        }
+        None => MerkleNode::Empty,
+    }
+}
+
+/// Key of the Merkle tree node at a given depth along a path:
+/// the depth followed by the bits of the path above that depth.
+fn merkle_node_key(path: &MerklePath, depth: usize) -> Vec<u8> {
+    let mut key = (depth as u16).to_be_bytes().to_vec();
+    key.extend(&path[..(depth + 7) / 8]);
+    if depth % 8 != 0 {
+        if let Some(last) = key.last_mut() {
+            *last &= 0xffu8 << (8 - depth % 8);
+        }
+    }
+    key
+}
+
+/// Key of a child of the node at a given depth along a path
+fn merkle_child_key(path: &MerklePath, depth: usize, bit: u8) -> Vec<u8> {
+    let mut child_path = *path;
+    child_path[depth / 8] &= !(1 << (7 - depth % 8));
+    child_path[depth / 8] |= bit << (7 - depth % 8);
+    merkle_node_key(&child_path, depth + 1)
+}
+
 impl LedgerDB {
     /// Create and initialize a new LedgerDB.
     ///
@@ -108,11 +156,12 @@ impl LedgerDB {
             vec![
                 ColumnFamilyDescriptor::new(LEDGER_CF, Options::default()),
                 ColumnFamilyDescriptor::new(METADATA_CF, Options::default()),
+                ColumnFamilyDescriptor::new(MERKLE_CF, Options::default()),
             ],
         )
         .expect(OPEN_ERROR);
 
-        LedgerDB {
+        let ledger_db = LedgerDB {
             db,
             thread_count,
             amount_serializer: AmountSerializer::new(),
@@ -124,14 +173,46 @@ impl LedgerDB {
                 Bound::Included(Amount::MIN),
                 Bound::Included(Amount::MAX),
             ),
+        };
+        ledger_db.backfill_merkle_tree();
+        ledger_db
+    }
+
+    /// Builds the Merkle tree of a disk ledger written before the tree was stored along with it.
+    /// The ledger keys are added to the tree in chunks, to bound the size of the batches.
+    fn backfill_merkle_tree(&self) {
+        let ledger_handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
+        let merkle_handle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+        if self
+            .db
+            .iterator_cf(merkle_handle, IteratorMode::Start)
+            .next()
+            .is_some()
+        {
+            return;
+        }
+        // the iterator reads from an implicit snapshot, unaffected by the tree writes
+        let mut batch = LedgerBatch::default();
+        for (key, value) in self
+            .db
+            .iterator_cf(ledger_handle, IteratorMode::Start)
+            .flatten()
+        {
+            batch
+                .merkle_leaves
+                .insert(key.to_vec(), Some(Hash::compute_from(&value)));
+            if batch.merkle_leaves.len() >= MERKLE_BACKFILL_CHUNK_SIZE {
+                self.write_batch(std::mem::take(&mut batch));
+            }
         }
+        self.write_batch(batch);
     }
 
     /// Loads the initial disk ledger
     ///
     /// # Arguments
     pub fn load_initial_ledger(&mut self, initial_ledger: HashMap<Address, LedgerEntry>) {
-        let mut batch = WriteBatch::default();
+        let mut batch = LedgerBatch::default();
         for (address, entry) in initial_ledger {
             self.put_entry(&address, entry, &mut batch);
         }
@@ -149,7 +230,7 @@ impl LedgerDB {
     /// * slot: new slot associated to the final ledger
     pub fn apply_changes(&mut self, changes: LedgerChanges, slot: Slot) {
         // create the batch
-        let mut batch = WriteBatch::default();
+        let mut batch = LedgerBatch::default();
         // for all incoming changes
         for (addr, change) in changes.0 {
             match change {
@@ -177,11 +258,217 @@ impl LedgerDB {
         self.write_batch(batch);
     }
 
-    /// Apply the given operation batch to the disk ledger.
+    /// Apply the given operation batch to the disk ledger, updating the Merkle tree along.
     ///
     /// NOTE: the batch is not saved within the object because it cannot be shared between threads safely
-    fn write_batch(&self, batch: WriteBatch) {
-        self.db.write(batch).expect(CRUD_ERROR);
+    fn write_batch(&self, mut batch: LedgerBatch) {
+        self.update_merkle_tree(&mut batch);
+        self.db.write(batch.write_batch).expect(CRUD_ERROR);
+    }
+
+    /// Get a node of the Merkle tree, empty if absent
+    ///
+    /// # Arguments
+    /// * `node_key`: key of the node, see `merkle_node_key`
+    fn get_merkle_node(&self, node_key: &[u8]) -> MerkleNode {
+        let handle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+        decode_merkle_node(self.db.get_cf(handle, node_key).expect(CRUD_ERROR))
+    }
+
+    /// Write a node of the Merkle tree to a batch, empty nodes being deleted
+    fn put_merkle_node(&self, node_key: Vec<u8>, node: &MerkleNode, batch: &mut LedgerBatch) {
+        let handle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+        match node {
+            MerkleNode::Empty => batch.write_batch.delete_cf(handle, node_key),
+            _ => batch.write_batch.put_cf(handle, node_key, node.to_bytes()),
+        }
+    }
+
+    /// Add the Merkle tree updates matching the ledger keys written by a batch to it.
+    ///
+    /// All the written keys are applied in a single pass over the tree,
+    /// so that the nodes shared by their paths are only read and written once.
+    fn update_merkle_tree(&self, batch: &mut LedgerBatch) {
+        let mut changes: Vec<(MerklePath, Option<Hash>)> = std::mem::take(&mut batch.merkle_leaves)
+            .into_iter()
+            .map(|(key, value_hash)| (merkle_path(&key), value_hash))
+            .collect();
+        if changes.is_empty() {
+            return;
+        }
+        changes.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        let root = self.update_merkle_subtree(0, &changes, batch);
+        self.put_merkle_node(merkle_node_key(&changes[0].0, 0), &root, batch);
+    }
+
+    /// Update a subtree of the Merkle tree.
+    ///
+    /// The nodes below the root of the subtree are written to the batch,
+    /// and the new root is returned for the caller to write it.
+    ///
+    /// # Arguments
+    /// * `depth`: depth of the root of the subtree
+    /// * `changes`: new value hashes of the keys of the subtree, sorted by path, not empty
+    /// * `batch`: the given operation batch to update
+    fn update_merkle_subtree(
+        &self,
+        depth: usize,
+        changes: &[(MerklePath, Option<Hash>)],
+        batch: &mut LedgerBatch,
+    ) -> MerkleNode {
+        let path = changes[0].0;
+        let node = self.get_merkle_node(&merkle_node_key(&path, depth));
+        if let MerkleNode::Inner(_) = node {
+            // paths going left come first as changes are sorted
+            let split = changes.partition_point(|(path, _)| merkle_path_bit(path, depth) == 0);
+            let [left, right] =
+                [(0u8, &changes[..split]), (1u8, &changes[split..])].map(|(bit, changes)| {
+                    match changes.is_empty() {
+                        true => self.get_merkle_node(&merkle_child_key(&path, depth, bit)),
+                        false => self.update_merkle_subtree(depth + 1, changes, batch),
+                    }
+                });
+            // a subtree left with a single key is replaced by its leaf
+            let node = match (left, right) {
+                (MerkleNode::Empty, MerkleNode::Empty) => MerkleNode::Empty,
+                (leaf @ MerkleNode::Leaf { .. }, MerkleNode::Empty)
+                | (MerkleNode::Empty, leaf @ MerkleNode::Leaf { .. }) => leaf,
+                _ => MerkleNode::Inner(merkle_node_hash(&left.hash(), &right.hash())),
+            };
+            let (left, right) = match node {
+                MerkleNode::Inner(_) => (left, right),
+                _ => (MerkleNode::Empty, MerkleNode::Empty),
+            };
+            self.put_merkle_node(merkle_child_key(&path, depth, 0), &left, batch);
+            self.put_merkle_node(merkle_child_key(&path, depth, 1), &right, batch);
+            return node;
+        }
+
+        // nothing is stored below an empty subtree or a leaf: rebuild the subtree
+        let mut leaves: Vec<(MerklePath, Hash)> = changes
+            .iter()
+            .filter_map(|(path, value_hash)| value_hash.map(|value_hash| (*path, value_hash)))
+            .collect();
+        if let MerkleNode::Leaf { path, value_hash } = node {
+            if changes.binary_search_by(|(p, _)| p.cmp(&path)).is_err() {
+                let index = leaves.partition_point(|(p, _)| *p < path);
+                leaves.insert(index, (path, value_hash));
+            }
+        }
+        self.build_merkle_subtree(depth, &leaves, batch)
+    }
+
+    /// Build a subtree of the Merkle tree where nothing is stored yet.
+    ///
+    /// The nodes below the root of the subtree are written to the batch,
+    /// and the root is returned for the caller to write it.
+    ///
+    /// # Arguments
+    /// * `depth`: depth of the root of the subtree
+    /// * `leaves`: paths and value hashes of the keys of the subtree, sorted by path
+    /// * `batch`: the given operation batch to update
+    fn build_merkle_subtree(
+        &self,
+        depth: usize,
+        leaves: &[(MerklePath, Hash)],
+        batch: &mut LedgerBatch,
+    ) -> MerkleNode {
+        match leaves {
+            [] => MerkleNode::Empty,
+            [(path, value_hash)] => MerkleNode::Leaf {
+                path: *path,
+                value_hash: *value_hash,
+            },
+            _ => {
+                let path = leaves[0].0;
+                let split = leaves.partition_point(|(path, _)| merkle_path_bit(path, depth) == 0);
+                let [left, right] =
+                    [(0u8, &leaves[..split]), (1u8, &leaves[split..])].map(|(bit, leaves)| {
+                        let child = self.build_merkle_subtree(depth + 1, leaves, batch);
+                        self.put_merkle_node(merkle_child_key(&path, depth, bit), &child, batch);
+                        child
+                    });
+                MerkleNode::Inner(merkle_node_hash(&left.hash(), &right.hash()))
+            }
+        }
+    }
+
+    /// Get the root of the ledger Merkle tree
+    pub fn get_ledger_root(&self) -> Hash {
+        self.get_merkle_node(&merkle_node_key(&[0u8; HASH_SIZE_BYTES], 0))
+            .hash()
+    }
+
+    /// Get the root of the ledger Merkle tree and proofs of the values of sub-entries,
+    /// or of their absence, all read from a single snapshot of the disk ledger.
+    ///
+    /// # Arguments
+    /// * entries: addresses and types of the queried sub-entries
+    ///
+    /// # Returns
+    /// The root and one proof per queried sub-entry, checkable against that root
+    pub fn get_ledger_proofs(
+        &self,
+        entries: &[(Address, LedgerSubEntry)],
+    ) -> (Hash, Vec<LedgerProof>) {
+        let snapshot = self.db.snapshot();
+        let root = self
+            .get_snapshot_merkle_node(&snapshot, &merkle_node_key(&[0u8; HASH_SIZE_BYTES], 0))
+            .hash();
+        let proofs = entries
+            .iter()
+            .map(|(addr, ty)| self.get_snapshot_ledger_proof(&snapshot, addr, ty))
+            .collect();
+        (root, proofs)
+    }
+
+    /// Get a node of the Merkle tree from a snapshot of the disk ledger, empty if absent
+    fn get_snapshot_merkle_node(&self, snapshot: &Snapshot, node_key: &[u8]) -> MerkleNode {
+        let handle = self.db.cf_handle(MERKLE_CF).expect(CF_ERROR);
+        decode_merkle_node(snapshot.get_cf(handle, node_key).expect(CRUD_ERROR))
+    }
+
+    /// Get a proof of the value of the given sub-entry of a given address, or of its absence,
+    /// from a snapshot of the disk ledger.
+    ///
+    /// # Arguments
+    /// * snapshot: snapshot of the disk ledger to read from
+    /// * addr: associated address
+    /// * ty: type of the queried sub-entry
+    fn get_snapshot_ledger_proof(
+        &self,
+        snapshot: &Snapshot,
+        addr: &Address,
+        ty: &LedgerSubEntry,
+    ) -> LedgerProof {
+        let key = ty.key(addr);
+        let path = merkle_path(&key);
+        let mut siblings = Vec::new();
+        let mut node = self.get_snapshot_merkle_node(snapshot, &merkle_node_key(&path, 0));
+        while let MerkleNode::Inner(_) = node {
+            let depth = siblings.len();
+            let bit = merkle_path_bit(&path, depth);
+            siblings.push(
+                self.get_snapshot_merkle_node(snapshot, &merkle_child_key(&path, depth, 1 - bit))
+                    .hash(),
+            );
+            node = self.get_snapshot_merkle_node(snapshot, &merkle_child_key(&path, depth, bit));
+        }
+        let other_leaf = match node {
+            MerkleNode::Leaf {
+                path: leaf_path,
+                value_hash,
+            } if leaf_path != path => Some((leaf_path, value_hash)),
+            _ => None,
+        };
+        let handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
+        let value = snapshot.get_cf(handle, &key).expect(CRUD_ERROR);
+        LedgerProof {
+            key,
+            value,
+            siblings,
+            other_leaf,
+        }
     }
 
     /// Set the disk ledger metadata
@@ -191,12 +478,12 @@ impl LedgerDB {
     /// * batch: the given operation batch to update
     ///
     /// NOTE: right now the metadata is only a Slot, use a struct in the future
-    fn set_metadata(&self, slot: Slot, batch: &mut WriteBatch) {
+    fn set_metadata(&self, slot: Slot, batch: &mut LedgerBatch) {
         let handle = self.db.cf_handle(METADATA_CF).expect(CF_ERROR);
         let mut bytes = Vec::new();
         // Slot serialization never fails
         self.slot_serializer.serialize(&slot, &mut bytes).unwrap();
-        batch.put_cf(handle, SLOT_KEY, bytes);
+        batch.write_batch.put_cf(handle, SLOT_KEY, bytes);
     }
 
     /// Add every sub-entry individually for a given entry.
@@ -205,7 +492,7 @@ impl LedgerDB {
     /// * addr: associated address
     /// * ledger_entry: complete entry to be added
     /// * batch: the given operation batch to update
-    fn put_entry(&mut self, addr: &Address, ledger_entry: LedgerEntry, batch: &mut WriteBatch) {
+    fn put_entry(&mut self, addr: &Address, ledger_entry: LedgerEntry, batch: &mut LedgerBatch) {
         let handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
         // note that Amount serialization never fails
         let mut bytes_parallel_balance = Vec::new();
@@ -220,17 +507,17 @@ impl LedgerDB {
             )
             .unwrap();
         // sequential balance
-        batch.put_cf(handle, seq_balance_key!(addr), bytes_sequential_balance);
+        batch.put(handle, seq_balance_key!(addr), bytes_sequential_balance);
 
         // parallel balance
-        batch.put_cf(handle, par_balance_key!(addr), bytes_parallel_balance);
+        batch.put(handle, par_balance_key!(addr), bytes_parallel_balance);
 
         // bytecode
-        batch.put_cf(handle, bytecode_key!(addr), ledger_entry.bytecode);
+        batch.put(handle, bytecode_key!(addr), ledger_entry.bytecode);
 
         // datastore
         for (hash, entry) in ledger_entry.datastore {
-            batch.put_cf(handle, data_key!(addr, hash), entry);
+            batch.put(handle, data_key!(addr, hash), entry);
         }
     }
 
@@ -245,24 +532,7 @@ impl LedgerDB {
     pub fn get_sub_entry(&self, addr: &Address, ty: LedgerSubEntry) -> Option<Vec<u8>> {
         let handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
 
-        match ty {
-            LedgerSubEntry::SeqBalance => self
-                .db
-                .get_cf(handle, seq_balance_key!(addr))
-                .expect(CRUD_ERROR),
-            LedgerSubEntry::ParBalance => self
-                .db
-                .get_cf(handle, par_balance_key!(addr))
-                .expect(CRUD_ERROR),
-            LedgerSubEntry::Bytecode => self
-                .db
-                .get_cf(handle, bytecode_key!(addr))
-                .expect(CRUD_ERROR),
-            LedgerSubEntry::Datastore(hash) => self
-                .db
-                .get_cf(handle, data_key!(addr, hash))
-                .expect(CRUD_ERROR),
-        }
+        self.db.get_cf(handle, ty.key(addr)).expect(CRUD_ERROR)
     }
 
     /// Get every key of the datastore for a given address.
@@ -295,7 +565,7 @@ impl LedgerDB {
         &mut self,
         addr: &Address,
         entry_update: LedgerEntryUpdate,
-        batch: &mut WriteBatch,
+        batch: &mut LedgerBatch,
     ) {
         let handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
 
@@ -307,7 +577,7 @@ impl LedgerDB {
             self.amount_serializer
                 .serialize(&balance, &mut bytes)
                 .unwrap();
-            batch.put_cf(handle, seq_balance_key!(addr), bytes);
+            batch.put(handle, seq_balance_key!(addr), bytes);
         }
 
         // parallel balance
@@ -317,19 +587,19 @@ impl LedgerDB {
             self.amount_serializer
                 .serialize(&balance, &mut bytes)
                 .unwrap();
-            batch.put_cf(handle, par_balance_key!(addr), bytes);
+            batch.put(handle, par_balance_key!(addr), bytes);
         }
 
         // bytecode
         if let SetOrKeep::Set(bytecode) = entry_update.bytecode {
-            batch.put_cf(handle, bytecode_key!(addr), bytecode);
+            batch.put(handle, bytecode_key!(addr), bytecode);
         }
 
         // datastore
         for (hash, update) in entry_update.datastore {
             match update {
-                SetOrDelete::Set(entry) => batch.put_cf(handle, data_key!(addr, hash), entry),
-                SetOrDelete::Delete => batch.delete_cf(handle, data_key!(addr, hash)),
+                SetOrDelete::Set(entry) => batch.put(handle, data_key!(addr, hash), entry),
+                SetOrDelete::Delete => batch.delete(handle, data_key!(addr, hash)),
             }
         }
     }
@@ -338,17 +608,17 @@ impl LedgerDB {
     ///
     /// # Arguments
     /// * batch: the given operation batch to update
-    fn delete_entry(&self, addr: &Address, batch: &mut WriteBatch) {
+    fn delete_entry(&self, addr: &Address, batch: &mut LedgerBatch) {
         let handle = self.db.cf_handle(LEDGER_CF).expect(CF_ERROR);
 
         // sequential balance
-        batch.delete_cf(handle, seq_balance_key!(addr));
+        batch.delete(handle, seq_balance_key!(addr));
 
         // parallel balance
-        batch.delete_cf(handle, par_balance_key!(addr));
+        batch.delete(handle, par_balance_key!(addr));
 
         // bytecode
-        batch.delete_cf(handle, bytecode_key!(addr));
+        batch.delete(handle, bytecode_key!(addr));
 
         // datastore
         let mut opt = ReadOptions::default();
@@ -362,7 +632,7 @@ impl LedgerDB {
             )
             .flatten()
         {
-            batch.delete_cf(handle, key);
+            batch.delete(handle, key.to_vec());
         }
     }
 
@@ -426,7 +696,7 @@ impl LedgerDB {
             VecU8Deserializer::new(Bound::Included(0), Bound::Excluded(u64::MAX));
         let key_deserializer = KeyDeserializer::new(self.max_datastore_key_length);
         let mut last_key = Rc::new(None);
-        let mut batch = WriteBatch::default();
+        let mut batch = LedgerBatch::default();
 
         // Since this data is coming from the network, deser to address and ser back to bytes for a security check.
         let (rest, _) = many0(|input: &'a [u8]| {
@@ -437,14 +707,14 @@ impl LedgerDB {
             *Rc::get_mut(&mut last_key).ok_or_else(|| {
                 nom::Err::Error(nom::error::Error::new(input, nom::error::ErrorKind::Fail))
             })? = Some(key.clone());
-            batch.put_cf(handle, key, value);
+            batch.put(handle, key, value);
             Ok((rest, ()))
         })(data)
         .map_err(|_| ModelsError::SerializeError("Error in deserialization".to_string()))?;
 
         // Every byte should have been read
         if rest.is_empty() {
-            self.db.write(batch).expect(CRUD_ERROR);
+            self.write_batch(batch);
             Ok((*last_key).clone())
         } else {
             Err(ModelsError::SerializeError(
@@ -525,16 +795,17 @@ impl LedgerDB {
 
 #[cfg(test)]
 mod tests {
-    use super::LedgerDB;
-    use crate::ledger_db::LedgerSubEntry;
-    use massa_ledger_exports::{LedgerEntry, LedgerEntryUpdate, SetOrKeep};
+    use super::{LedgerBatch, LedgerDB, LEDGER_CF, MERKLE_CF, METADATA_CF};
+    use massa_ledger_exports::{
+        merkle_empty_hash, LedgerEntry, LedgerEntryUpdate, LedgerSubEntry, SetOrKeep,
+    };
     use massa_models::{
         address::Address,
         amount::{Amount, AmountDeserializer},
     };
     use massa_serialization::{DeserializeError, Deserializer};
     use massa_signature::KeyPair;
-    use rocksdb::WriteBatch;
+    use rocksdb::{ColumnFamilyDescriptor, IteratorMode, Options, DB};
     use std::collections::BTreeMap;
     use std::ops::Bound::Included;
     use tempfile::TempDir;
@@ -560,7 +831,7 @@ mod tests {
         // write data
         let temp_dir = TempDir::new().unwrap();
         let mut db = LedgerDB::new(temp_dir.path().to_path_buf(), 32, 255, 1_000_000);
-        let mut batch = WriteBatch::default();
+        let mut batch = LedgerBatch::default();
         db.put_entry(&addr, entry, &mut batch);
         db.update_entry(&addr, entry_update, &mut batch);
         db.write_batch(batch);
@@ -595,7 +866,7 @@ mod tests {
         assert_eq!(data, db.get_entire_datastore(&a));
 
         // delete entry
-        let mut batch = WriteBatch::default();
+        let mut batch = LedgerBatch::default();
         db.delete_entry(&a, &mut batch);
         db.write_batch(batch);
 
@@ -612,4 +883,154 @@ mod tests {
         let res = db.get_ledger_part(&None).unwrap();
         db.set_ledger_part(&res.0[..]).unwrap();
     }
+
+    #[test]
+    fn test_ledger_merkle_proofs() {
+        let a = Address::from_public_key(&KeyPair::generate().get_public_key());
+        let b = Address::from_public_key(&KeyPair::generate().get_public_key());
+        let (db, data) = init_test_ledger(a);
+        let root = db.get_ledger_root();
+        assert_ne!(root, merkle_empty_hash());
+
+        // inclusion proof
+        let (proven_root, proofs) = db.get_ledger_proofs(&[
+            (a, LedgerSubEntry::ParBalance),
+            (a, LedgerSubEntry::Datastore(b"4".to_vec())),
+            (b, LedgerSubEntry::SeqBalance),
+        ]);
+        assert_eq!(proven_root, root);
+        let proof = &proofs[0];
+        assert_eq!(
+            proof.value,
+            db.get_sub_entry(&a, LedgerSubEntry::ParBalance)
+        );
+        assert!(proof.verify(&root));
+        let mut forged = proof.clone();
+        forged.value = db.get_sub_entry(&a, LedgerSubEntry::SeqBalance);
+        assert!(!forged.verify(&root));
+
+        // exclusion proofs
+        for proof in &proofs[1..] {
+            assert!(proof.value.is_none());
+            assert!(proof.verify(&root));
+        }
+
+        // the root only depends on the content of the ledger
+        let temp_dir = TempDir::new().unwrap();
+        let mut other_db = LedgerDB::new(temp_dir.path().to_path_buf(), 32, 255, 1_000_000);
+        let entry = LedgerEntry {
+            parallel_balance: Amount::from_mantissa_scale(21, 0),
+            datastore: data,
+            ..Default::default()
+        };
+        let mut batch = LedgerBatch::default();
+        other_db.put_entry(&a, entry, &mut batch);
+        other_db.write_batch(batch);
+        assert_eq!(other_db.get_ledger_root(), root);
+
+        // an empty ledger has an empty root
+        let mut batch = LedgerBatch::default();
+        db.delete_entry(&a, &mut batch);
+        db.write_batch(batch);
+        assert_eq!(db.get_ledger_root(), merkle_empty_hash());
+    }
+
+    #[test]
+    fn test_ledger_merkle_updates() {
+        let addresses: Vec<Address> = (0..20)
+            .map(|_| Address::from_public_key(&KeyPair::generate().get_public_key()))
+            .collect();
+        let entry = |index: u64| LedgerEntry {
+            parallel_balance: Amount::from_mantissa_scale(index, 0),
+            ..Default::default()
+        };
+
+        // write the entries one by one, then delete half of them in a single batch
+        let temp_dir = TempDir::new().unwrap();
+        let mut db = LedgerDB::new(temp_dir.path().to_path_buf(), 32, 255, 1_000_000);
+        for (index, addr) in addresses.iter().enumerate() {
+            let mut batch = LedgerBatch::default();
+            db.put_entry(addr, entry(index as u64), &mut batch);
+            db.write_batch(batch);
+        }
+        let mut batch = LedgerBatch::default();
+        for addr in addresses.iter().step_by(2) {
+            db.delete_entry(addr, &mut batch);
+        }
+        db.write_batch(batch);
+
+        // write the remaining entries in a single batch
+        let other_dir = TempDir::new().unwrap();
+        let mut other_db = LedgerDB::new(other_dir.path().to_path_buf(), 32, 255, 1_000_000);
+        let mut batch = LedgerBatch::default();
+        for (index, addr) in addresses.iter().enumerate().skip(1).step_by(2) {
+            other_db.put_entry(addr, entry(index as u64), &mut batch);
+        }
+        other_db.write_batch(batch);
+
+        // same tree, without any node left behind by the deletions
+        let root = db.get_ledger_root();
+        assert_eq!(other_db.get_ledger_root(), root);
+        let node_count = |db: &LedgerDB| {
+            let handle = db.db.cf_handle(MERKLE_CF).unwrap();
+            db.db.iterator_cf(handle, IteratorMode::Start).count()
+        };
+        assert_eq!(node_count(&db), node_count(&other_db));
+
+        // every key can be proven in or out of the ledger
+        let queries: Vec<_> = addresses
+            .iter()
+            .map(|addr| (*addr, LedgerSubEntry::ParBalance))
+            .collect();
+        let (proven_root, proofs) = db.get_ledger_proofs(&queries);
+        assert_eq!(proven_root, root);
+        for (index, proof) in proofs.iter().enumerate() {
+            assert_eq!(proof.value.is_some(), index % 2 == 1);
+            assert!(proof.verify(&root));
+        }
+    }
+
+    #[test]
+    fn test_ledger_merkle_backfill() {
+        let a = Address::from_public_key(&KeyPair::generate().get_public_key());
+        let (reference_db, _) = init_test_ledger(a);
+        let root = reference_db.get_ledger_root();
+
+        // write a ledger without the merkle column family, as done before it existed
+        let temp_dir = TempDir::new().unwrap();
+        {
+            let mut db_opts = Options::default();
+            db_opts.create_if_missing(true);
+            db_opts.create_missing_column_families(true);
+            let legacy_db = DB::open_cf_descriptors(
+                &db_opts,
+                temp_dir.path(),
+                vec![
+                    ColumnFamilyDescriptor::new(LEDGER_CF, Options::default()),
+                    ColumnFamilyDescriptor::new(METADATA_CF, Options::default()),
+                ],
+            )
+            .unwrap();
+            let reference_handle = reference_db.db.cf_handle(LEDGER_CF).unwrap();
+            let legacy_handle = legacy_db.cf_handle(LEDGER_CF).unwrap();
+            for (key, value) in reference_db
+                .db
+                .iterator_cf(reference_handle, IteratorMode::Start)
+                .flatten()
+            {
+                legacy_db.put_cf(legacy_handle, key, value).unwrap();
+            }
+        }
+
+        // the tree is rebuilt when the ledger is opened
+        let db = LedgerDB::new(temp_dir.path().to_path_buf(), 32, 255, 1_000_000);
+        assert_eq!(db.get_ledger_root(), root);
+        let (proven_root, proofs) = db.get_ledger_proofs(&[(a, LedgerSubEntry::ParBalance)]);
+        assert_eq!(proven_root, root);
+        assert_eq!(
+            proofs[0].value,
+            db.get_sub_entry(&a, LedgerSubEntry::ParBalance)
+        );
+        assert!(proofs[0].verify(&root));
+    }
 }
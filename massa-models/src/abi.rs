@@ -0,0 +1,144 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Descriptor of the functions exported and the events emitted by a smart contract.
+//!
+//! A smart contract publishes its descriptor by storing it as JSON in its own datastore,
+//! under the `CONTRACT_ABI_KEY` key.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/// Datastore key under which a smart contract publishes the JSON descriptor of its ABI
+pub const CONTRACT_ABI_KEY: &str = "massa_contract_abi";
+
+/// Type of a smart contract function argument or return value
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbiType {
+    /// boolean
+    Bool,
+    /// unsigned 8-bit integer
+    U8,
+    /// signed 32-bit integer
+    I32,
+    /// unsigned 32-bit integer
+    U32,
+    /// signed 64-bit integer
+    I64,
+    /// unsigned 64-bit integer
+    U64,
+    /// 32-bit float
+    F32,
+    /// 64-bit float
+    F64,
+    /// UTF-8 string
+    String,
+    /// massa address
+    Address,
+    /// raw bytes
+    Bytes,
+}
+
+impl Display for AbiType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AbiType::Bool => "bool",
+            AbiType::U8 => "u8",
+            AbiType::I32 => "i32",
+            AbiType::U32 => "u32",
+            AbiType::I64 => "i64",
+            AbiType::U64 => "u64",
+            AbiType::F32 => "f32",
+            AbiType::F64 => "f64",
+            AbiType::String => "string",
+            AbiType::Address => "address",
+            AbiType::Bytes => "bytes",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Named argument of a smart contract function
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiArgument {
+    /// name of the argument
+    pub name: String,
+    /// type of the argument
+    #[serde(rename = "type")]
+    pub arg_type: AbiType,
+}
+
+/// Function exported by a smart contract
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiFunction {
+    /// name of the function
+    pub name: String,
+    /// arguments of the function, in order
+    #[serde(default)]
+    pub arguments: Vec<AbiArgument>,
+    /// type of the value returned by the function, if any
+    #[serde(default)]
+    pub returns: Option<AbiType>,
+}
+
+impl Display for AbiFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (index, argument) in self.arguments.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", argument.name, argument.arg_type)?;
+        }
+        write!(f, ")")?;
+        if let Some(returns) = &self.returns {
+            write!(f, " -> {}", returns)?;
+        }
+        Ok(())
+    }
+}
+
+/// Event emitted by a smart contract
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbiEvent {
+    /// name of the event
+    pub name: String,
+    /// arguments of the event, in order
+    #[serde(default)]
+    pub arguments: Vec<AbiArgument>,
+}
+
+impl Display for AbiEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (index, argument) in self.arguments.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", argument.name, argument.arg_type)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Descriptor of the functions exported and the events emitted by a smart contract
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractAbi {
+    /// exported functions
+    pub functions: Vec<AbiFunction>,
+    /// emitted events
+    #[serde(default)]
+    pub events: Vec<AbiEvent>,
+}
+
+impl ContractAbi {
+    /// Gets an exported function by name
+    pub fn get_function(&self, name: &str) -> Option<&AbiFunction> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+
+    /// Gets an emitted event by name
+    pub fn get_event(&self, name: &str) -> Option<&AbiEvent> {
+        self.events.iter().find(|event| event.name == name)
+    }
+}
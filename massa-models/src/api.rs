@@ -1,20 +1,25 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::abi::ContractAbi;
 use crate::address::ExecutionAddressCycleInfo;
+use crate::bytecode::BytecodeMetadata;
 use crate::endorsement::{EndorsementId, WrappedEndorsement};
+use crate::execution::ReadOnlyStateOverride;
 use crate::ledger_models::LedgerData;
-use crate::node::NodeId;
+use crate::node::{BanReason, NodeId};
 use crate::operation::{OperationId, WrappedOperation};
+use crate::output_event::AsyncMessageOrigin;
 use crate::stats::{ConsensusStats, ExecutionStats, NetworkStats};
 use crate::{
     address::Address, amount::Amount, block::Block, block::BlockId, config::CompactConfig,
     slot::Slot, version::Version,
 };
+use massa_hash::Hash;
 use massa_signature::{PublicKey, Signature};
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 
 /// operation input
 #[derive(Serialize, Deserialize, Debug)]
@@ -42,6 +47,9 @@ pub struct NodeStatus {
     pub current_cycle: u64,
     /// connected nodes (node id, ip address, true if the connection is outgoing, false if incoming)
     pub connected_nodes: HashMap<NodeId, (IpAddr, bool)>,
+    /// reputation score of the peer behind each connected node
+    #[serde(default)]
+    pub connected_nodes_scores: HashMap<NodeId, f64>,
     /// latest slot, none if now is before genesis timestamp
     pub last_slot: Option<Slot>,
     /// next slot
@@ -88,18 +96,48 @@ impl std::fmt::Display for NodeStatus {
 
         writeln!(f, "Connected nodes:")?;
         for (node_id, (ip_addr, is_outgoing)) in &self.connected_nodes {
-            writeln!(
+            write!(
                 f,
                 "Node's ID: {} / IP address: {} / {} connection",
                 node_id,
                 ip_addr,
                 if *is_outgoing { "Out" } else { "In" }
-            )?
+            )?;
+            if let Some(score) = self.connected_nodes_scores.get(node_id) {
+                write!(f, " / score: {:.2}", score)?;
+            }
+            writeln!(f)?
         }
         Ok(())
     }
 }
 
+/// A currently banned peer
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BannedPeerInfo {
+    /// address the peer listens on
+    pub address: SocketAddr,
+    /// why the peer was banned
+    pub reason: BanReason,
+    /// time at which the peer was banned
+    pub banned_at: MassaTime,
+    /// time at which the ban is lifted
+    pub expires_at: MassaTime,
+}
+
+impl std::fmt::Display for BannedPeerInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}: {} (banned at {}, until {})",
+            self.address,
+            self.reason,
+            self.banned_at.to_utc_string(),
+            self.expires_at.to_utc_string()
+        )
+    }
+}
+
 /// Operation and contextual info about it
 #[derive(Debug, Deserialize, Serialize)]
 pub struct OperationInfo {
@@ -225,6 +263,11 @@ pub struct AddressInfo {
     /// candidate datastore keys
     pub candidate_datastore_keys: Vec<Vec<u8>>,
 
+    /// final metadata of the bytecode (hash, version, mutability), None if the address does not exist
+    pub final_bytecode_metadata: Option<BytecodeMetadata>,
+    /// candidate metadata of the bytecode (hash, version, mutability), None if the address does not exist
+    pub candidate_bytecode_metadata: Option<BytecodeMetadata>,
+
     /// deferred credits
     pub deferred_credits: Vec<SlotAmount>,
 
@@ -257,6 +300,19 @@ impl std::fmt::Display for AddressInfo {
             "\tParallel balance: final={}, candidate={}",
             self.final_parallel_balance, self.candidate_parallel_balance
         )?;
+        if let Some(metadata) = &self.candidate_bytecode_metadata {
+            writeln!(
+                f,
+                "\tBytecode: hash={}, version={}, {}",
+                metadata.code_hash,
+                metadata.version,
+                if metadata.immutable {
+                    "immutable"
+                } else {
+                    "mutable"
+                }
+            )?;
+        }
         writeln!(f, "\tLocked coins:")?;
         for slot_amount in &self.deferred_credits {
             writeln!(
@@ -555,6 +611,10 @@ pub struct EventFilter {
     pub original_caller_address: Option<Address>,
     /// optional operation id
     pub original_operation_id: Option<OperationId>,
+    /// optional asynchronous message whose processing generated the events
+    pub original_async_message: Option<AsyncMessageOrigin>,
+    /// optional structured event topic
+    pub topic: Option<String>,
     /// optional event status
     ///
     /// Some(true) means final
@@ -574,6 +634,12 @@ pub struct ReadOnlyBytecodeExecution {
     pub bytecode: Vec<u8>,
     /// caller's address, optional
     pub address: Option<Address>,
+    /// state injected before the execution
+    #[serde(default)]
+    pub state_overrides: Vec<ReadOnlyStateOverride>,
+    /// execute on top of the final state only, ignoring candidate slots
+    #[serde(default)]
+    pub final_state_only: bool,
 }
 
 /// read SC call request
@@ -591,4 +657,532 @@ pub struct ReadOnlyCall {
     pub parameter: String,
     /// caller's address, optional
     pub caller_address: Option<Address>,
+    /// state injected before the execution
+    #[serde(default)]
+    pub state_overrides: Vec<ReadOnlyStateOverride>,
+    /// execute on top of the final state only, ignoring candidate slots
+    #[serde(default)]
+    pub final_state_only: bool,
+}
+
+/// batch of read SC call requests executed against the same state
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct ReadOnlyCallBatch {
+    /// calls to execute, in order
+    pub calls: Vec<ReadOnlyCall>,
+    /// make the state changes of each call visible to the next ones
+    #[serde(default)]
+    pub share_state: bool,
+}
+
+/// filter used when retrieving asynchronous messages
+#[derive(Default, Debug, Deserialize, Clone, Serialize)]
+pub struct AsyncMessageFilter {
+    /// optional sender address
+    pub sender: Option<Address>,
+    /// optional destination address
+    pub destination: Option<Address>,
+    /// optional emission slot
+    pub emission_slot: Option<Slot>,
+    /// optional emission index (index of the message among the ones emitted at the same slot)
+    pub emission_index: Option<u64>,
+    /// number of matching messages to skip
+    #[serde(default)]
+    pub offset: u64,
+    /// maximum number of matching messages to return, all of them if not set
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+impl AsyncMessageFilter {
+    /// Returns true if the message described by the given fields matches the filter
+    pub fn matches(
+        &self,
+        sender: &Address,
+        destination: &Address,
+        emission_slot: &Slot,
+        emission_index: u64,
+    ) -> bool {
+        self.sender.map_or(true, |addr| &addr == sender)
+            && self.destination.map_or(true, |addr| &addr == destination)
+            && self
+                .emission_slot
+                .map_or(true, |slot| &slot == emission_slot)
+            && self
+                .emission_index
+                .map_or(true, |index| index == emission_index)
+    }
+}
+
+/// Reason why an asynchronous message was removed from the pool without being executed
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncMessageEvictionReason {
+    /// the validity period of the message ended before it could be executed
+    Expired,
+    /// the pool was full and the message had a too low priority to stay in it
+    LowPriority,
+    /// the message was cancelled by its sender
+    Cancelled,
+}
+
+impl std::fmt::Display for AsyncMessageEvictionReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AsyncMessageEvictionReason::Expired => write!(f, "expired"),
+            AsyncMessageEvictionReason::LowPriority => write!(f, "low priority"),
+            AsyncMessageEvictionReason::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// Status of an asynchronous message
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub enum AsyncMessageStatus {
+    /// the message is waiting in the pool to be executed
+    Pending,
+    /// the message was executed successfully
+    Executed,
+    /// the execution of the message failed, its coins were reimbursed to the sender
+    ExecutionFailed(String),
+    /// the message was removed from the pool without being executed, its coins were reimbursed to the sender
+    Evicted(AsyncMessageEvictionReason),
+}
+
+impl std::fmt::Display for AsyncMessageStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AsyncMessageStatus::Pending => write!(f, "pending"),
+            AsyncMessageStatus::Executed => write!(f, "executed"),
+            AsyncMessageStatus::ExecutionFailed(err) => write!(f, "execution failed: {}", err),
+            AsyncMessageStatus::Evicted(reason) => write!(f, "evicted ({})", reason),
+        }
+    }
+}
+
+/// Asynchronous message and its current status
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AsyncMessageInfo {
+    /// slot at which the message was emitted
+    pub emission_slot: Slot,
+    /// index of the message among the ones emitted at `emission_slot`
+    pub emission_index: u64,
+    /// address that sent the message
+    pub sender: Address,
+    /// address towards which the message is being sent
+    pub destination: Address,
+    /// handler function name within the destination address' bytecode
+    pub handler: String,
+    /// maximum gas to use when processing the message
+    pub max_gas: u64,
+    /// gas price to take into account when executing the message
+    pub gas_price: Amount,
+    /// coins sent from the sender to the destination along with the message
+    pub coins: Amount,
+    /// slot at which the message starts being valid (bound included in the validity range)
+    pub validity_start: Slot,
+    /// slot at which the message stops being valid (bound not included in the validity range)
+    pub validity_end: Slot,
+    /// slot at which the current status was reached (None for pending messages)
+    pub status_slot: Option<Slot>,
+    /// current status of the message
+    pub status: AsyncMessageStatus,
+    /// true if the status is final
+    pub is_final: bool,
+}
+
+impl std::fmt::Display for AsyncMessageInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Async message emitted at slot {} with index {}{}",
+            self.emission_slot,
+            self.emission_index,
+            display_if_true(self.is_final, " (final)")
+        )?;
+        write!(f, "\tStatus: {}", self.status)?;
+        if let Some(slot) = self.status_slot {
+            write!(f, " at slot {}", slot)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "\tSender: {}", self.sender)?;
+        writeln!(
+            f,
+            "\tDestination: {} (handler: {})",
+            self.destination, self.handler
+        )?;
+        writeln!(
+            f,
+            "\tValidity: from slot {} (included) to slot {} (excluded)",
+            self.validity_start, self.validity_end
+        )?;
+        writeln!(
+            f,
+            "\tMax gas: {}, gas price: {}, coins: {}",
+            self.max_gas, self.gas_price, self.coins
+        )?;
+        Ok(())
+    }
+}
+
+/// Paginated request for the history of an address
+#[derive(Debug, Deserialize, Clone, Serialize)]
+pub struct AddressHistoryRequest {
+    /// address whose history is requested
+    pub address: Address,
+    /// number of most recent entries to skip
+    pub offset: u64,
+    /// maximum number of entries to return
+    pub limit: u64,
+}
+
+/// Kind of an address history entry
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum AddressHistoryEntryKind {
+    /// operation sent by `from`, optionally targeting `to`. `amount` is the fee paid by the sender
+    Operation,
+    /// transfer of sequential coins to `to`, optionally debited from `from` (None for coin creation)
+    SequentialTransfer,
+    /// transfer of parallel coins to `to`, optionally debited from `from` (None for coin creation)
+    ParallelTransfer,
+}
+
+impl std::fmt::Display for AddressHistoryEntryKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddressHistoryEntryKind::Operation => write!(f, "operation"),
+            AddressHistoryEntryKind::SequentialTransfer => write!(f, "sequential transfer"),
+            AddressHistoryEntryKind::ParallelTransfer => write!(f, "parallel transfer"),
+        }
+    }
+}
+
+/// Entry of the operation and coin transfer history of an address
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct AddressHistoryEntry {
+    /// slot at which the entry was recorded
+    pub slot: Slot,
+    /// index of the entry among the ones recorded at the same slot
+    pub index_in_slot: u64,
+    /// kind of the entry
+    pub kind: AddressHistoryEntryKind,
+    /// operation that caused the entry, if any
+    pub operation_id: Option<OperationId>,
+    /// sender of the operation, or debited address of the transfer
+    pub from: Option<Address>,
+    /// recipient or target of the operation, or credited address of the transfer
+    pub to: Option<Address>,
+    /// amount of coins transferred, or fee paid for operations
+    pub amount: Amount,
+    /// true if the entry is final
+    pub is_final: bool,
+}
+
+impl AddressHistoryEntry {
+    /// Returns true if the entry involves the given address
+    pub fn involves(&self, address: &Address) -> bool {
+        self.from.as_ref() == Some(address) || self.to.as_ref() == Some(address)
+    }
+}
+
+impl std::fmt::Display for AddressHistoryEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Slot {} (index {}){}: {} of {} from {} to {}",
+            self.slot,
+            self.index_in_slot,
+            display_if_true(self.is_final, " (final)"),
+            self.kind,
+            self.amount,
+            display_option_address(&self.from),
+            display_option_address(&self.to),
+        )?;
+        if let Some(op_id) = self.operation_id {
+            write!(f, " (operation {})", op_id)?;
+        }
+        writeln!(f)
+    }
+}
+
+fn display_option_address(address: &Option<Address>) -> String {
+    match address {
+        Some(address) => address.to_string(),
+        None => "-".to_string(),
+    }
+}
+
+/// Outcome of an operation included in an executed block
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub enum OperationExecutionOutcome {
+    /// the operation was executed successfully
+    Success,
+    /// the fee was paid but the execution failed and its effects were reverted
+    Failure(String),
+    /// the operation was not executed and no fee was paid
+    Skipped(String),
+}
+
+impl std::fmt::Display for OperationExecutionOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OperationExecutionOutcome::Success => write!(f, "success"),
+            OperationExecutionOutcome::Failure(err) => write!(f, "failure: {}", err),
+            OperationExecutionOutcome::Skipped(reason) => write!(f, "skipped: {}", reason),
+        }
+    }
+}
+
+/// Receipt of the execution of an operation included in a block
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct OperationReceipt {
+    /// operation id
+    pub operation_id: OperationId,
+    /// outcome of the execution
+    pub outcome: OperationExecutionOutcome,
+    /// gas consumed by the operation, counted in the block gas (zero if skipped, max gas if the execution failed)
+    pub gas: u64,
+    /// max gas of the operation
+    pub max_gas: u64,
+    /// fee paid by the sender, unused gas excluded (zero if skipped)
+    pub fee: Amount,
+    /// coins refunded to the sequential balance of the sender for unused gas
+    pub refund: Amount,
+}
+
+impl std::fmt::Display for OperationReceipt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Operation {}: {} (gas: {}/{}, fee: {}, refund: {})",
+            self.operation_id, self.outcome, self.gas, self.max_gas, self.fee, self.refund
+        )
+    }
+}
+
+/// Receipt of the execution of a block
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BlockExecutionReceipt {
+    /// block id
+    pub block_id: BlockId,
+    /// slot of the block
+    pub slot: Slot,
+    /// block producer
+    pub producer: Address,
+    /// receipts of the operations of the block, in block order
+    pub operations: Vec<OperationReceipt>,
+    /// outcomes of the asynchronous messages executed, failed or evicted at the slot
+    pub async_messages: Vec<AsyncMessageInfo>,
+    /// total fee paid by the executed operations
+    pub total_fees: Amount,
+    /// block reward, credited along with the fees
+    pub block_reward: Amount,
+    /// part of the block credits paid to each endorser and to each endorsement target creator
+    pub block_credit_part: Amount,
+    /// credits paid to endorsement creators, in endorsement order
+    pub endorser_credits: Vec<(Address, Amount)>,
+    /// credits paid to the creators of the endorsed blocks, in endorsement order
+    pub endorsement_target_credits: Vec<(Address, Amount)>,
+    /// remainder of the block credits paid to the producer
+    pub producer_credit: Amount,
+    /// true if the execution of the block is final
+    pub is_final: bool,
+}
+
+impl std::fmt::Display for BlockExecutionReceipt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Block {} at slot {}{}, produced by {}",
+            self.block_id,
+            self.slot,
+            display_if_true(self.is_final, " (final)"),
+            self.producer
+        )?;
+        writeln!(
+            f,
+            "Fees: {}, block reward: {}, credit part: {}",
+            self.total_fees, self.block_reward, self.block_credit_part
+        )?;
+        writeln!(f, "Producer credit: {}", self.producer_credit)?;
+        for (address, amount) in &self.endorser_credits {
+            writeln!(f, "Endorser credit: {} to {}", amount, address)?;
+        }
+        for (address, amount) in &self.endorsement_target_credits {
+            writeln!(f, "Endorsement target credit: {} to {}", amount, address)?;
+        }
+        writeln!(f, "Operations:")?;
+        for operation in &self.operations {
+            write!(f, "\t{}", operation)?;
+        }
+        writeln!(f, "Asynchronous messages:")?;
+        for message in &self.async_messages {
+            write!(f, "{}", message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Status of a node running in light client mode
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LightClientStatus {
+    /// latest final block of each thread, with its slot
+    pub final_blocks: Vec<(BlockId, Slot)>,
+    /// slot of the latest header received and validated
+    pub last_header_slot: Option<Slot>,
+    /// number of validated headers that are not final yet
+    pub pending_header_count: usize,
+    /// number of connected full nodes that can answer light client queries
+    pub full_node_count: usize,
+}
+
+impl std::fmt::Display for LightClientStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Final blocks:")?;
+        for (block_id, slot) in &self.final_blocks {
+            writeln!(f, "\t{} at slot {}", block_id, slot)?;
+        }
+        match self.last_header_slot {
+            Some(slot) => writeln!(f, "Last header slot: {}", slot)?,
+            None => writeln!(f, "Last header slot: -")?,
+        }
+        writeln!(f, "Pending headers: {}", self.pending_header_count)?;
+        writeln!(f, "Connected full nodes: {}", self.full_node_count)
+    }
+}
+
+/// Final balance of an address, as fetched by a light client from a full node.
+/// It is proven against a ledger root that other full nodes confirmed,
+/// but that no block header commits to: it is not trustless.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LightClientBalance {
+    /// address
+    pub address: Address,
+    /// final sequential balance, None if the address is not in the ledger
+    pub final_balance: Option<Amount>,
+    /// full node that answered the query
+    pub node_id: NodeId,
+}
+
+impl std::fmt::Display for LightClientBalance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.final_balance {
+            Some(balance) => write!(f, "{}: {}", self.address, balance)?,
+            None => write!(f, "{}: not found", self.address)?,
+        }
+        writeln!(f, " (from node {})", self.node_id)
+    }
+}
+
+/// Inclusion of an operation in a block, as proven to a light client by a full node
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LightClientOperationInclusion {
+    /// operation id
+    pub operation_id: OperationId,
+    /// block including the operation, None if no block including it was proven
+    pub block_id: Option<BlockId>,
+    /// slot of that block
+    pub slot: Option<Slot>,
+    /// true if that block is final for the light client
+    pub is_final: bool,
+}
+
+impl std::fmt::Display for LightClientOperationInclusion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.block_id, self.slot) {
+            (Some(block_id), Some(slot)) => writeln!(
+                f,
+                "Operation {} included in block {} at slot {}{}",
+                self.operation_id,
+                block_id,
+                slot,
+                display_if_true(self.is_final, " (final)")
+            ),
+            _ => writeln!(f, "Operation {}: no inclusion proven", self.operation_id),
+        }
+    }
+}
+
+/// Source file of a smart contract
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct ContractSourceFile {
+    /// path of the file, relative to the root of the sources
+    pub path: String,
+    /// content of the file
+    pub content: String,
+}
+
+/// Sources of a deployed smart contract, submitted for verification
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ContractSourceBundle {
+    /// address at which the contract is deployed
+    pub address: Address,
+    /// version of the AssemblyScript compiler that produced the bytecode
+    pub compiler_version: String,
+    /// path of the file to compile, relative to the root of the sources
+    pub entry_file: String,
+    /// AssemblyScript source files
+    pub sources: Vec<ContractSourceFile>,
+    /// descriptor of the functions exported by the contract
+    pub abi: ContractAbi,
+}
+
+/// Sources verified to compile into a bytecode
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct VerifiedContractSources {
+    /// hash of the bytecode the sources compile into
+    pub code_hash: Hash,
+    /// version of the AssemblyScript compiler used
+    pub compiler_version: String,
+    /// path of the compiled file, relative to the root of the sources
+    pub entry_file: String,
+    /// AssemblyScript source files
+    pub sources: Vec<ContractSourceFile>,
+    /// descriptor of the functions exported by the contract
+    pub abi: ContractAbi,
+    /// time of the verification
+    pub verified_at: MassaTime,
+}
+
+/// Metadata of the smart contract deployed at an address
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ContractMetadata {
+    /// address
+    pub address: Address,
+    /// final metadata of the bytecode of the address
+    pub bytecode_metadata: Option<BytecodeMetadata>,
+    /// verified sources of the final bytecode of the address, if any
+    pub verified_sources: Option<VerifiedContractSources>,
+}
+
+impl std::fmt::Display for ContractMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Address: {}", self.address)?;
+        match &self.bytecode_metadata {
+            Some(metadata) => writeln!(
+                f,
+                "Bytecode: hash {}, version {}{}",
+                metadata.code_hash,
+                metadata.version,
+                display_if_true(metadata.immutable, " (immutable)")
+            )?,
+            None => writeln!(f, "Bytecode: none")?,
+        }
+        match &self.verified_sources {
+            Some(sources) => {
+                writeln!(
+                    f,
+                    "Verified sources: {} compiled with AssemblyScript {} ({} files)",
+                    sources.entry_file,
+                    sources.compiler_version,
+                    sources.sources.len()
+                )?;
+                writeln!(f, "Functions:")?;
+                for function in &sources.abi.functions {
+                    writeln!(f, "\t{}", function)?;
+                }
+            }
+            None => writeln!(f, "Verified sources: none")?,
+        }
+        Ok(())
+    }
 }
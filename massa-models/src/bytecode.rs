@@ -0,0 +1,218 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Bytecode metadata and upgradeability controls.
+//!
+//! Every time the bytecode of an address changes, the node records a `BytecodeMetadata`
+//! in the datastore of that address, under the reserved `BYTECODE_METADATA_KEY`.
+//! That key is not valid UTF-8 so smart contracts cannot read or write it through the datastore ABI,
+//! and it is left out of the datastore keys listed by the API.
+//!
+//! A smart contract forbids any future change of its bytecode by setting the `IMMUTABLE_BYTECODE_KEY`
+//! datastore entry of its own address. The datastore ABI only takes UTF-8 keys, so that key is reserved
+//! by starting with a NUL character, which no printable key contains. The entry itself is not stored:
+//! the bytecode is marked as immutable in its metadata instead, and this cannot be undone.
+//!
+//! Both only apply from `BYTECODE_METADATA_START_PERIOD` on.
+
+use crate::slot::{Slot, SlotDeserializer, SlotSerializer};
+use massa_hash::{Hash, HashDeserializer};
+use massa_serialization::{
+    DeserializeError, Deserializer, OptionDeserializer, OptionSerializer, SerializeError,
+    Serializer, U64VarIntDeserializer, U64VarIntSerializer,
+};
+use nom::{
+    error::{context, ContextError, ParseError},
+    sequence::tuple,
+    IResult, Parser,
+};
+use serde::{Deserialize, Serialize};
+use std::ops::Bound::{Excluded, Included};
+
+/// Reserved datastore key under which the metadata of the bytecode of an address is stored
+pub const BYTECODE_METADATA_KEY: &[u8] = b"\xffmassa_bytecode_metadata";
+
+/// Reserved datastore key that a smart contract sets in its own datastore to make its bytecode immutable
+pub const IMMUTABLE_BYTECODE_KEY: &str = "\0massa_immutable_bytecode";
+
+/// Metadata of the bytecode of an address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BytecodeMetadata {
+    /// hash of the bytecode
+    pub code_hash: Hash,
+    /// number of times the bytecode was set, 0 for a bytecode that was never changed since genesis
+    pub version: u64,
+    /// slot at which the bytecode was last set, None if it was never changed since genesis
+    pub last_change_slot: Option<Slot>,
+    /// whether the bytecode can no longer be changed
+    pub immutable: bool,
+}
+
+impl BytecodeMetadata {
+    /// Metadata of a bytecode that was never changed since genesis
+    pub fn genesis(bytecode: &[u8]) -> Self {
+        BytecodeMetadata {
+            code_hash: Hash::compute_from(bytecode),
+            version: 0,
+            last_change_slot: None,
+            immutable: false,
+        }
+    }
+
+    /// Reads the metadata of a bytecode from the value of the `BYTECODE_METADATA_KEY` datastore entry,
+    /// or builds the metadata of a bytecode that was never changed since genesis if there is no valid entry
+    pub fn from_datastore(
+        entry: Option<&[u8]>,
+        bytecode: &[u8],
+        deserializer: &BytecodeMetadataDeserializer,
+    ) -> Self {
+        entry
+            .and_then(|data| deserializer.deserialize::<DeserializeError>(data).ok())
+            .map_or_else(
+                || BytecodeMetadata::genesis(bytecode),
+                |(_, metadata)| metadata,
+            )
+    }
+
+    /// Serializes the metadata as the value of the `BYTECODE_METADATA_KEY` datastore entry
+    pub fn to_datastore(&self) -> Result<Vec<u8>, SerializeError> {
+        let mut data = Vec::new();
+        BytecodeMetadataSerializer::new().serialize(self, &mut data)?;
+        Ok(data)
+    }
+
+    /// Metadata following a change of the bytecode at a given slot
+    pub fn next(&self, bytecode: &[u8], slot: Slot) -> Self {
+        BytecodeMetadata {
+            code_hash: Hash::compute_from(bytecode),
+            version: self.version.saturating_add(1),
+            last_change_slot: Some(slot),
+            immutable: self.immutable,
+        }
+    }
+}
+
+/// Serializer for `BytecodeMetadata`
+pub struct BytecodeMetadataSerializer {
+    u64_serializer: U64VarIntSerializer,
+    slot_serializer: OptionSerializer<Slot, SlotSerializer>,
+}
+
+impl BytecodeMetadataSerializer {
+    /// Creates a new `BytecodeMetadataSerializer`
+    pub fn new() -> Self {
+        BytecodeMetadataSerializer {
+            u64_serializer: U64VarIntSerializer::new(),
+            slot_serializer: OptionSerializer::new(SlotSerializer::new()),
+        }
+    }
+}
+
+impl Default for BytecodeMetadataSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<BytecodeMetadata> for BytecodeMetadataSerializer {
+    /// ## Example:
+    /// ```rust
+    /// use massa_models::bytecode::{BytecodeMetadata, BytecodeMetadataSerializer};
+    /// use massa_models::slot::Slot;
+    /// use massa_serialization::Serializer;
+    ///
+    /// let metadata = BytecodeMetadata::genesis(&[1, 2, 3]).next(&[4, 5], Slot::new(1, 2));
+    /// let mut buffer = vec![];
+    /// BytecodeMetadataSerializer::new().serialize(&metadata, &mut buffer).unwrap();
+    /// ```
+    fn serialize(
+        &self,
+        value: &BytecodeMetadata,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        buffer.extend(value.code_hash.to_bytes());
+        self.u64_serializer.serialize(&value.version, buffer)?;
+        self.slot_serializer
+            .serialize(&value.last_change_slot, buffer)?;
+        buffer.push(u8::from(value.immutable));
+        Ok(())
+    }
+}
+
+/// Deserializer for `BytecodeMetadata`
+pub struct BytecodeMetadataDeserializer {
+    hash_deserializer: HashDeserializer,
+    u64_deserializer: U64VarIntDeserializer,
+    slot_deserializer: OptionDeserializer<Slot, SlotDeserializer>,
+}
+
+impl BytecodeMetadataDeserializer {
+    /// Creates a new `BytecodeMetadataDeserializer`
+    ///
+    /// # Arguments
+    /// * `thread_count`: number of threads, bounding the thread of the last change slot
+    pub fn new(thread_count: u8) -> Self {
+        BytecodeMetadataDeserializer {
+            hash_deserializer: HashDeserializer::new(),
+            u64_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+            slot_deserializer: OptionDeserializer::new(SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            )),
+        }
+    }
+}
+
+impl Deserializer<BytecodeMetadata> for BytecodeMetadataDeserializer {
+    /// ## Example:
+    /// ```rust
+    /// use massa_models::bytecode::{BytecodeMetadata, BytecodeMetadataDeserializer, BytecodeMetadataSerializer};
+    /// use massa_models::slot::Slot;
+    /// use massa_serialization::{Serializer, Deserializer, DeserializeError};
+    ///
+    /// let metadata = BytecodeMetadata::genesis(&[1, 2, 3]).next(&[4, 5], Slot::new(1, 2));
+    /// let mut buffer = vec![];
+    /// BytecodeMetadataSerializer::new().serialize(&metadata, &mut buffer).unwrap();
+    /// let (rest, deserialized) = BytecodeMetadataDeserializer::new(32).deserialize::<DeserializeError>(&buffer).unwrap();
+    /// assert_eq!(rest.len(), 0);
+    /// assert_eq!(metadata, deserialized);
+    /// ```
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], BytecodeMetadata, E> {
+        context(
+            "Failed BytecodeMetadata deserialization",
+            tuple((
+                context("Failed code_hash deserialization", |input| {
+                    self.hash_deserializer.deserialize(input)
+                }),
+                context("Failed version deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context("Failed last_change_slot deserialization", |input| {
+                    self.slot_deserializer.deserialize(input)
+                }),
+                context("Failed immutable deserialization", |input| {
+                    let (rest, value) = nom::number::complete::u8(input)?;
+                    match value {
+                        0 => Ok((rest, false)),
+                        1 => Ok((rest, true)),
+                        _ => Err(nom::Err::Error(ParseError::from_error_kind(
+                            input,
+                            nom::error::ErrorKind::Verify,
+                        ))),
+                    }
+                }),
+            )),
+        )
+        .map(
+            |(code_hash, version, last_change_slot, immutable)| BytecodeMetadata {
+                code_hash,
+                version,
+                last_change_slot,
+                immutable,
+            },
+        )
+        .parse(buffer)
+    }
+}
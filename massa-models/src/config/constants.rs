@@ -64,13 +64,15 @@ lazy_static::lazy_static! {
         if cfg!(feature = "sandbox") {
             "SAND.0.0"
         } else {
-            "TEST.14.7"
+            "TEST.14.8"
         }
         .parse()
         .unwrap()
     };
 }
 
+/// Lowest (major, minor) node version advertising peers with their port
+pub const PEER_PORTS_MIN_VERSION: (u32, u32) = (0, 8);
 /// Price of a roll in the network
 pub const ROLL_PRICE: Amount = Amount::from_mantissa_scale(100, 0);
 /// Block reward is given for each block creation
@@ -128,6 +130,14 @@ pub const MAX_DATASTORE_ENTRY_COUNT: u64 = u64::MAX;
 pub const MAX_FUNCTION_NAME_LENGTH: u16 = u16::MAX;
 /// Maximum size of parameters in call sc
 pub const MAX_PARAMETERS_SIZE: u32 = 10_000_000;
+/// Maximum number of public keys of a multisig account
+pub const MAX_MULTISIG_PUBLIC_KEYS: u8 = 20;
+/// Maximum length of the topic of a structured event
+pub const MAX_EVENT_TOPIC_LENGTH: u16 = 255;
+/// Maximum number of fields in a structured event
+pub const MAX_EVENT_FIELD_COUNT: u32 = 255;
+/// Maximum length of a string or binary value in a structured event
+pub const MAX_EVENT_VALUE_LENGTH: u64 = 10_000;
 /// Maximum length of rng_seed in thread cycle
 pub const MAX_RNG_SEED_LENGTH: u32 = PERIODS_PER_CYCLE.saturating_mul(THREAD_COUNT as u64) as u32;
 /// Maximum length of rolls_update in thread cycle
@@ -179,6 +189,32 @@ pub const PROTOCOL_EVENT_CHANNEL_SIZE: usize = 1024;
 pub const MAX_GAS_PER_BLOCK: u64 = 1_000_000_000;
 /// Maximum of GAS allowed for asynchronous messages execution on one slot
 pub const MAX_ASYNC_GAS: u64 = 1_000_000_000;
+/// Period from which the unused gas of operations is refunded and only the gas they consume counts in the block gas.
+/// Before it, operations pay for their max gas and use it up in the block gas.
+pub const GAS_REFUND_START_PERIOD: u64 = if cfg!(feature = "sandbox") {
+    0
+} else {
+    27_000 // Monday, September 26, 2022 10:00:00 PM UTC
+};
+/// Period from which `CancelAsyncMessage` operations can be included in blocks. Before it, they are skipped.
+pub const ASYNC_MESSAGE_CANCELLATION_START_PERIOD: u64 = if cfg!(feature = "sandbox") {
+    0
+} else {
+    27_000 // Monday, September 26, 2022 10:00:00 PM UTC
+};
+/// Period from which `MultisigExecute` operations can be included in blocks. Before it, they are skipped.
+pub const MULTISIG_START_PERIOD: u64 = if cfg!(feature = "sandbox") {
+    0
+} else {
+    27_000 // Monday, September 26, 2022 10:00:00 PM UTC
+};
+/// Period from which the metadata of bytecodes is recorded and smart contracts can make their bytecode immutable.
+/// Before it, bytecodes change without any metadata and `IMMUTABLE_BYTECODE_KEY` is an ordinary datastore key.
+pub const BYTECODE_METADATA_START_PERIOD: u64 = if cfg!(feature = "sandbox") {
+    0
+} else {
+    27_000 // Monday, September 26, 2022 10:00:00 PM UTC
+};
 
 //
 // Constants used in network
@@ -52,6 +52,8 @@ pub enum ModelsError {
     OperationPrefixJoinError,
     /// Outdated bootstrap cursor
     OutdatedBootstrapCursor,
+    /// multisig error: {0}
+    MultisigError(String),
     /// Error raised {0}
     ErrorRaised(String),
 }
@@ -1,15 +1,17 @@
 use std::{collections::VecDeque, fmt::Display};
 
-use crate::{output_event::SCOutputEvent, slot::Slot};
+use crate::{
+    address::Address, amount::Amount, api::AsyncMessageInfo, output_event::SCOutputEvent,
+    slot::Slot,
+};
 use serde::{Deserialize, Serialize};
 
 /// The result of the read-only execution.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum ReadOnlyResult {
-    /// An error occurred during execution.
-    Error(String),
-    /// The result of a successful execution.
-    /// TODO: specify result.
+    /// An error occurred during execution: chain of errors, outermost first.
+    Error(Vec<String>),
+    /// The execution succeeded.
     Ok,
 }
 
@@ -20,8 +22,64 @@ pub struct ExecuteReadOnlyResponse {
     pub executed_at: Slot,
     /// The result of the read-only execution.
     pub result: ReadOnlyResult,
+    /// The gas used by the read-only execution, zero if it failed.
+    pub gas_cost: u64,
     /// The output events generated by the read-only execution.
     pub output_events: VecDeque<SCOutputEvent>,
+    /// The state changes caused by the read-only execution, state overrides excluded.
+    pub state_changes: ReadOnlyStateChanges,
+}
+
+/// State injected for an address before a read-only execution.
+/// Fields left empty keep the value of the executed state.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReadOnlyStateOverride {
+    /// overridden address
+    pub address: Address,
+    /// sequential balance to set
+    #[serde(default)]
+    pub sequential_balance: Option<Amount>,
+    /// parallel balance to set
+    #[serde(default)]
+    pub parallel_balance: Option<Amount>,
+    /// bytecode to set
+    #[serde(default)]
+    pub bytecode: Option<Vec<u8>>,
+    /// datastore entries to set, as (key, value) pairs
+    #[serde(default)]
+    pub datastore: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// Change of a ledger entry caused by a read-only execution
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ReadOnlyLedgerChange {
+    /// changed address
+    pub address: Address,
+    /// true if the entry is deleted
+    pub deleted: bool,
+    /// new sequential balance, if changed
+    pub sequential_balance: Option<Amount>,
+    /// new parallel balance, if changed
+    pub parallel_balance: Option<Amount>,
+    /// new bytecode, if changed
+    pub bytecode: Option<Vec<u8>>,
+    /// changed datastore entries as (key, value) pairs, with no value for deleted entries
+    pub datastore: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+}
+
+/// State changes caused by a read-only execution
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ReadOnlyStateChanges {
+    /// changes of ledger entries
+    pub ledger_changes: Vec<ReadOnlyLedgerChange>,
+    /// asynchronous messages emitted
+    pub async_messages_added: Vec<AsyncMessageInfo>,
+    /// asynchronous messages removed from the pool, identified by emission slot and index
+    pub async_messages_removed: Vec<(Slot, u64)>,
+    /// new roll counts of addresses
+    pub roll_changes: Vec<(Address, u64)>,
+    /// deferred credits set, as (target slot, address, amount)
+    pub deferred_credits: Vec<(Slot, Address, Amount)>,
 }
 
 impl Display for ExecuteReadOnlyResponse {
@@ -31,11 +89,44 @@ impl Display for ExecuteReadOnlyResponse {
             f,
             "Result: {}",
             match &self.result {
-                ReadOnlyResult::Error(e) =>
-                    format!("an error occurred during the execution: {}", e),
+                ReadOnlyResult::Error(chain) => format!(
+                    "an error occurred during the execution: {}",
+                    chain.join(": ")
+                ),
                 ReadOnlyResult::Ok => "ok".to_string(),
             }
         )?;
+        writeln!(f, "Gas cost: {}", self.gas_cost)?;
+        let changes = &self.state_changes;
+        if !changes.ledger_changes.is_empty() {
+            writeln!(f, "Ledger changes:")?;
+            for change in changes.ledger_changes.iter() {
+                writeln!(f, "\t{}", change)?;
+            }
+        }
+        if !changes.async_messages_added.is_empty() {
+            writeln!(f, "Emitted asynchronous messages:")?;
+            for message in changes.async_messages_added.iter() {
+                writeln!(f, "{}", message)?;
+            }
+        }
+        if !changes.async_messages_removed.is_empty() {
+            writeln!(
+                f,
+                "Asynchronous messages removed from the pool: {}",
+                changes.async_messages_removed.len()
+            )?;
+        }
+        for (address, roll_count) in changes.roll_changes.iter() {
+            writeln!(f, "Roll count of {}: {}", address, roll_count)?;
+        }
+        for (slot, address, amount) in changes.deferred_credits.iter() {
+            writeln!(
+                f,
+                "Deferred credit of {} to {} at slot {}",
+                amount, address, slot
+            )?;
+        }
         if !self.output_events.is_empty() {
             writeln!(f, "Generated events:",)?;
             for event in self.output_events.iter() {
@@ -45,3 +136,25 @@ impl Display for ExecuteReadOnlyResponse {
         Ok(())
     }
 }
+
+impl Display for ReadOnlyLedgerChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.deleted {
+            return write!(f, "{}: deleted", self.address);
+        }
+        write!(f, "{}:", self.address)?;
+        if let Some(balance) = self.sequential_balance {
+            write!(f, " sequential balance {}", balance)?;
+        }
+        if let Some(balance) = self.parallel_balance {
+            write!(f, " parallel balance {}", balance)?;
+        }
+        if let Some(bytecode) = &self.bytecode {
+            write!(f, " bytecode of {} bytes", bytecode.len())?;
+        }
+        if !self.datastore.is_empty() {
+            write!(f, " {} datastore entries changed", self.datastore.len())?;
+        }
+        Ok(())
+    }
+}
@@ -9,6 +9,9 @@
 
 extern crate lazy_static;
 
+/// smart contract ABI descriptor
+pub mod abi;
+
 /// active blocks related structures
 pub mod active_block;
 /// address related structures
@@ -19,6 +22,8 @@ pub mod amount;
 pub mod api;
 /// block-related sturctures
 pub mod block;
+/// bytecode metadata and upgradeability controls
+pub mod bytecode;
 /// clique
 pub mod clique;
 /// various structures
@@ -33,6 +38,8 @@ pub mod error;
 pub mod execution;
 /// ledger related structures
 pub mod ledger_models;
+/// multi-signature accounts
+pub mod multisig;
 /// node related structure
 pub mod node;
 /// operations
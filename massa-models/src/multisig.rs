@@ -0,0 +1,688 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Multi-signature (M-of-N) accounts.
+//!
+//! A multisig address is derived from a set of public keys and a threshold.
+//! Coins held by a multisig address are spent through a `MultisigProposal`
+//! signed by at least `threshold` of the keys, submitted by any address of the same thread
+//! in a `OperationType::MultisigExecute` operation.
+
+use crate::config::MAX_MULTISIG_PUBLIC_KEYS;
+use crate::{
+    address::{Address, AddressDeserializer},
+    amount::{Amount, AmountDeserializer, AmountSerializer},
+    error::ModelsError,
+    operation::OperationType,
+};
+use massa_hash::Hash;
+use massa_serialization::{
+    Deserializer, SerializeError, Serializer, U32VarIntDeserializer, U32VarIntSerializer,
+    U64VarIntDeserializer, U64VarIntSerializer,
+};
+use massa_signature::{
+    KeyPair, PublicKey, PublicKeyDeserializer, Signature, SignatureDeserializer,
+};
+use nom::{
+    error::{context, ContextError, ParseError},
+    multi::length_count,
+    sequence::tuple,
+    IResult, Parser,
+};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use serde::{Deserialize, Serialize};
+use std::ops::Bound::Included;
+use std::ops::RangeInclusive;
+
+/// Prefix of the data hashed to compute a multisig address
+const MULTISIG_ADDRESS_PREFIX: &[u8] = b"massa_multisig";
+
+/// Set of public keys and the number of them that must sign to spend from the multisig address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigDescriptor {
+    /// minimal number of signatures required
+    pub threshold: u8,
+    /// public keys allowed to sign, sorted and without duplicates
+    pub public_keys: Vec<PublicKey>,
+}
+
+impl MultisigDescriptor {
+    /// Creates a new descriptor. Public keys are sorted and deduplicated.
+    ///
+    /// # Arguments
+    /// * `threshold`: minimal number of signatures required
+    /// * `public_keys`: public keys allowed to sign
+    pub fn new(threshold: u8, mut public_keys: Vec<PublicKey>) -> Result<Self, ModelsError> {
+        public_keys.sort_unstable();
+        public_keys.dedup();
+        let descriptor = MultisigDescriptor {
+            threshold,
+            public_keys,
+        };
+        descriptor.check()?;
+        Ok(descriptor)
+    }
+
+    /// Checks that the descriptor is well-formed:
+    /// keys sorted without duplicates, at most `MAX_MULTISIG_PUBLIC_KEYS` keys and `1 <= threshold <= key count`
+    pub fn check(&self) -> Result<(), ModelsError> {
+        if self.public_keys.len() > MAX_MULTISIG_PUBLIC_KEYS as usize {
+            return Err(ModelsError::MultisigError(format!(
+                "too many public keys: {} (max {})",
+                self.public_keys.len(),
+                MAX_MULTISIG_PUBLIC_KEYS
+            )));
+        }
+        if self.threshold == 0 || self.threshold as usize > self.public_keys.len() {
+            return Err(ModelsError::MultisigError(format!(
+                "invalid threshold {} for {} public keys",
+                self.threshold,
+                self.public_keys.len()
+            )));
+        }
+        if self.public_keys.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(ModelsError::MultisigError(
+                "public keys must be sorted and unique".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Computes the address of the multisig account
+    pub fn address(&self) -> Address {
+        let mut data = MULTISIG_ADDRESS_PREFIX.to_vec();
+        data.push(self.threshold);
+        for public_key in &self.public_keys {
+            data.extend(public_key.to_bytes());
+        }
+        Address(Hash::compute_from(&data))
+    }
+
+    /// Gets the index of a public key among the signers
+    pub fn signer_index(&self, public_key: &PublicKey) -> Option<u8> {
+        self.public_keys
+            .binary_search(public_key)
+            .ok()
+            .map(|index| index as u8)
+    }
+}
+
+impl std::fmt::Display for MultisigDescriptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}-of-{} multisig {}",
+            self.threshold,
+            self.public_keys.len(),
+            self.address()
+        )?;
+        for (index, public_key) in self.public_keys.iter().enumerate() {
+            writeln!(f, "\t- signer {}: {}", index, public_key)?;
+        }
+        Ok(())
+    }
+}
+
+/// Action performed on behalf of a multisig address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MultisigAction {
+    /// transfer sequential coins from the multisig address to the recipient
+    Transaction {
+        /// recipient address
+        recipient_address: Address,
+        /// amount
+        amount: Amount,
+    },
+    /// the multisig address buys `roll_count` rolls
+    RollBuy {
+        /// roll count
+        roll_count: u64,
+    },
+    /// the multisig address sells `roll_count` rolls
+    RollSell {
+        /// roll count
+        roll_count: u64,
+    },
+}
+
+impl MultisigAction {
+    /// Gets the equivalent operation type, as if the multisig address had sent it
+    pub fn to_operation_type(&self) -> OperationType {
+        match self {
+            MultisigAction::Transaction {
+                recipient_address,
+                amount,
+            } => OperationType::Transaction {
+                recipient_address: *recipient_address,
+                amount: *amount,
+            },
+            MultisigAction::RollBuy { roll_count } => OperationType::RollBuy {
+                roll_count: *roll_count,
+            },
+            MultisigAction::RollSell { roll_count } => OperationType::RollSell {
+                roll_count: *roll_count,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for MultisigAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MultisigAction::Transaction {
+                recipient_address,
+                amount,
+            } => write!(f, "transfer {} to {}", amount, recipient_address),
+            MultisigAction::RollBuy { roll_count } => write!(f, "buy {} rolls", roll_count),
+            MultisigAction::RollSell { roll_count } => write!(f, "sell {} rolls", roll_count),
+        }
+    }
+}
+
+/// Action proposed on behalf of a multisig address, to be signed by its signers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigProposal {
+    /// multisig account
+    pub descriptor: MultisigDescriptor,
+    /// proposed action
+    pub action: MultisigAction,
+    /// arbitrary number distinguishing otherwise identical proposals
+    pub nonce: u64,
+    /// expiration period, must match the one of the operation submitting the proposal
+    pub expire_period: u64,
+}
+
+impl MultisigProposal {
+    /// Computes the hash signed by the signers. It also identifies the proposal once executed.
+    pub fn compute_hash(&self) -> Result<Hash, ModelsError> {
+        let mut buffer = Vec::new();
+        MultisigProposalSerializer::new().serialize(self, &mut buffer)?;
+        Ok(Hash::compute_from(&buffer))
+    }
+
+    /// Gets the range of periods during which the proposal can be executed
+    pub fn get_validity_range(&self, operation_validity_period: u64) -> RangeInclusive<u64> {
+        let start = self.expire_period.saturating_sub(operation_validity_period);
+        start..=self.expire_period
+    }
+
+    /// Signs the proposal with a keypair that must be one of the signers
+    pub fn sign(&self, keypair: &KeyPair) -> Result<MultisigSignature, ModelsError> {
+        let signer_index = self
+            .descriptor
+            .signer_index(&keypair.get_public_key())
+            .ok_or_else(|| {
+                ModelsError::MultisigError(
+                    "the keypair is not among the multisig signers".to_string(),
+                )
+            })?;
+        Ok(MultisigSignature {
+            signer_index,
+            signature: keypair.sign(&self.compute_hash()?)?,
+        })
+    }
+
+    /// Verifies that enough distinct signers signed the proposal
+    pub fn verify(&self, signatures: &[MultisigSignature]) -> Result<(), ModelsError> {
+        self.descriptor.check()?;
+        let hash = self.compute_hash()?;
+        let mut signed = vec![false; self.descriptor.public_keys.len()];
+        for multisig_signature in signatures {
+            let index = multisig_signature.signer_index as usize;
+            let public_key = self.descriptor.public_keys.get(index).ok_or_else(|| {
+                ModelsError::MultisigError(format!("invalid signer index {}", index))
+            })?;
+            if signed[index] {
+                return Err(ModelsError::MultisigError(format!(
+                    "duplicate signature from signer {}",
+                    index
+                )));
+            }
+            public_key.verify_signature(&hash, &multisig_signature.signature)?;
+            signed[index] = true;
+        }
+        if signatures.len() < self.descriptor.threshold as usize {
+            return Err(ModelsError::MultisigError(format!(
+                "{} signatures provided, {} required",
+                signatures.len(),
+                self.descriptor.threshold
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for MultisigProposal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.descriptor)?;
+        writeln!(f, "\t- action: {}", self.action)?;
+        writeln!(f, "\t- nonce: {}", self.nonce)?;
+        writeln!(f, "\t- expire period: {}", self.expire_period)?;
+        Ok(())
+    }
+}
+
+/// Signature of a multisig proposal by one of the signers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigSignature {
+    /// index of the signer's public key in the descriptor
+    pub signer_index: u8,
+    /// signature of the proposal hash
+    pub signature: Signature,
+}
+
+/// Merges several sets of signatures of the same proposal, keeping one signature per signer,
+/// sorted by signer index.
+pub fn combine_multisig_signatures(
+    signature_sets: impl IntoIterator<Item = MultisigSignature>,
+) -> Vec<MultisigSignature> {
+    let mut res: Vec<MultisigSignature> = Vec::new();
+    for multisig_signature in signature_sets {
+        if !res
+            .iter()
+            .any(|s| s.signer_index == multisig_signature.signer_index)
+        {
+            res.push(multisig_signature);
+        }
+    }
+    res.sort_unstable_by_key(|s| s.signer_index);
+    res
+}
+
+#[derive(IntoPrimitive, TryFromPrimitive)]
+#[repr(u32)]
+enum MultisigActionId {
+    Transaction = 0,
+    RollBuy = 1,
+    RollSell = 2,
+}
+
+/// Serializer for `MultisigProposal`
+pub struct MultisigProposalSerializer {
+    u32_serializer: U32VarIntSerializer,
+    u64_serializer: U64VarIntSerializer,
+    amount_serializer: AmountSerializer,
+}
+
+impl MultisigProposalSerializer {
+    /// Creates a new `MultisigProposalSerializer`
+    pub fn new() -> Self {
+        Self {
+            u32_serializer: U32VarIntSerializer::new(),
+            u64_serializer: U64VarIntSerializer::new(),
+            amount_serializer: AmountSerializer::new(),
+        }
+    }
+}
+
+impl Default for MultisigProposalSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<MultisigProposal> for MultisigProposalSerializer {
+    /// ## Example
+    /// ```rust
+    /// use massa_models::{address::Address, amount::Amount, multisig::{MultisigAction, MultisigDescriptor, MultisigProposal, MultisigProposalSerializer}};
+    /// use massa_serialization::Serializer;
+    /// use massa_signature::KeyPair;
+    /// use std::str::FromStr;
+    ///
+    /// let keypair = KeyPair::generate();
+    /// let proposal = MultisigProposal {
+    ///     descriptor: MultisigDescriptor::new(1, vec![keypair.get_public_key()]).unwrap(),
+    ///     action: MultisigAction::Transaction {
+    ///         recipient_address: Address::from_public_key(&keypair.get_public_key()),
+    ///         amount: Amount::from_str("10").unwrap(),
+    ///     },
+    ///     nonce: 0,
+    ///     expire_period: 10,
+    /// };
+    /// let mut buffer = Vec::new();
+    /// MultisigProposalSerializer::new().serialize(&proposal, &mut buffer).unwrap();
+    /// ```
+    fn serialize(
+        &self,
+        value: &MultisigProposal,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        buffer.push(value.descriptor.threshold);
+        let key_count: u32 = value
+            .descriptor
+            .public_keys
+            .len()
+            .try_into()
+            .map_err(|err| {
+                SerializeError::NumberTooBig(format!("too many multisig public keys: {}", err))
+            })?;
+        self.u32_serializer.serialize(&key_count, buffer)?;
+        for public_key in &value.descriptor.public_keys {
+            buffer.extend(public_key.to_bytes());
+        }
+        match &value.action {
+            MultisigAction::Transaction {
+                recipient_address,
+                amount,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MultisigActionId::Transaction), buffer)?;
+                buffer.extend(recipient_address.to_bytes());
+                self.amount_serializer.serialize(amount, buffer)?;
+            }
+            MultisigAction::RollBuy { roll_count } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MultisigActionId::RollBuy), buffer)?;
+                self.u64_serializer.serialize(roll_count, buffer)?;
+            }
+            MultisigAction::RollSell { roll_count } => {
+                self.u32_serializer
+                    .serialize(&u32::from(MultisigActionId::RollSell), buffer)?;
+                self.u64_serializer.serialize(roll_count, buffer)?;
+            }
+        }
+        self.u64_serializer.serialize(&value.nonce, buffer)?;
+        self.u64_serializer
+            .serialize(&value.expire_period, buffer)?;
+        Ok(())
+    }
+}
+
+/// Deserializer for `MultisigProposal`
+pub struct MultisigProposalDeserializer {
+    key_count_deserializer: U32VarIntDeserializer,
+    public_key_deserializer: PublicKeyDeserializer,
+    id_deserializer: U32VarIntDeserializer,
+    u64_deserializer: U64VarIntDeserializer,
+    address_deserializer: AddressDeserializer,
+    amount_deserializer: AmountDeserializer,
+}
+
+impl MultisigProposalDeserializer {
+    /// Creates a new `MultisigProposalDeserializer`
+    pub fn new() -> Self {
+        Self {
+            key_count_deserializer: U32VarIntDeserializer::new(
+                Included(1),
+                Included(MAX_MULTISIG_PUBLIC_KEYS as u32),
+            ),
+            public_key_deserializer: PublicKeyDeserializer::new(),
+            id_deserializer: U32VarIntDeserializer::new(Included(0), Included(u32::MAX)),
+            u64_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+            address_deserializer: AddressDeserializer::new(),
+            amount_deserializer: AmountDeserializer::new(
+                Included(Amount::MIN),
+                Included(Amount::MAX),
+            ),
+        }
+    }
+
+    fn deserialize_action<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], MultisigAction, E> {
+        context("Failed MultisigAction deserialization", |buffer| {
+            let (input, id) = self.id_deserializer.deserialize(buffer)?;
+            let id = MultisigActionId::try_from(id).map_err(|_| {
+                nom::Err::Error(ParseError::from_error_kind(
+                    buffer,
+                    nom::error::ErrorKind::Eof,
+                ))
+            })?;
+            match id {
+                MultisigActionId::Transaction => tuple((
+                    context("Failed recipient_address deserialization", |input| {
+                        self.address_deserializer.deserialize(input)
+                    }),
+                    context("Failed amount deserialization", |input| {
+                        self.amount_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(|(recipient_address, amount)| MultisigAction::Transaction {
+                    recipient_address,
+                    amount,
+                })
+                .parse(input),
+                MultisigActionId::RollBuy => {
+                    context("Failed roll_count deserialization", |input| {
+                        self.u64_deserializer.deserialize(input)
+                    })
+                    .map(|roll_count| MultisigAction::RollBuy { roll_count })
+                    .parse(input)
+                }
+                MultisigActionId::RollSell => {
+                    context("Failed roll_count deserialization", |input| {
+                        self.u64_deserializer.deserialize(input)
+                    })
+                    .map(|roll_count| MultisigAction::RollSell { roll_count })
+                    .parse(input)
+                }
+            }
+        })
+        .parse(buffer)
+    }
+}
+
+impl Default for MultisigProposalDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deserializer<MultisigProposal> for MultisigProposalDeserializer {
+    /// ## Example
+    /// ```rust
+    /// use massa_models::{multisig::{MultisigAction, MultisigDescriptor, MultisigProposal, MultisigProposalSerializer, MultisigProposalDeserializer}};
+    /// use massa_serialization::{Serializer, Deserializer, DeserializeError};
+    /// use massa_signature::KeyPair;
+    ///
+    /// let keys = vec![KeyPair::generate().get_public_key(), KeyPair::generate().get_public_key()];
+    /// let proposal = MultisigProposal {
+    ///     descriptor: MultisigDescriptor::new(2, keys).unwrap(),
+    ///     action: MultisigAction::RollBuy { roll_count: 3 },
+    ///     nonce: 1,
+    ///     expire_period: 10,
+    /// };
+    /// let mut buffer = Vec::new();
+    /// MultisigProposalSerializer::new().serialize(&proposal, &mut buffer).unwrap();
+    /// let (rest, proposal_deser) = MultisigProposalDeserializer::new().deserialize::<DeserializeError>(&buffer).unwrap();
+    /// assert!(rest.is_empty());
+    /// assert_eq!(proposal, proposal_deser);
+    /// ```
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], MultisigProposal, E> {
+        context(
+            "Failed MultisigProposal deserialization",
+            tuple((
+                context("Failed threshold deserialization", |input| {
+                    nom::number::complete::u8(input)
+                }),
+                context(
+                    "Failed public_keys deserialization",
+                    length_count(
+                        |input| self.key_count_deserializer.deserialize(input),
+                        |input| self.public_key_deserializer.deserialize(input),
+                    ),
+                ),
+                |input| self.deserialize_action(input),
+                context("Failed nonce deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+                context("Failed expire_period deserialization", |input| {
+                    self.u64_deserializer.deserialize(input)
+                }),
+            )),
+        )
+        .map(
+            |(threshold, public_keys, action, nonce, expire_period)| MultisigProposal {
+                descriptor: MultisigDescriptor {
+                    threshold,
+                    public_keys,
+                },
+                action,
+                nonce,
+                expire_period,
+            },
+        )
+        .parse(buffer)
+    }
+}
+
+/// Serializer for a list of `MultisigSignature`
+pub struct MultisigSignaturesSerializer {
+    u32_serializer: U32VarIntSerializer,
+}
+
+impl MultisigSignaturesSerializer {
+    /// Creates a new `MultisigSignaturesSerializer`
+    pub fn new() -> Self {
+        Self {
+            u32_serializer: U32VarIntSerializer::new(),
+        }
+    }
+}
+
+impl Default for MultisigSignaturesSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<Vec<MultisigSignature>> for MultisigSignaturesSerializer {
+    fn serialize(
+        &self,
+        value: &Vec<MultisigSignature>,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        let count: u32 = value.len().try_into().map_err(|err| {
+            SerializeError::NumberTooBig(format!("too many multisig signatures: {}", err))
+        })?;
+        self.u32_serializer.serialize(&count, buffer)?;
+        for multisig_signature in value {
+            buffer.push(multisig_signature.signer_index);
+            buffer.extend(multisig_signature.signature.to_bytes());
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for a list of `MultisigSignature`
+pub struct MultisigSignaturesDeserializer {
+    count_deserializer: U32VarIntDeserializer,
+    signature_deserializer: SignatureDeserializer,
+}
+
+impl MultisigSignaturesDeserializer {
+    /// Creates a new `MultisigSignaturesDeserializer`
+    pub fn new() -> Self {
+        Self {
+            count_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(MAX_MULTISIG_PUBLIC_KEYS as u32),
+            ),
+            signature_deserializer: SignatureDeserializer::new(),
+        }
+    }
+}
+
+impl Default for MultisigSignaturesDeserializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deserializer<Vec<MultisigSignature>> for MultisigSignaturesDeserializer {
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], Vec<MultisigSignature>, E> {
+        context(
+            "Failed multisig signatures deserialization",
+            length_count(
+                |input| self.count_deserializer.deserialize(input),
+                tuple((
+                    context("Failed signer_index deserialization", |input| {
+                        nom::number::complete::u8(input)
+                    }),
+                    context("Failed signature deserialization", |input| {
+                        self.signature_deserializer.deserialize(input)
+                    }),
+                ))
+                .map(|(signer_index, signature)| MultisigSignature {
+                    signer_index,
+                    signature,
+                }),
+            ),
+        )
+        .parse(buffer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    fn sample_proposal(keypairs: &[KeyPair], threshold: u8) -> MultisigProposal {
+        MultisigProposal {
+            descriptor: MultisigDescriptor::new(
+                threshold,
+                keypairs.iter().map(|k| k.get_public_key()).collect(),
+            )
+            .unwrap(),
+            action: MultisigAction::Transaction {
+                recipient_address: Address::from_public_key(&keypairs[0].get_public_key()),
+                amount: Amount::from_str("10").unwrap(),
+            },
+            nonce: 0,
+            expire_period: 10,
+        }
+    }
+
+    #[test]
+    fn test_multisig_address_is_order_independent() {
+        let keypairs: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+        let keys: Vec<PublicKey> = keypairs.iter().map(|k| k.get_public_key()).collect();
+        let mut reversed_keys = keys.clone();
+        reversed_keys.reverse();
+        let descriptor = MultisigDescriptor::new(2, keys).unwrap();
+        assert_eq!(
+            descriptor.address(),
+            MultisigDescriptor::new(2, reversed_keys).unwrap().address()
+        );
+        assert_ne!(
+            descriptor.address(),
+            MultisigDescriptor::new(3, descriptor.public_keys.clone())
+                .unwrap()
+                .address()
+        );
+        assert!(MultisigDescriptor::new(4, descriptor.public_keys.clone()).is_err());
+        assert!(MultisigDescriptor::new(0, descriptor.public_keys).is_err());
+    }
+
+    #[test]
+    fn test_multisig_threshold_verification() {
+        let keypairs: Vec<KeyPair> = (0..3).map(|_| KeyPair::generate()).collect();
+        let proposal = sample_proposal(&keypairs, 2);
+
+        // one signature is not enough, even when repeated
+        let first = proposal.sign(&keypairs[0]).unwrap();
+        assert!(proposal.verify(&[first]).is_err());
+        assert!(proposal.verify(&[first, first]).is_err());
+
+        // partial signatures combined reach the threshold
+        let third = proposal.sign(&keypairs[2]).unwrap();
+        let combined = combine_multisig_signatures(vec![third, first, first]);
+        assert_eq!(combined.len(), 2);
+        proposal.verify(&combined).unwrap();
+
+        // signatures are bound to the proposal
+        let mut other_proposal = proposal.clone();
+        other_proposal.nonce = 1;
+        assert!(other_proposal.verify(&combined).is_err());
+
+        // outsiders cannot sign
+        assert!(proposal.sign(&KeyPair::generate()).is_err());
+    }
+}
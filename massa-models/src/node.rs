@@ -26,3 +26,27 @@ impl std::str::FromStr for NodeId {
         Ok(NodeId(PublicKey::from_bs58_check(s)?))
     }
 }
+
+/// Reason why a node was banned
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum BanReason {
+    /// banned by the node operator
+    Manual,
+    /// the node sent an invalid block or propagated an attack attempt
+    InvalidBlock,
+    /// the node sent an invalid operation
+    InvalidOperation,
+    /// the node did not follow the protocol
+    ProtocolViolation,
+}
+
+impl std::fmt::Display for BanReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BanReason::Manual => write!(f, "manual"),
+            BanReason::InvalidBlock => write!(f, "invalid block"),
+            BanReason::InvalidOperation => write!(f, "invalid operation"),
+            BanReason::ProtocolViolation => write!(f, "protocol violation"),
+        }
+    }
+}
@@ -2,11 +2,16 @@
 
 use crate::prehash::{PreHashSet, PreHashed};
 use crate::serialization::StringDeserializer;
+use crate::slot::{Slot, SlotDeserializer, SlotSerializer};
 use crate::wrapped::{Id, Wrapped, WrappedContent, WrappedDeserializer, WrappedSerializer};
 use crate::{
     address::{Address, AddressDeserializer},
     amount::{Amount, AmountDeserializer, AmountSerializer},
     error::ModelsError,
+    multisig::{
+        MultisigAction, MultisigProposal, MultisigProposalDeserializer, MultisigProposalSerializer,
+        MultisigSignature, MultisigSignaturesDeserializer, MultisigSignaturesSerializer,
+    },
     serialization::{StringSerializer, VecU8Deserializer, VecU8Serializer},
 };
 use massa_hash::{Hash, HashDeserializer};
@@ -27,7 +32,7 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
 use std::fmt::Formatter;
-use std::{ops::Bound::Included, ops::RangeInclusive, str::FromStr};
+use std::{ops::Bound::Excluded, ops::Bound::Included, ops::RangeInclusive, str::FromStr};
 
 /// Size in bytes of the serialized operation ID
 pub const OPERATION_ID_SIZE_BYTES: usize = massa_hash::HASH_SIZE_BYTES;
@@ -179,6 +184,8 @@ enum OperationTypeId {
     RollSell = 2,
     ExecuteSC = 3,
     CallSC = 4,
+    CancelAsyncMessage = 5,
+    MultisigExecute = 6,
 }
 
 /// the operation as sent in the network
@@ -270,6 +277,7 @@ pub struct OperationDeserializer {
 impl OperationDeserializer {
     /// Creates a `OperationDeserializer`
     pub fn new(
+        thread_count: u8,
         max_datastore_value_length: u64,
         max_function_name_length: u16,
         max_parameters_size: u32,
@@ -281,6 +289,7 @@ impl OperationDeserializer {
                 Included(Amount::MAX),
             ),
             op_type_deserializer: OperationTypeDeserializer::new(
+                thread_count,
                 max_datastore_value_length,
                 max_function_name_length,
                 max_parameters_size,
@@ -309,7 +318,7 @@ impl Deserializer<Operation> for OperationDeserializer {
     /// };
     /// let mut buffer = Vec::new();
     /// OperationSerializer::new().serialize(&operation, &mut buffer).unwrap();
-    /// let (rest, deserialized_operation) = OperationDeserializer::new(10000, 10000, 10000).deserialize::<DeserializeError>(&buffer).unwrap();
+    /// let (rest, deserialized_operation) = OperationDeserializer::new(32, 10000, 10000, 10000).deserialize::<DeserializeError>(&buffer).unwrap();
     /// assert_eq!(rest.len(), 0);
     /// assert_eq!(deserialized_operation.fee, operation.fee);
     /// assert_eq!(deserialized_operation.expire_period, operation.expire_period);
@@ -400,6 +409,21 @@ pub enum OperationType {
         /// The price per unit of gas that the caller is willing to pay for the execution.
         gas_price: Amount,
     },
+    /// Cancels a pending asynchronous message emitted by the sender, reimbursing its coins
+    CancelAsyncMessage {
+        /// Slot at which the message was emitted
+        emission_slot: Slot,
+        /// Index of the message among the ones emitted at `emission_slot`
+        emission_index: u64,
+    },
+    /// Executes an action on behalf of a multisig address.
+    /// The sender only pays the fee and must be in the same thread as the multisig address.
+    MultisigExecute {
+        /// Proposed action and the multisig account it applies to
+        proposal: MultisigProposal,
+        /// Signatures of the proposal by at least `threshold` signers
+        signatures: Vec<MultisigSignature>,
+    },
 }
 
 impl std::fmt::Display for OperationType {
@@ -451,6 +475,29 @@ impl std::fmt::Display for OperationType {
                 writeln!(f, "\t- sequential coins:{}", sequential_coins)?;
                 writeln!(f, "\t- parallel coins:{}", parallel_coins)?;
             }
+            OperationType::CancelAsyncMessage {
+                emission_slot,
+                emission_index,
+            } => {
+                writeln!(f, "CancelAsyncMessage:")?;
+                writeln!(f, "\t- emission slot:{}", emission_slot)?;
+                writeln!(f, "\t- emission index:{}", emission_index)?;
+            }
+            OperationType::MultisigExecute {
+                proposal,
+                signatures,
+            } => {
+                writeln!(f, "MultisigExecute:")?;
+                writeln!(f, "\t- multisig address:{}", proposal.descriptor.address())?;
+                writeln!(f, "\t- action:{}", proposal.action)?;
+                writeln!(f, "\t- nonce:{}", proposal.nonce)?;
+                writeln!(
+                    f,
+                    "\t- signatures:{}/{}",
+                    signatures.len(),
+                    proposal.descriptor.threshold
+                )?;
+            }
         }
         Ok(())
     }
@@ -462,8 +509,11 @@ pub struct OperationTypeSerializer {
     u64_serializer: U64VarIntSerializer,
     vec_u8_serializer: VecU8Serializer,
     amount_serializer: AmountSerializer,
+    slot_serializer: SlotSerializer,
     function_name_serializer: StringSerializer<U16VarIntSerializer, u16>,
     parameter_serializer: StringSerializer<U32VarIntSerializer, u32>,
+    multisig_proposal_serializer: MultisigProposalSerializer,
+    multisig_signatures_serializer: MultisigSignaturesSerializer,
 }
 
 impl OperationTypeSerializer {
@@ -474,8 +524,11 @@ impl OperationTypeSerializer {
             u64_serializer: U64VarIntSerializer::new(),
             vec_u8_serializer: VecU8Serializer::new(),
             amount_serializer: AmountSerializer::new(),
+            slot_serializer: SlotSerializer::new(),
             function_name_serializer: StringSerializer::new(U16VarIntSerializer::new()),
             parameter_serializer: StringSerializer::new(U32VarIntSerializer::new()),
+            multisig_proposal_serializer: MultisigProposalSerializer::new(),
+            multisig_signatures_serializer: MultisigSignaturesSerializer::new(),
         }
     }
 }
@@ -558,6 +611,26 @@ impl Serializer<OperationType> for OperationTypeSerializer {
                     .serialize(target_func, buffer)?;
                 self.parameter_serializer.serialize(param, buffer)?;
             }
+            OperationType::CancelAsyncMessage {
+                emission_slot,
+                emission_index,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(OperationTypeId::CancelAsyncMessage), buffer)?;
+                self.slot_serializer.serialize(emission_slot, buffer)?;
+                self.u64_serializer.serialize(emission_index, buffer)?;
+            }
+            OperationType::MultisigExecute {
+                proposal,
+                signatures,
+            } => {
+                self.u32_serializer
+                    .serialize(&u32::from(OperationTypeId::MultisigExecute), buffer)?;
+                self.multisig_proposal_serializer
+                    .serialize(proposal, buffer)?;
+                self.multisig_signatures_serializer
+                    .serialize(signatures, buffer)?;
+            }
         }
         Ok(())
     }
@@ -573,11 +646,16 @@ pub struct OperationTypeDeserializer {
     amount_deserializer: AmountDeserializer,
     function_name_deserializer: StringDeserializer<U16VarIntDeserializer, u16>,
     parameter_deserializer: StringDeserializer<U32VarIntDeserializer, u32>,
+    slot_deserializer: SlotDeserializer,
+    emission_index_deserializer: U64VarIntDeserializer,
+    multisig_proposal_deserializer: MultisigProposalDeserializer,
+    multisig_signatures_deserializer: MultisigSignaturesDeserializer,
 }
 
 impl OperationTypeDeserializer {
     /// Creates a new `OperationTypeDeserializer`
     pub fn new(
+        thread_count: u8,
         max_datastore_value_length: u64,
         max_function_name_length: u16,
         max_parameters_size: u32,
@@ -603,6 +681,16 @@ impl OperationTypeDeserializer {
                 Included(0),
                 Included(max_parameters_size),
             )),
+            slot_deserializer: SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            ),
+            emission_index_deserializer: U64VarIntDeserializer::new(
+                Included(0),
+                Included(u64::MAX),
+            ),
+            multisig_proposal_deserializer: MultisigProposalDeserializer::new(),
+            multisig_signatures_deserializer: MultisigSignaturesDeserializer::new(),
         }
     }
 }
@@ -624,7 +712,7 @@ impl Deserializer<OperationType> for OperationTypeDeserializer {
     /// };
     /// let mut buffer = Vec::new();
     /// OperationTypeSerializer::new().serialize(&op, &mut buffer).unwrap();
-    /// let (rest, op_deserialized) = OperationTypeDeserializer::new(10000, 10000, 10000).deserialize::<DeserializeError>(&buffer).unwrap();
+    /// let (rest, op_deserialized) = OperationTypeDeserializer::new(32, 10000, 10000, 10000).deserialize::<DeserializeError>(&buffer).unwrap();
     /// assert_eq!(rest.len(), 0);
     /// match op_deserialized {
     ///    OperationType::ExecuteSC {
@@ -752,6 +840,40 @@ impl Deserializer<OperationType> for OperationTypeDeserializer {
                     },
                 )
                 .parse(input),
+                OperationTypeId::CancelAsyncMessage => context(
+                    "Failed CancelAsyncMessage deserialization",
+                    tuple((
+                        context("Failed emission_slot deserialization", |input| {
+                            self.slot_deserializer.deserialize(input)
+                        }),
+                        context("Failed emission_index deserialization", |input| {
+                            self.emission_index_deserializer.deserialize(input)
+                        }),
+                    )),
+                )
+                .map(
+                    |(emission_slot, emission_index)| OperationType::CancelAsyncMessage {
+                        emission_slot,
+                        emission_index,
+                    },
+                )
+                .parse(input),
+                OperationTypeId::MultisigExecute => context(
+                    "Failed MultisigExecute deserialization",
+                    tuple((
+                        context("Failed proposal deserialization", |input| {
+                            self.multisig_proposal_deserializer.deserialize(input)
+                        }),
+                        context("Failed signatures deserialization", |input| {
+                            self.multisig_signatures_deserializer.deserialize(input)
+                        }),
+                    )),
+                )
+                .map(|(proposal, signatures)| OperationType::MultisigExecute {
+                    proposal,
+                    signatures,
+                })
+                .parse(input),
             }
         })
         .parse(buffer)
@@ -777,6 +899,8 @@ impl WrappedOperation {
             OperationType::RollBuy { .. } => 0,
             OperationType::RollSell { .. } => 0,
             OperationType::Transaction { .. } => 0,
+            OperationType::CancelAsyncMessage { .. } => 0,
+            OperationType::MultisigExecute { .. } => 0,
         }
     }
 
@@ -788,6 +912,8 @@ impl WrappedOperation {
             OperationType::RollBuy { .. } => Amount::default(),
             OperationType::RollSell { .. } => Amount::default(),
             OperationType::Transaction { .. } => Amount::default(),
+            OperationType::CancelAsyncMessage { .. } => Amount::default(),
+            OperationType::MultisigExecute { .. } => Amount::default(),
         }
     }
 
@@ -819,6 +945,16 @@ impl WrappedOperation {
             OperationType::CallSC { target_addr, .. } => {
                 res.insert(*target_addr);
             }
+            OperationType::CancelAsyncMessage { .. } => {}
+            OperationType::MultisigExecute { proposal, .. } => {
+                res.insert(proposal.descriptor.address());
+                if let MultisigAction::Transaction {
+                    recipient_address, ..
+                } = &proposal.action
+                {
+                    res.insert(*recipient_address);
+                }
+            }
         }
         res
     }
@@ -834,6 +970,9 @@ impl WrappedOperation {
             OperationType::CallSC {
                 sequential_coins, ..
             } => *sequential_coins,
+            OperationType::CancelAsyncMessage { .. } => Amount::zero(),
+            // the coins of the action are spent by the multisig address, not by the sender
+            OperationType::MultisigExecute { .. } => Amount::zero(),
         };
 
         // add all fees and return
@@ -843,7 +982,7 @@ impl WrappedOperation {
     /// get the addresses that are involved in this operation from a rolls point of view
     pub fn get_roll_involved_addresses(&self) -> Result<PreHashSet<Address>, ModelsError> {
         let mut res = PreHashSet::<Address>::default();
-        match self.content.op {
+        match &self.content.op {
             OperationType::Transaction { .. } => {}
             OperationType::RollBuy { .. } => {
                 res.insert(Address::from_public_key(&self.creator_public_key));
@@ -853,9 +992,35 @@ impl WrappedOperation {
             }
             OperationType::ExecuteSC { .. } => {}
             OperationType::CallSC { .. } => {}
+            OperationType::CancelAsyncMessage { .. } => {}
+            OperationType::MultisigExecute { proposal, .. } => match proposal.action {
+                MultisigAction::RollBuy { .. } | MultisigAction::RollSell { .. } => {
+                    res.insert(proposal.descriptor.address());
+                }
+                MultisigAction::Transaction { .. } => {}
+            },
         }
         Ok(res)
     }
+
+    /// Verifies the multisig signatures carried by a `MultisigExecute` operation.
+    /// Does nothing for other operation types.
+    pub fn verify_multisig_signatures(&self) -> Result<(), ModelsError> {
+        if let OperationType::MultisigExecute {
+            proposal,
+            signatures,
+        } = &self.content.op
+        {
+            if proposal.expire_period != self.content.expire_period {
+                return Err(ModelsError::MultisigError(format!(
+                    "proposal expire period {} does not match operation expire period {}",
+                    proposal.expire_period, self.content.expire_period
+                )));
+            }
+            proposal.verify(signatures)?;
+        }
+        Ok(())
+    }
 }
 
 /// Set of operation id's prefix
@@ -1184,6 +1349,7 @@ pub struct OperationsDeserializer {
 impl OperationsDeserializer {
     /// Creates a new `OperationsDeserializer`
     pub fn new(
+        thread_count: u8,
         max_operations_per_message: u32,
         max_datastore_value_length: u64,
         max_function_name_length: u16,
@@ -1195,6 +1361,7 @@ impl OperationsDeserializer {
                 Included(max_operations_per_message),
             ),
             signed_op_deserializer: WrappedDeserializer::new(OperationDeserializer::new(
+                thread_count,
                 max_datastore_value_length,
                 max_function_name_length,
                 max_parameters_size,
@@ -1225,7 +1392,7 @@ impl Deserializer<Vec<WrappedOperation>> for OperationsDeserializer {
     /// let operations = vec![op_wrapped.clone(), op_wrapped.clone()];
     /// let mut buffer = Vec::new();
     /// OperationsSerializer::new().serialize(&operations, &mut buffer).unwrap();
-    /// let (rest, deserialized_operations) = OperationsDeserializer::new(10000, 10000, 10000, 10000).deserialize::<DeserializeError>(&buffer).unwrap();
+    /// let (rest, deserialized_operations) = OperationsDeserializer::new(32, 10000, 10000, 10000, 10000).deserialize::<DeserializeError>(&buffer).unwrap();
     /// for (operation1, operation2) in deserialized_operations.iter().zip(operations.iter()) {
     ///     assert_eq!(operation1.id, operation2.id);
     ///     assert_eq!(operation1.signature, operation2.signature);
@@ -1255,7 +1422,7 @@ impl Deserializer<Vec<WrappedOperation>> for OperationsDeserializer {
 #[cfg(test)]
 mod tests {
     use crate::config::{
-        MAX_DATASTORE_VALUE_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_PARAMETERS_SIZE,
+        MAX_DATASTORE_VALUE_LENGTH, MAX_FUNCTION_NAME_LENGTH, MAX_PARAMETERS_SIZE, THREAD_COUNT,
     };
 
     use super::*;
@@ -1278,6 +1445,7 @@ mod tests {
             .serialize(&op, &mut ser_type)
             .unwrap();
         let (_, res_type) = OperationTypeDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1297,6 +1465,7 @@ mod tests {
             .serialize(&content, &mut ser_content)
             .unwrap();
         let (_, res_content) = OperationDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1314,6 +1483,7 @@ mod tests {
             .unwrap();
         let (_, res_op): (&[u8], WrappedOperation) =
             WrappedDeserializer::new(OperationDeserializer::new(
+                THREAD_COUNT,
                 MAX_DATASTORE_VALUE_LENGTH,
                 MAX_FUNCTION_NAME_LENGTH,
                 MAX_PARAMETERS_SIZE,
@@ -1341,6 +1511,7 @@ mod tests {
             .serialize(&op, &mut ser_type)
             .unwrap();
         let (_, res_type) = OperationTypeDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1360,6 +1531,7 @@ mod tests {
             .serialize(&content, &mut ser_content)
             .unwrap();
         let (_, res_content) = OperationDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1377,6 +1549,7 @@ mod tests {
             .unwrap();
         let (_, res_op): (&[u8], WrappedOperation) =
             WrappedDeserializer::new(OperationDeserializer::new(
+                THREAD_COUNT,
                 MAX_DATASTORE_VALUE_LENGTH,
                 MAX_FUNCTION_NAME_LENGTH,
                 MAX_PARAMETERS_SIZE,
@@ -1410,6 +1583,7 @@ mod tests {
             .serialize(&op, &mut ser_type)
             .unwrap();
         let (_, res_type) = OperationTypeDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1429,6 +1603,7 @@ mod tests {
             .serialize(&content, &mut ser_content)
             .unwrap();
         let (_, res_content) = OperationDeserializer::new(
+            THREAD_COUNT,
             MAX_DATASTORE_VALUE_LENGTH,
             MAX_FUNCTION_NAME_LENGTH,
             MAX_PARAMETERS_SIZE,
@@ -1446,6 +1621,7 @@ mod tests {
             .unwrap();
         let (_, res_op): (&[u8], WrappedOperation) =
             WrappedDeserializer::new(OperationDeserializer::new(
+                THREAD_COUNT,
                 MAX_DATASTORE_VALUE_LENGTH,
                 MAX_FUNCTION_NAME_LENGTH,
                 MAX_PARAMETERS_SIZE,
@@ -1456,4 +1632,55 @@ mod tests {
 
         assert_eq!(op.get_validity_range(10), 40..=50);
     }
+
+    #[test]
+    #[serial]
+    fn test_cancel_async_message() {
+        let sender_keypair = KeyPair::generate();
+
+        let op = OperationType::CancelAsyncMessage {
+            emission_slot: Slot::new(12, 3),
+            emission_index: 7,
+        };
+        let mut ser_type = Vec::new();
+        OperationTypeSerializer::new()
+            .serialize(&op, &mut ser_type)
+            .unwrap();
+        let (_, res_type) = OperationTypeDeserializer::new(
+            THREAD_COUNT,
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+        )
+        .deserialize::<DeserializeError>(&ser_type)
+        .unwrap();
+        assert_eq!(format!("{}", res_type), format!("{}", op));
+
+        let content = Operation {
+            fee: Amount::from_str("20").unwrap(),
+            op,
+            expire_period: 50,
+        };
+        let op =
+            Operation::new_wrapped(content, OperationSerializer::new(), &sender_keypair).unwrap();
+
+        let mut ser_op = Vec::new();
+        WrappedSerializer::new()
+            .serialize(&op, &mut ser_op)
+            .unwrap();
+        let (_, res_op): (&[u8], WrappedOperation) =
+            WrappedDeserializer::new(OperationDeserializer::new(
+                THREAD_COUNT,
+                MAX_DATASTORE_VALUE_LENGTH,
+                MAX_FUNCTION_NAME_LENGTH,
+                MAX_PARAMETERS_SIZE,
+            ))
+            .deserialize::<DeserializeError>(&ser_op)
+            .unwrap();
+        assert_eq!(format!("{}", res_op), format!("{}", op));
+        assert_eq!(op.get_gas_usage(), 0);
+        assert!(op
+            .get_ledger_involved_addresses()
+            .contains(&Address::from_public_key(&sender_keypair.get_public_key())));
+    }
 }
@@ -1,6 +1,28 @@
-use crate::{address::Address, block::BlockId, operation::OperationId, slot::Slot};
+use crate::{
+    address::{Address, AddressDeserializer},
+    amount::{Amount, AmountDeserializer, AmountSerializer},
+    api::AsyncMessageStatus,
+    block::BlockId,
+    error::ModelsError,
+    operation::OperationId,
+    serialization::{StringDeserializer, StringSerializer, VecU8Deserializer, VecU8Serializer},
+    slot::{Slot, SlotDeserializer, SlotSerializer},
+};
+use massa_serialization::{
+    DeserializeError, Deserializer, SerializeError, Serializer, U16VarIntDeserializer,
+    U16VarIntSerializer, U32VarIntDeserializer, U32VarIntSerializer, U64VarIntDeserializer,
+    U64VarIntSerializer,
+};
+use nom::{
+    error::{context, ContextError, ParseError},
+    multi::length_count,
+    sequence::tuple,
+    IResult, Parser,
+};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
 use serde::{Deserialize, Serialize};
-use std::{collections::VecDeque, fmt::Display};
+use std::ops::Bound::{Excluded, Included};
+use std::{collections::VecDeque, fmt::Display, str::FromStr};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 /// By product of a byte code execution
@@ -9,11 +31,16 @@ pub struct SCOutputEvent {
     pub context: EventExecutionContext,
     /// json data string
     pub data: String,
+    /// structured content of the event, if any (always present for events emitted by the node itself)
+    pub structured: Option<StructuredEvent>,
 }
 
 impl Display for SCOutputEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "Context: {}", self.context)?;
+        if let Some(structured) = &self.structured {
+            writeln!(f, "Structured: {}", structured)?;
+        }
         writeln!(f, "Data: {}", self.data)
     }
 }
@@ -33,6 +60,8 @@ pub struct EventExecutionContext {
     pub call_stack: VecDeque<Address>,
     /// origin operation id
     pub origin_operation_id: Option<OperationId>,
+    /// origin asynchronous message, if the event was generated while processing one
+    pub origin_async_message: Option<AsyncMessageOrigin>,
     /// if the event is final
     pub is_final: bool,
 }
@@ -56,6 +85,9 @@ impl Display for EventExecutionContext {
         if let Some(id) = self.origin_operation_id {
             writeln!(f, "Origin operation id: {}", id)?;
         }
+        if let Some(origin) = self.origin_async_message {
+            writeln!(f, "Origin async message: {}", origin)?;
+        }
         writeln!(
             f,
             "Call stack: {}",
@@ -67,3 +99,602 @@ impl Display for EventExecutionContext {
         )
     }
 }
+
+/// Identifies an asynchronous message by its emission slot and its index among the messages emitted at that slot
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AsyncMessageOrigin {
+    /// slot at which the message was emitted
+    pub emission_slot: Slot,
+    /// index of the message among the ones emitted at `emission_slot`
+    pub emission_index: u64,
+}
+
+impl Display for AsyncMessageOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "emitted at slot {} with index {}",
+            self.emission_slot, self.emission_index
+        )
+    }
+}
+
+impl FromStr for AsyncMessageOrigin {
+    type Err = ModelsError;
+    /// Parses `period,thread,index`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (slot, index) = s.rsplit_once(',').ok_or_else(|| {
+            ModelsError::DeserializeError("invalid async message origin format".to_string())
+        })?;
+        Ok(AsyncMessageOrigin {
+            emission_slot: Slot::from_str(slot)?,
+            emission_index: index
+                .parse::<u64>()
+                .map_err(|_| ModelsError::DeserializeError("invalid emission index".to_string()))?,
+        })
+    }
+}
+
+/// Key under which the outcome of an asynchronous message is stored in the JSON data of the corresponding event
+pub const ASYNC_MESSAGE_OUTCOME_EVENT_KEY: &str = "massa_async_message_outcome";
+
+/// Content of the event emitted by the node every time an asynchronous message is executed, fails or is evicted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsyncMessageOutcomeEvent {
+    /// slot at which the message was emitted
+    pub emission_slot: Slot,
+    /// index of the message among the ones emitted at `emission_slot`
+    pub emission_index: u64,
+    /// gas price of the message, which also defines its priority
+    pub gas_price: Amount,
+    /// what happened to the message
+    pub status: AsyncMessageStatus,
+    /// gas used by the execution of the message
+    pub gas_used: u64,
+    /// coins reimbursed to the sender of the message
+    pub refund: Amount,
+}
+
+/// Topic of the events emitted by the node when coins are transferred by a transaction operation
+pub const TRANSFER_EVENT_TOPIC: &str = "massa.transfer";
+/// Topic of the events emitted by the node when rolls are bought
+pub const ROLL_BUY_EVENT_TOPIC: &str = "massa.roll_buy";
+/// Topic of the events emitted by the node when rolls are sold
+pub const ROLL_SELL_EVENT_TOPIC: &str = "massa.roll_sell";
+/// Topic of the events emitted by the node when the bytecode of an address changes
+pub const BYTECODE_CHANGE_EVENT_TOPIC: &str = "massa.bytecode_change";
+/// Topic of the events emitted by the node when the bytecode of an address is made immutable
+pub const BYTECODE_LOCK_EVENT_TOPIC: &str = "massa.bytecode_lock";
+/// Topic of the events emitted by the node when an asynchronous message leaves the pool
+pub const ASYNC_MESSAGE_OUTCOME_EVENT_TOPIC: &str = "massa.async_message_outcome";
+/// Prefix of the topics of the events emitted by the node itself, that smart contracts cannot use
+pub const SYSTEM_EVENT_TOPIC_PREFIX: &str = "massa.";
+
+/// Prefix of the data a smart contract passes to `generate_event` to emit a structured event.
+/// It is followed by the bs58-check encoding of the event serialized with `StructuredEventSerializer`.
+/// The version number allows changing that encoding later without breaking deployed contracts.
+pub const STRUCTURED_EVENT_DATA_PREFIX: &str = "massa_structured_event_v1:";
+
+/// Structured event: a topic with a typed payload
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructuredEvent {
+    /// topic of the event, used for filtering
+    pub topic: String,
+    /// content of the event
+    pub payload: EventPayload,
+}
+
+impl StructuredEvent {
+    /// Creates a structured event made of typed key-value fields
+    pub fn with_fields(topic: &str, fields: Vec<(String, EventValue)>) -> Self {
+        StructuredEvent {
+            topic: topic.to_string(),
+            payload: EventPayload::Fields(fields),
+        }
+    }
+
+    /// Gets the value of a field by its key, if the payload is made of fields
+    pub fn get_field(&self, key: &str) -> Option<&EventValue> {
+        match &self.payload {
+            EventPayload::Fields(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            EventPayload::Binary { .. } => None,
+        }
+    }
+
+    /// Encodes the event as the data a smart contract passes to `generate_event` to emit it
+    /// (see `STRUCTURED_EVENT_DATA_PREFIX`)
+    pub fn to_event_data(&self) -> Result<String, SerializeError> {
+        let mut buffer = Vec::new();
+        StructuredEventSerializer::new().serialize(self, &mut buffer)?;
+        Ok(format!(
+            "{}{}",
+            STRUCTURED_EVENT_DATA_PREFIX,
+            bs58::encode(buffer).with_check().into_string()
+        ))
+    }
+
+    /// Decodes the data passed to `generate_event` by a smart contract
+    ///
+    /// # Returns
+    /// The structured event described by the data, or None if the data is a plain text event
+    pub fn from_event_data(
+        data: &str,
+        deserializer: &StructuredEventDeserializer,
+    ) -> Result<Option<Self>, ModelsError> {
+        let encoded = match data.strip_prefix(STRUCTURED_EVENT_DATA_PREFIX) {
+            Some(encoded) => encoded,
+            None => return Ok(None),
+        };
+        let buffer = bs58::decode(encoded)
+            .with_check(None)
+            .into_vec()
+            .map_err(|err| ModelsError::DeserializeError(err.to_string()))?;
+        let (rest, event) = deserializer
+            .deserialize::<DeserializeError>(&buffer)
+            .map_err(|err| ModelsError::DeserializeError(err.to_string()))?;
+        if !rest.is_empty() {
+            return Err(ModelsError::DeserializeError(
+                "trailing bytes after the structured event".to_string(),
+            ));
+        }
+        Ok(Some(event))
+    }
+}
+
+impl Display for StructuredEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ", self.topic)?;
+        match &self.payload {
+            EventPayload::Fields(fields) => write!(
+                f,
+                "{{{}}}",
+                fields
+                    .iter()
+                    .map(|(k, v)| format!("{}: {}", k, v))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            EventPayload::Binary { schema_id, data } => {
+                write!(f, "(schema {}) {}", schema_id, hex_string(data))
+            }
+        }
+    }
+}
+
+/// Payload of a structured event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventPayload {
+    /// ordered list of typed key-value fields
+    Fields(Vec<(String, EventValue)>),
+    /// binary payload whose layout is described by an external schema
+    Binary {
+        /// identifier of the schema describing `data`
+        schema_id: u64,
+        /// raw payload
+        data: Vec<u8>,
+    },
+}
+
+/// Typed value of a structured event field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EventValue {
+    /// boolean
+    Bool(bool),
+    /// unsigned integer
+    U64(u64),
+    /// amount of coins
+    Amount(Amount),
+    /// address
+    Address(Address),
+    /// slot
+    Slot(Slot),
+    /// UTF-8 string
+    String(String),
+    /// raw bytes
+    Bytes(Vec<u8>),
+}
+
+impl Display for EventValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventValue::Bool(value) => write!(f, "{}", value),
+            EventValue::U64(value) => write!(f, "{}", value),
+            EventValue::Amount(value) => write!(f, "{}", value),
+            EventValue::Address(value) => write!(f, "{}", value),
+            EventValue::Slot(value) => write!(f, "{}", value),
+            EventValue::String(value) => write!(f, "{:?}", value),
+            EventValue::Bytes(value) => write!(f, "{}", hex_string(value)),
+        }
+    }
+}
+
+fn hex_string(data: &[u8]) -> String {
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+#[derive(IntoPrimitive, TryFromPrimitive)]
+#[repr(u32)]
+enum EventPayloadId {
+    Fields = 0,
+    Binary = 1,
+}
+
+#[derive(IntoPrimitive, TryFromPrimitive)]
+#[repr(u32)]
+enum EventValueId {
+    Bool = 0,
+    U64 = 1,
+    Amount = 2,
+    Address = 3,
+    Slot = 4,
+    String = 5,
+    Bytes = 6,
+}
+
+/// Serializer for `StructuredEvent`
+pub struct StructuredEventSerializer {
+    key_serializer: StringSerializer<U16VarIntSerializer, u16>,
+    string_serializer: StringSerializer<U64VarIntSerializer, u64>,
+    u32_serializer: U32VarIntSerializer,
+    u64_serializer: U64VarIntSerializer,
+    amount_serializer: AmountSerializer,
+    slot_serializer: SlotSerializer,
+    vec_u8_serializer: VecU8Serializer,
+}
+
+impl StructuredEventSerializer {
+    /// Creates a new `StructuredEventSerializer`
+    pub fn new() -> Self {
+        Self {
+            key_serializer: StringSerializer::new(U16VarIntSerializer::new()),
+            string_serializer: StringSerializer::new(U64VarIntSerializer::new()),
+            u32_serializer: U32VarIntSerializer::new(),
+            u64_serializer: U64VarIntSerializer::new(),
+            amount_serializer: AmountSerializer::new(),
+            slot_serializer: SlotSerializer::new(),
+            vec_u8_serializer: VecU8Serializer::new(),
+        }
+    }
+
+    fn serialize_value(
+        &self,
+        value: &EventValue,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        match value {
+            EventValue::Bool(value) => {
+                self.u32_serializer
+                    .serialize(&u32::from(EventValueId::Bool), buffer)?;
+                buffer.push(u8::from(*value));
+            }
+            EventValue::U64(value) => {
+                self.u32_serializer
+                    .serialize(&u32::from(EventValueId::U64), buffer)?;
+                self.u64_serializer.serialize(value, buffer)?;
+            }
+            EventValue::Amount(value) => {
+                self.u32_serializer
+                    .serialize(&u32::from(EventValueId::Amount), buffer)?;
+                self.amount_serializer.serialize(value, buffer)?;
+            }
+            EventValue::Address(value) => {
+                self.u32_serializer
+                    .serialize(&u32::from(EventValueId::Address), buffer)?;
+                buffer.extend(value.to_bytes());
+            }
+            EventValue::Slot(value) => {
+                self.u32_serializer
+                    .serialize(&u32::from(EventValueId::Slot), buffer)?;
+                self.slot_serializer.serialize(value, buffer)?;
+            }
+            EventValue::String(value) => {
+                self.u32_serializer
+                    .serialize(&u32::from(EventValueId::String), buffer)?;
+                self.string_serializer.serialize(value, buffer)?;
+            }
+            EventValue::Bytes(value) => {
+                self.u32_serializer
+                    .serialize(&u32::from(EventValueId::Bytes), buffer)?;
+                self.vec_u8_serializer.serialize(value, buffer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for StructuredEventSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<StructuredEvent> for StructuredEventSerializer {
+    /// ## Example
+    /// ```rust
+    /// use massa_models::output_event::{StructuredEvent, StructuredEventSerializer, EventValue};
+    /// use massa_serialization::Serializer;
+    ///
+    /// let event = StructuredEvent::with_fields("topic", vec![("key".to_string(), EventValue::U64(42))]);
+    /// let mut buffer = Vec::new();
+    /// StructuredEventSerializer::new().serialize(&event, &mut buffer).unwrap();
+    /// ```
+    fn serialize(
+        &self,
+        value: &StructuredEvent,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), SerializeError> {
+        self.key_serializer.serialize(&value.topic, buffer)?;
+        match &value.payload {
+            EventPayload::Fields(fields) => {
+                self.u32_serializer
+                    .serialize(&u32::from(EventPayloadId::Fields), buffer)?;
+                let count: u32 = fields.len().try_into().map_err(|err| {
+                    SerializeError::NumberTooBig(format!("too many event fields: {}", err))
+                })?;
+                self.u32_serializer.serialize(&count, buffer)?;
+                for (key, field_value) in fields {
+                    self.key_serializer.serialize(key, buffer)?;
+                    self.serialize_value(field_value, buffer)?;
+                }
+            }
+            EventPayload::Binary { schema_id, data } => {
+                self.u32_serializer
+                    .serialize(&u32::from(EventPayloadId::Binary), buffer)?;
+                self.u64_serializer.serialize(schema_id, buffer)?;
+                self.vec_u8_serializer.serialize(data, buffer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for `StructuredEvent`
+pub struct StructuredEventDeserializer {
+    key_deserializer: StringDeserializer<U16VarIntDeserializer, u16>,
+    string_deserializer: StringDeserializer<U64VarIntDeserializer, u64>,
+    id_deserializer: U32VarIntDeserializer,
+    field_count_deserializer: U32VarIntDeserializer,
+    u64_deserializer: U64VarIntDeserializer,
+    amount_deserializer: AmountDeserializer,
+    address_deserializer: AddressDeserializer,
+    slot_deserializer: SlotDeserializer,
+    vec_u8_deserializer: VecU8Deserializer,
+}
+
+impl StructuredEventDeserializer {
+    /// Creates a new `StructuredEventDeserializer`
+    ///
+    /// # Arguments
+    /// * `thread_count`: number of threads, bounding the thread of slot values
+    /// * `max_topic_length`: maximum length of the topic and of the field keys
+    /// * `max_field_count`: maximum number of fields
+    /// * `max_value_length`: maximum length of string and binary values
+    pub fn new(
+        thread_count: u8,
+        max_topic_length: u16,
+        max_field_count: u32,
+        max_value_length: u64,
+    ) -> Self {
+        Self {
+            key_deserializer: StringDeserializer::new(U16VarIntDeserializer::new(
+                Included(0),
+                Included(max_topic_length),
+            )),
+            string_deserializer: StringDeserializer::new(U64VarIntDeserializer::new(
+                Included(0),
+                Included(max_value_length),
+            )),
+            id_deserializer: U32VarIntDeserializer::new(Included(0), Included(u32::MAX)),
+            field_count_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(max_field_count),
+            ),
+            u64_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+            amount_deserializer: AmountDeserializer::new(
+                Included(Amount::MIN),
+                Included(Amount::MAX),
+            ),
+            address_deserializer: AddressDeserializer::new(),
+            slot_deserializer: SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            ),
+            vec_u8_deserializer: VecU8Deserializer::new(Included(0), Included(max_value_length)),
+        }
+    }
+
+    fn deserialize_value<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], EventValue, E> {
+        context("Failed EventValue deserialization", |buffer| {
+            let (input, id) = self.id_deserializer.deserialize(buffer)?;
+            let id = EventValueId::try_from(id).map_err(|_| {
+                nom::Err::Error(ParseError::from_error_kind(
+                    buffer,
+                    nom::error::ErrorKind::Eof,
+                ))
+            })?;
+            match id {
+                EventValueId::Bool => {
+                    let (rest, value) = nom::number::complete::u8(input)?;
+                    match value {
+                        0 => Ok((rest, EventValue::Bool(false))),
+                        1 => Ok((rest, EventValue::Bool(true))),
+                        _ => Err(nom::Err::Error(ParseError::from_error_kind(
+                            input,
+                            nom::error::ErrorKind::Verify,
+                        ))),
+                    }
+                }
+                EventValueId::U64 => self
+                    .u64_deserializer
+                    .deserialize(input)
+                    .map(|(rest, v)| (rest, EventValue::U64(v))),
+                EventValueId::Amount => self
+                    .amount_deserializer
+                    .deserialize(input)
+                    .map(|(rest, v)| (rest, EventValue::Amount(v))),
+                EventValueId::Address => self
+                    .address_deserializer
+                    .deserialize(input)
+                    .map(|(rest, v)| (rest, EventValue::Address(v))),
+                EventValueId::Slot => self
+                    .slot_deserializer
+                    .deserialize(input)
+                    .map(|(rest, v)| (rest, EventValue::Slot(v))),
+                EventValueId::String => self
+                    .string_deserializer
+                    .deserialize(input)
+                    .map(|(rest, v)| (rest, EventValue::String(v))),
+                EventValueId::Bytes => self
+                    .vec_u8_deserializer
+                    .deserialize(input)
+                    .map(|(rest, v)| (rest, EventValue::Bytes(v))),
+            }
+        })
+        .parse(buffer)
+    }
+}
+
+impl Deserializer<StructuredEvent> for StructuredEventDeserializer {
+    /// ## Example
+    /// ```rust
+    /// use massa_models::output_event::{StructuredEvent, StructuredEventSerializer, StructuredEventDeserializer, EventValue};
+    /// use massa_serialization::{Serializer, Deserializer, DeserializeError};
+    ///
+    /// let event = StructuredEvent::with_fields("topic", vec![("key".to_string(), EventValue::U64(42))]);
+    /// let mut buffer = Vec::new();
+    /// StructuredEventSerializer::new().serialize(&event, &mut buffer).unwrap();
+    /// let (rest, event_deser) = StructuredEventDeserializer::new(32, 255, 255, 10000).deserialize::<DeserializeError>(&buffer).unwrap();
+    /// assert!(rest.is_empty());
+    /// assert_eq!(event, event_deser);
+    /// ```
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], StructuredEvent, E> {
+        context("Failed StructuredEvent deserialization", |buffer| {
+            let (input, topic) = context("Failed topic deserialization", |input| {
+                self.key_deserializer.deserialize(input)
+            })
+            .parse(buffer)?;
+            let (input, id) = self.id_deserializer.deserialize(input)?;
+            let id = EventPayloadId::try_from(id).map_err(|_| {
+                nom::Err::Error(ParseError::from_error_kind(
+                    buffer,
+                    nom::error::ErrorKind::Eof,
+                ))
+            })?;
+            let (rest, payload) = match id {
+                EventPayloadId::Fields => context(
+                    "Failed fields deserialization",
+                    length_count(
+                        |input| self.field_count_deserializer.deserialize(input),
+                        tuple((
+                            |input| self.key_deserializer.deserialize(input),
+                            |input| self.deserialize_value(input),
+                        )),
+                    ),
+                )
+                .map(EventPayload::Fields)
+                .parse(input)?,
+                EventPayloadId::Binary => context(
+                    "Failed binary payload deserialization",
+                    tuple((
+                        |input| self.u64_deserializer.deserialize(input),
+                        |input| self.vec_u8_deserializer.deserialize(input),
+                    )),
+                )
+                .map(|(schema_id, data)| EventPayload::Binary { schema_id, data })
+                .parse(input)?,
+            };
+            Ok((rest, StructuredEvent { topic, payload }))
+        })
+        .parse(buffer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::config::{
+        MAX_EVENT_FIELD_COUNT, MAX_EVENT_TOPIC_LENGTH, MAX_EVENT_VALUE_LENGTH, THREAD_COUNT,
+    };
+    use massa_hash::Hash;
+
+    #[test]
+    fn test_structured_event_serialization() {
+        let deserializer = StructuredEventDeserializer::new(
+            THREAD_COUNT,
+            MAX_EVENT_TOPIC_LENGTH,
+            MAX_EVENT_FIELD_COUNT,
+            MAX_EVENT_VALUE_LENGTH,
+        );
+        let events = vec![
+            StructuredEvent::with_fields(
+                TRANSFER_EVENT_TOPIC,
+                vec![
+                    ("flag".to_string(), EventValue::Bool(true)),
+                    ("count".to_string(), EventValue::U64(12)),
+                    (
+                        "amount".to_string(),
+                        EventValue::Amount(Amount::from_str("10.5").unwrap()),
+                    ),
+                    (
+                        "address".to_string(),
+                        EventValue::Address(Address(Hash::compute_from(b"abc"))),
+                    ),
+                    ("slot".to_string(), EventValue::Slot(Slot::new(3, 1))),
+                    ("name".to_string(), EventValue::String("hello".to_string())),
+                    ("raw".to_string(), EventValue::Bytes(vec![1, 2, 3])),
+                ],
+            ),
+            StructuredEvent {
+                topic: "custom".to_string(),
+                payload: EventPayload::Binary {
+                    schema_id: 7,
+                    data: vec![4, 5, 6],
+                },
+            },
+        ];
+        for event in events {
+            let mut buffer = Vec::new();
+            StructuredEventSerializer::new()
+                .serialize(&event, &mut buffer)
+                .unwrap();
+            let (rest, res_event) = deserializer
+                .deserialize::<DeserializeError>(&buffer)
+                .unwrap();
+            assert!(rest.is_empty());
+            assert_eq!(res_event, event);
+        }
+    }
+
+    #[test]
+    fn test_structured_event_data() {
+        let deserializer = StructuredEventDeserializer::new(
+            THREAD_COUNT,
+            MAX_EVENT_TOPIC_LENGTH,
+            MAX_EVENT_FIELD_COUNT,
+            MAX_EVENT_VALUE_LENGTH,
+        );
+        let event = StructuredEvent::with_fields(
+            "token.transfer",
+            vec![("amount".to_string(), EventValue::U64(42))],
+        );
+        let data = event.to_event_data().unwrap();
+        assert!(data.starts_with(STRUCTURED_EVENT_DATA_PREFIX));
+        assert_eq!(
+            StructuredEvent::from_event_data(&data, &deserializer).unwrap(),
+            Some(event)
+        );
+        // plain text events are not structured
+        assert_eq!(
+            StructuredEvent::from_event_data("hello", &deserializer).unwrap(),
+            None
+        );
+        // corrupted structured events are rejected
+        assert!(StructuredEvent::from_event_data(&data[..data.len() - 1], &deserializer).is_err());
+    }
+}
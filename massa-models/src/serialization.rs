@@ -8,14 +8,14 @@ use massa_serialization::{
 };
 use nom::bytes::complete::take;
 use nom::multi::length_data;
-use nom::sequence::preceded;
+use nom::sequence::{preceded, tuple};
 use nom::{branch::alt, Parser, ToUsize};
 use nom::{
     error::{context, ContextError, ErrorKind, ParseError},
     IResult,
 };
 use std::convert::TryInto;
-use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::ops::Bound;
 use Bound::Included;
 
@@ -226,6 +226,90 @@ impl Deserializer<IpAddr> for IpAddrDeserializer {
     }
 }
 
+/// Serializer for `SocketAddr`: the `IpAddr` followed by the port as big endian bytes
+#[derive(Default)]
+pub struct SocketAddrSerializer {
+    ip_serializer: IpAddrSerializer,
+}
+
+impl SocketAddrSerializer {
+    /// Creates a `SocketAddrSerializer`
+    pub const fn new() -> Self {
+        Self {
+            ip_serializer: IpAddrSerializer::new(),
+        }
+    }
+}
+
+impl Serializer<SocketAddr> for SocketAddrSerializer {
+    /// ```
+    /// use massa_models::serialization::SocketAddrSerializer;
+    /// use massa_serialization::Serializer;
+    /// use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    ///
+    /// let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 31244);
+    /// let addr_serializer = SocketAddrSerializer::new();
+    /// let mut buffer = Vec::new();
+    /// addr_serializer.serialize(&addr, &mut buffer).unwrap();
+    /// ```
+    fn serialize(&self, value: &SocketAddr, buffer: &mut Vec<u8>) -> Result<(), SerializeError> {
+        self.ip_serializer.serialize(&value.ip(), buffer)?;
+        buffer.extend(value.port().to_be_bytes());
+        Ok(())
+    }
+}
+
+/// Deserializer for `SocketAddr`
+#[derive(Default)]
+pub struct SocketAddrDeserializer {
+    ip_deserializer: IpAddrDeserializer,
+}
+
+impl SocketAddrDeserializer {
+    /// Creates a `SocketAddrDeserializer`
+    pub const fn new() -> Self {
+        Self {
+            ip_deserializer: IpAddrDeserializer::new(),
+        }
+    }
+}
+
+impl Deserializer<SocketAddr> for SocketAddrDeserializer {
+    /// ```
+    /// use massa_models::serialization::{SocketAddrSerializer, SocketAddrDeserializer};
+    /// use massa_serialization::{Serializer, Deserializer, DeserializeError};
+    /// use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+    ///
+    /// let addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 31244);
+    /// let addr_serializer = SocketAddrSerializer::new();
+    /// let addr_deserializer = SocketAddrDeserializer::new();
+    /// let mut serialized = Vec::new();
+    /// addr_serializer.serialize(&addr, &mut serialized).unwrap();
+    /// let (rest, addr_deser) = addr_deserializer.deserialize::<DeserializeError>(&serialized).unwrap();
+    /// assert!(rest.is_empty());
+    /// assert_eq!(addr, addr_deser);
+    /// ```
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], SocketAddr, E> {
+        context(
+            "Failed SocketAddr deserialization",
+            tuple((
+                |input| self.ip_deserializer.deserialize(input),
+                context("Failed port deserialization", |input: &'a [u8]| {
+                    let (rest, port) = take(2usize)(input)?;
+                    // Safe because take would fail just above if less then 2
+                    let port: [u8; 2] = port.try_into().unwrap();
+                    Ok((rest, u16::from_be_bytes(port)))
+                }),
+            )),
+        )
+        .map(|(ip, port)| SocketAddr::new(ip, port))
+        .parse(buffer)
+    }
+}
+
 /// Basic `Vec<u8>` serializer
 pub struct VecU8Serializer {
     len_serializer: U64VarIntSerializer,
@@ -1,5 +1,6 @@
 //! Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::amount::Amount;
 use crate::slot::Slot;
 use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
@@ -16,6 +17,12 @@ pub struct ExecutionStats {
     pub final_block_count: usize,
     /// number of final executed operations in the time window
     pub final_executed_operations_count: usize,
+    /// gas consumed by the final executed operations in the time window
+    pub final_gas_used: u64,
+    /// max gas of the final executed operations in the time window
+    pub final_max_gas: u64,
+    /// coins refunded to the senders of the final executed operations for unused gas in the time window
+    pub final_gas_refunds: Amount,
     /// active execution cursor slot
     pub active_cursor: Slot,
 }
@@ -43,6 +50,12 @@ impl std::fmt::Display for ExecutionStats {
             "\tFinal executed operation count: {}",
             self.final_executed_operations_count
         )?;
+        writeln!(
+            f,
+            "\tFinal gas used: {} (max gas: {})",
+            self.final_gas_used, self.final_max_gas
+        )?;
+        writeln!(f, "\tFinal gas refunds: {}", self.final_gas_refunds)?;
         writeln!(f, "\tActive cursor: {}", self.active_cursor)?;
         Ok(())
     }
@@ -61,6 +74,10 @@ pub struct NetworkStats {
     pub banned_peer_count: u64,
     /// active node count
     pub active_node_count: u64,
+    /// size of the messages sent divided by the size actually written, compression included
+    pub sent_compression_ratio: f64,
+    /// size of the messages received divided by the size actually read, compression included
+    pub received_compression_ratio: f64,
 }
 
 impl std::fmt::Display for NetworkStats {
@@ -71,6 +88,11 @@ impl std::fmt::Display for NetworkStats {
         writeln!(f, "\tKnown peers: {}", self.known_peer_count)?;
         writeln!(f, "\tBanned peers: {}", self.banned_peer_count)?;
         writeln!(f, "\tActive nodes: {}", self.active_node_count)?;
+        writeln!(
+            f,
+            "\tCompression ratio: {:.2} sent, {:.2} received",
+            self.sent_compression_ratio, self.received_compression_ratio
+        )?;
         Ok(())
     }
 }
@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::config::PEER_PORTS_MIN_VERSION;
 use crate::error::ModelsError;
 use massa_serialization::{Deserializer, Serializer, U32VarIntDeserializer, U32VarIntSerializer};
 use nom::bytes::complete::take;
@@ -194,6 +195,23 @@ impl Version {
             && self.minor >= 7
             && other.minor >= 7
     }
+
+    /// true if a node announcing this version to a node running `own_version` advertises peers with their port
+    /// (older versions only know about peer IPs and assume the default protocol port).
+    /// Nodes of other instances are assumed not to support it.
+    ///
+    /// ```rust
+    /// # use massa_models::version::Version;
+    /// # use std::str::FromStr;
+    /// let own_version = Version::from_str("MAIN.1.2").unwrap();
+    /// assert!(Version::from_str("MAIN.0.8").unwrap().supports_peer_ports(&own_version));
+    /// assert!(Version::from_str("MAIN.1.0").unwrap().supports_peer_ports(&own_version));
+    /// assert!(!Version::from_str("MAIN.0.7").unwrap().supports_peer_ports(&own_version));
+    /// assert!(!Version::from_str("SAND.1.0").unwrap().supports_peer_ports(&own_version));
+    /// ```
+    pub fn supports_peer_ports(&self, own_version: &Version) -> bool {
+        self.instance == own_version.instance && (self.major, self.minor) >= PEER_PORTS_MIN_VERSION
+    }
 }
 
 impl fmt::Display for Version {
@@ -69,17 +69,25 @@
 //! Look at `massa-protocol-worker/src/node-info.rs` to look further how we
 //! remember which node know what.
 
-use crate::{BootstrapPeers, ConnectionClosureReason, Peers};
+use crate::{BootstrapPeers, ConnectionClosureReason, NodeCapabilities, PeerScoreEvent, Peers};
+use massa_hash::Hash;
+use massa_ledger_exports::LedgerProofs;
 use massa_models::{
+    address::Address,
     block::{BlockId, WrappedHeader},
     composite::PubkeySig,
     endorsement::WrappedEndorsement,
-    node::NodeId,
+    node::{BanReason, NodeId},
     operation::{OperationId, OperationPrefixIds, WrappedOperation},
+    slot::Slot,
     stats::NetworkStats,
 };
+use massa_time::MassaTime;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, net::IpAddr};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::{IpAddr, SocketAddr},
+};
 use tokio::sync::oneshot;
 
 /// network command
@@ -87,7 +95,7 @@ use tokio::sync::oneshot;
 #[allow(clippy::large_enum_variant)]
 pub enum NodeCommand {
     /// Send given peer list to node.
-    SendPeerList(Vec<IpAddr>),
+    SendPeerList(Vec<SocketAddr>),
     /// Send the header of a block to a node.
     SendBlockHeader(WrappedHeader),
     /// Ask for info on a list of blocks.
@@ -104,6 +112,22 @@ pub enum NodeCommand {
     AskForOperations(OperationPrefixIds),
     /// Endorsements
     SendEndorsements(Vec<WrappedEndorsement>),
+    /// Ask for the final balances of a list of addresses
+    AskForFinalBalances(Vec<Address>),
+    /// Reply with proofs of the final balances against the final ledger root
+    SendFinalBalances(LedgerProofs),
+    /// Ask for the final ledger root at the output of a slot
+    AskForLedgerRoot(Slot),
+    /// Reply with the final ledger root at the output of a slot, `None` if unknown
+    SendLedgerRoot(Slot, Option<Hash>),
+    /// Ask for proofs that operations were included in blocks
+    AskForOperationInclusion(Vec<OperationId>),
+    /// Reply with inclusion proofs, `None` for operations we know no block of
+    SendOperationInclusion(Vec<(OperationId, Option<OperationInclusionProof>)>),
+    /// Ask for the roll counts at the end of a final cycle
+    AskForCycleRolls(u64),
+    /// Reply with the roll counts at the end of a cycle, `None` if it is not complete or too old
+    SendCycleRolls(u64, Option<BTreeMap<Address, u64>>),
 }
 
 /// Event types that node worker can emit
@@ -115,7 +139,7 @@ pub enum NodeEventType {
     /// Node we are connected to asked for advertised peers
     AskedPeerList,
     /// Node we are connected to sent peer list
-    ReceivedPeerList(Vec<IpAddr>),
+    ReceivedPeerList(Vec<SocketAddr>),
     /// Node we are connected to sent block header
     ReceivedBlockHeader(WrappedHeader),
     /// Node we are connected asked for info on a list of blocks.
@@ -130,6 +154,22 @@ pub enum NodeEventType {
     ReceivedAskForOperations(OperationPrefixIds),
     /// Receive a set of endorsement
     ReceivedEndorsements(Vec<WrappedEndorsement>),
+    /// Node asked for the final balances of a list of addresses
+    ReceivedAskForFinalBalances(Vec<Address>),
+    /// Node sent proofs of final balances
+    ReceivedFinalBalances(LedgerProofs),
+    /// Node asked for the final ledger root at the output of a slot
+    ReceivedAskForLedgerRoot(Slot),
+    /// Node sent the final ledger root at the output of a slot
+    ReceivedLedgerRoot(Slot, Option<Hash>),
+    /// Node asked for operation inclusion proofs
+    ReceivedAskForOperationInclusion(Vec<OperationId>),
+    /// Node sent operation inclusion proofs
+    ReceivedOperationInclusion(Vec<(OperationId, Option<OperationInclusionProof>)>),
+    /// Node asked for the roll counts at the end of a cycle
+    ReceivedAskForCycleRolls(u64),
+    /// Node sent the roll counts at the end of a cycle
+    ReceivedCycleRolls(u64, Option<BTreeMap<Address, u64>>),
 }
 
 /// Events node worker can emit.
@@ -177,13 +217,34 @@ pub enum NetworkCommand {
     /// get peers for bootstrap server
     GetBootstrapPeers(oneshot::Sender<BootstrapPeers>),
     /// Ban a list of peer by their node id
-    NodeBanByIds(Vec<NodeId>),
+    NodeBanByIds {
+        /// node ids
+        ids: Vec<NodeId>,
+        /// why the peers are banned
+        reason: BanReason,
+        /// ban duration, the default one if none
+        duration: Option<MassaTime>,
+    },
     /// Ban a list of peer by their ip address
-    NodeBanByIps(Vec<IpAddr>),
+    NodeBanByIps {
+        /// ip addresses
+        ips: Vec<IpAddr>,
+        /// why the peers are banned
+        reason: BanReason,
+        /// ban duration, the default one if none
+        duration: Option<MassaTime>,
+    },
     /// Unban a list of peer by their node id
     NodeUnbanByIds(Vec<NodeId>),
     /// Unban a list of peer by their ip address
     NodeUnbanByIps(Vec<IpAddr>),
+    /// Update the reputation score of the peer behind a node
+    NodeScoreEvent {
+        /// node id
+        node: NodeId,
+        /// observed behavior
+        event: PeerScoreEvent,
+    },
     /// Send endorsements to a node
     SendEndorsements {
         /// to node id
@@ -229,6 +290,90 @@ pub enum NetworkCommand {
     Whitelist(Vec<IpAddr>),
     /// Remove from whitelist a list of `IpAddr`
     RemoveFromWhitelist(Vec<IpAddr>),
+    /// Ask a node for the final balances of a list of addresses
+    AskForFinalBalances {
+        /// to node id
+        node: NodeId,
+        /// addresses
+        addresses: Vec<Address>,
+    },
+    /// Send proofs of final balances to a node
+    SendFinalBalances {
+        /// to node id
+        node: NodeId,
+        /// proof of the sequential balance of each asked address against the final ledger root
+        proofs: LedgerProofs,
+    },
+    /// Ask a node for the final ledger root at the output of a slot
+    AskForLedgerRoot {
+        /// to node id
+        node: NodeId,
+        /// final slot
+        slot: Slot,
+    },
+    /// Send the final ledger root at the output of a slot to a node
+    SendLedgerRoot {
+        /// to node id
+        node: NodeId,
+        /// final slot
+        slot: Slot,
+        /// ledger root, `None` if the slot is not final yet or too old
+        root: Option<Hash>,
+    },
+    /// Ask a node for proofs that operations were included in blocks
+    AskForOperationInclusion {
+        /// to node id
+        node: NodeId,
+        /// operation ids
+        operation_ids: Vec<OperationId>,
+    },
+    /// Send operation inclusion proofs to a node
+    SendOperationInclusion {
+        /// to node id
+        node: NodeId,
+        /// proof for each asked operation, `None` if no block including it is known
+        proofs: Vec<(OperationId, Option<OperationInclusionProof>)>,
+    },
+    /// Ask a node for the roll counts at the end of a final cycle
+    AskForCycleRolls {
+        /// to node id
+        node: NodeId,
+        /// cycle
+        cycle: u64,
+    },
+    /// Send the roll counts at the end of a cycle to a node
+    SendCycleRolls {
+        /// to node id
+        node: NodeId,
+        /// cycle
+        cycle: u64,
+        /// roll counts, `None` if the cycle is not complete yet or too old
+        rolls: Option<BTreeMap<Address, u64>>,
+    },
+}
+
+/// Proof that an operation was included in a block:
+/// the signed header of the block and the ids of all its operations.
+/// The hash of the operation ids must match the `operation_merkle_root` of the header.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationInclusionProof {
+    /// header of the including block
+    pub header: WrappedHeader,
+    /// ids of all the operations of the block, in order
+    pub operation_ids: Vec<OperationId>,
+}
+
+impl OperationInclusionProof {
+    /// Checks that the operation ids match the header and contain `operation_id`.
+    /// The signature of the header and the selection of its creator are not checked here.
+    pub fn proves(&self, operation_id: &OperationId) -> bool {
+        let mut total_hash: Vec<u8> = vec![];
+        for op_id in &self.operation_ids {
+            total_hash.extend(op_id.to_bytes());
+        }
+        self.header.content.operation_merkle_root == Hash::compute_from(&total_hash)
+            && self.operation_ids.contains(operation_id)
+    }
 }
 
 /// A node replied with info about a block.
@@ -249,8 +394,8 @@ pub enum BlockInfoReply {
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum NetworkEvent {
-    /// new connection from node
-    NewConnection(NodeId),
+    /// new connection from node, with the capabilities it advertised
+    NewConnection(NodeId, NodeCapabilities),
     /// connection to node was closed
     ConnectionClosed(NodeId),
     /// Info about a block was received
@@ -302,6 +447,66 @@ pub enum NetworkEvent {
         /// Endorsements
         endorsements: Vec<WrappedEndorsement>,
     },
+    /// Someone asked for the final balances of a list of addresses
+    AskedForFinalBalances {
+        /// from node id
+        node: NodeId,
+        /// addresses
+        addresses: Vec<Address>,
+    },
+    /// Received previously asked proofs of final balances
+    ReceivedFinalBalances {
+        /// from node id
+        node: NodeId,
+        /// proof of the sequential balance of each asked address against the final ledger root
+        proofs: LedgerProofs,
+    },
+    /// Someone asked for the final ledger root at the output of a slot
+    AskedForLedgerRoot {
+        /// from node id
+        node: NodeId,
+        /// final slot
+        slot: Slot,
+    },
+    /// Received a previously asked final ledger root
+    ReceivedLedgerRoot {
+        /// from node id
+        node: NodeId,
+        /// final slot
+        slot: Slot,
+        /// ledger root, `None` if the node does not know it
+        root: Option<Hash>,
+    },
+    /// Someone asked for operation inclusion proofs
+    AskedForOperationInclusion {
+        /// from node id
+        node: NodeId,
+        /// operation ids
+        operation_ids: Vec<OperationId>,
+    },
+    /// Received previously asked operation inclusion proofs
+    ReceivedOperationInclusion {
+        /// from node id
+        node: NodeId,
+        /// proofs, `None` for operations the node knows no block of
+        proofs: Vec<(OperationId, Option<OperationInclusionProof>)>,
+    },
+    /// Someone asked for the roll counts at the end of a cycle
+    AskedForCycleRolls {
+        /// from node id
+        node: NodeId,
+        /// cycle
+        cycle: u64,
+    },
+    /// Received previously asked roll counts at the end of a cycle
+    ReceivedCycleRolls {
+        /// from node id
+        node: NodeId,
+        /// cycle
+        cycle: u64,
+        /// roll counts, `None` if the node does not know them
+        rolls: Option<BTreeMap<Address, u64>>,
+    },
 }
 
 /// Network management command
@@ -14,6 +14,32 @@ impl std::fmt::Display for ConnectionId {
     }
 }
 
+/// Optional features supported by a node, advertised during the handshake as a bitfield.
+/// Unknown flags are ignored, so capabilities can be added without breaking older nodes.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct NodeCapabilities(pub u64);
+
+impl NodeCapabilities {
+    /// The node accepts compressed messages
+    pub const COMPRESSION: u64 = 1 << 0;
+    /// The node keeps the blocks that other nodes pruned from their history
+    pub const ARCHIVE: u64 = 1 << 1;
+    /// The node runs a bootstrap server
+    pub const BOOTSTRAP_SERVER: u64 = 1 << 2;
+    /// The node only follows headers and cannot serve blocks, ledger data or inclusion proofs
+    pub const LIGHT_CLIENT: u64 = 1 << 3;
+
+    /// Whether all the given flags are set
+    pub fn contains(&self, flags: u64) -> bool {
+        self.0 & flags == flags
+    }
+
+    /// Returns these capabilities with the given flags set
+    pub fn with(self, flags: u64) -> Self {
+        NodeCapabilities(self.0 | flags)
+    }
+}
+
 /// Possible reasons for a connection closure
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum ConnectionClosureReason {
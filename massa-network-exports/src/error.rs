@@ -4,7 +4,7 @@ use crate::{peers::PeerType, ConnectionId};
 use displaydoc::Display;
 use massa_models::error::ModelsError;
 use massa_serialization::SerializeError;
-use std::net::IpAddr;
+use std::net::{IpAddr, SocketAddr};
 use thiserror::Error;
 
 /// Network error
@@ -96,21 +96,21 @@ pub use throw_handshake_error;
 /// Incoming and outgoing connection with other peers error list
 pub enum NetworkConnectionErrorType {
     /// Try to close connection with no connection: {0}
-    CloseConnectionWithNoConnectionToClose(IpAddr),
+    CloseConnectionWithNoConnectionToClose(SocketAddr),
     /// Peer info not found for address: {0}
-    PeerInfoNotFoundError(IpAddr),
+    PeerInfoNotFoundError(SocketAddr),
     /// Peer info not found for address: {0}
     PeerTypeNotFoundError(PeerType),
     /// Too many connection attempt: {0}
-    TooManyConnectionAttempts(IpAddr),
+    TooManyConnectionAttempts(SocketAddr),
     /// Too many connection failure: {0}
-    TooManyConnectionFailure(IpAddr),
+    TooManyConnectionFailure(SocketAddr),
     /// Max connected peers reached: {0}
-    MaxPeersConnectionReached(IpAddr),
+    MaxPeersConnectionReached(SocketAddr),
     /// Attempt too connect from you own IP
     SelfConnection,
     /// A banned peer is trying to connect: {0}
-    BannedPeerTryingToConnect(IpAddr),
+    BannedPeerTryingToConnect(SocketAddr),
     /// Unexpected error
     UnexpectedError,
 }
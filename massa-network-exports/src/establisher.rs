@@ -14,6 +14,7 @@ mod types {
 }
 #[cfg(not(feature = "testing"))]
 mod types {
+    use crate::{settings::Socks5ProxyConfig, socks5::connect_through_proxy};
     use massa_time::MassaTime;
     use std::{io, net::SocketAddr};
     use tokio::{
@@ -47,9 +48,10 @@ mod types {
         }
     }
 
-    /// Initiates a connection with given timeout in milliseconds
+    /// Initiates a connection with given timeout in milliseconds,
+    /// through a SOCKS5 proxy if one is configured
     #[derive(Debug)]
-    pub struct DefaultConnector(MassaTime);
+    pub struct DefaultConnector(MassaTime, Option<Socks5ProxyConfig>);
 
     impl DefaultConnector {
         /// Tries to connect to a address
@@ -57,7 +59,13 @@ mod types {
         /// # Argument
         /// * `addr`: `SocketAddr` we are trying to connect to.
         pub async fn connect(&mut self, addr: SocketAddr) -> io::Result<(ReadHalf, WriteHalf)> {
-            match timeout(self.0.to_duration(), TcpStream::connect(addr)).await {
+            let connection = async {
+                match &self.1 {
+                    Some(proxy) => connect_through_proxy(proxy, addr).await,
+                    None => TcpStream::connect(addr).await,
+                }
+            };
+            match timeout(self.0.to_duration(), connection).await {
                 Ok(Ok(sock)) => {
                     let (reader, writer) = sock.into_split();
                     Ok((reader, writer))
@@ -70,12 +78,20 @@ mod types {
 
     /// Establishes a connection
     #[derive(Debug)]
-    pub struct DefaultEstablisher;
+    pub struct DefaultEstablisher {
+        /// SOCKS5 proxy used for outgoing connections
+        proxy: Option<Socks5ProxyConfig>,
+    }
 
     impl DefaultEstablisher {
         /// Creates an Establisher.
         pub fn new() -> Self {
-            DefaultEstablisher {}
+            DefaultEstablisher { proxy: None }
+        }
+
+        /// Creates an Establisher dialing outgoing connections through `proxy` if it is set.
+        pub fn with_proxy(proxy: Option<Socks5ProxyConfig>) -> Self {
+            DefaultEstablisher { proxy }
         }
 
         /// Gets the associated listener
@@ -94,7 +110,7 @@ mod types {
             &mut self,
             timeout_duration: MassaTime,
         ) -> io::Result<DefaultConnector> {
-            Ok(DefaultConnector(timeout_duration))
+            Ok(DefaultConnector(timeout_duration, self.proxy.clone()))
         }
     }
 
@@ -6,18 +6,20 @@
 
 pub use commands::{
     AskForBlocksInfo, BlockInfoReply, NetworkCommand, NetworkEvent, NetworkManagementCommand,
-    NodeCommand, NodeEvent, NodeEventType,
+    NodeCommand, NodeEvent, NodeEventType, OperationInclusionProof,
 };
 
-pub use common::{ConnectionClosureReason, ConnectionId};
+pub use common::{ConnectionClosureReason, ConnectionId, NodeCapabilities};
 pub use error::{HandshakeErrorType, NetworkConnectionErrorType, NetworkError};
 pub use establisher::{Establisher, Listener, ReadHalf, WriteHalf};
 pub use network_controller::{NetworkCommandSender, NetworkEventReceiver, NetworkManager};
 pub use peers::{
-    BootstrapPeers, BootstrapPeersDeserializer, BootstrapPeersSerializer, ConnectionCount, Peer,
-    PeerInfo, PeerType, Peers,
+    BootstrapPeers, BootstrapPeersDeserializer, BootstrapPeersSerializer, ConnectionCount,
+    LegacyBootstrapPeers, LegacyBootstrapPeersDeserializer, LegacyBootstrapPeersSerializer, Peer,
+    PeerBan, PeerInfo, PeerScoreEvent, PeerType, Peers,
 };
 pub use settings::NetworkConfig;
+pub use socks5::connect_through_proxy;
 
 mod commands;
 mod common;
@@ -25,6 +27,7 @@ mod error;
 mod establisher;
 mod network_controller;
 mod peers;
+mod socks5;
 
 /// network settings
 pub mod settings;
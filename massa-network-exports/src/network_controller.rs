@@ -3,18 +3,24 @@
 use crate::{
     commands::{AskForBlocksInfo, NetworkManagementCommand},
     error::NetworkError,
-    BlockInfoReply, BootstrapPeers, NetworkCommand, NetworkEvent, Peers,
+    BlockInfoReply, BootstrapPeers, NetworkCommand, NetworkEvent, OperationInclusionProof,
+    PeerScoreEvent, Peers,
 };
+use massa_hash::Hash;
+use massa_ledger_exports::LedgerProofs;
 use massa_models::{
+    address::Address,
     block::{BlockId, WrappedHeader},
     composite::PubkeySig,
     endorsement::WrappedEndorsement,
-    node::NodeId,
-    operation::{OperationPrefixIds, WrappedOperation},
+    node::{BanReason, NodeId},
+    operation::{OperationId, OperationPrefixIds, WrappedOperation},
+    slot::Slot,
     stats::NetworkStats,
 };
+use massa_time::MassaTime;
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{BTreeMap, HashMap, VecDeque},
     net::IpAddr,
 };
 use tokio::{
@@ -28,19 +34,52 @@ use tracing::info;
 pub struct NetworkCommandSender(pub mpsc::Sender<NetworkCommand>);
 
 impl NetworkCommandSender {
-    /// ban node(s) by id(s)
-    pub async fn node_ban_by_ids(&self, ids: Vec<NodeId>) -> Result<(), NetworkError> {
+    /// ban node(s) by id(s), for `duration` or the default ban duration
+    pub async fn node_ban_by_ids(
+        &self,
+        ids: Vec<NodeId>,
+        reason: BanReason,
+        duration: Option<MassaTime>,
+    ) -> Result<(), NetworkError> {
         self.0
-            .send(NetworkCommand::NodeBanByIds(ids))
+            .send(NetworkCommand::NodeBanByIds {
+                ids,
+                reason,
+                duration,
+            })
             .await
             .map_err(|_| NetworkError::ChannelError("could not send BanId command".into()))?;
         Ok(())
     }
 
-    /// ban node(s) by ip(s)
-    pub async fn node_ban_by_ips(&self, ips: Vec<IpAddr>) -> Result<(), NetworkError> {
+    /// report the behavior of a node, updating the reputation score of its peer
+    pub async fn node_score_event(
+        &self,
+        node: NodeId,
+        event: PeerScoreEvent,
+    ) -> Result<(), NetworkError> {
+        self.0
+            .send(NetworkCommand::NodeScoreEvent { node, event })
+            .await
+            .map_err(|_| {
+                NetworkError::ChannelError("could not send NodeScoreEvent command".into())
+            })?;
+        Ok(())
+    }
+
+    /// ban node(s) by ip(s), for `duration` or the default ban duration
+    pub async fn node_ban_by_ips(
+        &self,
+        ips: Vec<IpAddr>,
+        reason: BanReason,
+        duration: Option<MassaTime>,
+    ) -> Result<(), NetworkError> {
         self.0
-            .send(NetworkCommand::NodeBanByIps(ips))
+            .send(NetworkCommand::NodeBanByIps {
+                ips,
+                reason,
+                duration,
+            })
             .await
             .map_err(|_| NetworkError::ChannelError("could not send BanIp command".into()))?;
         Ok(())
@@ -243,6 +282,123 @@ impl NetworkCommandSender {
         Ok(())
     }
 
+    /// Ask a node for the final balances of a list of addresses
+    pub async fn ask_for_final_balances(
+        &self,
+        node: NodeId,
+        addresses: Vec<Address>,
+    ) -> Result<(), NetworkError> {
+        self.0
+            .send(NetworkCommand::AskForFinalBalances { node, addresses })
+            .await
+            .map_err(|_| {
+                NetworkError::ChannelError("could not send AskForFinalBalances command".into())
+            })?;
+        Ok(())
+    }
+
+    /// Send proofs of final balances to a node
+    pub async fn send_final_balances(
+        &self,
+        node: NodeId,
+        proofs: LedgerProofs,
+    ) -> Result<(), NetworkError> {
+        self.0
+            .send(NetworkCommand::SendFinalBalances { node, proofs })
+            .await
+            .map_err(|_| {
+                NetworkError::ChannelError("could not send SendFinalBalances command".into())
+            })?;
+        Ok(())
+    }
+
+    /// Ask a node for the final ledger root at the output of a slot
+    pub async fn ask_for_ledger_root(&self, node: NodeId, slot: Slot) -> Result<(), NetworkError> {
+        self.0
+            .send(NetworkCommand::AskForLedgerRoot { node, slot })
+            .await
+            .map_err(|_| {
+                NetworkError::ChannelError("could not send AskForLedgerRoot command".into())
+            })?;
+        Ok(())
+    }
+
+    /// Send the final ledger root at the output of a slot to a node
+    pub async fn send_ledger_root(
+        &self,
+        node: NodeId,
+        slot: Slot,
+        root: Option<Hash>,
+    ) -> Result<(), NetworkError> {
+        self.0
+            .send(NetworkCommand::SendLedgerRoot { node, slot, root })
+            .await
+            .map_err(|_| {
+                NetworkError::ChannelError("could not send SendLedgerRoot command".into())
+            })?;
+        Ok(())
+    }
+
+    /// Ask a node for proofs that operations were included in blocks
+    pub async fn ask_for_operation_inclusion(
+        &self,
+        node: NodeId,
+        operation_ids: Vec<OperationId>,
+    ) -> Result<(), NetworkError> {
+        self.0
+            .send(NetworkCommand::AskForOperationInclusion {
+                node,
+                operation_ids,
+            })
+            .await
+            .map_err(|_| {
+                NetworkError::ChannelError("could not send AskForOperationInclusion command".into())
+            })?;
+        Ok(())
+    }
+
+    /// Send operation inclusion proofs to a node
+    pub async fn send_operation_inclusion(
+        &self,
+        node: NodeId,
+        proofs: Vec<(OperationId, Option<OperationInclusionProof>)>,
+    ) -> Result<(), NetworkError> {
+        self.0
+            .send(NetworkCommand::SendOperationInclusion { node, proofs })
+            .await
+            .map_err(|_| {
+                NetworkError::ChannelError("could not send SendOperationInclusion command".into())
+            })?;
+        Ok(())
+    }
+
+    /// Ask a node for the roll counts at the end of a final cycle
+    pub async fn ask_for_cycle_rolls(&self, node: NodeId, cycle: u64) -> Result<(), NetworkError> {
+        self.0
+            .send(NetworkCommand::AskForCycleRolls { node, cycle })
+            .await
+            .map_err(|_| {
+                NetworkError::ChannelError("could not send AskForCycleRolls command".into())
+            })?;
+        Ok(())
+    }
+
+    /// Send the roll counts at the end of a cycle to a node
+    pub async fn send_cycle_rolls(
+        &self,
+        node: NodeId,
+        cycle: u64,
+        rolls: Option<BTreeMap<Address, u64>>,
+    ) -> Result<(), NetworkError> {
+        self.0
+            .send(NetworkCommand::SendCycleRolls { node, cycle, rolls })
+            .await
+            .map_err(|_| {
+                NetworkError::ChannelError("could not send SendCycleRolls command".into())
+            })?;
+        Ok(())
+    }
+
     /// Sign a message using the node's keypair
     pub async fn node_sign_message(&self, msg: Vec<u8>) -> Result<PubkeySig, NetworkError> {
         let (response_tx, response_rx) = oneshot::channel();
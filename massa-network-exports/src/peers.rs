@@ -1,8 +1,10 @@
-use crate::settings::PeerTypeConnectionConfig;
+use crate::settings::{PeerScoreConfig, PeerTypeConnectionConfig};
 use displaydoc::Display;
 use enum_map::Enum;
-use massa_models::node::NodeId;
-use massa_models::serialization::{IpAddrDeserializer, IpAddrSerializer};
+use massa_models::node::{BanReason, NodeId};
+use massa_models::serialization::{
+    IpAddrDeserializer, IpAddrSerializer, SocketAddrDeserializer, SocketAddrSerializer,
+};
 use massa_serialization::{
     Deserializer, SerializeError, Serializer, U32VarIntDeserializer, U32VarIntSerializer,
 };
@@ -12,7 +14,10 @@ use nom::multi::length_count;
 use nom::{IResult, Parser};
 use serde::{Deserialize, Serialize};
 use std::ops::Bound::Included;
-use std::{collections::HashMap, net::IpAddr};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+};
 /// Associate a peer info with nodes
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Peer {
@@ -28,17 +33,31 @@ pub struct Peers {
     /// our node id
     pub our_node_id: NodeId,
     /// peers
-    pub peers: HashMap<IpAddr, Peer>,
+    pub peers: HashMap<SocketAddr, Peer>,
 }
 
 /// Peers that are transmitted during bootstrap
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct BootstrapPeers(pub Vec<IpAddr>);
+pub struct BootstrapPeers(pub Vec<SocketAddr>);
+
+impl BootstrapPeers {
+    /// Converts to `LegacyBootstrapPeers`,
+    /// keeping only the peers listening on the default protocol port
+    pub fn to_legacy(&self, default_port: u16) -> LegacyBootstrapPeers {
+        LegacyBootstrapPeers(
+            self.0
+                .iter()
+                .filter(|addr| addr.port() == default_port)
+                .map(|addr| addr.ip())
+                .collect(),
+        )
+    }
+}
 
 /// Serializer for `BootstrapPeers`
 pub struct BootstrapPeersSerializer {
     u32_serializer: U32VarIntSerializer,
-    ip_addr_serializer: IpAddrSerializer,
+    socket_addr_serializer: SocketAddrSerializer,
 }
 
 impl BootstrapPeersSerializer {
@@ -46,7 +65,7 @@ impl BootstrapPeersSerializer {
     pub fn new() -> Self {
         Self {
             u32_serializer: U32VarIntSerializer::new(),
-            ip_addr_serializer: IpAddrSerializer::new(),
+            socket_addr_serializer: SocketAddrSerializer::new(),
         }
     }
 }
@@ -62,10 +81,10 @@ impl Serializer<BootstrapPeers> for BootstrapPeersSerializer {
     /// use massa_network_exports::{BootstrapPeers, BootstrapPeersSerializer};
     /// use massa_serialization::Serializer;
     /// use std::str::FromStr;
-    /// use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    /// use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
     ///
-    /// let localhost_v4 = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
-    /// let localhost_v6 = IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1));
+    /// let localhost_v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 31244);
+    /// let localhost_v6 = SocketAddr::new(IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1)), 31245);
     /// let mut serialized = Vec::new();
     /// let peers = BootstrapPeers(vec![localhost_v4, localhost_v6]);
     /// let peers_serializer = BootstrapPeersSerializer::new();
@@ -84,7 +103,7 @@ impl Serializer<BootstrapPeers> for BootstrapPeersSerializer {
         })?;
         self.u32_serializer.serialize(&peers_count, buffer)?;
         for peer in value.0.iter() {
-            self.ip_addr_serializer.serialize(peer, buffer)?;
+            self.socket_addr_serializer.serialize(peer, buffer)?;
         }
         Ok(())
     }
@@ -93,7 +112,7 @@ impl Serializer<BootstrapPeers> for BootstrapPeersSerializer {
 /// Deserializer for `BootstrapPeers`
 pub struct BootstrapPeersDeserializer {
     length_deserializer: U32VarIntDeserializer,
-    ip_addr_deserializer: IpAddrDeserializer,
+    socket_addr_deserializer: SocketAddrDeserializer,
 }
 
 impl BootstrapPeersDeserializer {
@@ -105,7 +124,7 @@ impl BootstrapPeersDeserializer {
     pub fn new(max_peers: u32) -> Self {
         Self {
             length_deserializer: U32VarIntDeserializer::new(Included(0), Included(max_peers)),
-            ip_addr_deserializer: IpAddrDeserializer::new(),
+            socket_addr_deserializer: SocketAddrDeserializer::new(),
         }
     }
 }
@@ -115,10 +134,10 @@ impl Deserializer<BootstrapPeers> for BootstrapPeersDeserializer {
     /// use massa_network_exports::{BootstrapPeers, BootstrapPeersSerializer, BootstrapPeersDeserializer};
     /// use massa_serialization::{Serializer, Deserializer, DeserializeError};
     /// use std::str::FromStr;
-    /// use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    /// use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
     ///
-    /// let localhost_v4 = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
-    /// let localhost_v6 = IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1));
+    /// let localhost_v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 31244);
+    /// let localhost_v6 = SocketAddr::new(IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1)), 31245);
     /// let mut serialized = Vec::new();
     /// let peers = BootstrapPeers(vec![localhost_v4, localhost_v6]);
     /// let peers_serializer = BootstrapPeersSerializer::new();
@@ -134,13 +153,145 @@ impl Deserializer<BootstrapPeers> for BootstrapPeersDeserializer {
     ) -> IResult<&'a [u8], BootstrapPeers, E> {
         length_count(
             |input| self.length_deserializer.deserialize(input),
-            |input| self.ip_addr_deserializer.deserialize(input),
+            |input| self.socket_addr_deserializer.deserialize(input),
         )
         .map(BootstrapPeers)
         .parse(buffer)
     }
 }
 
+/// Peers that are transmitted during bootstrap to nodes that do not support peer ports:
+/// they are assumed to listen on the default protocol port
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LegacyBootstrapPeers(pub Vec<IpAddr>);
+
+impl LegacyBootstrapPeers {
+    /// Converts to `BootstrapPeers`, using the default protocol port for all peers
+    ///
+    /// ```
+    /// use massa_network_exports::{BootstrapPeers, LegacyBootstrapPeers};
+    /// use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    ///
+    /// let localhost = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    /// let peers = LegacyBootstrapPeers(vec![localhost]).with_port(31244);
+    /// assert_eq!(peers, BootstrapPeers(vec![SocketAddr::new(localhost, 31244)]));
+    /// ```
+    pub fn with_port(self, default_port: u16) -> BootstrapPeers {
+        BootstrapPeers(
+            self.0
+                .into_iter()
+                .map(|ip| SocketAddr::new(ip, default_port))
+                .collect(),
+        )
+    }
+}
+
+/// Serializer for `LegacyBootstrapPeers`
+pub struct LegacyBootstrapPeersSerializer {
+    u32_serializer: U32VarIntSerializer,
+    ip_addr_serializer: IpAddrSerializer,
+}
+
+impl LegacyBootstrapPeersSerializer {
+    /// Creates a new `LegacyBootstrapPeersSerializer`
+    pub fn new() -> Self {
+        Self {
+            u32_serializer: U32VarIntSerializer::new(),
+            ip_addr_serializer: IpAddrSerializer::new(),
+        }
+    }
+}
+
+impl Default for LegacyBootstrapPeersSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Serializer<LegacyBootstrapPeers> for LegacyBootstrapPeersSerializer {
+    /// ```
+    /// use massa_network_exports::{LegacyBootstrapPeers, LegacyBootstrapPeersSerializer};
+    /// use massa_serialization::Serializer;
+    /// use std::str::FromStr;
+    /// use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    ///
+    /// let localhost_v4 = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    /// let localhost_v6 = IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1));
+    /// let mut serialized = Vec::new();
+    /// let peers = LegacyBootstrapPeers(vec![localhost_v4, localhost_v6]);
+    /// let peers_serializer = LegacyBootstrapPeersSerializer::new();
+    /// peers_serializer.serialize(&peers, &mut serialized).unwrap();
+    /// ```
+    fn serialize(
+        &self,
+        value: &LegacyBootstrapPeers,
+        buffer: &mut Vec<u8>,
+    ) -> Result<(), massa_serialization::SerializeError> {
+        let peers_count: u32 = value.0.len().try_into().map_err(|err| {
+            SerializeError::NumberTooBig(format!(
+                "too many peers blocks in LegacyBootstrapPeers: {}",
+                err
+            ))
+        })?;
+        self.u32_serializer.serialize(&peers_count, buffer)?;
+        for peer in value.0.iter() {
+            self.ip_addr_serializer.serialize(peer, buffer)?;
+        }
+        Ok(())
+    }
+}
+
+/// Deserializer for `LegacyBootstrapPeers`
+pub struct LegacyBootstrapPeersDeserializer {
+    length_deserializer: U32VarIntDeserializer,
+    ip_addr_deserializer: IpAddrDeserializer,
+}
+
+impl LegacyBootstrapPeersDeserializer {
+    /// Creates a new `LegacyBootstrapPeersDeserializer`
+    ///
+    /// Arguments:
+    ///
+    /// * max_peers: maximum peers that can be serialized
+    pub fn new(max_peers: u32) -> Self {
+        Self {
+            length_deserializer: U32VarIntDeserializer::new(Included(0), Included(max_peers)),
+            ip_addr_deserializer: IpAddrDeserializer::new(),
+        }
+    }
+}
+
+impl Deserializer<LegacyBootstrapPeers> for LegacyBootstrapPeersDeserializer {
+    /// ```
+    /// use massa_network_exports::{LegacyBootstrapPeers, LegacyBootstrapPeersSerializer, LegacyBootstrapPeersDeserializer};
+    /// use massa_serialization::{Serializer, Deserializer, DeserializeError};
+    /// use std::str::FromStr;
+    /// use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    ///
+    /// let localhost_v4 = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
+    /// let localhost_v6 = IpAddr::V6(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1));
+    /// let mut serialized = Vec::new();
+    /// let peers = LegacyBootstrapPeers(vec![localhost_v4, localhost_v6]);
+    /// let peers_serializer = LegacyBootstrapPeersSerializer::new();
+    /// let peers_deserializer = LegacyBootstrapPeersDeserializer::new(1000);
+    /// peers_serializer.serialize(&peers, &mut serialized).unwrap();
+    /// let (rest, peers_deser) = peers_deserializer.deserialize::<DeserializeError>(&serialized).unwrap();
+    /// assert!(rest.is_empty());
+    /// assert_eq!(peers, peers_deser);
+    /// ```
+    fn deserialize<'a, E: ParseError<&'a [u8]> + ContextError<&'a [u8]>>(
+        &self,
+        buffer: &'a [u8],
+    ) -> IResult<&'a [u8], LegacyBootstrapPeers, E> {
+        length_count(
+            |input| self.length_deserializer.deserialize(input),
+            |input| self.ip_addr_deserializer.deserialize(input),
+        )
+        .map(LegacyBootstrapPeers)
+        .parse(buffer)
+    }
+}
+
 /// Peer categories.
 /// There is a defined number of slots for each category.
 /// Order matters: less prioritized peer type first
@@ -159,6 +310,36 @@ pub enum PeerType {
     Bootstrap,
 }
 
+/// Record of a peer ban
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct PeerBan {
+    /// why the peer was banned
+    pub reason: BanReason,
+    /// time in milliseconds when the peer was banned
+    pub banned_at: MassaTime,
+    /// time in milliseconds when the ban is lifted
+    pub expires_at: MassaTime,
+}
+
+/// Observed behavior of a peer, used to update its reputation score
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum PeerScoreEvent {
+    /// The peer relayed a block header we did not know yet
+    UsefulBlock,
+    /// The peer relayed operations we did not know yet
+    UsefulOperations(usize),
+    /// The peer relayed endorsements we did not know yet
+    UsefulEndorsements(usize),
+    /// The peer sent invalid data
+    InvalidData,
+    /// The peer did not answer a request in time
+    Timeout,
+    /// The peer sent data it had already sent us
+    Duplicate,
+    /// The peer exceeded its message budget
+    RateLimited,
+}
+
 mod test {
 
     #[test]
@@ -178,8 +359,11 @@ impl Default for PeerType {
 /// All information concerning a peer is here
 #[derive(Clone, Copy, Serialize, Deserialize, Debug)]
 pub struct PeerInfo {
-    /// Peer ip address.
-    pub ip: IpAddr,
+    /// Peer address: ip and port the peer listens on.
+    /// Legacy peer files only provide an `ip`, in which case the port is set to 0
+    /// and replaced by the default protocol port when the file is loaded.
+    #[serde(alias = "ip", deserialize_with = "deserialize_peer_address")]
+    pub address: SocketAddr,
     /// The category the peer is in affects how it's treated.
     pub peer_type: PeerType,
     /// Time in milliseconds when peer was last alive
@@ -190,6 +374,13 @@ pub struct PeerInfo {
     pub advertised: bool,
     /// peer was banned
     pub banned: bool,
+    /// Reason and expiry of the current ban, if any
+    #[serde(default)]
+    pub ban: Option<PeerBan>,
+    /// Whether the peer was an outgoing connection when the node stopped.
+    /// Anchor peers are tried first on startup.
+    #[serde(default)]
+    pub anchor: bool,
     /// Current number of active out connection attempts with that peer.
     /// Isn't dump into peer file.
     #[serde(default = "usize::default")]
@@ -202,15 +393,30 @@ pub struct PeerInfo {
     /// Isn't dump into peer file.
     #[serde(default = "usize::default")]
     pub active_in_connections: usize,
+    /// Reputation score of the peer at `last_score_update`
+    #[serde(default)]
+    pub score: f64,
+    /// Time in milliseconds of the last score update
+    #[serde(default)]
+    pub last_score_update: Option<MassaTime>,
 }
 
 impl PeerInfo {
-    /// Cleans up the `PeerInfo` by normalizing the IP address
+    /// Cleans up the `PeerInfo` by normalizing the address
     /// and resetting active connection counts.
-    pub fn cleanup(&mut self) {
-        // canonicalize IP
-        self.ip = self.ip.to_canonical();
-        self.banned = false;
+    ///
+    /// # Arguments
+    /// * `default_port`: port to use if the address has none
+    pub fn cleanup(&mut self, default_port: u16) {
+        // canonicalize IP and fill missing port
+        self.address.set_ip(self.address.ip().to_canonical());
+        if self.address.port() == 0 {
+            self.address.set_port(default_port);
+        }
+        // only bans with a record are persisted, their expiry is checked on cleanup
+        if self.ban.is_none() {
+            self.banned = false;
+        }
         // ensure that connections are set to zero
         self.active_out_connection_attempts = 0;
         self.active_out_connections = 0;
@@ -227,15 +433,15 @@ impl PeerInfo {
             || self.active_in_connections > 0
     }
 
-    /// New standard `PeerInfo` for `IpAddr`
+    /// New standard `PeerInfo` for `SocketAddr`
     ///
     /// # Arguments
-    /// * `ip`: the IP address of the peer
+    /// * `address`: the address the peer listens on
     /// * `advertised`: true if this peer was advertised as routable,
     /// which means that our node can attempt outgoing connections to it
-    pub fn new(ip: IpAddr, advertised: bool) -> PeerInfo {
+    pub fn new(address: SocketAddr, advertised: bool) -> PeerInfo {
         PeerInfo {
-            ip,
+            address,
             last_alive: None,
             last_failure: None,
             advertised,
@@ -244,9 +450,37 @@ impl PeerInfo {
             active_in_connections: 0,
             peer_type: Default::default(),
             banned: false,
+            ban: None,
+            anchor: false,
+            score: 0.0,
+            last_score_update: None,
+        }
+    }
+
+    /// Reputation score of the peer at `now`, decayed since its last update
+    ///
+    /// # Arguments
+    /// * `half_life`: time after which a score is halved, zero disables decay
+    /// * `now`: current time
+    pub fn get_score(&self, half_life: MassaTime, now: MassaTime) -> f64 {
+        match self.last_score_update {
+            Some(last_update) if half_life.to_millis() > 0 => {
+                let elapsed = now.saturating_sub(last_update).to_millis() as f64;
+                self.score * 0.5f64.powf(elapsed / half_life.to_millis() as f64)
+            }
+            _ => self.score,
         }
     }
 
+    /// Decays the score up to `now`, then applies `delta`.
+    /// The result is clamped to `[-max_score, max_score]` and returned.
+    pub fn update_score(&mut self, delta: f64, cfg: &PeerScoreConfig, now: MassaTime) -> f64 {
+        self.score =
+            (self.get_score(cfg.half_life, now) + delta).clamp(-cfg.max_score, cfg.max_score);
+        self.last_score_update = Some(now);
+        self.score
+    }
+
     /// peer is ready to be retried, enough time has elapsed since last failure
     pub fn is_peer_ready(&self, wakeup_interval: MassaTime, now: MassaTime) -> bool {
         if let Some(last_failure) = self.last_failure {
@@ -264,6 +498,21 @@ impl PeerInfo {
     }
 }
 
+/// Deserializes a peer address, also accepting the ip-only format of legacy peer files
+fn deserialize_peer_address<'de, D>(deserializer: D) -> Result<SocketAddr, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let address = String::deserialize(deserializer)?;
+    if let Ok(address) = address.parse::<SocketAddr>() {
+        return Ok(address);
+    }
+    address
+        .parse::<IpAddr>()
+        .map(|ip| SocketAddr::new(ip, 0))
+        .map_err(serde::de::Error::custom)
+}
+
 /// Connection count for a category
 #[derive(Default, Debug)]
 pub struct ConnectionCount {
@@ -5,7 +5,8 @@ use massa_time::MassaTime;
 use serde::Deserialize;
 use std::net::{IpAddr, SocketAddr};
 
-use crate::peers::PeerType;
+use crate::peers::{PeerScoreEvent, PeerType};
+use crate::NodeCapabilities;
 
 /// Network configuration
 #[derive(Debug, Deserialize, Clone)]
@@ -14,7 +15,7 @@ pub struct NetworkConfig {
     pub bind: SocketAddr,
     /// Our own IP if it is routable, else None.
     pub routable_ip: Option<IpAddr>,
-    /// Protocol port
+    /// Default protocol port, assumed for peers advertised without a port
     pub protocol_port: u16,
     /// Time interval spent waiting for a response from a peer.
     /// In milliseconds
@@ -30,8 +31,19 @@ pub struct NetworkConfig {
     pub keypair_file: std::path::PathBuf,
     /// Configuration for `PeerType` connections
     pub peer_types_config: EnumMap<PeerType, PeerTypeConnectionConfig>,
+    /// Configuration of the peer reputation scores
+    pub peer_score_config: PeerScoreConfig,
     /// Limit on the number of in connections per ip.
     pub max_in_connections_per_ip: usize,
+    /// Limit on the number of out connections and attempts towards standard peers
+    /// of the same network group (IPv4 /16 or IPv6 /32).
+    pub max_out_connections_per_network_group: usize,
+    /// Number of outgoing peers remembered as anchors when the node stops,
+    /// to reconnect to them first on the next start.
+    pub max_anchor_peers: usize,
+    /// Every `out_connection_rotation_interval` in milliseconds the lowest scored outgoing
+    /// standard connection is closed to make room for another candidate. Zero disables rotation.
+    pub out_connection_rotation_interval: MassaTime,
     /// Limit on the number of idle peers we remember.
     pub max_idle_peers: usize,
     /// Limit on the number of banned peers we remember.
@@ -44,7 +56,7 @@ pub struct NetworkConfig {
     pub ask_peer_list_interval: MassaTime,
     /// Max wait time for sending a Network or Node event.
     pub max_send_wait: MassaTime,
-    /// Time after which we forget a node
+    /// Default duration of a ban, after which the peer is unbanned
     pub ban_timeout: MassaTime,
     /// Timeout Duration when we send a `PeerList` in handshake
     pub peer_list_send_timeout: MassaTime,
@@ -56,6 +68,12 @@ pub struct NetworkConfig {
     pub max_bytes_read: f64,
     /// Write limitation for a connection in bytes per seconds
     pub max_bytes_write: f64,
+    /// Offer message compression to peers during the handshake
+    pub enable_message_compression: bool,
+    /// Messages smaller than this size in bytes are never compressed
+    pub message_compression_min_size: usize,
+    /// Capabilities advertised to peers during the handshake, besides compression
+    pub capabilities: NodeCapabilities,
     /// Max number ids in ask blocks message
     pub max_ask_blocks: u32,
     /// Max operations per block
@@ -86,6 +104,37 @@ pub struct NetworkConfig {
     pub node_event_channel_size: usize,
 }
 
+impl NetworkConfig {
+    /// Our own advertised address if our IP is routable:
+    /// the canonical routable IP with the port we listen on.
+    pub fn routable_address(&self) -> Option<SocketAddr> {
+        self.routable_ip
+            .map(|ip| SocketAddr::new(ip.to_canonical(), self.bind.port()))
+    }
+}
+
+/// SOCKS5 proxy used to dial outgoing connections
+#[derive(Debug, Deserialize, Clone)]
+pub struct Socks5ProxyConfig {
+    /// address of the proxy
+    pub address: SocketAddr,
+    /// username, if the proxy requires authentication
+    #[serde(default)]
+    pub username: Option<String>,
+    /// password, if the proxy requires authentication
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl Socks5ProxyConfig {
+    /// Username and password to authenticate with, if a username is set
+    pub fn credentials(&self) -> Option<(&str, &str)> {
+        self.username
+            .as_deref()
+            .map(|username| (username, self.password.as_deref().unwrap_or_default()))
+    }
+}
+
 /// Connection configuration for a peer type
 /// Limit the current connections for a given peer type as a whole
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -98,6 +147,63 @@ pub struct PeerTypeConnectionConfig {
     pub max_out_attempts: usize,
 }
 
+/// Peer reputation score configuration
+///
+/// Useful data relayed by a peer raises its score, misbehavior lowers it.
+/// Scores decay towards zero over time.
+#[derive(Debug, Deserialize, Clone)]
+pub struct PeerScoreConfig {
+    /// reward for relaying a block header we did not know yet
+    pub useful_block_reward: f64,
+    /// reward per operation relayed that we did not know yet
+    pub useful_operation_reward: f64,
+    /// reward per endorsement relayed that we did not know yet
+    pub useful_endorsement_reward: f64,
+    /// penalty for sending invalid data
+    pub invalid_data_penalty: f64,
+    /// penalty for not answering a request in time
+    pub timeout_penalty: f64,
+    /// penalty for sending data we already received from that peer
+    pub duplicate_penalty: f64,
+    /// penalty per message sent over the peer's message budget
+    pub rate_limit_penalty: f64,
+    /// maximum number of rewards applied to a peer per `reward_window`,
+    /// further rewards are ignored until the window ends. Penalties are never ignored.
+    pub max_rewards_per_window: u32,
+    /// duration of the windows over which rewards are limited.
+    /// In milliseconds
+    pub reward_window: MassaTime,
+    /// scores are clamped to `[-max_score, max_score]`
+    pub max_score: f64,
+    /// a peer is banned when its score drops to `ban_threshold` or below
+    pub ban_threshold: f64,
+    /// a connected peer is replaced by a candidate peer
+    /// if the candidate scores at least `eviction_margin` more
+    pub eviction_margin: f64,
+    /// time after which a score is halved, zero disables decay.
+    /// In milliseconds
+    pub half_life: MassaTime,
+}
+
+impl PeerScoreConfig {
+    /// Score variation caused by an event
+    pub fn score_delta(&self, event: &PeerScoreEvent) -> f64 {
+        match event {
+            PeerScoreEvent::UsefulBlock => self.useful_block_reward,
+            PeerScoreEvent::UsefulOperations(count) => {
+                self.useful_operation_reward * (*count as f64)
+            }
+            PeerScoreEvent::UsefulEndorsements(count) => {
+                self.useful_endorsement_reward * (*count as f64)
+            }
+            PeerScoreEvent::InvalidData => -self.invalid_data_penalty,
+            PeerScoreEvent::Timeout => -self.timeout_penalty,
+            PeerScoreEvent::Duplicate => -self.duplicate_penalty,
+            PeerScoreEvent::RateLimited => -self.rate_limit_penalty,
+        }
+    }
+}
+
 /// setting tests
 #[cfg(feature = "testing")]
 pub mod tests {
@@ -114,7 +220,27 @@ pub mod tests {
     use massa_time::MassaTime;
     use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 
-    use super::PeerTypeConnectionConfig;
+    use super::{PeerScoreConfig, PeerTypeConnectionConfig};
+
+    impl Default for PeerScoreConfig {
+        fn default() -> Self {
+            PeerScoreConfig {
+                useful_block_reward: 1.0,
+                useful_operation_reward: 0.01,
+                useful_endorsement_reward: 0.05,
+                invalid_data_penalty: 50.0,
+                timeout_penalty: 5.0,
+                duplicate_penalty: 0.5,
+                rate_limit_penalty: 2.0,
+                max_rewards_per_window: 100,
+                reward_window: MassaTime::from_millis(1000),
+                max_score: 100.0,
+                ban_threshold: -100.0,
+                eviction_margin: 20.0,
+                half_life: MassaTime::from_millis(3_600_000),
+            }
+        }
+    }
 
     impl Default for NetworkConfig {
         fn default() -> Self {
@@ -138,11 +264,14 @@ pub mod tests {
             NetworkConfig {
                 bind: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8080),
                 routable_ip: Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
-                protocol_port: 0,
+                protocol_port: 8080,
                 connect_timeout: MassaTime::from_millis(180_000),
                 wakeup_interval: MassaTime::from_millis(10_000),
                 peers_file: std::path::PathBuf::new(),
                 max_in_connections_per_ip: 2,
+                max_out_connections_per_network_group: 10,
+                max_anchor_peers: 2,
+                out_connection_rotation_interval: MassaTime::from_millis(0),
                 max_idle_peers: 3,
                 max_banned_peers: 3,
                 peers_file_dump_interval: MassaTime::from_millis(10_000),
@@ -155,9 +284,13 @@ pub mod tests {
                 peer_list_send_timeout: MassaTime::from_millis(500),
                 max_in_connection_overflow: 2,
                 peer_types_config,
+                peer_score_config: Default::default(),
                 max_operations_per_message: MAX_OPERATIONS_PER_MESSAGE,
                 max_bytes_read: std::f64::INFINITY,
                 max_bytes_write: std::f64::INFINITY,
+                enable_message_compression: true,
+                message_compression_min_size: 512,
+                capabilities: NodeCapabilities::default(),
                 max_ask_blocks: MAX_ASK_BLOCKS_PER_MESSAGE,
                 endorsement_count: ENDORSEMENT_COUNT,
                 max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE,
@@ -206,6 +339,9 @@ pub mod tests {
                 peers_file: peers_file.to_path_buf(),
                 wakeup_interval: MassaTime::from_millis(3000),
                 max_in_connections_per_ip: 100,
+                max_out_connections_per_network_group: 100,
+                max_anchor_peers: 2,
+                out_connection_rotation_interval: MassaTime::from_millis(0),
                 max_idle_peers: 100,
                 max_banned_peers: 100,
                 peers_file_dump_interval: MassaTime::from_millis(30000),
@@ -218,9 +354,13 @@ pub mod tests {
                 peer_list_send_timeout: MassaTime::from_millis(50),
                 max_in_connection_overflow: 10,
                 peer_types_config,
+                peer_score_config: Default::default(),
                 max_operations_per_message: MAX_OPERATIONS_PER_MESSAGE,
                 max_bytes_read: std::f64::INFINITY,
                 max_bytes_write: std::f64::INFINITY,
+                enable_message_compression: true,
+                message_compression_min_size: 512,
+                capabilities: NodeCapabilities::default(),
                 max_ask_blocks: 10,
                 endorsement_count: 8,
                 max_endorsements_per_message: MAX_ENDORSEMENTS_PER_MESSAGE,
@@ -0,0 +1,226 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Minimal SOCKS5 client (RFC 1928) with username/password authentication (RFC 1929),
+//! used to dial outgoing network and bootstrap connections through a proxy.
+
+use crate::settings::Socks5ProxyConfig;
+use std::io;
+use std::net::SocketAddr;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const SOCKS_VERSION: u8 = 0x05;
+const AUTH_VERSION: u8 = 0x01;
+const METHOD_NO_AUTH: u8 = 0x00;
+const METHOD_USERNAME_PASSWORD: u8 = 0x02;
+const CMD_CONNECT: u8 = 0x01;
+const ATYP_IPV4: u8 = 0x01;
+const ATYP_DOMAIN: u8 = 0x03;
+const ATYP_IPV6: u8 = 0x04;
+const REPLY_SUCCEEDED: u8 = 0x00;
+
+fn proxy_error(kind: io::ErrorKind, msg: String) -> io::Error {
+    io::Error::new(kind, format!("SOCKS5 proxy: {}", msg))
+}
+
+/// Opens a TCP connection to `target` through the SOCKS5 proxy `proxy`.
+///
+/// # Arguments
+/// * `proxy`: proxy address and optional credentials
+/// * `target`: address the proxy connects us to
+pub async fn connect_through_proxy(
+    proxy: &Socks5ProxyConfig,
+    target: SocketAddr,
+) -> io::Result<TcpStream> {
+    let mut stream = TcpStream::connect(proxy.address).await?;
+
+    // negotiate the authentication method
+    let credentials = proxy.credentials();
+    let method = if credentials.is_some() {
+        METHOD_USERNAME_PASSWORD
+    } else {
+        METHOD_NO_AUTH
+    };
+    stream.write_all(&[SOCKS_VERSION, 1, method]).await?;
+    let mut reply = [0u8; 2];
+    stream.read_exact(&mut reply).await?;
+    if reply != [SOCKS_VERSION, method] {
+        return Err(proxy_error(
+            io::ErrorKind::PermissionDenied,
+            "authentication method refused".into(),
+        ));
+    }
+
+    // authenticate
+    if let Some((username, password)) = credentials {
+        if username.is_empty() || username.len() > 255 || password.len() > 255 {
+            return Err(proxy_error(
+                io::ErrorKind::InvalidInput,
+                "username and password must be 1 to 255 bytes long".into(),
+            ));
+        }
+        let mut request = Vec::with_capacity(3 + username.len() + password.len());
+        request.push(AUTH_VERSION);
+        request.push(username.len() as u8);
+        request.extend_from_slice(username.as_bytes());
+        request.push(password.len() as u8);
+        request.extend_from_slice(password.as_bytes());
+        stream.write_all(&request).await?;
+        stream.read_exact(&mut reply).await?;
+        if reply[1] != REPLY_SUCCEEDED {
+            return Err(proxy_error(
+                io::ErrorKind::PermissionDenied,
+                "authentication failed".into(),
+            ));
+        }
+    }
+
+    // ask the proxy to connect to the target
+    let mut request = vec![SOCKS_VERSION, CMD_CONNECT, 0];
+    match target {
+        SocketAddr::V4(addr) => {
+            request.push(ATYP_IPV4);
+            request.extend_from_slice(&addr.ip().octets());
+        }
+        SocketAddr::V6(addr) => {
+            request.push(ATYP_IPV6);
+            request.extend_from_slice(&addr.ip().octets());
+        }
+    }
+    request.extend_from_slice(&target.port().to_be_bytes());
+    stream.write_all(&request).await?;
+
+    let mut header = [0u8; 4];
+    stream.read_exact(&mut header).await?;
+    if header[0] != SOCKS_VERSION {
+        return Err(proxy_error(
+            io::ErrorKind::InvalidData,
+            format!("unexpected version {}", header[0]),
+        ));
+    }
+    if header[1] != REPLY_SUCCEEDED {
+        return Err(proxy_error(
+            io::ErrorKind::ConnectionRefused,
+            format!("could not connect to {}: reply code {}", target, header[1]),
+        ));
+    }
+    // skip the address bound by the proxy
+    let bound_address_length = match header[3] {
+        ATYP_IPV4 => 4,
+        ATYP_IPV6 => 16,
+        ATYP_DOMAIN => stream.read_u8().await? as usize,
+        atyp => {
+            return Err(proxy_error(
+                io::ErrorKind::InvalidData,
+                format!("unknown address type {}", atyp),
+            ))
+        }
+    };
+    let mut bound_address = vec![0u8; bound_address_length + 2];
+    stream.read_exact(&mut bound_address).await?;
+
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// In-process SOCKS5 stand-in: checks the handshake, reports the requested target,
+    /// then echoes everything back instead of relaying it.
+    async fn start_proxy_stand_in(
+        credentials: Option<(&'static str, &'static str)>,
+    ) -> (SocketAddr, tokio::task::JoinHandle<io::Result<SocketAddr>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await?;
+            let mut greeting = [0u8; 3];
+            stream.read_exact(&mut greeting).await?;
+            let method = match credentials {
+                Some(_) => METHOD_USERNAME_PASSWORD,
+                None => METHOD_NO_AUTH,
+            };
+            assert_eq!(greeting, [SOCKS_VERSION, 1, method]);
+            stream.write_all(&[SOCKS_VERSION, method]).await?;
+            if let Some((username, password)) = credentials {
+                let mut version_and_length = [0u8; 2];
+                stream.read_exact(&mut version_and_length).await?;
+                let mut received_username = vec![0u8; version_and_length[1] as usize];
+                stream.read_exact(&mut received_username).await?;
+                let mut received_password = vec![0u8; stream.read_u8().await? as usize];
+                stream.read_exact(&mut received_password).await?;
+                let status = if received_username == username.as_bytes()
+                    && received_password == password.as_bytes()
+                {
+                    REPLY_SUCCEEDED
+                } else {
+                    0x01
+                };
+                stream.write_all(&[AUTH_VERSION, status]).await?;
+                if status != REPLY_SUCCEEDED {
+                    return Err(io::ErrorKind::PermissionDenied.into());
+                }
+            }
+            let mut header = [0u8; 4];
+            stream.read_exact(&mut header).await?;
+            assert_eq!(header[..3], [SOCKS_VERSION, CMD_CONNECT, 0]);
+            let mut ip = [0u8; 4];
+            stream.read_exact(&mut ip).await?;
+            let port = stream.read_u16().await?;
+            stream
+                .write_all(&[SOCKS_VERSION, REPLY_SUCCEEDED, 0, ATYP_IPV4])
+                .await?;
+            // bound address 0.0.0.0:0
+            stream.write_all(&[0u8; 6]).await?;
+            let mut buffer = [0u8; 4];
+            stream.read_exact(&mut buffer).await?;
+            stream.write_all(&buffer).await?;
+            Ok(SocketAddr::from((ip, port)))
+        });
+        (address, handle)
+    }
+
+    #[tokio::test]
+    async fn test_connect_through_proxy() {
+        let (address, stand_in) = start_proxy_stand_in(None).await;
+        let proxy = Socks5ProxyConfig {
+            address,
+            username: None,
+            password: None,
+        };
+        let target: SocketAddr = "203.0.113.7:31244".parse().unwrap();
+        let mut stream = connect_through_proxy(&proxy, target).await.unwrap();
+        stream.write_all(b"ping").await.unwrap();
+        let mut echo = [0u8; 4];
+        stream.read_exact(&mut echo).await.unwrap();
+        assert_eq!(&echo, b"ping");
+        assert_eq!(stand_in.await.unwrap().unwrap(), target);
+    }
+
+    #[tokio::test]
+    async fn test_connect_through_proxy_with_credentials() {
+        let (address, stand_in) = start_proxy_stand_in(Some(("massa", "secret"))).await;
+        let proxy = Socks5ProxyConfig {
+            address,
+            username: Some("massa".into()),
+            password: Some("secret".into()),
+        };
+        let target: SocketAddr = "203.0.113.7:31245".parse().unwrap();
+        let mut stream = connect_through_proxy(&proxy, target).await.unwrap();
+        stream.write_all(b"ping").await.unwrap();
+        let mut echo = [0u8; 4];
+        stream.read_exact(&mut echo).await.unwrap();
+        assert_eq!(stand_in.await.unwrap().unwrap(), target);
+
+        let (address, _stand_in) = start_proxy_stand_in(Some(("massa", "secret"))).await;
+        let proxy = Socks5ProxyConfig {
+            address,
+            username: Some("massa".into()),
+            password: Some("wrong".into()),
+        };
+        let err = connect_through_proxy(&proxy, target).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+    }
+}
@@ -1,5 +1,6 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use crate::settings::Socks5ProxyConfig;
 use massa_time::MassaTime;
 use std::io;
 use std::net::SocketAddr;
@@ -128,6 +129,11 @@ impl MockEstablisher {
         unreachable!("place holder")
     }
 
+    /// place holder
+    pub fn with_proxy(_proxy: Option<Socks5ProxyConfig>) -> Self {
+        unreachable!("place holder")
+    }
+
     /// get connection listener
     pub async fn get_listener(&mut self, _addr: SocketAddr) -> io::Result<MockListener> {
         Ok(MockListener {
@@ -12,15 +12,117 @@ use massa_models::{
 use massa_network_exports::{NetworkError, ReadHalf, WriteHalf};
 use massa_serialization::Serializer;
 use massa_serialization::{DeserializeError, Deserializer};
+use std::borrow::Cow;
 use std::convert::TryInto;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tracing::log::warn;
 
+/// Flag prefixing a message sent as is on a connection with compression enabled
+const UNCOMPRESSED_MESSAGE: u8 = 0;
+/// Flag prefixing a compressed message, followed by its decompressed size and the lz4 block
+const COMPRESSED_MESSAGE: u8 = 1;
+
+/// Sizes of the messages sent and received on connections with compression enabled,
+/// before and after compression. Shared by all connections.
+#[derive(Debug, Default)]
+pub struct CompressionStats {
+    sent_raw_bytes: AtomicU64,
+    sent_wire_bytes: AtomicU64,
+    received_raw_bytes: AtomicU64,
+    received_wire_bytes: AtomicU64,
+}
+
+impl CompressionStats {
+    fn ratio(raw_bytes: &AtomicU64, wire_bytes: &AtomicU64) -> f64 {
+        match wire_bytes.load(Ordering::Relaxed) {
+            0 => 1.0,
+            wire => raw_bytes.load(Ordering::Relaxed) as f64 / wire as f64,
+        }
+    }
+
+    /// Size of the sent messages divided by the size actually written
+    pub fn sent_ratio(&self) -> f64 {
+        Self::ratio(&self.sent_raw_bytes, &self.sent_wire_bytes)
+    }
+
+    /// Size of the received messages divided by the size actually read
+    pub fn received_ratio(&self) -> f64 {
+        Self::ratio(&self.received_raw_bytes, &self.received_wire_bytes)
+    }
+}
+
+/// Compression settings of a connection
+#[derive(Debug, Clone)]
+pub struct MessageCompression {
+    /// messages smaller than this are sent uncompressed
+    pub min_size: usize,
+    /// statistics shared by all connections
+    pub stats: Arc<CompressionStats>,
+}
+
+/// Prefixes a serialized message with its compression flag.
+/// The message is compressed if it is at least `min_size` long and compression makes it smaller.
+fn compress_message(buf: Vec<u8>, min_size: usize) -> Vec<u8> {
+    if buf.len() >= min_size {
+        let compressed = lz4_flex::block::compress(&buf);
+        if compressed.len() + 4 < buf.len() {
+            let mut framed = Vec::with_capacity(compressed.len() + 5);
+            framed.push(COMPRESSED_MESSAGE);
+            framed.extend((buf.len() as u32).to_be_bytes());
+            framed.extend(compressed);
+            return framed;
+        }
+    }
+    let mut framed = Vec::with_capacity(buf.len() + 1);
+    framed.push(UNCOMPRESSED_MESSAGE);
+    framed.extend(buf);
+    framed
+}
+
+/// Reads the compression flag of a received message and decompresses it if needed.
+/// Decompressed messages can't be larger than `max_message_size`.
+fn decompress_message(buf: &[u8], max_message_size: u32) -> Result<Cow<[u8]>, NetworkError> {
+    match buf.split_first() {
+        Some((&UNCOMPRESSED_MESSAGE, message)) => Ok(Cow::Borrowed(message)),
+        Some((&COMPRESSED_MESSAGE, data)) if data.len() >= 4 => {
+            let (size, compressed) = data.split_at(4);
+            let size = u32::from_be_bytes(size.try_into().map_err(|_| {
+                NetworkError::GeneralProtocolError("invalid decompressed size".into())
+            })?);
+            if size > max_message_size {
+                return Err(NetworkError::GeneralProtocolError(
+                    "decompressed message too long".into(),
+                ));
+            }
+            let message =
+                lz4_flex::block::decompress(compressed, size as usize).map_err(|err| {
+                    NetworkError::GeneralProtocolError(format!(
+                        "invalid compressed message: {}",
+                        err
+                    ))
+                })?;
+            if message.len() != size as usize {
+                return Err(NetworkError::GeneralProtocolError(
+                    "decompressed message size mismatch".into(),
+                ));
+            }
+            Ok(Cow::Owned(message))
+        }
+        _ => Err(NetworkError::GeneralProtocolError(
+            "invalid message compression flag".into(),
+        )),
+    }
+}
+
 /// Used to serialize and send data.
 pub struct WriteBinder {
     write_half: Resource<WriteHalf, StandardClock>,
     message_index: u64,
     max_message_size: u32,
+    /// set once compression was negotiated with the peer
+    compression: Option<MessageCompression>,
 }
 
 impl WriteBinder {
@@ -34,9 +136,16 @@ impl WriteBinder {
             write_half: <Limiter>::new(limit).limit(write_half),
             message_index: 0,
             max_message_size,
+            compression: None,
         }
     }
 
+    /// Prefixes the following messages with a compression flag,
+    /// and compresses those that are large enough.
+    pub fn enable_compression(&mut self, compression: MessageCompression) {
+        self.compression = Some(compression);
+    }
+
     /// Sends a serialized message.
     ///
     /// # Argument
@@ -45,6 +154,15 @@ impl WriteBinder {
         //        massa_trace!("binder.send", { "msg": msg });
         let mut buf = Vec::new();
         MessageSerializer::new().serialize(msg, &mut buf)?;
+        if let Some(compression) = &self.compression {
+            let raw_size = buf.len() as u64;
+            buf = compress_message(buf, compression.min_size);
+            let stats = &compression.stats;
+            stats.sent_raw_bytes.fetch_add(raw_size, Ordering::Relaxed);
+            stats
+                .sent_wire_bytes
+                .fetch_add(buf.len() as u64, Ordering::Relaxed);
+        }
         let msg_size: u32 = buf
             .len()
             .try_into()
@@ -72,6 +190,8 @@ pub struct ReadBinder {
     msg_size: Option<u32>,
     max_message_size: u32,
     message_deserializer: MessageDeserializer,
+    /// set once compression was negotiated with the peer
+    compression_stats: Option<Arc<CompressionStats>>,
 }
 
 impl ReadBinder {
@@ -94,9 +214,15 @@ impl ReadBinder {
             msg_size: None,
             max_message_size,
             message_deserializer,
+            compression_stats: None,
         }
     }
 
+    /// Expects the following messages to be prefixed with a compression flag.
+    pub fn enable_compression(&mut self, stats: Arc<CompressionStats>) {
+        self.compression_stats = Some(stats);
+    }
+
     /// Awaits the next incoming message and deserializes it. Asynchronous cancel-safe.
     /// Returns the message, as well as the serialized object in the case of a block.
     ///
@@ -170,9 +296,22 @@ impl ReadBinder {
                 }
             }
         }
+        let message = match &self.compression_stats {
+            Some(stats) => {
+                let message = decompress_message(&self.buf, self.max_message_size)?;
+                stats
+                    .received_raw_bytes
+                    .fetch_add(message.len() as u64, Ordering::Relaxed);
+                stats
+                    .received_wire_bytes
+                    .fetch_add(self.buf.len() as u64, Ordering::Relaxed);
+                message
+            }
+            None => Cow::Borrowed(&self.buf[..]),
+        };
         let (_, res_msg) = self
             .message_deserializer
-            .deserialize::<DeserializeError>(&self.buf)
+            .deserialize::<DeserializeError>(&message)
             .map_err(|err| {
                 warn!("error deserializing message: {:?}", err);
                 NetworkError::ModelsError(ModelsError::DeserializeError(err.to_string()))
@@ -191,3 +330,33 @@ impl ReadBinder {
         Ok(Some((res_index, res_msg)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_compression_round_trip() {
+        let small = vec![7u8; 16];
+        let framed = compress_message(small.clone(), 512);
+        assert_eq!(framed[0], UNCOMPRESSED_MESSAGE);
+        assert_eq!(
+            decompress_message(&framed, 1024).unwrap().as_ref(),
+            &small[..]
+        );
+
+        let large = vec![7u8; 1000];
+        let framed = compress_message(large.clone(), 512);
+        assert_eq!(framed[0], COMPRESSED_MESSAGE);
+        assert!(framed.len() < large.len());
+        assert_eq!(
+            decompress_message(&framed, 1024).unwrap().as_ref(),
+            &large[..]
+        );
+
+        // announced size above the limit
+        assert!(decompress_message(&framed, 512).is_err());
+        // unknown flag
+        assert!(decompress_message(&[2, 0], 1024).is_err());
+    }
+}
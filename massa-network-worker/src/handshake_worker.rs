@@ -5,7 +5,7 @@
 use crate::messages::MessageDeserializer;
 
 use super::{
-    binders::{ReadBinder, WriteBinder},
+    binders::{MessageCompression, ReadBinder, WriteBinder},
     messages::Message,
 };
 use futures::future::try_join;
@@ -24,8 +24,8 @@ use massa_models::{
     node::NodeId,
 };
 use massa_network_exports::{
-    throw_handshake_error as throw, ConnectionId, HandshakeErrorType, NetworkError, ReadHalf,
-    WriteHalf,
+    throw_handshake_error as throw, ConnectionId, HandshakeErrorType, NetworkError,
+    NodeCapabilities, ReadHalf, WriteHalf,
 };
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
@@ -33,8 +33,10 @@ use rand::{rngs::StdRng, RngCore, SeedableRng};
 use tokio::{task::JoinHandle, time::timeout};
 use tracing::debug;
 
-/// Type alias for more readability
-pub type HandshakeReturnType = Result<(NodeId, ReadBinder, WriteBinder), NetworkError>;
+/// Type alias for more readability.
+/// On success, contains the node id, version and capabilities of the other node.
+pub type HandshakeReturnType =
+    Result<(NodeId, ReadBinder, WriteBinder, Version, NodeCapabilities), NetworkError>;
 
 /// Manages handshakes.
 pub struct HandshakeWorker {
@@ -49,6 +51,10 @@ pub struct HandshakeWorker {
     /// After `timeout_duration` milliseconds, the handshake attempt is dropped.
     timeout_duration: MassaTime,
     version: Version,
+    /// Our capabilities, besides compression.
+    capabilities: NodeCapabilities,
+    /// Message compression, if we support it.
+    compression: Option<MessageCompression>,
 }
 
 impl HandshakeWorker {
@@ -68,6 +74,8 @@ impl HandshakeWorker {
     /// * `timeout_duration`: after `timeout_duration` milliseconds, the handshake attempt is dropped.
     /// * `connection_id`: Node we are trying to connect for debugging
     /// * `version`: Node version used in handshake initialization (check peers compatibility)
+    /// * `capabilities`: our capabilities advertised to the other node, besides compression
+    /// * `compression`: message compression settings, if we support it
     #[allow(clippy::too_many_arguments)]
     pub fn spawn(
         socket_reader: ReadHalf,
@@ -79,6 +87,8 @@ impl HandshakeWorker {
         connection_id: ConnectionId,
         max_bytes_read: f64,
         max_bytes_write: f64,
+        capabilities: NodeCapabilities,
+        compression: Option<MessageCompression>,
     ) -> JoinHandle<(ConnectionId, HandshakeReturnType)> {
         debug!("starting handshake with connection_id={}", connection_id);
         massa_trace!("network_worker.new_connection", {
@@ -112,6 +122,8 @@ impl HandshakeWorker {
                     keypair,
                     timeout_duration,
                     version,
+                    capabilities,
+                    compression,
                 }
                 .run()
                 .await,
@@ -128,11 +140,16 @@ impl HandshakeWorker {
         let mut self_random_bytes = [0u8; 32];
         StdRng::from_entropy().fill_bytes(&mut self_random_bytes);
         let self_random_hash = Hash::compute_from(&self_random_bytes);
+        let mut self_capabilities = self.capabilities;
+        if self.compression.is_some() {
+            self_capabilities = self_capabilities.with(NodeCapabilities::COMPRESSION);
+        }
         // send handshake init future
         let msg = Message::HandshakeInitiation {
             public_key: self.self_node_id.0,
             random_bytes: self_random_bytes,
             version: self.version,
+            capabilities: self_capabilities,
         };
         let send_init_fut = self.writer.send(&msg);
 
@@ -140,7 +157,7 @@ impl HandshakeWorker {
         let recv_init_fut = self.reader.next();
 
         // join send_init_fut and recv_init_fut with a timeout, and match result
-        let (other_node_id, other_random_bytes, other_version) = match timeout(
+        let (other_node_id, other_random_bytes, other_version, other_capabilities) = match timeout(
             self.timeout_duration.to_duration(),
             try_join(send_init_fut, recv_init_fut),
         )
@@ -154,7 +171,8 @@ impl HandshakeWorker {
                     public_key: pk,
                     random_bytes: rb,
                     version,
-                } => (NodeId(pk), rb, version),
+                    capabilities,
+                } => (NodeId(pk), rb, version, capabilities),
                 Message::PeerList(list) => throw!(PeerListReceived, list),
                 _ => throw!(HandshakeWrongMessage),
             },
@@ -207,6 +225,20 @@ impl HandshakeWorker {
                 NetworkError::HandshakeError(HandshakeErrorType::HandshakeInvalidSignature)
             })?;
 
-        Ok((other_node_id, self.reader, self.writer))
+        // compress the following messages if both nodes support it
+        if let Some(compression) = self.compression {
+            if other_capabilities.contains(NodeCapabilities::COMPRESSION) {
+                self.reader.enable_compression(compression.stats.clone());
+                self.writer.enable_compression(compression);
+            }
+        }
+
+        Ok((
+            other_node_id,
+            self.reader,
+            self.writer,
+            other_version,
+            other_capabilities,
+        ))
     }
 }
@@ -1,23 +1,31 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
-use massa_hash::HashDeserializer;
+use massa_hash::{Hash, HashDeserializer};
+use massa_ledger_exports::{LedgerProofs, LedgerProofsDeserializer, LedgerProofsSerializer};
 use massa_models::{
+    address::{Address, AddressDeserializer},
     block::{BlockHeader, BlockHeaderDeserializer, BlockId, WrappedHeader},
     config::HANDSHAKE_RANDOMNESS_SIZE_BYTES,
     endorsement::{Endorsement, EndorsementDeserializer, WrappedEndorsement},
     operation::{
-        OperationIdsDeserializer, OperationIdsSerializer, OperationPrefixIds,
-        OperationPrefixIdsDeserializer, OperationPrefixIdsSerializer, OperationsDeserializer,
-        OperationsSerializer, WrappedOperation,
+        OperationId, OperationIdDeserializer, OperationIdsDeserializer, OperationIdsSerializer,
+        OperationPrefixIds, OperationPrefixIdsDeserializer, OperationPrefixIdsSerializer,
+        OperationsDeserializer, OperationsSerializer, WrappedOperation,
     },
     serialization::array_from_slice,
-    serialization::{IpAddrDeserializer, IpAddrSerializer},
+    serialization::{
+        IpAddrDeserializer, IpAddrSerializer, SocketAddrDeserializer, SocketAddrSerializer,
+    },
+    slot::{Slot, SlotDeserializer, SlotSerializer},
     version::{Version, VersionDeserializer, VersionSerializer},
     wrapped::{WrappedDeserializer, WrappedSerializer},
 };
-use massa_network_exports::{AskForBlocksInfo, BlockInfoReply};
+use massa_network_exports::{
+    AskForBlocksInfo, BlockInfoReply, NodeCapabilities, OperationInclusionProof,
+};
 use massa_serialization::{
     Deserializer, SerializeError, Serializer, U32VarIntDeserializer, U32VarIntSerializer,
+    U64VarIntDeserializer, U64VarIntSerializer,
 };
 use massa_signature::{PublicKey, PublicKeyDeserializer, Signature, SignatureDeserializer};
 use nom::{
@@ -29,7 +37,8 @@ use nom::{
 };
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 use serde::{Deserialize, Serialize};
-use std::net::IpAddr;
+use std::collections::BTreeMap;
+use std::net::{IpAddr, SocketAddr};
 use std::ops::Bound::{Excluded, Included};
 
 /// All messages that can be sent or received.
@@ -45,6 +54,9 @@ pub enum Message {
         /// let us know their public key.
         random_bytes: [u8; HANDSHAKE_RANDOMNESS_SIZE_BYTES],
         version: Version,
+        /// Optional features we support.
+        /// Absent from the messages of nodes predating capabilities.
+        capabilities: NodeCapabilities,
     },
     /// Reply to a handshake initiation message.
     HandshakeReply {
@@ -59,11 +71,15 @@ pub enum Message {
     ReplyForBlocks(Vec<(BlockId, BlockInfoReply)>),
     /// Message asking the peer for its advertisable peers list.
     AskPeerList,
-    /// Reply to a `AskPeerList` message
+    /// Reply to a `AskPeerList` message, for nodes that do not support peer ports
     /// Peers are ordered from most to less reliable.
     /// If the ip of the node that sent that message is routable,
     /// it is the first ip of the list.
+    /// All peers are assumed to listen on the default protocol port.
     PeerList(Vec<IpAddr>),
+    /// Reply to a `AskPeerList` message, for nodes that support peer ports
+    /// Same as `PeerList` but each peer comes with the port it listens on.
+    PeerListWithPorts(Vec<SocketAddr>),
     /// Batch of operation ids
     OperationsAnnouncement(OperationPrefixIds),
     /// Someone ask for operations.
@@ -72,6 +88,22 @@ pub enum Message {
     Operations(Vec<WrappedOperation>),
     /// Endorsements
     Endorsements(Vec<WrappedEndorsement>),
+    /// Someone asks for the final balances of a list of addresses.
+    AskForFinalBalances(Vec<Address>),
+    /// Proofs of final balances against the final ledger root.
+    FinalBalances(LedgerProofs),
+    /// Someone asks for the final ledger root at the output of a slot.
+    AskForLedgerRoot(Slot),
+    /// Final ledger root at the output of a slot, `None` if unknown.
+    LedgerRoot(Slot, Option<Hash>),
+    /// Someone asks for proofs that operations were included in blocks.
+    AskForOperationInclusion(Vec<OperationId>),
+    /// Operation inclusion proofs, `None` for operations without known including block.
+    OperationInclusion(Vec<(OperationId, Option<OperationInclusionProof>)>),
+    /// Someone asks for the roll counts at the end of a final cycle.
+    AskForCycleRolls(u64),
+    /// Roll counts at the end of a cycle, `None` if unknown.
+    CycleRolls(u64, Option<BTreeMap<Address, u64>>),
 }
 
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
@@ -88,6 +120,15 @@ pub(crate) enum MessageTypeId {
     AskForOperations,
     OperationsAnnouncement,
     ReplyForBlocks,
+    PeerListWithPorts,
+    AskForFinalBalances,
+    FinalBalances,
+    AskForOperationInclusion,
+    OperationInclusion,
+    AskForLedgerRoot,
+    LedgerRoot,
+    AskForCycleRolls,
+    CycleRolls,
 }
 
 #[derive(IntoPrimitive, Debug, Eq, PartialEq, TryFromPrimitive)]
@@ -103,11 +144,15 @@ pub(crate) enum BlockInfoType {
 pub struct MessageSerializer {
     version_serializer: VersionSerializer,
     u32_serializer: U32VarIntSerializer,
+    u64_serializer: U64VarIntSerializer,
     wrapped_serializer: WrappedSerializer,
     operation_prefix_ids_serializer: OperationPrefixIdsSerializer,
     operations_ids_serializer: OperationIdsSerializer,
     operations_serializer: OperationsSerializer,
     ip_addr_serializer: IpAddrSerializer,
+    socket_addr_serializer: SocketAddrSerializer,
+    ledger_proofs_serializer: LedgerProofsSerializer,
+    slot_serializer: SlotSerializer,
 }
 
 impl MessageSerializer {
@@ -116,11 +161,15 @@ impl MessageSerializer {
         MessageSerializer {
             version_serializer: VersionSerializer::new(),
             u32_serializer: U32VarIntSerializer::new(),
+            u64_serializer: U64VarIntSerializer::new(),
             wrapped_serializer: WrappedSerializer::new(),
             operation_prefix_ids_serializer: OperationPrefixIdsSerializer::new(),
             operations_ids_serializer: OperationIdsSerializer::new(),
             operations_serializer: OperationsSerializer::new(),
             ip_addr_serializer: IpAddrSerializer::new(),
+            socket_addr_serializer: SocketAddrSerializer::new(),
+            ledger_proofs_serializer: LedgerProofsSerializer::new(),
+            slot_serializer: SlotSerializer::new(),
         }
     }
 }
@@ -140,12 +189,14 @@ impl Serializer<Message> for MessageSerializer {
                 public_key,
                 random_bytes,
                 version,
+                capabilities,
             } => {
                 self.u32_serializer
                     .serialize(&(MessageTypeId::HandshakeInitiation as u32), buffer)?;
                 buffer.extend(public_key.to_bytes());
                 buffer.extend(random_bytes);
                 self.version_serializer.serialize(version, buffer)?;
+                self.u64_serializer.serialize(&capabilities.0, buffer)?;
             }
             Message::HandshakeReply { signature } => {
                 self.u32_serializer
@@ -215,6 +266,15 @@ impl Serializer<Message> for MessageSerializer {
                     self.ip_addr_serializer.serialize(peer, buffer)?;
                 }
             }
+            Message::PeerListWithPorts(peers) => {
+                self.u32_serializer
+                    .serialize(&(MessageTypeId::PeerListWithPorts as u32), buffer)?;
+                self.u32_serializer
+                    .serialize(&(peers.len() as u32), buffer)?;
+                for peer in peers {
+                    self.socket_addr_serializer.serialize(peer, buffer)?;
+                }
+            }
             Message::OperationsAnnouncement(operation_prefix_ids) => {
                 self.u32_serializer
                     .serialize(&(MessageTypeId::OperationsAnnouncement as u32), buffer)?;
@@ -241,6 +301,83 @@ impl Serializer<Message> for MessageSerializer {
                     self.wrapped_serializer.serialize(endorsement, buffer)?;
                 }
             }
+            Message::AskForFinalBalances(addresses) => {
+                self.u32_serializer
+                    .serialize(&(MessageTypeId::AskForFinalBalances as u32), buffer)?;
+                self.u32_serializer
+                    .serialize(&(addresses.len() as u32), buffer)?;
+                for address in addresses {
+                    buffer.extend(address.to_bytes());
+                }
+            }
+            Message::FinalBalances(proofs) => {
+                self.u32_serializer
+                    .serialize(&(MessageTypeId::FinalBalances as u32), buffer)?;
+                self.ledger_proofs_serializer.serialize(proofs, buffer)?;
+            }
+            Message::AskForLedgerRoot(slot) => {
+                self.u32_serializer
+                    .serialize(&(MessageTypeId::AskForLedgerRoot as u32), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+            }
+            Message::LedgerRoot(slot, root) => {
+                self.u32_serializer
+                    .serialize(&(MessageTypeId::LedgerRoot as u32), buffer)?;
+                self.slot_serializer.serialize(slot, buffer)?;
+                match root {
+                    Some(root) => {
+                        buffer.push(1);
+                        buffer.extend(root.to_bytes());
+                    }
+                    None => buffer.push(0),
+                }
+            }
+            Message::AskForOperationInclusion(operation_ids) => {
+                self.u32_serializer
+                    .serialize(&(MessageTypeId::AskForOperationInclusion as u32), buffer)?;
+                self.operations_ids_serializer
+                    .serialize(operation_ids, buffer)?;
+            }
+            Message::OperationInclusion(proofs) => {
+                self.u32_serializer
+                    .serialize(&(MessageTypeId::OperationInclusion as u32), buffer)?;
+                self.u32_serializer
+                    .serialize(&(proofs.len() as u32), buffer)?;
+                for (operation_id, proof) in proofs {
+                    buffer.extend(operation_id.to_bytes());
+                    match proof {
+                        Some(proof) => {
+                            buffer.push(1);
+                            self.wrapped_serializer.serialize(&proof.header, buffer)?;
+                            self.operations_ids_serializer
+                                .serialize(&proof.operation_ids, buffer)?;
+                        }
+                        None => buffer.push(0),
+                    }
+                }
+            }
+            Message::AskForCycleRolls(cycle) => {
+                self.u32_serializer
+                    .serialize(&(MessageTypeId::AskForCycleRolls as u32), buffer)?;
+                self.u64_serializer.serialize(cycle, buffer)?;
+            }
+            Message::CycleRolls(cycle, rolls) => {
+                self.u32_serializer
+                    .serialize(&(MessageTypeId::CycleRolls as u32), buffer)?;
+                self.u64_serializer.serialize(cycle, buffer)?;
+                match rolls {
+                    Some(rolls) => {
+                        buffer.push(1);
+                        self.u32_serializer
+                            .serialize(&(rolls.len() as u32), buffer)?;
+                        for (address, count) in rolls {
+                            buffer.extend(address.to_bytes());
+                            self.u64_serializer.serialize(count, buffer)?;
+                        }
+                    }
+                    None => buffer.push(0),
+                }
+            }
         }
         Ok(())
     }
@@ -251,6 +388,7 @@ pub struct MessageDeserializer {
     public_key_deserializer: PublicKeyDeserializer,
     signature_deserializer: SignatureDeserializer,
     version_deserializer: VersionDeserializer,
+    capabilities_deserializer: U64VarIntDeserializer,
     id_deserializer: U32VarIntDeserializer,
     ask_block_number_deserializer: U32VarIntDeserializer,
     peer_list_length_deserializer: U32VarIntDeserializer,
@@ -262,6 +400,14 @@ pub struct MessageDeserializer {
     operation_prefix_ids_deserializer: OperationPrefixIdsDeserializer,
     infos_deserializer: OperationIdsDeserializer,
     ip_addr_deserializer: IpAddrDeserializer,
+    socket_addr_deserializer: SocketAddrDeserializer,
+    address_deserializer: AddressDeserializer,
+    operation_id_deserializer: OperationIdDeserializer,
+    ledger_proofs_deserializer: LedgerProofsDeserializer,
+    slot_deserializer: SlotDeserializer,
+    cycle_deserializer: U64VarIntDeserializer,
+    roll_counts_length_deserializer: U32VarIntDeserializer,
+    roll_count_deserializer: U64VarIntDeserializer,
 }
 
 impl MessageDeserializer {
@@ -283,6 +429,7 @@ impl MessageDeserializer {
             public_key_deserializer: PublicKeyDeserializer::new(),
             signature_deserializer: SignatureDeserializer::new(),
             version_deserializer: VersionDeserializer::new(),
+            capabilities_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
             id_deserializer: U32VarIntDeserializer::new(Included(0), Included(u32::MAX)),
             ask_block_number_deserializer: U32VarIntDeserializer::new(
                 Included(0),
@@ -293,6 +440,7 @@ impl MessageDeserializer {
                 Included(max_advertise_length),
             ),
             operations_deserializer: OperationsDeserializer::new(
+                thread_count,
                 max_operations_per_block,
                 max_datastore_value_length,
                 max_function_name_length,
@@ -316,6 +464,27 @@ impl MessageDeserializer {
             ),
             infos_deserializer: OperationIdsDeserializer::new(max_operations_per_block),
             ip_addr_deserializer: IpAddrDeserializer::new(),
+            socket_addr_deserializer: SocketAddrDeserializer::new(),
+            address_deserializer: AddressDeserializer::new(),
+            operation_id_deserializer: OperationIdDeserializer::new(),
+            // proofs may be asked for any ledger key, whose datastore part fits in a byte
+            ledger_proofs_deserializer: LedgerProofsDeserializer::new(
+                thread_count,
+                max_ask_block,
+                u8::MAX,
+                max_datastore_value_length,
+            ),
+            slot_deserializer: SlotDeserializer::new(
+                (Included(0), Included(u64::MAX)),
+                (Included(0), Excluded(thread_count)),
+            ),
+            cycle_deserializer: U64VarIntDeserializer::new(Included(0), Included(u64::MAX)),
+            // the number of roll owners is only bounded by the maximum message size
+            roll_counts_length_deserializer: U32VarIntDeserializer::new(
+                Included(0),
+                Included(u32::MAX),
+            ),
+            roll_count_deserializer: U64VarIntDeserializer::new(Included(1), Included(u64::MAX)),
         }
     }
 }
@@ -347,15 +516,30 @@ impl Deserializer<Message> for MessageDeserializer {
                         context("Failed version deserialization", |input| {
                             self.version_deserializer.deserialize(input)
                         }),
+                        context(
+                            "Failed capabilities deserialization",
+                            |input: &'a [u8]| {
+                                // nodes predating capabilities end the message after the version
+                                if input.is_empty() {
+                                    return Ok((input, NodeCapabilities::default()));
+                                }
+                                self.capabilities_deserializer
+                                    .deserialize(input)
+                                    .map(|(rest, flags)| (rest, NodeCapabilities(flags)))
+                            },
+                        ),
                     ))
-                    .map(|(public_key, random_bytes, version)| {
-                        // Unwrap safety: we checked above that we took enough bytes
-                        Message::HandshakeInitiation {
-                            public_key,
-                            random_bytes: array_from_slice(random_bytes).unwrap(),
-                            version,
-                        }
-                    }),
+                    .map(
+                        |(public_key, random_bytes, version, capabilities)| {
+                            // Unwrap safety: we checked above that we took enough bytes
+                            Message::HandshakeInitiation {
+                                public_key,
+                                random_bytes: array_from_slice(random_bytes).unwrap(),
+                                version,
+                                capabilities,
+                            }
+                        },
+                    ),
                 )
                 .parse(input),
                 MessageTypeId::HandshakeReply => {
@@ -487,6 +671,19 @@ impl Deserializer<Message> for MessageDeserializer {
                 )
                 .map(Message::PeerList)
                 .parse(input),
+                MessageTypeId::PeerListWithPorts => context(
+                    "Failed PeerListWithPorts deserialization",
+                    length_count(
+                        context("Failed length deserialization", |input| {
+                            self.peer_list_length_deserializer.deserialize(input)
+                        }),
+                        context("Failed peer deserialization", |input| {
+                            self.socket_addr_deserializer.deserialize(input)
+                        }),
+                    ),
+                )
+                .map(Message::PeerListWithPorts)
+                .parse(input),
                 MessageTypeId::Operations => {
                     context("Failed Operations deserialization", |input| {
                         self.operations_deserializer.deserialize(input)
@@ -521,6 +718,142 @@ impl Deserializer<Message> for MessageDeserializer {
                 )
                 .map(Message::Endorsements)
                 .parse(input),
+                // queries are bounded like block queries
+                MessageTypeId::AskForFinalBalances => context(
+                    "Failed AskForFinalBalances deserialization",
+                    length_count(
+                        context("Failed length deserialization", |input| {
+                            self.ask_block_number_deserializer.deserialize(input)
+                        }),
+                        context("Failed address deserialization", |input| {
+                            self.address_deserializer.deserialize(input)
+                        }),
+                    ),
+                )
+                .map(Message::AskForFinalBalances)
+                .parse(input),
+                MessageTypeId::FinalBalances => {
+                    context("Failed FinalBalances deserialization", |input| {
+                        self.ledger_proofs_deserializer.deserialize(input)
+                    })
+                    .map(Message::FinalBalances)
+                    .parse(input)
+                }
+                MessageTypeId::AskForLedgerRoot => {
+                    context("Failed AskForLedgerRoot deserialization", |input| {
+                        self.slot_deserializer.deserialize(input)
+                    })
+                    .map(Message::AskForLedgerRoot)
+                    .parse(input)
+                }
+                MessageTypeId::LedgerRoot => context(
+                    "Failed LedgerRoot deserialization",
+                    tuple((
+                        context("Failed slot deserialization", |input| {
+                            self.slot_deserializer.deserialize(input)
+                        }),
+                        context("Failed root deserialization", |input: &'a [u8]| match input
+                            .split_first()
+                        {
+                            Some((0, rest)) => Ok((rest, None)),
+                            Some((1, rest)) => self
+                                .hash_deserializer
+                                .deserialize(rest)
+                                .map(|(rest, root)| (rest, Some(root))),
+                            _ => Err(nom::Err::Error(ParseError::from_error_kind(
+                                input,
+                                nom::error::ErrorKind::Tag,
+                            ))),
+                        }),
+                    )),
+                )
+                .map(|(slot, root)| Message::LedgerRoot(slot, root))
+                .parse(input),
+                MessageTypeId::AskForOperationInclusion => {
+                    context("Failed AskForOperationInclusion deserialization", |input| {
+                        self.infos_deserializer.deserialize(input)
+                    })
+                    .map(Message::AskForOperationInclusion)
+                    .parse(input)
+                }
+                MessageTypeId::OperationInclusion => context(
+                    "Failed OperationInclusion deserialization",
+                    length_count(
+                        context("Failed length deserialization", |input| {
+                            self.ask_block_number_deserializer.deserialize(input)
+                        }),
+                        tuple((
+                            context("Failed operation id deserialization", |input| {
+                                self.operation_id_deserializer.deserialize(input)
+                            }),
+                            context(
+                                "Failed proof deserialization",
+                                |input: &'a [u8]| match input.split_first() {
+                                    Some((0, rest)) => Ok((rest, None)),
+                                    Some((1, rest)) => tuple((
+                                        |input| self.block_header_deserializer.deserialize(input),
+                                        |input| self.infos_deserializer.deserialize(input),
+                                    ))
+                                    .map(|(header, operation_ids)| {
+                                        Some(OperationInclusionProof {
+                                            header,
+                                            operation_ids,
+                                        })
+                                    })
+                                    .parse(rest),
+                                    _ => Err(nom::Err::Error(ParseError::from_error_kind(
+                                        input,
+                                        nom::error::ErrorKind::Tag,
+                                    ))),
+                                },
+                            ),
+                        )),
+                    ),
+                )
+                .map(Message::OperationInclusion)
+                .parse(input),
+                MessageTypeId::AskForCycleRolls => {
+                    context("Failed AskForCycleRolls deserialization", |input| {
+                        self.cycle_deserializer.deserialize(input)
+                    })
+                    .map(Message::AskForCycleRolls)
+                    .parse(input)
+                }
+                MessageTypeId::CycleRolls => context(
+                    "Failed CycleRolls deserialization",
+                    tuple((
+                        context("Failed cycle deserialization", |input| {
+                            self.cycle_deserializer.deserialize(input)
+                        }),
+                        context(
+                            "Failed rolls deserialization",
+                            |input: &'a [u8]| match input.split_first() {
+                                Some((0, rest)) => Ok((rest, None)),
+                                Some((1, rest)) => length_count(
+                                    context("Failed length deserialization", |input| {
+                                        self.roll_counts_length_deserializer.deserialize(input)
+                                    }),
+                                    tuple((
+                                        context("Failed address deserialization", |input| {
+                                            self.address_deserializer.deserialize(input)
+                                        }),
+                                        context("Failed roll count deserialization", |input| {
+                                            self.roll_count_deserializer.deserialize(input)
+                                        }),
+                                    )),
+                                )
+                                .map(|rolls| Some(rolls.into_iter().collect()))
+                                .parse(rest),
+                                _ => Err(nom::Err::Error(ParseError::from_error_kind(
+                                    input,
+                                    nom::error::ErrorKind::Tag,
+                                ))),
+                            },
+                        ),
+                    )),
+                )
+                .map(|(cycle, rolls)| Message::CycleRolls(cycle, rolls))
+                .parse(input),
             }
         })
         .parse(buffer)
@@ -530,11 +863,14 @@ impl Deserializer<Message> for MessageDeserializer {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use massa_hash::Hash;
+    use massa_ledger_exports::{LedgerProof, LedgerSubEntry};
     use massa_models::config::{
         ENDORSEMENT_COUNT, MAX_ADVERTISE_LENGTH, MAX_ASK_BLOCKS_PER_MESSAGE,
         MAX_DATASTORE_VALUE_LENGTH, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_FUNCTION_NAME_LENGTH,
         MAX_OPERATIONS_PER_BLOCK, MAX_OPERATIONS_PER_MESSAGE, MAX_PARAMETERS_SIZE, THREAD_COUNT,
     };
+    use massa_models::{block::BlockHeaderSerializer, wrapped::Id};
     use massa_serialization::DeserializeError;
     use massa_signature::KeyPair;
     use rand::{prelude::StdRng, RngCore, SeedableRng};
@@ -564,6 +900,7 @@ mod tests {
             public_key: keypair.get_public_key(),
             random_bytes,
             version: Version::from_str("TEST.1.10").unwrap(),
+            capabilities: NodeCapabilities::default().with(NodeCapabilities::COMPRESSION),
         };
         let mut ser = Vec::new();
         message_serializer.serialize(&msg, &mut ser).unwrap();
@@ -576,18 +913,167 @@ mod tests {
                     public_key: pk1,
                     random_bytes: rb1,
                     version: v1,
+                    capabilities: c1,
                 },
                 Message::HandshakeInitiation {
                     public_key,
                     random_bytes,
                     version,
+                    capabilities,
                 },
             ) => {
                 assert_eq!(pk1, public_key);
                 assert_eq!(rb1, random_bytes);
                 assert_eq!(v1, version);
+                assert_eq!(c1, capabilities);
+            }
+            _ => panic!("unexpected message"),
+        }
+
+        // handshakes of nodes predating capabilities end after the version
+        ser.pop();
+        match message_deserializer.deserialize::<DeserializeError>(&ser) {
+            Ok((_, Message::HandshakeInitiation { capabilities, .. })) => {
+                assert_eq!(capabilities, NodeCapabilities::default())
             }
             _ => panic!("unexpected message"),
         }
     }
+
+    #[test]
+    #[serial]
+    fn test_light_client_messages_ser_deser() {
+        let message_serializer = MessageSerializer::new();
+        let message_deserializer = MessageDeserializer::new(
+            THREAD_COUNT,
+            ENDORSEMENT_COUNT,
+            MAX_ADVERTISE_LENGTH,
+            MAX_ASK_BLOCKS_PER_MESSAGE,
+            MAX_OPERATIONS_PER_BLOCK,
+            MAX_OPERATIONS_PER_MESSAGE,
+            MAX_ENDORSEMENTS_PER_MESSAGE,
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+        );
+        let keypair = KeyPair::generate();
+        let address = Address::from_public_key(&keypair.get_public_key());
+
+        // final balance proofs
+        let proofs = LedgerProofs {
+            slot: Slot::new(3, 1),
+            root: Hash::compute_from(b"root"),
+            proofs: vec![
+                LedgerProof {
+                    key: LedgerSubEntry::SeqBalance.key(&address),
+                    value: Some(vec![42]),
+                    siblings: vec![Hash::compute_from(b"left"), Hash::compute_from(b"right")],
+                    other_leaf: None,
+                },
+                LedgerProof {
+                    key: LedgerSubEntry::SeqBalance.key(&address),
+                    value: None,
+                    siblings: vec![Hash::compute_from(b"sibling")],
+                    other_leaf: Some(([7; 32], Hash::compute_from(b"other"))),
+                },
+            ],
+        };
+        let mut ser = Vec::new();
+        message_serializer
+            .serialize(&Message::FinalBalances(proofs.clone()), &mut ser)
+            .unwrap();
+        match message_deserializer.deserialize::<DeserializeError>(&ser) {
+            Ok((rest, Message::FinalBalances(deser))) => {
+                assert!(rest.is_empty());
+                assert_eq!(proofs.slot, deser.slot);
+                assert_eq!(proofs.root, deser.root);
+                assert_eq!(proofs.proofs, deser.proofs);
+            }
+            _ => panic!("unexpected message"),
+        }
+
+        // ledger roots
+        for root in [Some(Hash::compute_from(b"root")), None] {
+            let mut ser = Vec::new();
+            message_serializer
+                .serialize(&Message::LedgerRoot(Slot::new(3, 1), root), &mut ser)
+                .unwrap();
+            match message_deserializer.deserialize::<DeserializeError>(&ser) {
+                Ok((rest, Message::LedgerRoot(slot, deser))) => {
+                    assert!(rest.is_empty());
+                    assert_eq!(slot, Slot::new(3, 1));
+                    assert_eq!(root, deser);
+                }
+                _ => panic!("unexpected message"),
+            }
+        }
+
+        // operation inclusion proofs
+        let operation_ids: Vec<OperationId> = (0..3u8)
+            .map(|i| OperationId::new(Hash::compute_from(&[i])))
+            .collect();
+        let mut total_hash: Vec<u8> = vec![];
+        for op_id in &operation_ids {
+            total_hash.extend(op_id.to_bytes());
+        }
+        let header = BlockHeader::new_wrapped(
+            BlockHeader {
+                slot: Slot::new(1, 0),
+                parents: (0..THREAD_COUNT)
+                    .map(|i| BlockId(Hash::compute_from(&[i])))
+                    .collect(),
+                operation_merkle_root: Hash::compute_from(&total_hash),
+                endorsements: Vec::new(),
+            },
+            BlockHeaderSerializer::new(),
+            &keypair,
+        )
+        .unwrap();
+        let missing_id = OperationId::new(Hash::compute_from(b"missing"));
+        let proofs = vec![
+            (
+                operation_ids[1],
+                Some(OperationInclusionProof {
+                    header: header.clone(),
+                    operation_ids: operation_ids.clone(),
+                }),
+            ),
+            (missing_id, None),
+        ];
+        let mut ser = Vec::new();
+        message_serializer
+            .serialize(&Message::OperationInclusion(proofs), &mut ser)
+            .unwrap();
+        match message_deserializer.deserialize::<DeserializeError>(&ser) {
+            Ok((rest, Message::OperationInclusion(deser))) => {
+                assert!(rest.is_empty());
+                assert_eq!(deser.len(), 2);
+                assert_eq!(deser[0].0, operation_ids[1]);
+                let proof = deser[0].1.as_ref().expect("missing proof");
+                assert_eq!(proof.header.id, header.id);
+                assert!(proof.proves(&operation_ids[1]));
+                assert!(!proof.proves(&missing_id));
+                assert_eq!(deser[1].0, missing_id);
+                assert!(deser[1].1.is_none());
+            }
+            _ => panic!("unexpected message"),
+        }
+
+        // cycle roll counts
+        let rolls = BTreeMap::from([(address, 3), (Address::from_bytes(&[1; 32]), 70)]);
+        for rolls in [Some(rolls), None] {
+            let mut ser = Vec::new();
+            message_serializer
+                .serialize(&Message::CycleRolls(12, rolls.clone()), &mut ser)
+                .unwrap();
+            match message_deserializer.deserialize::<DeserializeError>(&ser) {
+                Ok((rest, Message::CycleRolls(cycle, deser))) => {
+                    assert!(rest.is_empty());
+                    assert_eq!(cycle, 12);
+                    assert_eq!(rolls, deser);
+                }
+                _ => panic!("unexpected message"),
+            }
+        }
+    }
 }
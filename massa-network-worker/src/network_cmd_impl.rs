@@ -10,8 +10,9 @@
 //! async fn manage_network_command(&mut self, cmd: NetworkCommand) -> Result<(), NetworkError> {
 //!     use crate::network_cmd_impl::*;
 //!     match cmd {
-//!         NetworkCommand::NodeBanByIps(ips) => on_node_ban_by_ips_cmd(self, ips).await?,
-//!         NetworkCommand::NodeBanByIds(ids) => on_node_ban_by_ids_cmd(self, ids).await?,
+//!         NetworkCommand::NodeBanByIps { ips, reason, duration } => on_node_ban_by_ips_cmd(self, ips, reason, duration).await?,
+//!         NetworkCommand::NodeBanByIds { ids, reason, duration } => on_node_ban_by_ids_cmd(self, ids, reason, duration).await?,
+//!         NetworkCommand::NodeScoreEvent { node, event } => on_node_score_event_cmd(self, node, event).await?,
 //!         NetworkCommand::SendBlockHeader { node, header } => on_send_block_header_cmd(self, node, header).await?,
 //!         NetworkCommand::AskForBlocks { list } => on_ask_for_block_cmd(self, list).await,
 //!         NetworkCommand::SendBlock { node, block } => on_send_block_cmd(self, node, block).await?,
@@ -22,22 +23,26 @@
 use crate::network_worker::NetworkWorker;
 use futures::{stream::FuturesUnordered, StreamExt};
 use massa_hash::Hash;
+use massa_ledger_exports::LedgerProofs;
 use massa_logging::massa_trace;
 use massa_models::{
+    address::Address,
     block::{BlockId, WrappedHeader},
     composite::PubkeySig,
     endorsement::WrappedEndorsement,
-    node::NodeId,
-    operation::{OperationPrefixIds, WrappedOperation},
+    node::{BanReason, NodeId},
+    operation::{OperationId, OperationPrefixIds, WrappedOperation},
+    slot::Slot,
     stats::NetworkStats,
 };
 use massa_network_exports::{
     AskForBlocksInfo, BlockInfoReply, BootstrapPeers, ConnectionClosureReason, ConnectionId,
-    NetworkError, NodeCommand, Peer, Peers,
+    NetworkError, NodeCommand, OperationInclusionProof, Peer, PeerInfo, PeerScoreEvent, Peers,
 };
+use massa_time::MassaTime;
 use std::{
-    collections::{HashMap, HashSet},
-    net::IpAddr,
+    collections::{BTreeMap, HashMap, HashSet},
+    net::{IpAddr, SocketAddr},
 };
 use tokio::sync::oneshot;
 use tracing::warn;
@@ -66,17 +71,24 @@ async fn ban_connection_ids(worker: &mut NetworkWorker, ids: HashSet<ConnectionI
     }
 }
 
-/// Ban the connections corresponding to `ips` from the `worker`
+/// Ban the peers behind `ips` and their connections from the `worker`
 /// See also [ban_connection_ids]
-async fn node_ban_by_ips(worker: &mut NetworkWorker, ips: Vec<IpAddr>) -> Result<(), NetworkError> {
+async fn node_ban_by_ips(
+    worker: &mut NetworkWorker,
+    ips: Vec<IpAddr>,
+    reason: BanReason,
+    duration: Option<MassaTime>,
+) -> Result<(), NetworkError> {
     for ip in ips.iter() {
-        worker.peer_info_db.peer_banned(ip)?;
+        for addr in worker.peer_info_db.get_addresses_for_ip(ip) {
+            worker.peer_info_db.peer_banned(&addr, reason, duration)?;
+        }
     }
     let connexion_ids = worker
         .active_connections
         .iter()
-        .filter_map(|(conn_id, (ip, _))| {
-            if ips.contains(ip) {
+        .filter_map(|(conn_id, (addr, _))| {
+            if ips.contains(&addr.ip()) {
                 Some(conn_id)
             } else {
                 None
@@ -90,11 +102,16 @@ async fn node_ban_by_ips(worker: &mut NetworkWorker, ips: Vec<IpAddr>) -> Result
 
 /// Ban the connections corresponding to node `ids` from the `worker`
 /// See also [ban_connection_ids]
-async fn node_ban_by_ids(worker: &mut NetworkWorker, ids: Vec<NodeId>) -> Result<(), NetworkError> {
+async fn node_ban_by_ids(
+    worker: &mut NetworkWorker,
+    ids: Vec<NodeId>,
+    reason: BanReason,
+    duration: Option<MassaTime>,
+) -> Result<(), NetworkError> {
     // get all connection IDs to ban
     let connection_ids_to_ban = ids
         .iter()
-        .map(|id| get_connection_ids(worker, id))
+        .map(|id| get_connection_ids(worker, id, reason, duration))
         .filter(|res| res.is_ok())
         .flat_map(|res| res.unwrap())
         .collect::<HashSet<_>>();
@@ -103,21 +120,28 @@ async fn node_ban_by_ids(worker: &mut NetworkWorker, ids: Vec<NodeId>) -> Result
     Ok(())
 }
 
-/// For each peer get all node id associated to this peer ip.
+/// For each peer get all node id associated to this peer address.
+/// Peer scores are reported decayed up to now.
 async fn get_peers(worker: &mut NetworkWorker, response_tx: oneshot::Sender<Peers>) {
-    let peers: HashMap<IpAddr, Peer> = worker
+    let peers: HashMap<SocketAddr, Peer> = worker
         .peer_info_db
         .get_peers()
         .iter()
-        .map(|(peer_ip_addr, peer)| {
+        .map(|(peer_addr, peer)| {
             (
-                *peer_ip_addr,
+                *peer_addr,
                 Peer {
-                    peer_info: *peer,
+                    peer_info: PeerInfo {
+                        score: worker
+                            .peer_info_db
+                            .get_peer_score(peer)
+                            .unwrap_or(peer.score),
+                        ..*peer
+                    },
                     active_nodes: worker
                         .active_connections
                         .iter()
-                        .filter(|(_, (ip_addr, _))| &peer.ip == ip_addr)
+                        .filter(|(_, (addr, _))| &peer.address == addr)
                         .filter_map(|(out_conn_id, (_, out_going))| {
                             worker
                                 .active_nodes
@@ -153,23 +177,56 @@ async fn get_peers(worker: &mut NetworkWorker, response_tx: oneshot::Sender<Peer
 pub async fn on_node_ban_by_ips_cmd(
     worker: &mut NetworkWorker,
     ips: Vec<IpAddr>,
+    reason: BanReason,
+    duration: Option<MassaTime>,
 ) -> Result<(), NetworkError> {
     massa_trace!(
         "network_worker.manage_network_command receive NetworkCommand::NodeBanByIps",
-        { "ips": ips }
+        { "ips": ips, "reason": reason, "duration": duration }
     );
-    node_ban_by_ips(worker, ips).await
+    node_ban_by_ips(worker, ips, reason, duration).await
 }
 
 pub async fn on_node_ban_by_ids_cmd(
     worker: &mut NetworkWorker,
     ids: Vec<NodeId>,
+    reason: BanReason,
+    duration: Option<MassaTime>,
 ) -> Result<(), NetworkError> {
     massa_trace!(
         "network_worker.manage_network_command receive NetworkCommand::NodeBanByIds",
-        { "ids": ids }
+        { "ids": ids, "reason": reason, "duration": duration }
+    );
+    node_ban_by_ids(worker, ids, reason, duration).await
+}
+
+/// Updates the score of the peer behind `node`, banning it if the score got too low
+pub async fn on_node_score_event_cmd(
+    worker: &mut NetworkWorker,
+    node: NodeId,
+    event: PeerScoreEvent,
+) -> Result<(), NetworkError> {
+    massa_trace!(
+        "network_worker.manage_network_command receive NetworkCommand::NodeScoreEvent",
+        { "node": node, "event": event }
     );
-    node_ban_by_ids(worker, ids).await
+    let addr = match worker
+        .active_nodes
+        .get(&node)
+        .and_then(|(conn_id, _)| worker.active_connections.get(conn_id))
+    {
+        Some((addr, _)) => *addr,
+        // the connection was closed in the meantime
+        None => return Ok(()),
+    };
+    if worker.peer_info_db.peer_score_event(&addr, &event)? {
+        warn!(
+            "banning node {} at {}: reputation score too low",
+            node, addr
+        );
+        node_ban_by_ids(worker, vec![node], BanReason::ProtocolViolation, None).await?;
+    }
+    Ok(())
 }
 
 pub async fn on_send_block_header_cmd(
@@ -245,7 +302,7 @@ pub async fn on_get_bootstrap_peers_cmd(
         "network_worker.manage_network_command receive NetworkCommand::GetBootstrapPeers",
         {}
     );
-    let peer_list = worker.peer_info_db.get_advertisable_peer_ips();
+    let peer_list = worker.peer_info_db.get_advertisable_peer_addresses();
     if response_tx.send(BootstrapPeers(peer_list)).is_err() {
         warn!("network: could not send GetBootstrapPeers response upstream");
     }
@@ -270,6 +327,152 @@ pub async fn on_send_endorsements_cmd(
         .await;
 }
 
+pub async fn on_ask_for_final_balances_cmd(
+    worker: &mut NetworkWorker,
+    node: NodeId,
+    addresses: Vec<Address>,
+) {
+    massa_trace!(
+        "network_worker.manage_network_command receive NetworkCommand::AskForFinalBalances",
+        { "node": node, "addresses": addresses }
+    );
+    worker
+        .event
+        .forward(
+            node,
+            worker.active_nodes.get(&node),
+            NodeCommand::AskForFinalBalances(addresses),
+        )
+        .await;
+}
+
+pub async fn on_send_final_balances_cmd(
+    worker: &mut NetworkWorker,
+    node: NodeId,
+    proofs: LedgerProofs,
+) {
+    massa_trace!(
+        "network_worker.manage_network_command receive NetworkCommand::SendFinalBalances",
+        { "node": node, "proofs": proofs }
+    );
+    worker
+        .event
+        .forward(
+            node,
+            worker.active_nodes.get(&node),
+            NodeCommand::SendFinalBalances(proofs),
+        )
+        .await;
+}
+
+pub async fn on_ask_for_ledger_root_cmd(worker: &mut NetworkWorker, node: NodeId, slot: Slot) {
+    massa_trace!(
+        "network_worker.manage_network_command receive NetworkCommand::AskForLedgerRoot",
+        { "node": node, "slot": slot }
+    );
+    worker
+        .event
+        .forward(
+            node,
+            worker.active_nodes.get(&node),
+            NodeCommand::AskForLedgerRoot(slot),
+        )
+        .await;
+}
+
+pub async fn on_send_ledger_root_cmd(
+    worker: &mut NetworkWorker,
+    node: NodeId,
+    slot: Slot,
+    root: Option<Hash>,
+) {
+    massa_trace!(
+        "network_worker.manage_network_command receive NetworkCommand::SendLedgerRoot",
+        { "node": node, "slot": slot, "root": root }
+    );
+    worker
+        .event
+        .forward(
+            node,
+            worker.active_nodes.get(&node),
+            NodeCommand::SendLedgerRoot(slot, root),
+        )
+        .await;
+}
+
+pub async fn on_ask_for_cycle_rolls_cmd(worker: &mut NetworkWorker, node: NodeId, cycle: u64) {
+    massa_trace!(
+        "network_worker.manage_network_command receive NetworkCommand::AskForCycleRolls",
+        { "node": node, "cycle": cycle }
+    );
+    worker
+        .event
+        .forward(
+            node,
+            worker.active_nodes.get(&node),
+            NodeCommand::AskForCycleRolls(cycle),
+        )
+        .await;
+}
+
+pub async fn on_send_cycle_rolls_cmd(
+    worker: &mut NetworkWorker,
+    node: NodeId,
+    cycle: u64,
+    rolls: Option<BTreeMap<Address, u64>>,
+) {
+    massa_trace!(
+        "network_worker.manage_network_command receive NetworkCommand::SendCycleRolls",
+        { "node": node, "cycle": cycle, "rolls": rolls }
+    );
+    worker
+        .event
+        .forward(
+            node,
+            worker.active_nodes.get(&node),
+            NodeCommand::SendCycleRolls(cycle, rolls),
+        )
+        .await;
+}
+
+pub async fn on_ask_for_operation_inclusion_cmd(
+    worker: &mut NetworkWorker,
+    node: NodeId,
+    operation_ids: Vec<OperationId>,
+) {
+    massa_trace!(
+        "network_worker.manage_network_command receive NetworkCommand::AskForOperationInclusion",
+        { "node": node, "operation_ids": operation_ids }
+    );
+    worker
+        .event
+        .forward(
+            node,
+            worker.active_nodes.get(&node),
+            NodeCommand::AskForOperationInclusion(operation_ids),
+        )
+        .await;
+}
+
+pub async fn on_send_operation_inclusion_cmd(
+    worker: &mut NetworkWorker,
+    node: NodeId,
+    proofs: Vec<(OperationId, Option<OperationInclusionProof>)>,
+) {
+    massa_trace!(
+        "network_worker.manage_network_command receive NetworkCommand::SendOperationInclusion",
+        { "node": node, "proofs": proofs }
+    );
+    worker
+        .event
+        .forward(
+            node,
+            worker.active_nodes.get(&node),
+            NodeCommand::SendOperationInclusion(proofs),
+        )
+        .await;
+}
+
 pub async fn on_node_sign_message_cmd(
     worker: &mut NetworkWorker,
     msg: Vec<u8>,
@@ -339,6 +542,8 @@ pub async fn on_get_stats_cmd(
             .filter(|(_, p)| p.banned)
             .fold(0, |acc, _| acc + 1),
         active_node_count: worker.active_nodes.len() as u64,
+        sent_compression_ratio: worker.compression_stats.sent_ratio(),
+        received_compression_ratio: worker.compression_stats.received_ratio(),
     };
     if response_tx.send(res).is_err() {
         warn!("network: could not send NodeSignMessage response upstream");
@@ -426,13 +631,17 @@ pub async fn on_ask_for_operations_cmd(
 fn get_connection_ids(
     worker: &mut NetworkWorker,
     node: &NodeId,
+    reason: BanReason,
+    duration: Option<MassaTime>,
 ) -> Result<HashSet<ConnectionId>, NetworkError> {
     let mut ids: HashSet<ConnectionId> = HashSet::new();
     if let Some((orig_conn_id, _)) = worker.active_nodes.get(node) {
-        if let Some((orig_ip, _)) = worker.active_connections.get(orig_conn_id) {
-            worker.peer_info_db.peer_banned(orig_ip)?;
-            for (target_conn_id, (target_ip, _)) in worker.active_connections.iter() {
-                if target_ip == orig_ip {
+        if let Some((orig_addr, _)) = worker.active_connections.get(orig_conn_id) {
+            worker
+                .peer_info_db
+                .peer_banned(orig_addr, reason, duration)?;
+            for (target_conn_id, (target_addr, _)) in worker.active_connections.iter() {
+                if target_addr == orig_addr {
                     ids.insert(*target_conn_id);
                 }
             }
@@ -444,10 +653,10 @@ fn get_connection_ids(
 
 fn get_ip(worker: &mut NetworkWorker, node: &NodeId) -> Option<IpAddr> {
     if let Some((orig_conn_id, _)) = worker.active_nodes.get(node) {
-        if let Some((orig_ip, _)) = worker.active_connections.get(orig_conn_id) {
-            for (_, (target_ip, _)) in worker.active_connections.iter() {
-                if target_ip == orig_ip {
-                    return Some(*target_ip);
+        if let Some((orig_addr, _)) = worker.active_connections.get(orig_conn_id) {
+            for (_, (target_addr, _)) in worker.active_connections.iter() {
+                if target_addr == orig_addr {
+                    return Some(target_addr.ip());
                 }
             }
         }
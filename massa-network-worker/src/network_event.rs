@@ -78,17 +78,23 @@ impl EventSender {
 
 pub mod event_impl {
     use crate::network_worker::NetworkWorker;
+    use massa_hash::Hash;
+    use massa_ledger_exports::LedgerProofs;
     use massa_logging::massa_trace;
     use massa_models::{
+        address::Address,
         block::{BlockId, WrappedHeader},
         endorsement::WrappedEndorsement,
         node::NodeId,
-        operation::{OperationPrefixIds, WrappedOperation},
+        operation::{OperationId, OperationPrefixIds, WrappedOperation},
+        slot::Slot,
         wrapped::Id,
     };
-    use massa_network_exports::{AskForBlocksInfo, BlockInfoReply, NodeCommand};
+    use massa_network_exports::{
+        AskForBlocksInfo, BlockInfoReply, NodeCommand, OperationInclusionProof,
+    };
     use massa_network_exports::{NetworkError, NetworkEvent};
-    use std::net::IpAddr;
+    use std::{collections::BTreeMap, net::SocketAddr};
     use tracing::{debug, info};
     macro_rules! evt_failed {
         ($err: ident) => {
@@ -100,12 +106,16 @@ pub mod event_impl {
     pub fn on_received_peer_list(
         worker: &mut NetworkWorker,
         from: NodeId,
-        list: &[IpAddr],
+        list: &[SocketAddr],
     ) -> Result<(), NetworkError> {
-        debug!("node_id={} sent us a peer list ({} ips)", from, list.len());
+        debug!(
+            "node_id={} sent us a peer list ({} addresses)",
+            from,
+            list.len()
+        );
         massa_trace!("peer_list_received", {
             "node_id": from,
-            "ips": list
+            "addresses": list
         });
         worker.peer_info_db.merge_candidate_peers(list)?;
         Ok(())
@@ -168,7 +178,7 @@ pub mod event_impl {
     ) -> Result<(), NetworkError> {
         debug!("node_id={} asked us for peer list", from);
         massa_trace!("node_asked_peer_list", { "node_id": from });
-        let peer_list = worker.peer_info_db.get_advertisable_peer_ips();
+        let peer_list = worker.peer_info_db.get_advertisable_peer_addresses();
         if let Some((_, node_command_tx)) = worker.active_nodes.get(&from) {
             let res = node_command_tx
                 .send(NodeCommand::SendPeerList(peer_list))
@@ -279,4 +289,164 @@ pub mod event_impl {
             evt_failed!(err)
         }
     }
+
+    pub async fn on_received_ask_for_final_balances(
+        worker: &mut NetworkWorker,
+        from: NodeId,
+        addresses: Vec<Address>,
+    ) {
+        massa_trace!(
+            "network_worker.on_node_event receive NetworkEvent::AskedForFinalBalances",
+            { "addresses": addresses }
+        );
+        if let Err(err) = worker
+            .event
+            .send(NetworkEvent::AskedForFinalBalances {
+                node: from,
+                addresses,
+            })
+            .await
+        {
+            evt_failed!(err)
+        }
+    }
+
+    pub async fn on_received_final_balances(
+        worker: &mut NetworkWorker,
+        from: NodeId,
+        proofs: LedgerProofs,
+    ) {
+        massa_trace!(
+            "network_worker.on_node_event receive NetworkEvent::ReceivedFinalBalances",
+            { "proofs": proofs }
+        );
+        if let Err(err) = worker
+            .event
+            .send(NetworkEvent::ReceivedFinalBalances { node: from, proofs })
+            .await
+        {
+            evt_failed!(err)
+        }
+    }
+
+    pub async fn on_received_ask_for_ledger_root(
+        worker: &mut NetworkWorker,
+        from: NodeId,
+        slot: Slot,
+    ) {
+        massa_trace!(
+            "network_worker.on_node_event receive NetworkEvent::AskedForLedgerRoot",
+            { "slot": slot }
+        );
+        if let Err(err) = worker
+            .event
+            .send(NetworkEvent::AskedForLedgerRoot { node: from, slot })
+            .await
+        {
+            evt_failed!(err)
+        }
+    }
+
+    pub async fn on_received_ledger_root(
+        worker: &mut NetworkWorker,
+        from: NodeId,
+        slot: Slot,
+        root: Option<Hash>,
+    ) {
+        massa_trace!(
+            "network_worker.on_node_event receive NetworkEvent::ReceivedLedgerRoot",
+            { "slot": slot, "root": root }
+        );
+        if let Err(err) = worker
+            .event
+            .send(NetworkEvent::ReceivedLedgerRoot {
+                node: from,
+                slot,
+                root,
+            })
+            .await
+        {
+            evt_failed!(err)
+        }
+    }
+
+    pub async fn on_received_ask_for_cycle_rolls(
+        worker: &mut NetworkWorker,
+        from: NodeId,
+        cycle: u64,
+    ) {
+        massa_trace!(
+            "network_worker.on_node_event receive NetworkEvent::AskedForCycleRolls",
+            { "cycle": cycle }
+        );
+        if let Err(err) = worker
+            .event
+            .send(NetworkEvent::AskedForCycleRolls { node: from, cycle })
+            .await
+        {
+            evt_failed!(err)
+        }
+    }
+
+    pub async fn on_received_cycle_rolls(
+        worker: &mut NetworkWorker,
+        from: NodeId,
+        cycle: u64,
+        rolls: Option<BTreeMap<Address, u64>>,
+    ) {
+        massa_trace!(
+            "network_worker.on_node_event receive NetworkEvent::ReceivedCycleRolls",
+            { "cycle": cycle, "rolls": rolls }
+        );
+        if let Err(err) = worker
+            .event
+            .send(NetworkEvent::ReceivedCycleRolls {
+                node: from,
+                cycle,
+                rolls,
+            })
+            .await
+        {
+            evt_failed!(err)
+        }
+    }
+
+    pub async fn on_received_ask_for_operation_inclusion(
+        worker: &mut NetworkWorker,
+        from: NodeId,
+        operation_ids: Vec<OperationId>,
+    ) {
+        massa_trace!(
+            "network_worker.on_node_event receive NetworkEvent::AskedForOperationInclusion",
+            { "operation_ids": operation_ids }
+        );
+        if let Err(err) = worker
+            .event
+            .send(NetworkEvent::AskedForOperationInclusion {
+                node: from,
+                operation_ids,
+            })
+            .await
+        {
+            evt_failed!(err)
+        }
+    }
+
+    pub async fn on_received_operation_inclusion(
+        worker: &mut NetworkWorker,
+        from: NodeId,
+        proofs: Vec<(OperationId, Option<OperationInclusionProof>)>,
+    ) {
+        massa_trace!(
+            "network_worker.on_node_event receive NetworkEvent::ReceivedOperationInclusion",
+            { "proofs": proofs }
+        );
+        if let Err(err) = worker
+            .event
+            .send(NetworkEvent::ReceivedOperationInclusion { node: from, proofs })
+            .await
+        {
+            evt_failed!(err)
+        }
+    }
 }
@@ -5,7 +5,7 @@ use super::{
     handshake_worker::HandshakeReturnType, node_worker::NodeWorker, peer_info_database::*,
 };
 use crate::{
-    binders::{ReadBinder, WriteBinder},
+    binders::{CompressionStats, MessageCompression, ReadBinder, WriteBinder},
     handshake_worker::HandshakeWorker,
     messages::{Message, MessageDeserializer},
     network_event::EventSender,
@@ -21,7 +21,8 @@ use massa_network_exports::{
 use massa_signature::KeyPair;
 use std::{
     collections::{hash_map, HashMap, HashSet},
-    net::{IpAddr, SocketAddr},
+    net::SocketAddr,
+    sync::Arc,
 };
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
@@ -58,12 +59,16 @@ pub struct NetworkWorker {
     /// Node worker handles
     node_worker_handles:
         FuturesUnordered<JoinHandle<(NodeId, Result<ConnectionClosureReason, NetworkError>)>>,
-    /// Map of connection to ip, `is_outgoing`.
-    pub(crate) active_connections: HashMap<ConnectionId, (IpAddr, bool)>,
+    /// Map of connection to peer address, `is_outgoing`.
+    pub(crate) active_connections: HashMap<ConnectionId, (SocketAddr, bool)>,
     /// Node version
     version: Version,
     /// Event sender
     pub(crate) event: EventSender,
+    /// Message compression offered to peers, if enabled
+    message_compression: Option<MessageCompression>,
+    /// Compression statistics of all connections
+    pub(crate) compression_stats: Arc<CompressionStats>,
 }
 
 pub struct NetworkWorkerChannels {
@@ -102,6 +107,15 @@ impl NetworkWorker {
         let (node_event_tx, node_event_rx) =
             mpsc::channel::<NodeEvent>(cfg.node_event_channel_size);
         let max_wait_event = cfg.max_send_wait.to_duration();
+        let compression_stats = Arc::new(CompressionStats::default());
+        let message_compression = if cfg.enable_message_compression {
+            Some(MessageCompression {
+                min_size: cfg.message_compression_min_size,
+                stats: compression_stats.clone(),
+            })
+        } else {
+            None
+        };
         NetworkWorker {
             cfg,
             self_node_id,
@@ -120,6 +134,8 @@ impl NetworkWorker {
             node_worker_handles: FuturesUnordered::new(),
             active_connections: HashMap::new(),
             version,
+            message_compression,
+            compression_stats,
         }
     }
 
@@ -133,23 +149,33 @@ impl NetworkWorker {
         let mut wakeup_interval = tokio::time::interval(self.cfg.wakeup_interval.to_duration());
         let mut need_connect_retry = true;
 
+        // periodically replace one outgoing connection, if enabled
+        let rotation_enabled = self.cfg.out_connection_rotation_interval.to_millis() > 0;
+        let rotation_period = std::cmp::max(
+            self.cfg.out_connection_rotation_interval.to_duration(),
+            std::time::Duration::from_millis(1),
+        );
+        let mut rotation_interval = tokio::time::interval_at(
+            tokio::time::Instant::now() + rotation_period,
+            rotation_period,
+        );
+
         loop {
             if need_connect_retry {
-                // try to connect to candidate IPs
-                let candidate_ips = self.peer_info_db.get_out_connection_candidate_ips()?;
-                for ip in candidate_ips {
-                    debug!("starting outgoing connection attempt towards ip={}", ip);
-                    massa_trace!("out_connection_attempt_start", { "ip": ip });
-                    self.peer_info_db.new_out_connection_attempt(&ip)?;
+                // try to connect to candidate addresses
+                let candidate_addrs = self.peer_info_db.get_out_connection_candidate_addresses()?;
+                for addr in candidate_addrs {
+                    debug!("starting outgoing connection attempt towards addr={}", addr);
+                    massa_trace!("out_connection_attempt_start", { "address": addr });
+                    self.peer_info_db.new_out_connection_attempt(&addr)?;
                     let mut connector = self
                         .establisher
                         .get_connector(self.cfg.connect_timeout)
                         .await?;
-                    let addr = SocketAddr::new(ip, self.cfg.protocol_port);
                     out_connecting_futures.push(async move {
                         match connector.connect(addr).await {
-                            Ok((reader, writer)) => (addr.ip(), Ok((reader, writer))),
-                            Err(e) => (addr.ip(), Err(e)),
+                            Ok((reader, writer)) => (addr, Ok((reader, writer))),
+                            Err(e) => (addr, Err(e)),
                         }
                     });
                 }
@@ -192,6 +218,14 @@ impl NetworkWorker {
                 // wake up interval
                 _ = wakeup_interval.tick() => {
                     self.peer_info_db.update()?; // notify tick to peer db
+                    self.evict_low_score_peers().await?;
+
+                    need_connect_retry = true; // retry out connections
+                }
+
+                // outgoing connection rotation interval
+                _ = rotation_interval.tick(), if rotation_enabled => {
+                    self.rotate_out_connection().await?;
 
                     need_connect_retry = true; // retry out connections
                 }
@@ -243,11 +277,11 @@ impl NetworkWorker {
                 },
 
                 // out-connector event
-                Some((ip_addr, res)) = out_connecting_futures.next() => {
+                Some((addr, res)) = out_connecting_futures.next() => {
                     need_connect_retry = true; // retry out connections
                     self.manage_out_connections(
                         res,
-                        ip_addr,
+                        addr,
                         &mut cur_connection_id,
                     ).await?
                 },
@@ -327,7 +361,13 @@ impl NetworkWorker {
         });
         match outcome {
             // a handshake finished, and succeeded
-            Ok((new_node_id, socket_reader, socket_writer)) => {
+            Ok((
+                new_node_id,
+                socket_reader,
+                socket_writer,
+                new_node_version,
+                new_node_capabilities,
+            )) => {
                 debug!(
                     "handshake with connection_id={} succeeded => node_id={}",
                     new_connection_id, new_node_id
@@ -374,21 +414,24 @@ impl NetworkWorker {
                         });
 
                         // Note connection alive.
-                        let (ip, _) = self
+                        let (addr, _) = self
                             .active_connections
                             .get(&new_connection_id)
                             .ok_or(NetworkError::ActiveConnectionMissing(new_connection_id))?;
-                        self.peer_info_db.peer_alive(ip)?;
+                        self.peer_info_db.peer_alive(addr)?;
 
                         // spawn node_controller_fn
                         let (node_command_tx, node_command_rx) =
                             mpsc::channel::<NodeCommand>(self.cfg.node_command_channel_size);
                         let node_event_tx_clone = self.event.clone_node_sender();
                         let cfg_copy = self.cfg.clone();
+                        let node_supports_peer_ports =
+                            new_node_version.supports_peer_ports(&self.version);
                         let node_fn_handle = tokio::spawn(async move {
                             let res = NodeWorker::new(
                                 cfg_copy,
                                 new_node_id,
+                                node_supports_peer_ports,
                                 socket_reader,
                                 socket_writer,
                                 node_command_rx,
@@ -403,7 +446,10 @@ impl NetworkWorker {
 
                         let res = self
                             .event
-                            .send(NetworkEvent::NewConnection(new_node_id))
+                            .send(NetworkEvent::NewConnection(
+                                new_node_id,
+                                new_node_capabilities,
+                            ))
                             .await;
 
                         // If we failed to send the event to protocol, close the connection.
@@ -427,6 +473,11 @@ impl NetworkWorker {
                 // Manage the final of an handshake that send us a list of new peers
                 // instead of accepting a connection. Notify to the DB that `to_remove`
                 // has failed and merge new `to_add` candidates.
+                // Peer lists sent during handshake only contain peers on the default port.
+                let peers: Vec<SocketAddr> = peers
+                    .into_iter()
+                    .map(|ip| SocketAddr::new(ip, self.cfg.protocol_port))
+                    .collect();
                 self.peer_info_db.merge_candidate_peers(&peers)?;
                 self.running_handshakes.remove(&new_connection_id);
                 self.connection_closed(new_connection_id, ConnectionClosureReason::Failed)
@@ -455,36 +506,81 @@ impl NetworkWorker {
         id: ConnectionId,
         reason: ConnectionClosureReason,
     ) -> Result<(), NetworkError> {
-        let (ip, is_outgoing) = self
+        let (addr, is_outgoing) = self
             .active_connections
             .remove(&id)
             .ok_or(NetworkError::ActiveConnectionMissing(id))?;
         debug!(
-            "connection closed connection_id={}, ip={}, reason={:?}",
-            id, ip, reason
+            "connection closed connection_id={}, addr={}, reason={:?}",
+            id, addr, reason
         );
         massa_trace!("network_worker.connection_closed", {
             "connection_id": id,
-            "ip": ip,
+            "address": addr,
             "reason": reason
         });
         match reason {
             ConnectionClosureReason::Normal => {}
             ConnectionClosureReason::Failed => {
-                self.peer_info_db.peer_failed(&ip)?;
+                self.peer_info_db.peer_failed(&addr)?;
             }
             ConnectionClosureReason::Banned => {
                 // nothing here, because peer_info_db.peer_banned called in NetworkCommand::Ban
             }
         }
         if is_outgoing {
-            self.peer_info_db.out_connection_closed(&ip)?;
+            self.peer_info_db.out_connection_closed(&addr)?;
         } else {
-            self.peer_info_db.in_connection_closed(&ip)?;
+            self.peer_info_db.in_connection_closed(&addr)?;
         }
         Ok(())
     }
 
+    /// Closes the outgoing connections of low scored peers
+    /// for which better scored candidates are available.
+    /// The freed slots are filled on the next connection retry.
+    async fn evict_low_score_peers(&mut self) -> Result<(), NetworkError> {
+        for addr in self.peer_info_db.get_eviction_candidate_addresses()? {
+            debug!("evicting addr={} for a better scored peer", addr);
+            self.close_out_connections(addr).await;
+        }
+        Ok(())
+    }
+
+    /// Closes the outgoing connection of the lowest scored standard peer
+    /// so that its slot is given to another candidate, which makes it harder
+    /// for an attacker to hold on to all our outgoing connections.
+    async fn rotate_out_connection(&mut self) -> Result<(), NetworkError> {
+        if let Some(addr) = self.peer_info_db.get_rotation_candidate_address()? {
+            debug!("rotating outgoing connection to addr={}", addr);
+            self.close_out_connections(addr).await;
+        }
+        Ok(())
+    }
+
+    /// Asks the node workers of the outgoing connections to `addr` to close.
+    async fn close_out_connections(&self, addr: SocketAddr) {
+        let connection_ids: HashSet<ConnectionId> = self
+            .active_connections
+            .iter()
+            .filter(|(_, (conn_addr, is_outgoing))| *is_outgoing && *conn_addr == addr)
+            .map(|(conn_id, _)| *conn_id)
+            .collect();
+        for (node_id, (conn_id, node_command_tx)) in self.active_nodes.iter() {
+            if !connection_ids.contains(conn_id) {
+                continue;
+            }
+            debug!(
+                "closing outgoing connection node_id={} addr={}",
+                node_id, addr
+            );
+            // the node might have closed by itself just before
+            let _ = node_command_tx
+                .send(NodeCommand::Close(ConnectionClosureReason::Normal))
+                .await;
+        }
+    }
+
     /// Manages network commands
     /// Only used inside worker's `run_loop`
     ///
@@ -507,8 +603,19 @@ impl NetworkWorker {
     async fn manage_network_command(&mut self, cmd: NetworkCommand) -> Result<(), NetworkError> {
         use crate::network_cmd_impl::*;
         match cmd {
-            NetworkCommand::NodeBanByIps(ips) => on_node_ban_by_ips_cmd(self, ips).await?,
-            NetworkCommand::NodeBanByIds(ids) => on_node_ban_by_ids_cmd(self, ids).await?,
+            NetworkCommand::NodeBanByIps {
+                ips,
+                reason,
+                duration,
+            } => on_node_ban_by_ips_cmd(self, ips, reason, duration).await?,
+            NetworkCommand::NodeBanByIds {
+                ids,
+                reason,
+                duration,
+            } => on_node_ban_by_ids_cmd(self, ids, reason, duration).await?,
+            NetworkCommand::NodeScoreEvent { node, event } => {
+                on_node_score_event_cmd(self, node, event).await?
+            }
             NetworkCommand::SendBlockHeader { node, header } => {
                 on_send_block_header_cmd(self, node, header).await?
             }
@@ -542,6 +649,31 @@ impl NetworkWorker {
             NetworkCommand::RemoveFromWhitelist(ips) => {
                 on_remove_from_whitelist_cmd(self, ips).await?
             }
+            NetworkCommand::AskForFinalBalances { node, addresses } => {
+                on_ask_for_final_balances_cmd(self, node, addresses).await
+            }
+            NetworkCommand::SendFinalBalances { node, proofs } => {
+                on_send_final_balances_cmd(self, node, proofs).await
+            }
+            NetworkCommand::AskForLedgerRoot { node, slot } => {
+                on_ask_for_ledger_root_cmd(self, node, slot).await
+            }
+            NetworkCommand::SendLedgerRoot { node, slot, root } => {
+                on_send_ledger_root_cmd(self, node, slot, root).await
+            }
+            NetworkCommand::AskForCycleRolls { node, cycle } => {
+                on_ask_for_cycle_rolls_cmd(self, node, cycle).await
+            }
+            NetworkCommand::SendCycleRolls { node, cycle, rolls } => {
+                on_send_cycle_rolls_cmd(self, node, cycle, rolls).await
+            }
+            NetworkCommand::AskForOperationInclusion {
+                node,
+                operation_ids,
+            } => on_ask_for_operation_inclusion_cmd(self, node, operation_ids).await,
+            NetworkCommand::SendOperationInclusion { node, proofs } => {
+                on_send_operation_inclusion_cmd(self, node, proofs).await
+            }
         };
         Ok(())
     }
@@ -551,49 +683,48 @@ impl NetworkWorker {
     ///
     /// # Arguments
     /// * `res`: `(reader, writer)` in a result coming out of `out_connecting_futures`
-    /// * `ip_addr`: distant address we are trying to reach.
+    /// * `addr`: distant address we are trying to reach.
     /// * `cur_connection_id`: connection id of the node we are trying to reach
     async fn manage_out_connections(
         &mut self,
         res: tokio::io::Result<(ReadHalf, WriteHalf)>,
-        ip_addr: IpAddr,
+        addr: SocketAddr,
         cur_connection_id: &mut ConnectionId,
     ) -> Result<(), NetworkError> {
         match res {
             Ok((reader, writer)) => {
                 if self
                     .peer_info_db
-                    .try_out_connection_attempt_success(&ip_addr)?
+                    .try_out_connection_attempt_success(&addr)?
                 {
                     // outgoing connection established
                     let connection_id = *cur_connection_id;
                     debug!(
-                        "out connection towards ip={} established => connection_id={}",
-                        ip_addr, connection_id
+                        "out connection towards addr={} established => connection_id={}",
+                        addr, connection_id
                     );
                     massa_trace!("out_connection_established", {
-                        "ip": ip_addr,
+                        "address": addr,
                         "connection_id": connection_id
                     });
                     cur_connection_id.0 += 1;
-                    self.active_connections
-                        .insert(connection_id, (ip_addr, true));
+                    self.active_connections.insert(connection_id, (addr, true));
                     self.manage_successful_connection(connection_id, reader, writer)?;
                 } else {
-                    debug!("out connection towards ip={} refused", ip_addr);
-                    massa_trace!("out_connection_refused", { "ip": ip_addr });
+                    debug!("out connection towards addr={} refused", addr);
+                    massa_trace!("out_connection_refused", { "address": addr });
                 }
             }
             Err(err) => {
                 debug!(
-                    "outgoing connection attempt towards ip={} failed: {}",
-                    ip_addr, err
+                    "outgoing connection attempt towards addr={} failed: {}",
+                    addr, err
                 );
                 massa_trace!("out_connection_attempt_failed", {
-                    "ip": ip_addr,
+                    "address": addr,
                     "err": err.to_string()
                 });
-                self.peer_info_db.out_connection_attempt_failed(&ip_addr)?;
+                self.peer_info_db.out_connection_attempt_failed(&addr)?;
             }
         }
         Ok(())
@@ -620,7 +751,7 @@ impl NetworkWorker {
         match res {
             Ok((reader, writer, remote_addr)) => {
                 match self.peer_info_db.try_new_in_connection(&remote_addr.ip()) {
-                    Ok(_) => {
+                    Ok(peer_addr) => {
                         let connection_id = *cur_connection_id;
                         debug!(
                             "inbound connection from addr={} succeeded => connection_id={}",
@@ -632,7 +763,7 @@ impl NetworkWorker {
                         });
                         cur_connection_id.0 += 1;
                         self.active_connections
-                            .insert(connection_id, (remote_addr.ip(), false));
+                            .insert(connection_id, (peer_addr, false));
                         self.manage_successful_connection(connection_id, reader, writer)?;
                     }
                     Err(NetworkError::PeerConnectionError(
@@ -675,6 +806,8 @@ impl NetworkWorker {
     /// In the `symmetric read & write` the current node simulate a handshake
     /// managed by the *connection node* in `HandshakeWorker::run()`, the
     /// current node send a `ListPeer` as a message.
+    /// As the version of the connecting node is unknown at this point,
+    /// the legacy format is used and only peers on the default port are sent.
     ///
     /// Spawn a future in `self.handshake_peer_list_futures` managed by the
     /// main loop.
@@ -689,7 +822,14 @@ impl NetworkWorker {
             {"address": remote_addr}
         );
         if self.cfg.max_in_connection_overflow > self.handshake_peer_list_futures.len() {
-            let msg = Message::PeerList(self.peer_info_db.get_advertisable_peer_ips());
+            let msg = Message::PeerList(
+                self.peer_info_db
+                    .get_advertisable_peer_addresses()
+                    .into_iter()
+                    .filter(|addr| addr.port() == self.cfg.protocol_port)
+                    .map(|addr| addr.ip())
+                    .collect(),
+            );
             let timeout = self.cfg.peer_list_send_timeout.to_duration();
             let max_bytes_read = self.cfg.max_bytes_read;
             let max_bytes_write = self.cfg.max_bytes_write;
@@ -769,6 +909,8 @@ impl NetworkWorker {
             connection_id,
             self.cfg.max_bytes_read,
             self.cfg.max_bytes_write,
+            self.cfg.capabilities,
+            self.message_compression.clone(),
         ));
         Ok(())
     }
@@ -809,6 +951,30 @@ impl NetworkWorker {
             NodeEvent(node, NodeEventType::ReceivedAskForOperations(operation_ids)) => {
                 event_impl::on_received_ask_for_operations(self, node, operation_ids).await
             }
+            NodeEvent(node, NodeEventType::ReceivedAskForFinalBalances(addresses)) => {
+                event_impl::on_received_ask_for_final_balances(self, node, addresses).await
+            }
+            NodeEvent(node, NodeEventType::ReceivedFinalBalances(proofs)) => {
+                event_impl::on_received_final_balances(self, node, proofs).await
+            }
+            NodeEvent(node, NodeEventType::ReceivedAskForLedgerRoot(slot)) => {
+                event_impl::on_received_ask_for_ledger_root(self, node, slot).await
+            }
+            NodeEvent(node, NodeEventType::ReceivedLedgerRoot(slot, root)) => {
+                event_impl::on_received_ledger_root(self, node, slot, root).await
+            }
+            NodeEvent(node, NodeEventType::ReceivedAskForCycleRolls(cycle)) => {
+                event_impl::on_received_ask_for_cycle_rolls(self, node, cycle).await
+            }
+            NodeEvent(node, NodeEventType::ReceivedCycleRolls(cycle, rolls)) => {
+                event_impl::on_received_cycle_rolls(self, node, cycle, rolls).await
+            }
+            NodeEvent(node, NodeEventType::ReceivedAskForOperationInclusion(operation_ids)) => {
+                event_impl::on_received_ask_for_operation_inclusion(self, node, operation_ids).await
+            }
+            NodeEvent(node, NodeEventType::ReceivedOperationInclusion(proofs)) => {
+                event_impl::on_received_operation_inclusion(self, node, proofs).await
+            }
         }
         Ok(())
     }
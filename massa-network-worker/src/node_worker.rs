@@ -5,6 +5,7 @@ use super::{
     messages::Message,
 };
 use itertools::Itertools;
+use massa_ledger_exports::LedgerProofs;
 use massa_logging::massa_trace;
 use massa_models::{
     config::{MAX_ENDORSEMENTS_PER_MESSAGE, NODE_SEND_CHANNEL_SIZE},
@@ -14,13 +15,14 @@ use massa_models::{
 use massa_network_exports::{
     ConnectionClosureReason, NetworkConfig, NetworkError, NodeCommand, NodeEvent, NodeEventType,
 };
+use std::net::SocketAddr;
 use tokio::{
     sync::mpsc,
     sync::mpsc::{
         error::{SendTimeoutError, TrySendError},
         Sender,
     },
-    time::timeout,
+    time::{timeout, Instant},
 };
 use tracing::{debug, trace, warn};
 
@@ -31,6 +33,8 @@ pub struct NodeWorker {
     cfg: NetworkConfig,
     /// Node id associated to that worker.
     node_id: NodeId,
+    /// True if the node advertises peers with their port (see `Version::supports_peer_ports`).
+    node_supports_peer_ports: bool,
     /// Reader for incoming data.
     socket_reader: ReadBinder,
     /// Optional writer to send data.
@@ -39,6 +43,8 @@ pub struct NodeWorker {
     node_command_rx: mpsc::Receiver<NodeCommand>,
     /// Channel to send node events.
     node_event_tx: mpsc::Sender<NodeEvent>,
+    /// When the last peer list accepted from the node was received.
+    last_peer_list_received: Option<Instant>,
 }
 
 impl NodeWorker {
@@ -47,6 +53,7 @@ impl NodeWorker {
     /// # Arguments
     /// * `cfg`: Network configuration.
     /// * `node_id`: Node id associated to that worker.
+    /// * `node_supports_peer_ports`: true if the node advertises peers with their port (see `Version::supports_peer_ports`).
     /// * `socket_reader`: Reader for incoming data.
     /// * `socket_writer`: Writer for sending data.
     /// * `node_command_rx`: Channel to receive node commands.
@@ -55,6 +62,7 @@ impl NodeWorker {
     pub fn new(
         cfg: NetworkConfig,
         node_id: NodeId,
+        node_supports_peer_ports: bool,
         socket_reader: ReadBinder,
         socket_writer: WriteBinder,
         node_command_rx: mpsc::Receiver<NodeCommand>,
@@ -63,10 +71,12 @@ impl NodeWorker {
         NodeWorker {
             cfg,
             node_id,
+            node_supports_peer_ports,
             socket_reader,
             socket_writer_opt: Some(socket_writer),
             node_command_rx,
             node_event_tx,
+            last_peer_list_received: None,
         }
     }
 
@@ -115,6 +125,39 @@ impl NodeWorker {
         }
     }
 
+    /// Builds the peer list message in a format the node understands.
+    /// Nodes that do not support peer ports only receive
+    /// the peers listening on the default protocol port.
+    fn peer_list_message(&self, addresses: Vec<SocketAddr>) -> Message {
+        if self.node_supports_peer_ports {
+            Message::PeerListWithPorts(addresses)
+        } else {
+            Message::PeerList(
+                addresses
+                    .into_iter()
+                    .filter(|addr| addr.port() == self.cfg.protocol_port)
+                    .map(|addr| addr.ip())
+                    .collect(),
+            )
+        }
+    }
+
+    /// Peer lists are only sent in answer to our asks, which happen every `ask_peer_list_interval`:
+    /// the ones received sooner after the previous accepted one are dropped.
+    ///
+    /// Returns true if the peer list received now must be processed.
+    fn accept_peer_list(&mut self) -> bool {
+        let now = Instant::now();
+        if let Some(last) = self.last_peer_list_received {
+            if now.saturating_duration_since(last) < self.cfg.ask_peer_list_interval.to_duration() {
+                massa_trace!("node_worker.run_loop. drop peer list over rate limit", {"node": self.node_id});
+                return false;
+            }
+        }
+        self.last_peer_list_received = Some(now);
+        true
+    }
+
     /// node event loop. Consumes self.
     pub async fn run_loop(mut self) -> Result<ConnectionClosureReason, NetworkError> {
         let (writer_command_tx, mut writer_command_rx) =
@@ -229,7 +272,17 @@ impl NodeWorker {
                             }
                             Message::PeerList(pl) =>  {
                                 massa_trace!("node_worker.run_loop. receive Message::PeerList", {"peerlist": pl, "node": self.node_id});
-                                self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedPeerList(pl))).await;
+                                if self.accept_peer_list() {
+                                    // legacy peer lists only contain peers listening on the default port
+                                    let pl = pl.into_iter().map(|ip| SocketAddr::new(ip, self.cfg.protocol_port)).collect();
+                                    self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedPeerList(pl))).await;
+                                }
+                            }
+                            Message::PeerListWithPorts(pl) =>  {
+                                massa_trace!("node_worker.run_loop. receive Message::PeerListWithPorts", {"peerlist": pl, "node": self.node_id});
+                                if self.accept_peer_list() {
+                                    self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedPeerList(pl))).await;
+                                }
                             }
                             Message::AskPeerList => {
                                 self.send_node_event(NodeEvent(self.node_id, NodeEventType::AskedPeerList)).await;
@@ -258,6 +311,38 @@ impl NodeWorker {
                                 massa_trace!("node_worker.run_loop. receive Message::Endorsement", {"node": self.node_id, "endorsements": endorsements});
                                 self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedEndorsements(endorsements))).await;
                             }
+                            Message::AskForFinalBalances(addresses) => {
+                                massa_trace!("node_worker.run_loop. receive Message::AskForFinalBalances", {"node": self.node_id, "addresses": addresses});
+                                self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedAskForFinalBalances(addresses))).await;
+                            }
+                            Message::FinalBalances(proofs) => {
+                                massa_trace!("node_worker.run_loop. receive Message::FinalBalances", {"node": self.node_id, "proofs": proofs});
+                                self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedFinalBalances(proofs))).await;
+                            }
+                            Message::AskForLedgerRoot(slot) => {
+                                massa_trace!("node_worker.run_loop. receive Message::AskForLedgerRoot", {"node": self.node_id, "slot": slot});
+                                self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedAskForLedgerRoot(slot))).await;
+                            }
+                            Message::LedgerRoot(slot, root) => {
+                                massa_trace!("node_worker.run_loop. receive Message::LedgerRoot", {"node": self.node_id, "slot": slot, "root": root});
+                                self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedLedgerRoot(slot, root))).await;
+                            }
+                            Message::AskForCycleRolls(cycle) => {
+                                massa_trace!("node_worker.run_loop. receive Message::AskForCycleRolls", {"node": self.node_id, "cycle": cycle});
+                                self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedAskForCycleRolls(cycle))).await;
+                            }
+                            Message::CycleRolls(cycle, rolls) => {
+                                massa_trace!("node_worker.run_loop. receive Message::CycleRolls", {"node": self.node_id, "cycle": cycle, "rolls": rolls});
+                                self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedCycleRolls(cycle, rolls))).await;
+                            }
+                            Message::AskForOperationInclusion(operation_ids) => {
+                                massa_trace!("node_worker.run_loop. receive Message::AskForOperationInclusion", {"node": self.node_id, "operation_ids": operation_ids});
+                                self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedAskForOperationInclusion(operation_ids))).await;
+                            }
+                            Message::OperationInclusion(proofs) => {
+                                massa_trace!("node_worker.run_loop. receive Message::OperationInclusion", {"node": self.node_id, "proofs": proofs});
+                                self.send_node_event(NodeEvent(self.node_id, NodeEventType::ReceivedOperationInclusion(proofs))).await;
+                            }
                             _ => {
                                 // TODO: Write a more user-friendly warning/logout after several consecutive fails? see #1082
                                 massa_trace!("node_worker.run_loop.self.socket_reader.next(). Unexpected message Warning", {});
@@ -282,9 +367,9 @@ impl NodeWorker {
                             exit_reason = r;
                             break;
                         },
-                        Some(NodeCommand::SendPeerList(ip_vec)) => {
-                            massa_trace!("node_worker.run_loop. send Message::PeerList", {"peerlist": ip_vec, "node": self.node_id});
-                            if self.try_send_to_node(&writer_command_tx, Message::PeerList(ip_vec)).is_err() {
+                        Some(NodeCommand::SendPeerList(addr_vec)) => {
+                            massa_trace!("node_worker.run_loop. send Message::PeerList", {"peerlist": addr_vec, "node": self.node_id});
+                            if self.try_send_to_node(&writer_command_tx, self.peer_list_message(addr_vec)).is_err() {
                                 break;
                             }
                         },
@@ -357,6 +442,68 @@ impl NodeWorker {
                                 }
                             }
                         },
+                        Some(NodeCommand::AskForFinalBalances(addresses)) => {
+                            massa_trace!("node_worker.run_loop. send Message::AskForFinalBalances", {"node": self.node_id, "addresses": addresses});
+                            for to_send_list in addresses.chunks(self.cfg.max_ask_blocks as usize) {
+                                if self.try_send_to_node(&writer_command_tx, Message::AskForFinalBalances(to_send_list.to_vec())).is_err() {
+                                    break 'select_loop;
+                                }
+                            }
+                        },
+                        Some(NodeCommand::SendFinalBalances(proofs)) => {
+                            massa_trace!("node_worker.run_loop. send Message::FinalBalances", {"node": self.node_id, "proofs": proofs});
+                            // every chunk carries the slot and root its proofs are checked against
+                            for to_send_list in proofs.proofs.chunks(self.cfg.max_ask_blocks as usize) {
+                                let chunk = LedgerProofs {
+                                    slot: proofs.slot,
+                                    root: proofs.root,
+                                    proofs: to_send_list.to_vec(),
+                                };
+                                if self.try_send_to_node(&writer_command_tx, Message::FinalBalances(chunk)).is_err() {
+                                    break 'select_loop;
+                                }
+                            }
+                        },
+                        Some(NodeCommand::AskForLedgerRoot(slot)) => {
+                            massa_trace!("node_worker.run_loop. send Message::AskForLedgerRoot", {"node": self.node_id, "slot": slot});
+                            if self.try_send_to_node(&writer_command_tx, Message::AskForLedgerRoot(slot)).is_err() {
+                                break 'select_loop;
+                            }
+                        },
+                        Some(NodeCommand::SendLedgerRoot(slot, root)) => {
+                            massa_trace!("node_worker.run_loop. send Message::LedgerRoot", {"node": self.node_id, "slot": slot, "root": root});
+                            if self.try_send_to_node(&writer_command_tx, Message::LedgerRoot(slot, root)).is_err() {
+                                break 'select_loop;
+                            }
+                        },
+                        Some(NodeCommand::AskForCycleRolls(cycle)) => {
+                            massa_trace!("node_worker.run_loop. send Message::AskForCycleRolls", {"node": self.node_id, "cycle": cycle});
+                            if self.try_send_to_node(&writer_command_tx, Message::AskForCycleRolls(cycle)).is_err() {
+                                break 'select_loop;
+                            }
+                        },
+                        Some(NodeCommand::SendCycleRolls(cycle, rolls)) => {
+                            massa_trace!("node_worker.run_loop. send Message::CycleRolls", {"node": self.node_id, "cycle": cycle, "rolls": rolls});
+                            if self.try_send_to_node(&writer_command_tx, Message::CycleRolls(cycle, rolls)).is_err() {
+                                break 'select_loop;
+                            }
+                        },
+                        Some(NodeCommand::AskForOperationInclusion(operation_ids)) => {
+                            massa_trace!("node_worker.run_loop. send Message::AskForOperationInclusion", {"node": self.node_id, "operation_ids": operation_ids});
+                            for to_send_list in operation_ids.chunks(self.cfg.max_ask_blocks as usize) {
+                                if self.try_send_to_node(&writer_command_tx, Message::AskForOperationInclusion(to_send_list.to_vec())).is_err() {
+                                    break 'select_loop;
+                                }
+                            }
+                        },
+                        Some(NodeCommand::SendOperationInclusion(proofs)) => {
+                            massa_trace!("node_worker.run_loop. send Message::OperationInclusion", {"node": self.node_id, "proofs": proofs});
+                            for to_send_list in proofs.chunks(self.cfg.max_ask_blocks as usize) {
+                                if self.try_send_to_node(&writer_command_tx, Message::OperationInclusion(to_send_list.to_vec())).is_err() {
+                                    break 'select_loop;
+                                }
+                            }
+                        },
                         None => {
                             // Note: this should never happen,
                             // since it implies the network worker dropped its node command sender
@@ -3,39 +3,44 @@
 use enum_map::EnumMap;
 use itertools::Itertools;
 use massa_logging::massa_trace;
+use massa_models::node::BanReason;
 use massa_network_exports::settings::PeerTypeConnectionConfig;
 use massa_network_exports::ConnectionCount;
 use massa_network_exports::NetworkConfig;
 use massa_network_exports::NetworkConnectionErrorType;
 use massa_network_exports::NetworkError;
+use massa_network_exports::PeerBan;
 use massa_network_exports::PeerInfo;
+use massa_network_exports::PeerScoreEvent;
 use massa_network_exports::PeerType;
 use massa_time::MassaTime;
 use serde_json::json;
 use std::cmp::Reverse;
-use std::collections::HashMap;
-use std::net::IpAddr;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::path::Path;
 use tokio::sync::watch;
 use tokio::task::JoinHandle;
 use tokio::time::{sleep, Duration};
-use tracing::{trace, warn};
+use tracing::{info, trace, warn};
 /// Contains all information about every peers we know about.
 pub struct PeerInfoDatabase {
     /// Network configuration.
     pub(crate) network_settings: NetworkConfig,
-    /// Maps an ip address to peer's info
-    pub peers: HashMap<IpAddr, PeerInfo>,
+    /// Maps a peer address (ip and listening port) to peer's info
+    pub peers: HashMap<SocketAddr, PeerInfo>,
     /// Handle on the task managing the dump
     pub(crate) saver_join_handle: JoinHandle<()>,
     /// Monitor changed peers.
-    pub(crate) saver_watch_tx: watch::Sender<HashMap<IpAddr, PeerInfo>>,
+    pub(crate) saver_watch_tx: watch::Sender<HashMap<SocketAddr, PeerInfo>>,
     /// Connections count for each `PeerType`
     pub(crate) peer_types_connection_count: EnumMap<PeerType, ConnectionCount>,
     /// Every `wakeup_interval` we try to establish a connection with known inactive peers
     pub(crate) wakeup_interval: MassaTime,
     /// Clock compensation.
     pub(crate) clock_compensation: i64,
+    /// Start of the current reward window of each peer, and number of rewards applied in it
+    pub(crate) reward_windows: HashMap<SocketAddr, (MassaTime, u32)>,
 }
 
 /// Saves advertised and non standard peers to a file.
@@ -44,7 +49,7 @@ pub struct PeerInfoDatabase {
 /// * `peers`: peers to save
 /// * `file_path`: path to the file
 async fn dump_peers(
-    peers: &HashMap<IpAddr, PeerInfo>,
+    peers: &HashMap<SocketAddr, PeerInfo>,
     file_path: &Path,
 ) -> Result<(), NetworkError> {
     let peer_vec: Vec<_> = peers
@@ -52,12 +57,16 @@ async fn dump_peers(
         .filter(|v| v.advertised || v.peer_type != PeerType::Standard || v.banned)
         .map(|peer| {
             json!({
-                "ip": peer.ip,
+                "address": peer.address,
                 "banned": peer.banned,
+                "ban": peer.ban,
                 "peer_type": peer.peer_type,
                 "last_alive": peer.last_alive,
                 "last_failure": peer.last_failure,
                 "advertised": peer.advertised,
+                "score": peer.score,
+                "last_score_update": peer.last_score_update,
+                "anchor": peer.anchor,
             })
         })
         .collect();
@@ -67,6 +76,29 @@ async fn dump_peers(
     Ok(())
 }
 
+/// Returns the address with its IP canonicalized
+#[inline]
+fn canonical_address(address: &SocketAddr) -> SocketAddr {
+    SocketAddr::new(address.ip().to_canonical(), address.port())
+}
+
+/// Returns the network group of an IP: its IPv4 /16 or IPv6 /32 prefix.
+///
+/// Outgoing connections to standard peers are spread over network groups
+/// so that a single operator cannot easily take over all our outgoing slots.
+pub(crate) fn network_group(ip: &IpAddr) -> IpAddr {
+    match ip.to_canonical() {
+        IpAddr::V4(ip) => {
+            let [a, b, _, _] = ip.octets();
+            IpAddr::V4(Ipv4Addr::new(a, b, 0, 0))
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            IpAddr::V6(Ipv6Addr::new(segments[0], segments[1], 0, 0, 0, 0, 0, 0))
+        }
+    }
+}
+
 /// Cleans up the peer database using max values
 /// provided by `NetworkConfig.ProtocolConfig`.
 /// If `opt_new_peers` is provided, adds its contents as well.
@@ -78,11 +110,11 @@ async fn dump_peers(
 /// * `peers`: peers to clean up
 /// * `opt_new_peers`: optional peers to add to the database
 /// * `clock_compensation`: to be sync with server time
-/// * `ban_timeout`: after that time we forget we banned a peer
+/// * `ban_timeout`: after that time we forget we banned a peer that has no ban record
 pub(crate) fn cleanup_peers(
     cfg: &NetworkConfig,
-    peers: &mut HashMap<IpAddr, PeerInfo>,
-    opt_new_peers: Option<&Vec<IpAddr>>,
+    peers: &mut HashMap<SocketAddr, PeerInfo>,
+    opt_new_peers: Option<&Vec<SocketAddr>>,
     clock_compensation: i64,
     ban_timeout: MassaTime,
 ) -> Result<(), NetworkError> {
@@ -90,28 +122,26 @@ pub(crate) fn cleanup_peers(
     let mut res_new_peers: Vec<PeerInfo> = if let Some(new_peers) = opt_new_peers {
         new_peers
             .iter()
-            .map(|ip| ip.to_canonical())
+            .map(canonical_address)
             .unique()
-            .filter(|&ip| {
-                if let Some(mut p) = peers.get_mut(&ip) {
-                    // avoid already-known IPs, but mark them as advertised
+            .filter(|&address| {
+                if let Some(mut p) = peers.get_mut(&address) {
+                    // avoid already-known addresses, but mark them as advertised
                     p.advertised = true;
                     return false;
                 }
-                if !ip.is_global() {
-                    // avoid non-global IPs
+                if !address.ip().is_global() || address.port() == 0 {
+                    // avoid non-global IPs and invalid ports
                     return false;
                 }
-                if let Some(our_ip) = cfg.routable_ip {
-                    // avoid our own IP
-                    if ip == our_ip.to_canonical() {
-                        return false;
-                    }
+                if Some(address) == cfg.routable_address() {
+                    // avoid our own address
+                    return false;
                 }
                 true
             })
             .take(cfg.max_peer_advertise_length as usize)
-            .map(|ip| PeerInfo::new(ip, true))
+            .map(|address| PeerInfo::new(address, true))
             .collect()
     } else {
         Vec::new()
@@ -121,21 +151,26 @@ pub(crate) fn cleanup_peers(
     // inactive banned peers (banned_peers)
     // and other inactive but advertised peers (idle_peers)
     // drop other peers (inactive non-advertised, non-keep)
+    let now = MassaTime::now(clock_compensation)?;
+    let ban_limit = now.saturating_sub(ban_timeout);
     let mut keep_peers: Vec<PeerInfo> = Vec::new();
     let mut banned_peers: Vec<PeerInfo> = Vec::new();
     let mut idle_peers: Vec<PeerInfo> = Vec::new();
-    for (ip, p) in peers.drain() {
-        if !ip.is_global() {
+    for (address, mut p) in peers.drain() {
+        if !address.ip().is_global() {
             // avoid non-global IPs
             continue;
         }
-        if let Some(our_ip) = cfg.routable_ip {
-            // avoid our own IP
-            if ip == our_ip.to_canonical() {
-                continue;
-            }
+        if Some(address) == cfg.routable_address() {
+            // avoid our own address
+            continue;
+        }
+        // lift expired bans
+        if p.ban.map_or(false, |ban| ban.expires_at <= now) {
+            p.banned = false;
+            p.ban = None;
         }
-        if p.peer_type != Default::default() || p.is_active() {
+        if p.peer_type != Default::default() || p.is_active() || (p.anchor && !p.banned) {
             keep_peers.push(p);
         } else if p.banned {
             banned_peers.push(p);
@@ -153,16 +188,15 @@ pub(crate) fn cleanup_peers(
     idle_peers.truncate(cfg.max_idle_peers);
 
     // sort and truncate inactive banned peers
-    // forget about old banned peers
-    let ban_limit = MassaTime::now(clock_compensation)?.saturating_sub(ban_timeout);
-    banned_peers.retain(|p| p.last_failure.map_or(false, |v| v >= ban_limit));
+    // forget about old banned peers that have no ban record
+    banned_peers.retain(|p| p.ban.is_some() || p.last_failure.map_or(false, |v| v >= ban_limit));
     banned_peers.sort_unstable_by_key(|&p| (std::cmp::Reverse(p.last_failure), p.last_alive));
     banned_peers.truncate(cfg.max_banned_peers);
 
     // gather everything back
-    peers.extend(keep_peers.into_iter().map(|p| (p.ip, p)));
-    peers.extend(banned_peers.into_iter().map(|p| (p.ip, p)));
-    peers.extend(idle_peers.into_iter().map(|p| (p.ip, p)));
+    peers.extend(keep_peers.into_iter().map(|p| (p.address, p)));
+    peers.extend(banned_peers.into_iter().map(|p| (p.address, p)));
+    peers.extend(idle_peers.into_iter().map(|p| (p.address, p)));
     Ok(())
 }
 
@@ -183,10 +217,10 @@ impl PeerInfoDatabase {
         )?
         .into_iter()
         .map(|mut p| {
-            p.cleanup();
-            (p.ip, p)
+            p.cleanup(cfg.protocol_port);
+            (p.address, p)
         })
-        .collect::<HashMap<IpAddr, PeerInfo>>();
+        .collect::<HashMap<SocketAddr, PeerInfo>>();
         if cfg.peers_file.is_file() {
             peers.extend(
                 // previously known peers
@@ -195,8 +229,8 @@ impl PeerInfoDatabase {
                 )?
                 .into_iter()
                 .map(|mut p| {
-                    p.cleanup();
-                    (p.ip, p)
+                    p.cleanup(cfg.protocol_port);
+                    (p.address, p)
                 }),
             );
         }
@@ -244,12 +278,14 @@ impl PeerInfoDatabase {
             wakeup_interval,
             clock_compensation,
             peer_types_connection_count: EnumMap::default(),
+            reward_windows: Default::default(),
         })
     }
 
-    /// Cleanly closes `peerInfoDatabase`, performing one last peer dump.
+    /// Cleanly closes `peerInfoDatabase`, marking anchor peers and performing one last peer dump.
     /// A warning is raised on dump failure.
-    pub async fn stop(self) -> Result<(), NetworkError> {
+    pub async fn stop(mut self) -> Result<(), NetworkError> {
+        self.mark_anchor_peers()?;
         drop(self.saver_watch_tx);
         self.saver_join_handle.await?;
         if let Err(e) = dump_peers(&self.peers, &self.network_settings.peers_file).await {
@@ -300,6 +336,9 @@ impl PeerInfoDatabase {
             self.clock_compensation,
             self.network_settings.ban_timeout,
         )?;
+        let peers = &self.peers;
+        self.reward_windows
+            .retain(|address, _| peers.contains_key(address));
         Ok(())
     }
 
@@ -319,7 +358,7 @@ impl PeerInfoDatabase {
     ///
     /// # Argument
     /// `new_peers`: peers we are trying to merge
-    pub fn merge_candidate_peers(&mut self, new_peers: &[IpAddr]) -> Result<(), NetworkError> {
+    pub fn merge_candidate_peers(&mut self, new_peers: &[SocketAddr]) -> Result<(), NetworkError> {
         if new_peers.is_empty() {
             return Ok(());
         }
@@ -337,14 +376,32 @@ impl PeerInfoDatabase {
     // high level peer management //
     ////////////////////////////////
 
-    /// Unban a list of ip
+    /// Returns the known addresses of the peers behind an ip.
+    /// If there are none, returns the address of that ip on the default protocol port.
+    pub fn get_addresses_for_ip(&self, ip: &IpAddr) -> Vec<SocketAddr> {
+        let ip = ip.to_canonical();
+        let addresses: Vec<SocketAddr> = self
+            .peers
+            .keys()
+            .filter(|address| address.ip() == ip)
+            .copied()
+            .collect();
+        if addresses.is_empty() {
+            vec![SocketAddr::new(ip, self.network_settings.protocol_port)]
+        } else {
+            addresses
+        }
+    }
+
+    /// Unban a list of ip, along with all the peers behind them
     pub fn unban(&mut self, ips: Vec<IpAddr>) -> Result<(), NetworkError> {
         let mut update_happened = false;
         for ip in ips.into_iter() {
             let ip = ip.to_canonical();
-            if let Some(peer) = self.peers.get_mut(&ip) {
+            for peer in self.peers.values_mut().filter(|p| p.address.ip() == ip) {
                 update_happened = update_happened || peer.banned;
                 peer.banned = false;
+                peer.ban = None;
             }
         }
         self.update()?;
@@ -355,9 +412,12 @@ impl PeerInfoDatabase {
     }
 
     pub async fn whitelist(&mut self, ips: Vec<IpAddr>) -> Result<(), NetworkError> {
-        for ip in ips.into_iter() {
-            let ip = ip.to_canonical();
-            let old_pt = if let Some(peer) = self.peers.get_mut(&ip) {
+        let addresses: Vec<SocketAddr> = ips
+            .iter()
+            .flat_map(|ip| self.get_addresses_for_ip(ip))
+            .collect();
+        for address in addresses.into_iter() {
+            let old_pt = if let Some(peer) = self.peers.get_mut(&address) {
                 let pt = peer.peer_type;
                 if pt == PeerType::WhiteListed {
                     continue;
@@ -365,26 +425,26 @@ impl PeerInfoDatabase {
                 peer.peer_type = PeerType::WhiteListed;
                 pt
             } else {
-                let mut p = PeerInfo::new(ip, false);
+                let mut p = PeerInfo::new(address, false);
                 p.peer_type = PeerType::WhiteListed;
-                self.peers.insert(ip, p);
+                self.peers.insert(address, p);
                 continue;
             };
             // update global connection counts by peer type
-            let peer = *self.peers.get(&ip).unwrap(); // filled just before
+            let peer = *self.peers.get(&address).unwrap(); // filled just before
             if peer.active_out_connection_attempts > 0 {
-                self.decrease_global_active_out_connection_attempt_count(old_pt, &ip)?;
+                self.decrease_global_active_out_connection_attempt_count(old_pt, &address)?;
                 self.increase_global_active_out_connection_attempt_count(
                     PeerType::WhiteListed,
-                    &ip,
+                    &address,
                 )?
             }
             if peer.active_out_connections > 0 {
-                self.decrease_global_active_out_connection_count(old_pt, &ip)?;
+                self.decrease_global_active_out_connection_count(old_pt, &address)?;
                 self.increase_global_active_out_connection_count(PeerType::WhiteListed)?
             }
             if peer.active_in_connections > 0 {
-                self.decrease_global_active_in_connection_count(old_pt, &ip)?;
+                self.decrease_global_active_in_connection_count(old_pt, &address)?;
                 self.increase_global_active_in_connection_count(PeerType::WhiteListed)?
             }
         }
@@ -392,9 +452,12 @@ impl PeerInfoDatabase {
     }
 
     pub async fn remove_from_whitelist(&mut self, ips: Vec<IpAddr>) -> Result<(), NetworkError> {
-        for ip in ips.into_iter() {
-            let ip = ip.to_canonical();
-            let old_pt = if let Some(peer) = self.peers.get_mut(&ip) {
+        let addresses: Vec<SocketAddr> = ips
+            .iter()
+            .flat_map(|ip| self.get_addresses_for_ip(ip))
+            .collect();
+        for address in addresses.into_iter() {
+            let old_pt = if let Some(peer) = self.peers.get_mut(&address) {
                 let old = peer.peer_type;
                 peer.peer_type = Default::default();
                 old
@@ -405,20 +468,20 @@ impl PeerInfoDatabase {
             if old_pt != Default::default() {
                 // update global connection counts by peer type
                 // as the peer isn't whitelist anymore
-                let peer = *self.peers.get(&ip).unwrap(); // filled just before
+                let peer = *self.peers.get(&address).unwrap(); // filled just before
                 if peer.active_out_connection_attempts > 0 {
-                    self.decrease_global_active_out_connection_attempt_count(old_pt, &ip)?;
+                    self.decrease_global_active_out_connection_attempt_count(old_pt, &address)?;
                     self.increase_global_active_out_connection_attempt_count(
                         Default::default(),
-                        &ip,
+                        &address,
                     )?
                 }
                 if peer.active_out_connections > 0 {
-                    self.decrease_global_active_out_connection_count(old_pt, &ip)?;
+                    self.decrease_global_active_out_connection_count(old_pt, &address)?;
                     self.increase_global_active_out_connection_count(Default::default())?
                 }
                 if peer.active_in_connections > 0 {
-                    self.decrease_global_active_in_connection_count(old_pt, &ip)?;
+                    self.decrease_global_active_in_connection_count(old_pt, &address)?;
                     self.increase_global_active_in_connection_count(Default::default())?
                 }
             }
@@ -426,37 +489,37 @@ impl PeerInfoDatabase {
         self.update()
     }
 
-    /// Acknowledges a new out connection attempt to ip.
+    /// Acknowledges a new out connection attempt to address.
     ///
     /// # Argument
-    /// `ip`: `IpAddr` we are now connected to
-    pub fn new_out_connection_attempt(&mut self, ip: &IpAddr) -> Result<(), NetworkError> {
-        let ip = ip.to_canonical();
-        if !ip.is_global() {
-            return Err(NetworkError::InvalidIpError(ip));
+    /// `address`: `SocketAddr` we are now connected to
+    pub fn new_out_connection_attempt(&mut self, address: &SocketAddr) -> Result<(), NetworkError> {
+        let address = canonical_address(address);
+        if !address.ip().is_global() {
+            return Err(NetworkError::InvalidIpError(address.ip()));
         }
-        let peer_type = if let Some(peer) = self.peers.get(&ip) {
+        let peer_type = if let Some(peer) = self.peers.get(&address) {
             if self.can_try_new_out_connection(peer.peer_type) {
                 // Can unwrap because we checked above that there is a peer.
-                let peer = self.peers.get_mut(&ip).unwrap();
+                let peer = self.peers.get_mut(&address).unwrap();
                 peer.active_out_connection_attempts += 1;
                 Ok(peer.peer_type)
             } else {
                 Err(NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::TooManyConnectionAttempts(ip),
+                    NetworkConnectionErrorType::TooManyConnectionAttempts(address),
                 ))
             }
         } else if self.can_try_new_out_connection(Default::default()) {
-            let mut peer = PeerInfo::new(ip, false);
+            let mut peer = PeerInfo::new(address, false);
             peer.active_out_connection_attempts += 1;
-            self.peers.insert(ip, peer);
+            self.peers.insert(address, peer);
             Ok(peer.peer_type)
         } else {
             Err(NetworkError::PeerConnectionError(
-                NetworkConnectionErrorType::TooManyConnectionAttempts(ip),
+                NetworkConnectionErrorType::TooManyConnectionAttempts(address),
             ))
         }?;
-        self.increase_global_active_out_connection_attempt_count(peer_type, &ip)?;
+        self.increase_global_active_out_connection_attempt_count(peer_type, &address)?;
         self.update()
     }
 
@@ -464,14 +527,14 @@ impl PeerInfoDatabase {
     /// Requests a subsequent dump.
     ///
     /// # Argument
-    /// * ip : ip address of the considered peer.
-    pub fn peer_alive(&mut self, ip: &IpAddr) -> Result<(), NetworkError> {
-        let ip = ip.to_canonical();
+    /// * address : address of the considered peer.
+    pub fn peer_alive(&mut self, address: &SocketAddr) -> Result<(), NetworkError> {
+        let address = canonical_address(address);
         self.peers
-            .get_mut(&ip)
+            .get_mut(&address)
             .ok_or({
                 NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
+                    NetworkConnectionErrorType::PeerInfoNotFoundError(address),
                 )
             })?
             .last_alive = Some(MassaTime::now(self.clock_compensation)?);
@@ -482,33 +545,53 @@ impl PeerInfoDatabase {
     /// Requests a dump.
     ///
     /// # Argument
-    /// * ip : ip address of the considered peer.
-    pub fn peer_failed(&mut self, ip: &IpAddr) -> Result<(), NetworkError> {
-        let ip = ip.to_canonical();
+    /// * address : address of the considered peer.
+    pub fn peer_failed(&mut self, address: &SocketAddr) -> Result<(), NetworkError> {
+        let address = canonical_address(address);
         self.peers
-            .get_mut(&ip)
+            .get_mut(&address)
             .ok_or({
                 NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
+                    NetworkConnectionErrorType::PeerInfoNotFoundError(address),
                 )
             })?
             .last_failure = Some(MassaTime::now(self.clock_compensation)?);
         self.request_dump()
     }
 
-    /// Sets that the peer is banned now.
+    /// Sets that the peer is banned now, replacing any previous ban record.
     /// If the peer is not active, the database is cleaned up.
     /// A dump is requested.
     ///
     /// # Argument
-    /// * ip : ip address of the considered peer.
-    pub fn peer_banned(&mut self, ip: &IpAddr) -> Result<(), NetworkError> {
-        let ip = ip.to_canonical();
+    /// * address : address of the considered peer.
+    /// * reason : why the peer is banned.
+    /// * duration : ban duration, `ban_timeout` if none.
+    pub fn peer_banned(
+        &mut self,
+        address: &SocketAddr,
+        reason: BanReason,
+        duration: Option<MassaTime>,
+    ) -> Result<(), NetworkError> {
+        let address = canonical_address(address);
+        let now = MassaTime::now(self.clock_compensation)?;
+        let expires_at = now.saturating_add(duration.unwrap_or(self.network_settings.ban_timeout));
+        info!(
+            "banning peer {} until {}: {}",
+            address,
+            expires_at.to_utc_string(),
+            reason
+        );
         let peer = self
             .peers
-            .entry(ip)
-            .or_insert_with(|| PeerInfo::new(ip, false));
-        peer.last_failure = Some(MassaTime::now(self.clock_compensation)?);
+            .entry(address)
+            .or_insert_with(|| PeerInfo::new(address, false));
+        peer.last_failure = Some(now);
+        peer.ban = Some(PeerBan {
+            reason,
+            banned_at: now,
+            expires_at,
+        });
         if !peer.banned {
             peer.banned = true;
             if !peer.is_active() {
@@ -524,25 +607,25 @@ impl PeerInfoDatabase {
     /// peers are cleaned up and a dump is requested
     ///
     /// # Argument
-    /// * ip : ip address of the considered peer.
-    pub fn out_connection_closed(&mut self, ip: &IpAddr) -> Result<(), NetworkError> {
-        let ip = ip.to_canonical();
+    /// * address : address of the considered peer.
+    pub fn out_connection_closed(&mut self, address: &SocketAddr) -> Result<(), NetworkError> {
+        let address = canonical_address(address);
         let peer_type = {
-            let peer = self.peers.get(&ip).ok_or({
+            let peer = self.peers.get(&address).ok_or({
                 NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
+                    NetworkConnectionErrorType::PeerInfoNotFoundError(address),
                 )
             })?;
             if peer.active_out_connections == 0
                 || !self.can_remove_active_out_connection_count(peer.peer_type)
             {
                 return Err(NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(ip),
+                    NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(address),
                 ));
             }
-            let peer = self.peers.get_mut(&ip).ok_or({
+            let peer = self.peers.get_mut(&address).ok_or({
                 NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
+                    NetworkConnectionErrorType::PeerInfoNotFoundError(address),
                 )
             })?;
             peer.active_out_connections -= 1;
@@ -553,7 +636,7 @@ impl PeerInfoDatabase {
             }
             peer_type
         };
-        self.decrease_global_active_out_connection_count(peer_type, &ip)?;
+        self.decrease_global_active_out_connection_count(peer_type, &address)?;
         Ok(())
     }
 
@@ -563,25 +646,25 @@ impl PeerInfoDatabase {
     /// peers are cleaned up and a dump is requested.
     ///
     /// # Argument
-    /// * ip : ip address of the considered peer.
-    pub fn in_connection_closed(&mut self, ip: &IpAddr) -> Result<(), NetworkError> {
-        let ip = ip.to_canonical();
+    /// * address : address of the considered peer.
+    pub fn in_connection_closed(&mut self, address: &SocketAddr) -> Result<(), NetworkError> {
+        let address = canonical_address(address);
         let peer_type = {
-            let peer = self.peers.get(&ip).ok_or({
+            let peer = self.peers.get(&address).ok_or({
                 NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
+                    NetworkConnectionErrorType::PeerInfoNotFoundError(address),
                 )
             })?;
             if peer.active_in_connections == 0
                 || !self.can_decrease_global_active_in_connection_count(peer.peer_type)
             {
                 return Err(NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(ip),
+                    NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(address),
                 ));
             }
-            let peer = self.peers.get_mut(&ip).ok_or({
+            let peer = self.peers.get_mut(&address).ok_or({
                 NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
+                    NetworkConnectionErrorType::PeerInfoNotFoundError(address),
                 )
             })?;
             peer.active_in_connections -= 1;
@@ -593,7 +676,7 @@ impl PeerInfoDatabase {
             peer_type
         };
 
-        self.decrease_global_active_in_connection_count(peer_type, &ip)?;
+        self.decrease_global_active_in_connection_count(peer_type, &address)?;
         Ok(())
     }
 
@@ -604,16 +687,18 @@ impl PeerInfoDatabase {
     /// A dump is requested.
     ///
     /// # Argument
-    /// * ip : ip address of the considered peer.
+    /// * address : address of the considered peer.
     pub fn try_out_connection_attempt_success(
         &mut self,
-        ip: &IpAddr,
+        address: &SocketAddr,
     ) -> Result<bool, NetworkError> {
-        let ip = ip.to_canonical();
+        let address = canonical_address(address);
         // a connection attempt succeeded
         // remove out connection attempt and add out connection
-        let peer_type = self.get_peer_type(&ip).ok_or({
-            NetworkError::PeerConnectionError(NetworkConnectionErrorType::PeerInfoNotFoundError(ip))
+        let peer_type = self.get_peer_type(&address).ok_or({
+            NetworkError::PeerConnectionError(NetworkConnectionErrorType::PeerInfoNotFoundError(
+                address,
+            ))
         })?;
 
         // have we reached target yet ?
@@ -621,28 +706,31 @@ impl PeerInfoDatabase {
             return Ok(false);
         }
 
-        self.decrease_global_active_out_connection_attempt_count(peer_type, &ip)?;
+        self.decrease_global_active_out_connection_attempt_count(peer_type, &address)?;
+
+        // bans apply to every peer behind that ip
+        let ip_banned = self.get_banned_ips().contains(&address.ip());
 
         let peer_type = {
-            let peer = self.peers.get(&ip).ok_or({
+            let peer = self.peers.get(&address).ok_or({
                 NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
+                    NetworkConnectionErrorType::PeerInfoNotFoundError(address),
                 )
             })?;
             if peer.active_out_connection_attempts == 0 {
                 return Err(NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::TooManyConnectionAttempts(ip),
+                    NetworkConnectionErrorType::TooManyConnectionAttempts(address),
                 ));
             }
-            let peer = self.peers.get_mut(&ip).ok_or({
+            let peer = self.peers.get_mut(&address).ok_or({
                 NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
+                    NetworkConnectionErrorType::PeerInfoNotFoundError(address),
                 )
             })?;
             peer.active_out_connection_attempts -= 1;
             peer.advertised = true; // we just connected to it. Assume advertised.
 
-            if peer.banned {
+            if ip_banned {
                 peer.last_failure = Some(MassaTime::now(self.clock_compensation)?);
                 if !peer.is_active() && peer.peer_type == Default::default() {
                     self.update()?;
@@ -651,6 +739,7 @@ impl PeerInfoDatabase {
                 return Ok(false);
             }
             peer.active_out_connections += 1;
+            peer.anchor = false;
             peer.peer_type
         };
         self.increase_global_active_out_connection_count(peer_type)?;
@@ -663,55 +752,63 @@ impl PeerInfoDatabase {
     /// A dump is requested.
     ///
     /// # Argument
-    /// * ip : ip address of the considered peer.
-    pub fn out_connection_attempt_failed(&mut self, ip: &IpAddr) -> Result<(), NetworkError> {
-        let ip = ip.to_canonical();
+    /// * address : address of the considered peer.
+    pub fn out_connection_attempt_failed(
+        &mut self,
+        address: &SocketAddr,
+    ) -> Result<(), NetworkError> {
+        let address = canonical_address(address);
         let peer_type = {
             let peer = self
                 .peers
-                .get(&ip)
+                .get(&address)
                 .ok_or(NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
+                    NetworkConnectionErrorType::PeerInfoNotFoundError(address),
                 ))?;
             if peer.active_out_connection_attempts == 0
                 || !self.can_remove_new_out_connection_attempt(peer.peer_type)
             {
                 return Err(NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::TooManyConnectionFailure(ip),
+                    NetworkConnectionErrorType::TooManyConnectionFailure(address),
                 ));
             }
             let peer = self
                 .peers
-                .get_mut(&ip)
+                .get_mut(&address)
                 .ok_or(NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
+                    NetworkConnectionErrorType::PeerInfoNotFoundError(address),
                 ))?;
             peer.active_out_connection_attempts -= 1;
             peer.last_failure = Some(MassaTime::now(self.clock_compensation)?);
+            peer.anchor = false;
             let pt = peer.peer_type;
             if !peer.is_active() && peer.peer_type == PeerType::Standard {
                 self.update()?;
             }
             pt
         };
-        self.decrease_global_active_out_connection_attempt_count(peer_type, &ip)?;
+        self.decrease_global_active_out_connection_attempt_count(peer_type, &address)?;
         self.request_dump()
     }
 
     /// An ip has successfully connected to us.
-    /// returns true if some in slots for connections are left.
+    /// Returns the peer address the connection is accounted under if some in slots
+    /// for connections are left.
+    /// The remote port of an inbound connection is ephemeral: the connection is accounted
+    /// under the ip with the default protocol port, and limits apply to the whole ip.
     /// If the corresponding peer exists, it is updated,
     /// otherwise it is created (not advertised).
     /// A dump is requested.
     ///
     /// # Argument
     /// * ip : ip address of the considered peer.
-    pub fn try_new_in_connection(&mut self, ip: &IpAddr) -> Result<(), NetworkError> {
+    pub fn try_new_in_connection(&mut self, ip: &IpAddr) -> Result<SocketAddr, NetworkError> {
         let ip = ip.to_canonical();
+        let address = SocketAddr::new(ip, self.network_settings.protocol_port);
         // try to create a new input connection, return false if no slots
         if !ip.is_global() || self.network_settings.max_in_connections_per_ip == 0 {
             return Err(NetworkError::PeerConnectionError(
-                NetworkConnectionErrorType::MaxPeersConnectionReached(ip),
+                NetworkConnectionErrorType::MaxPeersConnectionReached(address),
             ));
         }
         if let Some(our_ip) = self.network_settings.routable_ip {
@@ -726,37 +823,45 @@ impl PeerInfoDatabase {
 
         let peer_type = self
             .peers
-            .entry(ip)
-            .or_insert_with(|| PeerInfo::new(ip, false))
+            .entry(address)
+            .or_insert_with(|| PeerInfo::new(address, false))
             .peer_type;
 
         // we need to first check if there is a global slot available
         if self.is_max_in_connection_count_reached(peer_type) {
             return Err(NetworkError::PeerConnectionError(
-                NetworkConnectionErrorType::MaxPeersConnectionReached(ip),
+                NetworkConnectionErrorType::MaxPeersConnectionReached(address),
             ));
         }
 
+        // bans and the in connection limit apply to every peer behind that ip
+        let (ip_banned, ip_in_connections) = self
+            .peers
+            .values()
+            .filter(|p| p.address.ip() == ip)
+            .fold((false, 0), |(banned, count), p| {
+                (banned || p.banned, count + p.active_in_connections)
+            });
+
         let peer_type = {
-            let peer = self.peers.get_mut(&ip).ok_or({
+            let peer = self.peers.get_mut(&address).ok_or({
                 NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::PeerInfoNotFoundError(ip),
+                    NetworkConnectionErrorType::PeerInfoNotFoundError(address),
                 )
             })?; // peer was inserted just before
 
             // is there a attempt slot available
-            if peer.banned {
-                massa_trace!("in_connection_refused_peer_banned", {"ip": peer.ip});
+            if ip_banned {
+                massa_trace!("in_connection_refused_peer_banned", {"address": peer.address});
                 peer.last_failure = Some(MassaTime::now(self.clock_compensation)?);
                 self.request_dump()?;
                 return Err(NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::BannedPeerTryingToConnect(ip),
+                    NetworkConnectionErrorType::BannedPeerTryingToConnect(address),
                 ));
-            } else if peer.active_in_connections >= self.network_settings.max_in_connections_per_ip
-            {
+            } else if ip_in_connections >= self.network_settings.max_in_connections_per_ip {
                 self.request_dump()?;
                 return Err(NetworkError::PeerConnectionError(
-                    NetworkConnectionErrorType::MaxPeersConnectionReached(ip),
+                    NetworkConnectionErrorType::MaxPeersConnectionReached(address),
                 ));
             } else {
                 peer.active_in_connections += 1;
@@ -766,16 +871,58 @@ impl PeerInfoDatabase {
 
         self.increase_global_active_in_connection_count(peer_type)?;
         self.request_dump()?;
-        Ok(())
+        Ok(address)
+    }
+
+    /// Updates the reputation score of a peer after an observed behavior.
+    ///
+    /// Rewards beyond `max_rewards_per_window` in the current reward window of the peer are ignored,
+    /// so that a peer cannot flood cheap useful data to outweigh its misbehavior.
+    ///
+    /// Returns true if the peer is a standard peer
+    /// whose score dropped to the ban threshold or below.
+    ///
+    /// # Arguments
+    /// * address : address of the considered peer.
+    /// * event : observed behavior of the peer.
+    pub fn peer_score_event(
+        &mut self,
+        address: &SocketAddr,
+        event: &PeerScoreEvent,
+    ) -> Result<bool, NetworkError> {
+        let address = canonical_address(address);
+        let now = MassaTime::now(self.clock_compensation)?;
+        let cfg = &self.network_settings.peer_score_config;
+        let peer = match self.peers.get_mut(&address) {
+            Some(peer) => peer,
+            None => return Ok(false),
+        };
+        let delta = cfg.score_delta(event);
+        if delta > 0.0 {
+            let (window_start, count) = self.reward_windows.entry(address).or_insert((now, 0));
+            if now.saturating_sub(*window_start) >= cfg.reward_window {
+                *window_start = now;
+                *count = 0;
+            }
+            if *count >= cfg.max_rewards_per_window {
+                massa_trace!("peer_score_reward_ignored", {"address": address, "event": event});
+                return Ok(false);
+            }
+            *count += 1;
+        }
+        let score = peer.update_score(delta, cfg, now);
+        massa_trace!("peer_score_updated", {"address": address, "event": event, "score": score});
+        Ok(peer.peer_type == PeerType::Standard && score <= cfg.ban_threshold)
     }
 
     ////////////////////
     // public getters //
     ////////////////////
 
-    /// Sorts peers by `( last_failure, rev(last_success) )`
+    /// Sorts peers by `( rev(anchor), rev(score), last_failure, rev(last_success) )`
     /// and returns as many peers as there are available slots to attempt outgoing connections to.
-    pub fn get_out_connection_candidate_ips(&self) -> Result<Vec<IpAddr>, NetworkError> {
+    /// Standard peers are limited to `max_out_connections_per_network_group` per network group.
+    pub fn get_out_connection_candidate_addresses(&self) -> Result<Vec<SocketAddr>, NetworkError> {
         let mut connections = vec![];
         let mut peer_types: Vec<PeerType> = self
             .peer_types_connection_count
@@ -784,7 +931,7 @@ impl PeerInfoDatabase {
             .collect();
         peer_types.sort_by_key(|&peer_type| Reverse(peer_type));
         for &peer_type in peer_types.iter() {
-            connections.append(&mut self.get_out_connection_candidate_ips_for_type(
+            connections.append(&mut self.get_out_connection_candidate_addresses_for_type(
                 peer_type,
                 &self.peer_types_connection_count[peer_type],
                 &self.network_settings.peer_types_config[peer_type],
@@ -793,30 +940,141 @@ impl PeerInfoDatabase {
         Ok(connections)
     }
 
-    /// returns Hashmap of `IpAddrs` -> `PeerInfo`
-    pub fn get_peers(&self) -> &HashMap<IpAddr, PeerInfo> {
+    /// Returns the addresses of connected peers that should make room for better candidates.
+    ///
+    /// For each peer type whose outgoing connection target is reached,
+    /// the lowest scored outgoing peer is returned if a ready candidate
+    /// scores at least `eviction_margin` more.
+    pub fn get_eviction_candidate_addresses(&self) -> Result<Vec<SocketAddr>, NetworkError> {
+        let now = MassaTime::now(self.clock_compensation)?;
+        let score_cfg = &self.network_settings.peer_score_config;
+        let mut evicted = Vec::new();
+        for (peer_type, _) in self.peer_types_connection_count.iter() {
+            if !self.is_target_out_connection_count_reached(peer_type) {
+                continue;
+            }
+            let worst_connected = self
+                .peers
+                .values()
+                .filter(|p| p.peer_type == peer_type && p.active_out_connections > 0)
+                .map(|p| (p.get_score(score_cfg.half_life, now), p.address))
+                .min_by(|a, b| a.0.total_cmp(&b.0));
+            let best_candidate = self
+                .peers
+                .values()
+                .filter(|p| {
+                    p.peer_type == peer_type
+                        && p.advertised
+                        && !p.is_active()
+                        && !p.banned
+                        && p.is_peer_ready(self.wakeup_interval, now)
+                })
+                .map(|p| p.get_score(score_cfg.half_life, now))
+                .max_by(|a, b| a.total_cmp(b));
+            if let (Some((worst_score, address)), Some(best_score)) =
+                (worst_connected, best_candidate)
+            {
+                if best_score - worst_score >= score_cfg.eviction_margin {
+                    evicted.push(address);
+                }
+            }
+        }
+        Ok(evicted)
+    }
+
+    /// Returns the address of the outgoing standard peer to disconnect
+    /// when rotating outgoing connections: the lowest scored one,
+    /// if a ready standard candidate can take its slot.
+    pub fn get_rotation_candidate_address(&self) -> Result<Option<SocketAddr>, NetworkError> {
+        let now = MassaTime::now(self.clock_compensation)?;
+        let half_life = self.network_settings.peer_score_config.half_life;
+        let has_candidate = self.peers.values().any(|p| {
+            p.peer_type == PeerType::Standard
+                && p.advertised
+                && !p.is_active()
+                && !p.banned
+                && p.is_peer_ready(self.wakeup_interval, now)
+        });
+        if !has_candidate {
+            return Ok(None);
+        }
+        Ok(self
+            .peers
+            .values()
+            .filter(|p| p.peer_type == PeerType::Standard && p.active_out_connections > 0)
+            .map(|p| (p.get_score(half_life, now), p.address))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, address)| address))
+    }
+
+    /// Marks up to `max_anchor_peers` of the best scored outgoing standard peers as anchors,
+    /// so that they are tried first on the next start. Other peers lose their anchor flag.
+    pub(crate) fn mark_anchor_peers(&mut self) -> Result<(), NetworkError> {
+        let now = MassaTime::now(self.clock_compensation)?;
+        let half_life = self.network_settings.peer_score_config.half_life;
+        let mut outgoing: Vec<(f64, SocketAddr)> = self
+            .peers
+            .values()
+            .filter(|p| {
+                p.peer_type == PeerType::Standard && p.active_out_connections > 0 && !p.banned
+            })
+            .map(|p| (p.get_score(half_life, now), p.address))
+            .collect();
+        outgoing.sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+        let anchors: HashSet<SocketAddr> = outgoing
+            .into_iter()
+            .take(self.network_settings.max_anchor_peers)
+            .map(|(_, address)| address)
+            .collect();
+        for peer in self.peers.values_mut() {
+            peer.anchor = anchors.contains(&peer.address);
+        }
+        Ok(())
+    }
+
+    /// returns Hashmap of `SocketAddr` -> `PeerInfo`
+    pub fn get_peers(&self) -> &HashMap<SocketAddr, PeerInfo> {
         &self.peers
     }
 
-    /// Returns a vector of advertisable `IpAddr` sorted by `( last_failure, rev(last_success) )`
-    pub fn get_advertisable_peer_ips(&self) -> Vec<IpAddr> {
+    /// Current reputation score of a peer, decayed up to now
+    pub fn get_peer_score(&self, peer: &PeerInfo) -> Result<f64, NetworkError> {
+        Ok(peer.get_score(
+            self.network_settings.peer_score_config.half_life,
+            MassaTime::now(self.clock_compensation)?,
+        ))
+    }
+
+    /// Returns a vector of advertisable `SocketAddr` sorted by `( last_failure, rev(last_success) )`
+    pub fn get_advertisable_peer_addresses(&self) -> Vec<SocketAddr> {
+        let banned_ips = self.get_banned_ips();
         let mut sorted_peers: Vec<PeerInfo> = self
             .peers
             .values()
-            .filter(|&p| (p.advertised && !p.banned))
+            .filter(|&p| (p.advertised && !banned_ips.contains(&p.address.ip())))
             .copied()
             .collect();
         sorted_peers.sort_unstable_by_key(|&p| (std::cmp::Reverse(p.last_alive), p.last_failure));
-        let mut sorted_ips: Vec<IpAddr> = sorted_peers
+        let mut sorted_addresses: Vec<SocketAddr> = sorted_peers
             .into_iter()
             .take(self.network_settings.max_peer_advertise_length as usize)
-            .map(|p| p.ip)
+            .map(|p| p.address)
             .collect();
-        if let Some(our_ip) = self.network_settings.routable_ip {
-            sorted_ips.insert(0, our_ip.to_canonical());
-            sorted_ips.truncate(self.network_settings.max_peer_advertise_length as usize);
+        if let Some(our_address) = self.network_settings.routable_address() {
+            sorted_addresses.insert(0, our_address);
+            sorted_addresses.truncate(self.network_settings.max_peer_advertise_length as usize);
         }
-        sorted_ips
+        sorted_addresses
+    }
+
+    /// Get the ips behind which at least one peer is banned:
+    /// bans apply to every port of a banned ip.
+    fn get_banned_ips(&self) -> HashSet<IpAddr> {
+        self.peers
+            .values()
+            .filter(|p| p.banned)
+            .map(|p| p.address.ip())
+            .collect()
     }
 
     //////////////////////////////
@@ -839,7 +1097,7 @@ impl PeerInfoDatabase {
             >= self.network_settings.peer_types_config[peer_type].max_in_connections
     }
 
-    /// Get ips we want to connect to for a given peer type
+    /// Get addresses we want to connect to for a given peer type
     ///
     /// # Arguments
     /// * `peer_type`: which type to consider
@@ -847,32 +1105,81 @@ impl PeerInfoDatabase {
     /// * `cfg`: settings for that peer type
     ///
     /// Returns an iterator
-    fn get_out_connection_candidate_ips_for_type(
+    fn get_out_connection_candidate_addresses_for_type(
         &self,
         peer_type: PeerType,
         count: &ConnectionCount,
         cfg: &PeerTypeConnectionConfig,
-    ) -> Result<Vec<IpAddr>, NetworkError> {
+    ) -> Result<Vec<SocketAddr>, NetworkError> {
         let available_slots = count.get_available_out_connection_attempts(cfg);
         let now = MassaTime::now(self.clock_compensation)?;
-        let f = move |p: &&PeerInfo| {
-            if p.peer_type != peer_type || !p.advertised || p.is_active() || p.banned {
+        let half_life = self.network_settings.peer_score_config.half_life;
+        let banned_ips = self.get_banned_ips();
+        let f = |p: &&PeerInfo| {
+            if p.peer_type != peer_type
+                || !p.advertised
+                || p.is_active()
+                || banned_ips.contains(&p.address.ip())
+            {
                 return false;
             }
             p.is_peer_ready(self.wakeup_interval, now)
         };
-        let mut res: Vec<_> = self
-            .peers
-            .values()
-            .filter(f)
+        let mut res: Vec<_> = self.peers.values().filter(f).collect();
+        // prefer anchors, then the best scored peers
+        res.sort_unstable_by(|&a, &b| {
+            b.anchor
+                .cmp(&a.anchor)
+                .then_with(|| {
+                    b.get_score(half_life, now)
+                        .total_cmp(&a.get_score(half_life, now))
+                })
+                .then_with(|| {
+                    (a.last_failure, std::cmp::Reverse(a.last_alive))
+                        .cmp(&(b.last_failure, std::cmp::Reverse(b.last_alive)))
+                })
+        });
+        if peer_type != PeerType::Standard {
+            return Ok(res
+                .into_iter()
+                .take(available_slots)
+                .map(|p| p.address)
+                .collect());
+        }
+        // spread outgoing connections over network groups
+        let max_per_group = self.network_settings.max_out_connections_per_network_group;
+        let mut group_counts = self.get_out_connection_count_by_network_group();
+        Ok(res
+            .into_iter()
+            .filter(|p| {
+                let count = group_counts
+                    .entry(network_group(&p.address.ip()))
+                    .or_default();
+                if *count >= max_per_group {
+                    return false;
+                }
+                *count += 1;
+                true
+            })
             .take(available_slots)
-            .collect();
-        res.sort_unstable_by_key(|&p| (p.last_failure, std::cmp::Reverse(p.last_alive)));
-        Ok(res.into_iter().map(|p| p.ip).collect())
+            .map(|p| p.address)
+            .collect())
+    }
+
+    /// Number of outgoing connections and attempts to standard peers, by network group
+    fn get_out_connection_count_by_network_group(&self) -> HashMap<IpAddr, usize> {
+        let mut counts: HashMap<IpAddr, usize> = HashMap::new();
+        for p in self.peers.values() {
+            let count = p.active_out_connections + p.active_out_connection_attempts;
+            if p.peer_type == PeerType::Standard && count > 0 {
+                *counts.entry(network_group(&p.address.ip())).or_default() += count;
+            }
+        }
+        counts
     }
 
-    fn get_peer_type(&self, ip: &IpAddr) -> Option<PeerType> {
-        Some(self.peers.get(ip)?.peer_type)
+    fn get_peer_type(&self, address: &SocketAddr) -> Option<PeerType> {
+        Some(self.peers.get(address)?.peer_type)
     }
 
     fn can_try_new_out_connection(&self, peer_type: PeerType) -> bool {
@@ -886,11 +1193,11 @@ impl PeerInfoDatabase {
     fn increase_global_active_out_connection_attempt_count(
         &mut self,
         peer_type: PeerType,
-        ip: &IpAddr,
+        address: &SocketAddr,
     ) -> Result<(), NetworkError> {
         if !self.can_try_new_out_connection(peer_type) {
             return Err(NetworkError::PeerConnectionError(
-                NetworkConnectionErrorType::TooManyConnectionAttempts(*ip),
+                NetworkConnectionErrorType::TooManyConnectionAttempts(*address),
             ));
         }
         self.peer_types_connection_count[peer_type].active_out_connection_attempts += 1;
@@ -900,11 +1207,11 @@ impl PeerInfoDatabase {
     fn decrease_global_active_out_connection_attempt_count(
         &mut self,
         peer_type: PeerType,
-        ip: &IpAddr,
+        address: &SocketAddr,
     ) -> Result<(), NetworkError> {
         if !self.can_remove_new_out_connection_attempt(peer_type) {
             return Err(NetworkError::PeerConnectionError(
-                NetworkConnectionErrorType::TooManyConnectionAttempts(*ip),
+                NetworkConnectionErrorType::TooManyConnectionAttempts(*address),
             ));
         }
         self.peer_types_connection_count[peer_type].active_out_connection_attempts -= 1;
@@ -918,11 +1225,11 @@ impl PeerInfoDatabase {
     fn decrease_global_active_out_connection_count(
         &mut self,
         peer_type: PeerType,
-        ip: &IpAddr,
+        address: &SocketAddr,
     ) -> Result<(), NetworkError> {
         if !self.can_remove_active_out_connection_count(peer_type) {
             return Err(NetworkError::PeerConnectionError(
-                NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(*ip),
+                NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(*address),
             ));
         }
         self.peer_types_connection_count[peer_type].active_out_connections -= 1;
@@ -944,11 +1251,11 @@ impl PeerInfoDatabase {
     fn decrease_global_active_in_connection_count(
         &mut self,
         peer_type: PeerType,
-        ip: &IpAddr,
+        address: &SocketAddr,
     ) -> Result<(), NetworkError> {
         if !self.can_decrease_global_active_in_connection_count(peer_type) {
             return Err(NetworkError::PeerConnectionError(
-                NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(*ip),
+                NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(*address),
             ));
         }
         self.peer_types_connection_count[peer_type].active_in_connections -= 1;
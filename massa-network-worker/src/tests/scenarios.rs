@@ -23,14 +23,16 @@ use massa_models::config::{
 use massa_models::{
     block::BlockId,
     endorsement::{Endorsement, EndorsementSerializer},
-    node::NodeId,
+    node::{BanReason, NodeId},
     slot::Slot,
     wrapped::WrappedContent,
 };
-use massa_network_exports::{settings::PeerTypeConnectionConfig, NodeCommand, NodeEvent};
+use massa_network_exports::{
+    settings::PeerTypeConnectionConfig, NodeCommand, NodeEvent, NodeEventType,
+};
 use massa_network_exports::{
     AskForBlocksInfo, BlockInfoReply, ConnectionClosureReason, ConnectionId, HandshakeErrorType,
-    PeerInfo, PeerType,
+    PeerInfo, PeerScoreEvent, PeerType,
 };
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
@@ -104,6 +106,7 @@ async fn test_node_worker_shutdown() {
         NodeWorker::new(
             network_conf,
             mock_node_id,
+            true,
             reader,
             writer,
             node_command_rx,
@@ -176,6 +179,7 @@ async fn test_node_worker_operations_message() {
         NodeWorker::new(
             network_conf,
             mock_node_id,
+            true,
             reader,
             writer,
             node_command_rx,
@@ -210,6 +214,89 @@ async fn test_node_worker_operations_message() {
     node_fn_handle.await.unwrap().unwrap();
 }
 
+/// Test that a node worker drops the peer lists a node sends too often.
+#[tokio::test]
+#[serial]
+async fn test_node_worker_peer_list_rate_limit() {
+    let bind_port: u16 = 50_000;
+    let temp_peers_file = super::tools::generate_peers_file(&[]);
+    let network_conf = NetworkConfig {
+        ask_peer_list_interval: MassaTime::from_millis(600_000),
+        ..NetworkConfig::scenarios_default(bind_port, temp_peers_file.path())
+    };
+    let (duplex_controller, duplex_mock) = tokio::io::duplex(1024);
+    let (duplex_controller_read, duplex_controller_write) = tokio::io::split(duplex_controller);
+    let (_duplex_mock_read, duplex_mock_write) = tokio::io::split(duplex_mock);
+    let reader = ReadBinder::new(
+        duplex_controller_read,
+        f64::INFINITY,
+        MAX_MESSAGE_SIZE,
+        MessageDeserializer::new(
+            THREAD_COUNT,
+            ENDORSEMENT_COUNT,
+            MAX_ADVERTISE_LENGTH,
+            MAX_ASK_BLOCKS_PER_MESSAGE,
+            MAX_OPERATIONS_PER_BLOCK,
+            MAX_OPERATIONS_PER_MESSAGE,
+            MAX_ENDORSEMENTS_PER_MESSAGE,
+            MAX_DATASTORE_VALUE_LENGTH,
+            MAX_FUNCTION_NAME_LENGTH,
+            MAX_PARAMETERS_SIZE,
+        ),
+    );
+    let writer = WriteBinder::new(duplex_controller_write, f64::INFINITY, MAX_MESSAGE_SIZE);
+    let mut mock_writer = WriteBinder::new(duplex_mock_write, f64::INFINITY, MAX_MESSAGE_SIZE);
+
+    let (node_command_tx, node_command_rx) = mpsc::channel::<NodeCommand>(1);
+    let (node_event_tx, mut node_event_rx) = mpsc::channel::<NodeEvent>(10);
+
+    let keypair = KeyPair::generate();
+    let mock_node_id = NodeId(keypair.get_public_key());
+
+    let node_fn_handle = tokio::spawn(async move {
+        NodeWorker::new(
+            network_conf,
+            mock_node_id,
+            true,
+            reader,
+            writer,
+            node_command_rx,
+            node_event_tx,
+        )
+        .run_loop()
+        .await
+    });
+
+    // The node sends two peer lists in a row.
+    let first_peer = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 0, 11)), 4000);
+    let second_peer = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 0, 12)), 4000);
+    mock_writer
+        .send(&Message::PeerListWithPorts(vec![first_peer]))
+        .await
+        .unwrap();
+    mock_writer
+        .send(&Message::PeerList(vec![second_peer.ip()]))
+        .await
+        .unwrap();
+
+    // Only the first one is forwarded.
+    let mut received_peer_lists = Vec::new();
+    while let Ok(Some(NodeEvent(_, event))) =
+        tokio::time::timeout(Duration::from_millis(500), node_event_rx.recv()).await
+    {
+        if let NodeEventType::ReceivedPeerList(peer_list) = event {
+            received_peer_lists.push(peer_list);
+        }
+    }
+    assert_eq!(received_peer_lists, vec![vec![first_peer]]);
+
+    node_command_tx
+        .send(NodeCommand::Close(ConnectionClosureReason::Normal))
+        .await
+        .unwrap();
+    node_fn_handle.await.unwrap().unwrap();
+}
+
 // test connecting two different peers simultaneously to the controller
 // then attempt to connect to controller from an already connected peer to test max_in_connections_per_ip
 // then try to connect a third peer to test max_in_connection
@@ -343,7 +430,7 @@ async fn test_peer_ban() {
 
     let mock_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 0, 11)), bind_port);
     // add advertised peer to controller
-    let temp_peers_file = super::tools::generate_peers_file(&[PeerInfo::new(mock_addr.ip(), true)]);
+    let temp_peers_file = super::tools::generate_peers_file(&[PeerInfo::new(mock_addr, true)]);
 
     let network_conf = NetworkConfig {
         wakeup_interval: 1000.into(),
@@ -388,7 +475,7 @@ async fn test_peer_ban() {
 
             // ban connection1.
             network_command_sender
-                .node_ban_by_ids(vec![conn1_id])
+                .node_ban_by_ids(vec![conn1_id], BanReason::Manual, None)
                 .await
                 .expect("error during send ban command.");
 
@@ -478,7 +565,7 @@ async fn test_peer_ban_by_ip() {
 
     let mock_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 0, 11)), bind_port);
     // add advertised peer to controller
-    let temp_peers_file = super::tools::generate_peers_file(&[PeerInfo::new(mock_addr.ip(), true)]);
+    let temp_peers_file = super::tools::generate_peers_file(&[PeerInfo::new(mock_addr, true)]);
 
     let network_conf = NetworkConfig {
         wakeup_interval: 1000.into(),
@@ -523,7 +610,7 @@ async fn test_peer_ban_by_ip() {
 
             // ban connection1.
             network_command_sender
-                .node_ban_by_ips(vec![mock_addr.ip()])
+                .node_ban_by_ips(vec![mock_addr.ip()], BanReason::Manual, None)
                 .await
                 .expect("error during send ban command.");
 
@@ -589,6 +676,106 @@ async fn test_peer_ban_by_ip() {
     .await;
 }
 
+// test peer ban by score
+// add an advertised peer
+// accept controller's connection attempt to that peer
+// report invalid data from that peer until its score drops below the ban threshold
+// make sure the peer is banned
+// attempt to connect banned peer to controller : must fail
+#[tokio::test]
+#[serial]
+async fn test_peer_ban_by_score() {
+    // test config
+    let bind_port: u16 = 50_000;
+
+    let mock_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 0, 11)), bind_port);
+    // add advertised peer to controller
+    let temp_peers_file = super::tools::generate_peers_file(&[PeerInfo::new(mock_addr, true)]);
+
+    let network_conf = NetworkConfig {
+        wakeup_interval: 1000.into(),
+        ..NetworkConfig::scenarios_default(bind_port, temp_peers_file.path())
+    };
+
+    tools::network_test(
+        network_conf.clone(),
+        temp_peers_file,
+        async move |network_command_sender,
+                    mut network_event_receiver,
+                    network_manager,
+                    mut mock_interface| {
+            // accept connection from controller to peer
+            let (conn1_id, conn1_r, conn1_w) = tools::full_connection_from_controller(
+                &mut network_event_receiver,
+                &mut mock_interface,
+                mock_addr,
+                1_000u64,
+                1_000u64,
+                1_000u64,
+                ConnectionId(0),
+            )
+            .await;
+            let conn1_drain = tools::incoming_message_drain_start(conn1_r).await;
+
+            // the first report leaves the peer above the ban threshold
+            network_command_sender
+                .node_score_event(conn1_id, PeerScoreEvent::InvalidData)
+                .await
+                .expect("error during send score event command.");
+            sleep(Duration::from_millis(200)).await;
+            let peers = network_command_sender.get_peers().await.unwrap();
+            assert!(!peers.peers[&mock_addr].peer_info.banned);
+
+            // further reports drive the score down to the ban threshold
+            for _ in 0..2 {
+                network_command_sender
+                    .node_score_event(conn1_id, PeerScoreEvent::InvalidData)
+                    .await
+                    .expect("error during send score event command.");
+            }
+            sleep(Duration::from_millis(200)).await;
+            let peers = network_command_sender.get_peers().await.unwrap();
+            let peer_info = peers.peers[&mock_addr].peer_info;
+            assert!(peer_info.banned);
+            assert_eq!(
+                peer_info.ban.expect("missing ban record").reason,
+                BanReason::ProtocolViolation
+            );
+
+            // stop conn1
+            tools::incoming_message_drain_stop(conn1_drain).await;
+            drop(conn1_w);
+            sleep(Duration::from_millis(200)).await;
+
+            // drain all messages
+            let _ = tools::wait_network_event(&mut network_event_receiver, 500.into(), |_msg| {
+                Option::<()>::None
+            })
+            .await;
+
+            // attempt a new connection from peer to controller: should be rejected
+            let _: NetworkError = tools::rejected_connection_to_controller(
+                &mut network_event_receiver,
+                &mut mock_interface,
+                mock_addr,
+                1_000u64,
+                1_000u64,
+                1_000u64,
+                ConnectionId(1),
+            )
+            .await;
+
+            (
+                network_event_receiver,
+                network_manager,
+                mock_interface,
+                vec![],
+            )
+        },
+    )
+    .await;
+}
+
 // test merge_advertised_peer_list, advertised and wakeup_interval:
 //   setup one non-advertised peer
 //   use merge_advertised_peer_list to add another peer (this one is advertised)
@@ -612,7 +799,7 @@ async fn test_advertised_and_wakeup_interval() {
     let mock_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 0, 12)), bind_port);
     let mock_ignore_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 0, 13)), bind_port);
     let temp_peers_file = super::tools::generate_peers_file(&[PeerInfo {
-        ip: mock_ignore_addr.ip(),
+        address: mock_ignore_addr,
         peer_type: PeerType::Bootstrap,
         last_alive: None,
         last_failure: None,
@@ -621,6 +808,10 @@ async fn test_advertised_and_wakeup_interval() {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        ban: None,
+        anchor: false,
+        score: 0.0,
+        last_score_update: None,
     }]);
     let network_conf = NetworkConfig {
         wakeup_interval: MassaTime::from_millis(500),
@@ -711,7 +902,7 @@ async fn test_advertised_and_wakeup_interval() {
                 &mut network_event_receiver,
                 1000.into(),
                 |msg| match msg {
-                    NetworkEvent::NewConnection(_) => Some(()),
+                    NetworkEvent::NewConnection(_, _) => Some(()),
                     _ => None,
                 },
             )
@@ -747,7 +938,7 @@ async fn test_block_not_found() {
     let mock_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 0, 11)), bind_port);
     // add advertised peer to controller
     let temp_peers_file = super::tools::generate_peers_file(&[PeerInfo {
-        ip: mock_addr.ip(),
+        address: mock_addr,
         peer_type: PeerType::Bootstrap,
         last_alive: None,
         last_failure: None,
@@ -756,6 +947,10 @@ async fn test_block_not_found() {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        ban: None,
+        anchor: false,
+        score: 0.0,
+        last_score_update: None,
     }]);
     let network_conf = NetworkConfig {
         peer_types_config: default_testing_peer_type_enum_map(),
@@ -929,7 +1124,7 @@ async fn test_retry_connection_closed() {
     let mock_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 0, 11)), bind_port);
     // add advertised peer to controller
     let temp_peers_file = super::tools::generate_peers_file(&[PeerInfo {
-        ip: mock_addr.ip(),
+        address: mock_addr,
         peer_type: PeerType::Bootstrap,
         last_alive: None,
         last_failure: None,
@@ -938,6 +1133,10 @@ async fn test_retry_connection_closed() {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        ban: None,
+        anchor: false,
+        score: 0.0,
+        last_score_update: None,
     }]);
     let network_conf = NetworkConfig {
         peer_types_config: default_testing_peer_type_enum_map(),
@@ -964,7 +1163,7 @@ async fn test_retry_connection_closed() {
 
             // Ban the node.
             network_command_sender
-                .node_ban_by_ids(vec![node_id])
+                .node_ban_by_ids(vec![node_id], BanReason::Manual, None)
                 .await
                 .expect("error during send ban command.");
 
@@ -1028,7 +1227,7 @@ async fn test_operation_messages() {
     let mock_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 0, 11)), bind_port);
     // add advertised peer to controller
     let temp_peers_file = super::tools::generate_peers_file(&[PeerInfo {
-        ip: mock_addr.ip(),
+        address: mock_addr,
         peer_type: PeerType::Bootstrap,
         last_alive: None,
         last_failure: None,
@@ -1037,6 +1236,10 @@ async fn test_operation_messages() {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        ban: None,
+        anchor: false,
+        score: 0.0,
+        last_score_update: None,
     }]);
     let network_conf = NetworkConfig {
         peer_types_config: default_testing_peer_type_enum_map(),
@@ -1143,7 +1346,7 @@ async fn test_endorsements_messages() {
     let mock_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(169, 202, 0, 11)), bind_port);
     // add advertised peer to controller
     let temp_peers_file = super::tools::generate_peers_file(&[PeerInfo {
-        ip: mock_addr.ip(),
+        address: mock_addr,
         peer_type: PeerType::Bootstrap,
         last_alive: None,
         last_failure: None,
@@ -1152,6 +1355,10 @@ async fn test_endorsements_messages() {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        ban: None,
+        anchor: false,
+        score: 0.0,
+        last_score_update: None,
     }]);
     let network_conf = NetworkConfig {
         peer_types_config: default_testing_peer_type_enum_map(),
@@ -1,14 +1,19 @@
 use crate::{
-    peer_info_database::{cleanup_peers, PeerInfoDatabase},
+    peer_info_database::{cleanup_peers, network_group, PeerInfoDatabase},
     NetworkConfig, NetworkError,
 };
 use enum_map::enum_map;
+use massa_models::node::BanReason;
 use massa_network_exports::{
-    settings::PeerTypeConnectionConfig, NetworkConnectionErrorType, PeerInfo, PeerType,
+    settings::{PeerScoreConfig, PeerTypeConnectionConfig},
+    NetworkConnectionErrorType, PeerInfo, PeerScoreEvent, PeerType,
 };
 use massa_time::MassaTime;
 use serial_test::serial;
-use std::{collections::HashMap, net::IpAddr};
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+};
 use tokio::sync::watch;
 
 #[tokio::test]
@@ -29,18 +34,18 @@ async fn test_try_new_in_connection_in_connection_closed() {
         peer_types_config,
         ..Default::default()
     };
-    let mut peers: HashMap<IpAddr, PeerInfo> = HashMap::new();
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
 
     // add peers
     // peer Ok, return
     let connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
     let mut connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)));
     connected_peers1.peer_type = PeerType::Bootstrap;
     connected_peers1.banned = true;
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
 
     let wakeup_interval = network_settings.wakeup_interval;
     let (saver_watch_tx, mut saver_watch_rx) = watch::channel(peers.clone());
@@ -55,16 +60,22 @@ async fn test_try_new_in_connection_in_connection_closed() {
         saver_watch_tx,
         wakeup_interval,
         clock_compensation: 0,
+        reward_windows: Default::default(),
         peer_types_connection_count: Default::default(),
     };
 
     // test with no connection attempt before
-    let res = db.in_connection_closed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
+    let res = db.in_connection_closed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11))),
+            ip_err
+        );
     } else {
         panic!("ToManyConnectionAttempt error not return");
     }
@@ -80,35 +91,52 @@ async fn test_try_new_in_connection_in_connection_closed() {
         .expect_err("banned peer not detected.");
 
     // test with a not connected peer
-    let res = db.in_connection_closed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)));
+    let res = db.in_connection_closed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 12,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12))),
+            ip_err
+        );
     } else {
         panic!("ToManyConnectionAttempt error not return");
     }
 
     // test with a not connected peer
-    let res = db.in_connection_closed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13)));
+    let res = db.in_connection_closed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 13,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::PeerInfoNotFoundError(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13))),
+            ip_err
+        );
     } else {
         panic!("PeerInfoNotFoundError error not return");
     }
 
-    db.in_connection_closed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)))
-        .unwrap();
-    let res = db.in_connection_closed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
+    db.in_connection_closed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))))
+    .unwrap();
+    let res = db.in_connection_closed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11))),
+            ip_err
+        );
     } else {
         panic!("TooManyConnectionAttempt error not return");
     }
@@ -132,18 +160,18 @@ async fn test_out_connection_attempt_failed() {
         peer_types_config,
         ..Default::default()
     };
-    let mut peers: HashMap<IpAddr, PeerInfo> = HashMap::new();
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
 
     // add peers
     // peer Ok, return
     let connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
     let mut connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)));
     connected_peers1.peer_type = PeerType::Bootstrap;
     connected_peers1.banned = true;
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
 
     let wakeup_interval = network_settings.wakeup_interval;
     let (saver_watch_tx, mut saver_watch_rx) = watch::channel(peers.clone());
@@ -159,59 +187,80 @@ async fn test_out_connection_attempt_failed() {
         peer_types_connection_count: Default::default(),
         wakeup_interval,
         clock_compensation: 0,
+        reward_windows: Default::default(),
     };
 
     // test with no connection attempt before
-    let res =
-        db.out_connection_attempt_failed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
+    let res = db.out_connection_attempt_failed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::TooManyConnectionFailure(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11))),
+            ip_err
+        );
     } else {
         println!("res: {:?}", res);
         panic!("TooManyConnectionFailure error not return");
     }
 
-    db.new_out_connection_attempt(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)))
-        .unwrap();
+    db.new_out_connection_attempt(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))))
+    .unwrap();
 
     // peer not found.
-    let res =
-        db.out_connection_attempt_failed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13)));
+    let res = db.out_connection_attempt_failed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 13,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::PeerInfoNotFoundError(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13))),
+            ip_err
+        );
     } else {
         println!("res: {:?}", res);
         panic!("PeerInfoNotFoundError error not return");
     }
     // peer with no attempt.
-    let res =
-        db.out_connection_attempt_failed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)));
+    let res = db.out_connection_attempt_failed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 12,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::TooManyConnectionFailure(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12))),
+            ip_err
+        );
     } else {
         println!("res: {:?}", res);
         panic!("ToManyConnectionFailure error not return");
     }
     // call ok.
-    db.out_connection_attempt_failed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)))
-        .expect("out_connection_attempt_failed failed");
-
-    let res =
-        db.out_connection_attempt_failed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
+    db.out_connection_attempt_failed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))))
+    .expect("out_connection_attempt_failed failed");
+
+    let res = db.out_connection_attempt_failed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::TooManyConnectionFailure(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11))),
+            ip_err
+        );
     } else {
         panic!("ToManyConnectionFailure error not return");
     }
@@ -243,18 +292,18 @@ async fn test_try_out_connection_attempt_success() {
         peer_types_config,
         ..Default::default()
     };
-    let mut peers: HashMap<IpAddr, PeerInfo> = HashMap::new();
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
 
     // add peers
     // peer Ok, return
     let connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
     let mut connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)));
     connected_peers1.peer_type = PeerType::Bootstrap;
     connected_peers1.banned = true;
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
 
     let wakeup_interval = network_settings.wakeup_interval;
     let (saver_watch_tx, mut saver_watch_rx) = watch::channel(peers.clone());
@@ -270,56 +319,77 @@ async fn test_try_out_connection_attempt_success() {
         peer_types_connection_count: Default::default(),
         wakeup_interval,
         clock_compensation: 0,
+        reward_windows: Default::default(),
     };
 
     // test with no connection attempt before
-    let res = db
-        .try_out_connection_attempt_success(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
+    let res = db.try_out_connection_attempt_success(&peer_address(IpAddr::V4(
+        std::net::Ipv4Addr::new(169, 202, 0, 11),
+    )));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::TooManyConnectionAttempts(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11))),
+            ip_err
+        );
     } else {
         panic!("ToManyConnectionAttempt error not return");
     }
 
-    db.new_out_connection_attempt(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)))
-        .unwrap();
+    db.new_out_connection_attempt(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))))
+    .unwrap();
 
     // peer not found.
-    let res = db
-        .try_out_connection_attempt_success(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13)));
+    let res = db.try_out_connection_attempt_success(&peer_address(IpAddr::V4(
+        std::net::Ipv4Addr::new(169, 202, 0, 13),
+    )));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::PeerInfoNotFoundError(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13))),
+            ip_err
+        );
     } else {
         println!("res: {:?}", res);
         panic!("PeerInfoNotFoundError error not return");
     }
 
     let res = db
-        .try_out_connection_attempt_success(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)))
+        .try_out_connection_attempt_success(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 11,
+        ))))
         .unwrap();
     assert!(res, "try_out_connection_attempt_success failed");
 
-    let res = db
-        .try_out_connection_attempt_success(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)));
+    let res = db.try_out_connection_attempt_success(&peer_address(IpAddr::V4(
+        std::net::Ipv4Addr::new(169, 202, 0, 12),
+    )));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::TooManyConnectionAttempts(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12))),
+            ip_err
+        );
     } else {
         panic!("TooManyConnectionAttempts error not return");
     }
 
-    db.new_out_connection_attempt(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)))
-        .unwrap();
+    db.new_out_connection_attempt(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 12,
+    ))))
+    .unwrap();
     let res = db
-        .try_out_connection_attempt_success(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)))
+        .try_out_connection_attempt_success(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 12,
+        ))))
         .unwrap();
     assert!(!res, "try_out_connection_attempt_success not banned");
 }
@@ -342,13 +412,13 @@ async fn test_new_out_connection_closed() {
         peer_types_config,
         ..Default::default()
     };
-    let mut peers: HashMap<IpAddr, PeerInfo> = HashMap::new();
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
 
     // add peers
     // peer Ok, return
     let connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
     let wakeup_interval = network_settings.wakeup_interval;
     let (saver_watch_tx, mut saver_watch_rx) = watch::channel(peers.clone());
     let saver_join_handle =
@@ -362,45 +432,67 @@ async fn test_new_out_connection_closed() {
         peer_types_connection_count: Default::default(),
         wakeup_interval,
         clock_compensation: 0,
+        reward_windows: Default::default(),
     };
 
     //
-    let res = db.out_connection_closed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
+    let res = db.out_connection_closed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11))),
+            ip_err
+        );
     } else {
         panic!("CloseConnectionWithNoConnectionToClose error not return");
     }
 
     // add a new connection attempt
-    db.new_out_connection_attempt(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)))
-        .unwrap();
+    db.new_out_connection_attempt(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))))
+    .unwrap();
     let res = db
-        .try_out_connection_attempt_success(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)))
+        .try_out_connection_attempt_success(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 11,
+        ))))
         .unwrap();
     assert!(res, "try_out_connection_attempt_success failed");
 
-    let res = db.out_connection_closed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)));
+    let res = db.out_connection_closed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 12,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::PeerInfoNotFoundError(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12))),
+            ip_err
+        );
     } else {
         panic!("PeerInfoNotFoundError error not return");
     }
 
-    db.out_connection_closed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)))
-        .unwrap();
-    let res = db.out_connection_closed(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
+    db.out_connection_closed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))))
+    .unwrap();
+    let res = db.out_connection_closed(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::CloseConnectionWithNoConnectionToClose(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11))),
+            ip_err
+        );
     } else {
         panic!("CloseConnectionWithNoConnectionToClose error not return");
     }
@@ -424,13 +516,13 @@ async fn test_new_out_connection_attempt() {
         peer_types_config,
         ..Default::default()
     };
-    let mut peers: HashMap<IpAddr, PeerInfo> = HashMap::new();
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
 
     // add peers
     // peer Ok, return
     let connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
     let wakeup_interval = network_settings.wakeup_interval;
     let (saver_watch_tx, _) = watch::channel(peers.clone());
     let saver_join_handle = tokio::spawn(async move {});
@@ -443,10 +535,13 @@ async fn test_new_out_connection_attempt() {
         peer_types_connection_count: Default::default(),
         wakeup_interval,
         clock_compensation: 0,
+        reward_windows: Default::default(),
     };
 
     // test with no peers.
-    let res = db.new_out_connection_attempt(&IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 0, 11)));
+    let res = db.new_out_connection_attempt(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        192, 168, 0, 11,
+    ))));
     if let Err(NetworkError::InvalidIpError(ip_err)) = res {
         assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 0, 11)), ip_err);
     } else {
@@ -455,20 +550,29 @@ async fn test_new_out_connection_attempt() {
 
     // if peer not found, it's created
     assert!(db
-        .new_out_connection_attempt(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)))
+        .new_out_connection_attempt(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 12
+        ))))
         .is_ok());
 
     (0..4).for_each(|_| {
         // ip 12 + 4 ip 11 == 5 total connections
-        db.new_out_connection_attempt(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)))
-            .unwrap()
+        db.new_out_connection_attempt(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 11,
+        ))))
+        .unwrap()
     });
-    let res = db.new_out_connection_attempt(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
+    let res = db.new_out_connection_attempt(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+        169, 202, 0, 11,
+    ))));
     if let Err(NetworkError::PeerConnectionError(
         NetworkConnectionErrorType::TooManyConnectionAttempts(ip_err),
     )) = res
     {
-        assert_eq!(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)), ip_err);
+        assert_eq!(
+            peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11))),
+            ip_err
+        );
     } else {
         panic!("ToManyConnectionAttempt error not return");
     }
@@ -478,45 +582,45 @@ async fn test_new_out_connection_attempt() {
 #[serial]
 async fn test_get_advertisable_peer_ips() {
     let network_settings = NetworkConfig::default();
-    let mut peers: HashMap<IpAddr, PeerInfo> = HashMap::new();
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
 
     // add peers
     // peer Ok, return
     let connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
     // peer banned not return.
     let mut banned_host1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 23)));
     banned_host1.peer_type = PeerType::Bootstrap;
     banned_host1.banned = true;
     banned_host1.last_alive = Some(MassaTime::now(0).unwrap().checked_sub(1000.into()).unwrap());
-    peers.insert(banned_host1.ip, banned_host1);
+    peers.insert(banned_host1.address, banned_host1);
     // peer not advertised, not return
     let mut connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 18)));
     connected_peers1.advertised = false;
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
     // peer Ok, return
     let mut connected_peers2 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13)));
     connected_peers2.last_alive = Some(MassaTime::now(0).unwrap().checked_sub(800.into()).unwrap());
     connected_peers2.last_failure =
         Some(MassaTime::now(0).unwrap().checked_sub(1000.into()).unwrap());
-    peers.insert(connected_peers2.ip, connected_peers2);
+    peers.insert(connected_peers2.address, connected_peers2);
     // peer Ok, connected return
     let mut connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 17)));
     connected_peers1.active_out_connections = 1;
     connected_peers1.last_alive = Some(MassaTime::now(0).unwrap().checked_sub(900.into()).unwrap());
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
     // peer failure before alive but to early. return
     let mut connected_peers2 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 14)));
     connected_peers2.last_alive = Some(MassaTime::now(0).unwrap().checked_sub(800.into()).unwrap());
     connected_peers2.last_failure =
         Some(MassaTime::now(0).unwrap().checked_sub(2000.into()).unwrap());
-    peers.insert(connected_peers2.ip, connected_peers2);
+    peers.insert(connected_peers2.address, connected_peers2);
 
     let wakeup_interval = network_settings.wakeup_interval;
     let (saver_watch_tx, _) = watch::channel(peers.clone());
@@ -530,31 +634,32 @@ async fn test_get_advertisable_peer_ips() {
         peer_types_connection_count: Default::default(),
         wakeup_interval,
         clock_compensation: 0,
+        reward_windows: Default::default(),
     };
 
     // test with no peers.
-    let ip_list = db.get_advertisable_peer_ips();
+    let ip_list = db.get_advertisable_peer_addresses();
 
     assert_eq!(5, ip_list.len());
 
     assert_eq!(
-        IpAddr::V4(std::net::Ipv4Addr::new(127, 0, 0, 1)),
+        peer_address(IpAddr::V4(std::net::Ipv4Addr::new(127, 0, 0, 1))),
         ip_list[0]
     );
     assert_eq!(
-        IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 14)),
+        peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 14))),
         ip_list[1]
     );
     assert_eq!(
-        IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13)),
+        peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13))),
         ip_list[2]
     );
     assert_eq!(
-        IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 17)),
+        peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 17))),
         ip_list[3]
     );
     assert_eq!(
-        IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)),
+        peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11))),
         ip_list[4]
     );
 }
@@ -563,21 +668,21 @@ async fn test_get_advertisable_peer_ips() {
 #[serial]
 async fn test_get_out_connection_candidate_ips() {
     let network_settings = NetworkConfig::default();
-    let mut peers: HashMap<IpAddr, PeerInfo> = HashMap::new();
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
 
     // add peers
     // peer Ok, return
     let mut connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
     connected_peers1.peer_type = PeerType::Bootstrap;
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
 
     // peer failure too early. not return
     let mut connected_peers2 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)));
     connected_peers2.last_failure =
         Some(MassaTime::now(0).unwrap().checked_sub(900.into()).unwrap());
-    peers.insert(connected_peers2.ip, connected_peers2);
+    peers.insert(connected_peers2.address, connected_peers2);
 
     // peer failure before alive but too early. return
     let mut connected_peers2 =
@@ -585,14 +690,14 @@ async fn test_get_out_connection_candidate_ips() {
     connected_peers2.last_alive = Some(MassaTime::now(0).unwrap().checked_sub(900.into()).unwrap());
     connected_peers2.last_failure =
         Some(MassaTime::now(0).unwrap().checked_sub(1000.into()).unwrap());
-    peers.insert(connected_peers2.ip, connected_peers2);
+    peers.insert(connected_peers2.address, connected_peers2);
 
     // peer alive no failure. return
     let mut connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 14)));
     connected_peers1.last_alive =
         Some(MassaTime::now(0).unwrap().checked_sub(1000.into()).unwrap());
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
 
     // peer banned not return.
     let mut banned_host1 =
@@ -600,7 +705,7 @@ async fn test_get_out_connection_candidate_ips() {
     banned_host1.peer_type = PeerType::Bootstrap;
     banned_host1.banned = true;
     banned_host1.last_alive = Some(MassaTime::now(0).unwrap().checked_sub(1000.into()).unwrap());
-    peers.insert(banned_host1.ip, banned_host1);
+    peers.insert(banned_host1.address, banned_host1);
 
     // peer failure after alive not too early. return
     let mut connected_peers2 =
@@ -617,7 +722,7 @@ async fn test_get_out_connection_candidate_ips() {
             .checked_sub(11000.into())
             .unwrap(),
     );
-    peers.insert(connected_peers2.ip, connected_peers2);
+    peers.insert(connected_peers2.address, connected_peers2);
 
     // peer failure after alive too early. not return
     let mut connected_peers2 =
@@ -626,19 +731,19 @@ async fn test_get_out_connection_candidate_ips() {
         Some(MassaTime::now(0).unwrap().checked_sub(2000.into()).unwrap());
     connected_peers2.last_failure =
         Some(MassaTime::now(0).unwrap().checked_sub(1000.into()).unwrap());
-    peers.insert(connected_peers2.ip, connected_peers2);
+    peers.insert(connected_peers2.address, connected_peers2);
 
     // peer Ok, connected, not return
     let mut connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 17)));
     connected_peers1.active_out_connections = 1;
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
 
     // peer Ok, not advertised, not return
     let mut connected_peers1 =
         default_peer_info_not_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 18)));
     connected_peers1.advertised = false;
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
 
     let wakeup_interval = network_settings.wakeup_interval;
     let (saver_watch_tx, _) = watch::channel(peers.clone());
@@ -652,34 +757,563 @@ async fn test_get_out_connection_candidate_ips() {
         peer_types_connection_count: Default::default(),
         wakeup_interval,
         clock_compensation: 0,
+        reward_windows: Default::default(),
     };
 
     // test with no peers.
-    let ip_list = db.get_out_connection_candidate_ips().unwrap();
+    let ip_list = db.get_out_connection_candidate_addresses().unwrap();
     assert_eq!(4, ip_list.len());
 
     // first bootstrap peers
     assert_eq!(
-        IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)),
+        peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11))),
         ip_list[0]
     );
     // then whitelist
     // then standard
 
     assert_eq!(
-        IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 14)),
+        peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 14))),
         ip_list[1]
     );
     assert_eq!(
-        IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 15)),
+        peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 15))),
         ip_list[2]
     );
     assert_eq!(
-        IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13)),
+        peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13))),
         ip_list[3]
     );
 }
 
+#[tokio::test]
+#[serial]
+async fn test_peers_with_custom_ports() {
+    let peer_types_config = enum_map! {
+        PeerType::Standard => {
+            PeerTypeConnectionConfig {
+                target_out_connections: 5,
+                max_in_connections: 5,
+                max_out_attempts: 5,
+            }
+        },
+        PeerType::Bootstrap => Default::default(),
+        PeerType::WhiteListed => Default::default()
+    };
+    let network_settings = NetworkConfig {
+        peer_types_config,
+        max_in_connections_per_ip: 2,
+        ..Default::default()
+    };
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
+
+    // two peers behind the same ip, one of them on a custom port
+    let ip = IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11));
+    let default_port_peer = default_peer_info_not_connected(ip);
+    peers.insert(default_port_peer.address, default_port_peer);
+    let mut custom_port_peer = default_peer_info_not_connected(ip);
+    custom_port_peer.address = SocketAddr::new(ip, 4000);
+    peers.insert(custom_port_peer.address, custom_port_peer);
+
+    let wakeup_interval = network_settings.wakeup_interval;
+    let (saver_watch_tx, mut saver_watch_rx) = watch::channel(peers.clone());
+
+    let saver_join_handle =
+        tokio::spawn(async move { while let Ok(()) = saver_watch_rx.changed().await {} });
+
+    let mut db = PeerInfoDatabase {
+        network_settings,
+        peers,
+        saver_join_handle,
+        saver_watch_tx,
+        peer_types_connection_count: Default::default(),
+        wakeup_interval,
+        clock_compensation: 0,
+        reward_windows: Default::default(),
+    };
+
+    let mut addresses = db.get_addresses_for_ip(&ip);
+    addresses.sort();
+    assert_eq!(addresses, vec![SocketAddr::new(ip, 4000), peer_address(ip)]);
+
+    // outgoing connections use the advertised port
+    db.new_out_connection_attempt(&SocketAddr::new(ip, 4000))
+        .unwrap();
+    assert!(db
+        .try_out_connection_attempt_success(&SocketAddr::new(ip, 4000))
+        .unwrap());
+
+    // inbound connections are accounted under the default port, the limit applies per ip
+    assert_eq!(db.try_new_in_connection(&ip).unwrap(), peer_address(ip));
+    assert_eq!(db.try_new_in_connection(&ip).unwrap(), peer_address(ip));
+    if let Err(NetworkError::PeerConnectionError(
+        NetworkConnectionErrorType::MaxPeersConnectionReached(addr_err),
+    )) = db.try_new_in_connection(&ip)
+    {
+        assert_eq!(peer_address(ip), addr_err);
+    } else {
+        panic!("MaxPeersConnectionReached error not return");
+    }
+    db.in_connection_closed(&peer_address(ip)).unwrap();
+    db.in_connection_closed(&peer_address(ip)).unwrap();
+
+    // banning a peer behind an ip refuses inbound connections from that ip
+    db.peer_banned(&SocketAddr::new(ip, 4000), BanReason::Manual, None)
+        .unwrap();
+    if let Err(NetworkError::PeerConnectionError(
+        NetworkConnectionErrorType::BannedPeerTryingToConnect(addr_err),
+    )) = db.try_new_in_connection(&ip)
+    {
+        assert_eq!(peer_address(ip), addr_err);
+    } else {
+        panic!("BannedPeerTryingToConnect error not return");
+    }
+    db.unban(vec![ip]).unwrap();
+    db.try_new_in_connection(&ip)
+        .expect("in connection not accepted after unban.");
+
+    // advertised peers keep their port, peers without a valid port are ignored
+    let other_ip = IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 20));
+    db.merge_candidate_peers(&[
+        SocketAddr::new(other_ip, 4000),
+        peer_address(other_ip),
+        SocketAddr::new(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 21)), 0),
+    ])
+    .unwrap();
+    assert!(db.peers.contains_key(&SocketAddr::new(other_ip, 4000)));
+    assert!(db.peers.contains_key(&peer_address(other_ip)));
+    assert!(!db
+        .peers
+        .keys()
+        .any(|addr| addr.ip() == IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 21))));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_ban_applies_to_every_port() {
+    let peer_types_config = enum_map! {
+        PeerType::Standard => {
+            PeerTypeConnectionConfig {
+                target_out_connections: 5,
+                max_in_connections: 5,
+                max_out_attempts: 5,
+            }
+        },
+        PeerType::Bootstrap => Default::default(),
+        PeerType::WhiteListed => Default::default()
+    };
+    let network_settings = NetworkConfig {
+        peer_types_config,
+        ..Default::default()
+    };
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
+
+    // two peers behind the same ip on different ports, and a peer behind another ip
+    let ip = IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11));
+    let other_ip = IpAddr::V4(std::net::Ipv4Addr::new(170, 1, 0, 1));
+    let default_port_peer = default_peer_info_not_connected(ip);
+    peers.insert(default_port_peer.address, default_port_peer);
+    let mut custom_port_peer = default_peer_info_not_connected(ip);
+    custom_port_peer.address = SocketAddr::new(ip, 4000);
+    peers.insert(custom_port_peer.address, custom_port_peer);
+    let other_peer = default_peer_info_not_connected(other_ip);
+    peers.insert(other_peer.address, other_peer);
+
+    let wakeup_interval = network_settings.wakeup_interval;
+    let (saver_watch_tx, mut saver_watch_rx) = watch::channel(peers.clone());
+
+    let saver_join_handle =
+        tokio::spawn(async move { while let Ok(()) = saver_watch_rx.changed().await {} });
+
+    let mut db = PeerInfoDatabase {
+        network_settings,
+        peers,
+        saver_join_handle,
+        saver_watch_tx,
+        peer_types_connection_count: Default::default(),
+        wakeup_interval,
+        clock_compensation: 0,
+        reward_windows: Default::default(),
+    };
+
+    let mut candidates = db.get_out_connection_candidate_addresses().unwrap();
+    candidates.sort();
+    assert_eq!(
+        candidates,
+        vec![
+            SocketAddr::new(ip, 4000),
+            peer_address(ip),
+            peer_address(other_ip)
+        ]
+    );
+
+    // banning one port of an ip stops dialing and advertising the other ports of that ip
+    db.peer_banned(&SocketAddr::new(ip, 4000), BanReason::Manual, None)
+        .unwrap();
+    assert_eq!(
+        db.get_out_connection_candidate_addresses().unwrap(),
+        vec![peer_address(other_ip)]
+    );
+    assert!(!db
+        .get_advertisable_peer_addresses()
+        .iter()
+        .any(|addr| addr.ip() == ip));
+
+    // a dial already in flight to another port of the banned ip does not succeed
+    db.unban(vec![ip]).unwrap();
+    db.new_out_connection_attempt(&peer_address(ip)).unwrap();
+    db.peer_banned(&SocketAddr::new(ip, 4000), BanReason::Manual, None)
+        .unwrap();
+    assert!(!db
+        .try_out_connection_attempt_success(&peer_address(ip))
+        .unwrap());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_peer_scores() {
+    let peer_types_config = enum_map! {
+        PeerType::Standard => {
+            PeerTypeConnectionConfig {
+                target_out_connections: 1,
+                max_in_connections: 5,
+                max_out_attempts: 1,
+            }
+        },
+        PeerType::Bootstrap => Default::default(),
+        PeerType::WhiteListed => Default::default()
+    };
+    let network_settings = NetworkConfig {
+        peer_types_config,
+        ..Default::default()
+    };
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
+
+    let good_ip = IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11));
+    let bad_ip = IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12));
+    let good_peer = default_peer_info_not_connected(good_ip);
+    peers.insert(good_peer.address, good_peer);
+    let bad_peer = default_peer_info_not_connected(bad_ip);
+    peers.insert(bad_peer.address, bad_peer);
+
+    let wakeup_interval = network_settings.wakeup_interval;
+    let (saver_watch_tx, mut saver_watch_rx) = watch::channel(peers.clone());
+
+    let saver_join_handle =
+        tokio::spawn(async move { while let Ok(()) = saver_watch_rx.changed().await {} });
+
+    let mut db = PeerInfoDatabase {
+        network_settings,
+        peers,
+        saver_join_handle,
+        saver_watch_tx,
+        peer_types_connection_count: Default::default(),
+        wakeup_interval,
+        clock_compensation: 0,
+        reward_windows: Default::default(),
+    };
+
+    // unknown peers are ignored
+    assert!(!db
+        .peer_score_event(
+            &peer_address(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 13))),
+            &PeerScoreEvent::InvalidData
+        )
+        .unwrap());
+
+    // the best scored peer is preferred for the only outgoing slot
+    assert!(!db
+        .peer_score_event(&peer_address(bad_ip), &PeerScoreEvent::Timeout)
+        .unwrap());
+    assert_eq!(
+        db.get_out_connection_candidate_addresses().unwrap(),
+        vec![peer_address(good_ip)]
+    );
+
+    // the bad peer takes the slot, then gets evicted once the good peer scores higher
+    db.new_out_connection_attempt(&peer_address(bad_ip))
+        .unwrap();
+    assert!(db
+        .try_out_connection_attempt_success(&peer_address(bad_ip))
+        .unwrap());
+    assert!(db.get_eviction_candidate_addresses().unwrap().is_empty());
+    for _ in 0..20 {
+        db.peer_score_event(&peer_address(good_ip), &PeerScoreEvent::UsefulBlock)
+            .unwrap();
+    }
+    assert_eq!(
+        db.get_eviction_candidate_addresses().unwrap(),
+        vec![peer_address(bad_ip)]
+    );
+
+    // scores are clamped
+    for _ in 0..200 {
+        db.peer_score_event(&peer_address(good_ip), &PeerScoreEvent::UsefulBlock)
+            .unwrap();
+    }
+    let good_peer = db.get_peers()[&peer_address(good_ip)];
+    assert!(db.get_peer_score(&good_peer).unwrap() <= 100.0);
+
+    // invalid data drives the score down to the ban threshold
+    assert!(!db
+        .peer_score_event(&peer_address(bad_ip), &PeerScoreEvent::InvalidData)
+        .unwrap());
+    assert!(db
+        .peer_score_event(&peer_address(bad_ip), &PeerScoreEvent::InvalidData)
+        .unwrap());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_peer_reward_rate_limit() {
+    let network_settings = NetworkConfig {
+        peer_score_config: PeerScoreConfig {
+            max_rewards_per_window: 2,
+            reward_window: MassaTime::from_millis(3_600_000),
+            half_life: MassaTime::from_millis(0),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
+    let ip = IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11));
+    let peer = default_peer_info_not_connected(ip);
+    peers.insert(peer.address, peer);
+
+    let wakeup_interval = network_settings.wakeup_interval;
+    let (saver_watch_tx, mut saver_watch_rx) = watch::channel(peers.clone());
+
+    let saver_join_handle =
+        tokio::spawn(async move { while let Ok(()) = saver_watch_rx.changed().await {} });
+
+    let mut db = PeerInfoDatabase {
+        network_settings,
+        peers,
+        saver_join_handle,
+        saver_watch_tx,
+        peer_types_connection_count: Default::default(),
+        wakeup_interval,
+        clock_compensation: 0,
+        reward_windows: Default::default(),
+    };
+    let score = |db: &PeerInfoDatabase| {
+        db.get_peer_score(&db.get_peers()[&peer_address(ip)])
+            .unwrap()
+    };
+
+    // rewards over the window budget are ignored
+    for _ in 0..3 {
+        db.peer_score_event(&peer_address(ip), &PeerScoreEvent::UsefulBlock)
+            .unwrap();
+    }
+    assert_eq!(score(&db), 2.0);
+
+    // penalties are always applied
+    db.peer_score_event(&peer_address(ip), &PeerScoreEvent::Timeout)
+        .unwrap();
+    assert_eq!(score(&db), -3.0);
+    db.peer_score_event(&peer_address(ip), &PeerScoreEvent::UsefulBlock)
+        .unwrap();
+    assert_eq!(score(&db), -3.0);
+
+    // the budget is restored once the window ends
+    db.reward_windows.get_mut(&peer_address(ip)).unwrap().0 = MassaTime::from_millis(0);
+    db.peer_score_event(&peer_address(ip), &PeerScoreEvent::UsefulBlock)
+        .unwrap();
+    assert_eq!(score(&db), -2.0);
+
+    // the windows of forgotten peers are dropped
+    db.peers.clear();
+    db.update().unwrap();
+    assert!(db.reward_windows.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_network_groups_and_anchors() {
+    // IPv4 peers are grouped by /16, IPv6 peers by /32
+    assert_eq!(
+        network_group(&"169.202.3.4".parse().unwrap()),
+        "169.202.0.0".parse::<IpAddr>().unwrap()
+    );
+    assert_eq!(
+        network_group(&"::ffff:169.202.3.4".parse().unwrap()),
+        "169.202.0.0".parse::<IpAddr>().unwrap()
+    );
+    assert_eq!(
+        network_group(&"2001:db8:1:2::1".parse().unwrap()),
+        "2001:db8::".parse::<IpAddr>().unwrap()
+    );
+
+    let peer_types_config = enum_map! {
+        PeerType::Standard => {
+            PeerTypeConnectionConfig {
+                target_out_connections: 3,
+                max_in_connections: 5,
+                max_out_attempts: 3,
+            }
+        },
+        PeerType::Bootstrap => Default::default(),
+        PeerType::WhiteListed => Default::default()
+    };
+    let network_settings = NetworkConfig {
+        peer_types_config,
+        max_out_connections_per_network_group: 1,
+        max_anchor_peers: 1,
+        ..Default::default()
+    };
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
+
+    let same_group_ip = IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11));
+    let anchor_ip = IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12));
+    let other_group_ip = IpAddr::V4(std::net::Ipv4Addr::new(170, 1, 0, 11));
+    for ip in [same_group_ip, anchor_ip, other_group_ip] {
+        let peer = default_peer_info_not_connected(ip);
+        peers.insert(peer.address, peer);
+    }
+    peers.get_mut(&peer_address(anchor_ip)).unwrap().anchor = true;
+
+    let wakeup_interval = network_settings.wakeup_interval;
+    let (saver_watch_tx, mut saver_watch_rx) = watch::channel(peers.clone());
+
+    let saver_join_handle =
+        tokio::spawn(async move { while let Ok(()) = saver_watch_rx.changed().await {} });
+
+    let mut db = PeerInfoDatabase {
+        network_settings,
+        peers,
+        saver_join_handle,
+        saver_watch_tx,
+        peer_types_connection_count: Default::default(),
+        wakeup_interval,
+        clock_compensation: 0,
+        reward_windows: Default::default(),
+    };
+
+    // the anchor comes first, and only one peer per network group is picked
+    let candidates = db.get_out_connection_candidate_addresses().unwrap();
+    assert_eq!(
+        candidates,
+        vec![peer_address(anchor_ip), peer_address(other_group_ip)]
+    );
+
+    // connecting to an anchor clears the flag, and its network group is then full
+    db.new_out_connection_attempt(&peer_address(anchor_ip))
+        .unwrap();
+    assert!(db
+        .try_out_connection_attempt_success(&peer_address(anchor_ip))
+        .unwrap());
+    assert!(!db.get_peers()[&peer_address(anchor_ip)].anchor);
+    assert_eq!(
+        db.get_out_connection_candidate_addresses().unwrap(),
+        vec![peer_address(other_group_ip)]
+    );
+
+    // the lowest scored outgoing peer is rotated out while a candidate is ready
+    db.new_out_connection_attempt(&peer_address(other_group_ip))
+        .unwrap();
+    assert!(db
+        .try_out_connection_attempt_success(&peer_address(other_group_ip))
+        .unwrap());
+    db.peer_score_event(&peer_address(other_group_ip), &PeerScoreEvent::Timeout)
+        .unwrap();
+    assert_eq!(
+        db.get_rotation_candidate_address().unwrap(),
+        Some(peer_address(other_group_ip))
+    );
+
+    // the best scored outgoing peers are kept as anchors
+    db.mark_anchor_peers().unwrap();
+    assert!(db.get_peers()[&peer_address(anchor_ip)].anchor);
+    assert!(!db.get_peers()[&peer_address(other_group_ip)].anchor);
+    assert!(!db.get_peers()[&peer_address(same_group_ip)].anchor);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_ban_expiry() {
+    let network_settings = NetworkConfig::default();
+    let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
+
+    let short_ip = IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11));
+    let long_ip = IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12));
+    let short_peer = default_peer_info_not_connected(short_ip);
+    peers.insert(short_peer.address, short_peer);
+    let long_peer = default_peer_info_not_connected(long_ip);
+    peers.insert(long_peer.address, long_peer);
+
+    let wakeup_interval = network_settings.wakeup_interval;
+    let (saver_watch_tx, mut saver_watch_rx) = watch::channel(peers.clone());
+
+    let saver_join_handle =
+        tokio::spawn(async move { while let Ok(()) = saver_watch_rx.changed().await {} });
+
+    let mut db = PeerInfoDatabase {
+        network_settings,
+        peers,
+        saver_join_handle,
+        saver_watch_tx,
+        peer_types_connection_count: Default::default(),
+        wakeup_interval,
+        clock_compensation: 0,
+        reward_windows: Default::default(),
+    };
+
+    // ban one peer for a short time, the other one for the default duration
+    let before = MassaTime::now(0).unwrap();
+    db.peer_banned(
+        &peer_address(short_ip),
+        BanReason::InvalidOperation,
+        Some(MassaTime::from_millis(100)),
+    )
+    .unwrap();
+    db.peer_banned(&peer_address(long_ip), BanReason::Manual, None)
+        .unwrap();
+
+    let short_peer = db.get_peers()[&peer_address(short_ip)];
+    assert!(short_peer.banned);
+    let ban = short_peer.ban.expect("ban not recorded");
+    assert_eq!(ban.reason, BanReason::InvalidOperation);
+    assert!(ban.banned_at >= before);
+    assert_eq!(
+        ban.expires_at,
+        ban.banned_at.saturating_add(MassaTime::from_millis(100))
+    );
+    let long_ban = db.get_peers()[&peer_address(long_ip)]
+        .ban
+        .expect("ban not recorded");
+    assert_eq!(long_ban.reason, BanReason::Manual);
+    assert_eq!(
+        long_ban.expires_at,
+        long_ban
+            .banned_at
+            .saturating_add(db.network_settings.ban_timeout)
+    );
+
+    // bans with a record survive a reload of the peers file
+    let mut reloaded: PeerInfo =
+        serde_json::from_value(serde_json::to_value(short_peer).unwrap()).unwrap();
+    reloaded.cleanup(db.network_settings.protocol_port);
+    assert!(reloaded.banned);
+    assert_eq!(reloaded.ban, Some(ban));
+
+    // the short ban expires, the other one stays
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    db.update().unwrap();
+    let short_peer = db.get_peers()[&peer_address(short_ip)];
+    assert!(!short_peer.banned);
+    assert!(short_peer.ban.is_none());
+    assert!(db.get_peers()[&peer_address(long_ip)].banned);
+    db.try_new_in_connection(&short_ip)
+        .expect("in connection not accepted after ban expiry.");
+
+    // unbanning clears the ban record
+    db.unban(vec![long_ip]).unwrap();
+    let long_peer = db.get_peers()[&peer_address(long_ip)];
+    assert!(!long_peer.banned);
+    assert!(long_peer.ban.is_none());
+}
+
 #[tokio::test]
 #[serial]
 async fn test_cleanup_peers() {
@@ -707,7 +1341,7 @@ async fn test_cleanup_peers() {
         default_peer_info_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)));
     connected_peers1.last_alive =
         Some(MassaTime::now(0).unwrap().checked_sub(1000.into()).unwrap());
-    peers.insert(connected_peers1.ip, connected_peers1);
+    peers.insert(connected_peers1.address, connected_peers1);
 
     let mut connected_peers2 =
         default_peer_info_connected(IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12)));
@@ -753,16 +1387,16 @@ async fn test_cleanup_peers() {
     advertised_host2.active_out_connections = 0;
     advertised_host2.last_alive = Some(now.checked_sub(900.into()).unwrap());
 
-    peers.insert(advertised_host1.ip, advertised_host1);
-    peers.insert(banned_host1.ip, banned_host1);
-    peers.insert(non_global.ip, non_global);
-    peers.insert(same_connected_peer.ip, same_connected_peer);
-    peers.insert(connected_peers2.ip, connected_peers2);
-    peers.insert(connected_peers1.ip, connected_peers1);
-    peers.insert(advertised_host2.ip, advertised_host2);
-    peers.insert(same_host.ip, same_host);
-    peers.insert(banned_host3.ip, banned_host3);
-    peers.insert(banned_host2.ip, banned_host2);
+    peers.insert(advertised_host1.address, advertised_host1);
+    peers.insert(banned_host1.address, banned_host1);
+    peers.insert(non_global.address, non_global);
+    peers.insert(same_connected_peer.address, same_connected_peer);
+    peers.insert(connected_peers2.address, connected_peers2);
+    peers.insert(connected_peers1.address, connected_peers1);
+    peers.insert(advertised_host2.address, advertised_host2);
+    peers.insert(same_host.address, same_host);
+    peers.insert(banned_host3.address, banned_host3);
+    peers.insert(banned_host2.address, banned_host2);
 
     cleanup_peers(
         &network_settings,
@@ -773,24 +1407,55 @@ async fn test_cleanup_peers() {
     )
     .unwrap();
 
-    assert!(peers.contains_key(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11))));
-    assert!(peers.contains_key(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 12))));
+    assert!(
+        peers.contains_key(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 11
+        ))))
+    );
+    assert!(
+        peers.contains_key(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 12
+        ))))
+    );
 
-    assert!(peers.contains_key(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 23))));
-    assert!(!peers.contains_key(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 24))));
-    assert!(peers.contains_key(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 25))));
+    assert!(
+        peers.contains_key(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 23
+        ))))
+    );
+    assert!(
+        !peers.contains_key(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 24
+        ))))
+    );
+    assert!(
+        peers.contains_key(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 25
+        ))))
+    );
 
-    assert!(!peers.contains_key(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 35))));
-    assert!(peers.contains_key(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 36))));
+    assert!(
+        !peers.contains_key(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 35
+        ))))
+    );
+    assert!(
+        peers.contains_key(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 36
+        ))))
+    );
 
     // test with advertised peers
-    let advertised = vec![
+    let advertised: Vec<SocketAddr> = vec![
         IpAddr::V4(std::net::Ipv4Addr::new(192, 168, 0, 10)),
         IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 43)),
         IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 11)),
         IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 44)),
         IpAddr::V4(std::net::Ipv4Addr::new(127, 0, 0, 1)),
-    ];
+    ]
+    .into_iter()
+    .map(peer_address)
+    .collect();
 
     network_settings.max_idle_peers = 5;
 
@@ -803,7 +1468,11 @@ async fn test_cleanup_peers() {
     )
     .unwrap();
 
-    assert!(peers.contains_key(&IpAddr::V4(std::net::Ipv4Addr::new(169, 202, 0, 43))));
+    assert!(
+        peers.contains_key(&peer_address(IpAddr::V4(std::net::Ipv4Addr::new(
+            169, 202, 0, 43
+        ))))
+    );
 }
 
 #[tokio::test]
@@ -814,9 +1483,14 @@ async fn test() {
     assert!(!p.is_active());
 }
 
+/// Address of a peer listening on the default protocol port
+fn peer_address(ip: IpAddr) -> SocketAddr {
+    SocketAddr::new(ip, NetworkConfig::default().protocol_port)
+}
+
 fn default_peer_info_connected(ip: IpAddr) -> PeerInfo {
     PeerInfo {
-        ip,
+        address: peer_address(ip),
         peer_type: PeerType::Standard,
         last_alive: None,
         last_failure: None,
@@ -825,12 +1499,16 @@ fn default_peer_info_connected(ip: IpAddr) -> PeerInfo {
         active_out_connections: 1,
         active_in_connections: 0,
         banned: false,
+        ban: None,
+        anchor: false,
+        score: 0.0,
+        last_score_update: None,
     }
 }
 
 fn default_peer_info_not_connected(ip: IpAddr) -> PeerInfo {
     PeerInfo {
-        ip,
+        address: peer_address(ip),
         peer_type: PeerType::Standard,
         last_alive: None,
         last_failure: None,
@@ -839,6 +1517,10 @@ fn default_peer_info_not_connected(ip: IpAddr) -> PeerInfo {
         active_out_connections: 0,
         active_in_connections: 0,
         banned: false,
+        ban: None,
+        anchor: false,
+        score: 0.0,
+        last_score_update: None,
     }
 }
 
@@ -846,11 +1528,11 @@ impl From<u32> for PeerInfoDatabase {
     fn from(peers_number: u32) -> Self {
         use rand::Rng;
         let mut rng = rand::thread_rng();
-        let mut peers: HashMap<IpAddr, PeerInfo> = HashMap::new();
+        let mut peers: HashMap<SocketAddr, PeerInfo> = HashMap::new();
         for i in 0..peers_number {
             let ip: [u8; 4] = [rng.gen(), rng.gen(), rng.gen(), rng.gen()];
             let peer = PeerInfo {
-                ip: IpAddr::from(ip),
+                address: peer_address(IpAddr::from(ip)),
                 peer_type: match ip[0] % 5 {
                     0 | 1 => PeerType::Bootstrap,
                     2 | 3 | 4 => PeerType::Standard,
@@ -879,8 +1561,12 @@ impl From<u32> for PeerInfoDatabase {
                 active_out_connections: 0,
                 active_in_connections: 0,
                 banned: ip[1] % 5 == 0,
+                ban: None,
+                anchor: false,
+                score: 0.0,
+                last_score_update: None,
             };
-            peers.insert(peer.ip, peer);
+            peers.insert(peer.address, peer);
         }
         let network_settings = NetworkConfig::default();
         let wakeup_interval = network_settings.wakeup_interval;
@@ -894,6 +1580,7 @@ impl From<u32> for PeerInfoDatabase {
             peer_types_connection_count: Default::default(),
             wakeup_interval,
             clock_compensation: 0,
+            reward_windows: Default::default(),
         }
     }
 }
@@ -21,7 +21,8 @@ use massa_models::{
 };
 use massa_network_exports::test_exports::mock_establisher::{self, MockEstablisherInterface};
 use massa_network_exports::{
-    ConnectionId, NetworkCommandSender, NetworkEventReceiver, NetworkManager, PeerInfo,
+    ConnectionId, NetworkCommandSender, NetworkEventReceiver, NetworkManager, NodeCapabilities,
+    PeerInfo,
 };
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
@@ -94,6 +95,8 @@ pub async fn full_connection_to_controller(
         connection_id,
         f64::INFINITY,
         f64::INFINITY,
+        NodeCapabilities::default(),
+        None,
     )
     .await
     .expect("handshake creation failed")
@@ -105,7 +108,7 @@ pub async fn full_connection_to_controller(
         network_event_receiver,
         event_timeout_ms.into(),
         |msg| match msg {
-            NetworkEvent::NewConnection(conn_node_id) => {
+            NetworkEvent::NewConnection(conn_node_id, _) => {
                 if conn_node_id == mock_node_id {
                     Some(())
                 } else {
@@ -153,6 +156,8 @@ pub async fn rejected_connection_to_controller(
         connection_id,
         f64::INFINITY,
         f64::INFINITY,
+        NodeCapabilities::default(),
+        None,
     )
     .await
     .expect("handshake creation failed")
@@ -169,7 +174,7 @@ pub async fn rejected_connection_to_controller(
         network_event_receiver,
         event_timeout_ms.into(),
         |msg| match msg {
-            NetworkEvent::NewConnection(conn_node_id) => {
+            NetworkEvent::NewConnection(conn_node_id, _) => {
                 if conn_node_id == mock_node_id {
                     Some(())
                 } else {
@@ -238,6 +243,8 @@ pub async fn full_connection_from_controller(
         connection_id,
         f64::INFINITY,
         f64::INFINITY,
+        NodeCapabilities::default(),
+        None,
     )
     .await
     .expect("handshake creation failed")
@@ -249,7 +256,7 @@ pub async fn full_connection_from_controller(
         network_event_receiver,
         event_timeout_ms.into(),
         |evt| match evt {
-            NetworkEvent::NewConnection(node_id) => {
+            NetworkEvent::NewConnection(node_id, _) => {
                 if node_id == mock_node_id {
                     Some(())
                 } else {
@@ -7,9 +7,12 @@ extern crate massa_logging;
 use crate::settings::SETTINGS;
 
 use dialoguer::Password;
-use massa_api::{APIConfig, Private, Public, RpcServer, StopHandle, API};
+use massa_api::{APIConfig, ContractRegistry, Light, Private, Public, RpcServer, StopHandle, API};
 use massa_async_pool::AsyncPoolConfig;
-use massa_bootstrap::{get_state, start_bootstrap_server, BootstrapConfig, BootstrapManager};
+use massa_bootstrap::{
+    get_light_state, get_state, start_bootstrap_server, BootstrapConfig, BootstrapManager,
+    GlobalBootstrapState, LightBootstrapState,
+};
 use massa_consensus_exports::ConsensusManager;
 use massa_consensus_exports::{
     events::ConsensusEvent, settings::ConsensusChannels, ConsensusConfig, ConsensusEventReceiver,
@@ -25,30 +28,35 @@ use massa_ledger_worker::FinalLedger;
 use massa_logging::massa_trace;
 use massa_models::address::Address;
 use massa_models::config::constants::{
-    BLOCK_REWARD, BOOTSTRAP_RANDOMNESS_SIZE_BYTES, ENDORSEMENT_COUNT, END_TIMESTAMP, GENESIS_KEY,
-    GENESIS_TIMESTAMP, INITIAL_DRAW_SEED, LEDGER_PART_SIZE_MESSAGE_BYTES, MAX_ADVERTISE_LENGTH,
-    MAX_ASK_BLOCKS_PER_MESSAGE, MAX_ASYNC_GAS, MAX_ASYNC_POOL_LENGTH, MAX_BLOCK_SIZE,
-    MAX_BOOTSTRAP_ASYNC_POOL_CHANGES, MAX_BOOTSTRAP_BLOCKS, MAX_BOOTSTRAP_ERROR_LENGTH,
-    MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE, MAX_BOOTSTRAP_MESSAGE_SIZE, MAX_DATASTORE_ENTRY_COUNT,
-    MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH, MAX_DATA_ASYNC_MESSAGE,
-    MAX_ENDORSEMENTS_PER_MESSAGE, MAX_FUNCTION_NAME_LENGTH, MAX_GAS_PER_BLOCK,
-    MAX_LEDGER_CHANGES_COUNT, MAX_MESSAGE_SIZE, MAX_OPERATIONS_PER_BLOCK, MAX_PARAMETERS_SIZE,
-    NETWORK_CONTROLLER_CHANNEL_SIZE, NETWORK_EVENT_CHANNEL_SIZE, OPERATION_VALIDITY_PERIODS,
-    PERIODS_PER_CYCLE, ROLL_PRICE, T0, THREAD_COUNT, VERSION,
+    ASYNC_MESSAGE_CANCELLATION_START_PERIOD, BLOCK_REWARD, BOOTSTRAP_RANDOMNESS_SIZE_BYTES,
+    BYTECODE_METADATA_START_PERIOD, ENDORSEMENT_COUNT, END_TIMESTAMP, GAS_REFUND_START_PERIOD,
+    GENESIS_KEY, GENESIS_TIMESTAMP, INITIAL_DRAW_SEED, LEDGER_PART_SIZE_MESSAGE_BYTES,
+    MAX_ADVERTISE_LENGTH, MAX_ASK_BLOCKS_PER_MESSAGE, MAX_ASYNC_GAS, MAX_ASYNC_POOL_LENGTH,
+    MAX_BLOCK_SIZE, MAX_BOOTSTRAP_ASYNC_POOL_CHANGES, MAX_BOOTSTRAP_BLOCKS,
+    MAX_BOOTSTRAP_ERROR_LENGTH, MAX_BOOTSTRAP_FINAL_STATE_PARTS_SIZE, MAX_BOOTSTRAP_MESSAGE_SIZE,
+    MAX_DATASTORE_ENTRY_COUNT, MAX_DATASTORE_KEY_LENGTH, MAX_DATASTORE_VALUE_LENGTH,
+    MAX_DATA_ASYNC_MESSAGE, MAX_ENDORSEMENTS_PER_MESSAGE, MAX_FUNCTION_NAME_LENGTH,
+    MAX_GAS_PER_BLOCK, MAX_LEDGER_CHANGES_COUNT, MAX_MESSAGE_SIZE, MAX_OPERATIONS_PER_BLOCK,
+    MAX_PARAMETERS_SIZE, MULTISIG_START_PERIOD, NETWORK_CONTROLLER_CHANNEL_SIZE,
+    NETWORK_EVENT_CHANNEL_SIZE, OPERATION_VALIDITY_PERIODS, PERIODS_PER_CYCLE, ROLL_PRICE, T0,
+    THREAD_COUNT, VERSION,
 };
 use massa_models::config::{
     ASYNC_POOL_PART_SIZE_MESSAGE_BYTES, CHANNEL_SIZE, DELTA_F0, NETWORK_NODE_COMMAND_CHANNEL_SIZE,
     NETWORK_NODE_EVENT_CHANNEL_SIZE, POS_MISS_RATE_DEACTIVATION_THRESHOLD,
     PROTOCOL_CONTROLLER_CHANNEL_SIZE, PROTOCOL_EVENT_CHANNEL_SIZE,
 };
-use massa_network_exports::{Establisher, NetworkConfig, NetworkManager};
+use massa_network_exports::{Establisher, NetworkConfig, NetworkManager, NodeCapabilities};
 use massa_network_worker::start_network_controller;
 use massa_pool_exports::{PoolConfig, PoolController};
 use massa_pool_worker::start_pool;
-use massa_pos_exports::{SelectorConfig, SelectorManager};
+use massa_pos_exports::{PoSFinalState, SelectorConfig, SelectorController, SelectorManager};
 use massa_pos_worker::start_selector_worker;
-use massa_protocol_exports::{ProtocolConfig, ProtocolManager};
-use massa_protocol_worker::start_protocol_controller;
+use massa_protocol_exports::{
+    LightClientConfig, LightClientEvent, LightClientEventReceiver, LightClientManager,
+    ProtocolConfig, ProtocolManager,
+};
+use massa_protocol_worker::{start_light_client, start_protocol_controller};
 use massa_storage::Storage;
 use massa_time::MassaTime;
 use massa_wallet::Wallet;
@@ -63,32 +71,25 @@ use tracing_subscriber::filter::{filter_fn, LevelFilter};
 
 mod settings;
 
-async fn launch(
-    node_wallet: Arc<RwLock<Wallet>>,
-) -> (
-    ConsensusEventReceiver,
-    Option<BootstrapManager>,
-    ConsensusManager,
-    Box<dyn ExecutionManager>,
+/// Launches the selector worker
+fn start_selector() -> (Box<dyn SelectorManager>, Box<dyn SelectorController>) {
+    start_selector_worker(SelectorConfig {
+        max_draw_cache: SETTINGS.selector.max_draw_cache,
+        channel_size: CHANNEL_SIZE,
+        thread_count: THREAD_COUNT,
+        endorsement_count: ENDORSEMENT_COUNT,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        genesis_address: Address::from_public_key(&GENESIS_KEY.get_public_key()),
+    })
+    .expect("could not start selector worker")
+}
+
+/// Initializes the final state and the selector worker fed by it
+fn init_final_state() -> (
     Box<dyn SelectorManager>,
-    Box<dyn PoolController>,
-    ProtocolManager,
-    NetworkManager,
-    Box<dyn FactoryManager>,
-    mpsc::Receiver<()>,
-    StopHandle,
-    StopHandle,
+    Box<dyn SelectorController>,
+    Arc<RwLock<FinalState>>,
 ) {
-    info!("Node version : {}", *VERSION);
-    if let Some(end) = *END_TIMESTAMP {
-        if MassaTime::now(0).expect("could not get now time") > end {
-            panic!("This episode has come to an end, please get the latest testnet node version to continue");
-        }
-    }
-
-    // Storage shared by multiple components.
-    let shared_storage: Storage = Storage::create_root();
-
     // init final state
     let ledger_config = LedgerConfig {
         thread_count: THREAD_COUNT,
@@ -124,15 +125,7 @@ async fn launch(
     let ledger = FinalLedger::new(ledger_config.clone()).expect("could not init final ledger");
 
     // launch selector worker
-    let (selector_manager, selector_controller) = start_selector_worker(SelectorConfig {
-        max_draw_cache: SETTINGS.selector.max_draw_cache,
-        channel_size: CHANNEL_SIZE,
-        thread_count: THREAD_COUNT,
-        endorsement_count: ENDORSEMENT_COUNT,
-        periods_per_cycle: PERIODS_PER_CYCLE,
-        genesis_address: Address::from_public_key(&GENESIS_KEY.get_public_key()),
-    })
-    .expect("could not start selector worker");
+    let (selector_manager, selector_controller) = start_selector();
 
     // Create final state
     let final_state = Arc::new(parking_lot::RwLock::new(
@@ -144,11 +137,11 @@ async fn launch(
         .expect("could not init final state"),
     ));
 
-    // interrupt signal listener
-    let stop_signal = signal::ctrl_c();
-    tokio::pin!(stop_signal);
+    (selector_manager, selector_controller, final_state)
+}
 
-    let bootstrap_config: BootstrapConfig = BootstrapConfig {
+fn bootstrap_config() -> BootstrapConfig {
+    BootstrapConfig {
         bootstrap_list: SETTINGS.bootstrap.bootstrap_list.clone(),
         bind: SETTINGS.bootstrap.bind,
         connect_timeout: SETTINGS.bootstrap.connect_timeout,
@@ -171,6 +164,7 @@ async fn launch(
         periods_per_cycle: PERIODS_PER_CYCLE,
         endorsement_count: ENDORSEMENT_COUNT,
         max_advertise_length: MAX_ADVERTISE_LENGTH,
+        protocol_port: SETTINGS.network.protocol_port,
         max_bootstrap_async_pool_changes: MAX_BOOTSTRAP_ASYNC_POOL_CHANGES,
         max_bootstrap_blocks_length: MAX_BOOTSTRAP_BLOCKS,
         max_bootstrap_error_length: MAX_BOOTSTRAP_ERROR_LENGTH,
@@ -183,18 +177,27 @@ async fn launch(
         max_ledger_changes_count: MAX_LEDGER_CHANGES_COUNT,
         max_parameters_size: MAX_PARAMETERS_SIZE,
         max_changes_slot_count: SETTINGS.ledger.final_history_length as u32,
-    };
+    }
+}
+
+/// Bootstraps the final state and returns the rest of the bootstrapped state
+async fn bootstrap(
+    bootstrap_config: &BootstrapConfig,
+    final_state: Arc<RwLock<FinalState>>,
+) -> GlobalBootstrapState {
+    // interrupt signal listener
+    let stop_signal = signal::ctrl_c();
+    tokio::pin!(stop_signal);
 
-    // bootstrap
-    let bootstrap_state = tokio::select! {
+    tokio::select! {
         _ = &mut stop_signal => {
             info!("interrupt signal received in bootstrap loop");
             process::exit(0);
         },
         res = get_state(
-            &bootstrap_config,
-            final_state.clone(),
-            massa_bootstrap::types::Establisher::new(),
+            bootstrap_config,
+            final_state,
+            massa_bootstrap::types::Establisher::with_proxy(SETTINGS.bootstrap.proxy.clone()),
             *VERSION,
             *GENESIS_TIMESTAMP,
             *END_TIMESTAMP,
@@ -202,11 +205,44 @@ async fn launch(
             Ok(vals) => vals,
             Err(err) => panic!("critical error detected in the bootstrap process: {}", err)
         }
-    };
+    }
+}
+
+/// Bootstraps the final headers and the Proof of Stake cycle history of a light client
+async fn light_bootstrap(
+    bootstrap_config: &BootstrapConfig,
+    pos_state: &mut PoSFinalState,
+) -> LightBootstrapState {
+    // interrupt signal listener
+    let stop_signal = signal::ctrl_c();
+    tokio::pin!(stop_signal);
 
-    let network_config: NetworkConfig = NetworkConfig {
+    tokio::select! {
+        _ = &mut stop_signal => {
+            info!("interrupt signal received in bootstrap loop");
+            process::exit(0);
+        },
+        res = get_light_state(
+            bootstrap_config,
+            pos_state,
+            massa_bootstrap::types::Establisher::with_proxy(SETTINGS.bootstrap.proxy.clone()),
+            *VERSION,
+            *GENESIS_TIMESTAMP,
+            *END_TIMESTAMP,
+        ) => match res {
+            Ok(vals) => vals,
+            Err(err) => panic!("critical error detected in the light bootstrap process: {}", err)
+        }
+    }
+}
+
+fn network_config(node_capabilities: NodeCapabilities) -> NetworkConfig {
+    NetworkConfig {
         bind: SETTINGS.network.bind,
-        routable_ip: SETTINGS.network.routable_ip,
+        routable_ip: SETTINGS
+            .network
+            .routable_ip
+            .filter(|_| !SETTINGS.network.hide_routable_ip),
         protocol_port: SETTINGS.network.protocol_port,
         connect_timeout: SETTINGS.network.connect_timeout,
         wakeup_interval: SETTINGS.network.wakeup_interval,
@@ -214,7 +250,13 @@ async fn launch(
         peers_file: SETTINGS.network.peers_file.clone(),
         keypair_file: SETTINGS.network.keypair_file.clone(),
         peer_types_config: SETTINGS.network.peer_types_config.clone(),
+        peer_score_config: SETTINGS.network.peer_score_config.clone(),
         max_in_connections_per_ip: SETTINGS.network.max_in_connections_per_ip,
+        max_out_connections_per_network_group: SETTINGS
+            .network
+            .max_out_connections_per_network_group,
+        max_anchor_peers: SETTINGS.network.max_anchor_peers,
+        out_connection_rotation_interval: SETTINGS.network.out_connection_rotation_interval,
         max_idle_peers: SETTINGS.network.max_idle_peers,
         max_banned_peers: SETTINGS.network.max_banned_peers,
         peers_file_dump_interval: SETTINGS.network.peers_file_dump_interval,
@@ -227,6 +269,9 @@ async fn launch(
         max_operations_per_message: SETTINGS.network.max_operations_per_message,
         max_bytes_read: SETTINGS.network.max_bytes_read,
         max_bytes_write: SETTINGS.network.max_bytes_write,
+        enable_message_compression: SETTINGS.network.enable_message_compression,
+        message_compression_min_size: SETTINGS.network.message_compression_min_size,
+        capabilities: node_capabilities,
         max_ask_blocks: MAX_ASK_BLOCKS_PER_MESSAGE,
         max_operations_per_block: MAX_OPERATIONS_PER_BLOCK,
         thread_count: THREAD_COUNT,
@@ -241,13 +286,56 @@ async fn launch(
         event_channel_size: NETWORK_EVENT_CHANNEL_SIZE,
         node_command_channel_size: NETWORK_NODE_COMMAND_CHANNEL_SIZE,
         node_event_channel_size: NETWORK_NODE_EVENT_CHANNEL_SIZE,
-    };
+    }
+}
+
+async fn launch(
+    node_wallet: Arc<RwLock<Wallet>>,
+) -> (
+    ConsensusEventReceiver,
+    Option<BootstrapManager>,
+    ConsensusManager,
+    Box<dyn ExecutionManager>,
+    Box<dyn SelectorManager>,
+    Box<dyn PoolController>,
+    ProtocolManager,
+    NetworkManager,
+    Box<dyn FactoryManager>,
+    mpsc::Receiver<()>,
+    StopHandle,
+    StopHandle,
+) {
+    info!("Node version : {}", *VERSION);
+    if let Some(end) = *END_TIMESTAMP {
+        if MassaTime::now(0).expect("could not get now time") > end {
+            panic!("This episode has come to an end, please get the latest testnet node version to continue");
+        }
+    }
+
+    // Storage shared by multiple components.
+    let shared_storage: Storage = Storage::create_root();
+
+    let (selector_manager, selector_controller, final_state) = init_final_state();
+
+    let bootstrap_config = bootstrap_config();
+    let bootstrap_state = bootstrap(&bootstrap_config, final_state.clone()).await;
+
+    // capabilities advertised to peers, besides compression which is set by the network worker
+    let mut node_capabilities = NodeCapabilities::default();
+    if SETTINGS.network.archive_node {
+        node_capabilities = node_capabilities.with(NodeCapabilities::ARCHIVE);
+    }
+    if SETTINGS.bootstrap.bind.is_some() {
+        node_capabilities = node_capabilities.with(NodeCapabilities::BOOTSTRAP_SERVER);
+    }
+
+    let network_config = network_config(node_capabilities);
 
     // launch network controller
     let (network_command_sender, network_event_receiver, network_manager, private_key, node_id) =
         start_network_controller(
             &network_config,
-            Establisher::new(),
+            Establisher::with_proxy(SETTINGS.network.proxy.clone()),
             bootstrap_state.compensation_millis,
             bootstrap_state.peers,
             *VERSION,
@@ -264,11 +352,22 @@ async fn launch(
     // launch execution module
     let execution_config = ExecutionConfig {
         max_final_events: SETTINGS.execution.max_final_events,
+        max_final_async_message_outcomes: SETTINGS.execution.max_final_async_message_outcomes,
+        max_final_block_receipts: SETTINGS.execution.max_final_block_receipts,
+        address_history_enabled: SETTINGS.execution.address_history_enabled,
+        max_address_history_length: SETTINGS.execution.max_address_history_length,
+        max_final_address_history_entries: SETTINGS.execution.max_final_address_history_entries,
+        parallel_execution_threads: SETTINGS.execution.parallel_execution_threads,
         readonly_queue_length: SETTINGS.execution.readonly_queue_length,
+        max_read_only_batch_gas: SETTINGS.execution.max_read_only_batch_gas,
         cursor_delay: SETTINGS.execution.cursor_delay,
         clock_compensation: bootstrap_state.compensation_millis,
         max_async_gas: MAX_ASYNC_GAS,
         max_gas_per_block: MAX_GAS_PER_BLOCK,
+        gas_refund_start_period: GAS_REFUND_START_PERIOD,
+        bytecode_metadata_start_period: BYTECODE_METADATA_START_PERIOD,
+        async_message_cancellation_start_period: ASYNC_MESSAGE_CANCELLATION_START_PERIOD,
+        multisig_start_period: MULTISIG_START_PERIOD,
         roll_price: ROLL_PRICE,
         thread_count: THREAD_COUNT,
         t0: T0,
@@ -327,6 +426,9 @@ async fn launch(
         t0: T0,
         max_operations_propagation_time: SETTINGS.protocol.max_operations_propagation_time,
         max_endorsements_propagation_time: SETTINGS.protocol.max_endorsements_propagation_time,
+        archive_block_age: SETTINGS.protocol.archive_block_age,
+        clock_compensation: bootstrap_state.compensation_millis,
+        message_rate_limits: SETTINGS.protocol.message_rate_limits,
     };
     let (protocol_command_sender, protocol_event_receiver, protocol_manager) =
         start_protocol_controller(
@@ -334,6 +436,7 @@ async fn launch(
             network_command_sender.clone(),
             network_event_receiver,
             pool_manager.clone(),
+            execution_controller.clone(),
             shared_storage.clone(),
         )
         .await
@@ -355,7 +458,15 @@ async fn launch(
         periods_per_cycle: PERIODS_PER_CYCLE,
         stats_timespan: SETTINGS.consensus.stats_timespan,
         max_send_wait: SETTINGS.consensus.max_send_wait,
-        force_keep_final_periods: SETTINGS.consensus.force_keep_final_periods,
+        // archive nodes keep final blocks longer, to serve them to the peers asking for old blocks
+        force_keep_final_periods: if SETTINGS.network.archive_node {
+            std::cmp::max(
+                SETTINGS.consensus.force_keep_final_periods,
+                SETTINGS.consensus.archive_force_keep_final_periods,
+            )
+        } else {
+            SETTINGS.consensus.force_keep_final_periods
+        },
         endorsement_count: ENDORSEMENT_COUNT,
         block_db_prune_interval: SETTINGS.consensus.block_db_prune_interval,
         max_item_return_count: SETTINGS.consensus.max_item_return_count,
@@ -421,15 +532,23 @@ async fn launch(
         max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
         max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
         max_parameter_size: MAX_PARAMETERS_SIZE,
+        contract_compiler_command: SETTINGS.api.contract_compiler_command.clone(),
+        contract_compilation_timeout: SETTINGS.api.contract_compilation_timeout,
+        max_contract_source_file_size: SETTINGS.api.max_contract_source_file_size,
+        max_contract_sources_size: SETTINGS.api.max_contract_sources_size,
+        max_contract_compilations: SETTINGS.api.max_contract_compilations,
     };
+    let contract_registry = ContractRegistry::load(SETTINGS.api.contract_registry_path.clone());
     // spawn private API
     let (api_private, api_private_stop_rx) = API::<Private>::new(
         consensus_command_sender.clone(),
         network_command_sender.clone(),
         execution_controller.clone(),
-        api_config,
+        api_config.clone(),
         consensus_config.clone(),
         node_wallet,
+        bootstrap_state.compensation_millis,
+        contract_registry.clone(),
     );
     let api_private_handle = api_private.serve(&SETTINGS.api.bind_private);
 
@@ -448,6 +567,7 @@ async fn launch(
         bootstrap_state.compensation_millis,
         node_id,
         shared_storage.clone(),
+        contract_registry,
     );
     let api_public_handle = api_public.serve(&SETTINGS.api.bind_public);
 
@@ -492,6 +612,178 @@ async fn launch(
     )
 }
 
+/// Launches the node in light client mode: it follows and checks block headers
+/// without executing blocks, and fetches ledger data from full nodes
+async fn launch_light() -> (LightClientEventReceiver, LightManagers, StopHandle) {
+    info!("Node version : {} (light client mode)", *VERSION);
+
+    // launch selector worker
+    let (selector_manager, selector_controller) = start_selector();
+
+    // only the final headers and the PoS cycle history are bootstrapped, not the ledger
+    let mut pos_state = PoSFinalState::new(
+        &INITIAL_DRAW_SEED.to_string(),
+        &SETTINGS.selector.initial_rolls_path,
+        PERIODS_PER_CYCLE,
+        THREAD_COUNT,
+        selector_controller.clone(),
+    )
+    .expect("could not init PoS state");
+    let bootstrap_state = light_bootstrap(&bootstrap_config(), &mut pos_state).await;
+
+    let network_config =
+        network_config(NodeCapabilities::default().with(NodeCapabilities::LIGHT_CLIENT));
+
+    // launch network controller
+    let (network_command_sender, network_event_receiver, network_manager, _private_key, _node_id) =
+        start_network_controller(
+            &network_config,
+            Establisher::with_proxy(SETTINGS.network.proxy.clone()),
+            bootstrap_state.compensation_millis,
+            Some(bootstrap_state.peers),
+            *VERSION,
+        )
+        .await
+        .expect("could not start network controller");
+
+    // the draws of the bootstrapped cycles are used to check headers
+    pos_state
+        .compute_initial_draws()
+        .expect("could not compute initial draws");
+
+    // launch light client
+    let light_client_config = LightClientConfig {
+        thread_count: THREAD_COUNT,
+        endorsement_count: ENDORSEMENT_COUNT,
+        periods_per_cycle: PERIODS_PER_CYCLE,
+        delta_f0: DELTA_F0,
+        genesis_timestamp: *GENESIS_TIMESTAMP,
+        t0: T0,
+        clock_compensation: bootstrap_state.compensation_millis,
+        query_timeout: SETTINGS.light_client.query_timeout,
+        max_pending_queries_per_node: SETTINGS.light_client.max_pending_queries_per_node,
+        max_query_length: MAX_ASK_BLOCKS_PER_MESSAGE as usize,
+        max_final_headers: SETTINGS.light_client.max_final_headers,
+        max_waiting_headers: SETTINGS.light_client.max_waiting_headers,
+        ledger_root_confirmations: SETTINGS.light_client.ledger_root_confirmations,
+        cycle_rolls_sources: SETTINGS.light_client.cycle_rolls_sources,
+        controller_channel_size: PROTOCOL_CONTROLLER_CHANNEL_SIZE,
+        event_channel_size: PROTOCOL_EVENT_CHANNEL_SIZE,
+    };
+    let (light_client_command_sender, light_client_event_receiver, light_client_manager) =
+        start_light_client(
+            light_client_config,
+            network_command_sender,
+            network_event_receiver,
+            selector_controller,
+            bootstrap_state.final_headers,
+            bootstrap_state.slot,
+            pos_state.cycle_history.into(),
+            pos_state.initial_rolls,
+        )
+        .await
+        .expect("could not start light client");
+
+    let api_config: APIConfig = APIConfig {
+        bind_private: SETTINGS.api.bind_private,
+        bind_public: SETTINGS.api.bind_public,
+        draw_lookahead_period_count: SETTINGS.api.draw_lookahead_period_count,
+        max_arguments: SETTINGS.api.max_arguments,
+        max_datastore_value_length: MAX_DATASTORE_VALUE_LENGTH,
+        max_function_name_length: MAX_FUNCTION_NAME_LENGTH,
+        max_parameter_size: MAX_PARAMETERS_SIZE,
+        contract_compiler_command: SETTINGS.api.contract_compiler_command.clone(),
+        contract_compilation_timeout: SETTINGS.api.contract_compilation_timeout,
+        max_contract_source_file_size: SETTINGS.api.max_contract_source_file_size,
+        max_contract_sources_size: SETTINGS.api.max_contract_sources_size,
+        max_contract_compilations: SETTINGS.api.max_contract_compilations,
+    };
+    // spawn light client API
+    let api_light = API::<Light>::new(light_client_command_sender, api_config);
+    let api_light_handle = api_light.serve(&SETTINGS.api.bind_public);
+
+    (
+        light_client_event_receiver,
+        LightManagers {
+            selector_manager,
+            light_client_manager,
+            network_manager,
+        },
+        api_light_handle,
+    )
+}
+
+struct LightManagers {
+    selector_manager: Box<dyn SelectorManager>,
+    light_client_manager: LightClientManager,
+    network_manager: NetworkManager,
+}
+
+async fn stop_light(
+    light_client_event_receiver: LightClientEventReceiver,
+    LightManagers {
+        mut selector_manager,
+        light_client_manager,
+        network_manager,
+    }: LightManagers,
+    api_light_handle: StopHandle,
+) {
+    // stop light client API
+    api_light_handle.stop();
+
+    // stop light client
+    let network_event_receiver = light_client_manager
+        .stop(light_client_event_receiver)
+        .await
+        .expect("light client shutdown failed");
+
+    // stop selector controller
+    selector_manager.stop();
+
+    // stop network controller
+    network_manager
+        .stop(network_event_receiver)
+        .await
+        .expect("network shutdown failed");
+}
+
+/// Runs the node in light client mode until it is stopped
+async fn run_light() {
+    loop {
+        let (mut light_client_event_receiver, managers, api_light_handle) = launch_light().await;
+
+        // interrupt signal listener
+        let stop_signal = signal::ctrl_c();
+        tokio::pin!(stop_signal);
+        let restart = loop {
+            tokio::select! {
+                evt = light_client_event_receiver.wait_event() => {
+                    match evt {
+                        Ok(LightClientEvent::NeedSync) => {
+                            warn!("the light client is missing draws, the node is going to bootstrap again");
+                            break true;
+                        },
+                        Err(err) => {
+                            error!("light_client_event_receiver.wait_event error: {}", err);
+                            break false;
+                        }
+                    }
+                },
+
+                _ = &mut stop_signal => {
+                    info!("interrupt signal received");
+                    break false;
+                }
+            }
+        };
+        stop_light(light_client_event_receiver, managers, api_light_handle).await;
+
+        if !restart {
+            break;
+        }
+    }
+}
+
 struct Managers {
     bootstrap_manager: Option<BootstrapManager>,
     consensus_manager: ConsensusManager,
@@ -635,6 +927,11 @@ async fn main(args: Args) -> anyhow::Result<()> {
     // load or create wallet, asking for password if necessary
     let node_wallet = load_wallet(args.password, &SETTINGS.factory.staking_wallet_path)?;
 
+    if SETTINGS.light_client.enabled {
+        run_light().await;
+        return Ok(());
+    }
+
     loop {
         let (
             mut consensus_event_receiver,
@@ -10,7 +10,11 @@ use massa_time::MassaTime;
 use serde::Deserialize;
 use std::net::{IpAddr, SocketAddr};
 
-use massa_network_exports::{settings::PeerTypeConnectionConfig, PeerType};
+use massa_network_exports::{
+    settings::{PeerScoreConfig, PeerTypeConnectionConfig, Socks5ProxyConfig},
+    PeerType,
+};
+use massa_protocol_exports::MessageRateLimits;
 
 lazy_static::lazy_static! {
     pub static ref SETTINGS: Settings = build_massa_settings("massa-node", "MASSA_NODE");
@@ -24,7 +28,14 @@ pub struct LoggingSettings {
 #[derive(Clone, Debug, Deserialize)]
 pub struct ExecutionSettings {
     pub max_final_events: usize,
+    pub max_final_async_message_outcomes: usize,
+    pub max_final_block_receipts: usize,
+    pub address_history_enabled: bool,
+    pub max_address_history_length: usize,
+    pub max_final_address_history_entries: usize,
+    pub parallel_execution_threads: usize,
     pub readonly_queue_length: usize,
+    pub max_read_only_batch_gas: u64,
     pub cursor_delay: MassaTime,
     pub stats_time_window_duration: MassaTime,
 }
@@ -46,6 +57,9 @@ pub struct LedgerSettings {
 pub struct NetworkSettings {
     pub bind: SocketAddr,
     pub routable_ip: Option<IpAddr>,
+    #[serde(default)]
+    pub hide_routable_ip: bool,
+    pub proxy: Option<Socks5ProxyConfig>,
     pub protocol_port: u16,
     pub connect_timeout: MassaTime,
     pub wakeup_interval: MassaTime,
@@ -53,7 +67,11 @@ pub struct NetworkSettings {
     pub peers_file: std::path::PathBuf,
     pub keypair_file: std::path::PathBuf,
     pub peer_types_config: EnumMap<PeerType, PeerTypeConnectionConfig>,
+    pub peer_score_config: PeerScoreConfig,
     pub max_in_connections_per_ip: usize,
+    pub max_out_connections_per_network_group: usize,
+    pub max_anchor_peers: usize,
+    pub out_connection_rotation_interval: MassaTime,
     pub max_idle_peers: usize,
     pub max_banned_peers: usize,
     pub peers_file_dump_interval: MassaTime,
@@ -66,6 +84,10 @@ pub struct NetworkSettings {
     pub max_operations_per_message: u32,
     pub max_bytes_read: f64,
     pub max_bytes_write: f64,
+    pub enable_message_compression: bool,
+    pub message_compression_min_size: usize,
+    #[serde(default)]
+    pub archive_node: bool,
 }
 
 /// Bootstrap config.
@@ -73,6 +95,7 @@ pub struct NetworkSettings {
 pub struct BootstrapSettings {
     pub bootstrap_list: Vec<(SocketAddr, PublicKey)>,
     pub bind: Option<SocketAddr>,
+    pub proxy: Option<Socks5ProxyConfig>,
     pub connect_timeout: MassaTime,
     pub read_timeout: MassaTime,
     pub write_timeout: MassaTime,
@@ -97,6 +120,26 @@ pub struct FactorySettings {
     pub staking_wallet_path: PathBuf,
 }
 
+/// Light client settings
+#[derive(Debug, Deserialize, Clone)]
+pub struct LightClientSettings {
+    /// Run the node as a light client: follow headers without executing blocks
+    pub enabled: bool,
+    /// Queries to full nodes fail if they are not answered after this delay
+    pub query_timeout: MassaTime,
+    /// Max queries waiting for an answer from a single full node
+    pub max_pending_queries_per_node: usize,
+    /// Max final headers kept in memory
+    pub max_final_headers: usize,
+    /// Max headers kept in memory while waiting for their parents
+    pub max_waiting_headers: usize,
+    /// Number of other full nodes that must confirm a ledger root before trusting balances proven against it.
+    /// Headers do not commit to the ledger root: this does not protect against peers controlled by a single party.
+    pub ledger_root_confirmations: usize,
+    /// Number of full nodes that must send the same roll counts of a cycle before they are used to draw
+    pub cycle_rolls_sources: usize,
+}
+
 /// Pool configuration, read from a file configuration
 #[derive(Debug, Deserialize, Clone)]
 pub struct PoolSettings {
@@ -113,6 +156,12 @@ pub struct APISettings {
     pub bind_private: SocketAddr,
     pub bind_public: SocketAddr,
     pub max_arguments: u64,
+    pub contract_registry_path: PathBuf,
+    pub contract_compiler_command: Vec<String>,
+    pub contract_compilation_timeout: MassaTime,
+    pub max_contract_source_file_size: u64,
+    pub max_contract_sources_size: u64,
+    pub max_contract_compilations: usize,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -128,6 +177,7 @@ pub struct Settings {
     pub ledger: LedgerSettings,
     pub selector: SelectionSettings,
     pub factory: FactorySettings,
+    pub light_client: LightClientSettings,
 }
 
 /// Consensus configuration
@@ -148,6 +198,8 @@ pub struct ConsensusSettings {
     pub max_send_wait: MassaTime,
     /// force keep at least this number of final periods in RAM for each thread
     pub force_keep_final_periods: u64,
+    /// number of final periods kept in RAM for each thread by archive nodes (see `archive_node`)
+    pub archive_force_keep_final_periods: u64,
     /// old blocks are pruned every `block_db_prune_interval`
     pub block_db_prune_interval: MassaTime,
     /// max number of items returned while querying
@@ -190,6 +242,10 @@ pub struct ProtocolSettings {
     pub max_operations_propagation_time: MassaTime,
     /// Time threshold after which operation are not propagated
     pub max_endorsements_propagation_time: MassaTime,
+    /// Blocks older than this are asked to archive nodes first
+    pub archive_block_age: MassaTime,
+    /// per node budgets for each kind of message received
+    pub message_rate_limits: MessageRateLimits,
 }
 
 #[cfg(test)]
@@ -180,6 +180,19 @@ impl OperationPool {
         let mut remaining_space = self.config.max_block_size as usize;
         // init remaining gas
         let mut remaining_gas = self.config.max_block_gas;
+        // Execution only counts the gas actually consumed by an operation in the block gas.
+        // Estimate it from the share of their max gas that recently finalized operations consumed,
+        // while still requiring the max gas of each selected operation to fit in the remaining gas.
+        let stats = self.execution_controller.get_stats();
+        let (final_gas_used, final_max_gas) = (stats.final_gas_used, stats.final_max_gas);
+        let estimate_gas_usage = |max_gas: u64| -> u64 {
+            if final_max_gas == 0 {
+                return max_gas;
+            }
+            let estimate = (max_gas as u128 * final_gas_used as u128 + final_max_gas as u128 - 1)
+                / final_max_gas as u128;
+            estimate.min(max_gas as u128) as u64
+        };
         // cache of sequential balances
         let mut sequential_balance_cache: PreHashMap<Address, Amount> = Default::default();
 
@@ -243,7 +256,7 @@ impl OperationPool {
             remaining_space -= op_info.size;
 
             // update remaining block gas
-            remaining_gas -= op_info.max_gas;
+            remaining_gas -= estimate_gas_usage(op_info.max_gas);
 
             // update sequential balance cache
             *creator_seq_balance =
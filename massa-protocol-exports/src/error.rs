@@ -50,6 +50,8 @@ pub enum ProtocolError {
     NetworkError(#[from] NetworkError),
     /// container inconsistency error: {0}
     ContainerInconsistencyError(String),
+    /// light client query failed: {0}
+    LightClientQueryError(String),
 }
 
 #[derive(Debug)]
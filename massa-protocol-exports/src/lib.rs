@@ -6,15 +6,20 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 mod error;
+mod light_client;
 mod protocol_controller;
 mod settings;
 
 pub use error::ProtocolError;
+pub use light_client::{
+    LightClientCommand, LightClientCommandSender, LightClientEvent, LightClientEventReceiver,
+    LightClientManagementCommand, LightClientManager,
+};
 pub use protocol_controller::{
     BlocksResults, ProtocolCommand, ProtocolCommandSender, ProtocolEvent, ProtocolEventReceiver,
     ProtocolManagementCommand, ProtocolManager,
 };
-pub use settings::ProtocolConfig;
+pub use settings::{LightClientConfig, MessageRateLimit, MessageRateLimits, ProtocolConfig};
 
 /// TODO: Add only if test. Removed the configuration test because don't work if running cargo test on an other sub-crate.
 pub mod tests;
@@ -0,0 +1,160 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use crate::error::ProtocolError;
+use massa_logging::massa_trace;
+use massa_models::{
+    address::Address,
+    api::{LightClientBalance, LightClientOperationInclusion, LightClientStatus},
+    operation::OperationId,
+};
+use massa_network_exports::NetworkEventReceiver;
+use serde::Serialize;
+use tokio::{
+    sync::{mpsc, oneshot},
+    task::JoinHandle,
+};
+use tracing::{debug, info};
+
+/// Events emitted by the light client
+#[derive(Debug)]
+pub enum LightClientEvent {
+    /// The selector draws needed to validate the latest headers are missing:
+    /// the node has to bootstrap again
+    NeedSync,
+}
+
+/// Commands that the light client worker can process
+#[derive(Debug)]
+pub enum LightClientCommand {
+    /// Get the status of the light client
+    GetStatus(oneshot::Sender<LightClientStatus>),
+    /// Fetch the final balances of addresses from a full node
+    GetFinalBalances {
+        /// addresses to query
+        addresses: Vec<Address>,
+        /// response channel
+        response_tx: oneshot::Sender<Result<Vec<LightClientBalance>, ProtocolError>>,
+    },
+    /// Fetch proofs of inclusion of operations in blocks from a full node
+    GetOperationInclusion {
+        /// operations to query
+        operation_ids: Vec<OperationId>,
+        /// response channel
+        response_tx: oneshot::Sender<Result<Vec<LightClientOperationInclusion>, ProtocolError>>,
+    },
+}
+
+/// light client management commands
+#[derive(Debug, Serialize)]
+pub enum LightClientManagementCommand {}
+
+/// light client command sender
+#[derive(Clone)]
+pub struct LightClientCommandSender(pub mpsc::Sender<LightClientCommand>);
+
+impl LightClientCommandSender {
+    /// Get the status of the light client
+    pub async fn get_status(&self) -> Result<LightClientStatus, ProtocolError> {
+        massa_trace!("protocol.light_client_command_sender.get_status", {});
+        let (response_tx, response_rx) = oneshot::channel();
+        self.0
+            .send(LightClientCommand::GetStatus(response_tx))
+            .await
+            .map_err(|_| ProtocolError::ChannelError("get_status command send error".into()))?;
+        Ok(response_rx.await?)
+    }
+
+    /// Fetch the final balances of addresses from a full node
+    pub async fn get_final_balances(
+        &self,
+        addresses: Vec<Address>,
+    ) -> Result<Vec<LightClientBalance>, ProtocolError> {
+        massa_trace!("protocol.light_client_command_sender.get_final_balances", {
+            "addresses": addresses
+        });
+        let (response_tx, response_rx) = oneshot::channel();
+        self.0
+            .send(LightClientCommand::GetFinalBalances {
+                addresses,
+                response_tx,
+            })
+            .await
+            .map_err(|_| {
+                ProtocolError::ChannelError("get_final_balances command send error".into())
+            })?;
+        response_rx.await?
+    }
+
+    /// Fetch proofs of inclusion of operations in blocks from a full node
+    pub async fn get_operation_inclusion(
+        &self,
+        operation_ids: Vec<OperationId>,
+    ) -> Result<Vec<LightClientOperationInclusion>, ProtocolError> {
+        massa_trace!("protocol.light_client_command_sender.get_operation_inclusion", {
+            "operation_ids": operation_ids
+        });
+        let (response_tx, response_rx) = oneshot::channel();
+        self.0
+            .send(LightClientCommand::GetOperationInclusion {
+                operation_ids,
+                response_tx,
+            })
+            .await
+            .map_err(|_| {
+                ProtocolError::ChannelError("get_operation_inclusion command send error".into())
+            })?;
+        response_rx.await?
+    }
+}
+
+/// Light client event receiver
+pub struct LightClientEventReceiver(pub mpsc::Receiver<LightClientEvent>);
+
+impl LightClientEventReceiver {
+    /// Receives the next `LightClientEvent`
+    pub async fn wait_event(&mut self) -> Result<LightClientEvent, ProtocolError> {
+        massa_trace!("protocol.light_client_event_receiver.wait_event", {});
+        self.0.recv().await.ok_or_else(|| {
+            ProtocolError::ChannelError("light client wait_event channel recv failed".into())
+        })
+    }
+
+    /// drains remaining events
+    pub async fn drain(mut self) {
+        while self.0.recv().await.is_some() {
+            debug!("after receiving event from LightClientEventReceiver.0 in drain");
+        }
+    }
+}
+
+/// light client manager used to stop the light client
+pub struct LightClientManager {
+    join_handle: JoinHandle<Result<NetworkEventReceiver, ProtocolError>>,
+    manager_tx: mpsc::Sender<LightClientManagementCommand>,
+}
+
+impl LightClientManager {
+    /// new light client manager
+    pub fn new(
+        join_handle: JoinHandle<Result<NetworkEventReceiver, ProtocolError>>,
+        manager_tx: mpsc::Sender<LightClientManagementCommand>,
+    ) -> Self {
+        LightClientManager {
+            join_handle,
+            manager_tx,
+        }
+    }
+
+    /// Stop the light client
+    pub async fn stop(
+        self,
+        light_client_event_receiver: LightClientEventReceiver,
+    ) -> Result<NetworkEventReceiver, ProtocolError> {
+        info!("stopping light client...");
+        drop(self.manager_tx);
+        light_client_event_receiver.drain().await;
+        let network_event_receiver = self.join_handle.await??;
+        info!("light client stopped");
+        Ok(network_event_receiver)
+    }
+}
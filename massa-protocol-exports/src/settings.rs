@@ -50,4 +50,82 @@ pub struct ProtocolConfig {
     pub max_operations_propagation_time: MassaTime,
     /// max time we propagate endorsements
     pub max_endorsements_propagation_time: MassaTime,
+    /// blocks older than this are asked to archive nodes first
+    pub archive_block_age: MassaTime,
+    /// clock compensation in milliseconds
+    pub clock_compensation: i64,
+    /// per node budgets for each kind of message received
+    pub message_rate_limits: MessageRateLimits,
+}
+
+/// Token bucket budget for a kind of message received from a node.
+/// Each message costs one token per item it contains (blocks, operations, endorsements...).
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct MessageRateLimit {
+    /// items per second added to the bucket
+    pub rate: f64,
+    /// bucket capacity: max number of items accepted in a burst
+    pub burst: f64,
+}
+
+/// Per node message budgets. Messages over budget are dropped
+/// and the node sending them is penalized.
+#[derive(Debug, Deserialize, Clone, Copy)]
+pub struct MessageRateLimits {
+    /// a node exceeding its budgets is penalized at most once per `penalty_interval` milliseconds
+    pub penalty_interval: MassaTime,
+    /// block headers
+    pub block_headers: MessageRateLimit,
+    /// block info replies (operation lists, operations of a block)
+    pub block_infos: MessageRateLimit,
+    /// asks for blocks
+    pub asks_for_blocks: MessageRateLimit,
+    /// full operations
+    pub operations: MessageRateLimit,
+    /// operation announcements
+    pub operation_announcements: MessageRateLimit,
+    /// asks for operations
+    pub asks_for_operations: MessageRateLimit,
+    /// endorsements
+    pub endorsements: MessageRateLimit,
+    /// light client queries for final balances and operation inclusion proofs
+    pub light_client: MessageRateLimit,
+}
+
+/// Light client configuration, mixing static settings and constants
+#[derive(Debug, Clone, Copy)]
+pub struct LightClientConfig {
+    /// running threads count
+    pub thread_count: u8,
+    /// number of endorsements per block
+    pub endorsement_count: u32,
+    /// periods per cycle
+    pub periods_per_cycle: u64,
+    /// a block is final once the summed fitness of its descendants exceeds this
+    pub delta_f0: u64,
+    /// genesis timestamp
+    pub genesis_timestamp: MassaTime,
+    /// period duration
+    pub t0: MassaTime,
+    /// clock compensation in milliseconds
+    pub clock_compensation: i64,
+    /// a query sent to a full node fails if it is not answered after this delay
+    pub query_timeout: MassaTime,
+    /// max queries waiting for an answer from a single full node
+    pub max_pending_queries_per_node: usize,
+    /// max addresses or operations in a single query
+    pub max_query_length: usize,
+    /// max final headers kept in memory
+    pub max_final_headers: usize,
+    /// max headers kept in memory while waiting for their parents
+    pub max_waiting_headers: usize,
+    /// number of other full nodes that must confirm a ledger root before trusting balances proven against it.
+    /// Headers do not commit to the ledger root: this does not protect against peers controlled by a single party.
+    pub ledger_root_confirmations: usize,
+    /// number of full nodes that must send the same roll counts of a cycle before they are used to draw
+    pub cycle_rolls_sources: usize,
+    /// light client commands channel size
+    pub controller_channel_size: usize,
+    /// light client events channel size
+    pub event_channel_size: usize,
 }
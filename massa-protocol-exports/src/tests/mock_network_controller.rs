@@ -1,6 +1,9 @@
 // Copyright (c) 2022 MASSA LABS <info@massa.net>
 
+use massa_hash::Hash;
+use massa_ledger_exports::LedgerProofs;
 use massa_models::{
+    address::Address,
     block::{BlockId, WrappedHeader},
     endorsement::WrappedEndorsement,
 };
@@ -8,12 +11,14 @@ use massa_models::{
     config::CHANNEL_SIZE,
     node::NodeId,
     operation::{OperationId, WrappedOperation},
+    slot::Slot,
 };
 use massa_network_exports::{
     AskForBlocksInfo, BlockInfoReply, NetworkCommand, NetworkCommandSender, NetworkEvent,
-    NetworkEventReceiver,
+    NetworkEventReceiver, NodeCapabilities, OperationInclusionProof,
 };
 use massa_time::MassaTime;
+use std::collections::BTreeMap;
 use tokio::{sync::mpsc, time::sleep};
 
 /// mock network controller
@@ -58,8 +63,18 @@ impl MockNetworkController {
 
     /// new connection
     pub async fn new_connection(&mut self, new_node_id: NodeId) {
+        self.new_connection_with_capabilities(new_node_id, NodeCapabilities::default())
+            .await;
+    }
+
+    /// new connection from a node advertising the given capabilities
+    pub async fn new_connection_with_capabilities(
+        &mut self,
+        new_node_id: NodeId,
+        capabilities: NodeCapabilities,
+    ) {
         self.network_event_tx
-            .send(NetworkEvent::NewConnection(new_node_id))
+            .send(NetworkEvent::NewConnection(new_node_id, capabilities))
             .await
             .expect("Couldn't connect node to protocol.");
     }
@@ -177,4 +192,116 @@ impl MockNetworkController {
             .await
             .expect("Couldn't send ask for block to protocol.");
     }
+
+    /// ask for final balances
+    pub async fn send_ask_for_final_balances(
+        &mut self,
+        source_node_id: NodeId,
+        addresses: Vec<Address>,
+    ) {
+        self.network_event_tx
+            .send(NetworkEvent::AskedForFinalBalances {
+                node: source_node_id,
+                addresses,
+            })
+            .await
+            .expect("Couldn't send ask for final balances to protocol.");
+    }
+
+    /// send final balance proofs
+    pub async fn send_final_balances(&mut self, source_node_id: NodeId, proofs: LedgerProofs) {
+        self.network_event_tx
+            .send(NetworkEvent::ReceivedFinalBalances {
+                node: source_node_id,
+                proofs,
+            })
+            .await
+            .expect("Couldn't send final balances to protocol.");
+    }
+
+    /// ask for the final ledger root at a slot
+    pub async fn send_ask_for_ledger_root(&mut self, source_node_id: NodeId, slot: Slot) {
+        self.network_event_tx
+            .send(NetworkEvent::AskedForLedgerRoot {
+                node: source_node_id,
+                slot,
+            })
+            .await
+            .expect("Couldn't send ask for ledger root to protocol.");
+    }
+
+    /// send the final ledger root at a slot
+    pub async fn send_ledger_root(
+        &mut self,
+        source_node_id: NodeId,
+        slot: Slot,
+        root: Option<Hash>,
+    ) {
+        self.network_event_tx
+            .send(NetworkEvent::ReceivedLedgerRoot {
+                node: source_node_id,
+                slot,
+                root,
+            })
+            .await
+            .expect("Couldn't send ledger root to protocol.");
+    }
+
+    /// ask for the roll counts at the end of a cycle
+    pub async fn send_ask_for_cycle_rolls(&mut self, source_node_id: NodeId, cycle: u64) {
+        self.network_event_tx
+            .send(NetworkEvent::AskedForCycleRolls {
+                node: source_node_id,
+                cycle,
+            })
+            .await
+            .expect("Couldn't send ask for cycle rolls to protocol.");
+    }
+
+    /// send the roll counts at the end of a cycle
+    pub async fn send_cycle_rolls(
+        &mut self,
+        source_node_id: NodeId,
+        cycle: u64,
+        rolls: Option<BTreeMap<Address, u64>>,
+    ) {
+        self.network_event_tx
+            .send(NetworkEvent::ReceivedCycleRolls {
+                node: source_node_id,
+                cycle,
+                rolls,
+            })
+            .await
+            .expect("Couldn't send cycle rolls to protocol.");
+    }
+
+    /// ask for operation inclusion proofs
+    pub async fn send_ask_for_operation_inclusion(
+        &mut self,
+        source_node_id: NodeId,
+        operation_ids: Vec<OperationId>,
+    ) {
+        self.network_event_tx
+            .send(NetworkEvent::AskedForOperationInclusion {
+                node: source_node_id,
+                operation_ids,
+            })
+            .await
+            .expect("Couldn't send ask for operation inclusion to protocol.");
+    }
+
+    /// send operation inclusion proofs
+    pub async fn send_operation_inclusion(
+        &mut self,
+        source_node_id: NodeId,
+        proofs: Vec<(OperationId, Option<OperationInclusionProof>)>,
+    ) {
+        self.network_event_tx
+            .send(NetworkEvent::ReceivedOperationInclusion {
+                node: source_node_id,
+                proofs,
+            })
+            .await
+            .expect("Couldn't send operation inclusion to protocol.");
+    }
 }
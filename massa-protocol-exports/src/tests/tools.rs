@@ -2,7 +2,7 @@
 
 use super::mock_network_controller::MockNetworkController;
 use crate::protocol_controller::{ProtocolCommandSender, ProtocolEventReceiver};
-use crate::{ProtocolConfig, ProtocolEvent};
+use crate::{MessageRateLimit, MessageRateLimits, ProtocolConfig, ProtocolEvent};
 use massa_hash::Hash;
 use massa_models::node::NodeId;
 use massa_models::operation::OperationSerializer;
@@ -16,7 +16,7 @@ use massa_models::{
     operation::{Operation, OperationType, WrappedOperation},
     slot::Slot,
 };
-use massa_network_exports::{AskForBlocksInfo, BlockInfoReply, NetworkCommand};
+use massa_network_exports::{AskForBlocksInfo, BlockInfoReply, NetworkCommand, PeerScoreEvent};
 use massa_signature::KeyPair;
 use massa_time::MassaTime;
 use std::collections::HashMap;
@@ -283,6 +283,27 @@ pub fn create_protocol_config() -> ProtocolConfig {
         t0: MassaTime::from_millis(16000),
         max_operations_propagation_time: MassaTime::from_millis(30000),
         max_endorsements_propagation_time: MassaTime::from_millis(60000),
+        archive_block_age: MassaTime::from_millis(300000),
+        clock_compensation: 0,
+        message_rate_limits: create_message_rate_limits(MessageRateLimit {
+            rate: 1000.0,
+            burst: 1000.0,
+        }),
+    }
+}
+
+/// create `MessageRateLimits` with the same budget for every kind of message
+pub fn create_message_rate_limits(limit: MessageRateLimit) -> MessageRateLimits {
+    MessageRateLimits {
+        penalty_interval: MassaTime::from_millis(1000),
+        block_headers: limit,
+        block_infos: limit,
+        asks_for_blocks: limit,
+        operations: limit,
+        operation_announcements: limit,
+        asks_for_operations: limit,
+        endorsements: limit,
+        light_client: limit,
     }
 }
 
@@ -340,6 +361,35 @@ pub async fn asked_list(
         .expect("Hash not asked for before timer.")
 }
 
+/// assert a list of node(s) has been reported for sending invalid data
+pub async fn assert_penalized_nodes(
+    mut nodes: Vec<NodeId>,
+    network_controller: &mut MockNetworkController,
+) {
+    let timer = sleep(MassaTime::from_millis(5000).into());
+    tokio::pin!(timer);
+    loop {
+        tokio::select! {
+            msg = network_controller
+                   .wait_command(2000.into(), |cmd| match cmd {
+                       NetworkCommand::NodeScoreEvent {
+                           node,
+                           event: PeerScoreEvent::InvalidData,
+                       } => Some(node),
+                       _ => None,
+                   })
+             =>  {
+                 let penalized_node = msg.expect("Nodes not penalized before timeout.");
+                 nodes.retain(|id| id != &penalized_node);
+                 if nodes.is_empty() {
+                     break;
+                 }
+            },
+            _ = &mut timer => panic!("Nodes not penalized before timeout.")
+        }
+    }
+}
+
 /// assert a list of node(s) has been banned
 pub async fn assert_banned_nodes(
     mut nodes: Vec<NodeId>,
@@ -351,7 +401,7 @@ pub async fn assert_banned_nodes(
         tokio::select! {
             msg = network_controller
                    .wait_command(2000.into(), |cmd| match cmd {
-                       NetworkCommand::NodeBanByIds(node) => Some(node),
+                       NetworkCommand::NodeBanByIds { ids, .. } => Some(ids),
                        _ => None,
                    })
              =>  {
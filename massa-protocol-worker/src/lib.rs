@@ -12,8 +12,10 @@
 /// protocol worker
 pub mod protocol_worker;
 pub mod worker_operations_impl;
+pub use light_client::start_light_client;
 pub use protocol_worker::start_protocol_controller;
 mod checked_operations;
+mod light_client;
 mod node_info;
 mod protocol_network;
 
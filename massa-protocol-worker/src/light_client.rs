@@ -0,0 +1,1449 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Light client: follows block headers, checks them against the selector draws
+//! and tracks their finality without executing blocks.
+//!
+//! A block is considered final once the summed fitness of the validated blocks
+//! descending from it exceeds `delta_f0`, its parents are final and it outweighs by
+//! more than `delta_f0` every block incompatible with it. Incompatibilities follow the
+//! thread and grandpa tests of consensus, restricted to the headers the light client tracks.
+//!
+//! The draws of the cycles to come are computed without bootstrapping again: the seed
+//! of each cycle is built from its final headers and misses, and the roll counts at the end
+//! of each cycle are fetched from `cycle_rolls_sources` full nodes that must all agree.
+//! Roll counts result from the execution of blocks, they are not checked against the headers.
+//!
+//! Ledger data and operation inclusion are fetched on demand from full nodes.
+//! Inclusion proofs are checked against the operation merkle root of a header.
+//! Balances are checked against the ledger root they are proven with, once
+//! `ledger_root_confirmations` other full nodes confirmed that root.
+//! Block headers do not commit to the ledger root: balances are NOT trustless.
+//! They are only as reliable as the full nodes consulted, and peers controlled
+//! by a single party (Sybil) can agree on a forged root.
+
+use bitvec::vec::BitVec;
+use massa_hash::Hash;
+use massa_ledger_exports::{LedgerProofs, LedgerSubEntry};
+use massa_logging::massa_trace;
+use massa_models::{
+    address::Address,
+    amount::{Amount, AmountDeserializer},
+    api::{LightClientBalance, LightClientOperationInclusion, LightClientStatus},
+    block::{BlockId, WrappedHeader},
+    node::NodeId,
+    operation::OperationId,
+    prehash::{PreHashMap, PreHashSet},
+    slot::Slot,
+    timeslots::get_block_slot_timestamp,
+};
+use massa_network_exports::{
+    AskForBlocksInfo, BlockInfoReply, NetworkCommandSender, NetworkEvent, NetworkEventReceiver,
+    NodeCapabilities, OperationInclusionProof, PeerScoreEvent,
+};
+use massa_pos_exports::{CycleInfo, PosError, SelectorController};
+use massa_protocol_exports::{
+    LightClientCommand, LightClientCommandSender, LightClientConfig, LightClientEvent,
+    LightClientEventReceiver, LightClientManagementCommand, LightClientManager, ProtocolError,
+};
+use massa_serialization::{DeserializeError, Deserializer};
+use massa_time::MassaTime;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::ops::Bound::Included;
+use tokio::{
+    sync::{mpsc, oneshot},
+    time::{sleep, Instant},
+};
+use tracing::{debug, error, info, warn};
+
+/// start a new light client
+///
+/// # Arguments
+/// * `config`: light client settings
+/// * `network_command_sender`: the `NetworkCommandSender` we interact with
+/// * `network_event_receiver`: the `NetworkEventReceiver` we interact with
+/// * `selector_controller`: selector draws used to check headers
+/// * `final_headers`: headers of the final blocks received at bootstrap
+/// * `draw_slot`: latest final slot of the PoS cycle history received at bootstrap
+/// * `cycle_history`: PoS cycle history received at bootstrap
+/// * `initial_rolls`: initial roll counts, used as the roll counts of cycle -1
+#[allow(clippy::too_many_arguments)]
+pub async fn start_light_client(
+    config: LightClientConfig,
+    network_command_sender: NetworkCommandSender,
+    network_event_receiver: NetworkEventReceiver,
+    selector_controller: Box<dyn SelectorController>,
+    final_headers: Vec<WrappedHeader>,
+    draw_slot: Slot,
+    cycle_history: Vec<CycleInfo>,
+    initial_rolls: BTreeMap<Address, u64>,
+) -> Result<
+    (
+        LightClientCommandSender,
+        LightClientEventReceiver,
+        LightClientManager,
+    ),
+    ProtocolError,
+> {
+    debug!("starting light client");
+
+    // launch worker
+    let (controller_event_tx, event_rx) =
+        mpsc::channel::<LightClientEvent>(config.event_channel_size);
+    let (command_tx, controller_command_rx) =
+        mpsc::channel::<LightClientCommand>(config.controller_channel_size);
+    let (manager_tx, controller_manager_rx) = mpsc::channel::<LightClientManagementCommand>(1);
+    let join_handle = tokio::spawn(async move {
+        let res = LightClientWorker::new(
+            config,
+            network_command_sender,
+            network_event_receiver,
+            selector_controller,
+            controller_event_tx,
+            controller_command_rx,
+            controller_manager_rx,
+            final_headers,
+            draw_slot,
+            cycle_history,
+            initial_rolls,
+        )
+        .run_loop()
+        .await;
+        match res {
+            Err(err) => {
+                error!("light client crashed: {}", err);
+                Err(err)
+            }
+            Ok(v) => {
+                info!("light client finished cleanly");
+                Ok(v)
+            }
+        }
+    });
+    debug!("light client ready");
+    Ok((
+        LightClientCommandSender(command_tx),
+        LightClientEventReceiver(event_rx),
+        LightClientManager::new(join_handle, manager_tx),
+    ))
+}
+
+/// A validated header followed by the light client
+struct TrackedHeader {
+    /// the header
+    header: WrappedHeader,
+    /// summed fitness of the tracked blocks descending from this one
+    descendants_fitness: u64,
+    /// tracked blocks directly incompatible with this one
+    incompatibilities: PreHashSet<BlockId>,
+    /// whether the block is final
+    is_final: bool,
+}
+
+impl TrackedHeader {
+    /// Fitness of the block and of its tracked descendants
+    fn branch_fitness(&self) -> u64 {
+        self.header
+            .get_fitness()
+            .saturating_add(self.descendants_fitness)
+    }
+}
+
+/// Outcome of the checks of a header
+#[derive(Debug, PartialEq, Eq)]
+enum HeaderCheck {
+    /// the header is valid
+    Valid,
+    /// the header is invalid: the node that sent it is penalized
+    Invalid,
+    /// the draws of the slot of the header are not known
+    MissingDraws,
+}
+
+/// A query sent to a full node, waiting for its answer
+enum PendingQuery {
+    /// final balances of addresses
+    FinalBalances {
+        addresses: Vec<Address>,
+        response_tx: oneshot::Sender<Result<Vec<LightClientBalance>, ProtocolError>>,
+    },
+    /// inclusion of operations in blocks
+    OperationInclusion {
+        operation_ids: Vec<OperationId>,
+        response_tx: oneshot::Sender<Result<Vec<LightClientOperationInclusion>, ProtocolError>>,
+    },
+    /// confirmation of the ledger root another node proved balances against
+    LedgerRoot { check_id: u64, slot: Slot },
+    /// roll counts at the end of a cycle
+    CycleRolls { cycle: u64 },
+}
+
+/// Balances proven against a ledger root, waiting for other full nodes to confirm that root
+struct RootCheck {
+    /// root the balances were proven against
+    root: Hash,
+    /// proven balances
+    balances: Vec<LightClientBalance>,
+    /// number of confirmations still missing
+    missing: usize,
+    /// channel answering the balances query
+    response_tx: oneshot::Sender<Result<Vec<LightClientBalance>, ProtocolError>>,
+}
+
+/// Roll counts at the end of a cycle asked to several full nodes, used once they all agree
+struct RollsCheck {
+    /// roll counts of the first answer
+    rolls: Option<BTreeMap<Address, u64>>,
+    /// number of answers still missing
+    missing: usize,
+}
+
+/// Checks the proofs of the final balances of addresses against the root they come with.
+/// Returns the proven balances, or None if a proof is invalid.
+fn verify_balance_proofs(
+    node_id: NodeId,
+    addresses: &[Address],
+    proofs: &LedgerProofs,
+) -> Option<Vec<LightClientBalance>> {
+    if proofs.proofs.len() != addresses.len() {
+        return None;
+    }
+    let amount_deserializer = AmountDeserializer::new(Included(Amount::MIN), Included(Amount::MAX));
+    addresses
+        .iter()
+        .zip(proofs.proofs.iter())
+        .map(|(address, proof)| {
+            if proof.key != LedgerSubEntry::SeqBalance.key(address) || !proof.verify(&proofs.root) {
+                return None;
+            }
+            let final_balance = match &proof.value {
+                Some(bytes) => match amount_deserializer.deserialize::<DeserializeError>(bytes) {
+                    Ok((rest, amount)) if rest.is_empty() => Some(amount),
+                    _ => return None,
+                },
+                None => None,
+            };
+            Some(LightClientBalance {
+                address: *address,
+                final_balance,
+                node_id,
+            })
+        })
+        .collect()
+}
+
+/// light client worker
+struct LightClientWorker {
+    /// light client configuration
+    config: LightClientConfig,
+    /// associated network command sender
+    network_command_sender: NetworkCommandSender,
+    /// associated network event receiver
+    network_event_receiver: NetworkEventReceiver,
+    /// selector draws
+    selector_controller: Box<dyn SelectorController>,
+    /// channel to send light client events to the controller
+    controller_event_tx: mpsc::Sender<LightClientEvent>,
+    /// channel receiving commands from the controller
+    controller_command_rx: mpsc::Receiver<LightClientCommand>,
+    /// channel receiving management commands
+    controller_manager_rx: mpsc::Receiver<LightClientManagementCommand>,
+    /// connected nodes and their capabilities
+    active_nodes: HashMap<NodeId, NodeCapabilities>,
+    /// validated headers, final or not
+    headers: PreHashMap<BlockId, TrackedHeader>,
+    /// final blocks in the order they became final, used to prune `headers`
+    final_order: VecDeque<BlockId>,
+    /// latest final block of each thread
+    latest_final_blocks: Vec<Option<(BlockId, Slot)>>,
+    /// validated headers waiting for their parents, with the node that sent them
+    waiting_headers: PreHashMap<BlockId, (WrappedHeader, NodeId)>,
+    /// slot of the latest validated header
+    last_header_slot: Option<Slot>,
+    /// queries sent to each node, oldest first
+    pending_queries: HashMap<NodeId, VecDeque<(Instant, PendingQuery)>>,
+    /// proven balances waiting for the confirmation of their ledger root
+    root_checks: HashMap<u64, RootCheck>,
+    /// identifier of the next ledger root check
+    next_root_check_id: u64,
+    /// whether a `NeedSync` event was already sent
+    need_sync_sent: bool,
+    /// latest slot whose seed bit is known
+    draw_cursor: Slot,
+    /// seed bits of the cycle of `draw_cursor`, up to it
+    seed_bits: BitVec<u8>,
+    /// seed bits of the final blocks after `draw_cursor`
+    final_seed_bits: BTreeMap<Slot, bool>,
+    /// seeds of the complete cycles whose draws wait for the roll counts of the previous cycle
+    cycle_seeds: BTreeMap<u64, Hash>,
+    /// known roll counts at the end of complete cycles
+    cycle_rolls: BTreeMap<u64, BTreeMap<Address, u64>>,
+    /// initial roll counts, used as the roll counts of cycle -1
+    initial_rolls: BTreeMap<Address, u64>,
+    /// roll counts at the end of cycles being asked to full nodes
+    rolls_checks: HashMap<u64, RollsCheck>,
+}
+
+impl LightClientWorker {
+    /// Creates a new light client worker, following the given final headers
+    /// and computing the draws that follow the given PoS cycle history
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        config: LightClientConfig,
+        network_command_sender: NetworkCommandSender,
+        network_event_receiver: NetworkEventReceiver,
+        selector_controller: Box<dyn SelectorController>,
+        controller_event_tx: mpsc::Sender<LightClientEvent>,
+        controller_command_rx: mpsc::Receiver<LightClientCommand>,
+        controller_manager_rx: mpsc::Receiver<LightClientManagementCommand>,
+        mut final_headers: Vec<WrappedHeader>,
+        draw_slot: Slot,
+        cycle_history: Vec<CycleInfo>,
+        initial_rolls: BTreeMap<Address, u64>,
+    ) -> LightClientWorker {
+        let mut worker = LightClientWorker {
+            config,
+            network_command_sender,
+            network_event_receiver,
+            selector_controller,
+            controller_event_tx,
+            controller_command_rx,
+            controller_manager_rx,
+            active_nodes: Default::default(),
+            headers: Default::default(),
+            final_order: Default::default(),
+            latest_final_blocks: vec![None; config.thread_count as usize],
+            waiting_headers: Default::default(),
+            last_header_slot: None,
+            pending_queries: Default::default(),
+            root_checks: Default::default(),
+            next_root_check_id: 0,
+            need_sync_sent: false,
+            draw_cursor: draw_slot,
+            seed_bits: cycle_history
+                .last()
+                .map(|cycle_info| cycle_info.rng_seed.clone())
+                .unwrap_or_default(),
+            final_seed_bits: Default::default(),
+            cycle_seeds: Default::default(),
+            cycle_rolls: cycle_history
+                .into_iter()
+                .filter(|cycle_info| cycle_info.complete)
+                .map(|cycle_info| (cycle_info.cycle, cycle_info.roll_counts))
+                .collect(),
+            initial_rolls,
+            rolls_checks: Default::default(),
+        };
+        final_headers.sort_unstable_by_key(|header| header.content.slot);
+        for header in final_headers {
+            let block_id = header.id;
+            worker.headers.insert(
+                block_id,
+                TrackedHeader {
+                    header,
+                    descendants_fitness: 0,
+                    incompatibilities: Default::default(),
+                    is_final: true,
+                },
+            );
+            worker.mark_final(block_id);
+        }
+        worker
+    }
+
+    /// Main loop of the light client
+    async fn run_loop(mut self) -> Result<NetworkEventReceiver, ProtocolError> {
+        // the final headers received at bootstrap may already complete cycles
+        self.advance_draws()?;
+        let query_timer = sleep(self.config.query_timeout.into());
+        tokio::pin!(query_timer);
+        loop {
+            massa_trace!("protocol.light_client_worker.run_loop.begin", {});
+            tokio::select! {
+                // listen to management commands
+                cmd = self.controller_manager_rx.recv() => {
+                    match cmd {
+                        None => break,
+                        Some(_) => {}
+                    };
+                }
+
+                // listen to incoming commands
+                Some(cmd) = self.controller_command_rx.recv() => {
+                    self.process_command(cmd).await?;
+                }
+
+                // listen to network controller events
+                evt = self.network_event_receiver.wait_event() => {
+                    self.on_network_event(evt?).await?;
+                }
+
+                // queries timeout
+                _ = &mut query_timer => {
+                    self.prune_timed_out_queries();
+                    self.ask_for_missing_cycle_rolls().await?;
+                    query_timer.set(sleep(self.config.query_timeout.into()));
+                }
+            }
+        }
+
+        Ok(self.network_event_receiver)
+    }
+
+    /// Processes a command of the controller
+    async fn process_command(&mut self, cmd: LightClientCommand) -> Result<(), ProtocolError> {
+        match cmd {
+            LightClientCommand::GetStatus(response_tx) => {
+                let _ = response_tx.send(self.get_status());
+            }
+            LightClientCommand::GetFinalBalances {
+                addresses,
+                response_tx,
+            } => {
+                let query = PendingQuery::FinalBalances {
+                    addresses: addresses.clone(),
+                    response_tx,
+                };
+                if let Some(node_id) = self.prepare_query(addresses.len(), query) {
+                    self.network_command_sender
+                        .ask_for_final_balances(node_id, addresses)
+                        .await
+                        .map_err(|_| {
+                            ProtocolError::ChannelError(
+                                "ask for final balances network command send failed".into(),
+                            )
+                        })?;
+                }
+            }
+            LightClientCommand::GetOperationInclusion {
+                operation_ids,
+                response_tx,
+            } => {
+                let query = PendingQuery::OperationInclusion {
+                    operation_ids: operation_ids.clone(),
+                    response_tx,
+                };
+                if let Some(node_id) = self.prepare_query(operation_ids.len(), query) {
+                    self.network_command_sender
+                        .ask_for_operation_inclusion(node_id, operation_ids)
+                        .await
+                        .map_err(|_| {
+                            ProtocolError::ChannelError(
+                                "ask for operation inclusion network command send failed".into(),
+                            )
+                        })?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Full nodes that can take another query, with their number of pending queries
+    fn available_full_nodes(&self) -> impl Iterator<Item = (usize, NodeId)> + '_ {
+        self.active_nodes
+            .iter()
+            .filter(|(_, capabilities)| !capabilities.contains(NodeCapabilities::LIGHT_CLIENT))
+            .map(|(node_id, _)| {
+                let pending = self.pending_queries.get(node_id).map_or(0, VecDeque::len);
+                (pending, *node_id)
+            })
+            .filter(|(pending, _)| *pending < self.config.max_pending_queries_per_node)
+    }
+
+    /// Picks the full node with the fewest pending queries and records the query for it.
+    /// Returns None if the query was answered with an error instead.
+    fn prepare_query(&mut self, query_length: usize, query: PendingQuery) -> Option<NodeId> {
+        if query_length > self.config.max_query_length {
+            self.fail_query(query, "too many items in the query");
+            return None;
+        }
+        let node_id = self
+            .available_full_nodes()
+            .min_by_key(|(pending, _)| *pending)
+            .map(|(_, node_id)| node_id);
+        match node_id {
+            Some(node_id) => {
+                self.pending_queries
+                    .entry(node_id)
+                    .or_default()
+                    .push_back((Instant::now(), query));
+                Some(node_id)
+            }
+            None => {
+                self.fail_query(query, "no full node available");
+                None
+            }
+        }
+    }
+
+    /// Answers a query with an error
+    fn fail_query(&mut self, query: PendingQuery, reason: &str) {
+        let err = ProtocolError::LightClientQueryError(reason.to_string());
+        match query {
+            PendingQuery::FinalBalances { response_tx, .. } => {
+                let _ = response_tx.send(Err(err));
+            }
+            PendingQuery::OperationInclusion { response_tx, .. } => {
+                let _ = response_tx.send(Err(err));
+            }
+            // the balances waiting for the root cannot be trusted anymore
+            PendingQuery::LedgerRoot { check_id, .. } => {
+                if let Some(check) = self.root_checks.remove(&check_id) {
+                    let _ = check.response_tx.send(Err(err));
+                }
+            }
+            // asked again on the next query timeout tick
+            PendingQuery::CycleRolls { cycle } => {
+                debug!(
+                    "light client: roll counts of cycle {} not obtained: {}",
+                    cycle, err
+                );
+                self.rolls_checks.remove(&cycle);
+            }
+        }
+    }
+
+    /// Fails the queries that were not answered in time
+    fn prune_timed_out_queries(&mut self) {
+        let now = Instant::now();
+        let timeout = self.config.query_timeout.to_duration();
+        let mut timed_out = Vec::new();
+        for queries in self.pending_queries.values_mut() {
+            while let Some((asked_at, _)) = queries.front() {
+                if now.saturating_duration_since(*asked_at) < timeout {
+                    break;
+                }
+                if let Some((_, query)) = queries.pop_front() {
+                    timed_out.push(query);
+                }
+            }
+        }
+        self.pending_queries
+            .retain(|_, queries| !queries.is_empty());
+        for query in timed_out {
+            self.fail_query(query, "query timed out");
+        }
+    }
+
+    /// Removes the oldest query of a kind sent to a node
+    fn take_pending_query(
+        &mut self,
+        node_id: &NodeId,
+        is_kind: impl Fn(&PendingQuery) -> bool,
+    ) -> Option<PendingQuery> {
+        let queries = self.pending_queries.get_mut(node_id)?;
+        let index = queries.iter().position(|(_, query)| is_kind(query))?;
+        queries.remove(index).map(|(_, query)| query)
+    }
+
+    /// Current status of the light client
+    fn get_status(&self) -> LightClientStatus {
+        LightClientStatus {
+            final_blocks: self.latest_final_blocks.iter().flatten().copied().collect(),
+            last_header_slot: self.last_header_slot,
+            pending_header_count: self.headers.values().filter(|t| !t.is_final).count(),
+            full_node_count: self
+                .active_nodes
+                .values()
+                .filter(|capabilities| !capabilities.contains(NodeCapabilities::LIGHT_CLIENT))
+                .count(),
+        }
+    }
+
+    /// Manages network events
+    async fn on_network_event(&mut self, evt: NetworkEvent) -> Result<(), ProtocolError> {
+        match evt {
+            NetworkEvent::NewConnection(node_id, capabilities) => {
+                info!("Connected to node {}", node_id);
+                self.active_nodes.insert(node_id, capabilities);
+            }
+            NetworkEvent::ConnectionClosed(node_id) => {
+                if self.active_nodes.remove(&node_id).is_some() {
+                    info!("Connection closed with {}", node_id);
+                }
+                if let Some(queries) = self.pending_queries.remove(&node_id) {
+                    for (_, query) in queries {
+                        self.fail_query(query, "connection closed");
+                    }
+                }
+            }
+            NetworkEvent::ReceivedBlockHeader {
+                source_node_id,
+                header,
+            } => {
+                self.on_header_received(source_node_id, header).await?;
+            }
+            NetworkEvent::ReceivedBlockInfo { node, info } => {
+                for (_, reply) in info {
+                    if let BlockInfoReply::Header(header) = reply {
+                        self.on_header_received(node, header).await?;
+                    }
+                }
+            }
+            NetworkEvent::AskedForBlocks { node, list } => {
+                // light clients keep no blocks
+                let info = list
+                    .into_iter()
+                    .map(|(block_id, _)| (block_id, BlockInfoReply::NotFound))
+                    .collect();
+                self.network_command_sender
+                    .send_block_info(node, info)
+                    .await
+                    .map_err(|_| {
+                        ProtocolError::ChannelError(
+                            "send block info network command send failed".into(),
+                        )
+                    })?;
+            }
+            NetworkEvent::ReceivedFinalBalances { node, proofs } => {
+                self.on_final_balances_received(node, proofs).await?;
+            }
+            NetworkEvent::ReceivedLedgerRoot { node, slot, root } => {
+                self.on_ledger_root_received(node, slot, root);
+            }
+            NetworkEvent::ReceivedOperationInclusion { node, proofs } => {
+                self.on_operation_inclusion_received(node, proofs).await?;
+            }
+            NetworkEvent::ReceivedCycleRolls { node, cycle, rolls } => {
+                self.on_cycle_rolls_received(node, cycle, rolls)?;
+            }
+            // light clients neither relay operations and endorsements nor serve ledger data
+            NetworkEvent::ReceivedOperations { .. }
+            | NetworkEvent::ReceivedEndorsements { .. }
+            | NetworkEvent::ReceivedOperationAnnouncements { .. }
+            | NetworkEvent::ReceiveAskForOperations { .. }
+            | NetworkEvent::AskedForFinalBalances { .. }
+            | NetworkEvent::AskedForLedgerRoot { .. }
+            | NetworkEvent::AskedForOperationInclusion { .. }
+            | NetworkEvent::AskedForCycleRolls { .. } => {}
+        }
+        Ok(())
+    }
+
+    /// Penalizes a node that sent invalid data
+    async fn report_invalid_data(&self, node_id: NodeId) -> Result<(), ProtocolError> {
+        self.network_command_sender
+            .node_score_event(node_id, PeerScoreEvent::InvalidData)
+            .await
+            .map_err(|_| ProtocolError::ChannelError("Node score event command send failed".into()))
+    }
+
+    /// Sends a `NeedSync` event, once
+    async fn need_sync(&mut self) {
+        if self.need_sync_sent {
+            return;
+        }
+        warn!("the selector draws needed to check the latest headers are missing");
+        self.need_sync_sent = true;
+        if self
+            .controller_event_tx
+            .send(LightClientEvent::NeedSync)
+            .await
+            .is_err()
+        {
+            warn!("could not send NeedSync light client event");
+        }
+    }
+
+    /// Whether a block of a given slot can no longer become final,
+    /// because a later block of its thread is final
+    fn is_stale(&self, slot: &Slot) -> bool {
+        match self.latest_final_blocks[slot.thread as usize] {
+            Some((_, final_slot)) => *slot <= final_slot,
+            None => false,
+        }
+    }
+
+    /// Checks a header: signature, endorsements and selector draws
+    fn check_header(&self, header: &WrappedHeader) -> Result<HeaderCheck, ProtocolError> {
+        let slot = header.content.slot;
+        if header.content.parents.len() != self.config.thread_count as usize {
+            return Ok(HeaderCheck::Invalid);
+        }
+
+        // refuse headers from the future
+        let now = MassaTime::now(self.config.clock_compensation)?;
+        let slot_timestamp = get_block_slot_timestamp(
+            self.config.thread_count,
+            self.config.t0,
+            self.config.genesis_timestamp,
+            slot,
+        )?;
+        if slot_timestamp > now.saturating_add(self.config.t0) {
+            return Ok(HeaderCheck::Invalid);
+        }
+
+        if header.verify_signature().is_err() {
+            return Ok(HeaderCheck::Invalid);
+        }
+
+        // check the block producer
+        let selection = match self.selector_controller.get_selection(slot) {
+            Ok(selection) => selection,
+            Err(PosError::CycleUnavailable(_)) | Err(PosError::CycleUnfinalised(_)) => {
+                return Ok(HeaderCheck::MissingDraws)
+            }
+            Err(err) => {
+                return Err(ProtocolError::GeneralProtocolError(format!(
+                    "could not get the selection of slot {}: {}",
+                    slot, err
+                )))
+            }
+        };
+        if selection.producer != header.creator_address {
+            return Ok(HeaderCheck::Invalid);
+        }
+
+        // check the endorsements
+        let mut used_endorsement_indices: HashSet<u32> =
+            HashSet::with_capacity(header.content.endorsements.len());
+        for endorsement in header.content.endorsements.iter() {
+            if endorsement.content.index >= self.config.endorsement_count
+                || !used_endorsement_indices.insert(endorsement.content.index)
+                || endorsement.content.slot.thread != slot.thread
+                || endorsement.content.slot >= slot
+                || endorsement.content.endorsed_block
+                    != header.content.parents[slot.thread as usize]
+                || endorsement.verify_signature().is_err()
+            {
+                return Ok(HeaderCheck::Invalid);
+            }
+            let endorsement_selection = match self
+                .selector_controller
+                .get_selection(endorsement.content.slot)
+            {
+                Ok(selection) => selection,
+                Err(_) => return Ok(HeaderCheck::MissingDraws),
+            };
+            if endorsement_selection
+                .endorsements
+                .get(endorsement.content.index as usize)
+                != Some(&endorsement.creator_address)
+            {
+                return Ok(HeaderCheck::Invalid);
+            }
+        }
+        Ok(HeaderCheck::Valid)
+    }
+
+    /// A node sent us a header
+    async fn on_header_received(
+        &mut self,
+        source_node_id: NodeId,
+        header: WrappedHeader,
+    ) -> Result<(), ProtocolError> {
+        massa_trace!("protocol.light_client_worker.on_header_received", { "node": source_node_id, "header": header });
+        let block_id = header.id;
+        if self.headers.contains_key(&block_id)
+            || self.waiting_headers.contains_key(&block_id)
+            || header.content.slot.period == 0
+            || self.is_stale(&header.content.slot)
+        {
+            return Ok(());
+        }
+        match self.check_header(&header)? {
+            HeaderCheck::Valid => {}
+            HeaderCheck::Invalid => {
+                warn!("node {} sent us an invalid header", source_node_id);
+                return self.report_invalid_data(source_node_id).await;
+            }
+            HeaderCheck::MissingDraws => {
+                self.need_sync().await;
+                return Ok(());
+            }
+        }
+
+        // wait for unknown parents, asking for their headers
+        let missing_parents: Vec<BlockId> = header
+            .content
+            .parents
+            .iter()
+            .filter(|parent| !self.headers.contains_key(parent))
+            .copied()
+            .collect();
+        if !missing_parents.is_empty() {
+            if self.waiting_headers.len() >= self.config.max_waiting_headers {
+                return Ok(());
+            }
+            self.waiting_headers
+                .insert(block_id, (header, source_node_id));
+            let list = missing_parents
+                .into_iter()
+                .map(|parent| (parent, AskForBlocksInfo::Header))
+                .collect();
+            return self
+                .network_command_sender
+                .ask_for_block_list(HashMap::from([(source_node_id, list)]))
+                .await
+                .map_err(|_| {
+                    ProtocolError::ChannelError("ask for block network command send failed".into())
+                });
+        }
+
+        self.integrate_header(header);
+        // integrate the waiting headers whose parents are now known
+        loop {
+            let ready: Vec<BlockId> = self
+                .waiting_headers
+                .iter()
+                .filter(|(_, (header, _))| {
+                    header
+                        .content
+                        .parents
+                        .iter()
+                        .all(|parent| self.headers.contains_key(parent))
+                })
+                .map(|(block_id, _)| *block_id)
+                .collect();
+            if ready.is_empty() {
+                break;
+            }
+            for block_id in ready {
+                if let Some((header, _)) = self.waiting_headers.remove(&block_id) {
+                    self.integrate_header(header);
+                }
+            }
+        }
+        self.advance_draws()?;
+        self.ask_for_missing_cycle_rolls().await
+    }
+
+    /// Tracked non-final ancestors of a block with the given parents
+    fn non_final_ancestors(&self, parents: &[BlockId]) -> PreHashSet<BlockId> {
+        let mut ancestors = PreHashSet::<BlockId>::default();
+        let mut to_visit = parents.to_vec();
+        while let Some(ancestor_id) = to_visit.pop() {
+            if let Some(ancestor) = self.headers.get(&ancestor_id) {
+                if !ancestor.is_final && ancestors.insert(ancestor_id) {
+                    to_visit.extend(ancestor.header.content.parents.iter().copied());
+                }
+            }
+        }
+        ancestors
+    }
+
+    /// Period of a tracked block
+    fn tracked_period(&self, block_id: &BlockId) -> Option<u64> {
+        self.headers
+            .get(block_id)
+            .map(|tracked| tracked.header.content.slot.period)
+    }
+
+    /// Whether `c` is grandpa incompatible with `b`: `c` builds on a later block
+    /// of its own thread than `b` does, but on an earlier block of the thread of `b`
+    /// than the parent of `b`. Unknown parents are considered compatible.
+    fn is_grandpa_incompatible(&self, b: &WrappedHeader, c: &WrappedHeader) -> bool {
+        let tb = b.content.slot.thread as usize;
+        let tc = c.content.slot.thread as usize;
+        match (
+            self.tracked_period(&c.content.parents[tc]),
+            self.tracked_period(&b.content.parents[tc]),
+            self.tracked_period(&c.content.parents[tb]),
+            self.tracked_period(&b.content.parents[tb]),
+        ) {
+            (Some(c_in_tc), Some(b_in_tc), Some(c_in_tb), Some(b_in_tb)) => {
+                c_in_tc > b_in_tc && c_in_tb < b_in_tb
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether two blocks are incompatible, following the thread and grandpa tests of consensus
+    fn are_incompatible(&self, a: &WrappedHeader, b: &WrappedHeader) -> bool {
+        // genesis blocks have no parents and are compatible with every block
+        if a.content.parents.is_empty() || b.content.parents.is_empty() {
+            return false;
+        }
+        let ta = a.content.slot.thread as usize;
+        let tb = b.content.slot.thread as usize;
+        if ta == tb {
+            return a.content.parents[ta] == b.content.parents[ta];
+        }
+        self.is_grandpa_incompatible(a, b) || self.is_grandpa_incompatible(b, a)
+    }
+
+    /// Follows a validated header whose parents are known,
+    /// adding its fitness to its ancestors and updating finality
+    fn integrate_header(&mut self, header: WrappedHeader) {
+        let block_id = header.id;
+        let slot = header.content.slot;
+        let fitness = header.get_fitness();
+        let ancestors = self.non_final_ancestors(&header.content.parents);
+        let incompatibilities: PreHashSet<BlockId> = self
+            .headers
+            .iter()
+            .filter(|(_, tracked)| self.are_incompatible(&header, &tracked.header))
+            .map(|(incompatible_id, _)| *incompatible_id)
+            .collect();
+
+        // like consensus, drop blocks incompatible with a final block or with their own ancestors
+        let is_final = |id: &BlockId| self.headers.get(id).map_or(false, |t| t.is_final);
+        if incompatibilities
+            .iter()
+            .any(|id| ancestors.contains(id) || is_final(id))
+            || ancestors.iter().any(|id| {
+                self.headers
+                    .get(id)
+                    .map_or(false, |t| !t.incompatibilities.is_disjoint(&ancestors))
+            })
+        {
+            debug!("light client: dropping incompatible block {}", block_id);
+            return;
+        }
+
+        if self.last_header_slot.map_or(true, |last| last < slot) {
+            self.last_header_slot = Some(slot);
+        }
+        for incompatible_id in incompatibilities.iter() {
+            if let Some(tracked) = self.headers.get_mut(incompatible_id) {
+                tracked.incompatibilities.insert(block_id);
+            }
+        }
+        self.headers.insert(
+            block_id,
+            TrackedHeader {
+                header,
+                descendants_fitness: 0,
+                incompatibilities,
+                is_final: false,
+            },
+        );
+        for ancestor_id in ancestors {
+            if let Some(ancestor) = self.headers.get_mut(&ancestor_id) {
+                ancestor.descendants_fitness = ancestor.descendants_fitness.saturating_add(fitness);
+            }
+        }
+        self.update_finality();
+    }
+
+    /// Whether a tracked block can become final: its descendants are fit enough,
+    /// its parents are final and it outweighs every block incompatible with it by more than `delta_f0`
+    fn can_become_final(&self, tracked: &TrackedHeader) -> bool {
+        tracked.descendants_fitness > self.config.delta_f0
+            && tracked
+                .header
+                .content
+                .parents
+                .iter()
+                .all(|parent| self.headers.get(parent).map_or(true, |p| p.is_final))
+            && tracked
+                .incompatibilities
+                .iter()
+                .filter_map(|incompatible_id| self.headers.get(incompatible_id))
+                .all(|incompatible| {
+                    incompatible
+                        .branch_fitness()
+                        .saturating_add(self.config.delta_f0)
+                        < tracked.branch_fitness()
+                })
+    }
+
+    /// Marks final the blocks that can become final, oldest first
+    fn update_finality(&mut self) {
+        let mut updated = false;
+        loop {
+            let next_final = self
+                .headers
+                .iter()
+                .filter(|(_, tracked)| !tracked.is_final && self.can_become_final(tracked))
+                .map(|(block_id, tracked)| (tracked.header.content.slot, *block_id))
+                .min();
+            match next_final {
+                Some((_, block_id)) => {
+                    self.mark_final(block_id);
+                    self.discard_incompatibilities(&block_id);
+                    updated = true;
+                }
+                None => break,
+            }
+        }
+        if updated {
+            self.prune_headers();
+        }
+    }
+
+    /// Forgets the blocks incompatible with a final block and their descendants,
+    /// withdrawing their fitness from their remaining ancestors
+    fn discard_incompatibilities(&mut self, block_id: &BlockId) {
+        let mut discarded = match self.headers.get(block_id) {
+            Some(tracked) => tracked.incompatibilities.clone(),
+            None => return,
+        };
+        loop {
+            let descendants: Vec<BlockId> = self
+                .headers
+                .iter()
+                .filter(|(id, tracked)| {
+                    !discarded.contains(id)
+                        && tracked
+                            .header
+                            .content
+                            .parents
+                            .iter()
+                            .any(|parent| discarded.contains(parent))
+                })
+                .map(|(id, _)| *id)
+                .collect();
+            if descendants.is_empty() {
+                break;
+            }
+            discarded.extend(descendants);
+        }
+        let removed: Vec<TrackedHeader> = discarded
+            .iter()
+            .filter_map(|id| self.headers.remove(id))
+            .collect();
+        for tracked in removed {
+            debug!(
+                "light client: discarding block {} incompatible with final blocks",
+                tracked.header.id
+            );
+            let fitness = tracked.header.get_fitness();
+            for ancestor_id in self.non_final_ancestors(&tracked.header.content.parents) {
+                if let Some(ancestor) = self.headers.get_mut(&ancestor_id) {
+                    ancestor.descendants_fitness =
+                        ancestor.descendants_fitness.saturating_sub(fitness);
+                }
+            }
+        }
+    }
+
+    /// Marks a tracked block as final
+    fn mark_final(&mut self, block_id: BlockId) {
+        let slot = match self.headers.get_mut(&block_id) {
+            Some(tracked) => {
+                tracked.is_final = true;
+                tracked.header.content.slot
+            }
+            None => return,
+        };
+        debug!("light client: block {} at slot {} is final", block_id, slot);
+        self.final_order.push_back(block_id);
+        if slot > self.draw_cursor {
+            self.final_seed_bits.insert(slot, block_id.get_first_bit());
+        }
+        let latest_final = &mut self.latest_final_blocks[slot.thread as usize];
+        if latest_final.map_or(true, |(_, final_slot)| final_slot < slot) {
+            *latest_final = Some((block_id, slot));
+        }
+    }
+
+    /// Forgets the headers that can no longer become final and the oldest final headers
+    fn prune_headers(&mut self) {
+        let stale: Vec<BlockId> = self
+            .headers
+            .iter()
+            .filter(|(_, tracked)| !tracked.is_final && self.is_stale(&tracked.header.content.slot))
+            .map(|(block_id, _)| *block_id)
+            .collect();
+        for block_id in stale {
+            self.headers.remove(&block_id);
+        }
+        let stale_waiting: Vec<BlockId> = self
+            .waiting_headers
+            .iter()
+            .filter(|(_, (header, _))| self.is_stale(&header.content.slot))
+            .map(|(block_id, _)| *block_id)
+            .collect();
+        for block_id in stale_waiting {
+            self.waiting_headers.remove(&block_id);
+        }
+        while self.final_order.len() > self.config.max_final_headers {
+            if let Some(block_id) = self.final_order.pop_front() {
+                self.headers.remove(&block_id);
+            }
+        }
+    }
+
+    /// Extends the seed bits with the final slots that follow `draw_cursor`, like execution does:
+    /// a final block gives the first bit of its id, a miss gives the first bit of its slot.
+    /// The seed of each completed cycle is used to feed the selector.
+    fn advance_draws(&mut self) -> Result<(), ProtocolError> {
+        let periods_per_cycle = self.config.periods_per_cycle;
+        loop {
+            let slot = self.draw_cursor.get_next_slot(self.config.thread_count)?;
+            let seed_bit = match self.final_seed_bits.remove(&slot) {
+                Some(seed_bit) => seed_bit,
+                // a slot without final block is a miss once a later block of its thread is final
+                None if self.is_stale(&slot) => slot.get_first_bit(),
+                None => break,
+            };
+            if slot.is_first_of_cycle(periods_per_cycle) {
+                self.seed_bits.clear();
+            }
+            self.seed_bits.push(seed_bit);
+            self.draw_cursor = slot;
+            if slot.is_last_of_cycle(periods_per_cycle, self.config.thread_count) {
+                let cycle = slot.get_cycle(periods_per_cycle);
+                debug!("light client: cycle {} is complete", cycle);
+                self.cycle_seeds.insert(
+                    cycle,
+                    Hash::compute_from(&self.seed_bits.clone().into_vec()),
+                );
+            }
+        }
+        self.feed_ready_cycles()
+    }
+
+    /// Latest cycle whose slots are all final
+    fn latest_complete_cycle(&self) -> Option<u64> {
+        let periods_per_cycle = self.config.periods_per_cycle;
+        let cycle = self.draw_cursor.get_cycle(periods_per_cycle);
+        if self
+            .draw_cursor
+            .is_last_of_cycle(periods_per_cycle, self.config.thread_count)
+        {
+            Some(cycle)
+        } else {
+            cycle.checked_sub(1)
+        }
+    }
+
+    /// Feeds the selector, in order, with the draws of the complete cycles
+    /// whose previous cycle roll counts are known: to draw cycle C+2,
+    /// the seed of cycle C and the roll counts at the end of cycle C-1 are used
+    fn feed_ready_cycles(&mut self) -> Result<(), ProtocolError> {
+        while let Some((&cycle, &seed)) = self.cycle_seeds.iter().next() {
+            let lookback_rolls = match cycle.checked_sub(1) {
+                Some(lookback_cycle) => match self.cycle_rolls.get(&lookback_cycle) {
+                    Some(rolls) => rolls.clone(),
+                    None => break,
+                },
+                None => self.initial_rolls.clone(),
+            };
+            let draw_cycle = cycle.saturating_add(2);
+            debug!(
+                "light client: feeding the selector with cycle {}",
+                draw_cycle
+            );
+            self.selector_controller
+                .feed_cycle(draw_cycle, lookback_rolls, seed)
+                .map_err(|err| {
+                    ProtocolError::GeneralProtocolError(format!(
+                        "could not feed the selector with cycle {}: {}",
+                        draw_cycle, err
+                    ))
+                })?;
+            self.cycle_seeds.remove(&cycle);
+        }
+        // forget the roll counts that no draw needs anymore
+        if let Some(latest_cycle) = self.latest_complete_cycle() {
+            let oldest_needed = self
+                .cycle_seeds
+                .keys()
+                .next()
+                .map_or(latest_cycle, |cycle| cycle.saturating_sub(1));
+            self.cycle_rolls.retain(|cycle, _| *cycle >= oldest_needed);
+        }
+        Ok(())
+    }
+
+    /// Asks full nodes for the unknown roll counts of the complete cycles the next draws need.
+    /// Each cycle is asked to `cycle_rolls_sources` full nodes at once.
+    async fn ask_for_missing_cycle_rolls(&mut self) -> Result<(), ProtocolError> {
+        let mut missing: Vec<u64> = self
+            .cycle_seeds
+            .keys()
+            .filter_map(|cycle| cycle.checked_sub(1))
+            .chain(self.latest_complete_cycle())
+            .filter(|cycle| {
+                !self.cycle_rolls.contains_key(cycle) && !self.rolls_checks.contains_key(cycle)
+            })
+            .collect();
+        missing.sort_unstable();
+        missing.dedup();
+        let sources = self.config.cycle_rolls_sources.max(1);
+        for cycle in missing {
+            // wait for the answers of a previous failed check
+            let asked = self
+                .pending_queries
+                .values()
+                .flatten()
+                .any(|(_, query)| match query {
+                    PendingQuery::CycleRolls { cycle: asked } => *asked == cycle,
+                    _ => false,
+                });
+            if asked {
+                continue;
+            }
+            let mut nodes: Vec<(usize, NodeId)> = self.available_full_nodes().collect();
+            if nodes.len() < sources {
+                debug!(
+                    "light client: not enough full nodes to ask for the roll counts of cycle {}",
+                    cycle
+                );
+                return Ok(());
+            }
+            nodes.sort_unstable_by_key(|(pending, _)| *pending);
+            nodes.truncate(sources);
+            self.rolls_checks.insert(
+                cycle,
+                RollsCheck {
+                    rolls: None,
+                    missing: nodes.len(),
+                },
+            );
+            for (_, node_id) in nodes {
+                self.pending_queries
+                    .entry(node_id)
+                    .or_default()
+                    .push_back((Instant::now(), PendingQuery::CycleRolls { cycle }));
+                self.network_command_sender
+                    .ask_for_cycle_rolls(node_id, cycle)
+                    .await
+                    .map_err(|_| {
+                        ProtocolError::ChannelError(
+                            "ask for cycle rolls network command send failed".into(),
+                        )
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// A full node answered a cycle roll counts query
+    fn on_cycle_rolls_received(
+        &mut self,
+        node_id: NodeId,
+        cycle: u64,
+        rolls: Option<BTreeMap<Address, u64>>,
+    ) -> Result<(), ProtocolError> {
+        let query = self.take_pending_query(
+            &node_id,
+            |query| matches!(query, PendingQuery::CycleRolls { cycle: asked } if *asked == cycle),
+        );
+        if query.is_none() {
+            warn!("node {} sent us unsolicited cycle roll counts", node_id);
+            return Ok(());
+        }
+        // the check may already have failed
+        let check = match self.rolls_checks.get_mut(&cycle) {
+            Some(check) => check,
+            None => return Ok(()),
+        };
+        let rolls = match rolls {
+            Some(rolls) if check.rolls.as_ref().map_or(true, |first| *first == rolls) => rolls,
+            _ => {
+                debug!(
+                    "node {} did not send the same roll counts of cycle {}",
+                    node_id, cycle
+                );
+                self.rolls_checks.remove(&cycle);
+                return Ok(());
+            }
+        };
+        check.missing = check.missing.saturating_sub(1);
+        if check.missing > 0 {
+            check.rolls = Some(rolls);
+            return Ok(());
+        }
+        self.rolls_checks.remove(&cycle);
+        self.cycle_rolls.insert(cycle, rolls);
+        self.feed_ready_cycles()
+    }
+
+    /// A full node answered a final balances query with proofs.
+    /// Valid proofs are trusted once other full nodes confirmed the root they are proven against.
+    async fn on_final_balances_received(
+        &mut self,
+        node_id: NodeId,
+        proofs: LedgerProofs,
+    ) -> Result<(), ProtocolError> {
+        let query = self.take_pending_query(&node_id, |query| {
+            matches!(query, PendingQuery::FinalBalances { .. })
+        });
+        let (addresses, response_tx) = match query {
+            Some(PendingQuery::FinalBalances {
+                addresses,
+                response_tx,
+            }) => (addresses, response_tx),
+            _ => {
+                warn!("node {} sent us unsolicited final balances", node_id);
+                return Ok(());
+            }
+        };
+        let balances = match verify_balance_proofs(node_id, &addresses, &proofs) {
+            Some(balances) => balances,
+            None => {
+                let _ = response_tx.send(Err(ProtocolError::LightClientQueryError(
+                    "the node answered with invalid balance proofs".into(),
+                )));
+                return self.report_invalid_data(node_id).await;
+            }
+        };
+        if self.config.ledger_root_confirmations == 0 {
+            let _ = response_tx.send(Ok(balances));
+            return Ok(());
+        }
+
+        // ask the least busy other full nodes to confirm the root
+        let mut confirming_nodes: Vec<(usize, NodeId)> = self
+            .available_full_nodes()
+            .filter(|(_, confirming_node)| *confirming_node != node_id)
+            .collect();
+        if confirming_nodes.len() < self.config.ledger_root_confirmations {
+            let _ = response_tx.send(Err(ProtocolError::LightClientQueryError(
+                "not enough full nodes to confirm the ledger root".into(),
+            )));
+            return Ok(());
+        }
+        confirming_nodes.sort_unstable_by_key(|(pending, _)| *pending);
+        confirming_nodes.truncate(self.config.ledger_root_confirmations);
+        let check_id = self.next_root_check_id;
+        self.next_root_check_id += 1;
+        self.root_checks.insert(
+            check_id,
+            RootCheck {
+                root: proofs.root,
+                balances,
+                missing: confirming_nodes.len(),
+                response_tx,
+            },
+        );
+        for (_, confirming_node) in confirming_nodes {
+            self.pending_queries
+                .entry(confirming_node)
+                .or_default()
+                .push_back((
+                    Instant::now(),
+                    PendingQuery::LedgerRoot {
+                        check_id,
+                        slot: proofs.slot,
+                    },
+                ));
+            self.network_command_sender
+                .ask_for_ledger_root(confirming_node, proofs.slot)
+                .await
+                .map_err(|_| {
+                    ProtocolError::ChannelError(
+                        "ask for ledger root network command send failed".into(),
+                    )
+                })?;
+        }
+        Ok(())
+    }
+
+    /// A full node answered a ledger root confirmation query
+    fn on_ledger_root_received(&mut self, node_id: NodeId, slot: Slot, root: Option<Hash>) {
+        let query = self.take_pending_query(
+            &node_id,
+            |query| matches!(query, PendingQuery::LedgerRoot { slot: asked, .. } if *asked == slot),
+        );
+        let check_id = match query {
+            Some(PendingQuery::LedgerRoot { check_id, .. }) => check_id,
+            _ => {
+                warn!("node {} sent us an unsolicited ledger root", node_id);
+                return;
+            }
+        };
+        // the check may already have failed
+        let check = match self.root_checks.get_mut(&check_id) {
+            Some(check) => check,
+            None => return,
+        };
+        if root != Some(check.root) {
+            debug!(
+                "node {} did not confirm the ledger root at slot {}",
+                node_id, slot
+            );
+            if let Some(check) = self.root_checks.remove(&check_id) {
+                let _ = check
+                    .response_tx
+                    .send(Err(ProtocolError::LightClientQueryError(
+                        "the ledger root was not confirmed by other full nodes".into(),
+                    )));
+            }
+            return;
+        }
+        check.missing = check.missing.saturating_sub(1);
+        if check.missing == 0 {
+            if let Some(check) = self.root_checks.remove(&check_id) {
+                let _ = check.response_tx.send(Ok(check.balances));
+            }
+        }
+    }
+
+    /// A full node answered an operation inclusion query
+    async fn on_operation_inclusion_received(
+        &mut self,
+        node_id: NodeId,
+        proofs: Vec<(OperationId, Option<OperationInclusionProof>)>,
+    ) -> Result<(), ProtocolError> {
+        let query = self.take_pending_query(&node_id, |query| {
+            matches!(query, PendingQuery::OperationInclusion { .. })
+        });
+        let (operation_ids, response_tx) = match query {
+            Some(PendingQuery::OperationInclusion {
+                operation_ids,
+                response_tx,
+            }) => (operation_ids, response_tx),
+            _ => {
+                warn!(
+                    "node {} sent us unsolicited operation inclusion proofs",
+                    node_id
+                );
+                return Ok(());
+            }
+        };
+        let mut inclusions = Vec::with_capacity(proofs.len());
+        let mut valid = proofs.len() == operation_ids.len();
+        for ((operation_id, proof), asked) in proofs.into_iter().zip(operation_ids.iter()) {
+            if operation_id != *asked {
+                valid = false;
+                break;
+            }
+            let proof = match proof {
+                Some(proof) => proof,
+                None => {
+                    inclusions.push(LightClientOperationInclusion {
+                        operation_id,
+                        block_id: None,
+                        slot: None,
+                        is_final: false,
+                    });
+                    continue;
+                }
+            };
+            if !proof.proves(&operation_id) {
+                valid = false;
+                break;
+            }
+            let block_id = proof.header.id;
+            let inclusion = match self.headers.get(&block_id) {
+                Some(tracked) => LightClientOperationInclusion {
+                    operation_id,
+                    block_id: Some(block_id),
+                    slot: Some(tracked.header.content.slot),
+                    is_final: tracked.is_final,
+                },
+                // not followed: check the header itself
+                None => match self.check_header(&proof.header)? {
+                    HeaderCheck::Valid => LightClientOperationInclusion {
+                        operation_id,
+                        block_id: Some(block_id),
+                        slot: Some(proof.header.content.slot),
+                        is_final: false,
+                    },
+                    HeaderCheck::Invalid => {
+                        valid = false;
+                        break;
+                    }
+                    HeaderCheck::MissingDraws => LightClientOperationInclusion {
+                        operation_id,
+                        block_id: None,
+                        slot: None,
+                        is_final: false,
+                    },
+                },
+            };
+            inclusions.push(inclusion);
+        }
+        if !valid {
+            let _ = response_tx.send(Err(ProtocolError::LightClientQueryError(
+                "the node answered with invalid inclusion proofs".into(),
+            )));
+            return self.report_invalid_data(node_id).await;
+        }
+        let _ = response_tx.send(Ok(inclusions));
+        Ok(())
+    }
+}
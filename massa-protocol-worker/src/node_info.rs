@@ -5,13 +5,84 @@
 //! # Operations
 //! Same as for wanted/known blocks, we remember here in cache which node asked
 //! for operations and which operations he seem to already know.
+//!
+//! # Rate limiting
+//! Each node has a token bucket per kind of message it can send us,
+//! from which each message takes one token per item it contains.
 
 use massa_models::prehash::{CapacityAllocator, PreHashMap, PreHashSet};
 use massa_models::{block::BlockId, endorsement::EndorsementId, operation::OperationId};
-use massa_protocol_exports::ProtocolConfig;
-use std::collections::VecDeque;
+use massa_network_exports::NodeCapabilities;
+use massa_protocol_exports::{MessageRateLimit, MessageRateLimits, ProtocolConfig};
+use massa_time::MassaTime;
+use std::collections::{HashMap, VecDeque};
 use tokio::time::Instant;
 
+/// Kinds of messages received from a node, each with its own budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum MessageKind {
+    BlockHeader,
+    BlockInfo,
+    AskForBlocks,
+    Operations,
+    OperationAnnouncements,
+    AskForOperations,
+    Endorsements,
+    LightClient,
+}
+
+impl MessageKind {
+    /// Budget of that kind of message
+    fn limit(self, limits: &MessageRateLimits) -> &MessageRateLimit {
+        match self {
+            MessageKind::BlockHeader => &limits.block_headers,
+            MessageKind::BlockInfo => &limits.block_infos,
+            MessageKind::AskForBlocks => &limits.asks_for_blocks,
+            MessageKind::Operations => &limits.operations,
+            MessageKind::OperationAnnouncements => &limits.operation_announcements,
+            MessageKind::AskForOperations => &limits.asks_for_operations,
+            MessageKind::Endorsements => &limits.endorsements,
+            MessageKind::LightClient => &limits.light_client,
+        }
+    }
+}
+
+/// Token bucket: refilled at `rate` tokens per second, up to `burst` tokens.
+/// The tokens can go below zero when a message costs more than `burst`.
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    /// tokens currently available
+    tokens: f64,
+    /// last time the bucket was refilled
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// Creates a full bucket
+    fn new(limit: &MessageRateLimit, now: Instant) -> Self {
+        TokenBucket {
+            tokens: limit.burst,
+            last_refill: now,
+        }
+    }
+
+    /// Refills the bucket, then takes `cost` tokens from it.
+    /// A message costing more than `burst` tokens needs a full bucket.
+    /// Returns false if not enough tokens were available.
+    fn try_consume(&mut self, limit: &MessageRateLimit, cost: f64, now: Instant) -> bool {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.rate).min(limit.burst);
+        self.last_refill = now;
+        if self.tokens < cost.min(limit.burst) {
+            return false;
+        }
+        self.tokens -= cost;
+        true
+    }
+}
+
 /// Information about a node we are connected to,
 /// essentially our view of its state.
 #[derive(Debug, Clone)]
@@ -23,6 +94,8 @@ pub(crate) struct NodeInfo {
     pub asked_blocks: PreHashMap<BlockId, Instant>,
     /// Instant when the node was added
     pub connection_instant: Instant,
+    /// Capabilities the node advertised during the handshake
+    pub capabilities: NodeCapabilities,
     /// all known operations
     known_operations: PreHashSet<OperationId>,
     /// Same as `known_operations` but sorted for a premature optimization :-)
@@ -31,15 +104,20 @@ pub(crate) struct NodeInfo {
     known_endorsements: PreHashSet<EndorsementId>,
     /// Same as `known_endorsements` but sorted for a premature optimization :-)
     known_endorsements_queue: VecDeque<EndorsementId>,
+    /// message budgets of the node, by kind of message
+    message_buckets: HashMap<MessageKind, TokenBucket>,
+    /// last time the node was penalized for exceeding its message budgets
+    last_rate_limit_penalty: Option<Instant>,
 }
 
 impl NodeInfo {
     /// Creates empty node info
-    pub fn new(pool_settings: &ProtocolConfig) -> NodeInfo {
+    pub fn new(pool_settings: &ProtocolConfig, capabilities: NodeCapabilities) -> NodeInfo {
         NodeInfo {
             known_blocks: PreHashMap::with_capacity(pool_settings.max_node_known_blocks_size),
             asked_blocks: Default::default(),
             connection_instant: Instant::now(),
+            capabilities,
             known_operations: PreHashSet::<OperationId>::with_capacity(
                 pool_settings.max_node_known_ops_size.saturating_add(1),
             ),
@@ -52,7 +130,38 @@ impl NodeInfo {
             known_endorsements_queue: VecDeque::with_capacity(
                 pool_settings.max_node_known_endorsements_size,
             ),
+            message_buckets: Default::default(),
+            last_rate_limit_penalty: None,
+        }
+    }
+
+    /// Takes a message of the given kind containing `item_count` items from the node's budget.
+    /// Returns false if the node exceeded its budget for that kind of message.
+    pub fn try_consume_message_budget(
+        &mut self,
+        kind: MessageKind,
+        item_count: usize,
+        limits: &MessageRateLimits,
+        now: Instant,
+    ) -> bool {
+        let limit = kind.limit(limits);
+        self.message_buckets
+            .entry(kind)
+            .or_insert_with(|| TokenBucket::new(limit, now))
+            .try_consume(limit, item_count.max(1) as f64, now)
+    }
+
+    /// Records a penalty for exceeding the message budgets,
+    /// unless the node was already penalized less than `penalty_interval` ago.
+    /// Returns true if the node must be penalized.
+    pub fn try_rate_limit_penalty(&mut self, penalty_interval: MassaTime, now: Instant) -> bool {
+        if let Some(last) = self.last_rate_limit_penalty {
+            if now.saturating_duration_since(last) < penalty_interval.to_duration() {
+                return false;
+            }
         }
+        self.last_rate_limit_penalty = Some(now);
+        true
     }
 
     /// Get boolean if block knows about the block and when this information was got
@@ -4,19 +4,24 @@
 
 use std::collections::hash_map::Entry;
 
-use crate::node_info::NodeInfo;
+use crate::node_info::{MessageKind, NodeInfo};
 use crate::protocol_worker::ProtocolWorker;
 use massa_hash::Hash;
+use massa_ledger_exports::LedgerSubEntry;
 use massa_logging::massa_trace;
 use massa_models::{
+    address::Address,
     block::Block,
     block::{BlockId, BlockSerializer, WrappedHeader},
     node::NodeId,
     operation::{OperationId, WrappedOperation},
     prehash::{CapacityAllocator, PreHashSet},
+    slot::Slot,
     wrapped::{Id, Wrapped},
 };
-use massa_network_exports::{AskForBlocksInfo, BlockInfoReply, NetworkEvent};
+use massa_network_exports::{
+    AskForBlocksInfo, BlockInfoReply, NetworkEvent, OperationInclusionProof, PeerScoreEvent,
+};
 use massa_protocol_exports::{ProtocolError, ProtocolEvent};
 use massa_serialization::Serializer;
 use massa_storage::Storage;
@@ -34,6 +39,71 @@ static ENDORSEMENTS: &str = "protocol.protocol_worker.on_network_event.received_
 static OPS_BATCH: &str =
     "protocol.protocol_worker.on_network_event.received_operation_announcements";
 static ASKED_OPS: &str = "protocol.protocol_worker.on_network_event.receive_ask_for_operations";
+static ASKED_BALANCES: &str =
+    "protocol.protocol_worker.on_network_event.asked_for_final_balances";
+static ASKED_ROOT: &str = "protocol.protocol_worker.on_network_event.asked_for_ledger_root";
+static ASKED_INCLUSION: &str =
+    "protocol.protocol_worker.on_network_event.asked_for_operation_inclusion";
+static ASKED_CYCLE_ROLLS: &str =
+    "protocol.protocol_worker.on_network_event.asked_for_cycle_rolls";
+static RATE_LIMITED: &str = "protocol.protocol_worker.on_network_event.rate_limited";
+
+/// Node, kind of message and number of items of the network events received from nodes
+fn message_kind(evt: &NetworkEvent) -> Option<(NodeId, MessageKind, usize)> {
+    match evt {
+        NetworkEvent::NewConnection(..) | NetworkEvent::ConnectionClosed(_) => None,
+        NetworkEvent::ReceivedBlockInfo { node, info } => {
+            Some((*node, MessageKind::BlockInfo, info.len()))
+        }
+        NetworkEvent::AskedForBlocks { node, list } => {
+            Some((*node, MessageKind::AskForBlocks, list.len()))
+        }
+        NetworkEvent::ReceivedBlockHeader { source_node_id, .. } => {
+            Some((*source_node_id, MessageKind::BlockHeader, 1))
+        }
+        NetworkEvent::ReceivedOperations { node, operations } => {
+            Some((*node, MessageKind::Operations, operations.len()))
+        }
+        NetworkEvent::ReceivedEndorsements { node, endorsements } => {
+            Some((*node, MessageKind::Endorsements, endorsements.len()))
+        }
+        NetworkEvent::ReceivedOperationAnnouncements {
+            node,
+            operation_prefix_ids,
+        } => Some((
+            *node,
+            MessageKind::OperationAnnouncements,
+            operation_prefix_ids.len(),
+        )),
+        NetworkEvent::ReceiveAskForOperations {
+            node,
+            operation_prefix_ids,
+        } => Some((
+            *node,
+            MessageKind::AskForOperations,
+            operation_prefix_ids.len(),
+        )),
+        NetworkEvent::AskedForFinalBalances { node, addresses } => {
+            Some((*node, MessageKind::LightClient, addresses.len()))
+        }
+        NetworkEvent::ReceivedFinalBalances { node, proofs } => {
+            Some((*node, MessageKind::LightClient, proofs.proofs.len()))
+        }
+        NetworkEvent::AskedForLedgerRoot { node, .. }
+        | NetworkEvent::ReceivedLedgerRoot { node, .. }
+        | NetworkEvent::AskedForCycleRolls { node, .. }
+        | NetworkEvent::ReceivedCycleRolls { node, .. } => {
+            Some((*node, MessageKind::LightClient, 1))
+        }
+        NetworkEvent::AskedForOperationInclusion {
+            node,
+            operation_ids,
+        } => Some((*node, MessageKind::LightClient, operation_ids.len())),
+        NetworkEvent::ReceivedOperationInclusion { node, proofs } => {
+            Some((*node, MessageKind::LightClient, proofs.len()))
+        }
+    }
+}
 
 impl ProtocolWorker {
     /// Manages network event
@@ -47,12 +117,20 @@ impl ProtocolWorker {
         evt: NetworkEvent,
         block_ask_timer: &mut std::pin::Pin<&mut Sleep>,
     ) -> Result<(), ProtocolError> {
+        if let Some((node_id, kind, item_count)) = message_kind(&evt) {
+            if !self
+                .try_consume_message_budget(&node_id, kind, item_count)
+                .await?
+            {
+                return Ok(());
+            }
+        }
         match evt {
-            NetworkEvent::NewConnection(node_id) => {
+            NetworkEvent::NewConnection(node_id, capabilities) => {
                 info!("Connected to node {}", node_id);
-                massa_trace!(NEW_CONN, { "node": node_id });
+                massa_trace!(NEW_CONN, { "node": node_id, "capabilities": capabilities });
                 self.active_nodes
-                    .insert(node_id, NodeInfo::new(&self.config));
+                    .insert(node_id, NodeInfo::new(&self.config, capabilities));
                 self.update_ask_block(block_ask_timer).await?;
             }
             NetworkEvent::ConnectionClosed(node_id) => {
@@ -96,6 +174,9 @@ impl ProtocolWorker {
                     self.note_header_from_node(&header, &source_node_id).await?
                 {
                     if is_new {
+                        let _ = self
+                            .report_node(&source_node_id, PeerScoreEvent::UsefulBlock)
+                            .await;
                         self.send_protocol_event(ProtocolEvent::ReceivedBlockHeader {
                             block_id,
                             header,
@@ -110,7 +191,9 @@ impl ProtocolWorker {
                         or a loss of sync between us and the remote node",
                         source_node_id,
                     );
-                    let _ = self.ban_node(&source_node_id).await;
+                    let _ = self
+                        .report_node(&source_node_id, PeerScoreEvent::InvalidData)
+                        .await;
                 }
             }
             NetworkEvent::ReceivedOperations { node, operations } => {
@@ -129,7 +212,7 @@ impl ProtocolWorker {
                         loss of sync between us and the remote node. Err = {}",
                         node, err
                     );
-                    let _ = self.ban_node(&node).await;
+                    let _ = self.report_node(&node, PeerScoreEvent::InvalidData).await;
                 }
             }
             NetworkEvent::ReceivedOperationAnnouncements {
@@ -148,10 +231,178 @@ impl ProtocolWorker {
                 self.on_asked_operations_received(node, operation_prefix_ids)
                     .await?;
             }
+            NetworkEvent::AskedForFinalBalances { node, addresses } => {
+                massa_trace!(ASKED_BALANCES, { "node": node, "addresses": addresses });
+                self.on_asked_for_final_balances_received(node, addresses)
+                    .await?;
+            }
+            NetworkEvent::AskedForLedgerRoot { node, slot } => {
+                massa_trace!(ASKED_ROOT, { "node": node, "slot": slot });
+                self.on_asked_for_ledger_root_received(node, slot).await?;
+            }
+            NetworkEvent::AskedForCycleRolls { node, cycle } => {
+                massa_trace!(ASKED_CYCLE_ROLLS, { "node": node, "cycle": cycle });
+                self.on_asked_for_cycle_rolls_received(node, cycle).await?;
+            }
+            NetworkEvent::AskedForOperationInclusion {
+                node,
+                operation_ids,
+            } => {
+                massa_trace!(ASKED_INCLUSION, { "node": node, "operation_ids": operation_ids });
+                self.on_asked_for_operation_inclusion_received(node, operation_ids)
+                    .await?;
+            }
+            // replies to light client queries: a full node never asks for them
+            NetworkEvent::ReceivedFinalBalances { node, .. }
+            | NetworkEvent::ReceivedLedgerRoot { node, .. }
+            | NetworkEvent::ReceivedOperationInclusion { node, .. }
+            | NetworkEvent::ReceivedCycleRolls { node, .. } => {
+                warn!("node {} sent us an unsolicited light client reply", node);
+            }
         }
         Ok(())
     }
 
+    /// A light client asked for the final balances of some addresses.
+    /// They are answered with proofs against the root of the final ledger,
+    /// that the light client checks before trusting them.
+    async fn on_asked_for_final_balances_received(
+        &mut self,
+        from_node_id: NodeId,
+        addresses: Vec<Address>,
+    ) -> Result<(), ProtocolError> {
+        if !self.active_nodes.contains_key(&from_node_id) {
+            return Ok(());
+        }
+        let proofs = self.execution_controller.get_final_ledger_proofs(
+            addresses
+                .into_iter()
+                .map(|address| (address, LedgerSubEntry::SeqBalance))
+                .collect(),
+        );
+        self.network_command_sender
+            .send_final_balances(from_node_id, proofs)
+            .await
+            .map_err(|_| {
+                ProtocolError::ChannelError(
+                    "send final balances network command send failed".into(),
+                )
+            })
+    }
+
+    /// A light client asked for the root of the final ledger at the output of a slot,
+    /// to confirm the root another node proved balances against.
+    async fn on_asked_for_ledger_root_received(
+        &mut self,
+        from_node_id: NodeId,
+        slot: Slot,
+    ) -> Result<(), ProtocolError> {
+        if !self.active_nodes.contains_key(&from_node_id) {
+            return Ok(());
+        }
+        let root = self.execution_controller.get_final_ledger_root(slot);
+        self.network_command_sender
+            .send_ledger_root(from_node_id, slot, root)
+            .await
+            .map_err(|_| {
+                ProtocolError::ChannelError("send ledger root network command send failed".into())
+            })
+    }
+
+    /// A light client asked for the roll counts at the end of a final cycle,
+    /// to feed its selector with the draws of the cycles to come.
+    async fn on_asked_for_cycle_rolls_received(
+        &mut self,
+        from_node_id: NodeId,
+        cycle: u64,
+    ) -> Result<(), ProtocolError> {
+        if !self.active_nodes.contains_key(&from_node_id) {
+            return Ok(());
+        }
+        let rolls = self.execution_controller.get_final_cycle_rolls(cycle);
+        self.network_command_sender
+            .send_cycle_rolls(from_node_id, cycle, rolls)
+            .await
+            .map_err(|_| {
+                ProtocolError::ChannelError("send cycle rolls network command send failed".into())
+            })
+    }
+
+    /// A light client asked in which blocks some operations were included.
+    /// Each included operation is answered with the header of a block containing it
+    /// and the operation list of that block, that the light client checks against
+    /// the operation merkle root of the header.
+    async fn on_asked_for_operation_inclusion_received(
+        &mut self,
+        from_node_id: NodeId,
+        operation_ids: Vec<OperationId>,
+    ) -> Result<(), ProtocolError> {
+        if !self.active_nodes.contains_key(&from_node_id) {
+            return Ok(());
+        }
+        let proofs = {
+            let blocks = self.storage.read_blocks();
+            operation_ids
+                .into_iter()
+                .map(|operation_id| {
+                    let proof = blocks
+                        .get_blocks_by_operation(&operation_id)
+                        .and_then(|block_ids| block_ids.iter().find_map(|id| blocks.get(id)))
+                        .map(|block| OperationInclusionProof {
+                            header: block.content.header.clone(),
+                            operation_ids: block.content.operations.clone(),
+                        });
+                    (operation_id, proof)
+                })
+                .collect()
+        };
+        self.network_command_sender
+            .send_operation_inclusion(from_node_id, proofs)
+            .await
+            .map_err(|_| {
+                ProtocolError::ChannelError(
+                    "send operation inclusion network command send failed".into(),
+                )
+            })
+    }
+
+    /// Takes a message of the given kind containing `item_count` items from the budget of a node.
+    /// A node over budget has its message dropped,
+    /// and is penalized at most once per `penalty_interval`.
+    ///
+    /// Returns false if the message must be dropped.
+    async fn try_consume_message_budget(
+        &mut self,
+        node_id: &NodeId,
+        kind: MessageKind,
+        item_count: usize,
+    ) -> Result<bool, ProtocolError> {
+        let limits = self.config.message_rate_limits;
+        let now = Instant::now();
+        let (within_budget, penalize) = match self.active_nodes.get_mut(node_id) {
+            Some(node_info) => {
+                let within_budget =
+                    node_info.try_consume_message_budget(kind, item_count, &limits, now);
+                let penalize = !within_budget
+                    && node_info.try_rate_limit_penalty(limits.penalty_interval, now);
+                (within_budget, penalize)
+            }
+            // messages from unknown nodes are handled case by case
+            None => (true, false),
+        };
+        if !within_budget {
+            massa_trace!(
+                RATE_LIMITED,
+                { "node": node_id, "kind": format!("{:?}", kind), "items": item_count }
+            );
+        }
+        if penalize {
+            self.report_node(node_id, PeerScoreEvent::RateLimited)
+                .await?;
+        }
+        Ok(within_budget)
+    }
+
     /// Network ask the local node for blocks
     ///
     /// React on another node asking for blocks informations. We can forward the operation ids if
@@ -246,6 +497,9 @@ impl ProtocolWorker {
                     "Node {} sent us header for block id {} but we already received it.",
                     from_node_id, block_id
                 );
+                let _ = self
+                    .report_node(&from_node_id, PeerScoreEvent::Duplicate)
+                    .await;
                 if let Some(node) = self.active_nodes.get_mut(&from_node_id) && node.asked_blocks.contains_key(&block_id) {
                     node.asked_blocks.remove(&block_id);
                     node.insert_known_blocks(&[block_id], false, Instant::now(), self.config.max_node_known_blocks_size);
@@ -261,7 +515,9 @@ impl ProtocolWorker {
                 or a loss of sync between us and the remote node. Err = {}",
                 from_node_id, err
             );
-            let _ = self.ban_node(&from_node_id).await;
+            let _ = self
+                .report_node(&from_node_id, PeerScoreEvent::InvalidData)
+                .await;
             return Ok(());
         };
         if let Some(info) = self.block_wishlist.get_mut(&block_id) {
@@ -278,10 +534,10 @@ impl ProtocolWorker {
     /// On block information received, manage when we get a list of operations.
     /// Ask for the missing operations that are not in the `checked_operations` cache variable.
     ///
-    /// # Ban
+    /// # Penalty
     /// Start compute the operations serialized total size with the operation we know.
-    /// Ban the node if the operations contained in the block overflow the max size. We don't
-    /// forward the block to the graph in that case.
+    /// Penalize the node if the operations contained in the block overflow the max size.
+    /// We don't forward the block to the graph in that case.
     ///
     /// # Parameters:
     /// - `from_node_id`: Node which sent us the information.
@@ -338,6 +594,9 @@ impl ProtocolWorker {
                 "Node {} sent us an operation list for block id {} but we already received it.",
                 from_node_id, block_id
             );
+            let _ = self
+                .report_node(&from_node_id, PeerScoreEvent::Duplicate)
+                .await;
             if let Some(node) = self.active_nodes.get_mut(&from_node_id) && node.asked_blocks.contains_key(&block_id) {
                 node.asked_blocks.remove(&block_id);
                 node.insert_known_blocks(&[block_id], false, Instant::now(), self.config.max_node_known_blocks_size);
@@ -364,7 +623,9 @@ impl ProtocolWorker {
 
             if info.operations_size > self.config.max_serialized_operations_size_per_block {
                 warn!("Node id {} sent us a operation list for block id {} but the operations we already have in our records exceed max size.", from_node_id, block_id);
-                let _ = self.ban_node(&from_node_id).await;
+                let _ = self
+                    .report_node(&from_node_id, PeerScoreEvent::InvalidData)
+                    .await;
                 return Ok(());
             }
 
@@ -381,7 +642,9 @@ impl ProtocolWorker {
             }
         } else {
             warn!("Node id {} sent us a operation list for block id {} but the hash in header doesn't match.", from_node_id, block_id);
-            let _ = self.ban_node(&from_node_id).await;
+            let _ = self
+                .report_node(&from_node_id, PeerScoreEvent::InvalidData)
+                .await;
         }
         Ok(())
     }
@@ -389,8 +652,8 @@ impl ProtocolWorker {
     /// Checks full block operations that we asked. (Because their was missing in the
     /// `checked_operations` cache variable, refer to `on_block_operation_list_received`)
     ///
-    /// # Ban
-    /// Ban the node if it doesn't fill the requirement. Forward to the graph with a
+    /// # Penalty
+    /// Penalize the node if it doesn't fill the requirement. Forward to the graph with a
     /// [ProtocolEvent::ReceivedBlock] if the operations are under a max size.
     ///
     /// - thread incorect for an operation
@@ -413,7 +676,9 @@ impl ProtocolWorker {
                 "Node id {} sent us operations for block id {} but they failed at verifications. Err = {}",
                 from_node_id, block_id, err
             );
-            let _ = self.ban_node(&from_node_id).await;
+            let _ = self
+                .report_node(&from_node_id, PeerScoreEvent::InvalidData)
+                .await;
             return Ok(());
         }
 
@@ -446,7 +711,7 @@ impl ProtocolWorker {
                 let block_ids_set = block_operation_ids.clone().into_iter().collect();
                 let known_operations = info.storage.claim_operation_refs(&block_ids_set);
 
-                // Ban the node if:
+                // Penalize the node if:
                 // - mismatch with asked operations (asked operations are the one that are not in storage) + operations already in storage and block operations
                 // - full operations serialized size overflow
                 let full_op_size: usize = {
@@ -458,7 +723,9 @@ impl ProtocolWorker {
                 };
                 if full_op_size > self.config.max_serialized_operations_size_per_block {
                     warn!("Node id {} sent us full operations for block id {} but they exceed max size.", from_node_id, block_id);
-                    let _ = self.ban_node(&from_node_id).await;
+                    let _ = self
+                        .report_node(&from_node_id, PeerScoreEvent::InvalidData)
+                        .await;
                     self.block_wishlist.remove(&block_id);
                     ProtocolEvent::InvalidBlock { block_id, header }
                 } else {
@@ -544,8 +811,8 @@ impl ProtocolWorker {
             BlockInfoReply::Info(operation_list) => {
                 // Ask for missing operations ids and print a warning if there is no header for
                 // that block.
-                // Ban the node if the operation ids hash doesn't match with the hash contained in
-                // the block_header.
+                // Penalize the node if the operation ids hash doesn't match with the hash
+                // contained in the block_header.
                 self.on_block_operation_list_received(from_node_id, block_id, operation_list)
                     .await
             }
@@ -3,6 +3,7 @@
 use crate::checked_operations::CheckedOperations;
 use crate::{node_info::NodeInfo, worker_operations_impl::OperationBatchBuffer};
 
+use massa_execution_exports::ExecutionController;
 use massa_logging::massa_trace;
 
 use massa_models::slot::Slot;
@@ -10,12 +11,14 @@ use massa_models::timeslots::get_block_slot_timestamp;
 use massa_models::{
     block::{BlockId, WrappedHeader},
     endorsement::{EndorsementId, WrappedEndorsement},
-    node::NodeId,
+    node::{BanReason, NodeId},
     operation::OperationPrefixId,
     operation::{OperationId, WrappedOperation},
     prehash::{CapacityAllocator, PreHashMap, PreHashSet},
 };
-use massa_network_exports::{AskForBlocksInfo, NetworkCommandSender, NetworkEventReceiver};
+use massa_network_exports::{
+    AskForBlocksInfo, NetworkCommandSender, NetworkEventReceiver, NodeCapabilities, PeerScoreEvent,
+};
 use massa_pool_exports::PoolController;
 use massa_protocol_exports::{
     ProtocolCommand, ProtocolCommandSender, ProtocolConfig, ProtocolError, ProtocolEvent,
@@ -43,12 +46,15 @@ use tracing::{debug, error, info, warn};
 /// * `config`: protocol settings
 /// * `network_command_sender`: the `NetworkCommandSender` we interact with
 /// * `network_event_receiver`: the `NetworkEventReceiver` we interact with
+/// * `pool_controller`: link to the pool component
+/// * `execution_controller`: link to the execution component, used to serve light clients
 /// * `storage`: Shared storage to fetch data that are fetch across all modules
 pub async fn start_protocol_controller(
     config: ProtocolConfig,
     network_command_sender: NetworkCommandSender,
     network_event_receiver: NetworkEventReceiver,
     pool_controller: Box<dyn PoolController>,
+    execution_controller: Box<dyn ExecutionController>,
     storage: Storage,
 ) -> Result<
     (
@@ -77,6 +83,7 @@ pub async fn start_protocol_controller(
                 controller_manager_rx,
             },
             pool_controller,
+            execution_controller,
             storage,
         )
         .run_loop()
@@ -137,6 +144,8 @@ pub struct ProtocolWorker {
     controller_event_tx: mpsc::Sender<ProtocolEvent>,
     /// Channel to send protocol pool events to the controller.
     pool_controller: Box<dyn PoolController>,
+    /// Link to the execution component, used to answer balance queries of light clients.
+    pub(crate) execution_controller: Box<dyn ExecutionController>,
     /// Channel receiving commands from the controller.
     controller_command_rx: mpsc::Receiver<ProtocolCommand>,
     /// Channel to send management commands to the controller.
@@ -193,6 +202,7 @@ impl ProtocolWorker {
             controller_manager_rx,
         }: ProtocolWorkerChannels,
         pool_controller: Box<dyn PoolController>,
+        execution_controller: Box<dyn ExecutionController>,
         storage: Storage,
     ) -> ProtocolWorker {
         ProtocolWorker {
@@ -201,6 +211,7 @@ impl ProtocolWorker {
             network_event_receiver,
             controller_event_tx,
             pool_controller,
+            execution_controller,
             controller_command_rx,
             controller_manager_rx,
             active_nodes: Default::default(),
@@ -517,8 +528,14 @@ impl ProtocolWorker {
 
         // list blocks to re-ask and gather candidate nodes to ask from
         let mut candidate_nodes: PreHashMap<BlockId, Vec<_>> = Default::default();
+        // blocks old enough to be asked to archive nodes first
+        let mut archive_blocks: PreHashSet<BlockId> = Default::default();
+        let archive_threshold = MassaTime::now(self.config.clock_compensation)?
+            .saturating_sub(self.config.archive_block_age);
         let mut ask_block_list: HashMap<NodeId, Vec<(BlockId, AskForBlocksInfo)>> =
             Default::default();
+        // nodes that did not answer a block request in time
+        let mut timed_out_nodes: Vec<NodeId> = Vec::new();
 
         // list blocks to re-ask and from whom
         for (hash, block_info) in self.block_wishlist.iter() {
@@ -541,8 +558,26 @@ impl ProtocolWorker {
                 )
             };
             let mut needs_ask = true;
+            if let Some(header) = &block_info.header {
+                let slot_timestamp = get_block_slot_timestamp(
+                    self.config.thread_count,
+                    self.config.t0,
+                    self.config.genesis_timestamp,
+                    header.content.slot,
+                )?;
+                if slot_timestamp < archive_threshold {
+                    archive_blocks.insert(*hash);
+                }
+            }
 
             for (node_id, node_info) in self.active_nodes.iter_mut() {
+                // light clients only follow headers: they never have full blocks
+                if node_info
+                    .capabilities
+                    .contains(NodeCapabilities::LIGHT_CLIENT)
+                {
+                    continue;
+                }
                 // map to remove the borrow on asked_blocks. Otherwise can't call insert_known_blocks
                 let ask_time_opt = node_info.asked_blocks.get(hash).copied();
                 let (timeout_at_opt, timed_out) = if let Some(ask_time) = ask_time_opt {
@@ -589,6 +624,7 @@ impl ProtocolWorker {
                                 timeout_at,
                                 self.config.max_node_known_blocks_size,
                             );
+                            timed_out_nodes.push(*node_id);
                             (2u8, ask_time_opt)
                         } else {
                             // told us it has it after a timeout: good candidate again
@@ -605,6 +641,7 @@ impl ProtocolWorker {
                                 timeout_at,
                                 self.config.max_node_known_blocks_size,
                             );
+                            timed_out_nodes.push(*node_id);
                         }
                         (2u8, ask_time_opt)
                     }
@@ -616,6 +653,7 @@ impl ProtocolWorker {
                             timeout_at,
                             self.config.max_node_known_blocks_size,
                         );
+                        timed_out_nodes.push(*node_id);
                         (2u8, ask_time_opt)
                     }
                 };
@@ -655,6 +693,7 @@ impl ProtocolWorker {
             .collect();
 
         for (hash, criteria) in candidate_nodes.into_iter() {
+            let archive_block = archive_blocks.contains(&hash);
             // find the best node
             if let Some((_knowledge, best_node, required_info)) = criteria
                 .into_iter()
@@ -664,11 +703,15 @@ impl ProtocolWorker {
                         <= self.config.max_simultaneous_ask_blocks_per_node
                 })
                 .min_by_key(|(knowledge, node_id, _)| {
+                    // will not panic, already checked
+                    let node_info = self.active_nodes.get(node_id).unwrap();
+                    let not_archive = !node_info.capabilities.contains(NodeCapabilities::ARCHIVE);
                     (
-                        *knowledge,                                                 // block knowledge
+                        archive_block && not_archive, // archive node for old blocks
+                        *knowledge,                   // block knowledge
                         *active_block_req_count.get(node_id).unwrap_or(&0), // active requests
-                        self.active_nodes.get(node_id).unwrap().connection_instant, // node age (will not panic, already checked)
-                        *node_id,                                                   // node ID
+                        node_info.connection_instant, // node age
+                        *node_id,                     // node ID
                     )
                 })
             {
@@ -690,6 +733,11 @@ impl ProtocolWorker {
             }
         }
 
+        // lower the score of nodes that did not answer in time
+        for node_id in timed_out_nodes {
+            let _ = self.report_node(&node_id, PeerScoreEvent::Timeout).await;
+        }
+
         // send AskBlockEvents
         if !ask_block_list.is_empty() {
             //massa_trace!("protocol.protocol_worker.update_ask_block", {
@@ -709,7 +757,21 @@ impl ProtocolWorker {
         Ok(())
     }
 
-    /// Ban a node.
+    /// Report the behavior of a node to the network,
+    /// which updates the score of its peer and bans it if the score gets too low.
+    pub(crate) async fn report_node(
+        &mut self,
+        node_id: &NodeId,
+        event: PeerScoreEvent,
+    ) -> Result<(), ProtocolError> {
+        massa_trace!("protocol.protocol_worker.report_node", { "node": node_id, "event": event });
+        self.network_command_sender
+            .node_score_event(*node_id, event)
+            .await
+            .map_err(|_| ProtocolError::ChannelError("Node score event command send failed".into()))
+    }
+
+    /// Ban a node that propagated an attack attempt.
     pub(crate) async fn ban_node(&mut self, node_id: &NodeId) -> Result<(), ProtocolError> {
         massa_trace!("protocol.protocol_worker.ban_node", { "node": node_id });
         self.active_nodes.remove(node_id);
@@ -717,7 +779,7 @@ impl ProtocolWorker {
             info!("Not connected to any peers.");
         }
         self.network_command_sender
-            .node_ban_by_ids(vec![*node_id])
+            .node_ban_by_ids(vec![*node_id], BanReason::InvalidBlock, None)
             .await
             .map_err(|_| ProtocolError::ChannelError("Ban node command send failed".into()))?;
         Ok(())
@@ -928,6 +990,7 @@ impl ProtocolWorker {
             if self.checked_operations.insert(&operation_id) {
                 // check signature if the operation wasn't in `checked_operation`
                 operation.verify_signature()?;
+                operation.verify_multisig_signatures()?;
                 new_operations.insert(operation_id, operation);
             };
         }
@@ -941,6 +1004,14 @@ impl ProtocolWorker {
         }
 
         if !new_operations.is_empty() {
+            // the node relayed operations we did not know yet
+            let _ = self
+                .report_node(
+                    source_node_id,
+                    PeerScoreEvent::UsefulOperations(new_operations.len()),
+                )
+                .await;
+
             // prune checked operations cache
             self.prune_checked_operations();
 
@@ -1037,6 +1108,14 @@ impl ProtocolWorker {
         }
 
         if !new_endorsements.is_empty() {
+            // the node relayed endorsements we did not know yet
+            let _ = self
+                .report_node(
+                    source_node_id,
+                    PeerScoreEvent::UsefulEndorsements(new_endorsements.len()),
+                )
+                .await;
+
             self.prune_checked_endorsements();
 
             let mut endorsements = self.storage.clone_without_refs();
@@ -1111,7 +1190,7 @@ mod tests {
     fn test_node_info_know_block() {
         let max_node_known_blocks_size = 10;
         let config = &PROTOCOL_CONFIG;
-        let mut nodeinfo = NodeInfo::new(config);
+        let mut nodeinfo = NodeInfo::new(config, NodeCapabilities::default());
         let instant = Instant::now();
 
         let hash_test = get_dummy_block_id("test");
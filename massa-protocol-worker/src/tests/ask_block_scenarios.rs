@@ -3,10 +3,11 @@
 use super::tools::protocol_test;
 use massa_models::prehash::PreHashSet;
 use massa_models::{block::BlockId, slot::Slot};
-use massa_network_exports::{AskForBlocksInfo, BlockInfoReply, NetworkCommand};
+use massa_network_exports::{AskForBlocksInfo, BlockInfoReply, NetworkCommand, NodeCapabilities};
 use massa_protocol_exports::tests::tools;
 use massa_protocol_exports::tests::tools::{asked_list, assert_hash_asked_to_node};
 use massa_protocol_exports::ProtocolEvent;
+use massa_time::MassaTime;
 use serial_test::serial;
 
 #[tokio::test]
@@ -570,3 +571,67 @@ async fn test_multiple_blocks_without_a_priori() {
     )
     .await;
 }
+
+#[tokio::test]
+#[serial]
+async fn test_old_block_asked_to_archive_node() {
+    let mut protocol_config = *tools::PROTOCOL_CONFIG;
+    // Slot (1, 0) is far enough in the past to be considered archived.
+    protocol_config.genesis_timestamp = MassaTime::now(0)
+        .unwrap()
+        .saturating_sub(protocol_config.t0.checked_mul(10).unwrap());
+    protocol_config.archive_block_age = protocol_config.t0.checked_mul(2).unwrap();
+    protocol_test(
+        &protocol_config,
+        async move |mut network_controller,
+                    mut protocol_event_receiver,
+                    mut protocol_command_sender,
+                    protocol_manager,
+                    protocol_pool_event_receiver| {
+            let node_a = tools::create_and_connect_nodes(1, &mut network_controller)
+                .await
+                .pop()
+                .unwrap();
+            let archive_node = tools::create_node();
+            network_controller
+                .new_connection_with_capabilities(
+                    archive_node.id,
+                    NodeCapabilities::default().with(NodeCapabilities::ARCHIVE),
+                )
+                .await;
+
+            let block = tools::create_block(&node_a.keypair);
+
+            // node A knows the block, but only archive nodes are expected to keep it
+            network_controller
+                .send_header(node_a.id, block.content.header.clone())
+                .await;
+
+            match protocol_event_receiver.wait_event().await.unwrap() {
+                ProtocolEvent::ReceivedBlockHeader { .. } => {}
+                _ => panic!("unexpected protocol event"),
+            };
+
+            protocol_command_sender
+                .send_wishlist_delta(
+                    vec![(block.id, Some(block.content.header.clone()))]
+                        .into_iter()
+                        .collect(),
+                    PreHashSet::<BlockId>::default(),
+                )
+                .await
+                .unwrap();
+
+            assert_hash_asked_to_node(block.id, archive_node.id, &mut network_controller).await;
+
+            (
+                network_controller,
+                protocol_event_receiver,
+                protocol_command_sender,
+                protocol_manager,
+                protocol_pool_event_receiver,
+            )
+        },
+    )
+    .await;
+}
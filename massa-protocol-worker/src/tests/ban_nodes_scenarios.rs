@@ -6,10 +6,10 @@ use massa_models::operation::OperationId;
 use massa_models::prehash::PreHashSet;
 use massa_models::wrapped::Id;
 use massa_models::{block::BlockId, slot::Slot};
-use massa_network_exports::{BlockInfoReply, NetworkCommand};
+use massa_network_exports::{BlockInfoReply, NetworkCommand, PeerScoreEvent};
 use massa_pool_exports::test_exports::MockPoolControllerMessage;
 use massa_protocol_exports::tests::tools;
-use massa_protocol_exports::ProtocolEvent;
+use massa_protocol_exports::{MessageRateLimit, ProtocolEvent};
 use massa_signature::KeyPair;
 use serial_test::serial;
 use std::collections::HashSet;
@@ -17,7 +17,7 @@ use std::time::Duration;
 
 #[tokio::test]
 #[serial]
-async fn test_protocol_bans_node_sending_block_header_with_invalid_signature() {
+async fn test_protocol_penalizes_node_sending_block_header_with_invalid_signature() {
     let protocol_config = &tools::PROTOCOL_CONFIG;
     protocol_test(
         protocol_config,
@@ -42,8 +42,8 @@ async fn test_protocol_bans_node_sending_block_header_with_invalid_signature() {
                 .send_header(creator_node.id, block.content.header.clone())
                 .await;
 
-            // The node is banned.
-            tools::assert_banned_nodes(vec![creator_node.id], &mut network_controller).await;
+            // The node is penalized.
+            tools::assert_penalized_nodes(vec![creator_node.id], &mut network_controller).await;
 
             // Check protocol does not send block to consensus.
             match tools::wait_protocol_event(&mut protocol_event_receiver, 1000.into(), |evt| {
@@ -72,7 +72,7 @@ async fn test_protocol_bans_node_sending_block_header_with_invalid_signature() {
 
 #[tokio::test]
 #[serial]
-async fn test_protocol_bans_node_sending_operation_with_invalid_signature() {
+async fn test_protocol_penalizes_node_sending_operation_with_invalid_signature() {
     let protocol_config = &tools::PROTOCOL_CONFIG;
     protocol_test(
         protocol_config,
@@ -98,8 +98,8 @@ async fn test_protocol_bans_node_sending_operation_with_invalid_signature() {
                 .send_operations(creator_node.id, vec![operation])
                 .await;
 
-            // The node is banned.
-            tools::assert_banned_nodes(vec![creator_node.id], &mut network_controller).await;
+            // The node is penalized.
+            tools::assert_penalized_nodes(vec![creator_node.id], &mut network_controller).await;
 
             // Check protocol does not send operation to pool.
             pool_event_receiver.wait_command(1000.into(), |evt| match evt {
@@ -180,8 +180,11 @@ async fn test_protocol_bans_node_sending_header_with_invalid_signature() {
                 )
                 .await;
 
-            // The node is banned.
-            tools::assert_banned_nodes(vec![to_ban_node.id], &mut network_controller).await;
+            // The node is penalized.
+            tools::assert_penalized_nodes(vec![to_ban_node.id], &mut network_controller).await;
+
+            // The network bans the node once its score drops below the threshold.
+            network_controller.close_connection(to_ban_node.id).await;
 
             // Create another node.
             let not_banned = tools::create_and_connect_nodes(1, &mut network_controller)
@@ -277,7 +280,10 @@ async fn test_protocol_does_not_asks_for_block_from_banned_node_who_propagated_h
             network_controller
                 .send_header(creator_node.id, block.content.header.clone())
                 .await;
-            tools::assert_banned_nodes(vec![creator_node.id], &mut network_controller).await;
+            tools::assert_penalized_nodes(vec![creator_node.id], &mut network_controller).await;
+
+            // The network bans the node once its score drops below the threshold.
+            network_controller.close_connection(creator_node.id).await;
 
             // 5. Ask for block.
             protocol_command_sender
@@ -346,7 +352,10 @@ async fn test_protocol_does_not_send_blocks_when_asked_for_by_banned_node() {
             network_controller
                 .send_header(nodes[1].id, bad_block.content.header.clone())
                 .await;
-            tools::assert_banned_nodes(vec![nodes[1].id], &mut network_controller).await;
+            tools::assert_penalized_nodes(vec![nodes[1].id], &mut network_controller).await;
+
+            // The network bans the node once its score drops below the threshold.
+            network_controller.close_connection(nodes[1].id).await;
 
             // 4. Simulate two nodes asking for a block.
             for node in nodes.iter().take(2) {
@@ -527,7 +536,7 @@ async fn test_protocol_removes_banned_node_on_disconnection() {
             network_controller
                 .send_header(creator_node.id, block.content.header)
                 .await;
-            tools::assert_banned_nodes(vec![creator_node.id], &mut network_controller).await;
+            tools::assert_penalized_nodes(vec![creator_node.id], &mut network_controller).await;
 
             // Close the connection.
             network_controller.close_connection(creator_node.id).await;
@@ -569,3 +578,94 @@ async fn test_protocol_removes_banned_node_on_disconnection() {
     )
     .await;
 }
+
+#[tokio::test]
+#[serial]
+async fn test_protocol_penalizes_node_exceeding_message_budget() {
+    let mut protocol_config = *tools::PROTOCOL_CONFIG;
+    // Allow asking for two blocks, never refilled.
+    protocol_config.message_rate_limits.asks_for_blocks = MessageRateLimit {
+        rate: 0.0,
+        burst: 2.0,
+    };
+    protocol_test(
+        &protocol_config,
+        async move |mut network_controller,
+                    protocol_event_receiver,
+                    protocol_command_sender,
+                    protocol_manager,
+                    protocol_pool_event_receiver| {
+            let mut nodes = tools::create_and_connect_nodes(1, &mut network_controller).await;
+            let node = nodes.pop().expect("Failed to get node info.");
+            let block = tools::create_block(&node.keypair);
+
+            // 1. The node asks for two blocks at once, then three times for a single block.
+            network_controller
+                .send_ask_for_block(
+                    node.id,
+                    vec![
+                        (block.id, Default::default()),
+                        (block.id, Default::default()),
+                    ],
+                )
+                .await;
+            for _ in 0..3 {
+                network_controller
+                    .send_ask_for_block(node.id, vec![(block.id, Default::default())])
+                    .await;
+            }
+
+            // 2. The first ask is answered, using the whole budget.
+            match network_controller
+                .wait_command(1000.into(), |cmd| match cmd {
+                    cmd @ NetworkCommand::SendBlockInfo { .. } => Some(cmd),
+                    _ => None,
+                })
+                .await
+            {
+                Some(NetworkCommand::SendBlockInfo { node: to_node, .. }) => {
+                    assert_eq!(to_node, node.id)
+                }
+                _ => panic!("Protocol did not answer the first ask."),
+            }
+
+            // 3. The next ones are dropped and the node penalized only once.
+            let mut penalties = 0;
+            while let Some(cmd) = network_controller
+                .wait_command(1000.into(), |cmd| match cmd {
+                    cmd @ NetworkCommand::SendBlockInfo { .. } => Some(cmd),
+                    cmd @ NetworkCommand::NodeScoreEvent { .. } => Some(cmd),
+                    _ => None,
+                })
+                .await
+            {
+                match cmd {
+                    NetworkCommand::NodeScoreEvent {
+                        node: penalized_node,
+                        event: PeerScoreEvent::RateLimited,
+                    } => {
+                        assert_eq!(penalized_node, node.id);
+                        penalties += 1;
+                    }
+                    NetworkCommand::SendBlockInfo { .. } => {
+                        panic!("Protocol answered an ask over budget.")
+                    }
+                    _ => {}
+                }
+            }
+            assert_eq!(
+                penalties, 1,
+                "Node not penalized once for exceeding its budget."
+            );
+
+            (
+                network_controller,
+                protocol_event_receiver,
+                protocol_command_sender,
+                protocol_manager,
+                protocol_pool_event_receiver,
+            )
+        },
+    )
+    .await;
+}
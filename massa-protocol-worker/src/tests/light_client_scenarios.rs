@@ -0,0 +1,619 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+use super::tools::protocol_test_with_storage;
+use crate::start_light_client;
+use bitvec::vec::BitVec;
+use massa_hash::Hash;
+use massa_ledger_exports::{LedgerProof, LedgerProofs, LedgerSubEntry};
+use massa_models::{
+    address::Address,
+    amount::{Amount, AmountSerializer},
+    block::{BlockHeader, BlockHeaderSerializer, BlockId, WrappedHeader},
+    slot::Slot,
+};
+use massa_network_exports::{NetworkCommand, NodeCapabilities};
+use massa_pos_exports::{
+    test_exports::{MockSelectorController, MockSelectorControllerMessage},
+    CycleInfo, Selection, SelectorController,
+};
+use massa_protocol_exports::tests::mock_network_controller::MockNetworkController;
+use massa_protocol_exports::tests::tools::{self, create_and_connect_nodes};
+use massa_protocol_exports::LightClientConfig;
+use massa_serialization::Serializer;
+use massa_signature::KeyPair;
+use massa_time::MassaTime;
+use serial_test::serial;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+#[tokio::test]
+#[serial]
+async fn test_protocol_sends_operation_inclusion_proofs() {
+    let protocol_config = &tools::PROTOCOL_CONFIG;
+
+    protocol_test_with_storage(
+        protocol_config,
+        async move |mut network_controller,
+                    protocol_event_receiver,
+                    protocol_command_sender,
+                    protocol_manager,
+                    protocol_pool_event_receiver,
+                    mut storage| {
+            let node = create_and_connect_nodes(1, &mut network_controller)
+                .await
+                .pop()
+                .unwrap();
+
+            // 1. Store a block including an operation.
+            let op = tools::create_operation_with_expire_period(&node.keypair, 5);
+            let unknown_op = tools::create_operation_with_expire_period(&node.keypair, 5);
+            let block = tools::create_block_with_operations(
+                &node.keypair,
+                Slot::new(1, 0),
+                vec![op.clone()],
+            );
+            storage.store_block(block.clone());
+
+            // 2. The node asks for the inclusion of the operations.
+            network_controller
+                .send_ask_for_operation_inclusion(node.id, vec![op.id, unknown_op.id])
+                .await;
+
+            // 3. Check that protocol answers with a proof for the known operation only.
+            let proofs = match network_controller
+                .wait_command(1000.into(), |cmd| match cmd {
+                    cmd @ NetworkCommand::SendOperationInclusion { .. } => Some(cmd),
+                    _ => None,
+                })
+                .await
+            {
+                Some(NetworkCommand::SendOperationInclusion {
+                    node: to_node,
+                    proofs,
+                }) => {
+                    assert_eq!(to_node, node.id);
+                    proofs
+                }
+                _ => panic!("Operation inclusion proofs not sent."),
+            };
+            assert_eq!(proofs.len(), 2);
+            let (proven_op, proof) = &proofs[0];
+            assert_eq!(*proven_op, op.id);
+            let proof = proof.as_ref().expect("missing inclusion proof");
+            assert_eq!(proof.header.id, block.content.header.id);
+            assert!(proof.proves(&op.id));
+            assert_eq!(proofs[1].0, unknown_op.id);
+            assert!(proofs[1].1.is_none());
+
+            (
+                network_controller,
+                protocol_event_receiver,
+                protocol_command_sender,
+                protocol_manager,
+                protocol_pool_event_receiver,
+            )
+        },
+    )
+    .await;
+}
+
+/// create a header without operations nor endorsements
+fn create_header(keypair: &KeyPair, slot: Slot, parents: Vec<BlockId>) -> WrappedHeader {
+    BlockHeader::new_wrapped(
+        BlockHeader {
+            slot,
+            parents,
+            operation_merkle_root: Hash::compute_from(&[]),
+            endorsements: Vec::new(),
+        },
+        BlockHeaderSerializer::new(),
+        keypair,
+    )
+    .unwrap()
+}
+
+/// light client settings with slots starting 100 periods ago
+fn create_light_client_config() -> LightClientConfig {
+    let t0: MassaTime = 1000.into();
+    LightClientConfig {
+        thread_count: 2,
+        endorsement_count: 0,
+        periods_per_cycle: 128,
+        delta_f0: 1,
+        genesis_timestamp: MassaTime::now(0)
+            .unwrap()
+            .saturating_sub(t0.saturating_mul(100)),
+        t0,
+        clock_compensation: 0,
+        query_timeout: 1000.into(),
+        max_pending_queries_per_node: 4,
+        max_query_length: 10,
+        max_final_headers: 100,
+        max_waiting_headers: 100,
+        ledger_root_confirmations: 1,
+        cycle_rolls_sources: 2,
+        controller_channel_size: 1024,
+        event_channel_size: 1024,
+    }
+}
+
+/// A cycle fed to the selector, with its lookback rolls and seed
+type FedCycle = (u64, BTreeMap<Address, u64>, Hash);
+
+/// selector drawing every slot to the same producer, forwarding the cycles it is fed with
+fn create_selector(
+    producer: Address,
+) -> (
+    Box<dyn SelectorController>,
+    std::thread::JoinHandle<()>,
+    mpsc::UnboundedReceiver<FedCycle>,
+) {
+    let (selector_controller, selector_receiver) = MockSelectorController::new_with_receiver();
+    let (fed_cycle_tx, fed_cycle_rx) = mpsc::unbounded_channel();
+    let selector_thread = std::thread::spawn(move || {
+        while let Ok(msg) = selector_receiver.recv() {
+            match msg {
+                MockSelectorControllerMessage::GetSelection { response_tx, .. } => {
+                    response_tx
+                        .send(Ok(Selection {
+                            endorsements: Vec::new(),
+                            producer,
+                        }))
+                        .unwrap();
+                }
+                MockSelectorControllerMessage::FeedCycle {
+                    cycle,
+                    lookback_rolls,
+                    lookback_seed,
+                } => {
+                    let _ = fed_cycle_tx.send((cycle, lookback_rolls, lookback_seed));
+                }
+                _ => {}
+            }
+        }
+    });
+    (selector_controller, selector_thread, fed_cycle_rx)
+}
+
+/// PoS cycle history at genesis: the first cycle only holds the seed bits of the genesis blocks
+fn create_genesis_cycle_history(
+    thread_count: u8,
+    initial_rolls: &BTreeMap<Address, u64>,
+) -> Vec<CycleInfo> {
+    vec![CycleInfo {
+        cycle: 0,
+        complete: false,
+        roll_counts: initial_rolls.clone(),
+        rng_seed: BitVec::repeat(false, thread_count as usize),
+        production_stats: Default::default(),
+    }]
+}
+
+/// proof of the balance of an address in a ledger whose other leaves are summed up in a sibling
+fn create_balance_proofs(address: &Address, balance: Amount) -> LedgerProofs {
+    let mut value = Vec::new();
+    AmountSerializer::new()
+        .serialize(&balance, &mut value)
+        .unwrap();
+    let proof = LedgerProof {
+        key: LedgerSubEntry::SeqBalance.key(address),
+        value: Some(value),
+        siblings: vec![Hash::compute_from(b"other leaves")],
+        other_leaf: None,
+    };
+    LedgerProofs {
+        slot: Slot::new(1, 0),
+        root: proof.compute_root().unwrap(),
+        proofs: vec![proof],
+    }
+}
+
+/// waits for the light client to ask a full node for final balances, returning that node
+async fn wait_ask_for_final_balances(
+    network_controller: &mut MockNetworkController,
+    address: Address,
+) -> massa_models::node::NodeId {
+    match network_controller
+        .wait_command(1000.into(), |cmd| match cmd {
+            cmd @ NetworkCommand::AskForFinalBalances { .. } => Some(cmd),
+            _ => None,
+        })
+        .await
+    {
+        Some(NetworkCommand::AskForFinalBalances { node, addresses }) => {
+            assert_eq!(addresses, vec![address]);
+            node
+        }
+        _ => panic!("Final balances not asked."),
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_light_client_follows_headers_and_queries_full_nodes() {
+    let keypair = KeyPair::generate();
+    let producer = Address::from_public_key(&keypair.get_public_key());
+    let config = create_light_client_config();
+    let (selector_controller, selector_thread, _) = create_selector(producer);
+
+    let genesis_0 = create_header(&keypair, Slot::new(0, 0), Vec::new());
+    let genesis_1 = create_header(&keypair, Slot::new(0, 1), Vec::new());
+    let (mut network_controller, network_command_sender, network_event_receiver) =
+        MockNetworkController::new();
+    let (light_client_command_sender, light_client_event_receiver, light_client_manager) =
+        start_light_client(
+            config,
+            network_command_sender,
+            network_event_receiver,
+            selector_controller,
+            vec![genesis_0.clone(), genesis_1.clone()],
+            Slot::new(0, 1),
+            create_genesis_cycle_history(config.thread_count, &BTreeMap::new()),
+            BTreeMap::new(),
+        )
+        .await
+        .expect("could not start light client");
+
+    let full_node = tools::create_node();
+    network_controller.new_connection(full_node.id).await;
+    let other_full_node = tools::create_node();
+    network_controller.new_connection(other_full_node.id).await;
+    let light_node = tools::create_node();
+    network_controller
+        .new_connection_with_capabilities(light_node.id, NodeCapabilities::LIGHT_CLIENT)
+        .await;
+    // 1. Follow a chain of headers until the first one is final.
+    let block_a = create_header(&keypair, Slot::new(1, 0), vec![genesis_0.id, genesis_1.id]);
+    let block_b = create_header(&keypair, Slot::new(1, 1), vec![block_a.id, genesis_1.id]);
+    let block_c = create_header(&keypair, Slot::new(2, 0), vec![block_a.id, block_b.id]);
+    network_controller
+        .send_header(full_node.id, block_a.clone())
+        .await;
+    network_controller
+        .send_header(full_node.id, block_b.clone())
+        .await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let status = light_client_command_sender.get_status().await.unwrap();
+    assert_eq!(status.last_header_slot, Some(Slot::new(1, 1)));
+    assert!(status
+        .final_blocks
+        .contains(&(genesis_0.id, Slot::new(0, 0))));
+
+    network_controller
+        .send_header(full_node.id, block_c.clone())
+        .await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let status = light_client_command_sender.get_status().await.unwrap();
+    assert_eq!(status.last_header_slot, Some(Slot::new(2, 0)));
+    assert!(status.final_blocks.contains(&(block_a.id, Slot::new(1, 0))));
+    assert!(status
+        .final_blocks
+        .contains(&(genesis_1.id, Slot::new(0, 1))));
+
+    // 2. A header signed by a key that was not drawn is rejected.
+    let intruder = KeyPair::generate();
+    let invalid = create_header(&intruder, Slot::new(2, 1), vec![block_c.id, block_b.id]);
+    network_controller.send_header(full_node.id, invalid).await;
+    match network_controller
+        .wait_command(1000.into(), |cmd| match cmd {
+            cmd @ NetworkCommand::NodeScoreEvent { .. } => Some(cmd),
+            _ => None,
+        })
+        .await
+    {
+        Some(NetworkCommand::NodeScoreEvent { node, .. }) => assert_eq!(node, full_node.id),
+        _ => panic!("Node sending an invalid header was not penalized."),
+    }
+
+    // 3. Balances are asked to a full node, not to the light one,
+    // and trusted once the other full node confirmed the ledger root they are proven against.
+    let address = Address::from_public_key(&KeyPair::generate().get_public_key());
+    let balance = Amount::from_str("100").unwrap();
+    let proofs = create_balance_proofs(&address, balance);
+    let query_sender = light_client_command_sender.clone();
+    let query = tokio::spawn(async move { query_sender.get_final_balances(vec![address]).await });
+    let prover = wait_ask_for_final_balances(&mut network_controller, address).await;
+    let confirmer = if prover == full_node.id {
+        other_full_node.id
+    } else {
+        assert_eq!(prover, other_full_node.id);
+        full_node.id
+    };
+    network_controller
+        .send_final_balances(prover, proofs.clone())
+        .await;
+    match network_controller
+        .wait_command(1000.into(), |cmd| match cmd {
+            cmd @ NetworkCommand::AskForLedgerRoot { .. } => Some(cmd),
+            _ => None,
+        })
+        .await
+    {
+        Some(NetworkCommand::AskForLedgerRoot { node, slot }) => {
+            assert_eq!(node, confirmer);
+            assert_eq!(slot, proofs.slot);
+        }
+        _ => panic!("Ledger root confirmation not asked."),
+    }
+    network_controller
+        .send_ledger_root(confirmer, proofs.slot, Some(proofs.root))
+        .await;
+    let balances = query.await.unwrap().unwrap();
+    assert_eq!(balances.len(), 1);
+    assert_eq!(balances[0].address, address);
+    assert_eq!(balances[0].final_balance, Some(balance));
+    assert_eq!(balances[0].node_id, prover);
+
+    // 4. Balances proven against a root the other full node does not confirm are refused.
+    let query_sender = light_client_command_sender.clone();
+    let query = tokio::spawn(async move { query_sender.get_final_balances(vec![address]).await });
+    let prover = wait_ask_for_final_balances(&mut network_controller, address).await;
+    let confirmer = if prover == full_node.id {
+        other_full_node.id
+    } else {
+        full_node.id
+    };
+    network_controller
+        .send_final_balances(prover, proofs.clone())
+        .await;
+    network_controller
+        .send_ledger_root(
+            confirmer,
+            proofs.slot,
+            Some(Hash::compute_from(b"other root")),
+        )
+        .await;
+    assert!(query.await.unwrap().is_err());
+
+    // 5. A balance that does not match its proof is refused and its sender penalized.
+    let query_sender = light_client_command_sender.clone();
+    let query = tokio::spawn(async move { query_sender.get_final_balances(vec![address]).await });
+    let prover = wait_ask_for_final_balances(&mut network_controller, address).await;
+    let mut forged = proofs.clone();
+    AmountSerializer::new()
+        .serialize(
+            &Amount::from_str("1000").unwrap(),
+            forged.proofs[0].value.insert(Vec::new()),
+        )
+        .unwrap();
+    network_controller.send_final_balances(prover, forged).await;
+    assert!(query.await.unwrap().is_err());
+    match network_controller
+        .wait_command(1000.into(), |cmd| match cmd {
+            cmd @ NetworkCommand::NodeScoreEvent { .. } => Some(cmd),
+            _ => None,
+        })
+        .await
+    {
+        Some(NetworkCommand::NodeScoreEvent { node, .. }) => assert_eq!(node, prover),
+        _ => panic!("Node sending an invalid balance proof was not penalized."),
+    }
+
+    light_client_manager
+        .stop(light_client_event_receiver)
+        .await
+        .expect("Failed to shutdown light client.");
+    selector_thread.join().unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn test_light_client_finality_with_competing_blocks() {
+    let keypair = KeyPair::generate();
+    let producer = Address::from_public_key(&keypair.get_public_key());
+    let config = create_light_client_config();
+    let (selector_controller, selector_thread, _) = create_selector(producer);
+
+    let genesis_0 = create_header(&keypair, Slot::new(0, 0), Vec::new());
+    let genesis_1 = create_header(&keypair, Slot::new(0, 1), Vec::new());
+    let (mut network_controller, network_command_sender, network_event_receiver) =
+        MockNetworkController::new();
+    let (light_client_command_sender, light_client_event_receiver, light_client_manager) =
+        start_light_client(
+            config,
+            network_command_sender,
+            network_event_receiver,
+            selector_controller,
+            vec![genesis_0.clone(), genesis_1.clone()],
+            Slot::new(0, 1),
+            create_genesis_cycle_history(config.thread_count, &BTreeMap::new()),
+            BTreeMap::new(),
+        )
+        .await
+        .expect("could not start light client");
+    let full_node = tools::create_node();
+    network_controller.new_connection(full_node.id).await;
+
+    // 1. Two forks, each block of one being incompatible with the block of the same thread of the other.
+    let block_a1 = create_header(&keypair, Slot::new(1, 0), vec![genesis_0.id, genesis_1.id]);
+    let block_b1 = create_header(&keypair, Slot::new(1, 1), vec![block_a1.id, genesis_1.id]);
+    let block_a2 = create_header(&keypair, Slot::new(2, 0), vec![genesis_0.id, genesis_1.id]);
+    let block_b2 = create_header(&keypair, Slot::new(2, 1), vec![block_a2.id, genesis_1.id]);
+    let block_c1 = create_header(&keypair, Slot::new(3, 0), vec![block_a1.id, block_b1.id]);
+    for header in [&block_a1, &block_b1, &block_a2, &block_b2, &block_c1] {
+        network_controller
+            .send_header(full_node.id, header.clone())
+            .await;
+    }
+
+    // 2. The first fork has enough descendants but does not outweigh the other one by more than delta_f0.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let status = light_client_command_sender.get_status().await.unwrap();
+    assert_eq!(status.last_header_slot, Some(Slot::new(3, 0)));
+    assert_eq!(status.pending_header_count, 5);
+    assert!(!status
+        .final_blocks
+        .contains(&(block_a1.id, Slot::new(1, 0))));
+
+    // 3. Extending the first fork makes it final, and the other fork is forgotten.
+    let block_d1 = create_header(&keypair, Slot::new(3, 1), vec![block_c1.id, block_b1.id]);
+    network_controller
+        .send_header(full_node.id, block_d1.clone())
+        .await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let status = light_client_command_sender.get_status().await.unwrap();
+    assert!(status
+        .final_blocks
+        .contains(&(block_a1.id, Slot::new(1, 0))));
+    assert!(status
+        .final_blocks
+        .contains(&(block_b1.id, Slot::new(1, 1))));
+    assert_eq!(status.pending_header_count, 2);
+
+    light_client_manager
+        .stop(light_client_event_receiver)
+        .await
+        .expect("Failed to shutdown light client.");
+    selector_thread.join().unwrap();
+}
+
+/// waits for the light client to ask two full nodes for the roll counts of a cycle, returning them
+async fn wait_ask_for_cycle_rolls(
+    network_controller: &mut MockNetworkController,
+    cycle: u64,
+) -> Vec<massa_models::node::NodeId> {
+    let mut nodes = Vec::new();
+    for _ in 0..2 {
+        match network_controller
+            .wait_command(2000.into(), |cmd| match cmd {
+                cmd @ NetworkCommand::AskForCycleRolls { .. } => Some(cmd),
+                _ => None,
+            })
+            .await
+        {
+            Some(NetworkCommand::AskForCycleRolls {
+                node,
+                cycle: asked_cycle,
+            }) => {
+                assert_eq!(asked_cycle, cycle);
+                nodes.push(node);
+            }
+            _ => panic!("Cycle roll counts not asked."),
+        }
+    }
+    assert_ne!(nodes[0], nodes[1]);
+    nodes
+}
+
+/// waits for the selector to be fed with a cycle
+async fn wait_fed_cycle(fed_cycle_rx: &mut mpsc::UnboundedReceiver<FedCycle>) -> FedCycle {
+    tokio::time::timeout(Duration::from_millis(1000), fed_cycle_rx.recv())
+        .await
+        .expect("no cycle was drawn")
+        .unwrap()
+}
+
+/// seed of a cycle from the seed bits of its slots
+fn compute_seed(seed_bits: &[bool]) -> Hash {
+    let seed_bits: BitVec<u8> = seed_bits.iter().copied().collect();
+    Hash::compute_from(&seed_bits.into_vec())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_light_client_advances_draws() {
+    let keypair = KeyPair::generate();
+    let producer = Address::from_public_key(&keypair.get_public_key());
+    let config = LightClientConfig {
+        periods_per_cycle: 2,
+        ..create_light_client_config()
+    };
+    let (selector_controller, selector_thread, mut fed_cycle_rx) = create_selector(producer);
+    let initial_rolls = BTreeMap::from([(producer, 1)]);
+
+    let genesis_0 = create_header(&keypair, Slot::new(0, 0), Vec::new());
+    let genesis_1 = create_header(&keypair, Slot::new(0, 1), Vec::new());
+    let (mut network_controller, network_command_sender, network_event_receiver) =
+        MockNetworkController::new();
+    let (_light_client_command_sender, light_client_event_receiver, light_client_manager) =
+        start_light_client(
+            config,
+            network_command_sender,
+            network_event_receiver,
+            selector_controller,
+            vec![genesis_0.clone(), genesis_1.clone()],
+            Slot::new(0, 1),
+            create_genesis_cycle_history(config.thread_count, &initial_rolls),
+            initial_rolls.clone(),
+        )
+        .await
+        .expect("could not start light client");
+    let full_node = tools::create_node();
+    network_controller.new_connection(full_node.id).await;
+    let other_full_node = tools::create_node();
+    network_controller.new_connection(other_full_node.id).await;
+
+    // 1. Once the blocks of the last slots of cycle 0 are final, cycle 2 is drawn
+    // from the initial rolls and the seed of cycle 0.
+    let block_a = create_header(&keypair, Slot::new(1, 0), vec![genesis_0.id, genesis_1.id]);
+    let block_b = create_header(&keypair, Slot::new(1, 1), vec![block_a.id, genesis_1.id]);
+    let block_c = create_header(&keypair, Slot::new(2, 0), vec![block_a.id, block_b.id]);
+    let block_d = create_header(&keypair, Slot::new(2, 1), vec![block_c.id, block_b.id]);
+    for header in [&block_a, &block_b, &block_c, &block_d] {
+        network_controller
+            .send_header(full_node.id, header.clone())
+            .await;
+    }
+    let (cycle, lookback_rolls, lookback_seed) = wait_fed_cycle(&mut fed_cycle_rx).await;
+    assert_eq!(cycle, 2);
+    assert_eq!(lookback_rolls, initial_rolls);
+    assert_eq!(
+        lookback_seed,
+        compute_seed(&[
+            false,
+            false,
+            block_a.id.get_first_bit(),
+            block_b.id.get_first_bit()
+        ])
+    );
+
+    // 2. The roll counts at the end of cycle 0 are asked to both full nodes.
+    let rolls = BTreeMap::from([(producer, 5)]);
+    for node in wait_ask_for_cycle_rolls(&mut network_controller, 0).await {
+        network_controller
+            .send_cycle_rolls(node, 0, Some(rolls.clone()))
+            .await;
+    }
+
+    // 3. Once cycle 1 is final, slot (3, 0) being missed, cycle 3 is drawn from those roll counts.
+    let block_f = create_header(&keypair, Slot::new(3, 1), vec![block_c.id, block_d.id]);
+    let block_g = create_header(&keypair, Slot::new(4, 0), vec![block_c.id, block_f.id]);
+    let block_h = create_header(&keypair, Slot::new(4, 1), vec![block_g.id, block_f.id]);
+    let block_i = create_header(&keypair, Slot::new(5, 0), vec![block_g.id, block_h.id]);
+    for header in [&block_f, &block_g, &block_h, &block_i] {
+        network_controller
+            .send_header(full_node.id, header.clone())
+            .await;
+    }
+    let (cycle, lookback_rolls, lookback_seed) = wait_fed_cycle(&mut fed_cycle_rx).await;
+    assert_eq!(cycle, 3);
+    assert_eq!(lookback_rolls, rolls);
+    assert_eq!(
+        lookback_seed,
+        compute_seed(&[
+            block_c.id.get_first_bit(),
+            block_d.id.get_first_bit(),
+            Slot::new(3, 0).get_first_bit(),
+            block_f.id.get_first_bit()
+        ])
+    );
+
+    // 4. Roll counts the full nodes disagree on are not used, and asked again later.
+    let nodes = wait_ask_for_cycle_rolls(&mut network_controller, 1).await;
+    network_controller
+        .send_cycle_rolls(nodes[0], 1, Some(rolls.clone()))
+        .await;
+    network_controller
+        .send_cycle_rolls(nodes[1], 1, Some(BTreeMap::from([(producer, 6)])))
+        .await;
+    wait_ask_for_cycle_rolls(&mut network_controller, 1).await;
+    assert!(fed_cycle_rx.try_recv().is_err());
+
+    light_client_manager
+        .stop(light_client_event_receiver)
+        .await
+        .expect("Failed to shutdown light client.");
+    selector_thread.join().unwrap();
+}
@@ -1,5 +1,6 @@
 use crate::start_protocol_controller;
 use futures::Future;
+use massa_execution_exports::test_exports::MockExecutionController;
 use massa_pool_exports::test_exports::{MockPoolController, PoolEventReceiver};
 use massa_protocol_exports::{
     tests::mock_network_controller::MockNetworkController, ProtocolCommandSender, ProtocolConfig,
@@ -30,6 +31,7 @@ where
         MockNetworkController::new();
 
     let (pool_controller, pool_event_receiver) = MockPoolController::new_with_receiver();
+    let (execution_controller, _execution_receiver) = MockExecutionController::new_with_receiver();
 
     // start protocol controller
     let (protocol_command_sender, protocol_event_receiver, protocol_manager): (
@@ -41,6 +43,7 @@ where
         network_command_sender,
         network_event_receiver,
         pool_controller,
+        execution_controller,
         Storage::create_root(),
     )
     .await
@@ -90,6 +93,7 @@ where
     let (network_controller, network_command_sender, network_event_receiver) =
         MockNetworkController::new();
     let (pool_controller, mock_pool_receiver) = MockPoolController::new_with_receiver();
+    let (execution_controller, _execution_receiver) = MockExecutionController::new_with_receiver();
     let storage = Storage::create_root();
     // start protocol controller
     let (protocol_command_sender, protocol_event_receiver, protocol_manager) =
@@ -98,6 +102,7 @@ where
             network_command_sender,
             network_event_receiver,
             pool_controller,
+            execution_controller,
             storage.clone(),
         )
         .await
@@ -17,6 +17,7 @@ use massa_models::{
     operation::{OperationPrefixIds, WrappedOperation},
     prehash::{CapacityAllocator, PreHashSet},
 };
+use massa_network_exports::PeerScoreEvent;
 use massa_protocol_exports::ProtocolError;
 use massa_time::TimeError;
 use tokio::time::{sleep_until, Instant, Sleep};
@@ -139,7 +140,9 @@ impl ProtocolWorker {
     ) {
         if let Err(err) = self.note_operations_from_node(operations, &node_id).await {
             warn!("node {} sent us critically incorrect operation, which may be an attack attempt by the remote node or a loss of sync between us and the remote node. Err = {}", node_id, err);
-            let _ = self.ban_node(&node_id).await;
+            let _ = self
+                .report_node(&node_id, PeerScoreEvent::InvalidData)
+                .await;
         }
     }
 
@@ -0,0 +1,657 @@
+// Copyright (c) 2022 MASSA LABS <info@massa.net>
+
+//! Encoding of smart contract call arguments and events according to a `ContractAbi`.
+//!
+//! Arguments are serialized the way the `Args` class of the contract SDK does:
+//! the values are concatenated in the order of the ABI, booleans as one byte, numbers in little-endian,
+//! and strings, addresses and bytes prefixed with their length in bytes as a little-endian `u32`.
+//! Strings are UTF-8 encoded and addresses are serialized as their string representation.
+//!
+//! * the serialized arguments are carried in the string parameter of a call with one character per byte
+//! * an event described by the ABI is emitted as a JSON object `{"event": name, "args": [...]}`
+//!
+//! In JSON, 64-bit integers are written as decimal strings because JSON numbers cannot represent them exactly in every language.
+//! Addresses are written as strings and bytes as arrays of numbers.
+
+use displaydoc::Display;
+use massa_models::abi::{AbiArgument, AbiFunction, AbiType, ContractAbi};
+use massa_models::address::Address;
+use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+/// ABI encoding error
+#[non_exhaustive]
+#[derive(Display, Error, Debug)]
+pub enum AbiError {
+    /// function {0} is not exported by the contract
+    UnknownFunction(String),
+    /// expected {expected} arguments, got {got}
+    WrongArgumentCount {
+        /// number of arguments of the function
+        expected: usize,
+        /// number of given arguments
+        got: usize,
+    },
+    /// invalid value for argument {0}: {1}
+    InvalidArgument(String, String),
+    /// invalid call parameter: {0}
+    InvalidParameter(String),
+    /// json error: {0}
+    JsonError(#[from] serde_json::Error),
+}
+
+/// Value of a smart contract function argument or event argument
+#[derive(Debug, Clone, PartialEq)]
+pub enum AbiValue {
+    /// boolean
+    Bool(bool),
+    /// unsigned 8-bit integer
+    U8(u8),
+    /// signed 32-bit integer
+    I32(i32),
+    /// unsigned 32-bit integer
+    U32(u32),
+    /// signed 64-bit integer
+    I64(i64),
+    /// unsigned 64-bit integer
+    U64(u64),
+    /// 32-bit float
+    F32(f32),
+    /// 64-bit float
+    F64(f64),
+    /// UTF-8 string
+    String(String),
+    /// massa address
+    Address(Address),
+    /// raw bytes
+    Bytes(Vec<u8>),
+}
+
+impl AbiValue {
+    /// Parses a value of a given type from its command line representation.
+    /// Bytes are given in hexadecimal.
+    pub fn parse(abi_type: &AbiType, input: &str) -> Result<Self, String> {
+        let err = |e: &dyn std::fmt::Display| e.to_string();
+        Ok(match abi_type {
+            AbiType::Bool => AbiValue::Bool(input.parse().map_err(|e| err(&e))?),
+            AbiType::U8 => AbiValue::U8(input.parse().map_err(|e| err(&e))?),
+            AbiType::I32 => AbiValue::I32(input.parse().map_err(|e| err(&e))?),
+            AbiType::U32 => AbiValue::U32(input.parse().map_err(|e| err(&e))?),
+            AbiType::I64 => AbiValue::I64(input.parse().map_err(|e| err(&e))?),
+            AbiType::U64 => AbiValue::U64(input.parse().map_err(|e| err(&e))?),
+            AbiType::F32 => AbiValue::F32(input.parse().map_err(|e| err(&e))?),
+            AbiType::F64 => AbiValue::F64(input.parse().map_err(|e| err(&e))?),
+            AbiType::String => AbiValue::String(input.to_string()),
+            AbiType::Address => AbiValue::Address(input.parse().map_err(|e| err(&e))?),
+            AbiType::Bytes => AbiValue::Bytes(parse_hex(input)?),
+        })
+    }
+
+    /// Reads a value of a given type from its JSON representation.
+    /// 64-bit integers are accepted both as numbers and as decimal strings.
+    pub fn from_json(abi_type: &AbiType, value: &Value) -> Result<Self, String> {
+        let invalid = || format!("expected a {} value, got {}", abi_type, value);
+        let as_u64 = || {
+            value
+                .as_u64()
+                .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+                .ok_or_else(invalid)
+        };
+        let as_i64 = || {
+            value
+                .as_i64()
+                .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+                .ok_or_else(invalid)
+        };
+        Ok(match abi_type {
+            AbiType::Bool => AbiValue::Bool(value.as_bool().ok_or_else(invalid)?),
+            AbiType::U8 => AbiValue::U8(as_u64()?.try_into().map_err(|_| invalid())?),
+            AbiType::I32 => AbiValue::I32(as_i64()?.try_into().map_err(|_| invalid())?),
+            AbiType::U32 => AbiValue::U32(as_u64()?.try_into().map_err(|_| invalid())?),
+            AbiType::I64 => AbiValue::I64(as_i64()?),
+            AbiType::U64 => AbiValue::U64(as_u64()?),
+            AbiType::F32 => AbiValue::F32(value.as_f64().ok_or_else(invalid)? as f32),
+            AbiType::F64 => AbiValue::F64(value.as_f64().ok_or_else(invalid)?),
+            AbiType::String => AbiValue::String(value.as_str().ok_or_else(invalid)?.to_string()),
+            AbiType::Address => AbiValue::Address(
+                value
+                    .as_str()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or_else(invalid)?,
+            ),
+            AbiType::Bytes => AbiValue::Bytes(
+                value
+                    .as_array()
+                    .ok_or_else(invalid)?
+                    .iter()
+                    .map(|byte| byte.as_u64().and_then(|b| u8::try_from(b).ok()))
+                    .collect::<Option<Vec<u8>>>()
+                    .ok_or_else(invalid)?,
+            ),
+        })
+    }
+
+    /// Appends the `Args` serialization of the value to a buffer
+    pub fn serialize_into(&self, buffer: &mut Vec<u8>) {
+        match self {
+            AbiValue::Bool(value) => buffer.push(u8::from(*value)),
+            AbiValue::U8(value) => buffer.push(*value),
+            AbiValue::I32(value) => buffer.extend(value.to_le_bytes()),
+            AbiValue::U32(value) => buffer.extend(value.to_le_bytes()),
+            AbiValue::I64(value) => buffer.extend(value.to_le_bytes()),
+            AbiValue::U64(value) => buffer.extend(value.to_le_bytes()),
+            AbiValue::F32(value) => buffer.extend(value.to_le_bytes()),
+            AbiValue::F64(value) => buffer.extend(value.to_le_bytes()),
+            AbiValue::String(value) => extend_with_length(buffer, value.as_bytes()),
+            AbiValue::Address(value) => extend_with_length(buffer, value.to_string().as_bytes()),
+            AbiValue::Bytes(value) => extend_with_length(buffer, value),
+        }
+    }
+
+    /// Reads a value of a given type at the start of `Args` serialized bytes,
+    /// and advances the input past it
+    pub fn deserialize(abi_type: &AbiType, input: &mut &[u8]) -> Result<Self, String> {
+        Ok(match abi_type {
+            AbiType::Bool => match take_bytes::<1>(input)? {
+                [0] => AbiValue::Bool(false),
+                [1] => AbiValue::Bool(true),
+                [byte] => return Err(format!("invalid boolean byte {}", byte)),
+            },
+            AbiType::U8 => AbiValue::U8(u8::from_le_bytes(take_bytes(input)?)),
+            AbiType::I32 => AbiValue::I32(i32::from_le_bytes(take_bytes(input)?)),
+            AbiType::U32 => AbiValue::U32(u32::from_le_bytes(take_bytes(input)?)),
+            AbiType::I64 => AbiValue::I64(i64::from_le_bytes(take_bytes(input)?)),
+            AbiType::U64 => AbiValue::U64(u64::from_le_bytes(take_bytes(input)?)),
+            AbiType::F32 => AbiValue::F32(f32::from_le_bytes(take_bytes(input)?)),
+            AbiType::F64 => AbiValue::F64(f64::from_le_bytes(take_bytes(input)?)),
+            AbiType::String => AbiValue::String(read_string(input)?),
+            AbiType::Address => {
+                AbiValue::Address(read_string(input)?.parse().map_err(|e| format!("{}", e))?)
+            }
+            AbiType::Bytes => AbiValue::Bytes(take_with_length(input)?.to_vec()),
+        })
+    }
+
+    /// JSON representation of the value
+    pub fn to_json(&self) -> Value {
+        match self {
+            AbiValue::Bool(value) => json!(value),
+            AbiValue::U8(value) => json!(value),
+            AbiValue::I32(value) => json!(value),
+            AbiValue::U32(value) => json!(value),
+            AbiValue::I64(value) => json!(value.to_string()),
+            AbiValue::U64(value) => json!(value.to_string()),
+            AbiValue::F32(value) => json!(value),
+            AbiValue::F64(value) => json!(value),
+            AbiValue::String(value) => json!(value),
+            AbiValue::Address(value) => json!(value.to_string()),
+            AbiValue::Bytes(value) => json!(value),
+        }
+    }
+}
+
+impl std::fmt::Display for AbiValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AbiValue::Bool(value) => write!(f, "{}", value),
+            AbiValue::U8(value) => write!(f, "{}", value),
+            AbiValue::I32(value) => write!(f, "{}", value),
+            AbiValue::U32(value) => write!(f, "{}", value),
+            AbiValue::I64(value) => write!(f, "{}", value),
+            AbiValue::U64(value) => write!(f, "{}", value),
+            AbiValue::F32(value) => write!(f, "{}", value),
+            AbiValue::F64(value) => write!(f, "{}", value),
+            AbiValue::String(value) => write!(f, "{:?}", value),
+            AbiValue::Address(value) => write!(f, "{}", value),
+            AbiValue::Bytes(value) => {
+                write!(f, "0x")?;
+                for byte in value {
+                    write!(f, "{:02x}", byte)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Serialize for AbiValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
+}
+
+/// Parses bytes given in hexadecimal, with or without a `0x` prefix
+fn parse_hex(input: &str) -> Result<Vec<u8>, String> {
+    let digits = input.strip_prefix("0x").unwrap_or(input);
+    if digits.len() % 2 != 0 {
+        return Err("odd number of hexadecimal digits".into());
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| {
+            digits
+                .get(index..index + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| format!("invalid hexadecimal bytes: {}", input))
+        })
+        .collect()
+}
+
+/// Appends data prefixed with its length in bytes
+fn extend_with_length(buffer: &mut Vec<u8>, data: &[u8]) {
+    buffer.extend((data.len() as u32).to_le_bytes());
+    buffer.extend(data);
+}
+
+/// Takes the next `length` bytes of an input
+fn take<'a>(input: &mut &'a [u8], length: usize) -> Result<&'a [u8], String> {
+    if input.len() < length {
+        return Err(format!(
+            "expected {} bytes, only {} remaining",
+            length,
+            input.len()
+        ));
+    }
+    let (taken, rest) = input.split_at(length);
+    *input = rest;
+    Ok(taken)
+}
+
+/// Takes the next bytes of an input, prefixed with their length
+fn take_with_length<'a>(input: &mut &'a [u8]) -> Result<&'a [u8], String> {
+    let length = u32::from_le_bytes(take_bytes(input)?);
+    take(input, length as usize)
+}
+
+/// Takes the next string of an input, prefixed with its length in bytes
+fn read_string(input: &mut &[u8]) -> Result<String, String> {
+    String::from_utf8(take_with_length(input)?.to_vec()).map_err(|e| e.to_string())
+}
+
+/// Takes the next `N` bytes of an input
+fn take_bytes<const N: usize>(input: &mut &[u8]) -> Result<[u8; N], String> {
+    let mut bytes = [0u8; N];
+    bytes.copy_from_slice(take(input, N)?);
+    Ok(bytes)
+}
+
+/// Serializes values the way the contract-side `Args` class does
+pub fn serialize_args(values: &[AbiValue]) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    for value in values {
+        value.serialize_into(&mut buffer);
+    }
+    buffer
+}
+
+/// Deserializes the values of arguments serialized by the contract-side `Args` class
+pub fn deserialize_args(arguments: &[AbiArgument], data: &[u8]) -> Result<Vec<AbiValue>, AbiError> {
+    let mut input = data;
+    let values = arguments
+        .iter()
+        .map(|argument| {
+            AbiValue::deserialize(&argument.arg_type, &mut input)
+                .map_err(|err| AbiError::InvalidArgument(argument.name.clone(), err))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if !input.is_empty() {
+        return Err(AbiError::InvalidParameter(format!(
+            "{} unexpected bytes after the arguments",
+            input.len()
+        )));
+    }
+    Ok(values)
+}
+
+/// Builds the string parameter of a call from serialized arguments, with one character per byte
+pub fn args_to_parameter(data: &[u8]) -> String {
+    data.iter().map(|byte| char::from(*byte)).collect()
+}
+
+/// Gets the serialized arguments carried in the string parameter of a call
+pub fn parameter_to_args(parameter: &str) -> Result<Vec<u8>, AbiError> {
+    parameter
+        .chars()
+        .map(|c| {
+            u8::try_from(c)
+                .map_err(|_| AbiError::InvalidParameter(format!("character {:?} is not a byte", c)))
+        })
+        .collect()
+}
+
+/// Builds the parameter of a call from the values of the arguments
+fn encode_values(values: Vec<AbiValue>) -> Result<String, AbiError> {
+    Ok(args_to_parameter(&serialize_args(&values)))
+}
+
+/// Reads the values of arguments from their JSON representations
+fn read_json_values(
+    arguments: &[AbiArgument],
+    values: &[Value],
+) -> Result<Vec<AbiValue>, AbiError> {
+    if values.len() != arguments.len() {
+        return Err(AbiError::WrongArgumentCount {
+            expected: arguments.len(),
+            got: values.len(),
+        });
+    }
+    arguments
+        .iter()
+        .zip(values)
+        .map(|(argument, value)| {
+            AbiValue::from_json(&argument.arg_type, value)
+                .map_err(|err| AbiError::InvalidArgument(argument.name.clone(), err))
+        })
+        .collect()
+}
+
+/// Encodes the parameter of a call to a function from the command line representations of its arguments
+///
+/// ## Example:
+/// ```rust
+/// use massa_models::abi::{AbiArgument, AbiFunction, AbiType};
+/// use massa_sdk::abi::{encode_arguments, parameter_to_args};
+///
+/// let function = AbiFunction {
+///     name: "transfer".to_string(),
+///     arguments: vec![
+///         AbiArgument { name: "amount".to_string(), arg_type: AbiType::U64 },
+///         AbiArgument { name: "memo".to_string(), arg_type: AbiType::String },
+///     ],
+///     returns: None,
+/// };
+/// let param = encode_arguments(&function, &["12".to_string(), "hello".to_string()]).unwrap();
+/// assert_eq!(
+///     parameter_to_args(&param).unwrap(),
+///     [&[12, 0, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0][..], b"hello"].concat()
+/// );
+/// ```
+pub fn encode_arguments(function: &AbiFunction, args: &[String]) -> Result<String, AbiError> {
+    if args.len() != function.arguments.len() {
+        return Err(AbiError::WrongArgumentCount {
+            expected: function.arguments.len(),
+            got: args.len(),
+        });
+    }
+    let values = function
+        .arguments
+        .iter()
+        .zip(args)
+        .map(|(argument, arg)| {
+            AbiValue::parse(&argument.arg_type, arg)
+                .map_err(|err| AbiError::InvalidArgument(argument.name.clone(), err))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    encode_values(values)
+}
+
+/// Encodes the parameter of a call to a function from JSON arguments:
+/// either an array of values in the order of the arguments, or an object mapping argument names to values
+pub fn encode_json_arguments(function: &AbiFunction, args: &Value) -> Result<String, AbiError> {
+    let values = match args {
+        Value::Array(values) => read_json_values(&function.arguments, values)?,
+        Value::Object(map) => {
+            let values = function
+                .arguments
+                .iter()
+                .map(|argument| {
+                    map.get(&argument.name).cloned().ok_or_else(|| {
+                        AbiError::InvalidArgument(argument.name.clone(), "missing".into())
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if map.len() != values.len() {
+                return Err(AbiError::WrongArgumentCount {
+                    expected: values.len(),
+                    got: map.len(),
+                });
+            }
+            read_json_values(&function.arguments, &values)?
+        }
+        _ => {
+            return Err(AbiError::InvalidArgument(
+                function.name.clone(),
+                "arguments must be a JSON array or object".into(),
+            ))
+        }
+    };
+    encode_values(values)
+}
+
+/// Decodes the arguments of a call to a function from its string parameter
+pub fn decode_arguments(
+    function: &AbiFunction,
+    parameter: &str,
+) -> Result<Vec<AbiValue>, AbiError> {
+    deserialize_args(&function.arguments, &parameter_to_args(parameter)?)
+}
+
+/// Event decoded according to the ABI of the contract that emitted it
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedEvent {
+    /// name of the event
+    pub name: String,
+    /// names and values of the arguments of the event
+    pub args: Vec<(String, AbiValue)>,
+}
+
+impl std::fmt::Display for DecodedEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (index, (name, value)) in self.args.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", name, value)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Decodes the data of an event, None if it is not an event described by the ABI
+///
+/// ## Example:
+/// ```rust
+/// use massa_models::abi::{AbiArgument, AbiEvent, AbiType, ContractAbi};
+/// use massa_sdk::abi::decode_event;
+///
+/// let abi = ContractAbi {
+///     functions: vec![],
+///     events: vec![AbiEvent {
+///         name: "Minted".to_string(),
+///         arguments: vec![AbiArgument { name: "amount".to_string(), arg_type: AbiType::U64 }],
+///     }],
+/// };
+/// let event = decode_event(&abi, r#"{"event":"Minted","args":["1000"]}"#).unwrap();
+/// assert_eq!(event.to_string(), "Minted(amount: 1000)");
+/// assert!(decode_event(&abi, "not an event").is_none());
+/// ```
+pub fn decode_event(abi: &ContractAbi, data: &str) -> Option<DecodedEvent> {
+    let value: Value = serde_json::from_str(data).ok()?;
+    let event = abi.get_event(value.get("event")?.as_str()?)?;
+    let values = read_json_values(&event.arguments, value.get("args")?.as_array()?).ok()?;
+    Some(DecodedEvent {
+        name: event.name.clone(),
+        args: event
+            .arguments
+            .iter()
+            .map(|argument| argument.name.clone())
+            .zip(values)
+            .collect(),
+    })
+}
+
+/// Parses the JSON descriptor of an ABI, as published by a contract under `CONTRACT_ABI_KEY`
+pub fn parse_contract_abi(data: &[u8]) -> Result<ContractAbi, AbiError> {
+    Ok(serde_json::from_slice(data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADDRESS: &str = "A12dG5xP1RDEB5ocdHkymNVvvSJmUL9BgHwCksDowqmGWxfpm93x";
+
+    fn with_length(data: &[u8]) -> Vec<u8> {
+        [&(data.len() as u32).to_le_bytes()[..], data].concat()
+    }
+
+    fn argument(name: &str, arg_type: AbiType) -> AbiArgument {
+        AbiArgument {
+            name: name.to_string(),
+            arg_type,
+        }
+    }
+
+    fn get_function(arguments: Vec<AbiArgument>, returns: Option<AbiType>) -> AbiFunction {
+        AbiFunction {
+            name: "test".to_string(),
+            arguments,
+            returns,
+        }
+    }
+
+    #[test]
+    fn test_args_encoding_of_each_type() {
+        let cases: Vec<(AbiType, &str, Vec<u8>)> = vec![
+            (AbiType::Bool, "true", vec![1]),
+            (AbiType::Bool, "false", vec![0]),
+            (AbiType::U8, "200", vec![200]),
+            (AbiType::I32, "-2", (-2i32).to_le_bytes().to_vec()),
+            (AbiType::U32, "305419896", vec![0x78, 0x56, 0x34, 0x12]),
+            (AbiType::I64, "-1", vec![0xff; 8]),
+            (AbiType::U64, "12", vec![12, 0, 0, 0, 0, 0, 0, 0]),
+            (AbiType::F32, "1.5", 1.5f32.to_le_bytes().to_vec()),
+            (AbiType::F64, "-0.25", (-0.25f64).to_le_bytes().to_vec()),
+            (AbiType::String, "héllo", with_length("héllo".as_bytes())),
+            (AbiType::String, "", vec![0, 0, 0, 0]),
+            (AbiType::Address, ADDRESS, with_length(ADDRESS.as_bytes())),
+            (AbiType::Bytes, "0x01ff", vec![2, 0, 0, 0, 0x01, 0xff]),
+        ];
+        for (abi_type, input, expected) in cases {
+            let value = AbiValue::parse(&abi_type, input).unwrap();
+            let data = serialize_args(&[value.clone()]);
+            assert_eq!(data, expected, "wrong encoding of {} {}", abi_type, input);
+            let mut data = &data[..];
+            assert_eq!(AbiValue::deserialize(&abi_type, &mut data).unwrap(), value);
+            assert!(data.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_args_decoding_errors() {
+        // truncated number
+        assert!(AbiValue::deserialize(&AbiType::U64, &mut &[1, 2, 3][..]).is_err());
+        // boolean that is neither 0 nor 1
+        assert!(AbiValue::deserialize(&AbiType::Bool, &mut &[2][..]).is_err());
+        // length prefix larger than the remaining data
+        assert!(AbiValue::deserialize(&AbiType::Bytes, &mut &[5, 0, 0, 0, 1][..]).is_err());
+        // invalid UTF-8
+        assert!(AbiValue::deserialize(&AbiType::String, &mut &with_length(&[0xff])[..]).is_err());
+        // invalid address
+        assert!(
+            AbiValue::deserialize(&AbiType::Address, &mut &with_length(b"not an address")[..])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_encode_and_decode_arguments() {
+        let function = get_function(
+            vec![
+                argument("recipient", AbiType::Address),
+                argument("amount", AbiType::U64),
+                argument("memo", AbiType::String),
+            ],
+            None,
+        );
+        let param = encode_arguments(
+            &function,
+            &[
+                ADDRESS.to_string(),
+                "1000".to_string(),
+                "thanks".to_string(),
+            ],
+        )
+        .unwrap();
+        let expected = [
+            with_length(ADDRESS.as_bytes()),
+            1000u64.to_le_bytes().to_vec(),
+            with_length(b"thanks"),
+        ]
+        .concat();
+        assert_eq!(parameter_to_args(&param).unwrap(), expected);
+        let values = vec![
+            AbiValue::Address(ADDRESS.parse().unwrap()),
+            AbiValue::U64(1000),
+            AbiValue::String("thanks".to_string()),
+        ];
+        assert_eq!(decode_arguments(&function, &param).unwrap(), values);
+
+        // JSON arguments, as an array or by name, produce the same parameter
+        let json_array = serde_json::json!([ADDRESS, "1000", "thanks"]);
+        assert_eq!(
+            encode_json_arguments(&function, &json_array).unwrap(),
+            param
+        );
+        let json_object =
+            serde_json::json!({"memo": "thanks", "amount": 1000, "recipient": ADDRESS});
+        assert_eq!(
+            encode_json_arguments(&function, &json_object).unwrap(),
+            param
+        );
+
+        // bytes that do not fit in one character each are refused
+        assert!(parameter_to_args("é€").is_err());
+    }
+
+    #[test]
+    fn test_mismatched_arity() {
+        let function = get_function(
+            vec![argument("a", AbiType::U32), argument("b", AbiType::Bool)],
+            None,
+        );
+        for args in [vec![], vec!["1"], vec!["1", "true", "2"]] {
+            let args: Vec<String> = args.into_iter().map(String::from).collect();
+            match encode_arguments(&function, &args) {
+                Err(AbiError::WrongArgumentCount { expected, got }) => {
+                    assert_eq!(expected, 2);
+                    assert_eq!(got, args.len());
+                }
+                other => panic!("unexpected result for {:?}: {:?}", args, other),
+            }
+        }
+        assert!(matches!(
+            encode_json_arguments(&function, &serde_json::json!([1])),
+            Err(AbiError::WrongArgumentCount {
+                expected: 2,
+                got: 1
+            })
+        ));
+        assert!(matches!(
+            encode_json_arguments(&function, &serde_json::json!({"a": 1, "b": true, "c": 2})),
+            Err(AbiError::WrongArgumentCount {
+                expected: 2,
+                got: 3
+            })
+        ));
+        assert!(matches!(
+            encode_json_arguments(&function, &serde_json::json!({"a": 1})),
+            Err(AbiError::InvalidArgument(..))
+        ));
+
+        // missing or extra serialized arguments
+        let one_argument = args_to_parameter(&serialize_args(&[AbiValue::U32(1)]));
+        assert!(decode_arguments(&function, &one_argument).is_err());
+        let three_arguments = args_to_parameter(&serialize_args(&[
+            AbiValue::U32(1),
+            AbiValue::Bool(true),
+            AbiValue::U8(2),
+        ]));
+        assert!(matches!(
+            decode_arguments(&function, &three_arguments),
+            Err(AbiError::InvalidParameter(_))
+        ));
+    }
+}
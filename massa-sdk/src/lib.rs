@@ -4,12 +4,16 @@
 #![warn(missing_docs)]
 #![warn(unused_crate_dependencies)]
 
+use abi::parse_contract_abi;
 use jsonrpc_core_client::transports::http;
 use jsonrpc_core_client::{RpcChannel, RpcError, RpcResult, TypedClient};
+use massa_models::abi::{ContractAbi, CONTRACT_ABI_KEY};
 use massa_models::api::{
-    AddressInfo, BlockInfo, BlockSummary, DatastoreEntryInput, DatastoreEntryOutput,
-    EndorsementInfo, EventFilter, NodeStatus, OperationInfo, OperationInput,
-    ReadOnlyBytecodeExecution, ReadOnlyCall, TimeInterval,
+    AddressHistoryEntry, AddressHistoryRequest, AddressInfo, AsyncMessageFilter, AsyncMessageInfo,
+    BannedPeerInfo, BlockExecutionReceipt, BlockInfo, BlockSummary, ContractMetadata,
+    ContractSourceBundle, DatastoreEntryInput, DatastoreEntryOutput, EndorsementInfo, EventFilter,
+    NodeStatus, OperationInfo, OperationInput, ReadOnlyBytecodeExecution, ReadOnlyCall,
+    ReadOnlyCallBatch, TimeInterval, VerifiedContractSources,
 };
 use massa_models::clique::Clique;
 use massa_models::composite::PubkeySig;
@@ -21,10 +25,13 @@ use massa_models::{
     address::Address, block::BlockId, endorsement::EndorsementId, operation::OperationId,
 };
 use massa_signature::KeyPair;
+use massa_time::MassaTime;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use std::net::{IpAddr, SocketAddr};
 
+pub mod abi;
+
 /// Client
 pub struct Client {
     /// public component
@@ -119,16 +126,32 @@ impl RpcClient {
             .await
     }
 
-    /// Bans given ip address(es)
+    /// Bans given ip address(es) for `duration`, or the node's default ban duration
     /// No confirmation to expect.
-    pub async fn node_ban_by_ip(&self, ips: Vec<IpAddr>) -> RpcResult<()> {
-        self.call_method("node_ban_by_ip", "()", vec![ips]).await
+    pub async fn node_ban_by_ip(
+        &self,
+        ips: Vec<IpAddr>,
+        duration: Option<MassaTime>,
+    ) -> RpcResult<()> {
+        self.call_method("node_ban_by_ip", "()", (ips, duration))
+            .await
     }
 
-    /// Bans given node id(s)
+    /// Bans given node id(s) for `duration`, or the node's default ban duration
     /// No confirmation to expect.
-    pub async fn node_ban_by_id(&self, ids: Vec<NodeId>) -> RpcResult<()> {
-        self.call_method("node_ban_by_id", "()", vec![ids]).await
+    pub async fn node_ban_by_id(
+        &self,
+        ids: Vec<NodeId>,
+        duration: Option<MassaTime>,
+    ) -> RpcResult<()> {
+        self.call_method("node_ban_by_id", "()", (ids, duration))
+            .await
+    }
+
+    /// Returns the currently banned peers
+    pub async fn get_banned_peers(&self) -> RpcResult<Vec<BannedPeerInfo>> {
+        self.call_method("get_banned_peers", "Vec<BannedPeerInfo>", ())
+            .await
     }
 
     /// Unbans given ip address(es)
@@ -155,6 +178,19 @@ impl RpcClient {
             .await
     }
 
+    /// Submit the sources of a deployed smart contract for verification
+    pub async fn submit_contract_sources(
+        &self,
+        bundle: ContractSourceBundle,
+    ) -> RpcResult<VerifiedContractSources> {
+        self.call_method(
+            "submit_contract_sources",
+            "VerifiedContractSources",
+            vec![bundle],
+        )
+        .await
+    }
+
     ////////////////
     // public-api //
     ////////////////
@@ -219,6 +255,41 @@ impl RpcClient {
         .await
     }
 
+    /// Get asynchronous messages and their status with various filters
+    pub async fn get_async_messages(
+        &self,
+        filter: AsyncMessageFilter,
+    ) -> RpcResult<Vec<AsyncMessageInfo>> {
+        self.call_method("get_async_messages", "Vec<AsyncMessageInfo>", vec![filter])
+            .await
+    }
+
+    /// Get a page of the history of operations and coin transfers involving an address, most recent first
+    pub async fn get_address_history(
+        &self,
+        request: AddressHistoryRequest,
+    ) -> RpcResult<Vec<AddressHistoryEntry>> {
+        self.call_method(
+            "get_address_history",
+            "Vec<AddressHistoryEntry>",
+            vec![request],
+        )
+        .await
+    }
+
+    /// Get the execution receipt of a recently executed block
+    pub async fn get_block_execution_receipt(
+        &self,
+        block_id: BlockId,
+    ) -> RpcResult<Option<BlockExecutionReceipt>> {
+        self.call_method(
+            "get_block_execution_receipt",
+            "Option<BlockExecutionReceipt>",
+            vec![block_id],
+        )
+        .await
+    }
+
     /// Get the block graph within the specified time interval.
     /// Optional parameters: from `<time_start>` (included) and to `<time_end>` (excluded) millisecond timestamp
     pub(crate) async fn _get_graph_interval(
@@ -235,6 +306,19 @@ impl RpcClient {
             .await
     }
 
+    /// Get the bytecode metadata and the verified sources of smart contracts
+    pub async fn get_contract_metadata(
+        &self,
+        addresses: Vec<Address>,
+    ) -> RpcResult<Vec<ContractMetadata>> {
+        self.call_method(
+            "get_contract_metadata",
+            "Vec<ContractMetadata>",
+            vec![addresses],
+        )
+        .await
+    }
+
     /// Get datastore entries
     pub async fn get_datastore_entries(
         &self,
@@ -248,6 +332,29 @@ impl RpcClient {
         .await
     }
 
+    /// Get the ABI of a smart contract: the one it published in its datastore under `CONTRACT_ABI_KEY`,
+    /// or else the one of its verified sources, if any
+    pub async fn get_contract_abi(&self, address: Address) -> RpcResult<Option<ContractAbi>> {
+        let published = self
+            .get_datastore_entries(vec![DatastoreEntryInput {
+                address,
+                key: CONTRACT_ABI_KEY.as_bytes().to_vec(),
+            }])
+            .await?
+            .pop()
+            .and_then(|entry| entry.candidate_value.or(entry.final_value))
+            .and_then(|data| parse_contract_abi(&data).ok());
+        if published.is_some() {
+            return Ok(published);
+        }
+        Ok(self
+            .get_contract_metadata(vec![address])
+            .await?
+            .pop()
+            .and_then(|metadata| metadata.verified_sources)
+            .map(|sources| sources.abi))
+    }
+
     // User (interaction with the node)
 
     /// Adds operations to pool. Returns operations that were ok and sent to pool.
@@ -290,4 +397,17 @@ impl RpcClient {
         .pop()
         .ok_or_else(|| RpcError::Client("missing return value on execute_read_only_call".into()))
     }
+
+    /// execute a batch of read only SC calls against the same state
+    pub async fn execute_read_only_call_batch(
+        &self,
+        batch: ReadOnlyCallBatch,
+    ) -> RpcResult<Vec<ExecuteReadOnlyResponse>> {
+        self.call_method(
+            "execute_read_only_call_batch",
+            "Vec<ExecuteReadOnlyResponse>",
+            vec![batch],
+        )
+        .await
+    }
 }
@@ -11,6 +11,7 @@ use massa_cipher::{decrypt, encrypt};
 use massa_hash::Hash;
 use massa_models::address::Address;
 use massa_models::composite::PubkeySig;
+use massa_models::multisig::{MultisigProposal, MultisigSignature};
 use massa_models::operation::{Operation, OperationSerializer, WrappedOperation};
 use massa_models::prehash::{PreHashMap, PreHashSet};
 use massa_models::wrapped::WrappedContent;
@@ -148,6 +149,23 @@ impl Wallet {
             .ok_or(WalletError::MissingKeyError(address))?;
         Ok(Operation::new_wrapped(content, OperationSerializer::new(), sender_keypair).unwrap())
     }
+
+    /// Signs a multisig proposal with every keypair of the wallet that is among its signers
+    pub fn sign_multisig_proposal(
+        &self,
+        proposal: &MultisigProposal,
+    ) -> Result<Vec<MultisigSignature>, WalletError> {
+        self.keys
+            .values()
+            .filter(|keypair| {
+                proposal
+                    .descriptor
+                    .signer_index(&keypair.get_public_key())
+                    .is_some()
+            })
+            .map(|keypair| Ok(proposal.sign(keypair)?))
+            .collect()
+    }
 }
 
 impl std::fmt::Display for Wallet {